@@ -1,148 +1,628 @@
 use std::io::{self, Read};
 use std::path::Path;
+use std::time::Duration;
 
-use qorvex_core::action::{ActionLog, ActionType};
+use chrono::{DateTime, SecondsFormat, Utc};
+use qorvex_core::action::{ActionLog, ActionType, MatchMode};
+use serde::Deserialize;
 
-/// Convert JSONL action logs to shell scripts that call `qorvex` CLI commands.
+/// Convert JSONL action logs to scripts that call `qorvex` CLI commands.
 pub struct LogConverter;
 
+/// The kind of script [`LogConverter`] writes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ScriptFormat {
+    /// A bash script, one `qorvex` command per line.
+    #[default]
+    Shell,
+    /// A pytest module with one test function per recorded session, running
+    /// `qorvex` through `subprocess`.
+    Pytest,
+}
+
+/// How [`LogConverter`] renders a log.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConvertOptions {
+    /// The kind of script to write.
+    pub format: ScriptFormat,
+    /// Reproduce the recorded pauses between actions with `sleep`, each
+    /// capped at this duration, and precede every command with a comment
+    /// giving its timestamp and duration. `None` replays at full speed.
+    pub preserve_timing: Option<Duration>,
+}
+
+/// The `qorvex log -f json` output: one envelope whose data is the log.
+#[derive(Deserialize)]
+struct LogOutput {
+    data: Vec<ActionLog>,
+}
+
 impl LogConverter {
-    pub fn convert_file(path: &Path) -> Result<String, io::Error> {
+    pub fn convert_file(path: &Path, options: ConvertOptions) -> Result<String, io::Error> {
         let content = std::fs::read_to_string(path)?;
-        Self::convert_str(&content)
+        Self::convert_str(&content, options)
     }
 
-    pub fn convert_stdin() -> Result<String, io::Error> {
+    pub fn convert_stdin(options: ConvertOptions) -> Result<String, io::Error> {
         let mut content = String::new();
         io::stdin().read_to_string(&mut content)?;
-        Self::convert_str(&content)
+        Self::convert_str(&content, options)
     }
 
-    fn convert_str(content: &str) -> Result<String, io::Error> {
+    fn convert_str(content: &str, options: ConvertOptions) -> Result<String, io::Error> {
+        let logs = Self::parse_logs(content)?;
+        Ok(match options.format {
+            ScriptFormat::Shell => Self::to_shell(&logs, options),
+            ScriptFormat::Pytest => Self::to_pytest(&logs, options),
+        })
+    }
+
+    fn to_shell(logs: &[ActionLog], options: ConvertOptions) -> String {
         let mut lines = vec![
             "#!/usr/bin/env bash".to_string(),
             "set -euo pipefail".to_string(),
             String::new(),
         ];
 
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() {
+        let mut previous: Option<DateTime<Utc>> = None;
+        for log in logs {
+            let Some(cmd) = Self::action_to_command(&log.action, log.tag.as_deref()) else {
                 continue;
+            };
+            if let Some(max_delay) = options.preserve_timing {
+                if let Some(pause) = previous.and_then(|p| Self::pause_before(p, log, max_delay)) {
+                    lines.push(format!("sleep {}", pause));
+                }
+                lines.push(Self::timing_comment(log));
+                previous = Some(log.timestamp);
             }
+            lines.push(cmd);
+        }
 
-            let log: ActionLog = serde_json::from_str(line).map_err(|e| {
-                io::Error::new(io::ErrorKind::InvalidData, format!("Invalid JSONL: {}", e))
-            })?;
+        lines.join("\n") + "\n"
+    }
 
-            if let Some(cmd) = Self::action_to_command(&log.action, log.tag.as_deref()) {
-                lines.push(cmd);
+    /// Writes a pytest module replaying the log.
+    ///
+    /// Each `StartSession` begins a new test function, named after the first
+    /// tag in it when there is one. A `GetValue` that read a value becomes an
+    /// assertion on that value; an `ElementExists` probe does not fail the
+    /// test either way.
+    fn to_pytest(logs: &[ActionLog], options: ConvertOptions) -> String {
+        let mut lines: Vec<String> = [
+            "\"\"\"Replays a recorded qorvex session. Generated by `qorvex convert --format pytest`.\"\"\"",
+            "",
+            "import subprocess",
+        ]
+        .map(String::from)
+        .to_vec();
+        if options.preserve_timing.is_some() {
+            lines.push("import time".to_string());
+        }
+        lines.extend(
+            [
+                "",
+                "",
+                "def qorvex(*args, check=True):",
+                "    \"\"\"Runs a qorvex command and returns its stdout, failing the test if it fails.\"\"\"",
+                "    result = subprocess.run([\"qorvex\", *args], capture_output=True, text=True)",
+                "    if check:",
+                "        assert result.returncode == 0, result.stderr.strip()",
+                "    return result.stdout.removesuffix(\"\\n\")",
+            ]
+            .map(String::from),
+        );
+
+        let mut names: Vec<String> = Vec::new();
+        for session in logs.split(|log| matches!(log.action, ActionType::StartSession)) {
+            let mut body = Vec::new();
+            let mut has_statement = false;
+            let mut previous: Option<DateTime<Utc>> = None;
+            for log in session {
+                if let Some(text) = comment_text(&log.action) {
+                    body.push(format!("    # {}", text.replace('\n', " ")));
+                    continue;
+                }
+                let statement = match Self::action_to_args(&log.action, log.tag.as_deref()) {
+                    Some(args) => Self::python_call(&log.action, log, &args),
+                    None => continue,
+                };
+                if let Some(max_delay) = options.preserve_timing {
+                    if let Some(pause) =
+                        previous.and_then(|p| Self::pause_before(p, log, max_delay))
+                    {
+                        body.push(format!("    time.sleep({})", pause));
+                    }
+                    body.push(format!("    {}", Self::timing_comment(log)));
+                    previous = Some(log.timestamp);
+                }
+                body.push(format!("    {}", statement));
+                has_statement = true;
             }
+            if !has_statement {
+                continue;
+            }
+
+            let name = Self::test_name(session, names.len() + 1, &names);
+            lines.extend([String::new(), String::new(), format!("def {}():", name)]);
+            lines.extend(body);
+            names.push(name);
         }
 
-        Ok(lines.join("\n") + "\n")
+        lines.join("\n") + "\n"
+    }
+
+    /// The Python statement that replays one logged action.
+    fn python_call(action: &ActionType, log: &ActionLog, args: &[String]) -> String {
+        let mut call = args
+            .iter()
+            .map(|arg| python_string(arg))
+            .collect::<Vec<_>>()
+            .join(", ");
+        match (action, &log.value) {
+            (ActionType::GetValue { .. }, Some(value)) => {
+                format!("assert qorvex({}) == {}", call, python_string(value))
+            }
+            // A probe, not an assertion: the recorded run may have found nothing
+            (ActionType::ElementExists { .. }, _) => {
+                call.push_str(", check=False");
+                format!("qorvex({})", call)
+            }
+            _ => format!("qorvex({})", call),
+        }
+    }
+
+    /// Names a session's test function after its first tag, falling back to
+    /// `test_session_<n>`, and keeps names unique within the module.
+    fn test_name(session: &[ActionLog], n: usize, taken: &[String]) -> String {
+        let slug = session
+            .iter()
+            .find_map(|log| log.tag.as_deref())
+            .map(|tag| {
+                tag.to_ascii_lowercase()
+                    .split(|c: char| !c.is_ascii_alphanumeric())
+                    .filter(|part| !part.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("_")
+            })
+            .filter(|slug| !slug.is_empty())
+            .unwrap_or_else(|| format!("session_{}", n));
+        let base = format!("test_{}", slug);
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while taken.contains(&name) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        name
+    }
+
+    /// Returns the idle time between the end of the previous command and the
+    /// start of `log`, in seconds for `sleep`, or `None` when there is none
+    /// worth waiting for.
+    ///
+    /// Entries are timestamped when an action finishes, so the action's own
+    /// duration is subtracted. The pause is capped at `max_delay` and rounded
+    /// to a tenth of a second.
+    fn pause_before(
+        previous: DateTime<Utc>,
+        log: &ActionLog,
+        max_delay: Duration,
+    ) -> Option<String> {
+        let own = log.duration_ms.unwrap_or(0) as i64;
+        let max = i64::try_from(max_delay.as_millis()).unwrap_or(i64::MAX);
+        let gap = ((log.timestamp - previous).num_milliseconds() - own).clamp(0, max);
+        let tenths = (gap + 50) / 100;
+        match tenths {
+            0 => None,
+            t if t % 10 == 0 => Some((t / 10).to_string()),
+            t => Some(format!("{}.{}", t / 10, t % 10)),
+        }
+    }
+
+    /// A comment recording when an action finished and how long it took.
+    fn timing_comment(log: &ActionLog) -> String {
+        let timestamp = log.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true);
+        match log.duration_ms {
+            Some(ms) => format!("# {} ({}ms)", timestamp, ms),
+            None => format!("# {}", timestamp),
+        }
+    }
+
+    /// Reads either a JSONL log file or `qorvex log -f json` output.
+    fn parse_logs(content: &str) -> Result<Vec<ActionLog>, io::Error> {
+        if let Ok(output) = serde_json::from_str::<LogOutput>(content) {
+            return Ok(output.data);
+        }
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                serde_json::from_str(line).map_err(|e| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("Invalid JSONL: {}", e))
+                })
+            })
+            .collect()
     }
 
     fn action_to_command(action: &ActionType, tag: Option<&str>) -> Option<String> {
-        let base = match action {
+        if let Some(text) = comment_text(action) {
+            let mut cmd = format!("# {}", text);
+            if let Some(t) = tag {
+                cmd.push_str(&format!(" --tag {}", shell_escape(t)));
+            }
+            return Some(cmd);
+        }
+        let args = Self::action_to_args(action, tag)?;
+        let mut cmd = std::iter::once("qorvex".to_string())
+            .chain(args.iter().map(|arg| shell_escape(arg)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        // A probe, not an assertion: the recorded run may have found nothing
+        if matches!(action, ActionType::ElementExists { .. }) {
+            cmd.push_str(" || true");
+        }
+        Some(cmd)
+    }
+
+    /// Returns the `qorvex` arguments that replay `action`, or `None` for
+    /// actions with no CLI command (session control and comments).
+    fn action_to_args(action: &ActionType, tag: Option<&str>) -> Option<Vec<String>> {
+        let mut args: Vec<String> = match action {
             ActionType::Tap {
                 selector,
                 by_label,
                 element_type,
+                require_enabled,
+                match_mode,
+                first,
                 ..
             } => {
-                let mut cmd = format!("qorvex tap {}", shell_escape(selector));
-                if *by_label {
-                    cmd.push_str(" --label");
+                let mut args = vec!["tap".to_string(), selector.clone()];
+                push_selector_flags(&mut args, *by_label, element_type.as_deref());
+                push_match_flags(&mut args, *match_mode, *first);
+                if *require_enabled {
+                    args.push("--require-enabled".to_string());
                 }
-                if let Some(t) = element_type {
-                    cmd.push_str(&format!(" -T {}", shell_escape(t)));
+                args
+            }
+            ActionType::TapLocation { x, y } => {
+                vec!["tap-location".to_string(), x.to_string(), y.to_string()]
+            }
+            ActionType::Swipe { direction } => vec!["swipe".to_string(), direction.clone()],
+            ActionType::TapElementOffset {
+                selector,
+                by_label,
+                dx_fraction,
+                dy_fraction,
+            } => {
+                let mut args = vec![
+                    "tap-offset".to_string(),
+                    selector.clone(),
+                    dx_fraction.to_string(),
+                    dy_fraction.to_string(),
+                ];
+                push_selector_flags(&mut args, *by_label, None);
+                args
+            }
+            ActionType::SwipeCoords {
+                start_x,
+                start_y,
+                end_x,
+                end_y,
+                duration,
+            } => {
+                let mut args = vec![
+                    "swipe-coords".to_string(),
+                    start_x.to_string(),
+                    start_y.to_string(),
+                    end_x.to_string(),
+                    end_y.to_string(),
+                ];
+                if let Some(d) = duration {
+                    args.extend(["--duration".to_string(), d.to_string()]);
                 }
-                Some(cmd)
+                args
+            }
+            ActionType::SwipeElement {
+                selector,
+                by_label,
+                direction,
+            } => {
+                let mut args = vec![
+                    "swipe-element".to_string(),
+                    selector.clone(),
+                    direction.clone(),
+                ];
+                push_selector_flags(&mut args, *by_label, None);
+                args
+            }
+            ActionType::SendKeys { text } => vec!["send-keys".to_string(), text.clone()],
+            ActionType::SendKey { key } => vec!["send-key".to_string(), key.to_string()],
+            ActionType::ClearText { selector, by_label } => {
+                let mut args = vec!["clear-text".to_string(), selector.clone()];
+                push_selector_flags(&mut args, *by_label, None);
+                args
             }
-            ActionType::TapLocation { x, y } => Some(format!("qorvex tap-location {} {}", x, y)),
-            ActionType::Swipe { direction } => {
-                Some(format!("qorvex swipe {}", shell_escape(direction)))
+            ActionType::PasteText { text } => vec!["paste".to_string(), text.clone()],
+            ActionType::GetScreenshot => vec!["screenshot".to_string()],
+            ActionType::GetElementScreenshot { selector, by_label } => {
+                let mut args = vec![
+                    "screenshot".to_string(),
+                    "--element".to_string(),
+                    selector.clone(),
+                ];
+                push_selector_flags(&mut args, *by_label, None);
+                args
             }
-            ActionType::SendKeys { text } => {
-                Some(format!("qorvex send-keys {}", shell_escape(text)))
+            // `--if-changed` is not replayed: an unchanged screen would exit
+            // non-zero and abort the generated script.
+            ActionType::GetScreenInfo { filter, .. } => {
+                let mut args = vec!["screen-info".to_string()];
+                for t in &filter.types {
+                    args.extend(["-T".to_string(), t.clone()]);
+                }
+                if filter.visible_only {
+                    args.push("--visible-only".to_string());
+                }
+                if let Some(depth) = filter.max_depth {
+                    args.extend(["--max-depth".to_string(), depth.to_string()]);
+                }
+                args
             }
-            ActionType::GetScreenshot => Some("qorvex screenshot".to_string()),
-            ActionType::GetScreenInfo => Some("qorvex screen-info".to_string()),
             ActionType::GetValue {
                 selector,
                 by_label,
                 element_type,
+                match_mode,
+                first,
                 ..
             } => {
-                let mut cmd = format!("qorvex get-value {}", shell_escape(selector));
-                if *by_label {
-                    cmd.push_str(" --label");
-                }
-                if let Some(t) = element_type {
-                    cmd.push_str(&format!(" -T {}", shell_escape(t)));
+                let mut args = vec!["get-value".to_string(), selector.clone()];
+                push_selector_flags(&mut args, *by_label, element_type.as_deref());
+                push_match_flags(&mut args, *match_mode, *first);
+                args
+            }
+            ActionType::GetAttribute {
+                selector,
+                by_label,
+                element_type,
+                attribute,
+            } => {
+                let mut args = vec![
+                    "get-attribute".to_string(),
+                    selector.clone(),
+                    "--attr".to_string(),
+                    attribute.to_string(),
+                ];
+                push_selector_flags(&mut args, *by_label, element_type.as_deref());
+                args
+            }
+            ActionType::ElementExists {
+                selector,
+                by_label,
+                element_type,
+            } => {
+                let mut args = vec!["exists".to_string(), selector.clone()];
+                push_selector_flags(&mut args, *by_label, element_type.as_deref());
+                args
+            }
+            ActionType::TapIfExists {
+                selector,
+                by_label,
+                element_type,
+            } => {
+                let mut args = vec!["tap-if-exists".to_string(), selector.clone()];
+                push_selector_flags(&mut args, *by_label, element_type.as_deref());
+                args
+            }
+            ActionType::FindAll {
+                selector,
+                by_label,
+                element_type,
+            } => {
+                let mut args = vec!["find-all".to_string()];
+                if !selector.is_empty() {
+                    args.push(selector.clone());
                 }
-                Some(cmd)
+                push_selector_flags(&mut args, *by_label, element_type.as_deref());
+                args
             }
             ActionType::WaitFor {
                 selector,
                 by_label,
                 element_type,
                 timeout_ms,
+                poll_interval_ms,
+                match_mode,
+                first,
                 ..
             } => {
-                let mut cmd = format!("qorvex wait-for {}", shell_escape(selector));
-                if *by_label {
-                    cmd.push_str(" --label");
+                let mut args = vec!["wait-for".to_string(), selector.clone()];
+                push_selector_flags(&mut args, *by_label, element_type.as_deref());
+                push_match_flags(&mut args, *match_mode, *first);
+                args.extend(["-o".to_string(), timeout_ms.to_string()]);
+                if let Some(ms) = poll_interval_ms {
+                    args.extend(["--poll-interval".to_string(), ms.to_string()]);
                 }
-                if let Some(t) = element_type {
-                    cmd.push_str(&format!(" -T {}", shell_escape(t)));
-                }
-                cmd.push_str(&format!(" -o {}", timeout_ms));
-                Some(cmd)
+                args
             }
             ActionType::WaitForNot {
                 selector,
                 by_label,
                 element_type,
                 timeout_ms,
+                poll_interval_ms,
+            } => {
+                let mut args = vec!["wait-for-not".to_string(), selector.clone()];
+                push_selector_flags(&mut args, *by_label, element_type.as_deref());
+                args.extend(["-o".to_string(), timeout_ms.to_string()]);
+                if let Some(ms) = poll_interval_ms {
+                    args.extend(["--poll-interval".to_string(), ms.to_string()]);
+                }
+                args
+            }
+            ActionType::WaitForAny {
+                selectors,
+                timeout_ms,
+                poll_interval_ms,
+            }
+            | ActionType::WaitForAll {
+                selectors,
+                timeout_ms,
+                poll_interval_ms,
             } => {
-                let mut cmd = format!("qorvex wait-for-not {}", shell_escape(selector));
-                if *by_label {
-                    cmd.push_str(" --label");
+                let command = if matches!(action, ActionType::WaitForAny { .. }) {
+                    "wait-for-any"
+                } else {
+                    "wait-for-all"
+                };
+                let first = selectors.first()?;
+                // The CLI applies one set of selector flags to every selector
+                if selectors
+                    .iter()
+                    .any(|s| s.by_label != first.by_label || s.element_type != first.element_type)
+                {
+                    return None;
                 }
-                if let Some(t) = element_type {
-                    cmd.push_str(&format!(" -T {}", shell_escape(t)));
+                let mut args = vec![command.to_string()];
+                args.extend(selectors.iter().map(|s| s.selector.clone()));
+                push_selector_flags(&mut args, first.by_label, first.element_type.as_deref());
+                args.extend(["-o".to_string(), timeout_ms.to_string()]);
+                if let Some(ms) = poll_interval_ms {
+                    args.extend(["--poll-interval".to_string(), ms.to_string()]);
                 }
-                cmd.push_str(&format!(" -o {}", timeout_ms));
-                Some(cmd)
+                args
+            }
+            ActionType::Wait { ms } => vec!["wait".to_string(), ms.to_string()],
+            ActionType::WaitForIdle {
+                timeout_ms,
+                stable_ms,
+            } => vec![
+                "wait-idle".to_string(),
+                "--stable".to_string(),
+                stable_ms.to_string(),
+                "-o".to_string(),
+                timeout_ms.to_string(),
+            ],
+            ActionType::LongPress { x, y, duration } => vec![
+                "long-press".to_string(),
+                x.to_string(),
+                y.to_string(),
+                "--duration".to_string(),
+                duration.to_string(),
+            ],
+            ActionType::LongPressElement {
+                selector,
+                by_label,
+                duration,
+            } => {
+                let mut args = vec!["long-press".to_string(), selector.clone()];
+                push_selector_flags(&mut args, *by_label, None);
+                args.extend(["--duration".to_string(), duration.to_string()]);
+                args
+            }
+            ActionType::MultiTap { finger_count, x, y } => vec![
+                "multi-tap".to_string(),
+                finger_count.to_string(),
+                x.to_string(),
+                y.to_string(),
+            ],
+            ActionType::Drag {
+                from,
+                to,
+                by_label,
+                hold_duration,
+                move_duration,
+            } => {
+                let mut args = vec!["drag".to_string(), from.clone(), to.clone()];
+                push_selector_flags(&mut args, *by_label, None);
+                args.extend([
+                    "--hold".to_string(),
+                    hold_duration.to_string(),
+                    "--duration".to_string(),
+                    move_duration.to_string(),
+                ]);
+                args
             }
-            ActionType::LongPress { x, y, duration } => Some(format!(
-                "qorvex long-press {} {} --duration {}",
-                x, y, duration
-            )),
             ActionType::SetTarget { bundle_id } => {
-                Some(format!("qorvex set-target {}", shell_escape(bundle_id)))
+                vec!["set-target".to_string(), bundle_id.clone()]
             }
-            ActionType::StartTarget => Some("qorvex start-target".to_string()),
-            ActionType::StopTarget => Some("qorvex stop-target".to_string()),
-            ActionType::GetTargetInfo => Some("qorvex get-target-info".to_string()),
-            ActionType::LogComment { message } => Some(format!("# {}", message)),
-            // Skip session management actions
-            ActionType::StartSession | ActionType::EndSession | ActionType::Quit => None,
-        };
-        match base {
-            Some(mut cmd) => {
-                if let Some(t) = tag {
-                    cmd.push_str(&format!(" --tag {}", shell_escape(t)));
-                }
-                Some(cmd)
+            ActionType::StartTarget => vec!["start-target".to_string()],
+            ActionType::StopTarget => vec!["stop-target".to_string()],
+            ActionType::GetTargetInfo => vec!["get-target-info".to_string()],
+            ActionType::SetAppearance { appearance } => {
+                vec!["appearance".to_string(), appearance.to_string()]
+            }
+            ActionType::SetOrientation { orientation } => {
+                vec!["orientation".to_string(), orientation.to_string()]
+            }
+            ActionType::SetLocale { language, region } => {
+                vec!["set-locale".to_string(), language.clone(), region.clone()]
             }
-            None => None,
+            // Comments and assertions are written in the target language;
+            // session management actions are skipped
+            ActionType::LogComment { .. }
+            | ActionType::Assertion { .. }
+            | ActionType::StartSession
+            | ActionType::EndSession
+            | ActionType::Quit => return None,
+        };
+        if let Some(t) = tag {
+            args.extend(["--tag".to_string(), t.to_string()]);
         }
+        Some(args)
     }
 }
 
+/// The text of actions written as comments rather than commands: comments,
+/// and assertions, whose outcome a replay cannot reproduce.
+fn comment_text(action: &ActionType) -> Option<String> {
+    match action {
+        ActionType::LogComment { message } => Some(message.clone()),
+        _ => action.assertion_summary(),
+    }
+}
+
+/// Appends the `--label` and `-T <type>` flags shared by selector commands.
+fn push_selector_flags(args: &mut Vec<String>, by_label: bool, element_type: Option<&str>) {
+    if by_label {
+        args.push("--label".to_string());
+    }
+    if let Some(t) = element_type {
+        args.extend(["-T".to_string(), t.to_string()]);
+    }
+}
+
+/// Appends the `--glob` and `--first` flags of a glob-matched selector.
+fn push_match_flags(args: &mut Vec<String>, match_mode: MatchMode, first: bool) {
+    if match_mode == MatchMode::Glob {
+        args.push("--glob".to_string());
+        if first {
+            args.push("--first".to_string());
+        }
+    }
+}
+
+/// Quotes a string as a Python string literal.
+fn python_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
 /// Shell-escape a string using single quotes. Internal single quotes become `'\''`.
 fn shell_escape(s: &str) -> String {
     if s.chars()
@@ -157,6 +637,11 @@ fn shell_escape(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use qorvex_core::action::SelectorSpec;
+    use qorvex_core::driver::{ElementFilter, Orientation};
+    use qorvex_core::element::ElementAttribute;
+    use qorvex_core::protocol::SpecialKey;
+    use qorvex_core::simctl::Appearance;
 
     #[test]
     fn test_tap_to_command() {
@@ -165,6 +650,9 @@ mod tests {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         };
         assert_eq!(
             LogConverter::action_to_command(&action, None),
@@ -179,6 +667,9 @@ mod tests {
             by_label: true,
             element_type: None,
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         };
         assert_eq!(
             LogConverter::action_to_command(&action, None),
@@ -193,6 +684,9 @@ mod tests {
             by_label: true,
             element_type: Some("Button".to_string()),
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         };
         assert_eq!(
             LogConverter::action_to_command(&action, None),
@@ -200,6 +694,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tap_glob_to_command() {
+        let action = ActionType::Tap {
+            selector: "row-*".to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Glob,
+            first: true,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex tap 'row-*' --glob --first".to_string())
+        );
+    }
+
     #[test]
     fn test_tap_selector_with_spaces() {
         let action = ActionType::Tap {
@@ -207,6 +718,9 @@ mod tests {
             by_label: true,
             element_type: None,
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         };
         assert_eq!(
             LogConverter::action_to_command(&action, None),
@@ -245,6 +759,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_send_key_to_command() {
+        let action = ActionType::SendKey {
+            key: SpecialKey::Return,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex send-key return".to_string())
+        );
+    }
+
     #[test]
     fn test_send_keys_with_spaces() {
         let action = ActionType::SendKeys {
@@ -267,6 +792,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_clear_text_to_command() {
+        let action = ActionType::ClearText {
+            selector: "email-field".to_string(),
+            by_label: false,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex clear-text email-field".to_string())
+        );
+        let action = ActionType::ClearText {
+            selector: "E-mail".to_string(),
+            by_label: true,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex clear-text E-mail --label".to_string())
+        );
+    }
+
+    #[test]
+    fn test_paste_text_to_command() {
+        let action = ActionType::PasteText {
+            text: "long text with emojis 🎉".to_string(),
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex paste 'long text with emojis 🎉'".to_string())
+        );
+    }
+
     #[test]
     fn test_screenshot_to_command() {
         assert_eq!(
@@ -275,14 +831,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_element_screenshot_to_command() {
+        let action = ActionType::GetElementScreenshot {
+            selector: "Sign In".to_string(),
+            by_label: true,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex screenshot --element 'Sign In' --label".to_string())
+        );
+    }
+
     #[test]
     fn test_screen_info_to_command() {
+        let action = ActionType::GetScreenInfo {
+            filter: ElementFilter::default(),
+            changed_since_hash: None,
+        };
         assert_eq!(
-            LogConverter::action_to_command(&ActionType::GetScreenInfo, None),
+            LogConverter::action_to_command(&action, None),
             Some("qorvex screen-info".to_string())
         );
     }
 
+    #[test]
+    fn test_screen_info_filtered_to_command() {
+        let action = ActionType::GetScreenInfo {
+            filter: ElementFilter {
+                types: vec!["Button".to_string()],
+                visible_only: true,
+                max_depth: Some(2),
+            },
+            changed_since_hash: None,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex screen-info -T Button --visible-only --max-depth 2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_attribute_to_command() {
+        let action = ActionType::GetAttribute {
+            selector: "Email".to_string(),
+            by_label: true,
+            element_type: Some("TextField".to_string()),
+            attribute: ElementAttribute::Placeholder,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex get-attribute Email --attr placeholder --label -T TextField".to_string())
+        );
+    }
+
     #[test]
     fn test_get_value_to_command() {
         let action = ActionType::GetValue {
@@ -290,6 +892,8 @@ mod tests {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
         };
         assert_eq!(
             LogConverter::action_to_command(&action, None),
@@ -297,6 +901,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tap_require_enabled_to_command() {
+        let action = ActionType::Tap {
+            selector: "submit".to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: Some(5000),
+            require_enabled: true,
+            match_mode: MatchMode::Exact,
+            first: false,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex tap submit --require-enabled".to_string())
+        );
+    }
+
+    #[test]
+    fn test_element_exists_to_command() {
+        let action = ActionType::ElementExists {
+            selector: "Log In".to_string(),
+            by_label: true,
+            element_type: Some("Button".to_string()),
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex exists 'Log In' --label -T Button || true".to_string())
+        );
+    }
+
+    #[test]
+    fn test_find_all_to_command() {
+        let action = ActionType::FindAll {
+            selector: String::new(),
+            by_label: false,
+            element_type: Some("Cell".to_string()),
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex find-all -T Cell".to_string())
+        );
+
+        let action = ActionType::FindAll {
+            selector: "Row *".to_string(),
+            by_label: true,
+            element_type: None,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex find-all 'Row *' --label".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tap_if_exists_to_command() {
+        let action = ActionType::TapIfExists {
+            selector: "Not Now".to_string(),
+            by_label: true,
+            element_type: None,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex tap-if-exists 'Not Now' --label".to_string())
+        );
+    }
+
     #[test]
     fn test_wait_for_to_command() {
         let action = ActionType::WaitFor {
@@ -305,6 +975,9 @@ mod tests {
             element_type: None,
             timeout_ms: 5000,
             require_stable: true,
+            poll_interval_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
         };
         assert_eq!(
             LogConverter::action_to_command(&action, None),
@@ -312,6 +985,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_wait_for_poll_interval_to_command() {
+        let action = ActionType::WaitFor {
+            selector: "dashboard".to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: 5000,
+            require_stable: true,
+            poll_interval_ms: Some(50),
+            match_mode: MatchMode::Exact,
+            first: false,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex wait-for dashboard -o 5000 --poll-interval 50".to_string())
+        );
+    }
+
     #[test]
     fn test_wait_for_not_to_command() {
         let action = ActionType::WaitForNot {
@@ -319,6 +1010,7 @@ mod tests {
             by_label: true,
             element_type: Some("ActivityIndicator".to_string()),
             timeout_ms: 10000,
+            poll_interval_ms: None,
         };
         assert_eq!(
             LogConverter::action_to_command(&action, None),
@@ -326,6 +1018,133 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_wait_for_any_to_command() {
+        let spec = |selector: &str, by_label| SelectorSpec {
+            selector: selector.to_string(),
+            by_label,
+            element_type: None,
+        };
+        let action = ActionType::WaitForAny {
+            selectors: vec![spec("Welcome", true), spec("Sign In Failed", true)],
+            timeout_ms: 5000,
+            poll_interval_ms: Some(250),
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some(
+                "qorvex wait-for-any Welcome 'Sign In Failed' --label -o 5000 --poll-interval 250"
+                    .to_string()
+            )
+        );
+
+        // Mixed selector kinds have no single CLI form
+        let mixed = ActionType::WaitForAll {
+            selectors: vec![spec("home-view", false), spec("Welcome", true)],
+            timeout_ms: 5000,
+            poll_interval_ms: None,
+        };
+        assert_eq!(LogConverter::action_to_command(&mixed, None), None);
+    }
+
+    #[test]
+    fn test_wait_for_idle_to_command() {
+        let action = ActionType::WaitForIdle {
+            timeout_ms: 8000,
+            stable_ms: 500,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex wait-idle --stable 500 -o 8000".to_string())
+        );
+    }
+
+    #[test]
+    fn test_multi_tap_to_command() {
+        let action = ActionType::MultiTap {
+            finger_count: 2,
+            x: 100,
+            y: 200,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex multi-tap 2 100 200".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tap_element_offset_to_command() {
+        let action = ActionType::TapElementOffset {
+            selector: "volume-slider".to_string(),
+            by_label: false,
+            dx_fraction: 0.8,
+            dy_fraction: 0.5,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex tap-offset volume-slider 0.8 0.5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_wait_to_command() {
+        let action = ActionType::Wait { ms: 500 };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex wait 500".to_string())
+        );
+    }
+
+    #[test]
+    fn test_swipe_coords_to_command() {
+        let mut action = ActionType::SwipeCoords {
+            start_x: 300,
+            start_y: 400,
+            end_x: 50,
+            end_y: 400,
+            duration: None,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex swipe-coords 300 400 50 400".to_string())
+        );
+        if let ActionType::SwipeCoords { duration, .. } = &mut action {
+            *duration = Some(0.8);
+        }
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex swipe-coords 300 400 50 400 --duration 0.8".to_string())
+        );
+    }
+
+    #[test]
+    fn test_swipe_element_to_command() {
+        let action = ActionType::SwipeElement {
+            selector: "Featured".to_string(),
+            by_label: true,
+            direction: "left".to_string(),
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex swipe-element Featured left --label".to_string())
+        );
+    }
+
+    #[test]
+    fn test_drag_to_command() {
+        let action = ActionType::Drag {
+            from: "Buy milk".to_string(),
+            to: "row-4".to_string(),
+            by_label: true,
+            hold_duration: 0.5,
+            move_duration: 1.0,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex drag 'Buy milk' row-4 --label --hold 0.5 --duration 1".to_string())
+        );
+    }
+
     #[test]
     fn test_long_press_to_command() {
         let action = ActionType::LongPress {
@@ -339,6 +1158,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_long_press_element_to_command() {
+        let action = ActionType::LongPressElement {
+            selector: "message-bubble".to_string(),
+            by_label: false,
+            duration: 1.0,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex long-press message-bubble --duration 1".to_string())
+        );
+        let action = ActionType::LongPressElement {
+            selector: "Hello there".to_string(),
+            by_label: true,
+            duration: 0.5,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex long-press 'Hello there' --label --duration 0.5".to_string())
+        );
+    }
+
     #[test]
     fn test_set_target_to_command() {
         let action = ActionType::SetTarget {
@@ -350,6 +1191,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_appearance_to_command() {
+        let action = ActionType::SetAppearance {
+            appearance: Appearance::Dark,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex appearance dark".to_string())
+        );
+    }
+
+    #[test]
+    fn test_set_orientation_to_command() {
+        let action = ActionType::SetOrientation {
+            orientation: Orientation::LandscapeLeft,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, Some("rotate")),
+            Some("qorvex orientation landscape-left --tag rotate".to_string())
+        );
+    }
+
+    #[test]
+    fn test_set_locale_to_command() {
+        let action = ActionType::SetLocale {
+            language: "fr".to_string(),
+            region: "FR".to_string(),
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("qorvex set-locale fr FR".to_string())
+        );
+    }
+
     #[test]
     fn test_log_comment_to_command() {
         let action = ActionType::LogComment {
@@ -361,6 +1236,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_assertion_to_comment() {
+        let action = ActionType::Assertion {
+            description: "banner shown".to_string(),
+            passed: false,
+        };
+        assert_eq!(
+            LogConverter::action_to_command(&action, None),
+            Some("# ✗ banner shown".to_string())
+        );
+    }
+
     #[test]
     fn test_session_actions_skipped() {
         assert!(LogConverter::action_to_command(&ActionType::StartSession, None).is_none());
@@ -385,6 +1272,9 @@ mod tests {
                 by_label: false,
                 element_type: None,
                 timeout_ms: None,
+                require_enabled: false,
+                match_mode: MatchMode::Exact,
+                first: false,
             },
             ActionResult::Success,
             None,
@@ -406,7 +1296,7 @@ mod tests {
             serde_json::to_string(&log3).unwrap(),
         );
 
-        let result = LogConverter::convert_str(&jsonl).unwrap();
+        let result = LogConverter::convert_str(&jsonl, ConvertOptions::default()).unwrap();
         assert!(result.starts_with("#!/usr/bin/env bash\n"));
         assert!(result.contains("set -euo pipefail"));
         assert!(result.contains("qorvex tap btn"));
@@ -414,6 +1304,198 @@ mod tests {
         assert!(!result.contains("end_session"));
     }
 
+    #[test]
+    fn test_convert_preserve_timing() {
+        use qorvex_core::action::ActionResult;
+
+        let start: DateTime<Utc> = "2026-02-20T10:00:00Z".parse().unwrap();
+        let entry = |action: ActionType, finished_ms: i64, duration_ms: Option<u64>| {
+            let mut log = ActionLog::new(action, ActionResult::Success, None, duration_ms, None);
+            log.timestamp = start + chrono::Duration::milliseconds(finished_ms);
+            serde_json::to_string(&log).unwrap()
+        };
+        let keys = |text: &str| ActionType::SendKeys {
+            text: text.to_string(),
+        };
+        let jsonl = [
+            entry(ActionType::StartSession, 0, None),
+            entry(keys("a"), 1_000, Some(200)),
+            // Starts 1.46s after "a" finished: rounds to 1.5s
+            entry(keys("b"), 2_700, Some(240)),
+            // Starts 2s after "b": a whole number of seconds
+            entry(keys("c"), 4_700, None),
+            // Starts 30ms after "c": too short to wait for
+            entry(keys("d"), 4_830, Some(100)),
+            // Starts a minute later: capped
+            entry(keys("e"), 64_830, None),
+        ]
+        .join("\n");
+
+        let options = ConvertOptions {
+            preserve_timing: Some(Duration::from_secs(5)),
+            ..ConvertOptions::default()
+        };
+        let result = LogConverter::convert_str(&jsonl, options).unwrap();
+        let body: Vec<_> = result.lines().skip(3).collect();
+        assert_eq!(
+            body,
+            [
+                "# 2026-02-20T10:00:01.000Z (200ms)",
+                "qorvex send-keys a",
+                "sleep 1.5",
+                "# 2026-02-20T10:00:02.700Z (240ms)",
+                "qorvex send-keys b",
+                "sleep 2",
+                "# 2026-02-20T10:00:04.700Z",
+                "qorvex send-keys c",
+                "# 2026-02-20T10:00:04.830Z (100ms)",
+                "qorvex send-keys d",
+                "sleep 5",
+                "# 2026-02-20T10:01:04.830Z",
+                "qorvex send-keys e",
+            ]
+        );
+
+        // Without the option, the script has no sleeps or timing comments
+        let result = LogConverter::convert_str(&jsonl, ConvertOptions::default()).unwrap();
+        assert!(!result.contains("sleep"));
+        assert!(!result.contains("# 2026"));
+    }
+
+    #[test]
+    fn test_convert_pytest() {
+        use qorvex_core::action::ActionResult;
+
+        let entry = |action: ActionType, tag: Option<&str>, value: Option<&str>| {
+            let mut log = ActionLog::new(
+                action,
+                ActionResult::Success,
+                None,
+                None,
+                tag.map(String::from),
+            );
+            log.value = value.map(String::from);
+            serde_json::to_string(&log).unwrap()
+        };
+        let get_value = |selector: &str| ActionType::GetValue {
+            selector: selector.to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: Some(5000),
+            match_mode: MatchMode::Exact,
+            first: false,
+        };
+        let jsonl = [
+            entry(ActionType::StartSession, None, None),
+            entry(
+                ActionType::LogComment {
+                    message: "sign in".to_string(),
+                },
+                None,
+                None,
+            ),
+            entry(
+                ActionType::SendKeys {
+                    text: "say \"hi\"\n".to_string(),
+                },
+                Some("Login flow"),
+                None,
+            ),
+            entry(get_value("email"), None, Some("user@example.com")),
+            entry(get_value("notes"), None, None),
+            entry(
+                ActionType::ElementExists {
+                    selector: "banner".to_string(),
+                    by_label: false,
+                    element_type: None,
+                },
+                None,
+                None,
+            ),
+            entry(ActionType::EndSession, None, None),
+            entry(ActionType::StartSession, None, None),
+            entry(ActionType::GetScreenshot, None, None),
+            entry(ActionType::StartSession, None, None),
+            entry(ActionType::StopTarget, Some("login flow!"), None),
+        ]
+        .join("\n");
+
+        let options = ConvertOptions {
+            format: ScriptFormat::Pytest,
+            ..ConvertOptions::default()
+        };
+        let result = LogConverter::convert_str(&jsonl, options).unwrap();
+        assert!(result.contains("\nimport subprocess\n"));
+        assert!(result.contains("\ndef qorvex(*args, check=True):\n"));
+
+        // One function per session, named after its first tag
+        let functions: Vec<_> = result
+            .lines()
+            .filter(|line| line.starts_with("def test_"))
+            .collect();
+        assert_eq!(
+            functions,
+            [
+                "def test_login_flow():",
+                "def test_session_2():",
+                "def test_login_flow_2():",
+            ]
+        );
+
+        let first: Vec<_> = result
+            .split("def test_login_flow():\n")
+            .nth(1)
+            .unwrap()
+            .split("\n\n")
+            .next()
+            .unwrap()
+            .lines()
+            .collect();
+        assert_eq!(
+            first,
+            [
+                "    # sign in",
+                r#"    qorvex("send-keys", "say \"hi\"\n", "--tag", "Login flow")"#,
+                r#"    assert qorvex("get-value", "email") == "user@example.com""#,
+                r#"    qorvex("get-value", "notes")"#,
+                r#"    qorvex("exists", "banner", check=False)"#,
+            ]
+        );
+        assert!(result.contains("def test_session_2():\n    qorvex(\"screenshot\")\n"));
+        assert!(!result.contains("import time"));
+    }
+
+    #[test]
+    fn test_python_string() {
+        assert_eq!(python_string("plain"), r#""plain""#);
+        assert_eq!(python_string(r#"a"b\c"#), r#""a\"b\\c""#);
+        assert_eq!(python_string("tab\tbell\u{7}"), r#""tab\tbell\x07""#);
+        assert_eq!(python_string("café ✉️"), "\"café ✉️\"");
+    }
+
+    #[test]
+    fn test_convert_log_json_output() {
+        use qorvex_core::action::ActionResult;
+
+        let log = ActionLog::new(
+            ActionType::SendKeys {
+                text: "hello".to_string(),
+            },
+            ActionResult::Success,
+            None,
+            None,
+            None,
+        );
+        let output = serde_json::json!({ "ok": true, "data": [log], "error": null });
+
+        let result = LogConverter::convert_str(
+            &serde_json::to_string_pretty(&output).unwrap(),
+            ConvertOptions::default(),
+        )
+        .unwrap();
+        assert!(result.contains("qorvex send-keys hello"));
+    }
+
     #[test]
     fn test_shell_escape_simple() {
         assert_eq!(shell_escape("hello"), "hello");
@@ -441,6 +1523,9 @@ mod tests {
                 by_label: false,
                 element_type: None,
                 timeout_ms: None,
+                require_enabled: false,
+                match_mode: MatchMode::Exact,
+                first: false,
             },
             ActionResult::Success,
             None,
@@ -464,6 +1549,9 @@ mod tests {
                 by_label: false,
                 element_type: None,
                 timeout_ms: None,
+                require_enabled: false,
+                match_mode: MatchMode::Exact,
+                first: false,
             },
             Some("my tag"),
         )
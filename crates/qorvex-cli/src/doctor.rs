@@ -0,0 +1,416 @@
+//! Environment diagnostics for `qorvex doctor`.
+//!
+//! Each check takes the output of the command it inspects rather than running
+//! it, so the pass/fail logic can be tested without Xcode installed.
+//! [`run_checks`] gathers the real outputs and runs every check.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use qorvex_core::screen_recording::{self, ScreenRecordingAccess};
+use qorvex_core::simctl::{Simctl, SimctlError, SimulatorDevice};
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// The check passed.
+    Pass,
+    /// Something is missing that only some workflows need.
+    Warn,
+    /// qorvex cannot drive a simulator until this is fixed.
+    Fail,
+}
+
+/// The result of one diagnostic check.
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    /// Short name of what was checked.
+    pub name: &'static str,
+    /// Whether it passed.
+    pub status: Status,
+    /// What was found.
+    pub detail: String,
+    /// How to fix it; `None` when the check passed.
+    pub hint: Option<&'static str>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn problem(
+        name: &'static str,
+        status: Status,
+        detail: impl Into<String>,
+        hint: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+            hint: Some(hint),
+        }
+    }
+}
+
+/// Output of an external command: whether it exited successfully and its
+/// trimmed stdout. `None` in place of this means it could not be run at all.
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub stdout: String,
+}
+
+impl CommandOutput {
+    /// Runs `program` with `args`, returning `None` if it cannot be spawned.
+    fn capture(program: &str, args: &[&str]) -> Option<Self> {
+        let output = Command::new(program).args(args).output().ok()?;
+        Some(Self {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        })
+    }
+}
+
+const XCRUN_HINT: &str = "Install Xcode and its command-line tools (xcode-select --install)";
+const XCODE_SELECT_HINT: &str =
+    "Point xcode-select at Xcode: sudo xcode-select -s /Applications/Xcode.app";
+const RUNTIME_HINT: &str = "Install an iOS runtime in Xcode > Settings > Platforms";
+const BOOTED_HINT: &str =
+    "Boot one with qorvex boot-device <udid>, or qorvex start --device <udid>";
+const STREAMER_HINT: &str =
+    "Build qorvex-streamer and put it on PATH; qorvex-live falls back to polling without it";
+const SCREEN_RECORDING_HINT: &str = "Grant your terminal access in System Settings > Privacy & \
+     Security > Screen & System Audio Recording, then restart it; only qorvex-live needs this";
+const SOCKET_HINT: &str = "Start a server with qorvex start";
+
+/// `xcrun --find simctl`: `xcrun` runs and can locate `simctl`.
+pub fn check_xcrun(output: Option<&CommandOutput>) -> Check {
+    const NAME: &str = "xcrun";
+    match output {
+        Some(out) if out.success => Check::pass(NAME, format!("simctl at {}", out.stdout)),
+        Some(_) => Check::problem(NAME, Status::Fail, "xcrun cannot find simctl", XCRUN_HINT),
+        None => Check::problem(NAME, Status::Fail, "xcrun not found", XCRUN_HINT),
+    }
+}
+
+/// `xcode-select -p`: the selected developer directory exists.
+pub fn check_xcode_select(output: Option<&CommandOutput>, exists: impl Fn(&Path) -> bool) -> Check {
+    const NAME: &str = "xcode-select";
+    match output {
+        Some(out) if out.success && !out.stdout.is_empty() => {
+            if exists(Path::new(&out.stdout)) {
+                Check::pass(NAME, out.stdout.clone())
+            } else {
+                Check::problem(
+                    NAME,
+                    Status::Fail,
+                    format!("{} does not exist", out.stdout),
+                    XCODE_SELECT_HINT,
+                )
+            }
+        }
+        Some(_) => Check::problem(
+            NAME,
+            Status::Fail,
+            "no developer directory selected",
+            XCODE_SELECT_HINT,
+        ),
+        None => Check::problem(
+            NAME,
+            Status::Fail,
+            "xcode-select not found",
+            XCODE_SELECT_HINT,
+        ),
+    }
+}
+
+/// `xcrun simctl list runtimes -j`: at least one runtime is available.
+pub fn check_runtimes(output: Option<&CommandOutput>) -> Check {
+    const NAME: &str = "Simulator runtimes";
+
+    #[derive(Deserialize)]
+    struct Runtimes {
+        runtimes: Vec<Runtime>,
+    }
+    #[derive(Deserialize)]
+    struct Runtime {
+        name: String,
+        #[serde(rename = "isAvailable", default)]
+        is_available: bool,
+    }
+
+    let Some(out) = output.filter(|out| out.success) else {
+        return Check::problem(NAME, Status::Fail, "could not list runtimes", RUNTIME_HINT);
+    };
+    let names: Vec<String> = match serde_json::from_str::<Runtimes>(&out.stdout) {
+        Ok(list) => list
+            .runtimes
+            .into_iter()
+            .filter(|r| r.is_available)
+            .map(|r| r.name)
+            .collect(),
+        Err(e) => {
+            return Check::problem(
+                NAME,
+                Status::Fail,
+                format!("unreadable runtime list: {}", e),
+                RUNTIME_HINT,
+            )
+        }
+    };
+    if names.is_empty() {
+        Check::problem(NAME, Status::Fail, "none installed", RUNTIME_HINT)
+    } else {
+        Check::pass(NAME, names.join(", "))
+    }
+}
+
+/// Whether a simulator is booted. Not critical: `qorvex start` can boot one.
+pub fn check_booted(devices: &Result<Vec<SimulatorDevice>, SimctlError>) -> Check {
+    const NAME: &str = "Booted simulator";
+    match devices {
+        Ok(devices) => match Simctl::find_booted_device(devices) {
+            Some(device) => Check::pass(NAME, format!("{} ({})", device.name, device.udid)),
+            None => Check::problem(NAME, Status::Warn, "none booted", BOOTED_HINT),
+        },
+        Err(e) => Check::problem(
+            NAME,
+            Status::Warn,
+            format!("could not list devices: {}", e),
+            BOOTED_HINT,
+        ),
+    }
+}
+
+/// Whether the `qorvex-streamer` binary was found. Only `qorvex-live` uses it.
+pub fn check_streamer(found: Option<&Path>) -> Check {
+    const NAME: &str = "qorvex-streamer";
+    match found {
+        Some(path) => Check::pass(NAME, path.display().to_string()),
+        None => Check::problem(NAME, Status::Warn, "not found", STREAMER_HINT),
+    }
+}
+
+/// Whether Screen Recording is allowed. Only `qorvex-live`'s video feed
+/// needs it.
+pub fn check_screen_recording(access: ScreenRecordingAccess) -> Check {
+    const NAME: &str = "Screen Recording";
+    match access {
+        ScreenRecordingAccess::Granted => Check::pass(NAME, "granted"),
+        ScreenRecordingAccess::Denied => {
+            Check::problem(NAME, Status::Warn, "not granted", SCREEN_RECORDING_HINT)
+        }
+        ScreenRecordingAccess::Unknown => Check::problem(
+            NAME,
+            Status::Warn,
+            "could not be checked",
+            SCREEN_RECORDING_HINT,
+        ),
+    }
+}
+
+/// Whether a server socket exists for `session`.
+pub fn check_socket(session: &str, socket: &Path, exists: bool) -> Check {
+    const NAME: &str = "Server socket";
+    if exists {
+        Check::pass(
+            NAME,
+            format!("session '{}' at {}", session, socket.display()),
+        )
+    } else {
+        Check::problem(
+            NAME,
+            Status::Warn,
+            format!("no server for session '{}'", session),
+            SOCKET_HINT,
+        )
+    }
+}
+
+/// Finds `name` in the directories of a `PATH`-style list, then next to the
+/// running executable (where `cargo install` and the release archive put it).
+fn find_binary(name: &str, path_var: Option<&OsStr>, exe: Option<&Path>) -> Option<PathBuf> {
+    path_var
+        .into_iter()
+        .flat_map(std::env::split_paths)
+        .chain(exe.and_then(Path::parent).map(Path::to_path_buf))
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Runs every check against the local machine, in checklist order.
+pub fn run_checks(session: &str) -> Vec<Check> {
+    let xcrun = CommandOutput::capture("xcrun", &["--find", "simctl"]);
+    let xcode_select = CommandOutput::capture("xcode-select", &["-p"]);
+    let runtimes = CommandOutput::capture("xcrun", &["simctl", "list", "runtimes", "-j"]);
+    let path_var = std::env::var_os("PATH");
+    let exe = std::env::current_exe().ok();
+    let streamer = find_binary("qorvex-streamer", path_var.as_deref(), exe.as_deref());
+    let socket = qorvex_core::ipc::socket_path(session);
+
+    vec![
+        check_xcrun(xcrun.as_ref()),
+        check_xcode_select(xcode_select.as_ref(), Path::is_dir),
+        check_runtimes(runtimes.as_ref()),
+        check_booted(&Simctl::list_devices()),
+        check_streamer(streamer.as_deref()),
+        check_screen_recording(screen_recording::check()),
+        check_socket(session, &socket, socket.exists()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok(stdout: &str) -> Option<CommandOutput> {
+        Some(CommandOutput {
+            success: true,
+            stdout: stdout.to_string(),
+        })
+    }
+
+    fn failed() -> Option<CommandOutput> {
+        Some(CommandOutput {
+            success: false,
+            stdout: String::new(),
+        })
+    }
+
+    fn device(name: &str, state: &str) -> SimulatorDevice {
+        SimulatorDevice {
+            udid: format!("{}-UDID", name),
+            name: name.to_string(),
+            state: state.to_string(),
+            device_type: None,
+            runtime: None,
+            is_available: true,
+        }
+    }
+
+    #[test]
+    fn xcrun_fails_when_missing_or_broken() {
+        let found = ok("/usr/bin/simctl");
+        assert_eq!(check_xcrun(found.as_ref()).status, Status::Pass);
+        assert_eq!(check_xcrun(failed().as_ref()).status, Status::Fail);
+        let missing = check_xcrun(None);
+        assert_eq!(missing.status, Status::Fail);
+        assert!(missing.hint.is_some());
+    }
+
+    #[test]
+    fn xcode_select_requires_an_existing_directory() {
+        let selected = ok("/Applications/Xcode.app/Contents/Developer");
+        assert_eq!(
+            check_xcode_select(selected.as_ref(), |_| true).status,
+            Status::Pass
+        );
+        let stale = check_xcode_select(selected.as_ref(), |_| false);
+        assert_eq!(stale.status, Status::Fail);
+        assert!(stale.detail.contains("does not exist"));
+        assert_eq!(
+            check_xcode_select(failed().as_ref(), |_| true).status,
+            Status::Fail
+        );
+    }
+
+    #[test]
+    fn runtimes_counts_only_available_ones() {
+        let json = r#"{"runtimes": [
+            {"name": "iOS 17.5", "isAvailable": true},
+            {"name": "iOS 16.4", "isAvailable": false}
+        ]}"#;
+        let check = check_runtimes(ok(json).as_ref());
+        assert_eq!(check.status, Status::Pass);
+        assert_eq!(check.detail, "iOS 17.5");
+
+        let only_unavailable = r#"{"runtimes": [{"name": "iOS 16.4", "isAvailable": false}]}"#;
+        assert_eq!(
+            check_runtimes(ok(only_unavailable).as_ref()).status,
+            Status::Fail
+        );
+        assert_eq!(check_runtimes(ok("not json").as_ref()).status, Status::Fail);
+        assert_eq!(check_runtimes(None).status, Status::Fail);
+    }
+
+    #[test]
+    fn booted_simulator_is_a_warning_only() {
+        let devices = Ok(vec![
+            device("iPhone 15", "Shutdown"),
+            device("iPhone 16", "Booted"),
+        ]);
+        let check = check_booted(&devices);
+        assert_eq!(check.status, Status::Pass);
+        assert_eq!(check.detail, "iPhone 16 (iPhone 16-UDID)");
+
+        let none = Ok(vec![device("iPhone 15", "Shutdown")]);
+        assert_eq!(check_booted(&none).status, Status::Warn);
+        assert_eq!(
+            check_booted(&Err(SimctlError::XcodeNotFound)).status,
+            Status::Warn
+        );
+    }
+
+    #[test]
+    fn streamer_and_socket_are_warnings_only() {
+        assert_eq!(
+            check_streamer(Some(Path::new("/usr/local/bin/qorvex-streamer"))).status,
+            Status::Pass
+        );
+        assert_eq!(check_streamer(None).status, Status::Warn);
+
+        let socket = Path::new("/tmp/qorvex_default.sock");
+        assert_eq!(check_socket("default", socket, true).status, Status::Pass);
+        let missing = check_socket("default", socket, false);
+        assert_eq!(missing.status, Status::Warn);
+        assert_eq!(missing.hint, Some(SOCKET_HINT));
+    }
+
+    #[test]
+    fn screen_recording_is_a_warning_only() {
+        assert_eq!(
+            check_screen_recording(ScreenRecordingAccess::Granted).status,
+            Status::Pass
+        );
+        let denied = check_screen_recording(ScreenRecordingAccess::Denied);
+        assert_eq!(denied.status, Status::Warn);
+        assert_eq!(denied.hint, Some(SCREEN_RECORDING_HINT));
+        assert_eq!(
+            check_screen_recording(ScreenRecordingAccess::Unknown).status,
+            Status::Warn
+        );
+    }
+
+    #[test]
+    fn find_binary_searches_path_then_exe_dir() {
+        let root = std::env::temp_dir().join(format!("qorvex-doctor-{}", std::process::id()));
+        let (on_path, beside_exe) = (root.join("bin"), root.join("exe"));
+        std::fs::create_dir_all(&on_path).unwrap();
+        std::fs::create_dir_all(&beside_exe).unwrap();
+        std::fs::write(beside_exe.join("tool"), b"").unwrap();
+        let exe = beside_exe.join("qorvex");
+        let path_var = std::env::join_paths([&on_path]).unwrap();
+
+        assert_eq!(
+            find_binary("tool", Some(&path_var), Some(&exe)),
+            Some(beside_exe.join("tool"))
+        );
+        std::fs::write(on_path.join("tool"), b"").unwrap();
+        assert_eq!(
+            find_binary("tool", Some(&path_var), Some(&exe)),
+            Some(on_path.join("tool"))
+        );
+        assert_eq!(find_binary("missing", Some(&path_var), None), None);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -26,6 +26,12 @@
 //! # Get screenshot (base64)
 //! qorvex screenshot > screen.b64
 //!
+//! # Save screenshot as a PNG file
+//! qorvex screenshot --output screen.png
+//!
+//! # Save a screenshot of one element
+//! qorvex screenshot --element login-button --output btn.png
+//!
 //! # Get screen info (concise actionable elements)
 //! qorvex screen-info
 //!
@@ -35,6 +41,9 @@
 //! # Get REPL-style formatted list
 //! qorvex screen-info --pretty
 //!
+//! # Only visible buttons
+//! qorvex screen-info --type Button --visible-only
+//!
 //! # Get element value (waits for element by default)
 //! qorvex get-value username-field
 //! qorvex get-value "Email" --label
@@ -51,13 +60,24 @@
 //! ```
 
 mod converter;
+mod doctor;
+mod output;
 
 use clap::{Parser, Subcommand};
-use qorvex_core::action::ActionType;
+use output::CliOutput;
+use qorvex_core::action::{ActionLog, ActionType, MatchMode, SelectorSpec};
 use qorvex_core::adb_device::Adb;
-use qorvex_core::element::{ElementFrame, UIElement};
+use qorvex_core::describe::ScreenSummary;
+use qorvex_core::driver::{ElementFilter, Orientation, ScreenInfo};
+use qorvex_core::element::{hash_elements, ElementAttribute, ElementFrame, UIElement};
 use qorvex_core::ipc::{qorvex_dir, IpcClient, IpcRequest, IpcResponse, Platform};
-use qorvex_core::simctl::Simctl;
+use qorvex_core::protocol::SpecialKey;
+use qorvex_core::result_data;
+use qorvex_core::session::SessionEvent;
+use qorvex_core::simctl::{
+    Appearance, ContainerKind, DeviceFilter, Simctl, SimctlError, DEFAULT_BOOT_TIMEOUT,
+};
+use serde::Serialize;
 use std::path::PathBuf;
 use std::process::ExitCode;
 use tracing_subscriber::EnvFilter;
@@ -72,7 +92,7 @@ struct Cli {
     #[arg(short, long, default_value = "default", env = "QORVEX_SESSION")]
     session: String,
 
-    /// Output format: text or json
+    /// Output format: text, or json (one `{ok, data, error}` object per command)
     #[arg(short, long, default_value = "text")]
     format: OutputFormat,
 
@@ -80,10 +100,93 @@ struct Cli {
     #[arg(short, long)]
     quiet: bool,
 
+    /// Seconds to wait for the session's server before giving up
+    #[arg(long, default_value = "5", value_parser = parse_positive_secs)]
+    connect_timeout: f64,
+
+    /// Seconds to wait for each server reply (default: no limit, since agent
+    /// builds and long waits can take minutes)
+    #[arg(long, value_parser = parse_positive_secs)]
+    request_timeout: Option<f64>,
+
     #[command(subcommand)]
     command: Command,
 }
 
+impl Command {
+    /// Whether `--no-screenshot` was passed to an action subcommand.
+    fn no_screenshot(&self) -> bool {
+        match *self {
+            Command::Tap { no_screenshot, .. }
+            | Command::TapLocation { no_screenshot, .. }
+            | Command::TapOffset { no_screenshot, .. }
+            | Command::MultiTap { no_screenshot, .. }
+            | Command::LongPress { no_screenshot, .. }
+            | Command::Drag { no_screenshot, .. }
+            | Command::SendKeys { no_screenshot, .. }
+            | Command::SendKey { no_screenshot, .. }
+            | Command::ClearText { no_screenshot, .. }
+            | Command::Paste { no_screenshot, .. }
+            | Command::Screenshot { no_screenshot, .. }
+            | Command::ScreenInfo { no_screenshot, .. }
+            | Command::Describe { no_screenshot, .. }
+            | Command::GetValue { no_screenshot, .. }
+            | Command::GetAttribute { no_screenshot, .. }
+            | Command::Exists { no_screenshot, .. }
+            | Command::TapIfExists { no_screenshot, .. }
+            | Command::FindAll { no_screenshot, .. }
+            | Command::Wait { no_screenshot, .. }
+            | Command::WaitFor { no_screenshot, .. }
+            | Command::WaitForNot { no_screenshot, .. }
+            | Command::WaitForAny { no_screenshot, .. }
+            | Command::WaitForAll { no_screenshot, .. }
+            | Command::WaitIdle { no_screenshot, .. }
+            | Command::Swipe { no_screenshot, .. }
+            | Command::SwipeCoords { no_screenshot, .. }
+            | Command::SwipeElement { no_screenshot, .. }
+            | Command::SetTarget { no_screenshot, .. }
+            | Command::Orientation { no_screenshot, .. } => no_screenshot,
+            // Logged without a screenshot whatever the session setting
+            Command::Comment { .. }
+            | Command::Assert { .. }
+            | Command::Appearance { .. }
+            | Command::SetLocale { .. } => false,
+            // Not actions, so never logged
+            Command::StartTarget
+            | Command::StopTarget
+            | Command::TargetInfo
+            | Command::DeviceInfo
+            | Command::ElementAt { .. }
+            | Command::Ping { .. }
+            | Command::BootDevice { .. }
+            | Command::Shutdown { .. }
+            | Command::CreateDevice { .. }
+            | Command::DeleteDevice { .. }
+            | Command::ListDevices { .. }
+            | Command::ListApps { .. }
+            | Command::AppContainer { .. }
+            | Command::ListPhysicalDevices
+            | Command::UseDevice { .. }
+            | Command::Convert { .. }
+            | Command::Status
+            | Command::Health
+            | Command::Log
+            | Command::SetScreenshots { .. }
+            | Command::Cancel
+            | Command::Record { .. }
+            | Command::ListSessions
+            | Command::Doctor
+            | Command::Start { .. }
+            | Command::StartSession
+            | Command::StartAgent { .. }
+            | Command::StopAgent
+            | Command::RestartAgent
+            | Command::Stop
+            | Command::Completions { .. } => false,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 enum OutputFormat {
     Text,
@@ -108,6 +211,117 @@ impl From<PlatformArg> for Platform {
     }
 }
 
+/// `qorvex record` subcommands.
+#[derive(Subcommand)]
+enum RecordAction {
+    /// Start recording (the server keeps recording until `record stop`)
+    Start {
+        /// Output video file (e.g. out.mov)
+        path: PathBuf,
+    },
+    /// Stop recording and finalize the video file
+    Stop,
+}
+
+/// Simulator appearance (CLI-facing; maps to
+/// [`qorvex_core::simctl::Appearance`]).
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum AppearanceArg {
+    Light,
+    Dark,
+}
+
+impl From<AppearanceArg> for Appearance {
+    fn from(a: AppearanceArg) -> Self {
+        match a {
+            AppearanceArg::Light => Appearance::Light,
+            AppearanceArg::Dark => Appearance::Dark,
+        }
+    }
+}
+
+/// Device orientation (CLI-facing; maps to
+/// [`qorvex_core::driver::Orientation`]).
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum OrientationArg {
+    Portrait,
+    PortraitUpsideDown,
+    LandscapeLeft,
+    LandscapeRight,
+}
+
+impl From<OrientationArg> for Orientation {
+    fn from(o: OrientationArg) -> Self {
+        match o {
+            OrientationArg::Portrait => Orientation::Portrait,
+            OrientationArg::PortraitUpsideDown => Orientation::PortraitUpsideDown,
+            OrientationArg::LandscapeLeft => Orientation::LandscapeLeft,
+            OrientationArg::LandscapeRight => Orientation::LandscapeRight,
+        }
+    }
+}
+
+/// An on/off setting.
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Toggle {
+    On,
+    Off,
+}
+
+/// Special keyboard key (CLI-facing; maps to
+/// [`qorvex_core::protocol::SpecialKey`]).
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum SpecialKeyArg {
+    Return,
+    Tab,
+    Delete,
+    Escape,
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl From<SpecialKeyArg> for SpecialKey {
+    fn from(k: SpecialKeyArg) -> Self {
+        match k {
+            SpecialKeyArg::Return => SpecialKey::Return,
+            SpecialKeyArg::Tab => SpecialKey::Tab,
+            SpecialKeyArg::Delete => SpecialKey::Delete,
+            SpecialKeyArg::Escape => SpecialKey::Escape,
+            SpecialKeyArg::Up => SpecialKey::Up,
+            SpecialKeyArg::Down => SpecialKey::Down,
+            SpecialKeyArg::Left => SpecialKey::Left,
+            SpecialKeyArg::Right => SpecialKey::Right,
+        }
+    }
+}
+
+/// Element attribute (CLI-facing; maps to
+/// [`qorvex_core::element::ElementAttribute`]).
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum AttributeArg {
+    Label,
+    Value,
+    Placeholder,
+    Identifier,
+    Traits,
+    Frame,
+}
+
+impl From<AttributeArg> for ElementAttribute {
+    fn from(a: AttributeArg) -> Self {
+        match a {
+            AttributeArg::Label => ElementAttribute::Label,
+            AttributeArg::Value => ElementAttribute::Value,
+            AttributeArg::Placeholder => ElementAttribute::Placeholder,
+            AttributeArg::Identifier => ElementAttribute::Identifier,
+            AttributeArg::Traits => ElementAttribute::Traits,
+            AttributeArg::Frame => ElementAttribute::Frame,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Command {
     /// Tap an element by ID or label
@@ -126,9 +340,22 @@ enum Command {
         /// Timeout in milliseconds for retrying
         #[arg(short = 'o', long, default_value = "5000", env = "QORVEX_TIMEOUT")]
         timeout: u64,
+        /// Fail instead of tapping if the element reports itself disabled
+        #[arg(long)]
+        require_enabled: bool,
+        /// Treat the selector as a glob (`*`, `?`) that must match exactly one element
+        #[arg(long)]
+        glob: bool,
+        /// With --glob, use the first of several matches instead of failing
+        #[arg(long, requires = "glob")]
+        first: bool,
         /// Annotate the action log entry with a free-text tag
         #[arg(long)]
         tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
     },
 
     /// Tap at screen coordinates
@@ -140,36 +367,174 @@ enum Command {
         /// Annotate the action log entry with a free-text tag
         #[arg(long)]
         tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
     },
 
-    /// Long press at screen coordinates
-    LongPress {
+    /// Tap a point within an element, as fractions of its width and height
+    TapOffset {
+        /// Element selector (ID or label with --label)
+        selector: String,
+        /// Fraction of the width from the left edge (0.0-1.0)
+        #[arg(value_parser = parse_fraction)]
+        dx: f64,
+        /// Fraction of the height from the top edge (0.0-1.0)
+        #[arg(value_parser = parse_fraction)]
+        dy: f64,
+        /// Match by accessibility label instead of ID
+        #[arg(short, long)]
+        label: bool,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Tap at screen coordinates with several fingers at once
+    MultiTap {
+        /// Number of fingers (1-5)
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=5))]
+        fingers: u8,
         /// X coordinate
         x: i32,
         /// Y coordinate
         y: i32,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Long press an element, or screen coordinates given as <X> <Y>
+    LongPress {
+        /// Element selector (ID or label with --label), or the X coordinate
+        target: String,
+        /// Y coordinate, when pressing coordinates
+        y: Option<i32>,
+        /// Match by accessibility label instead of ID
+        #[arg(short, long, conflicts_with = "y")]
+        label: bool,
         /// Duration in seconds (default: 1.0)
-        #[arg(long, short, default_value = "1.0")]
+        #[arg(long, short, default_value = "1.0", value_parser = parse_positive_secs)]
+        duration: f64,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Press an element, hold, and drag it onto another element
+    Drag {
+        /// Selector of the element to pick up
+        from: String,
+        /// Selector of the element to drop onto
+        to: String,
+        /// Match both selectors by accessibility label instead of ID
+        #[arg(short, long)]
+        label: bool,
+        /// Seconds to hold before moving (default: 0.5)
+        #[arg(long, default_value = "0.5")]
+        hold: f64,
+        /// Seconds the move takes (default: 0.5)
+        #[arg(long, short, default_value = "0.5")]
         duration: f64,
         /// Annotate the action log entry with a free-text tag
         #[arg(long)]
         tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
     },
 
     /// Send keyboard input
     SendKeys {
-        /// Text to type
+        /// Text to type; `{return}`, `{tab}` and other key names in braces
+        /// press that key
+        text: String,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Press a special key (return, tab, delete, escape or an arrow)
+    SendKey {
+        /// Key to press
+        #[arg(value_enum)]
+        key: SpecialKeyArg,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Delete all text in an input field
+    ClearText {
+        /// The selector (accessibility ID or label)
+        selector: String,
+        /// Match by accessibility label instead of ID
+        #[arg(short, long)]
+        label: bool,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Paste text into the focused field via the simulator pasteboard
+    ///
+    /// Faster than send-keys for long text and not subject to autocorrect.
+    Paste {
+        /// Text to paste
         text: String,
         /// Annotate the action log entry with a free-text tag
         #[arg(long)]
         tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
     },
 
     /// Capture a screenshot (outputs base64-encoded PNG)
     Screenshot {
+        /// Write the decoded PNG to this file instead (`-` for raw PNG on stdout)
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+        /// Crop to the frame of this element (accessibility ID or label)
+        #[arg(long, value_name = "SELECTOR")]
+        element: Option<String>,
+        /// Match --element by accessibility label instead of ID
+        #[arg(short, long, requires = "element")]
+        label: bool,
         /// Annotate the action log entry with a free-text tag
         #[arg(long)]
         tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
     },
 
     /// Get UI hierarchy information
@@ -180,9 +545,39 @@ enum Command {
         /// Output REPL-style formatted list
         #[arg(long)]
         pretty: bool,
+        /// Only include elements of this type (repeatable, e.g., -T Button -T Cell)
+        #[arg(short = 'T', long = "type")]
+        types: Vec<String>,
+        /// Only include elements with a non-zero on-screen frame
+        #[arg(long)]
+        visible_only: bool,
+        /// Only descend this many levels into the tree (0 = top-level only)
+        #[arg(long)]
+        max_depth: Option<u32>,
+        /// Print nothing and exit with code 4 if the screen still hashes to this value
+        #[arg(long, value_name = "HASH")]
+        if_changed: Option<String>,
+        /// Keep running and reprint whenever another client's action changes the screen
+        #[arg(long, conflicts_with_all = ["if_changed", "tag", "no_screenshot"])]
+        watch: bool,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Summarize the screen: title, element counts by type and tappable buttons
+    Describe {
         /// Annotate the action log entry with a free-text tag
         #[arg(long)]
         tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
     },
 
     /// Get the value of an element by ID or label
@@ -201,9 +596,102 @@ enum Command {
         /// Timeout in milliseconds for retrying
         #[arg(short = 'o', long, default_value = "5000", env = "QORVEX_TIMEOUT")]
         timeout: u64,
+        /// Treat the selector as a glob (`*`, `?`) that must match exactly one element
+        #[arg(long)]
+        glob: bool,
+        /// With --glob, use the first of several matches instead of failing
+        #[arg(long, requires = "glob")]
+        first: bool,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Read one attribute of an element (prints null if it has none)
+    GetAttribute {
+        /// The selector (accessibility ID or label)
+        selector: String,
+        /// Attribute to read
+        #[arg(long, value_enum)]
+        attr: AttributeArg,
+        /// Match by accessibility label instead of ID
+        #[arg(short, long)]
+        label: bool,
+        /// Filter by element type (e.g., Button, TextField)
+        #[arg(short = 'T', long = "type")]
+        element_type: Option<String>,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Check whether an element is on screen: exits 0 if it is, 1 if not
+    Exists {
+        /// The selector (accessibility ID or label)
+        selector: String,
+        /// Match by accessibility label instead of ID
+        #[arg(short, long)]
+        label: bool,
+        /// Filter by element type (e.g., Button, TextField)
+        #[arg(short = 'T', long = "type")]
+        element_type: Option<String>,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Tap an element if it is on screen; succeed without tapping if not
+    TapIfExists {
+        /// The selector (accessibility ID or label)
+        selector: String,
+        /// Match by accessibility label instead of ID
+        #[arg(short, long)]
+        label: bool,
+        /// Filter by element type (e.g., Button, TextField)
+        #[arg(short = 'T', long = "type")]
+        element_type: Option<String>,
         /// Annotate the action log entry with a free-text tag
         #[arg(long)]
         tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// List every element matching a selector, printing the count first
+    FindAll {
+        /// The selector (accessibility ID or label); omit to match any element
+        #[arg(default_value = "")]
+        selector: String,
+        /// Match by accessibility label instead of ID
+        #[arg(short, long)]
+        label: bool,
+        /// Filter by element type (e.g., Cell, Button)
+        #[arg(short = 'T', long = "type")]
+        element_type: Option<String>,
+        /// Fail unless exactly this many elements match
+        #[arg(long)]
+        expect: Option<usize>,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
     },
 
     /// Log a comment to the session
@@ -215,6 +703,32 @@ enum Command {
         tag: Option<String>,
     },
 
+    /// Record the outcome of a check made by a script; exits non-zero when
+    /// it failed
+    Assert {
+        /// What was checked, e.g. "welcome banner is shown"
+        description: String,
+        /// Record the check as failed
+        #[arg(long)]
+        failed: bool,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
+
+    /// Pause for a fixed time, recorded in the action log
+    Wait {
+        /// Milliseconds to pause
+        ms: u64,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
     /// Wait for an element to appear by ID or label
     WaitFor {
         /// The selector (accessibility ID or label)
@@ -228,9 +742,22 @@ enum Command {
         /// Timeout in milliseconds
         #[arg(short = 'o', long, default_value = "5000", env = "QORVEX_TIMEOUT")]
         timeout: u64,
+        /// Time between polls in milliseconds (default: the server's, 250)
+        #[arg(long = "poll-interval", value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+        poll_interval: Option<u64>,
+        /// Treat the selector as a glob (`*`, `?`) that must match exactly one element
+        #[arg(long)]
+        glob: bool,
+        /// With --glob, use the first of several matches instead of failing
+        #[arg(long, requires = "glob")]
+        first: bool,
         /// Annotate the action log entry with a free-text tag
         #[arg(long)]
         tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
     },
 
     /// Wait for an element to disappear by ID or label
@@ -246,9 +773,85 @@ enum Command {
         /// Timeout in milliseconds
         #[arg(short = 'o', long, default_value = "5000", env = "QORVEX_TIMEOUT")]
         timeout: u64,
+        /// Time between polls in milliseconds (default: the server's, 250)
+        #[arg(long = "poll-interval", value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+        poll_interval: Option<u64>,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Wait for any one of several elements to appear, reporting which
+    WaitForAny {
+        /// The selectors (accessibility IDs or labels), in order of preference
+        #[arg(required = true)]
+        selectors: Vec<String>,
+        /// Match by accessibility label instead of ID
+        #[arg(short, long)]
+        label: bool,
+        /// Filter by element type (e.g., Button, TextField)
+        #[arg(short = 'T', long = "type")]
+        element_type: Option<String>,
+        /// Timeout in milliseconds
+        #[arg(short = 'o', long, default_value = "5000", env = "QORVEX_TIMEOUT")]
+        timeout: u64,
+        /// Time between polls in milliseconds (default: the server's, 250)
+        #[arg(long = "poll-interval", value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+        poll_interval: Option<u64>,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Wait for several elements to all be on screen
+    WaitForAll {
+        /// The selectors (accessibility IDs or labels)
+        #[arg(required = true)]
+        selectors: Vec<String>,
+        /// Match by accessibility label instead of ID
+        #[arg(short, long)]
+        label: bool,
+        /// Filter by element type (e.g., Button, TextField)
+        #[arg(short = 'T', long = "type")]
+        element_type: Option<String>,
+        /// Timeout in milliseconds
+        #[arg(short = 'o', long, default_value = "5000", env = "QORVEX_TIMEOUT")]
+        timeout: u64,
+        /// Time between polls in milliseconds (default: the server's, 250)
+        #[arg(long = "poll-interval", value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+        poll_interval: Option<u64>,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Wait until the screen stops changing
+    WaitIdle {
+        /// How long the screen must stay unchanged, in milliseconds
+        #[arg(long, default_value = "500")]
+        stable: u64,
+        /// Timeout in milliseconds
+        #[arg(short = 'o', long, default_value = "5000", env = "QORVEX_TIMEOUT")]
+        timeout: u64,
         /// Annotate the action log entry with a free-text tag
         #[arg(long)]
         tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
     },
 
     /// Swipe the screen in a direction
@@ -258,6 +861,54 @@ enum Command {
         /// Annotate the action log entry with a free-text tag
         #[arg(long)]
         tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Swipe between two explicit screen coordinates
+    SwipeCoords {
+        /// Start X coordinate
+        #[arg(value_parser = clap::value_parser!(i32).range(0..))]
+        x1: i32,
+        /// Start Y coordinate
+        #[arg(value_parser = clap::value_parser!(i32).range(0..))]
+        y1: i32,
+        /// End X coordinate
+        #[arg(value_parser = clap::value_parser!(i32).range(0..))]
+        x2: i32,
+        /// End Y coordinate
+        #[arg(value_parser = clap::value_parser!(i32).range(0..))]
+        y2: i32,
+        /// Duration in seconds (default: 0.3)
+        #[arg(long, short, value_parser = parse_positive_secs)]
+        duration: Option<f64>,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Swipe in a direction within an element, e.g. a carousel
+    SwipeElement {
+        /// Element selector (ID or label with --label)
+        selector: String,
+        /// Direction: up, down, left, right
+        direction: String,
+        /// Match by accessibility label instead of ID
+        #[arg(short, long)]
+        label: bool,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
     },
 
     /// Set the target application bundle ID
@@ -267,17 +918,77 @@ enum Command {
         /// Annotate the action log entry with a free-text tag
         #[arg(long)]
         tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
     },
 
-    /// Launch the target application
-    StartTarget,
+    /// Switch the simulator between light and dark mode
+    Appearance {
+        /// Appearance to apply
+        #[arg(value_enum)]
+        appearance: AppearanceArg,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
 
-    /// Terminate the target application
-    StopTarget,
+    /// Set the simulator's language and region (e.g. `fr FR`); reboots it, so
+    /// the app and agent must be relaunched
+    SetLocale {
+        /// Language code, optionally with a script (e.g. fr, zh-Hans)
+        language: String,
+        /// Region code (e.g. FR)
+        region: String,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
+
+    /// Rotate the device; later coordinate actions use the new orientation
+    Orientation {
+        /// Orientation to rotate to
+        #[arg(value_enum)]
+        orientation: OrientationArg,
+        /// Annotate the action log entry with a free-text tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Don't attach a screenshot to this action's log entry, even with
+        /// `set-screenshots on`
+        #[arg(long)]
+        no_screenshot: bool,
+    },
+
+    /// Launch the target application
+    StartTarget,
+
+    /// Terminate the target application
+    StopTarget,
 
     /// Get metadata about the target application
     TargetInfo,
 
+    /// Print the device screen size, scale factor and orientation
+    DeviceInfo,
+
+    /// Print the element under a screen point (the smallest frame containing it)
+    ElementAt {
+        /// X coordinate
+        #[arg(value_parser = clap::value_parser!(i32).range(0..))]
+        x: i32,
+        /// Y coordinate
+        #[arg(value_parser = clap::value_parser!(i32).range(0..))]
+        y: i32,
+    },
+
+    /// Measure the round-trip latency to the automation agent
+    Ping {
+        /// Number of heartbeats to send; prints the average when above 1
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        count: u32,
+    },
+
     /// Boot a device (simulator UDID for iOS, AVD name / adb serial for Android)
     BootDevice {
         /// Device UDID (iOS) or AVD name / adb serial (Android)
@@ -285,6 +996,34 @@ enum Command {
         /// Target platform
         #[arg(long, value_enum, default_value_t = PlatformArg::Ios)]
         platform: PlatformArg,
+        /// Wait until the device has finished booting before returning
+        #[arg(long)]
+        wait: bool,
+    },
+
+    /// Shut down an iOS simulator (already shut down counts as success)
+    Shutdown {
+        /// Simulator UDID (defaults to the booted simulator)
+        udid: Option<String>,
+        /// Shut down every booted simulator
+        #[arg(long, conflicts_with = "udid")]
+        all: bool,
+    },
+
+    /// Create an iOS simulator and print its UDID
+    CreateDevice {
+        /// Name for the new simulator
+        name: String,
+        /// Device type, e.g. iPhone-15 (see `xcrun simctl list devicetypes`)
+        device_type: String,
+        /// Runtime, e.g. iOS-17-5 (see `xcrun simctl list runtimes`)
+        runtime: String,
+    },
+
+    /// Delete an iOS simulator
+    DeleteDevice {
+        /// Simulator UDID
+        udid: String,
     },
 
     /// List available devices (simulators for iOS, adb devices for Android)
@@ -292,6 +1031,42 @@ enum Command {
         /// Target platform
         #[arg(long, value_enum, default_value_t = PlatformArg::Ios)]
         platform: PlatformArg,
+        /// Only simulators whose runtime matches, e.g. "iOS-17" or "iOS 17.2"
+        #[arg(long)]
+        runtime: Option<String>,
+        /// Only simulators whose runtime is installed
+        #[arg(long)]
+        available_only: bool,
+        /// Only booted simulators
+        #[arg(long)]
+        booted_only: bool,
+    },
+
+    /// List apps installed on an iOS simulator
+    #[command(name = "list-apps")]
+    ListApps {
+        /// Simulator UDID (defaults to the booted simulator)
+        #[arg(long)]
+        device: Option<String>,
+        /// Only list user-installed apps
+        #[arg(long)]
+        user_only: bool,
+    },
+
+    /// Print the path of an app's container on an iOS simulator
+    #[command(name = "app-container")]
+    AppContainer {
+        /// Bundle identifier of the app
+        bundle_id: String,
+        /// Data container (Documents, Library, tmp) instead of the app bundle
+        #[arg(long, conflicts_with = "groups")]
+        data: bool,
+        /// First shared app group container instead of the app bundle
+        #[arg(long)]
+        groups: bool,
+        /// Simulator UDID (defaults to the booted simulator)
+        #[arg(long)]
+        device: Option<String>,
     },
 
     /// List connected physical iOS devices
@@ -305,26 +1080,64 @@ enum Command {
         udid: String,
     },
 
-    /// Convert a JSONL action log to a shell script
+    /// Convert a JSONL action log to a shell script or pytest module
     Convert {
         /// Path to the JSONL log file (reads from stdin if omitted)
         log: Option<PathBuf>,
+        /// Kind of script to write
+        #[arg(long, value_enum, default_value = "shell")]
+        format: converter::ScriptFormat,
+        /// Sleep between commands as long as the recording paused, and note
+        /// each command's timestamp and duration in a comment
+        #[arg(long)]
+        preserve_timing: bool,
+        /// Longest pause to reproduce with --preserve-timing, in milliseconds
+        #[arg(long, default_value = "5000", requires = "preserve_timing")]
+        max_delay: u64,
     },
 
     /// Get current session state
     Status,
 
+    /// Check that the server has an active session and a connected agent
+    /// (exits 1 if not)
+    Health,
+
     /// Get action log history
     Log,
 
+    /// Turn on or off attaching a screenshot to each logged action
+    SetScreenshots {
+        #[arg(value_enum)]
+        state: Toggle,
+    },
+
+    /// Cancel the action currently running in this session (e.g. a long wait-for)
+    Cancel,
+
+    /// Record the simulator screen to a video file
+    Record {
+        #[command(subcommand)]
+        action: RecordAction,
+    },
+
     /// List all running qorvex sessions
     ListSessions,
 
+    /// Check Xcode, simulators, the streamer and the session's server (exits
+    /// 1 if a critical check fails)
+    Doctor,
+
     /// Start server, session, and agent in one step
     Start {
         /// Device UDID (simulator or physical) to use for this session
         #[arg(short, long)]
         device: Option<String>,
+
+        /// TCP port the agent listens on, when `start` launches the server
+        /// (overrides QORVEX_AGENT_PORT and config.json; default 8080)
+        #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+        agent_port: Option<u16>,
     },
 
     /// Start an automation session (auto-starts agent if configured)
@@ -343,6 +1156,9 @@ enum Command {
     /// Stop the managed automation agent (leaves the server running)
     StopAgent,
 
+    /// Restart the managed automation agent and reconnect, keeping the target app
+    RestartAgent,
+
     /// Stop the server for this session
     Stop,
 
@@ -384,9 +1200,21 @@ async fn main() -> ExitCode {
         .init();
 
     let cli = Cli::parse();
+    let format = cli.format;
 
     match run(cli).await {
         Ok(()) => ExitCode::SUCCESS,
+        // `Absent` is the "no" answer of `exists`, which already reported it.
+        Err(e @ CliError::Absent) => e.exit_code(),
+        // `Unchanged` is a normal outcome of `screen-info --if-changed`, not an
+        // error; JSON mode already printed a success envelope for it.
+        Err(e @ CliError::Unchanged) => e.exit_code(),
+        Err(e) if format == OutputFormat::Json => {
+            if let Ok(json) = CliOutput::failure(e.to_string()).to_json() {
+                println!("{}", json);
+            }
+            e.exit_code()
+        }
         Err(e) => {
             eprintln!("Error: {}", e);
             e.exit_code()
@@ -399,6 +1227,9 @@ enum CliError {
     Connection(String),
     ActionFailed(String),
     Protocol(String),
+    Unchanged,
+    Absent,
+    ElementNotFound(String),
 }
 
 impl CliError {
@@ -407,6 +1238,9 @@ impl CliError {
             CliError::Connection(_) => ExitCode::from(2),
             CliError::ActionFailed(_) => ExitCode::from(1),
             CliError::Protocol(_) => ExitCode::from(3),
+            CliError::Unchanged => ExitCode::from(4),
+            CliError::Absent => ExitCode::from(1),
+            CliError::ElementNotFound(_) => ExitCode::from(5),
         }
     }
 }
@@ -417,8 +1251,114 @@ impl std::fmt::Display for CliError {
             CliError::Connection(msg) => write!(f, "Connection error: {}", msg),
             CliError::ActionFailed(msg) => write!(f, "Action failed: {}", msg),
             CliError::Protocol(msg) => write!(f, "Protocol error: {}", msg),
+            CliError::Unchanged => write!(f, "Screen unchanged"),
+            CliError::Absent => write!(f, "Element not found"),
+            CliError::ElementNotFound(msg) => write!(f, "Action failed: {}", msg),
+        }
+    }
+}
+
+/// Prints `data` on stdout as a successful [`CliOutput`] envelope.
+fn print_json(data: impl Serialize) -> Result<(), CliError> {
+    let json = CliOutput::success(data)
+        .to_json()
+        .map_err(|e| CliError::Protocol(e.to_string()))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Parses a server `data` payload as JSON, keeping it as a string if it is
+/// not JSON.
+fn parse_data(data: &str) -> serde_json::Value {
+    serde_json::from_str(data).unwrap_or_else(|_| serde_json::Value::String(data.to_string()))
+}
+
+/// Awaits an IPC round trip, failing with [`CliError::Connection`] if it
+/// takes longer than `limit` seconds.
+async fn within<T>(
+    limit: Option<f64>,
+    step: impl std::future::Future<Output = T>,
+) -> Result<T, CliError> {
+    let Some(secs) = limit else {
+        return Ok(step.await);
+    };
+    tokio::time::timeout(std::time::Duration::from_secs_f64(secs), step)
+        .await
+        .map_err(|_| CliError::Connection(format!("server not responding after {}s", secs)))
+}
+
+/// Parses a duration in seconds that must be positive and finite.
+fn parse_positive_secs(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs > 0.0 => Ok(secs),
+        Ok(_) => Err(format!("duration must be positive, got {}", s)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Builds the action for `long-press`: coordinates when a Y coordinate is
+/// given, otherwise an element selector.
+fn long_press_action(
+    target: &str,
+    y: Option<i32>,
+    by_label: bool,
+    duration: f64,
+) -> Result<ActionType, CliError> {
+    match y {
+        Some(y) => {
+            let x = target.parse::<i32>().map_err(|_| {
+                CliError::ActionFailed(format!("invalid X coordinate '{}'", target))
+            })?;
+            Ok(ActionType::LongPress { x, y, duration })
         }
+        None => Ok(ActionType::LongPressElement {
+            selector: target.to_string(),
+            by_label,
+            duration,
+        }),
+    }
+}
+
+/// Parses a fraction of an element's size, from 0.0 to 1.0 inclusive.
+fn parse_fraction(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(f) if (0.0..=1.0).contains(&f) => Ok(f),
+        Ok(_) => Err(format!("fraction must be between 0 and 1, got {}", s)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// The selector match mode for a command's `--glob` flag.
+fn match_mode(glob: bool) -> MatchMode {
+    if glob {
+        MatchMode::Glob
+    } else {
+        MatchMode::Exact
+    }
+}
+
+/// The simulator a host-side command runs against: `device` if given,
+/// otherwise the booted simulator. Warns when several are booted, since the
+/// choice is then arbitrary.
+fn target_simulator(device: &Option<String>) -> Result<String, CliError> {
+    if let Some(udid) = device {
+        return Ok(udid.clone());
+    }
+    let booted = Simctl::booted_udids()
+        .map_err(|e| CliError::ActionFailed(format!("No simulator selected: {}", e)))?;
+    if booted.len() > 1 {
+        eprintln!(
+            "Warning: {} simulators are booted; using {}. Pass --device to choose one.",
+            booted.len(),
+            booted[0]
+        );
     }
+    booted.into_iter().next().ok_or_else(|| {
+        CliError::ActionFailed(format!(
+            "No simulator selected: {}",
+            SimctlError::NoBootedSimulator
+        ))
+    })
 }
 
 fn discover_sessions() -> Vec<String> {
@@ -440,10 +1380,34 @@ fn discover_sessions() -> Vec<String> {
 async fn run(cli: Cli) -> Result<(), CliError> {
     // Handle commands that don't need an IPC connection
     match cli.command {
+        Command::Doctor => {
+            let checks = doctor::run_checks(&cli.session);
+            if cli.format == OutputFormat::Json {
+                print_json(&checks)?;
+            } else {
+                for check in &checks {
+                    let mark = match check.status {
+                        doctor::Status::Pass => "ok  ",
+                        doctor::Status::Warn => "warn",
+                        doctor::Status::Fail => "FAIL",
+                    };
+                    println!("[{}] {}: {}", mark, check.name, check.detail);
+                    if let Some(hint) = check.hint {
+                        println!("       {}", hint);
+                    }
+                }
+            }
+            // Like `exists`, failures are reported above and only set the exit code
+            return if checks.iter().any(|c| c.status == doctor::Status::Fail) {
+                Err(CliError::Absent)
+            } else {
+                Ok(())
+            };
+        }
         Command::ListSessions => {
             let sessions = discover_sessions();
             if cli.format == OutputFormat::Json {
-                println!("{}", serde_json::json!({ "sessions": sessions }));
+                print_json(&sessions)?;
             } else {
                 if sessions.is_empty() {
                     eprintln!("No running sessions found");
@@ -455,26 +1419,28 @@ async fn run(cli: Cli) -> Result<(), CliError> {
             }
             return Ok(());
         }
-        Command::ListDevices { platform } => {
+        Command::ListDevices {
+            platform,
+            ref runtime,
+            available_only,
+            booted_only,
+        } => {
+            let filter = DeviceFilter {
+                runtime: runtime.clone(),
+                available_only,
+                booted_only,
+            };
             match Platform::from(platform) {
                 Platform::Ios => match Simctl::list_devices() {
                     Ok(devices) => {
+                        let devices = filter.apply(devices);
                         if cli.format == OutputFormat::Json {
-                            println!(
-                                "{}",
-                                serde_json::to_string_pretty(&devices)
-                                    .map_err(|e| CliError::Protocol(e.to_string()))?
-                            );
+                            print_json(&devices)?;
                         } else if devices.is_empty() {
                             eprintln!("No simulator devices found");
                         } else {
                             for device in &devices {
-                                let state = if device.state == "Booted" {
-                                    " (Booted)"
-                                } else {
-                                    ""
-                                };
-                                println!("{} -- {}{}", device.udid, device.name, state);
+                                println!("{}", simulator_line(device));
                             }
                         }
                     }
@@ -485,14 +1451,16 @@ async fn run(cli: Cli) -> Result<(), CliError> {
                         )))
                     }
                 },
+                Platform::Android if filter.runtime.is_some() || available_only || booted_only => {
+                    return Err(CliError::ActionFailed(
+                        "--runtime, --available-only and --booted-only apply to simulators only"
+                            .to_string(),
+                    ));
+                }
                 Platform::Android => match Adb::list_devices() {
                     Ok(devices) => {
                         if cli.format == OutputFormat::Json {
-                            println!(
-                                "{}",
-                                serde_json::to_string_pretty(&devices)
-                                    .map_err(|e| CliError::Protocol(e.to_string()))?
-                            );
+                            print_json(&devices)?;
                         } else if devices.is_empty() {
                             eprintln!("No Android devices found");
                         } else {
@@ -512,12 +1480,72 @@ async fn run(cli: Cli) -> Result<(), CliError> {
             }
             return Ok(());
         }
-        Command::BootDevice { ref udid, platform } => {
+        Command::ListApps {
+            ref device,
+            user_only,
+        } => {
+            let udid = target_simulator(device)?;
+            let mut apps = Simctl::list_apps(&udid)
+                .map_err(|e| CliError::ActionFailed(format!("Failed to list apps: {}", e)))?;
+            if user_only {
+                apps.retain(|app| app.is_user());
+            }
+            if cli.format == OutputFormat::Json {
+                print_json(&apps)?;
+            } else if apps.is_empty() {
+                eprintln!("No apps found");
+            } else {
+                for app in &apps {
+                    println!(
+                        "{} -- {} ({})",
+                        app.bundle_id, app.display_name, app.app_type
+                    );
+                }
+            }
+            return Ok(());
+        }
+        Command::AppContainer {
+            ref bundle_id,
+            data,
+            groups,
+            ref device,
+        } => {
+            let kind = if data {
+                ContainerKind::Data
+            } else if groups {
+                ContainerKind::Groups
+            } else {
+                ContainerKind::App
+            };
+            let udid = target_simulator(device)?;
+            let path = Simctl::app_container(&udid, bundle_id, kind).map_err(|e| {
+                CliError::ActionFailed(format!("Failed to get app container: {}", e))
+            })?;
+            if cli.format == OutputFormat::Json {
+                print_json(serde_json::json!({ "path": path }))?;
+            } else {
+                println!("{}", path.display());
+            }
+            return Ok(());
+        }
+        Command::BootDevice {
+            ref udid,
+            platform,
+            wait,
+        } => {
             match Platform::from(platform) {
                 Platform::Ios => match Simctl::boot(udid) {
-                    Ok(()) => {
+                    Ok(()) | Err(SimctlError::AlreadyBooted) => {
+                        if wait {
+                            Simctl::wait_for_boot(udid, DEFAULT_BOOT_TIMEOUT).map_err(|e| {
+                                CliError::ActionFailed(format!(
+                                    "Device did not finish booting: {}",
+                                    e
+                                ))
+                            })?;
+                        }
                         if cli.format == OutputFormat::Json {
-                            println!("{}", serde_json::json!({ "success": true, "udid": udid }));
+                            print_json(serde_json::json!({ "udid": udid }))?;
                         } else {
                             eprintln!("Booted device {}", udid);
                         }
@@ -532,17 +1560,21 @@ async fn run(cli: Cli) -> Result<(), CliError> {
                 Platform::Android => {
                     // Android boot routes through the server so the selected
                     // serial / lifecycle is tracked in session state.
-                    let mut client = IpcClient::connect(&cli.session).await.map_err(|e| {
-                        CliError::Connection(format!(
-                            "Failed to connect to session '{}': {}",
-                            cli.session, e
-                        ))
-                    })?;
+                    let mut client =
+                        within(Some(cli.connect_timeout), IpcClient::connect(&cli.session))
+                            .await?
+                            .map_err(|e| {
+                                CliError::Connection(format!(
+                                    "Failed to connect to session '{}': {}",
+                                    cli.session, e
+                                ))
+                            })?;
                     return send_command(
                         &mut client,
                         IpcRequest::BootDevice {
                             udid: udid.clone(),
                             platform: Platform::Android,
+                            wait,
                         },
                         &cli,
                     )
@@ -551,24 +1583,85 @@ async fn run(cli: Cli) -> Result<(), CliError> {
             }
             return Ok(());
         }
-        Command::Convert { ref log } => {
+        Command::Shutdown { ref udid, all } => {
+            let udid = if all {
+                None
+            } else {
+                Some(target_simulator(udid)?)
+            };
+            match udid {
+                Some(ref udid) => Simctl::shutdown(udid),
+                None => Simctl::shutdown_all(),
+            }
+            .map_err(|e| CliError::ActionFailed(format!("Failed to shut down: {}", e)))?;
+            if cli.format == OutputFormat::Json {
+                print_json(serde_json::json!({ "udid": udid, "all": all }))?;
+            } else if let Some(udid) = udid {
+                eprintln!("Shut down device {}", udid);
+            } else {
+                eprintln!("Shut down all simulators");
+            }
+            return Ok(());
+        }
+        Command::CreateDevice {
+            ref name,
+            ref device_type,
+            ref runtime,
+        } => {
+            let udid = Simctl::create_device(name, device_type, runtime)
+                .map_err(|e| CliError::ActionFailed(format!("Failed to create device: {}", e)))?;
+            if cli.format == OutputFormat::Json {
+                print_json(serde_json::json!({ "udid": udid }))?;
+            } else {
+                println!("{}", udid);
+            }
+            return Ok(());
+        }
+        Command::DeleteDevice { ref udid } => {
+            Simctl::delete_device(udid)
+                .map_err(|e| CliError::ActionFailed(format!("Failed to delete device: {}", e)))?;
+            if cli.format == OutputFormat::Json {
+                print_json(serde_json::json!({ "udid": udid }))?;
+            } else {
+                eprintln!("Deleted device {}", udid);
+            }
+            return Ok(());
+        }
+        Command::Convert {
+            ref log,
+            format,
+            preserve_timing,
+            max_delay,
+        } => {
+            let options = converter::ConvertOptions {
+                format,
+                preserve_timing: preserve_timing
+                    .then(|| std::time::Duration::from_millis(max_delay)),
+            };
             let result = match log {
-                Some(path) => converter::LogConverter::convert_file(path)
+                Some(path) => converter::LogConverter::convert_file(path, options)
                     .map_err(|e| CliError::ActionFailed(format!("Failed to convert log: {}", e))),
-                None => converter::LogConverter::convert_stdin().map_err(|e| {
+                None => converter::LogConverter::convert_stdin(options).map_err(|e| {
                     CliError::ActionFailed(format!("Failed to convert from stdin: {}", e))
                 }),
             };
             match result {
                 Ok(script) => {
-                    print!("{}", script);
+                    if cli.format == OutputFormat::Json {
+                        print_json(serde_json::json!({ "script": script }))?;
+                    } else {
+                        print!("{}", script);
+                    }
                     return Ok(());
                 }
                 Err(e) => return Err(e),
             }
         }
-        Command::Start { ref device } => {
-            return start_all(&cli, device.clone()).await;
+        Command::Start {
+            ref device,
+            agent_port,
+        } => {
+            return start_all(&cli, device.clone(), agent_port).await;
         }
         Command::Completions { shell } => {
             use clap::CommandFactory;
@@ -577,16 +1670,30 @@ async fn run(cli: Cli) -> Result<(), CliError> {
             generate(shell, &mut cmd, "qorvex", &mut std::io::stdout());
             return Ok(());
         }
+        Command::Screenshot {
+            output: Some(ref path),
+            ..
+        } => {
+            if cli.format == OutputFormat::Json && path == std::path::Path::new("-") {
+                return Err(CliError::ActionFailed(
+                    "--output - writes the PNG to stdout and cannot be used with --format json"
+                        .to_string(),
+                ));
+            }
+            validate_output_path(path)?
+        }
         _ => {} // Fall through to IPC-connected commands
     }
 
     // Connect to the IPC server
-    let mut client = IpcClient::connect(&cli.session).await.map_err(|e| {
-        CliError::Connection(format!(
-            "Failed to connect to session '{}': {}",
-            cli.session, e
-        ))
-    })?;
+    let mut client = within(Some(cli.connect_timeout), IpcClient::connect(&cli.session))
+        .await?
+        .map_err(|e| {
+            CliError::Connection(format!(
+                "Failed to connect to session '{}': {}",
+                cli.session, e
+            ))
+        })?;
 
     match cli.command {
         Command::Tap {
@@ -595,7 +1702,11 @@ async fn run(cli: Cli) -> Result<(), CliError> {
             ref element_type,
             no_wait,
             timeout,
+            require_enabled,
+            glob,
+            first,
             ref tag,
+            ..
         } => {
             let timeout_ms = if no_wait { None } else { Some(timeout) };
             execute_action(
@@ -605,13 +1716,16 @@ async fn run(cli: Cli) -> Result<(), CliError> {
                     by_label: label,
                     element_type: element_type.clone(),
                     timeout_ms,
+                    require_enabled,
+                    match_mode: match_mode(glob),
+                    first,
                 },
                 tag.clone(),
                 &cli,
             )
             .await
         }
-        Command::TapLocation { x, y, ref tag } => {
+        Command::TapLocation { x, y, ref tag, .. } => {
             execute_action(
                 &mut client,
                 ActionType::TapLocation { x, y },
@@ -620,145 +1734,541 @@ async fn run(cli: Cli) -> Result<(), CliError> {
             )
             .await
         }
-        Command::LongPress {
+        Command::MultiTap {
+            fingers,
             x,
             y,
-            duration,
             ref tag,
+            ..
         } => {
             execute_action(
                 &mut client,
-                ActionType::LongPress { x, y, duration },
+                ActionType::MultiTap {
+                    finger_count: fingers,
+                    x,
+                    y,
+                },
                 tag.clone(),
                 &cli,
             )
             .await
         }
-        Command::SendKeys { ref text, ref tag } => {
+        Command::LongPress {
+            ref target,
+            y,
+            label,
+            duration,
+            ref tag,
+            ..
+        } => {
+            let action = long_press_action(target, y, label, duration)?;
+            execute_action(&mut client, action, tag.clone(), &cli).await
+        }
+        Command::Drag {
+            ref from,
+            ref to,
+            label,
+            hold,
+            duration,
+            ref tag,
+            ..
+        } => {
             execute_action(
                 &mut client,
-                ActionType::SendKeys { text: text.clone() },
+                ActionType::Drag {
+                    from: from.clone(),
+                    to: to.clone(),
+                    by_label: label,
+                    hold_duration: hold,
+                    move_duration: duration,
+                },
                 tag.clone(),
                 &cli,
             )
             .await
         }
-        Command::Screenshot { ref tag } => {
-            execute_action(&mut client, ActionType::GetScreenshot, tag.clone(), &cli).await
-        }
-        Command::ScreenInfo {
-            full,
-            pretty,
-            ref tag,
-        } => execute_screen_info(&mut client, &cli, full, pretty, tag.clone()).await,
-        Command::GetValue {
-            ref selector,
-            label,
-            ref element_type,
-            no_wait,
-            timeout,
-            ref tag,
+        Command::SendKeys {
+            ref text, ref tag, ..
         } => {
-            let timeout_ms = if no_wait { None } else { Some(timeout) };
             execute_action(
                 &mut client,
-                ActionType::GetValue {
-                    selector: selector.clone(),
-                    by_label: label,
-                    element_type: element_type.clone(),
-                    timeout_ms,
-                },
+                ActionType::SendKeys { text: text.clone() },
                 tag.clone(),
                 &cli,
             )
             .await
         }
-        Command::Swipe {
-            ref direction,
-            ref tag,
-        } => {
+        Command::SendKey { key, ref tag, .. } => {
             execute_action(
                 &mut client,
-                ActionType::Swipe {
-                    direction: direction.clone(),
-                },
+                ActionType::SendKey { key: key.into() },
                 tag.clone(),
                 &cli,
             )
             .await
         }
-        Command::SetTarget {
-            ref bundle_id,
+        Command::ClearText {
+            ref selector,
+            label,
             ref tag,
+            ..
         } => {
             execute_action(
                 &mut client,
-                ActionType::SetTarget {
-                    bundle_id: bundle_id.clone(),
+                ActionType::ClearText {
+                    selector: selector.clone(),
+                    by_label: label,
                 },
                 tag.clone(),
                 &cli,
             )
             .await
         }
-        Command::Comment {
-            ref message,
-            ref tag,
+        Command::Paste {
+            ref text, ref tag, ..
         } => {
             execute_action(
                 &mut client,
-                ActionType::LogComment {
-                    message: message.clone(),
-                },
+                ActionType::PasteText { text: text.clone() },
                 tag.clone(),
                 &cli,
             )
             .await
         }
-        Command::WaitFor {
+        Command::Screenshot {
+            ref output,
+            ref element,
+            label,
+            ref tag,
+            ..
+        } => {
+            let action = match element {
+                Some(selector) => ActionType::GetElementScreenshot {
+                    selector: selector.clone(),
+                    by_label: label,
+                },
+                None => ActionType::GetScreenshot,
+            };
+            match output {
+                Some(path) => {
+                    execute_screenshot_to_file(&mut client, &cli, path, action, tag.clone()).await
+                }
+                None => execute_action(&mut client, action, tag.clone(), &cli).await,
+            }
+        }
+        Command::ScreenInfo {
+            full,
+            pretty,
+            ref types,
+            visible_only,
+            max_depth,
+            ref if_changed,
+            watch,
+            ref tag,
+            ..
+        } => {
+            let filter = ElementFilter {
+                types: types.clone(),
+                visible_only,
+                max_depth,
+            };
+            if watch {
+                return watch_screen_info(&mut client, &cli, full, pretty, filter).await;
+            }
+            let action = ActionType::GetScreenInfo {
+                filter,
+                changed_since_hash: if_changed.clone(),
+            };
+            execute_screen_info(&mut client, &cli, full, pretty, action, tag.clone()).await
+        }
+        Command::Describe { ref tag, .. } => describe_screen(&mut client, &cli, tag.clone()).await,
+        Command::GetValue {
             ref selector,
             label,
             ref element_type,
+            no_wait,
             timeout,
+            glob,
+            first,
             ref tag,
+            ..
         } => {
+            let timeout_ms = if no_wait { None } else { Some(timeout) };
             execute_action(
                 &mut client,
-                ActionType::WaitFor {
+                ActionType::GetValue {
                     selector: selector.clone(),
                     by_label: label,
                     element_type: element_type.clone(),
-                    timeout_ms: timeout,
-                    require_stable: true,
+                    timeout_ms,
+                    match_mode: match_mode(glob),
+                    first,
                 },
                 tag.clone(),
                 &cli,
             )
             .await
         }
-        Command::WaitForNot {
+        Command::GetAttribute {
             ref selector,
+            attr,
             label,
             ref element_type,
-            timeout,
             ref tag,
+            ..
         } => {
             execute_action(
                 &mut client,
-                ActionType::WaitForNot {
+                ActionType::GetAttribute {
                     selector: selector.clone(),
                     by_label: label,
                     element_type: element_type.clone(),
-                    timeout_ms: timeout,
+                    attribute: attr.into(),
                 },
                 tag.clone(),
                 &cli,
             )
             .await
         }
-        Command::StartTarget => send_command(&mut client, IpcRequest::StartTarget, &cli).await,
-        Command::StopTarget => send_command(&mut client, IpcRequest::StopTarget, &cli).await,
+        Command::Exists {
+            ref selector,
+            label,
+            ref element_type,
+            ref tag,
+            ..
+        } => {
+            let action = ActionType::ElementExists {
+                selector: selector.clone(),
+                by_label: label,
+                element_type: element_type.clone(),
+            };
+            execute_exists(&mut client, action, tag.clone(), &cli).await
+        }
+        Command::TapIfExists {
+            ref selector,
+            label,
+            ref element_type,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::TapIfExists {
+                    selector: selector.clone(),
+                    by_label: label,
+                    element_type: element_type.clone(),
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::FindAll {
+            ref selector,
+            label,
+            ref element_type,
+            expect,
+            ref tag,
+            ..
+        } => {
+            let action = ActionType::FindAll {
+                selector: selector.clone(),
+                by_label: label,
+                element_type: element_type.clone(),
+            };
+            execute_find_all(&mut client, action, expect, tag.clone(), &cli).await
+        }
+        Command::Swipe {
+            ref direction,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::Swipe {
+                    direction: direction.clone(),
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::TapOffset {
+            ref selector,
+            dx,
+            dy,
+            label,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::TapElementOffset {
+                    selector: selector.clone(),
+                    by_label: label,
+                    dx_fraction: dx,
+                    dy_fraction: dy,
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::SwipeCoords {
+            x1,
+            y1,
+            x2,
+            y2,
+            duration,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::SwipeCoords {
+                    start_x: x1,
+                    start_y: y1,
+                    end_x: x2,
+                    end_y: y2,
+                    duration,
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::SwipeElement {
+            ref selector,
+            ref direction,
+            label,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::SwipeElement {
+                    selector: selector.clone(),
+                    by_label: label,
+                    direction: direction.clone(),
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::SetTarget {
+            ref bundle_id,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::SetTarget {
+                    bundle_id: bundle_id.clone(),
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::Appearance {
+            appearance,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::SetAppearance {
+                    appearance: appearance.into(),
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::SetLocale {
+            ref language,
+            ref region,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::SetLocale {
+                    language: language.clone(),
+                    region: region.clone(),
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::Orientation {
+            orientation,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::SetOrientation {
+                    orientation: orientation.into(),
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::Comment {
+            ref message,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::LogComment {
+                    message: message.clone(),
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::Assert {
+            ref description,
+            failed,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::Assertion {
+                    description: description.clone(),
+                    passed: !failed,
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::Wait { ms, ref tag, .. } => {
+            execute_action(&mut client, ActionType::Wait { ms }, tag.clone(), &cli).await
+        }
+        Command::WaitFor {
+            ref selector,
+            label,
+            ref element_type,
+            timeout,
+            poll_interval,
+            glob,
+            first,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::WaitFor {
+                    selector: selector.clone(),
+                    by_label: label,
+                    element_type: element_type.clone(),
+                    timeout_ms: timeout,
+                    poll_interval_ms: poll_interval,
+                    require_stable: true,
+                    match_mode: match_mode(glob),
+                    first,
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::WaitForNot {
+            ref selector,
+            label,
+            ref element_type,
+            timeout,
+            poll_interval,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::WaitForNot {
+                    selector: selector.clone(),
+                    by_label: label,
+                    element_type: element_type.clone(),
+                    timeout_ms: timeout,
+                    poll_interval_ms: poll_interval,
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::WaitForAny {
+            ref selectors,
+            label,
+            ref element_type,
+            timeout,
+            poll_interval,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::WaitForAny {
+                    selectors: selector_specs(selectors, label, element_type.as_deref()),
+                    timeout_ms: timeout,
+                    poll_interval_ms: poll_interval,
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::WaitForAll {
+            ref selectors,
+            label,
+            ref element_type,
+            timeout,
+            poll_interval,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::WaitForAll {
+                    selectors: selector_specs(selectors, label, element_type.as_deref()),
+                    timeout_ms: timeout,
+                    poll_interval_ms: poll_interval,
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::WaitIdle {
+            stable,
+            timeout,
+            ref tag,
+            ..
+        } => {
+            execute_action(
+                &mut client,
+                ActionType::WaitForIdle {
+                    timeout_ms: timeout,
+                    stable_ms: stable,
+                },
+                tag.clone(),
+                &cli,
+            )
+            .await
+        }
+        Command::StartTarget => send_command(&mut client, IpcRequest::StartTarget, &cli).await,
+        Command::StopTarget => send_command(&mut client, IpcRequest::StopTarget, &cli).await,
         Command::TargetInfo => execute_target_info(&mut client, &cli).await,
+        Command::DeviceInfo => execute_device_info(&mut client, &cli).await,
+        Command::ElementAt { x, y } => execute_element_at(&mut client, x, y, &cli).await,
+        Command::Ping { count } => execute_ping(&mut client, count, &cli).await,
         Command::StartSession => send_command(&mut client, IpcRequest::StartSession, &cli).await,
         Command::StartAgent {
             ref project_dir,
@@ -776,9 +2286,41 @@ async fn run(cli: Cli) -> Result<(), CliError> {
             .await
         }
         Command::StopAgent => send_command(&mut client, IpcRequest::StopAgent, &cli).await,
+        Command::RestartAgent => send_command(&mut client, IpcRequest::RestartAgent, &cli).await,
         Command::Stop => stop_server(&mut client, &cli).await,
         Command::Status => get_status(&mut client, &cli).await,
+        Command::Health => get_health(&mut client, &cli).await,
         Command::Log => get_log(&mut client, &cli).await,
+        Command::SetScreenshots { state } => {
+            send_command(
+                &mut client,
+                IpcRequest::SetCaptureScreenshots {
+                    enabled: state == Toggle::On,
+                },
+                &cli,
+            )
+            .await
+        }
+        Command::Cancel => send_command(&mut client, IpcRequest::Cancel, &cli).await,
+        Command::Record {
+            action: RecordAction::Start { ref path },
+        } => {
+            // The server has its own working directory, so resolve it here
+            let path = std::path::absolute(path).map_err(|e| {
+                CliError::ActionFailed(format!("Invalid path {}: {}", path.display(), e))
+            })?;
+            send_command(
+                &mut client,
+                IpcRequest::StartRecording {
+                    path: path.to_string_lossy().into_owned(),
+                },
+                &cli,
+            )
+            .await
+        }
+        Command::Record {
+            action: RecordAction::Stop,
+        } => send_command(&mut client, IpcRequest::StopRecording, &cli).await,
         Command::UseDevice { ref udid } => {
             send_command(
                 &mut client,
@@ -790,8 +2332,14 @@ async fn run(cli: Cli) -> Result<(), CliError> {
         Command::ListPhysicalDevices => list_physical_devices(&mut client, &cli).await,
         // These commands are handled before IPC connection above
         Command::ListSessions
+        | Command::Doctor
         | Command::ListDevices { .. }
+        | Command::ListApps { .. }
+        | Command::AppContainer { .. }
         | Command::BootDevice { .. }
+        | Command::Shutdown { .. }
+        | Command::CreateDevice { .. }
+        | Command::DeleteDevice { .. }
         | Command::Convert { .. }
         | Command::Start { .. }
         | Command::Completions { .. } => unreachable!(),
@@ -804,17 +2352,22 @@ async fn execute_action(
     tag: Option<String>,
     cli: &Cli,
 ) -> Result<(), CliError> {
-    let is_screenshot_action = matches!(action, ActionType::GetScreenshot);
+    let is_screenshot_action = matches!(
+        action,
+        ActionType::GetScreenshot | ActionType::GetElementScreenshot { .. }
+    );
     let is_data_action = matches!(
         action,
-        ActionType::GetScreenInfo | ActionType::GetValue { .. }
+        ActionType::GetScreenInfo { .. }
+            | ActionType::GetValue { .. }
+            | ActionType::GetAttribute { .. }
     );
+    let is_attribute_action = matches!(action, ActionType::GetAttribute { .. });
     let action_label = action.display_name();
     let action_target = action.display_target();
-    let request = IpcRequest::Execute { action, tag };
-    let response = client
-        .send(&request)
-        .await
+    let request = execute_request(action, tag, cli);
+    let response = within(cli.request_timeout, client.send(&request))
+        .await?
         .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
 
     match response {
@@ -823,15 +2376,21 @@ async fn execute_action(
             message,
             screenshot,
             data,
+            element_not_found,
         } => {
+            if element_not_found {
+                return Err(CliError::ElementNotFound(message));
+            }
             if cli.format == OutputFormat::Json {
-                let output = serde_json::json!({
-                    "success": success,
+                if !success {
+                    return Err(CliError::ActionFailed(message));
+                }
+                let screenshot = screenshot.filter(|_| is_screenshot_action);
+                print_json(serde_json::json!({
                     "message": message,
-                    "screenshot": if is_screenshot_action { screenshot.as_ref().map(|s| s.as_ref()) } else { None },
-                    "data": data.as_ref().and_then(|d| serde_json::from_str::<serde_json::Value>(d).ok()),
-                });
-                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+                    "screenshot": screenshot,
+                    "result": data.as_deref().map(parse_data),
+                }))?;
             } else {
                 // Text format - output depends on the action
                 if success {
@@ -844,15 +2403,20 @@ async fn execute_action(
                     // Output data payload for data-returning commands
                     if is_data_action {
                         if let Some(ref d) = data {
-                            println!("{}", d);
+                            // Attributes are JSON; print text ones bare, like get-value
+                            match serde_json::from_str(d) {
+                                Ok(serde_json::Value::String(s)) if is_attribute_action => {
+                                    println!("{}", s)
+                                }
+                                _ => println!("{}", d),
+                            }
                         }
                     }
                     if !cli.quiet {
                         let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S%.3fZ");
                         let duration_str = data
-                            .as_ref()
-                            .and_then(|d| serde_json::from_str::<serde_json::Value>(d).ok())
-                            .and_then(|parsed| parsed.get("elapsed_ms").and_then(|v| v.as_u64()))
+                            .as_deref()
+                            .and_then(result_data::elapsed_ms)
                             .map(|ms| format!("{}ms", ms))
                             .unwrap_or_default();
                         eprintln!(
@@ -871,10 +2435,176 @@ async fn execute_action(
     }
 }
 
+/// Runs an `ElementExists` action, answering through the exit status.
+async fn execute_exists(
+    client: &mut IpcClient,
+    action: ActionType,
+    tag: Option<String>,
+    cli: &Cli,
+) -> Result<(), CliError> {
+    let request = execute_request(action, tag, cli);
+    let response = within(cli.request_timeout, client.send(&request))
+        .await?
+        .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
+
+    match response {
+        IpcResponse::ActionResult {
+            success: true,
+            message,
+            data,
+            ..
+        } => {
+            let exists = data.as_deref() == Some("true");
+            if cli.format == OutputFormat::Json {
+                print_json(serde_json::json!({ "exists": exists }))?;
+            } else if !cli.quiet {
+                eprintln!("{}", message);
+            }
+            if exists {
+                Ok(())
+            } else {
+                Err(CliError::Absent)
+            }
+        }
+        IpcResponse::ActionResult { message, .. } | IpcResponse::Error { message } => {
+            Err(CliError::ActionFailed(message))
+        }
+        _ => Err(CliError::Protocol("Unexpected response type".to_string())),
+    }
+}
+
+/// Runs a `FindAll` action, printing the count and then each element.
+///
+/// With `expect`, a count other than the expected one fails the command.
+async fn execute_find_all(
+    client: &mut IpcClient,
+    action: ActionType,
+    expect: Option<usize>,
+    tag: Option<String>,
+    cli: &Cli,
+) -> Result<(), CliError> {
+    let request = execute_request(action, tag, cli);
+    let response = within(cli.request_timeout, client.send(&request))
+        .await?
+        .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
+
+    match response {
+        IpcResponse::ActionResult {
+            success: true,
+            data,
+            ..
+        } => {
+            let elements: Vec<UIElement> = serde_json::from_str(data.as_deref().unwrap_or("[]"))
+                .map_err(|e| CliError::Protocol(format!("Invalid element list: {}", e)))?;
+            check_count(elements.len(), expect)?;
+            if cli.format == OutputFormat::Json {
+                let concise: Vec<_> = elements.iter().map(element_to_concise_json).collect();
+                print_json(serde_json::json!({ "count": elements.len(), "elements": concise }))?;
+            } else {
+                println!("{}", elements.len());
+                for element in &elements {
+                    println!("{}", element.summary());
+                }
+            }
+            Ok(())
+        }
+        IpcResponse::ActionResult { message, .. } | IpcResponse::Error { message } => {
+            Err(CliError::ActionFailed(message))
+        }
+        _ => Err(CliError::Protocol("Unexpected response type".to_string())),
+    }
+}
+
+/// Checks a `find-all` match count against `--expect`, if given.
+fn check_count(count: usize, expect: Option<usize>) -> Result<(), CliError> {
+    match expect {
+        Some(expected) if expected != count => Err(CliError::ActionFailed(format!(
+            "Expected {} matching elements, found {}",
+            expected, count
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a screenshot can be written to `path` before asking for one.
+///
+/// `-` (stdout) is always valid; otherwise the parent directory must exist.
+fn validate_output_path(path: &std::path::Path) -> Result<(), CliError> {
+    if path == std::path::Path::new("-") {
+        return Ok(());
+    }
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() && !parent.is_dir() => {
+            Err(CliError::ActionFailed(format!(
+                "Output directory does not exist: {}",
+                parent.display()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Decodes a base64 PNG and writes the raw bytes to `path`, or to `stdout`
+/// when `path` is `-`. Returns the number of bytes written.
+fn write_screenshot(
+    screenshot: &str,
+    path: &std::path::Path,
+    stdout: &mut impl std::io::Write,
+) -> Result<usize, CliError> {
+    use base64::Engine;
+    let png = base64::engine::general_purpose::STANDARD
+        .decode(screenshot.trim())
+        .map_err(|e| CliError::Protocol(format!("Invalid screenshot data: {}", e)))?;
+    let written = if path == std::path::Path::new("-") {
+        stdout.write_all(&png).and_then(|()| stdout.flush())
+    } else {
+        std::fs::write(path, &png)
+    };
+    written.map_err(|e| {
+        CliError::ActionFailed(format!("Failed to write {}: {}", path.display(), e))
+    })?;
+    Ok(png.len())
+}
+
+async fn execute_screenshot_to_file(
+    client: &mut IpcClient,
+    cli: &Cli,
+    path: &std::path::Path,
+    action: ActionType,
+    tag: Option<String>,
+) -> Result<(), CliError> {
+    let request = execute_request(action, tag, cli);
+    let response = within(cli.request_timeout, client.send(&request))
+        .await?
+        .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
+
+    match response {
+        IpcResponse::ActionResult {
+            success: true,
+            screenshot: Some(screenshot),
+            ..
+        } => {
+            let bytes = write_screenshot(&screenshot, path, &mut std::io::stdout().lock())?;
+            if cli.format == OutputFormat::Json {
+                print_json(serde_json::json!({ "path": path, "bytes": bytes }))?;
+            } else if !cli.quiet {
+                eprintln!("Wrote {} bytes to {}", bytes, path.display());
+            }
+            Ok(())
+        }
+        IpcResponse::ActionResult { success: true, .. } => {
+            Err(CliError::ActionFailed("No screenshot returned".to_string()))
+        }
+        IpcResponse::ActionResult { message, .. } | IpcResponse::Error { message } => {
+            Err(CliError::ActionFailed(message))
+        }
+        _ => Err(CliError::Protocol("Unexpected response type".to_string())),
+    }
+}
+
 async fn execute_target_info(client: &mut IpcClient, cli: &Cli) -> Result<(), CliError> {
-    let response = client
-        .send(&IpcRequest::GetTargetInfo)
-        .await
+    let response = within(cli.request_timeout, client.send(&IpcRequest::GetTargetInfo))
+        .await?
         .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
 
     match response {
@@ -888,9 +2618,7 @@ async fn execute_target_info(client: &mut IpcClient, cli: &Cli) -> Result<(), Cl
                 return Err(CliError::ActionFailed(message));
             }
             if cli.format == OutputFormat::Json {
-                if let Some(ref d) = data {
-                    println!("{}", d);
-                }
+                print_json(data.as_deref().map(parse_data))?;
             } else if let Some(ref d) = data {
                 if let Ok(info) = serde_json::from_str::<serde_json::Value>(d) {
                     if let Some(bid) = info.get("bundle_id").and_then(|v| v.as_str()) {
@@ -932,6 +2660,148 @@ async fn execute_target_info(client: &mut IpcClient, cli: &Cli) -> Result<(), Cl
     }
 }
 
+async fn execute_device_info(client: &mut IpcClient, cli: &Cli) -> Result<(), CliError> {
+    let response = within(cli.request_timeout, client.send(&IpcRequest::GetDeviceInfo))
+        .await?
+        .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
+
+    match response {
+        IpcResponse::ActionResult {
+            success,
+            message,
+            data,
+            ..
+        } => {
+            if !success {
+                return Err(CliError::ActionFailed(message));
+            }
+            if cli.format == OutputFormat::Json {
+                print_json(data.as_deref().map(parse_data))?;
+            } else if let Some(ref d) = data {
+                match serde_json::from_str::<ScreenInfo>(d) {
+                    Ok(info) => print!("{}", format_device_info(&info)),
+                    Err(_) => println!("{}", d),
+                }
+            }
+            Ok(())
+        }
+        IpcResponse::CommandResult { success, message } => {
+            if success {
+                Ok(())
+            } else {
+                Err(CliError::ActionFailed(message))
+            }
+        }
+        IpcResponse::Error { message } => Err(CliError::ActionFailed(message)),
+        _ => Err(CliError::Protocol("Unexpected response type".to_string())),
+    }
+}
+
+async fn execute_element_at(
+    client: &mut IpcClient,
+    x: i32,
+    y: i32,
+    cli: &Cli,
+) -> Result<(), CliError> {
+    let response = within(
+        cli.request_timeout,
+        client.send(&IpcRequest::ElementAt { x, y }),
+    )
+    .await?
+    .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
+
+    match response {
+        IpcResponse::ActionResult {
+            success,
+            message,
+            data,
+            ..
+        } => {
+            if !success {
+                return Err(CliError::ActionFailed(message));
+            }
+            let element = data
+                .as_deref()
+                .and_then(|d| serde_json::from_str::<UIElement>(d).ok());
+            match (element, cli.format) {
+                (elem, OutputFormat::Json) => {
+                    print_json(elem.as_ref().map(element_to_concise_json))?
+                }
+                (Some(elem), OutputFormat::Text) => {
+                    println!("{}", elem.summary());
+                    if let Some(ref frame) = elem.frame {
+                        println!(
+                            "Frame: ({:.0}, {:.0}) {:.0} x {:.0}",
+                            frame.x, frame.y, frame.width, frame.height
+                        );
+                    }
+                }
+                (None, OutputFormat::Text) => println!("{}", message),
+            }
+            Ok(())
+        }
+        IpcResponse::CommandResult { success, message } => {
+            if success {
+                Ok(())
+            } else {
+                Err(CliError::ActionFailed(message))
+            }
+        }
+        IpcResponse::Error { message } => Err(CliError::ActionFailed(message)),
+        _ => Err(CliError::Protocol("Unexpected response type".to_string())),
+    }
+}
+
+async fn execute_ping(client: &mut IpcClient, count: u32, cli: &Cli) -> Result<(), CliError> {
+    let response = within(
+        cli.request_timeout,
+        client.send(&IpcRequest::PingAgent { count }),
+    )
+    .await?
+    .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
+
+    match response {
+        IpcResponse::ActionResult {
+            success,
+            message,
+            data,
+            ..
+        } => {
+            if !success {
+                return Err(CliError::ActionFailed(message));
+            }
+            match cli.format {
+                OutputFormat::Json => print_json(data.as_deref().map(parse_data))?,
+                OutputFormat::Text => println!("{}", message),
+            }
+            Ok(())
+        }
+        IpcResponse::CommandResult { success, message } => {
+            if success {
+                Ok(())
+            } else {
+                Err(CliError::ActionFailed(message))
+            }
+        }
+        IpcResponse::Error { message } => Err(CliError::ActionFailed(message)),
+        _ => Err(CliError::Protocol("Unexpected response type".to_string())),
+    }
+}
+
+/// Render screen info as aligned text lines, one field per line.
+fn format_device_info(info: &ScreenInfo) -> String {
+    format!(
+        "Size:        {} x {} pt\nPixels:      {} x {} px\nScale:       {}x\nOrientation: {}\n",
+        info.width,
+        info.height,
+        (info.width * info.scale).round(),
+        (info.height * info.scale).round(),
+        info.scale,
+        info.orientation
+            .map_or_else(|| "unknown".to_string(), |o| o.to_string())
+    )
+}
+
 /// Check if an element is "actionable" (has an identifier or label, and is a meaningful type).
 fn is_actionable(elem: &UIElement) -> bool {
     elem.identifier.is_some() || elem.label.is_some()
@@ -966,52 +2836,43 @@ fn element_to_concise_json(elem: &UIElement) -> serde_json::Value {
     if let Some(hittable) = elem.hittable {
         map.insert("hittable".into(), serde_json::Value::Bool(hittable));
     }
+    for (key, state) in [
+        ("enabled", elem.enabled),
+        ("selected", elem.selected),
+        ("focused", elem.focused),
+    ] {
+        if let Some(state) = state {
+            map.insert(key.into(), serde_json::Value::Bool(state));
+        }
+    }
     serde_json::Value::Object(map)
 }
 
 fn frame_to_rounded_json(frame: &ElementFrame) -> serde_json::Value {
+    let (center_x, center_y) = frame.center();
     serde_json::json!({
         "x": frame.x.round() as i64,
         "y": frame.y.round() as i64,
         "width": frame.width.round() as i64,
         "height": frame.height.round() as i64,
+        "center": {
+            "x": center_x.round() as i64,
+            "y": center_y.round() as i64,
+        },
     })
 }
 
-/// Format an element in the REPL style: `[Type] id "label" =value @(x,y)`
-fn format_element_pretty(elem: &UIElement) -> String {
-    let mut parts = Vec::new();
-    let elem_type = elem.element_type.as_deref().unwrap_or("Unknown");
-    parts.push(format!("[{}]", elem_type));
-    if let Some(ref id) = elem.identifier {
-        parts.push(id.clone());
-    }
-    if let Some(ref label) = elem.label {
-        parts.push(format!("\"{}\"", label));
-    }
-    if let Some(ref value) = elem.value {
-        parts.push(format!("={}", value));
-    }
-    if let Some(ref frame) = elem.frame {
-        parts.push(format!("@({:.0},{:.0})", frame.x, frame.y));
-    }
-    parts.join(" ")
-}
-
 async fn execute_screen_info(
     client: &mut IpcClient,
     cli: &Cli,
     full: bool,
     pretty: bool,
+    action: ActionType,
     tag: Option<String>,
 ) -> Result<(), CliError> {
-    let request = IpcRequest::Execute {
-        action: ActionType::GetScreenInfo,
-        tag,
-    };
-    let response = client
-        .send(&request)
-        .await
+    let request = execute_request(action, tag, cli);
+    let response = within(cli.request_timeout, client.send(&request))
+        .await?
         .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
 
     match response {
@@ -1025,36 +2886,108 @@ async fn execute_screen_info(
                 return Err(CliError::ActionFailed(message));
             }
             let data_str = data.as_deref().unwrap_or("[]");
+            let elements = parse_elements(data_str)?;
+            print_screen_info(cli, full, pretty, &elements, data_str)
+        }
+        IpcResponse::NoChange { hash } => {
+            if cli.format == OutputFormat::Json {
+                print_json(serde_json::json!({ "unchanged": true, "hash": hash }))?;
+            }
+            Err(CliError::Unchanged)
+        }
+        IpcResponse::Error { message } => Err(CliError::ActionFailed(message)),
+        _ => Err(CliError::Protocol("Unexpected response type".to_string())),
+    }
+}
 
-            if full {
-                // Original behavior: dump raw JSON
-                println!("{}", data_str);
-            } else if pretty {
-                // REPL-style formatted output
-                let elements: Vec<UIElement> = serde_json::from_str(data_str)
-                    .map_err(|e| CliError::Protocol(format!("Failed to parse elements: {}", e)))?;
-                let actionable = collect_actionable(&elements);
-                for elem in &actionable {
-                    println!("{}", format_element_pretty(elem));
-                }
-                if !cli.quiet {
-                    eprintln!("{} elements", actionable.len());
-                }
-            } else {
-                // Default: concise JSON, actionable only, no nulls, rounded frames
-                let elements: Vec<UIElement> = serde_json::from_str(data_str)
-                    .map_err(|e| CliError::Protocol(format!("Failed to parse elements: {}", e)))?;
-                let actionable = collect_actionable(&elements);
-                let concise: Vec<serde_json::Value> = actionable
+fn parse_elements(data: &str) -> Result<Vec<UIElement>, CliError> {
+    serde_json::from_str(data)
+        .map_err(|e| CliError::Protocol(format!("Failed to parse elements: {}", e)))
+}
+
+/// Prints a `screen-info` result; `raw` is the payload `elements` came from.
+fn print_screen_info(
+    cli: &Cli,
+    full: bool,
+    pretty: bool,
+    elements: &[UIElement],
+    raw: &str,
+) -> Result<(), CliError> {
+    if !cli.quiet {
+        eprintln!("hash: {}", hash_elements(elements));
+    }
+
+    if cli.format == OutputFormat::Json {
+        // Same elements as the text output; `--pretty` only affects text
+        if full {
+            print_json(elements)?;
+        } else {
+            print_json(
+                collect_actionable(elements)
                     .iter()
                     .map(|e| element_to_concise_json(e))
-                    .collect();
-                println!("{}", serde_json::to_string_pretty(&concise).unwrap());
-                if !cli.quiet {
-                    eprintln!("{} elements", actionable.len());
-                }
-            }
+                    .collect::<Vec<_>>(),
+            )?;
+        }
+    } else if full {
+        // Original behavior: dump raw JSON
+        println!("{}", raw);
+    } else if pretty {
+        // REPL-style formatted output
+        let actionable = collect_actionable(elements);
+        for elem in &actionable {
+            println!("{}", elem.summary());
+        }
+        if !cli.quiet {
+            eprintln!("{} elements", actionable.len());
+        }
+    } else {
+        // Default: concise JSON, actionable only, no nulls, rounded frames
+        let actionable = collect_actionable(elements);
+        let concise: Vec<serde_json::Value> = actionable
+            .iter()
+            .map(|e| element_to_concise_json(e))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&concise).unwrap());
+        if !cli.quiet {
+            eprintln!("{} elements", actionable.len());
+        }
+    }
+
+    Ok(())
+}
+
+async fn describe_screen(
+    client: &mut IpcClient,
+    cli: &Cli,
+    tag: Option<String>,
+) -> Result<(), CliError> {
+    let action = ActionType::GetScreenInfo {
+        filter: ElementFilter::default(),
+        changed_since_hash: None,
+    };
+    let request = execute_request(action, tag, cli);
+    let response = within(cli.request_timeout, client.send(&request))
+        .await?
+        .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
 
+    match response {
+        IpcResponse::ActionResult {
+            success,
+            message,
+            data,
+            ..
+        } => {
+            if !success {
+                return Err(CliError::ActionFailed(message));
+            }
+            let elements = parse_elements(data.as_deref().unwrap_or("[]"))?;
+            let summary = ScreenSummary::from_elements(&elements);
+            if cli.format == OutputFormat::Json {
+                print_json(&summary)?;
+            } else {
+                println!("{}", summary);
+            }
             Ok(())
         }
         IpcResponse::Error { message } => Err(CliError::ActionFailed(message)),
@@ -1062,26 +2995,144 @@ async fn execute_screen_info(
     }
 }
 
-async fn get_status(client: &mut IpcClient, cli: &Cli) -> Result<(), CliError> {
-    let response = client
-        .send(&IpcRequest::GetState)
+/// Where `screen-info --watch` reads the screen from.
+trait ScreenSource {
+    /// Fetches the element tree payload, or `None` if the screen still
+    /// hashes to `since`.
+    async fn fetch(&mut self, since: Option<&str>) -> Result<Option<String>, CliError>;
+}
+
+/// Reads the screen with `FetchScreenInfo` requests, which the server does
+/// not log.
+struct IpcScreenSource<'a> {
+    client: &'a mut IpcClient,
+    cli: &'a Cli,
+    filter: ElementFilter,
+}
+
+impl ScreenSource for IpcScreenSource<'_> {
+    async fn fetch(&mut self, since: Option<&str>) -> Result<Option<String>, CliError> {
+        let request = IpcRequest::FetchScreenInfo {
+            filter: self.filter.clone(),
+            changed_since_hash: since.map(str::to_string),
+        };
+        let response = within(self.cli.request_timeout, self.client.send(&request))
+            .await?
+            .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
+        match response {
+            IpcResponse::ActionResult {
+                success: true,
+                data,
+                ..
+            } => Ok(Some(data.unwrap_or_else(|| "[]".to_string()))),
+            IpcResponse::ActionResult { message, .. } => Err(CliError::ActionFailed(message)),
+            IpcResponse::NoChange { .. } => Ok(None),
+            IpcResponse::Error { message } => Err(CliError::ActionFailed(message)),
+            _ => Err(CliError::Protocol("Unexpected response type".to_string())),
+        }
+    }
+}
+
+/// Renders the screen, then fetches it again after each message on
+/// `changes` and renders it if it changed. Returns the number of renders
+/// once `changes` closes.
+async fn watch_screen(
+    source: &mut impl ScreenSource,
+    changes: &mut tokio::sync::mpsc::Receiver<()>,
+    mut render: impl FnMut(&[UIElement], &str) -> Result<(), CliError>,
+) -> Result<usize, CliError> {
+    let mut hash: Option<String> = None;
+    let mut renders = 0;
+    loop {
+        if let Some(raw) = source.fetch(hash.as_deref()).await? {
+            let elements = parse_elements(&raw)?;
+            hash = Some(hash_elements(&elements));
+            render(&elements, &raw)?;
+            renders += 1;
+        }
+        if changes.recv().await.is_none() {
+            return Ok(renders);
+        }
+    }
+}
+
+/// Runs `screen-info --watch`: subscribes to the session's events on a
+/// second connection and reprints the screen after every action another
+/// client runs, until Ctrl-C.
+async fn watch_screen_info(
+    client: &mut IpcClient,
+    cli: &Cli,
+    full: bool,
+    pretty: bool,
+    filter: ElementFilter,
+) -> Result<(), CliError> {
+    let mut events = within(Some(cli.connect_timeout), IpcClient::connect(&cli.session))
+        .await?
+        .map_err(|e| CliError::Connection(e.to_string()))?;
+    events
+        .subscribe()
         .await
+        .map_err(|e| CliError::Connection(e.to_string()))?;
+
+    // Capacity 1 coalesces a burst of actions into a single refresh
+    let (tx, mut changes) = tokio::sync::mpsc::channel(1);
+    tokio::spawn(async move {
+        while let Ok(response) = events.read_event().await {
+            if let IpcResponse::Event {
+                event: SessionEvent::ActionLogged(_),
+            } = response
+            {
+                let _ = tx.try_send(());
+            }
+        }
+    });
+
+    let mut source = IpcScreenSource {
+        client,
+        cli,
+        filter,
+    };
+    let render = |elements: &[UIElement], raw: &str| {
+        if cli.format == OutputFormat::Text {
+            // Clear the terminal and move the cursor home
+            print!("\x1b[2J\x1b[H");
+        }
+        print_screen_info(cli, full, pretty, elements, raw)
+    };
+    tokio::select! {
+        result = watch_screen(&mut source, &mut changes, render) => {
+            result?;
+            Err(CliError::Connection("server closed the event stream".to_string()))
+        }
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+async fn get_status(client: &mut IpcClient, cli: &Cli) -> Result<(), CliError> {
+    let response = within(cli.request_timeout, client.send(&IpcRequest::GetSnapshot))
+        .await?
         .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
 
     match response {
-        IpcResponse::State {
-            session_id,
-            screenshot,
-        } => {
+        IpcResponse::Snapshot { snapshot } => {
+            let has_screenshot = snapshot.last_screenshot.is_some();
             if cli.format == OutputFormat::Json {
-                let output = serde_json::json!({
-                    "session_id": session_id,
-                    "has_screenshot": screenshot.is_some(),
-                });
-                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+                print_json(serde_json::json!({
+                    "session_id": snapshot.session_id,
+                    "udid": snapshot.udid,
+                    "action_count": snapshot.action_count,
+                    "started_at": snapshot.started_at,
+                    "has_screenshot": has_screenshot,
+                }))?;
             } else {
-                println!("Session ID: {}", session_id);
-                println!("Has screenshot: {}", screenshot.is_some());
+                println!("Session ID: {}", snapshot.session_id);
+                println!("Device: {}", snapshot.udid.as_deref().unwrap_or("(none)"));
+                println!("Actions: {}", snapshot.action_count);
+                println!(
+                    "Started: {}",
+                    snapshot.started_at.format("%Y-%m-%d %H:%M:%S UTC")
+                );
+                println!("Has screenshot: {}", has_screenshot);
             }
             Ok(())
         }
@@ -1090,27 +3141,64 @@ async fn get_status(client: &mut IpcClient, cli: &Cli) -> Result<(), CliError> {
     }
 }
 
+async fn get_health(client: &mut IpcClient, cli: &Cli) -> Result<(), CliError> {
+    let response = within(cli.request_timeout, client.send(&IpcRequest::Health))
+        .await?
+        .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
+
+    match response {
+        IpcResponse::Health {
+            session_active,
+            driver_connected,
+            agent_target,
+            simulator_udid,
+        } => {
+            if cli.format == OutputFormat::Json {
+                print_json(serde_json::json!({
+                    "session_active": session_active,
+                    "driver_connected": driver_connected,
+                    "agent_target": agent_target,
+                    "simulator_udid": simulator_udid,
+                }))?;
+            } else {
+                println!("Session active: {}", session_active);
+                println!("Driver connected: {}", driver_connected);
+                println!(
+                    "Agent target: {}",
+                    agent_target.as_deref().unwrap_or("(none)")
+                );
+                println!(
+                    "Simulator: {}",
+                    simulator_udid.as_deref().unwrap_or("(none)")
+                );
+            }
+            // Like `exists`, a "no" is reported above and only sets the exit code
+            if session_active && driver_connected {
+                Ok(())
+            } else {
+                Err(CliError::Absent)
+            }
+        }
+        IpcResponse::Error { message } => Err(CliError::ActionFailed(message)),
+        _ => Err(CliError::Protocol("Unexpected response type".to_string())),
+    }
+}
+
 async fn get_log(client: &mut IpcClient, cli: &Cli) -> Result<(), CliError> {
-    let response = client
-        .send(&IpcRequest::GetLog)
-        .await
+    let response = within(cli.request_timeout, client.send(&IpcRequest::GetLog))
+        .await?
         .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
 
     match response {
         IpcResponse::Log { entries } => {
             if cli.format == OutputFormat::Json {
-                println!("{}", serde_json::to_string_pretty(&entries).unwrap());
+                print_json(&entries)?;
             } else {
                 if entries.is_empty() {
                     println!("No actions logged");
                 } else {
                     for entry in entries {
-                        println!(
-                            "[{}] {:?} - {:?}",
-                            entry.timestamp.format("%H:%M:%S"),
-                            entry.action,
-                            entry.result
-                        );
+                        println!("{}", log_line(&entry));
                     }
                 }
             }
@@ -1121,23 +3209,38 @@ async fn get_log(client: &mut IpcClient, cli: &Cli) -> Result<(), CliError> {
     }
 }
 
+/// One `qorvex log` line. Assertions show as a check mark or cross with
+/// what was checked; other actions show their full details and result.
+fn log_line(entry: &ActionLog) -> String {
+    let time = entry.timestamp.format("%H:%M:%S");
+    match entry.action.assertion_summary() {
+        Some(summary) => format!("[{}] {}", time, summary),
+        None => format!("[{}] {:?} - {:?}", time, entry.action, entry.result),
+    }
+}
+
+/// Builds the `Execute` request for an action, carrying `--no-screenshot`.
+fn execute_request(action: ActionType, tag: Option<String>, cli: &Cli) -> IpcRequest {
+    IpcRequest::Execute {
+        action,
+        tag,
+        capture_screenshot: cli.command.no_screenshot().then_some(false),
+    }
+}
+
 async fn send_command(
     client: &mut IpcClient,
     request: IpcRequest,
     cli: &Cli,
 ) -> Result<(), CliError> {
-    let response = client
-        .send(&request)
-        .await
+    let response = within(cli.request_timeout, client.send(&request))
+        .await?
         .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
 
     match response {
         IpcResponse::CommandResult { success, message } => {
             if success {
-                if !cli.quiet {
-                    eprintln!("{}", message);
-                }
-                Ok(())
+                report_message(&message, cli)
             } else {
                 Err(CliError::ActionFailed(message))
             }
@@ -1147,6 +3250,51 @@ async fn send_command(
     }
 }
 
+/// Reports a command's success message: as `{"message": ...}` data in JSON
+/// mode, otherwise on stderr unless `--quiet`.
+fn report_message(message: &str, cli: &Cli) -> Result<(), CliError> {
+    if cli.format == OutputFormat::Json {
+        print_json(serde_json::json!({ "message": message }))
+    } else {
+        if !cli.quiet {
+            eprintln!("{}", message);
+        }
+        Ok(())
+    }
+}
+
+/// Builds the selectors of `wait-for-any`/`wait-for-all`, which share one set
+/// of `--label`/`--type` flags.
+fn selector_specs(
+    selectors: &[String],
+    by_label: bool,
+    element_type: Option<&str>,
+) -> Vec<SelectorSpec> {
+    selectors
+        .iter()
+        .map(|selector| SelectorSpec {
+            selector: selector.clone(),
+            by_label,
+            element_type: element_type.map(str::to_string),
+        })
+        .collect()
+}
+
+/// One `list-devices` line: UDID, name, runtime, then state flags.
+fn simulator_line(device: &qorvex_core::simctl::SimulatorDevice) -> String {
+    let mut line = format!("{} -- {}", device.udid, device.name);
+    if let Some(runtime) = device.runtime_name() {
+        line.push_str(&format!(" [{}]", runtime));
+    }
+    if device.state == "Booted" {
+        line.push_str(" (Booted)");
+    }
+    if !device.is_available {
+        line.push_str(" (unavailable)");
+    }
+    line
+}
+
 /// True when `udid` names a known simulator in `simulators` (the simctl device
 /// list). `start --device` also accepts physical iOS UDIDs and Android serials;
 /// neither matches a simctl device, so both correctly read as non-simulator and
@@ -1155,7 +3303,11 @@ fn is_known_simulator(udid: &str, simulators: &[qorvex_core::simctl::SimulatorDe
     simulators.iter().any(|d| d.udid == udid)
 }
 
-async fn start_all(cli: &Cli, device: Option<String>) -> Result<(), CliError> {
+async fn start_all(
+    cli: &Cli,
+    device: Option<String>,
+    agent_port: Option<u16>,
+) -> Result<(), CliError> {
     use qorvex_core::config::QorvexConfig;
     use qorvex_core::ipc::socket_path;
 
@@ -1221,6 +3373,9 @@ async fn start_all(cli: &Cli, device: Option<String>) -> Result<(), CliError> {
                     .args(&args)
                     .stdout(if cli.quiet {
                         std::process::Stdio::null()
+                    } else if cli.format == OutputFormat::Json {
+                        // Keep stdout for the JSON envelope
+                        std::process::Stdio::from(std::io::stderr())
                     } else {
                         std::process::Stdio::inherit()
                     })
@@ -1246,6 +3401,9 @@ async fn start_all(cli: &Cli, device: Option<String>) -> Result<(), CliError> {
 
         let mut cmd = std::process::Command::new("qorvex-server");
         cmd.args(["-s", &cli.session]);
+        if let Some(port) = agent_port {
+            cmd.args(["--agent-port", &port.to_string()]);
+        }
         if let Some(f) = log_file {
             cmd.stdout(
                 f.try_clone()
@@ -1271,16 +3429,18 @@ async fn start_all(cli: &Cli, device: Option<String>) -> Result<(), CliError> {
     }
 
     // Connect and start session
-    let mut client = IpcClient::connect(&cli.session)
-        .await
+    let mut client = within(Some(cli.connect_timeout), IpcClient::connect(&cli.session))
+        .await?
         .map_err(|e| CliError::Connection(format!("Failed to connect: {}", e)))?;
 
     // Select device before starting session so agent auto-start uses the right connection mode
     if let Some(ref udid) = device {
-        let response = client
-            .send(&IpcRequest::UseDevice { udid: udid.clone() })
-            .await
-            .map_err(|e| CliError::Protocol(format!("Failed to select device: {}", e)))?;
+        let response = within(
+            cli.request_timeout,
+            client.send(&IpcRequest::UseDevice { udid: udid.clone() }),
+        )
+        .await?
+        .map_err(|e| CliError::Protocol(format!("Failed to select device: {}", e)))?;
 
         match response {
             IpcResponse::CommandResult { success, message } => {
@@ -1296,18 +3456,14 @@ async fn start_all(cli: &Cli, device: Option<String>) -> Result<(), CliError> {
         }
     }
 
-    let response = client
-        .send(&IpcRequest::StartSession)
-        .await
+    let response = within(cli.request_timeout, client.send(&IpcRequest::StartSession))
+        .await?
         .map_err(|e| CliError::Protocol(format!("Failed to start session: {}", e)))?;
 
     match response {
         IpcResponse::CommandResult { success, message } => {
             if success {
-                if !cli.quiet {
-                    eprintln!("{}", message);
-                }
-                Ok(())
+                report_message(&message, cli)
             } else {
                 Err(CliError::ActionFailed(message))
             }
@@ -1318,19 +3474,17 @@ async fn start_all(cli: &Cli, device: Option<String>) -> Result<(), CliError> {
 }
 
 async fn list_physical_devices(client: &mut IpcClient, cli: &Cli) -> Result<(), CliError> {
-    let response = client
-        .send(&IpcRequest::ListPhysicalDevices)
-        .await
-        .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
+    let response = within(
+        cli.request_timeout,
+        client.send(&IpcRequest::ListPhysicalDevices),
+    )
+    .await?
+    .map_err(|e| CliError::Protocol(format!("Failed to send request: {}", e)))?;
 
     match response {
         IpcResponse::PhysicalDeviceList { devices } => {
             if cli.format == OutputFormat::Json {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&devices)
-                        .map_err(|e| CliError::Protocol(e.to_string()))?
-                );
+                print_json(&devices)?;
             } else {
                 if devices.is_empty() {
                     eprintln!("No physical devices found");
@@ -1349,18 +3503,12 @@ async fn list_physical_devices(client: &mut IpcClient, cli: &Cli) -> Result<(),
 }
 
 async fn stop_server(client: &mut IpcClient, cli: &Cli) -> Result<(), CliError> {
-    let response = client
-        .send(&IpcRequest::Shutdown)
-        .await
+    let response = within(cli.request_timeout, client.send(&IpcRequest::Shutdown))
+        .await?
         .map_err(|e| CliError::Protocol(format!("Failed to send shutdown request: {}", e)))?;
 
     match response {
-        IpcResponse::ShutdownAck => {
-            if !cli.quiet {
-                eprintln!("Server stopped");
-            }
-            Ok(())
-        }
+        IpcResponse::ShutdownAck => report_message("Server stopped", cli),
         IpcResponse::Error { message } => Err(CliError::ActionFailed(message)),
         _ => Err(CliError::Protocol(
             "Unexpected response to Shutdown".to_string(),
@@ -1415,9 +3563,58 @@ mod tests {
             name: "iPhone 15 Pro".to_string(),
             state: "Booted".to_string(),
             device_type: None,
+            runtime: None,
+            is_available: true,
         }
     }
 
+    #[test]
+    fn log_line_marks_assertions() {
+        use chrono::TimeZone;
+        use qorvex_core::action::ActionResult;
+
+        let at = |action: ActionType, result: ActionResult| {
+            let mut entry = ActionLog::new(action, result, None, None, None);
+            entry.timestamp = chrono::Utc.with_ymd_and_hms(2026, 3, 2, 9, 15, 0).unwrap();
+            log_line(&entry)
+        };
+        let passed = at(
+            ActionType::Assertion {
+                description: "banner shown".to_string(),
+                passed: true,
+            },
+            ActionResult::Success,
+        );
+        assert_eq!(passed, "[09:15:00] ✓ banner shown");
+        let failed = at(
+            ActionType::Assertion {
+                description: "banner shown".to_string(),
+                passed: false,
+            },
+            ActionResult::Failure("Assertion failed: banner shown".to_string()),
+        );
+        assert_eq!(failed, "[09:15:00] ✗ banner shown");
+        let wait = at(ActionType::Wait { ms: 5 }, ActionResult::Success);
+        assert_eq!(wait, "[09:15:00] Wait { ms: 5 } - Success");
+    }
+
+    #[test]
+    fn simulator_line_shows_runtime_and_availability() {
+        let mut device = sim("SIM-AAAA-1111");
+        assert_eq!(
+            simulator_line(&device),
+            "SIM-AAAA-1111 -- iPhone 15 Pro (Booted)"
+        );
+
+        device.runtime = Some("com.apple.CoreSimulator.SimRuntime.iOS-17-0".to_string());
+        device.state = "Shutdown".to_string();
+        device.is_available = false;
+        assert_eq!(
+            simulator_line(&device),
+            "SIM-AAAA-1111 -- iPhone 15 Pro [iOS 17.0] (unavailable)"
+        );
+    }
+
     #[test]
     fn simulator_target_is_recognized() {
         let sims = vec![sim("SIM-AAAA-1111"), sim("SIM-BBBB-2222")];
@@ -1438,4 +3635,311 @@ mod tests {
         // Empty simctl list (e.g. simctl failed) -> nothing is a simulator.
         assert!(!is_known_simulator("SIM-AAAA-1111", &[]));
     }
+
+    // 1x1 PNG, base64-encoded as the server returns it
+    const PNG_B64: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=";
+
+    #[test]
+    fn write_screenshot_to_path_writes_raw_png() {
+        let path = std::env::temp_dir().join(format!("qorvex-shot-{}.png", std::process::id()));
+        let mut stdout = Vec::new();
+
+        let bytes = write_screenshot(PNG_B64, &path, &mut stdout).unwrap();
+        let written = fs::read(&path).unwrap();
+        assert_eq!(written.len(), bytes);
+        assert!(written.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(stdout.is_empty());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn write_screenshot_dash_writes_binary_to_stdout() {
+        let mut stdout = Vec::new();
+        let bytes = write_screenshot(PNG_B64, std::path::Path::new("-"), &mut stdout).unwrap();
+        assert_eq!(stdout.len(), bytes);
+        assert!(stdout.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+
+    #[test]
+    fn write_screenshot_rejects_invalid_base64() {
+        let mut stdout = Vec::new();
+        let err = write_screenshot("not base64!", std::path::Path::new("-"), &mut stdout);
+        assert!(matches!(err, Err(CliError::Protocol(_))));
+    }
+
+    #[test]
+    fn validate_output_path_requires_existing_parent() {
+        let tmp = std::env::temp_dir();
+        assert!(validate_output_path(std::path::Path::new("-")).is_ok());
+        assert!(validate_output_path(std::path::Path::new("screen.png")).is_ok());
+        assert!(validate_output_path(&tmp.join("screen.png")).is_ok());
+        assert!(matches!(
+            validate_output_path(&tmp.join("qorvex-no-such-dir").join("screen.png")),
+            Err(CliError::ActionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn element_output_includes_known_state() {
+        let elem: UIElement = serde_json::from_str(
+            r#"{"AXUniqueId":"save","type":"Button","enabled":false,"selected":true,"children":[]}"#,
+        )
+        .unwrap();
+        let concise = element_to_concise_json(&elem);
+        assert_eq!(concise["enabled"], false);
+        assert_eq!(concise["selected"], true);
+        // Unknown state is omitted, not reported as false
+        assert!(concise.get("focused").is_none());
+        assert_eq!(elem.summary(), "[Button] save disabled selected");
+    }
+
+    #[test]
+    fn swipe_coords_parses_endpoints_and_duration() {
+        let cli = Cli::try_parse_from([
+            "qorvex",
+            "swipe-coords",
+            "300",
+            "400",
+            "50",
+            "410",
+            "-d",
+            "0.8",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::SwipeCoords {
+                x1,
+                y1,
+                x2,
+                y2,
+                duration,
+                ..
+            } => {
+                assert_eq!((x1, y1, x2, y2), (300, 400, 50, 410));
+                assert_eq!(duration, Some(0.8));
+            }
+            _ => panic!("expected swipe-coords"),
+        }
+    }
+
+    #[test]
+    fn swipe_coords_rejects_negative_coordinates_and_bad_durations() {
+        for args in [
+            &["swipe-coords", "300", "400", "50"][..],
+            &["swipe-coords", "300", "400", "50", "x"],
+            &["swipe-coords", "300", "400", "--", "-5", "400"],
+            &["swipe-coords", "300", "400", "50", "400", "--duration", "0"],
+            &[
+                "swipe-coords",
+                "300",
+                "400",
+                "50",
+                "400",
+                "--duration",
+                "-1",
+            ],
+            &[
+                "swipe-coords",
+                "300",
+                "400",
+                "50",
+                "400",
+                "--duration",
+                "inf",
+            ],
+        ] {
+            let argv = std::iter::once("qorvex").chain(args.iter().copied());
+            assert!(Cli::try_parse_from(argv).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn tap_offset_validates_fractions() {
+        let cli =
+            Cli::try_parse_from(["qorvex", "tap-offset", "volume-slider", "0.8", "0.5"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::TapOffset { dx, dy, label: false, .. } if dx == 0.8 && dy == 0.5
+        ));
+        for (dx, dy) in [("1.5", "0.5"), ("0.5", "2"), ("NaN", "0.5"), ("x", "0.5")] {
+            assert!(
+                Cli::try_parse_from(["qorvex", "tap-offset", "s", dx, dy]).is_err(),
+                "{dx} {dy}"
+            );
+        }
+    }
+
+    #[test]
+    fn no_screenshot_is_only_an_action_option() {
+        let cli = Cli::try_parse_from(["qorvex", "tap-location", "5", "6", "--no-screenshot"]);
+        assert!(cli.unwrap().command.no_screenshot());
+        let cli = Cli::try_parse_from(["qorvex", "tap-location", "5", "6"]);
+        assert!(!cli.unwrap().command.no_screenshot());
+        for args in [
+            ["qorvex", "status", "--no-screenshot"],
+            ["qorvex", "--no-screenshot", "status"],
+            // Logged without a screenshot anyway
+            ["qorvex", "comment", "--no-screenshot"],
+        ] {
+            assert!(Cli::try_parse_from(args).is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn screen_info_watch_rejects_tag() {
+        assert!(Cli::try_parse_from(["qorvex", "screen-info", "--watch"]).is_ok());
+        // Watch reads are not logged, so there is nothing to tag
+        assert!(Cli::try_parse_from(["qorvex", "screen-info", "--watch", "--tag", "x"]).is_err());
+    }
+
+    #[test]
+    fn long_press_takes_a_selector_or_coordinates() {
+        let cli = Cli::try_parse_from([
+            "qorvex",
+            "long-press",
+            "message-bubble",
+            "--duration",
+            "1.5",
+        ])
+        .unwrap();
+        let Command::LongPress {
+            target,
+            y,
+            label,
+            duration,
+            ..
+        } = cli.command
+        else {
+            panic!("expected long-press");
+        };
+        assert!(matches!(
+            long_press_action(&target, y, label, duration).unwrap(),
+            ActionType::LongPressElement { ref selector, by_label: false, duration }
+                if selector == "message-bubble" && duration == 1.5
+        ));
+
+        let cli = Cli::try_parse_from(["qorvex", "long-press", "100", "200"]).unwrap();
+        let Command::LongPress {
+            target,
+            y,
+            label,
+            duration,
+            ..
+        } = cli.command
+        else {
+            panic!("expected long-press");
+        };
+        assert!(matches!(
+            long_press_action(&target, y, label, duration).unwrap(),
+            ActionType::LongPress { x: 100, y: 200, duration } if duration == 1.0
+        ));
+        assert!(long_press_action("row", Some(200), false, 1.0).is_err());
+
+        for args in [
+            &["long-press", "row", "--duration", "0"][..],
+            &["long-press", "row", "--duration", "-1"],
+            &["long-press", "100", "200", "--label"],
+        ] {
+            let argv = std::iter::once("qorvex").chain(args.iter().copied());
+            assert!(Cli::try_parse_from(argv).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn timeouts_default_to_five_second_connect_and_no_request_limit() {
+        let cli = Cli::try_parse_from(["qorvex", "status"]).unwrap();
+        assert_eq!(cli.connect_timeout, 5.0);
+        assert_eq!(cli.request_timeout, None);
+
+        let cli = Cli::try_parse_from([
+            "qorvex",
+            "--connect-timeout",
+            "1.5",
+            "--request-timeout",
+            "30",
+            "status",
+        ])
+        .unwrap();
+        assert_eq!(cli.connect_timeout, 1.5);
+        assert_eq!(cli.request_timeout, Some(30.0));
+
+        assert!(Cli::try_parse_from(["qorvex", "--connect-timeout", "0", "status"]).is_err());
+        assert!(Cli::try_parse_from(["qorvex", "--request-timeout", "-1", "status"]).is_err());
+    }
+
+    #[test]
+    fn check_count_enforces_expectation() {
+        assert!(check_count(5, None).is_ok());
+        assert!(check_count(5, Some(5)).is_ok());
+        let err = check_count(3, Some(5)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Action failed: Expected 5 matching elements, found 3"
+        );
+        assert!(check_count(0, Some(0)).is_ok());
+    }
+
+    #[test]
+    fn format_device_info_reports_points_and_pixels() {
+        let info = ScreenInfo {
+            width: 393.0,
+            height: 852.0,
+            scale: 3.0,
+            orientation: Some(Orientation::Portrait),
+        };
+        assert_eq!(
+            format_device_info(&info),
+            "Size:        393 x 852 pt\n\
+             Pixels:      1179 x 2556 px\n\
+             Scale:       3x\n\
+             Orientation: portrait\n"
+        );
+    }
+
+    /// Replays canned `screen-info` payloads, recording the hash each fetch
+    /// was given.
+    struct FakeScreen {
+        payloads: Vec<&'static str>,
+        seen: Vec<Option<String>>,
+    }
+
+    impl ScreenSource for FakeScreen {
+        async fn fetch(&mut self, since: Option<&str>) -> Result<Option<String>, CliError> {
+            self.seen.push(since.map(str::to_string));
+            let raw = self.payloads.remove(0);
+            let unchanged =
+                since.is_some_and(|h| hash_elements(&parse_elements(raw).unwrap()) == h);
+            Ok((!unchanged).then(|| raw.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn watch_screen_reprints_only_when_screen_changes() {
+        let first = r#"[{"AXUniqueId":"login","type":"Button","children":[]}]"#;
+        let second = r#"[{"AXUniqueId":"logout","type":"Button","children":[]}]"#;
+        let mut source = FakeScreen {
+            payloads: vec![first, first, second],
+            seen: Vec::new(),
+        };
+        let (tx, mut changes) = tokio::sync::mpsc::channel(2);
+        tx.send(()).await.unwrap();
+        tx.send(()).await.unwrap();
+        drop(tx);
+
+        let mut rendered = Vec::new();
+        let renders = watch_screen(&mut source, &mut changes, |elements, _| {
+            rendered.push(elements[0].identifier.clone().unwrap());
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(renders, 2);
+        assert_eq!(rendered, ["login", "logout"]);
+        let first_hash = hash_elements(&parse_elements(first).unwrap());
+        assert_eq!(
+            source.seen,
+            [None, Some(first_hash.clone()), Some(first_hash)]
+        );
+    }
 }
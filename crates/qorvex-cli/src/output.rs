@@ -0,0 +1,75 @@
+//! Machine-readable output for `--format json`.
+//!
+//! In JSON mode every command prints exactly one [`CliOutput`] envelope on
+//! stdout, whether it succeeded or failed, so a script can always check `ok`
+//! before reading `data` or `error`. Progress and diagnostics still go to
+//! stderr.
+
+use serde::Serialize;
+
+/// The JSON envelope wrapping a command's result.
+///
+/// Serializes as `{"ok": true, "data": ..., "error": null}` on success and
+/// `{"ok": false, "data": null, "error": "..."}` on failure.
+#[derive(Debug, Serialize)]
+pub struct CliOutput<T> {
+    /// Whether the command succeeded.
+    pub ok: bool,
+    /// The command's result; `null` on failure.
+    pub data: T,
+    /// What went wrong; `null` on success.
+    pub error: Option<String>,
+}
+
+impl<T: Serialize> CliOutput<T> {
+    /// A successful result carrying `data`.
+    pub fn success(data: T) -> Self {
+        Self {
+            ok: true,
+            data,
+            error: None,
+        }
+    }
+
+    /// Renders the envelope as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl CliOutput<()> {
+    /// A failed result with no data.
+    pub fn failure(error: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            data: (),
+            error: Some(error.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn render<T: Serialize>(output: CliOutput<T>) -> Value {
+        serde_json::from_str(&output.to_json().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn success_has_data_and_null_error() {
+        assert_eq!(
+            render(CliOutput::success(vec!["a", "b"])),
+            json!({ "ok": true, "data": ["a", "b"], "error": null })
+        );
+    }
+
+    #[test]
+    fn failure_has_null_data_and_error() {
+        assert_eq!(
+            render(CliOutput::failure("Connection error: refused")),
+            json!({ "ok": false, "data": null, "error": "Connection error: refused" })
+        );
+    }
+}
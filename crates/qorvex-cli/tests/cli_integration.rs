@@ -1,6 +1,9 @@
 #![allow(deprecated)]
 
+mod common;
+
 use assert_cmd::Command;
+use common::{qorvex, FakeServer};
 use predicates::prelude::*;
 use std::path::PathBuf;
 
@@ -48,6 +51,70 @@ fn test_convert_basic_session() {
     assert!(!stdout.contains("EndSession"));
 }
 
+#[test]
+fn test_convert_preserve_timing() {
+    let fixture = fixture_path("basic_session.jsonl");
+
+    let assert = Command::cargo_bin("qorvex")
+        .unwrap()
+        .args(["convert", fixture.to_str().unwrap(), "--preserve-timing"])
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+
+    // Actions finish a second apart; each pause excludes the next action's duration
+    assert!(stdout.contains("# 2026-02-20T10:00:02.000Z (120ms)\nqorvex send-keys"));
+    assert!(stdout.contains("sleep 0.9\n"));
+    assert!(stdout.contains("sleep 0.8\n"));
+}
+
+#[test]
+fn test_convert_pytest() {
+    let fixture = fixture_path("basic_session.jsonl");
+
+    let assert = Command::cargo_bin("qorvex")
+        .unwrap()
+        .args(["convert", fixture.to_str().unwrap(), "--format", "pytest"])
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert!(stdout.contains("def test_session_1():\n    qorvex(\"tap\", \"login-button\")\n"));
+    assert!(stdout.contains("    qorvex(\"swipe\", \"up\")\n"));
+    assert!(!stdout.contains("#!/usr/bin/env bash"));
+
+    // Compile the module when a Python interpreter is available
+    let compiled = std::process::Command::new("python3")
+        .args(["-c", "import ast, sys; ast.parse(sys.stdin.read())"])
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .ok()
+        .map(|mut python| {
+            use std::io::Write;
+            python
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(stdout.as_bytes())
+                .unwrap();
+            python.wait().unwrap()
+        });
+    if let Some(status) = compiled {
+        assert!(status.success(), "generated Python does not parse");
+    }
+}
+
+#[test]
+fn test_convert_max_delay_requires_preserve_timing() {
+    Command::cargo_bin("qorvex")
+        .unwrap()
+        .args(["convert", "--max-delay", "1000"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--preserve-timing"));
+}
+
 #[test]
 fn test_convert_error_session() {
     let fixture = fixture_path("error_session.jsonl");
@@ -97,6 +164,168 @@ fn test_list_devices_runs() {
         .success();
 }
 
+#[test]
+fn test_app_container_kinds_conflict() {
+    Command::cargo_bin("qorvex")
+        .unwrap()
+        .args(["app-container", "com.example.App", "--data", "--groups"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_shutdown_udid_and_all_conflict() {
+    Command::cargo_bin("qorvex")
+        .unwrap()
+        .args(["shutdown", "SOME-UDID", "--all"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+fn exists_result(exists: bool) -> serde_json::Value {
+    serde_json::json!({
+        "type": "ActionResult",
+        "success": true,
+        "message": "Element 'login-button' checked",
+        "screenshot": null,
+        "data": exists.to_string(),
+    })
+}
+
+#[test]
+fn test_exists_exits_zero_when_present() {
+    let server = FakeServer::start("exists-yes", exists_result(true));
+    qorvex(server.home())
+        .args(["exists", "login-button", "-T", "Button"])
+        .assert()
+        .code(0);
+    let request = server.finish();
+    assert_eq!(request["action"]["type"], "ElementExists");
+    assert_eq!(request["action"]["selector"], "login-button");
+    assert_eq!(request["action"]["element_type"], "Button");
+}
+
+#[test]
+fn test_exists_exits_one_when_absent() {
+    let server = FakeServer::start("exists-no", exists_result(false));
+    // Absence is an answer, not an error, so nothing alarming is printed
+    qorvex(server.home())
+        .args(["exists", "login-button"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Error").not());
+    server.finish();
+}
+
+#[test]
+fn test_exists_agent_failure_is_an_error() {
+    let server = FakeServer::start(
+        "exists-err",
+        serde_json::json!({ "type": "Error", "message": "Agent not connected" }),
+    );
+    qorvex(server.home())
+        .args(["exists", "login-button"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Error: Action failed: Agent not connected",
+        ));
+    server.finish();
+}
+
+fn cells_result() -> serde_json::Value {
+    serde_json::json!({
+        "type": "ActionResult",
+        "success": true,
+        "message": "Found 2 elements",
+        "screenshot": null,
+        "data": r#"[{"AXUniqueId":"row-1","type":"Cell"},{"AXUniqueId":"row-2","type":"Cell"}]"#,
+    })
+}
+
+#[test]
+fn test_find_all_prints_count_then_elements() {
+    let server = FakeServer::start("find-all", cells_result());
+    qorvex(server.home())
+        .args(["find-all", "--type", "Cell", "--expect", "2"])
+        .assert()
+        .code(0)
+        .stdout("2\n[Cell] row-1\n[Cell] row-2\n");
+    let request = server.finish();
+    assert_eq!(request["action"]["type"], "FindAll");
+    assert_eq!(request["action"]["selector"], "");
+    assert_eq!(request["action"]["element_type"], "Cell");
+}
+
+#[test]
+fn test_find_all_expect_fails_on_other_count() {
+    let server = FakeServer::start("find-all-expect", cells_result());
+    qorvex(server.home())
+        .args(["find-all", "-T", "Cell", "--expect", "5"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Expected 5 matching elements, found 2",
+        ));
+    server.finish();
+}
+
+#[test]
+fn test_wait_idle_sends_stable_window() {
+    let server = FakeServer::start(
+        "wait-idle",
+        serde_json::json!({
+            "type": "ActionResult",
+            "success": true,
+            "message": "Screen idle after 2 changes",
+            "screenshot": null,
+            "data": r#"{"elapsed_ms":900,"changes":2}"#,
+        }),
+    );
+    qorvex(server.home())
+        .args(["wait-idle", "--stable", "300", "-o", "8000"])
+        .assert()
+        .code(0);
+    let request = server.finish();
+    assert_eq!(
+        request["action"],
+        serde_json::json!({ "type": "WaitForIdle", "timeout_ms": 8000, "stable_ms": 300 })
+    );
+}
+
+#[test]
+fn test_tap_glob_sends_match_mode() {
+    let server = FakeServer::start(
+        "tap-glob",
+        serde_json::json!({
+            "type": "ActionResult",
+            "success": true,
+            "message": "Tapped element 'row-1' (glob match for 'row-*')",
+            "screenshot": null,
+            "data": null,
+        }),
+    );
+    qorvex(server.home())
+        .args(["tap", "row-*", "--glob", "--first", "--no-wait"])
+        .assert()
+        .code(0);
+    let request = server.finish();
+    assert_eq!(request["action"]["match_mode"], "glob");
+    assert_eq!(request["action"]["first"], true);
+}
+
+#[test]
+fn test_first_requires_glob() {
+    Command::cargo_bin("qorvex")
+        .unwrap()
+        .args(["wait-for", "row-*", "--first"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--glob"));
+}
+
 #[test]
 fn test_unknown_subcommand() {
     Command::cargo_bin("qorvex")
@@ -112,6 +341,25 @@ fn test_unknown_subcommand() {
 /// stdout: Broken pipe". With `SIGPIPE` restored to `SIG_DFL` the process is
 /// terminated by the signal and writes nothing alarming to stderr.
 #[cfg(unix)]
+#[test]
+fn test_screenshot_output_rejects_missing_directory() {
+    // Validated before connecting, so no server is needed
+    Command::cargo_bin("qorvex")
+        .unwrap()
+        .args([
+            "-s",
+            "no-such-session",
+            "screenshot",
+            "--output",
+            "/nonexistent-qorvex-dir/screen.png",
+        ])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Output directory does not exist: /nonexistent-qorvex-dir",
+        ));
+}
+
 #[test]
 fn test_broken_pipe_does_not_panic() {
     use std::io::{Read, Write};
@@ -0,0 +1,89 @@
+//! A one-shot fake IPC server for CLI tests.
+//!
+//! The server listens under a private `$HOME`, so a `qorvex` run with that
+//! home and `-s t` talks to it instead of a real qorvex-server.
+
+#![allow(dead_code)]
+
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+
+/// A private home directory with a fake server for session `t`.
+pub struct FakeServer {
+    home: PathBuf,
+    handle: JoinHandle<Value>,
+}
+
+impl FakeServer {
+    /// Answers the first request with `response` (an `IpcResponse` as JSON).
+    pub fn start(name: &str, response: Value) -> Self {
+        let home = private_home(name);
+        let dir = home.join(".qorvex");
+        std::fs::create_dir_all(&dir).unwrap();
+        let listener = UnixListener::bind(dir.join("qorvex_t.sock")).unwrap();
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let mut stream = stream;
+            writeln!(stream, "{}", response).unwrap();
+            serde_json::from_str(&line).unwrap()
+        });
+        Self { home, handle }
+    }
+
+    /// Reads the first request but never answers, like a wedged server.
+    pub fn silent(name: &str) -> Self {
+        let home = private_home(name);
+        let dir = home.join(".qorvex");
+        std::fs::create_dir_all(&dir).unwrap();
+        let listener = UnixListener::bind(dir.join("qorvex_t.sock")).unwrap();
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            // Hold the connection until the client gives up and closes it
+            let mut rest = String::new();
+            let _ = reader.read_line(&mut rest);
+            serde_json::from_str(&line).unwrap()
+        });
+        Self { home, handle }
+    }
+
+    /// The home directory to run `qorvex` with.
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    /// Waits for the request, removes the home directory, and returns the
+    /// request the server received.
+    pub fn finish(self) -> Value {
+        let request = self.handle.join().unwrap();
+        let _ = std::fs::remove_dir_all(&self.home);
+        request
+    }
+}
+
+/// Creates an empty home directory unique to this test process.
+pub fn private_home(name: &str) -> PathBuf {
+    let home = std::env::temp_dir().join(format!("qvx-cli-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&home);
+    std::fs::create_dir_all(&home).unwrap();
+    home
+}
+
+/// `qorvex -s t` with `home` as `$HOME` and no session or token overrides.
+pub fn qorvex(home: &Path) -> assert_cmd::Command {
+    #[allow(deprecated)]
+    let mut cmd = assert_cmd::Command::cargo_bin("qorvex").unwrap();
+    cmd.env("HOME", home)
+        .env_remove("QORVEX_SESSION")
+        .env_remove("QORVEX_IPC_TOKEN")
+        .args(["-s", "t"]);
+    cmd
+}
@@ -0,0 +1,464 @@
+//! `--format json` output: every command prints one `{ok, data, error}`
+//! envelope on stdout.
+//!
+//! Server-backed commands talk to a one-shot fake IPC server listening under a
+//! private `$HOME`, so no simulator or qorvex-server is needed.
+
+mod common;
+
+use common::{private_home, qorvex, FakeServer};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+/// Runs `qorvex -f json -s t <args>` against `server`, returning the exit
+/// code, the parsed stdout, and the request the server received.
+fn run_server(server: FakeServer, args: &[&str]) -> (i32, Value, Value) {
+    let (code, output) = run_json(server.home(), args);
+    (code, output, server.finish())
+}
+
+fn run_json(home: &std::path::Path, args: &[&str]) -> (i32, Value) {
+    let output = qorvex(home)
+        .args(["-f", "json"])
+        .args(args)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let value: Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("stdout is not one JSON value ({e}): {stdout}"));
+    assert_envelope(&value);
+    (output.status.code().unwrap(), value)
+}
+
+/// Checks the envelope shape: exactly `ok`, `data`, and `error`, with `data`
+/// null on failure and `error` null on success.
+fn assert_envelope(value: &Value) {
+    let object = value.as_object().expect("envelope is an object");
+    let mut keys: Vec<_> = object.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, ["data", "error", "ok"], "{value}");
+    if value["ok"].as_bool().expect("ok is a bool") {
+        assert!(value["error"].is_null(), "{value}");
+    } else {
+        assert!(value["data"].is_null(), "{value}");
+        assert!(value["error"].is_string(), "{value}");
+    }
+}
+
+fn action_result(success: bool, message: &str, data: Option<Value>) -> Value {
+    json!({
+        "type": "ActionResult",
+        "success": success,
+        "message": message,
+        "screenshot": null,
+        "data": data.map(|d| d.to_string()),
+    })
+}
+
+#[test]
+fn list_sessions_json() {
+    let home = private_home("list-sessions");
+    let (code, output) = run_json(&home, &["list-sessions"]);
+    assert_eq!(code, 0);
+    assert_eq!(output["data"], json!([]));
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn list_devices_json() {
+    // Whether or not simctl is available, the output is an envelope
+    let home = private_home("list-devices");
+    let (code, output) = run_json(&home, &["list-devices"]);
+    if output["ok"] == json!(true) {
+        assert_eq!(code, 0);
+        assert!(output["data"].is_array());
+    } else {
+        assert_eq!(code, 1);
+        assert!(output["error"]
+            .as_str()
+            .unwrap()
+            .contains("Failed to list devices"));
+    }
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn convert_json() {
+    let home = private_home("convert");
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("basic_session.jsonl");
+    let (code, output) = run_json(&home, &["convert", fixture.to_str().unwrap()]);
+    assert_eq!(code, 0);
+    let script = output["data"]["script"].as_str().unwrap();
+    assert!(script.contains("qorvex tap login-button"));
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn connection_failure_json() {
+    let home = private_home("no-server");
+    let (code, output) = run_json(&home, &["status"]);
+    assert_eq!(code, 2);
+    assert!(output["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("Connection error: Failed to connect to session 't'"));
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn request_timeout_json() {
+    let server = FakeServer::silent("request-timeout");
+    let (code, output, request) = run_server(server, &["--request-timeout", "0.5", "status"]);
+    assert_eq!(code, 2);
+    assert_eq!(request["type"], "GetSnapshot");
+    assert_eq!(
+        output["error"],
+        "Connection error: server not responding after 0.5s"
+    );
+}
+
+#[test]
+fn connect_timeout_json() {
+    // With a token set, connecting waits for the server to accept it
+    let server = FakeServer::silent("connect-timeout");
+    let output = qorvex(server.home())
+        .env("QORVEX_IPC_TOKEN", "secret")
+        .args(["-f", "json", "--connect-timeout", "0.5", "status"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let value: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        value["error"],
+        "Connection error: server not responding after 0.5s"
+    );
+    assert_eq!(server.finish()["type"], "Auth");
+}
+
+#[test]
+fn status_json() {
+    let session_id = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+    let server = FakeServer::start(
+        "status",
+        json!({
+            "type": "Snapshot",
+            "snapshot": {
+                "session_id": session_id,
+                "udid": "SIM-1",
+                "action_count": 3,
+                "last_screenshot": null,
+                "started_at": "2026-01-02T03:04:05Z",
+            },
+        }),
+    );
+    let (code, output, request) = run_server(server, &["status"]);
+    assert_eq!(code, 0);
+    assert_eq!(request["type"], "GetSnapshot");
+    assert_eq!(
+        output["data"],
+        json!({
+            "session_id": session_id,
+            "udid": "SIM-1",
+            "action_count": 3,
+            "started_at": "2026-01-02T03:04:05Z",
+            "has_screenshot": false,
+        })
+    );
+}
+
+#[test]
+fn health_json() {
+    let server = FakeServer::start(
+        "health",
+        json!({
+            "type": "Health",
+            "session_active": true,
+            "driver_connected": false,
+            "agent_target": "com.example.app",
+            "simulator_udid": null,
+        }),
+    );
+    let (code, output, request) = run_server(server, &["health"]);
+    // Reported, but not ready to run actions
+    assert_eq!(code, 1);
+    assert_eq!(request["type"], "Health");
+    assert_eq!(output["data"]["driver_connected"], false);
+    assert_eq!(output["data"]["agent_target"], "com.example.app");
+}
+
+#[test]
+fn log_json() {
+    let entry = json!({
+        "id": "b2c3d4e5-f6a7-8901-bcde-f12345678901",
+        "timestamp": "2026-02-20T10:00:01Z",
+        "action": { "type": "Tap", "selector": "login-button", "by_label": false, "element_type": null },
+        "result": "Success",
+        "screenshot": null,
+        "duration_ms": 45,
+    });
+    let server = FakeServer::start("log", json!({ "type": "Log", "entries": [entry] }));
+    let (code, output, request) = run_server(server, &["log"]);
+    assert_eq!(code, 0);
+    assert_eq!(request["type"], "GetLog");
+    assert_eq!(output["data"][0]["action"]["selector"], "login-button");
+}
+
+#[test]
+fn screen_info_json() {
+    let elements = json!([
+        {
+            "AXUniqueId": "login-button",
+            "AXLabel": "Log In",
+            "type": "Button",
+            "frame": { "x": 10.2, "y": 20.0, "width": 100.0, "height": 44.0 },
+            "children": [],
+        },
+        { "type": "Other", "children": [] },
+    ]);
+    let server = FakeServer::start(
+        "screen-info",
+        action_result(true, "Retrieved screen info", Some(elements)),
+    );
+    let (code, output, _) = run_server(server, &["screen-info"]);
+    assert_eq!(code, 0);
+    // Only actionable elements, in the concise form
+    let data = output["data"].as_array().unwrap();
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0]["id"], "login-button");
+    assert_eq!(data[0]["frame"]["x"], 10);
+}
+
+#[test]
+fn screen_info_unchanged_json() {
+    let server = FakeServer::start(
+        "screen-unchanged",
+        json!({ "type": "NoChange", "hash": "abc123" }),
+    );
+    let (code, output, request) = run_server(server, &["screen-info", "--if-changed", "abc123"]);
+    // Unchanged keeps its exit code but is not a failure
+    assert_eq!(code, 4);
+    assert_eq!(request["action"]["changed_since_hash"], "abc123");
+    assert_eq!(output["ok"], true);
+    assert_eq!(
+        output["data"],
+        json!({ "unchanged": true, "hash": "abc123" })
+    );
+}
+
+#[test]
+fn describe_json() {
+    let elements = json!([
+        {
+            "type": "NavigationBar",
+            "AXLabel": "Settings",
+            "children": [{ "type": "Button", "AXLabel": "Back", "children": [] }],
+        },
+        { "type": "StaticText", "AXLabel": "General", "children": [] },
+    ]);
+    let server = FakeServer::start(
+        "describe",
+        action_result(true, "Retrieved screen info", Some(elements)),
+    );
+    let (code, output, request) = run_server(server, &["describe"]);
+    assert_eq!(code, 0);
+    assert_eq!(request["action"]["type"], "GetScreenInfo");
+    assert_eq!(output["data"]["title"], "Settings");
+    assert_eq!(output["data"]["element_count"], 3);
+    assert_eq!(output["data"]["type_counts"]["Button"], 1);
+    assert_eq!(output["data"]["buttons"], json!(["Back"]));
+}
+
+#[test]
+fn device_info_json() {
+    let info = json!({ "width": 393.0, "height": 852.0, "scale": 3.0, "orientation": "portrait" });
+    let server = FakeServer::start("device-info", action_result(true, "ok", Some(info.clone())));
+    let (code, output, request) = run_server(server, &["device-info"]);
+    assert_eq!(code, 0);
+    assert_eq!(request["type"], "GetDeviceInfo");
+    assert_eq!(output["data"], info);
+}
+
+#[test]
+fn element_at_json() {
+    let element = json!({
+        "AXUniqueId": "buy",
+        "type": "Button",
+        "frame": { "x": 40.0, "y": 240.0, "width": 100.0, "height": 44.0 },
+        "children": [],
+    });
+    let server = FakeServer::start(
+        "element-at",
+        action_result(true, "[Button] buy @(40,240)", Some(element)),
+    );
+    let (code, output, request) = run_server(server, &["element-at", "60", "260"]);
+    assert_eq!(code, 0);
+    assert_eq!(request["type"], "ElementAt");
+    assert_eq!(
+        (request["x"].clone(), request["y"].clone()),
+        (json!(60), json!(260))
+    );
+    assert_eq!(output["data"]["id"], "buy");
+    assert_eq!(output["data"]["frame"]["width"], 100);
+}
+
+#[test]
+fn ping_json() {
+    let server = FakeServer::start(
+        "ping",
+        action_result(
+            true,
+            "Agent latency: avg 2.0ms, min 1.5ms, max 2.5ms (3 pings)",
+            Some(json!({ "samples": 3, "avg_ms": 2.0, "min_ms": 1.5, "max_ms": 2.5 })),
+        ),
+    );
+    let (code, output, request) = run_server(server, &["ping", "--count", "3"]);
+    assert_eq!(code, 0);
+    assert_eq!(request["type"], "PingAgent");
+    assert_eq!(request["count"], 3);
+    assert_eq!(output["data"]["samples"], 3);
+    assert_eq!(output["data"]["avg_ms"], 2.0);
+}
+
+#[test]
+fn action_json() {
+    let server = FakeServer::start(
+        "tap",
+        action_result(
+            true,
+            "Tapped 'login-button'",
+            Some(json!({ "elapsed_ms": 12 })),
+        ),
+    );
+    let (code, output, request) = run_server(server, &["tap", "login-button", "--no-wait"]);
+    assert_eq!(code, 0);
+    assert_eq!(request["action"]["selector"], "login-button");
+    assert_eq!(
+        output["data"],
+        json!({
+            "message": "Tapped 'login-button'",
+            "screenshot": null,
+            "result": { "elapsed_ms": 12 },
+        })
+    );
+}
+
+#[test]
+fn action_failure_json() {
+    let server = FakeServer::start(
+        "tap-fail",
+        action_result(false, "Element 'missing' not found", None),
+    );
+    let (code, output, _) = run_server(server, &["tap", "missing", "--no-wait"]);
+    assert_eq!(code, 1);
+    assert_eq!(output["ok"], false);
+    assert_eq!(
+        output["error"],
+        "Action failed: Element 'missing' not found"
+    );
+}
+
+#[test]
+fn element_not_found_json() {
+    let mut response = action_result(false, "Element with ID 'missing' not found", None);
+    response["element_not_found"] = json!(true);
+    let server = FakeServer::start("tap-not-found", response);
+    let (code, output, _) = run_server(server, &["tap", "missing", "--no-wait"]);
+    // A distinct exit code from other action failures
+    assert_eq!(code, 5);
+    assert_eq!(
+        output["error"],
+        "Action failed: Element with ID 'missing' not found"
+    );
+}
+
+#[test]
+fn exists_json() {
+    let server = FakeServer::start(
+        "exists",
+        action_result(
+            true,
+            "Element 'login-button' does not exist",
+            Some(json!(false)),
+        ),
+    );
+    let (code, output, _) = run_server(server, &["exists", "login-button"]);
+    // Still a successful answer, but the exit status says "no"
+    assert_eq!(code, 1);
+    assert_eq!(output["ok"], true);
+    assert_eq!(output["data"], json!({ "exists": false }));
+}
+
+#[test]
+fn tap_if_exists_json() {
+    let server = FakeServer::start(
+        "tap-if-exists",
+        action_result(
+            true,
+            "No element with label 'Not Now' on screen; nothing tapped",
+            Some(json!({ "tapped": false })),
+        ),
+    );
+    let (code, output, request) = run_server(server, &["tap-if-exists", "Not Now", "-l"]);
+    // Absence is still a success
+    assert_eq!(code, 0);
+    assert_eq!(request["action"]["type"], "TapIfExists");
+    assert_eq!(request["action"]["by_label"], true);
+    assert_eq!(output["data"]["result"], json!({ "tapped": false }));
+}
+
+#[test]
+fn find_all_json() {
+    let elements = json!([
+        { "AXUniqueId": "row-1", "type": "Cell", "children": [] },
+        { "AXUniqueId": "row-2", "type": "Cell", "children": [] },
+    ]);
+    let server = FakeServer::start(
+        "find-all",
+        action_result(true, "Found 2 elements", Some(elements)),
+    );
+    let (code, output, _) = run_server(server, &["find-all", "-T", "Cell"]);
+    assert_eq!(code, 0);
+    assert_eq!(output["data"]["count"], 2);
+    assert_eq!(output["data"]["elements"][1]["id"], "row-2");
+}
+
+#[test]
+fn command_json() {
+    let server = FakeServer::start(
+        "cancel",
+        json!({ "type": "CommandResult", "success": true, "message": "Cancelled" }),
+    );
+    let (code, output, request) = run_server(server, &["cancel"]);
+    assert_eq!(code, 0);
+    assert_eq!(request["type"], "Cancel");
+    assert_eq!(output["data"], json!({ "message": "Cancelled" }));
+}
+
+#[test]
+fn no_screenshot_flag_json() {
+    let server = FakeServer::start("no-screenshot", action_result(true, "Tapped", None));
+    let (code, _, request) = run_server(server, &["tap-location", "5", "6", "--no-screenshot"]);
+    assert_eq!(code, 0);
+    assert_eq!(request["type"], "Execute");
+    assert_eq!(request["capture_screenshot"], false);
+
+    let server = FakeServer::start("follow-session", action_result(true, "Tapped", None));
+    let (_, _, request) = run_server(server, &["tap-location", "5", "6"]);
+    assert!(request.get("capture_screenshot").is_none());
+}
+
+#[test]
+fn set_screenshots_json() {
+    let server = FakeServer::start(
+        "set-screenshots",
+        json!({ "type": "CommandResult", "success": true, "message": "Screenshot capture off" }),
+    );
+    let (code, output, request) = run_server(server, &["set-screenshots", "off"]);
+    assert_eq!(code, 0);
+    assert_eq!(request["type"], "SetCaptureScreenshots");
+    assert_eq!(request["enabled"], false);
+    assert_eq!(output["data"]["message"], "Screenshot capture off");
+}
@@ -7,16 +7,17 @@
 //!
 //! Actions fall into several categories:
 //!
-//! - **UI Interaction**: [`ActionType::Tap`], [`ActionType::TapLocation`], [`ActionType::Swipe`], [`ActionType::LongPress`], [`ActionType::SendKeys`]
-//! - **Information Retrieval**: [`ActionType::GetScreenshot`], [`ActionType::GetScreenInfo`], [`ActionType::GetValue`]
-//! - **Waiting**: [`ActionType::WaitFor`]
+//! - **UI Interaction**: [`ActionType::Tap`], [`ActionType::TapIfExists`], [`ActionType::TapLocation`], [`ActionType::TapElementOffset`], [`ActionType::MultiTap`], [`ActionType::Swipe`], [`ActionType::SwipeCoords`], [`ActionType::SwipeElement`], [`ActionType::LongPress`], [`ActionType::LongPressElement`], [`ActionType::Drag`], [`ActionType::SendKeys`], [`ActionType::SendKey`], [`ActionType::PasteText`], [`ActionType::ClearText`]
+//! - **Information Retrieval**: [`ActionType::GetScreenshot`], [`ActionType::GetElementScreenshot`], [`ActionType::GetScreenInfo`], [`ActionType::GetValue`], [`ActionType::GetAttribute`]
+//! - **Waiting**: [`ActionType::Wait`], [`ActionType::WaitFor`], [`ActionType::WaitForNot`], [`ActionType::WaitForAny`], [`ActionType::WaitForAll`], [`ActionType::WaitForIdle`]
+//! - **Device Settings**: [`ActionType::SetAppearance`], [`ActionType::SetOrientation`], [`ActionType::SetLocale`]
 //! - **Session Management**: [`ActionType::StartSession`], [`ActionType::EndSession`], [`ActionType::Quit`]
-//! - **Logging**: [`ActionType::LogComment`]
+//! - **Logging**: [`ActionType::LogComment`], [`ActionType::Assertion`]
 //!
 //! # Example
 //!
 //! ```
-//! use qorvex_core::action::{ActionType, ActionResult, ActionLog};
+//! use qorvex_core::action::{ActionType, ActionResult, ActionLog, MatchMode};
 //!
 //! // Create an action - tap by ID
 //! let action = ActionType::Tap {
@@ -24,6 +25,9 @@
 //!     by_label: false,
 //!     element_type: None,
 //!     timeout_ms: None,
+//!     require_enabled: false,
+//!     match_mode: MatchMode::Exact,
+//!     first: false,
 //! };
 //!
 //! // Create a log entry
@@ -36,10 +40,61 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::driver::{ElementFilter, Orientation};
+use crate::element::ElementAttribute;
+use crate::protocol::SpecialKey;
+use crate::simctl::Appearance;
+
 fn default_true() -> bool {
     true
 }
 
+/// One element selector of a multi-selector action such as
+/// [`ActionType::WaitForAny`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelectorSpec {
+    /// The selector value (accessibility ID or label).
+    pub selector: String,
+    /// If true, selector is an accessibility label; if false, it's an ID.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub by_label: bool,
+    /// Optional element type filter (e.g., "Button", "TextField").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element_type: Option<String>,
+}
+
+impl std::fmt::Display for SelectorSpec {
+    /// Formats the selector the way [`ActionType::display_target`] does.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.by_label {
+            write!(f, "label:'{}'", self.selector)
+        } else {
+            write!(f, "{}", self.selector)
+        }
+    }
+}
+
+/// How an action's selector is matched against on-screen elements.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    /// The backend matches the selector itself (exact, or a glob where the
+    /// backend supports one).
+    #[default]
+    Exact,
+    /// The selector is a glob (`*` and `?`) matched by the executor against
+    /// every element; it must match exactly one element unless the action
+    /// asks for the first match.
+    Glob,
+}
+
+impl MatchMode {
+    /// Returns true for [`MatchMode::Exact`], the serialized default.
+    pub fn is_exact(&self) -> bool {
+        *self == MatchMode::Exact
+    }
+}
+
 /// The result of executing an action.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ActionResult {
@@ -69,6 +124,17 @@ pub enum ActionType {
         /// until this many milliseconds have elapsed. If `None`, attempt once.
         #[serde(default)]
         timeout_ms: Option<u64>,
+        /// If true, fail without tapping when the element reports itself
+        /// disabled (`enabled: Some(false)`).
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        require_enabled: bool,
+        /// How `selector` is matched against elements; see [`MatchMode`].
+        #[serde(default, skip_serializing_if = "MatchMode::is_exact")]
+        match_mode: MatchMode,
+        /// With [`MatchMode::Glob`], act on the first of several matches
+        /// instead of failing.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        first: bool,
     },
 
     /// Tap at specific screen coordinates.
@@ -79,12 +145,58 @@ pub enum ActionType {
         y: i32,
     },
 
+    /// Tap at specific screen coordinates with several fingers at once.
+    MultiTap {
+        /// Number of fingers, 1 to 5.
+        finger_count: u8,
+        /// The x-coordinate in screen points.
+        x: i32,
+        /// The y-coordinate in screen points.
+        y: i32,
+    },
+
+    /// Tap a point within an element's frame, given as fractions of its size.
+    TapElementOffset {
+        /// The accessibility identifier or label of the element.
+        selector: String,
+        /// If true, `selector` is an accessibility label; if false, an ID.
+        by_label: bool,
+        /// How far across the frame to tap, from 0.0 (left) to 1.0 (right).
+        dx_fraction: f64,
+        /// How far down the frame to tap, from 0.0 (top) to 1.0 (bottom).
+        dy_fraction: f64,
+    },
+
     /// Swipe the screen in a direction.
     Swipe {
         /// Direction to swipe: "up", "down", "left", or "right".
         direction: String,
     },
 
+    /// Swipe between two explicit screen coordinates.
+    SwipeCoords {
+        /// The x-coordinate of the start point, in screen points.
+        start_x: i32,
+        /// The y-coordinate of the start point, in screen points.
+        start_y: i32,
+        /// The x-coordinate of the end point, in screen points.
+        end_x: i32,
+        /// The y-coordinate of the end point, in screen points.
+        end_y: i32,
+        /// How long the swipe takes in seconds; 0.3 if `None`.
+        duration: Option<f64>,
+    },
+
+    /// Swipe in a direction within one element's frame, e.g. a carousel.
+    SwipeElement {
+        /// The accessibility identifier or label of the element.
+        selector: String,
+        /// If true, `selector` is an accessibility label; if false, an ID.
+        by_label: bool,
+        /// Direction to swipe: "up", "down", "left", or "right".
+        direction: String,
+    },
+
     /// Long press at specific screen coordinates.
     LongPress {
         /// The x-coordinate in screen points.
@@ -95,19 +207,96 @@ pub enum ActionType {
         duration: f64,
     },
 
+    /// Long press the center of an element, e.g. to open a context menu.
+    LongPressElement {
+        /// The accessibility identifier or label of the element.
+        selector: String,
+        /// If true, `selector` is an accessibility label; if false, an ID.
+        by_label: bool,
+        /// How long to press in seconds; must be positive.
+        duration: f64,
+    },
+
+    /// Press one element, hold, and drag it onto another.
+    Drag {
+        /// Selector of the element to pick up.
+        from: String,
+        /// Selector of the element to drop onto.
+        to: String,
+        /// If true, both selectors are accessibility labels; if false, IDs.
+        by_label: bool,
+        /// Seconds to hold before moving.
+        hold_duration: f64,
+        /// Seconds the move takes.
+        move_duration: f64,
+    },
+
+    /// Pause for a fixed time without touching the UI.
+    Wait {
+        /// How long to pause, in milliseconds.
+        ms: u64,
+    },
+
     /// Log a comment (for documentation purposes).
     LogComment {
         /// The comment text to log.
         message: String,
     },
 
+    /// Record the outcome of a check made by a script.
+    ///
+    /// Nothing is sent to the device: the script decides whether the check
+    /// passed, and the entry fails when it did not, so logs and viewers show
+    /// assertions apart from the actions around them.
+    Assertion {
+        /// What was checked, e.g. "welcome banner is shown".
+        description: String,
+        /// Whether the check held.
+        passed: bool,
+    },
+
     /// Capture a screenshot of the current screen.
     ///
     /// Returns base64-encoded PNG data.
     GetScreenshot,
 
+    /// Capture a screenshot cropped to one element's frame.
+    ///
+    /// Returns base64-encoded PNG data.
+    GetElementScreenshot {
+        /// The selector value (accessibility ID or label).
+        selector: String,
+        /// If true, selector is an accessibility label; if false, it's an ID.
+        by_label: bool,
+    },
+
+    /// Read one attribute of an element by ID or label.
+    ///
+    /// The result's data is the attribute as JSON, `null` if the element
+    /// does not have it (see [`UIElement::attribute`](crate::element::UIElement::attribute)).
+    GetAttribute {
+        /// The selector value (accessibility ID or label).
+        selector: String,
+        /// If true, selector is an accessibility label; if false, it's an ID.
+        by_label: bool,
+        /// Optional element type filter (e.g., "TextField").
+        #[serde(default)]
+        element_type: Option<String>,
+        /// The attribute to read.
+        attribute: ElementAttribute,
+    },
+
     /// Get accessibility information for all elements on screen.
-    GetScreenInfo,
+    GetScreenInfo {
+        /// Optional type/visibility/depth filter; the default keeps everything.
+        #[serde(flatten)]
+        filter: ElementFilter,
+        /// If set, the server answers with a "no change" response instead of
+        /// the element list when the screen still hashes to this value (see
+        /// [`hash_elements`](crate::element::hash_elements)).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        changed_since_hash: Option<String>,
+    },
 
     /// Get the current value of an element by ID or label.
     GetValue {
@@ -121,6 +310,53 @@ pub enum ActionType {
         /// until this many milliseconds have elapsed. If `None`, attempt once.
         #[serde(default)]
         timeout_ms: Option<u64>,
+        /// How `selector` is matched against elements; see [`MatchMode`].
+        #[serde(default, skip_serializing_if = "MatchMode::is_exact")]
+        match_mode: MatchMode,
+        /// With [`MatchMode::Glob`], act on the first of several matches
+        /// instead of failing.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        first: bool,
+    },
+
+    /// Check whether an element is on screen, without waiting for it.
+    ///
+    /// Succeeds either way; the result's data is `true` or `false`.
+    ElementExists {
+        /// The selector value (accessibility ID or label).
+        selector: String,
+        /// If true, selector is an accessibility label; if false, it's an ID.
+        by_label: bool,
+        /// Optional element type filter (e.g., "Button", "TextField").
+        element_type: Option<String>,
+    },
+
+    /// Tap an element if it is on screen, without waiting for it.
+    ///
+    /// Succeeds when the element is absent, too; the result's data is
+    /// `{"tapped": true}` or `{"tapped": false}`. For optional dismissals such
+    /// as a "Not Now" button.
+    TapIfExists {
+        /// The selector value (accessibility ID or label).
+        selector: String,
+        /// If true, selector is an accessibility label; if false, it's an ID.
+        by_label: bool,
+        /// Optional element type filter (e.g., "Button", "TextField").
+        element_type: Option<String>,
+    },
+
+    /// List every element matching a selector.
+    ///
+    /// Succeeds even when nothing matches; the result's data is a JSON array
+    /// of the matching elements.
+    FindAll {
+        /// The selector value (accessibility ID or label). Empty matches any
+        /// element, so `element_type` alone can select.
+        selector: String,
+        /// If true, selector is an accessibility label; if false, it's an ID.
+        by_label: bool,
+        /// Optional element type filter (e.g., "Cell", "Button").
+        element_type: Option<String>,
     },
 
     /// Send keyboard input.
@@ -129,6 +365,32 @@ pub enum ActionType {
         text: String,
     },
 
+    /// Press a special key (Return, Tab, an arrow, ...) on the keyboard.
+    ///
+    /// [`SendKeys`](ActionType::SendKeys) can also press these inline with
+    /// `{name}` tokens, e.g. `"query{return}"`.
+    SendKey {
+        /// The key to press.
+        key: SpecialKey,
+    },
+
+    /// Paste text into the focused element via the simulator pasteboard.
+    ///
+    /// Faster than [`SendKeys`](ActionType::SendKeys) for long strings and
+    /// not subject to autocorrect.
+    PasteText {
+        /// The text to paste.
+        text: String,
+    },
+
+    /// Delete all text in an input field.
+    ClearText {
+        /// The selector value (accessibility ID or label).
+        selector: String,
+        /// If true, selector is an accessibility label; if false, it's an ID.
+        by_label: bool,
+    },
+
     /// Wait for an element to appear on screen by ID or label.
     WaitFor {
         /// The selector value (accessibility ID or label).
@@ -144,6 +406,17 @@ pub enum ActionType {
         /// skips frame-stability tracking).
         #[serde(default = "default_true")]
         require_stable: bool,
+        /// Time between polls in milliseconds; `None` uses the executor's
+        /// default (see [`ActionExecutor::with_poll_interval`](crate::executor::ActionExecutor::with_poll_interval)).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        poll_interval_ms: Option<u64>,
+        /// How `selector` is matched against elements; see [`MatchMode`].
+        #[serde(default, skip_serializing_if = "MatchMode::is_exact")]
+        match_mode: MatchMode,
+        /// With [`MatchMode::Glob`], act on the first of several matches
+        /// instead of failing.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        first: bool,
     },
 
     /// Wait for an element to disappear from screen by ID or label.
@@ -156,6 +429,49 @@ pub enum ActionType {
         element_type: Option<String>,
         /// Maximum time to wait in milliseconds.
         timeout_ms: u64,
+        /// Time between polls in milliseconds; `None` uses the executor's
+        /// default (see [`ActionExecutor::with_poll_interval`](crate::executor::ActionExecutor::with_poll_interval)).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        poll_interval_ms: Option<u64>,
+    },
+
+    /// Wait until any one of several elements is on screen.
+    ///
+    /// Each poll looks the selectors up in order; the first present one wins
+    /// and is reported in the result data
+    /// ([`WaitForAnyData`](crate::result_data::WaitForAnyData)).
+    WaitForAny {
+        /// The elements to wait for, in order of preference.
+        selectors: Vec<SelectorSpec>,
+        /// Maximum time to wait in milliseconds.
+        timeout_ms: u64,
+        /// Time between polls in milliseconds; `None` uses the executor's
+        /// default (see [`ActionExecutor::with_poll_interval`](crate::executor::ActionExecutor::with_poll_interval)).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        poll_interval_ms: Option<u64>,
+    },
+
+    /// Wait until every one of several elements is on screen at once.
+    WaitForAll {
+        /// The elements to wait for.
+        selectors: Vec<SelectorSpec>,
+        /// Maximum time to wait in milliseconds.
+        timeout_ms: u64,
+        /// Time between polls in milliseconds; `None` uses the executor's
+        /// default (see [`ActionExecutor::with_poll_interval`](crate::executor::ActionExecutor::with_poll_interval)).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        poll_interval_ms: Option<u64>,
+    },
+
+    /// Wait until the screen stops changing.
+    ///
+    /// Polls the element tree and succeeds once its hash has been unchanged
+    /// for `stable_ms`.
+    WaitForIdle {
+        /// Maximum time to wait in milliseconds.
+        timeout_ms: u64,
+        /// How long the tree must stay unchanged, in milliseconds.
+        stable_ms: u64,
     },
 
     /// Start a new automation session.
@@ -179,6 +495,31 @@ pub enum ActionType {
     /// Get metadata about the currently targeted application.
     GetTargetInfo,
 
+    /// Switch the simulator between light and dark mode.
+    SetAppearance {
+        /// The appearance to apply.
+        appearance: Appearance,
+    },
+
+    /// Set the simulator's language and region, rebooting it.
+    ///
+    /// Apps running before the reboot must be relaunched.
+    SetLocale {
+        /// BCP-47 language subtag, optionally with a script (e.g. "fr", "zh-Hans").
+        language: String,
+        /// BCP-47 region subtag (e.g. "FR").
+        region: String,
+    },
+
+    /// Rotate the device.
+    ///
+    /// Coordinate actions that follow use the new orientation's coordinate
+    /// space.
+    SetOrientation {
+        /// The orientation to rotate to.
+        orientation: Orientation,
+    },
+
     /// Quit the REPL entirely.
     Quit,
 }
@@ -190,19 +531,41 @@ impl ActionType {
         match self {
             ActionType::Tap { .. } => "tap",
             ActionType::TapLocation { .. } => "tap_location",
+            ActionType::TapElementOffset { .. } => "tap_element_offset",
+            ActionType::MultiTap { .. } => "multi_tap",
             ActionType::Swipe { .. } => "swipe",
+            ActionType::SwipeCoords { .. } => "swipe_coords",
+            ActionType::SwipeElement { .. } => "swipe_element",
             ActionType::LongPress { .. } => "long_press",
+            ActionType::LongPressElement { .. } => "long_press_element",
+            ActionType::Drag { .. } => "drag",
+            ActionType::Wait { .. } => "wait",
             ActionType::LogComment { .. } => "log_comment",
+            ActionType::Assertion { .. } => "assertion",
             ActionType::GetScreenshot => "get_screenshot",
-            ActionType::GetScreenInfo => "get_screen_info",
+            ActionType::GetElementScreenshot { .. } => "get_element_screenshot",
+            ActionType::GetScreenInfo { .. } => "get_screen_info",
             ActionType::GetValue { .. } => "get_value",
+            ActionType::GetAttribute { .. } => "get_attribute",
+            ActionType::ElementExists { .. } => "element_exists",
+            ActionType::TapIfExists { .. } => "tap_if_exists",
+            ActionType::FindAll { .. } => "find_all",
             ActionType::SendKeys { .. } => "send_keys",
+            ActionType::SendKey { .. } => "send_key",
+            ActionType::PasteText { .. } => "paste_text",
+            ActionType::ClearText { .. } => "clear_text",
             ActionType::WaitFor { .. } => "wait_for",
             ActionType::WaitForNot { .. } => "wait_for_not",
+            ActionType::WaitForAny { .. } => "wait_for_any",
+            ActionType::WaitForAll { .. } => "wait_for_all",
+            ActionType::WaitForIdle { .. } => "wait_for_idle",
             ActionType::SetTarget { .. } => "set_target",
             ActionType::StartTarget => "start_target",
             ActionType::StopTarget => "stop_target",
             ActionType::GetTargetInfo => "get_target_info",
+            ActionType::SetAppearance { .. } => "set_appearance",
+            ActionType::SetOrientation { .. } => "set_orientation",
+            ActionType::SetLocale { .. } => "set_locale",
             ActionType::StartSession => "start_session",
             ActionType::EndSession => "end_session",
             ActionType::Quit => "quit",
@@ -212,20 +575,41 @@ impl ActionType {
     /// Returns a human-friendly display name for CLI output.
     pub fn display_name(&self) -> &'static str {
         match self {
-            ActionType::Tap { .. } | ActionType::TapLocation { .. } => "Tap",
+            ActionType::Tap { .. }
+            | ActionType::TapLocation { .. }
+            | ActionType::TapElementOffset { .. }
+            | ActionType::MultiTap { .. } => "Tap",
             ActionType::Swipe { .. } => "Swipe",
-            ActionType::LongPress { .. } => "LongPress",
+            ActionType::SwipeCoords { .. } => "Swipe",
+            ActionType::SwipeElement { .. } => "SwipeElement",
+            ActionType::LongPress { .. } | ActionType::LongPressElement { .. } => "LongPress",
+            ActionType::Drag { .. } => "Drag",
+            ActionType::Wait { .. } => "Wait",
             ActionType::LogComment { .. } => "Comment",
-            ActionType::GetScreenshot => "Screenshot",
-            ActionType::GetScreenInfo => "ScreenInfo",
+            ActionType::Assertion { .. } => "Assert",
+            ActionType::GetScreenshot | ActionType::GetElementScreenshot { .. } => "Screenshot",
+            ActionType::GetScreenInfo { .. } => "ScreenInfo",
             ActionType::GetValue { .. } => "GetValue",
+            ActionType::GetAttribute { .. } => "GetAttribute",
+            ActionType::ElementExists { .. } => "Exists",
+            ActionType::TapIfExists { .. } => "TapIfExists",
+            ActionType::FindAll { .. } => "FindAll",
             ActionType::SendKeys { .. } => "Type",
+            ActionType::SendKey { .. } => "Key",
+            ActionType::PasteText { .. } => "Paste",
+            ActionType::ClearText { .. } => "Clear",
             ActionType::WaitFor { .. } => "Find",
             ActionType::WaitForNot { .. } => "Gone",
+            ActionType::WaitForAny { .. } => "FindAny",
+            ActionType::WaitForAll { .. } => "FindEach",
+            ActionType::WaitForIdle { .. } => "Idle",
             ActionType::SetTarget { .. } => "Target",
             ActionType::StartTarget => "StartTarget",
             ActionType::StopTarget => "StopTarget",
             ActionType::GetTargetInfo => "TargetInfo",
+            ActionType::SetAppearance { .. } => "Appearance",
+            ActionType::SetOrientation { .. } => "Orientation",
+            ActionType::SetLocale { .. } => "Locale",
             ActionType::StartSession => "Start",
             ActionType::EndSession => "End",
             ActionType::Quit => "Quit",
@@ -246,31 +630,151 @@ impl ActionType {
             }
             | ActionType::GetValue {
                 selector, by_label, ..
-            } => {
+            }
+            | ActionType::ElementExists {
+                selector, by_label, ..
+            }
+            | ActionType::TapIfExists {
+                selector, by_label, ..
+            }
+            | ActionType::GetElementScreenshot { selector, by_label }
+            | ActionType::ClearText { selector, by_label } => {
                 if *by_label {
                     format!("label:'{}'", selector)
                 } else {
                     selector.clone()
                 }
             }
+            ActionType::FindAll {
+                selector,
+                by_label,
+                element_type,
+            } => {
+                if selector.is_empty() {
+                    element_type.clone().unwrap_or_else(|| "*".to_string())
+                } else if *by_label {
+                    format!("label:'{}'", selector)
+                } else {
+                    selector.clone()
+                }
+            }
             ActionType::TapLocation { x, y } => format!("({},{})", x, y),
+            ActionType::TapElementOffset {
+                selector,
+                by_label,
+                dx_fraction,
+                dy_fraction,
+            } => {
+                if *by_label {
+                    format!("label:'{}' @{},{}", selector, dx_fraction, dy_fraction)
+                } else {
+                    format!("{} @{},{}", selector, dx_fraction, dy_fraction)
+                }
+            }
+            ActionType::MultiTap { finger_count, x, y } => {
+                format!("({},{}) {} fingers", x, y, finger_count)
+            }
             ActionType::Swipe { direction } => direction.clone(),
+            ActionType::SwipeCoords {
+                start_x,
+                start_y,
+                end_x,
+                end_y,
+                ..
+            } => format!("({},{}) -> ({},{})", start_x, start_y, end_x, end_y),
+            ActionType::SwipeElement {
+                selector,
+                by_label,
+                direction,
+            } => {
+                if *by_label {
+                    format!("label:'{}' {}", selector, direction)
+                } else {
+                    format!("{} {}", selector, direction)
+                }
+            }
             ActionType::LongPress { x, y, duration } => format!("({},{}) {:.1}s", x, y, duration),
-            ActionType::SendKeys { text } => {
-                if text.len() > 20 {
-                    format!("'{}..'", &text[..18])
+            ActionType::LongPressElement {
+                selector,
+                by_label,
+                duration,
+            } => {
+                if *by_label {
+                    format!("label:'{}' {:.1}s", selector, duration)
+                } else {
+                    format!("{} {:.1}s", selector, duration)
+                }
+            }
+            ActionType::Drag {
+                from, to, by_label, ..
+            } => {
+                if *by_label {
+                    format!("label:'{}' -> label:'{}'", from, to)
+                } else {
+                    format!("{} -> {}", from, to)
+                }
+            }
+            ActionType::SendKeys { text } | ActionType::PasteText { text } => {
+                // Truncate on characters; byte offsets can split emoji
+                if text.chars().count() > 20 {
+                    format!("'{}..'", text.chars().take(18).collect::<String>())
                 } else {
                     format!("'{}'", text)
                 }
             }
+            ActionType::WaitForIdle { stable_ms, .. } => format!("{}ms stable", stable_ms),
+            ActionType::WaitForAny { selectors, .. } => join_selectors(selectors, " | "),
+            ActionType::WaitForAll { selectors, .. } => join_selectors(selectors, " & "),
+            ActionType::Wait { ms } => format!("{}ms", ms),
             ActionType::LogComment { message } => message.clone(),
+            ActionType::Assertion { description, .. } => description.clone(),
             ActionType::SetTarget { bundle_id } => bundle_id.clone(),
+            ActionType::SetAppearance { appearance } => appearance.to_string(),
+            ActionType::SetOrientation { orientation } => orientation.to_string(),
+            ActionType::SetLocale { language, region } => format!("{language}-{region}"),
+            ActionType::SendKey { key } => key.to_string(),
+            ActionType::GetAttribute {
+                selector,
+                by_label,
+                attribute,
+                ..
+            } => {
+                if *by_label {
+                    format!("label:'{}' {}", selector, attribute)
+                } else {
+                    format!("{} {}", selector, attribute)
+                }
+            }
             ActionType::StartTarget | ActionType::StopTarget | ActionType::GetTargetInfo => {
                 String::new()
             }
             _ => String::new(),
         }
     }
+
+    /// For an [`ActionType::Assertion`], a check mark or cross followed by
+    /// what was checked, e.g. `✓ welcome banner is shown`.
+    pub fn assertion_summary(&self) -> Option<String> {
+        match self {
+            ActionType::Assertion {
+                description,
+                passed,
+            } => {
+                let mark = if *passed { '✓' } else { '✗' };
+                Some(format!("{} {}", mark, description))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Formats selectors for display, separated by `separator`.
+pub(crate) fn join_selectors(selectors: &[SelectorSpec], separator: &str) -> String {
+    selectors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
 }
 
 /// A logged action with metadata.
@@ -296,6 +800,11 @@ pub struct ActionLog {
     /// Wrapped in `Arc` for efficient cloning when broadcasting to multiple watchers.
     pub screenshot: Option<Arc<String>>,
 
+    /// Path of the PNG file the session saved the screenshot to, next to its
+    /// persistent log. The log file carries this instead of the image data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot_path: Option<String>,
+
     /// How long the action took in milliseconds (e.g., for `WaitFor`).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration_ms: Option<u64>,
@@ -311,6 +820,10 @@ pub struct ActionLog {
     /// Optional free-text tag for log filtering/analysis.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tag: Option<String>,
+
+    /// The value a successful `GetValue` read, if the element had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
 }
 
 impl ActionLog {
@@ -340,10 +853,12 @@ impl ActionLog {
             action,
             result,
             screenshot,
+            screenshot_path: None,
             duration_ms,
             wait_ms: None,
             tap_ms: None,
             tag,
+            value: None,
         }
     }
 }
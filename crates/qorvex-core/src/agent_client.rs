@@ -32,6 +32,7 @@ use socket2::TcpKeepalive;
 use thiserror::Error;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::TcpStream;
+use tokio::sync::mpsc;
 use tokio::time::timeout;
 
 use tracing::{debug, debug_span, trace, warn, Instrument};
@@ -47,12 +48,59 @@ use crate::protocol::{
 /// Timeout for establishing a TCP connection.
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 
-/// Timeout for reading a response frame from the agent.
-const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// Default timeout for reading a response frame from the agent.
+///
+/// Override per client with [`AgentClient::set_read_timeout`].
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Timeout for writing a request frame to the agent.
 const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// Environment variable that turns on [`AgentClient::set_protocol_trace`] for
+/// every new client.
+pub const PROTOCOL_TRACE_ENV: &str = "QORVEX_PROTOCOL_TRACE";
+
+/// Longest request or response description a protocol trace logs in full.
+const TRACE_MAX_CHARS: usize = 200;
+
+/// Frames buffered between a screenshot stream and its consumer before the
+/// stream stops reading from the agent.
+const STREAM_BUFFER_FRAMES: usize = 4;
+
+/// Reads [`PROTOCOL_TRACE_ENV`]: `1`, `true` or `yes` enable protocol tracing.
+pub fn protocol_trace_from_env() -> bool {
+    std::env::var(PROTOCOL_TRACE_ENV)
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Describes `request` for a protocol trace.
+fn describe_request(request: &Request) -> String {
+    match request {
+        Request::Batch { requests } => format!("Batch {{ {} requests }}", requests.len()),
+        other => truncate_for_trace(format!("{other:?}")),
+    }
+}
+
+/// Describes `response` for a protocol trace, eliding image bytes.
+fn describe_response(response: &Response) -> String {
+    match response {
+        Response::Screenshot { data } => format!("Screenshot {{ <{} bytes> }}", data.len()),
+        Response::Batch { responses } => format!("Batch {{ {} responses }}", responses.len()),
+        other => truncate_for_trace(format!("{other:?}")),
+    }
+}
+
+/// Cuts `text` to [`TRACE_MAX_CHARS`] characters, noting how many were dropped.
+fn truncate_for_trace(mut text: String) -> String {
+    if let Some((end, _)) = text.char_indices().nth(TRACE_MAX_CHARS) {
+        let dropped = text[end..].chars().count();
+        text.truncate(end);
+        text.push_str(&format!("… ({dropped} more chars)"));
+    }
+    text
+}
+
 // ---------------------------------------------------------------------------
 // AgentStream trait
 // ---------------------------------------------------------------------------
@@ -111,6 +159,8 @@ pub enum AgentClientError {
 pub struct AgentClient {
     stream: Option<Box<dyn AgentStream>>,
     addr: Option<SocketAddr>,
+    read_timeout: Duration,
+    protocol_trace: bool,
 }
 
 impl AgentClient {
@@ -121,6 +171,8 @@ impl AgentClient {
         Self {
             stream: None,
             addr: Some(addr),
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            protocol_trace: protocol_trace_from_env(),
         }
     }
 
@@ -131,9 +183,33 @@ impl AgentClient {
         Self {
             stream: Some(Box::new(stream)),
             addr: None,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            protocol_trace: protocol_trace_from_env(),
         }
     }
 
+    /// Set how long [`send`](Self::send) waits for a response before failing
+    /// with [`AgentClientError::Timeout`].
+    ///
+    /// Defaults to [`DEFAULT_READ_TIMEOUT`].
+    pub fn set_read_timeout(&mut self, read_timeout: Duration) {
+        self.read_timeout = read_timeout;
+    }
+
+    /// The read timeout applied by [`send`](Self::send).
+    pub fn read_timeout(&self) -> Duration {
+        self.read_timeout
+    }
+
+    /// Log every request sent and response received at debug level, with
+    /// frame sizes. Screenshot bytes are elided and long descriptions cut
+    /// short.
+    ///
+    /// Defaults to [`protocol_trace_from_env`].
+    pub fn set_protocol_trace(&mut self, enabled: bool) {
+        self.protocol_trace = enabled;
+    }
+
     /// Establish a TCP connection to the agent with a 5-second timeout.
     ///
     /// Only valid for clients created with [`new`](Self::new). Clients created
@@ -180,15 +256,19 @@ impl AgentClient {
     /// If the agent returns a [`Response::Error`], this method converts it into
     /// an [`AgentClientError::AgentError`] so callers can treat all failures
     /// uniformly via the error type.
+    ///
+    /// Fails with [`AgentClientError::Timeout`] if no response arrives within
+    /// the client's [read timeout](Self::set_read_timeout); the connection is
+    /// dropped so a late reply cannot be mistaken for the next response.
     pub async fn send(&mut self, request: &Request) -> Result<Response, AgentClientError> {
-        self.send_with_timeout(request, READ_TIMEOUT).await
+        self.send_with_timeout(request, self.read_timeout).await
     }
 
     /// Like [`send`](Self::send), but with a custom read timeout.
     ///
     /// Use this when the agent is expected to retry internally (e.g., when
     /// `timeout_ms` is set on a tap/get-value request) and may take longer
-    /// than the default [`READ_TIMEOUT`] to respond.
+    /// than the client's read timeout to respond.
     pub async fn send_with_timeout(
         &mut self,
         request: &Request,
@@ -198,10 +278,24 @@ impl AgentClient {
         let span = debug_span!("agent_send", opcode);
         async {
             let frame = encode_request(request);
+            if self.protocol_trace {
+                debug!(
+                    bytes = frame.len(),
+                    request = %describe_request(request),
+                    "sent frame"
+                );
+            }
             self.write_frame(&frame).await?;
 
             let payload = self.read_frame(read_timeout).await?;
             let response = decode_response(&payload)?;
+            if self.protocol_trace {
+                debug!(
+                    bytes = payload.len() + 4,
+                    response = %describe_response(&response),
+                    "received frame"
+                );
+            }
 
             match response {
                 Response::Error { message } => Err(AgentClientError::AgentError(message)),
@@ -246,6 +340,78 @@ impl AgentClient {
         }
     }
 
+    /// Put the connection into screenshot streaming mode.
+    ///
+    /// Sends [`Request::StreamScreenshots`] and, once the agent acknowledges,
+    /// hands the connection to a background task that forwards each pushed
+    /// frame (JPEG bytes) to the returned receiver in order. The connection
+    /// is dedicated to the stream, so this client is left disconnected; open
+    /// a separate client for commands.
+    ///
+    /// Dropping the receiver sends [`Request::StopStream`] and closes the
+    /// connection. The receiver yields `None` once the agent stops sending
+    /// (error, disconnect or a gap longer than the read timeout).
+    pub async fn stream_screenshots(
+        &mut self,
+        fps: u8,
+        quality: u8,
+    ) -> Result<mpsc::Receiver<Vec<u8>>, AgentClientError> {
+        self.send(&Request::StreamScreenshots { fps, quality })
+            .await?;
+
+        let mut streamer = AgentClient {
+            stream: self.stream.take(),
+            addr: None,
+            read_timeout: self.read_timeout,
+            protocol_trace: self.protocol_trace,
+        };
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER_FRAMES);
+        tokio::spawn(
+            async move {
+                loop {
+                    let payload = tokio::select! {
+                        payload = streamer.read_frame(streamer.read_timeout) => payload,
+                        _ = tx.closed() => break,
+                    };
+                    let response = match payload.map(|p| decode_response(&p)) {
+                        Ok(Ok(response)) => response,
+                        Ok(Err(e)) => {
+                            warn!(error = %e, "undecodable stream frame, stopping stream");
+                            return;
+                        }
+                        Err(e) => {
+                            debug!(error = %e, "screenshot stream ended");
+                            return;
+                        }
+                    };
+                    if streamer.protocol_trace {
+                        debug!(response = %describe_response(&response), "received frame");
+                    }
+                    match response {
+                        Response::Screenshot { data } => {
+                            if tx.send(data).await.is_err() {
+                                break;
+                            }
+                        }
+                        Response::Error { message } => {
+                            warn!(%message, "agent stopped the screenshot stream");
+                            return;
+                        }
+                        other => trace!(?other, "ignoring non-frame response in stream"),
+                    }
+                }
+                // The consumer went away: let the agent stop capturing. The
+                // connection closes when `streamer` drops.
+                let _ = streamer
+                    .write_frame(&encode_request(&Request::StopStream))
+                    .await;
+                debug!("screenshot stream stopped");
+            }
+            .instrument(debug_span!("screenshot_stream", fps, quality)),
+        );
+        Ok(rx)
+    }
+
     // -----------------------------------------------------------------------
     // Internal frame I/O
     // -----------------------------------------------------------------------
@@ -401,6 +567,26 @@ mod tests {
         addr
     }
 
+    #[tokio::test]
+    async fn send_times_out_when_agent_never_replies() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            // Accept and hold the connection without ever replying.
+            let (_stream, _) = listener.accept().await.unwrap();
+            std::future::pending::<()>().await;
+        });
+
+        let mut client = AgentClient::new(addr);
+        client.set_read_timeout(Duration::from_millis(100));
+        assert_eq!(client.read_timeout(), Duration::from_millis(100));
+        client.connect().await.unwrap();
+
+        let result = client.send(&Request::Heartbeat).await;
+        assert!(matches!(result, Err(AgentClientError::Timeout)));
+        assert!(!client.is_connected());
+    }
+
     #[tokio::test]
     async fn heartbeat_ok_via_mock_server() {
         let addr = mock_server(Response::Ok).await;
@@ -606,4 +792,175 @@ mod tests {
 
         client.heartbeat().await.unwrap();
     }
+
+    /// Read one request frame from the mock agent's side of the stream.
+    async fn read_request(stream: &mut (impl AsyncRead + Unpin)) -> Request {
+        let mut header = [0u8; 4];
+        stream.read_exact(&mut header).await.unwrap();
+        let len = crate::protocol::read_frame_length(&header) as usize;
+        let mut payload = vec![0u8; len];
+        stream.read_exact(&mut payload).await.unwrap();
+        crate::protocol::decode_request(&payload).unwrap()
+    }
+
+    /// Collects formatted log output for assertions.
+    #[derive(Clone, Default)]
+    struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl CapturedLogs {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    /// Sends a heartbeat answered with `response` under a debug-level test
+    /// subscriber, returning what was logged.
+    async fn traced_heartbeat(response: Response, trace: bool) -> String {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let addr = mock_server(response).await;
+        let mut client = AgentClient::new(addr);
+        client.set_protocol_trace(trace);
+        client.connect().await.unwrap();
+        client.send(&Request::Heartbeat).await.unwrap();
+        logs.text()
+    }
+
+    #[tokio::test]
+    async fn protocol_trace_logs_sent_and_received_frames() {
+        let logs = traced_heartbeat(Response::Ok, true).await;
+        assert!(logs.contains("sent frame"), "{logs}");
+        assert!(logs.contains("bytes=5 request=Heartbeat"), "{logs}");
+        assert!(logs.contains("received frame"), "{logs}");
+        assert!(logs.contains("bytes=6 response=Ok"), "{logs}");
+    }
+
+    #[tokio::test]
+    async fn protocol_trace_elides_screenshot_bytes() {
+        let logs = traced_heartbeat(
+            Response::Screenshot {
+                data: vec![0xAB; 10_000],
+            },
+            true,
+        )
+        .await;
+        assert!(logs.contains("Screenshot { <10000 bytes> }"), "{logs}");
+        assert!(!logs.contains("171, 171"), "screenshot bytes leaked: {logs}");
+    }
+
+    #[tokio::test]
+    async fn protocol_trace_is_silent_when_disabled() {
+        let logs = traced_heartbeat(Response::Ok, false).await;
+        assert!(!logs.contains("sent frame"), "{logs}");
+        assert!(!logs.contains("received frame"), "{logs}");
+    }
+
+    #[test]
+    fn trace_descriptions_are_truncated() {
+        let described = describe_response(&Response::Tree {
+            json: "é".repeat(500),
+        });
+        assert!(described.chars().count() < TRACE_MAX_CHARS + 30);
+        assert!(described.ends_with("more chars)"), "{described}");
+        assert_eq!(
+            describe_request(&Request::Batch {
+                requests: vec![Request::Heartbeat; 3]
+            }),
+            "Batch { 3 requests }"
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_screenshots_yields_frames_in_order() {
+        let (client_stream, mut server_stream) = tokio::io::duplex(4096);
+        let (stopped_tx, stopped_rx) = tokio::sync::oneshot::channel();
+
+        tokio::spawn(async move {
+            assert_eq!(
+                read_request(&mut server_stream).await,
+                Request::StreamScreenshots {
+                    fps: 10,
+                    quality: 70
+                }
+            );
+            let mut wire = encode_response(&Response::Ok);
+            for frame in 1..=3u8 {
+                wire.extend(encode_response(&Response::Screenshot {
+                    data: vec![frame; 3],
+                }));
+            }
+            server_stream.write_all(&wire).await.unwrap();
+            server_stream.flush().await.unwrap();
+            let _ = stopped_tx.send(read_request(&mut server_stream).await);
+        });
+
+        let mut client = AgentClient::from_stream(client_stream);
+        let mut frames = client.stream_screenshots(10, 70).await.unwrap();
+        // The connection now belongs to the stream
+        assert!(!client.is_connected());
+
+        for frame in 1..=3u8 {
+            assert_eq!(frames.recv().await, Some(vec![frame; 3]));
+        }
+        drop(frames);
+        assert_eq!(stopped_rx.await.unwrap(), Request::StopStream);
+    }
+
+    #[tokio::test]
+    async fn stream_screenshots_ends_on_agent_error() {
+        let (client_stream, mut server_stream) = tokio::io::duplex(4096);
+
+        tokio::spawn(async move {
+            read_request(&mut server_stream).await;
+            let mut wire = encode_response(&Response::Ok);
+            wire.extend(encode_response(&Response::Screenshot { data: vec![7] }));
+            wire.extend(encode_response(&Response::Error {
+                message: "Screenshot failed".to_string(),
+            }));
+            server_stream.write_all(&wire).await.unwrap();
+            server_stream.flush().await.unwrap();
+            // Hold the connection open so only the error ends the stream
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let mut client = AgentClient::from_stream(client_stream);
+        let mut frames = client.stream_screenshots(10, 70).await.unwrap();
+        assert_eq!(frames.recv().await, Some(vec![7]));
+        assert_eq!(frames.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn stream_screenshots_fails_when_agent_predates_opcode() {
+        let addr = mock_server(Response::Error {
+            message: "decode error: invalid opcode: 0x21".to_string(),
+        })
+        .await;
+
+        let mut client = AgentClient::new(addr);
+        client.connect().await.unwrap();
+        let result = client.stream_screenshots(10, 70).await;
+        assert!(
+            matches!(result, Err(AgentClientError::AgentError(msg)) if msg.contains("invalid opcode"))
+        );
+        // A rejected stream leaves the connection usable
+        assert!(client.is_connected());
+    }
 }
@@ -10,11 +10,13 @@
 //! [`crate::agent_session`]; this module supplies only the iOS/macOS transport
 //! ([`IosTransport`]) and the driver's constructors/accessors.
 //!
-//! The driver supports four connection modes (see [`ConnectionTarget`]):
+//! The driver supports five connection modes (see [`ConnectionTarget`]):
 //!
 //! - **Direct TCP** (simulators): connects to a host:port via TCP socket
 //! - **USB tunnel** (physical devices): tunnels through usbmuxd to a port on
 //!   the device, using the [`usb_tunnel`](crate::usb_tunnel) module
+//! - **Network tunnel** (WiFi-paired devices): same as the USB tunnel, but
+//!   forces usbmuxd's network connection record
 //! - **Tunneld** (pymobiledevice3): connects through a pymobiledevice3 tunnel
 //!   to the agent on the device
 //! - **CoreDevice** (iOS 17+): connects via the native CoreDevice tunnel
@@ -45,7 +47,8 @@ use tracing::{info, warn};
 use crate::agent_client::AgentClient;
 use crate::agent_lifecycle::AgentLifecycle;
 use crate::agent_session::{map_client_error, AgentSession, AgentTransport, Recovered};
-use crate::driver::DriverError;
+use crate::driver::{DriverBackend, DriverError};
+use crate::usb_tunnel::ConnectionKind;
 
 // ---------------------------------------------------------------------------
 // ConnectionTarget
@@ -68,6 +71,13 @@ pub enum ConnectionTarget {
         /// The TCP port the agent listens on *on the device*.
         device_port: u16,
     },
+    /// Connect via usbmuxd over the network (WiFi-paired physical device).
+    NetworkDevice {
+        /// The device UDID.
+        udid: String,
+        /// The TCP port the agent listens on *on the device*.
+        device_port: u16,
+    },
     /// Connect via a pymobiledevice3 tunnel (CoreDevice devices).
     Tunneld {
         /// Tunnel IP address (typically IPv6 link-local from tunneld).
@@ -99,6 +109,8 @@ pub struct IosTransport {
 
 #[async_trait]
 impl AgentTransport for IosTransport {
+    const BACKEND: DriverBackend = DriverBackend::Agent;
+
     async fn create_client(&self) -> Result<AgentClient, DriverError> {
         let mut client = match &self.target {
             ConnectionTarget::Direct { host, port } => {
@@ -118,6 +130,16 @@ impl AgentTransport for IosTransport {
                 let stream = crate::usb_tunnel::connect(udid, *device_port).await?;
                 AgentClient::from_stream(stream)
             }
+            ConnectionTarget::NetworkDevice { udid, device_port } => {
+                let stream = crate::usb_tunnel::connect_with(
+                    udid,
+                    *device_port,
+                    Some(ConnectionKind::Network),
+                    crate::usb_tunnel::NETWORK_CONNECT_TIMEOUT,
+                )
+                .await?;
+                AgentClient::from_stream(stream)
+            }
             ConnectionTarget::Tunneld {
                 tunnel_address,
                 agent_port,
@@ -224,6 +246,22 @@ impl AgentSession<IosTransport> {
         })
     }
 
+    /// Creates a driver that will tunnel to a WiFi-paired physical device.
+    ///
+    /// Unlike [`usb_device`](Self::usb_device), this always uses usbmuxd's
+    /// network record, even if the device is also plugged in over USB.
+    ///
+    /// No connection is established until [`connect`](AutomationDriver::connect) is called.
+    pub fn network_device(udid: impl Into<String>, device_port: u16) -> Self {
+        Self::from_transport(IosTransport {
+            target: ConnectionTarget::NetworkDevice {
+                udid: udid.into(),
+                device_port,
+            },
+            lifecycle: None,
+        })
+    }
+
     /// Creates a driver that will connect through a pymobiledevice3 tunnel.
     ///
     /// No connection is established until [`connect`](AutomationDriver::connect) is called.
@@ -278,6 +316,7 @@ impl AgentSession<IosTransport> {
         match &self.transport.target {
             ConnectionTarget::Direct { host, .. } => host,
             ConnectionTarget::UsbDevice { udid, .. } => udid,
+            ConnectionTarget::NetworkDevice { udid, .. } => udid,
             ConnectionTarget::Tunneld { tunnel_address, .. } => tunnel_address,
             ConnectionTarget::CoreDevice { udid, .. } => udid,
         }
@@ -288,6 +327,7 @@ impl AgentSession<IosTransport> {
         match &self.transport.target {
             ConnectionTarget::Direct { port, .. } => *port,
             ConnectionTarget::UsbDevice { device_port, .. } => *device_port,
+            ConnectionTarget::NetworkDevice { device_port, .. } => *device_port,
             ConnectionTarget::Tunneld { agent_port, .. } => *agent_port,
             ConnectionTarget::CoreDevice { port, .. } => *port,
         }
@@ -303,7 +343,7 @@ mod tests {
     use super::*;
     use crate::agent_client::AgentClientError;
     use crate::agent_session::{expect_ok, map_client_error};
-    use crate::driver::AutomationDriver;
+    use crate::driver::{AutomationDriver, Orientation};
     use crate::protocol::{encode_response, Response};
     use tokio::io::{AsyncReadExt, AsyncWriteExt};
     use tokio::net::TcpListener;
@@ -416,6 +456,19 @@ mod tests {
         assert!(!driver.is_connected());
     }
 
+    #[test]
+    fn network_device_creates_network_target() {
+        let driver = AgentDriver::network_device("ABC-123", 8080);
+        assert!(matches!(
+            driver.target(),
+            ConnectionTarget::NetworkDevice { udid, device_port }
+                if udid == "ABC-123" && *device_port == 8080
+        ));
+        assert_eq!(driver.host(), "ABC-123");
+        assert_eq!(driver.port(), 8080);
+        assert!(!driver.is_connected());
+    }
+
     #[test]
     fn tunneld_creates_tunneld_target() {
         let driver = AgentDriver::tunneld("fd00::1", 8080);
@@ -780,6 +833,209 @@ mod tests {
         driver.set_target("com.example.myapp").await.unwrap();
     }
 
+    #[tokio::test]
+    async fn query_state_surfaces_agent_state() {
+        let addr = mock_server_with_connect(Response::State {
+            target_bundle: Some("com.example.myapp".into()),
+            foreground_app: Some("com.apple.springboard".into()),
+        })
+        .await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        let state = driver.query_state().await.unwrap();
+        assert_eq!(state.target_bundle.as_deref(), Some("com.example.myapp"));
+        assert_eq!(
+            state.foreground_app.as_deref(),
+            Some("com.apple.springboard")
+        );
+    }
+
+    #[tokio::test]
+    async fn query_state_fails_when_agent_predates_opcode() {
+        let addr = mock_server_with_connect(Response::Error {
+            message: "invalid opcode: 0x18".into(),
+        })
+        .await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        assert!(matches!(
+            driver.query_state().await,
+            Err(DriverError::CommandFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn screen_info_surfaces_geometry() {
+        let addr = mock_server_with_connect(Response::ScreenInfo {
+            width: 852.0,
+            height: 393.0,
+            scale: 3.0,
+            orientation: "landscape_left".into(),
+        })
+        .await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        let info = driver.screen_info().await.unwrap();
+        assert_eq!(info.width, 852.0);
+        assert_eq!(info.height, 393.0);
+        assert_eq!(info.scale, 3.0);
+        assert_eq!(info.orientation, Some(Orientation::LandscapeLeft));
+    }
+
+    #[tokio::test]
+    async fn screen_scale_is_cached_after_screen_info() {
+        let addr = mock_server_with_connect(Response::ScreenInfo {
+            width: 375.0,
+            height: 667.0,
+            scale: 2.0,
+            orientation: "portrait".into(),
+        })
+        .await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        let info = driver.screen_info().await.unwrap();
+        // The mock agent answers only one request, so a second round trip
+        // would fail.
+        assert_eq!(driver.screen_scale().await.unwrap(), info.scale);
+    }
+
+    #[tokio::test]
+    async fn set_orientation_sends_request() {
+        let addr = mock_server_with_connect(Response::Ok).await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        driver
+            .set_orientation(Orientation::LandscapeRight)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn multi_tap_sends_request() {
+        let addr = mock_server_with_connect(Response::Ok).await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        driver.multi_tap(2, 100, 200).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn multi_tap_rejects_finger_count_out_of_range() {
+        // Validation happens before any request, so no agent is needed.
+        let driver = AgentDriver::new("127.0.0.1".to_string(), 1);
+        for finger_count in [0, 6] {
+            match driver.multi_tap(finger_count, 100, 200).await {
+                Err(DriverError::CommandFailed(msg)) => {
+                    assert!(msg.contains("between 1 and 5"), "{msg}")
+                }
+                other => panic!("expected CommandFailed, got {other:?}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn multi_tap_reports_old_agent_as_unsupported() {
+        let addr = mock_server_with_connect(Response::Error {
+            message: "invalid opcode: 0x1C".into(),
+        })
+        .await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        assert!(matches!(
+            driver.multi_tap(3, 100, 200).await,
+            Err(DriverError::Unsupported(op)) if op == "multi_tap"
+        ));
+    }
+
+    #[tokio::test]
+    async fn paste_sends_request() {
+        let addr = mock_server_with_connect(Response::Ok).await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        driver.paste().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn paste_reports_old_agent_as_unsupported() {
+        let addr = mock_server_with_connect(Response::Error {
+            message: "invalid opcode: 0x1D".into(),
+        })
+        .await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        assert!(matches!(
+            driver.paste().await,
+            Err(DriverError::Unsupported(op)) if op == "paste"
+        ));
+    }
+
+    #[tokio::test]
+    async fn clear_text_is_unsupported_on_old_agents() {
+        let addr = mock_server_with_connect(Response::Error {
+            message: "invalid opcode: 0x1E".into(),
+        })
+        .await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        assert!(matches!(
+            driver.clear_text("email-field", false).await,
+            Err(DriverError::Unsupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn long_press_element_sends_request() {
+        let addr = mock_server_with_connect(Response::Ok).await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        driver
+            .long_press_element("message-bubble", false, 1.0)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn long_press_element_is_unsupported_on_old_agents() {
+        let addr = mock_server_with_connect(Response::Error {
+            message: "invalid opcode: 0x25".into(),
+        })
+        .await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        assert!(matches!(
+            driver
+                .long_press_element("message-bubble", false, 1.0)
+                .await,
+            Err(DriverError::Unsupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn screen_info_fails_when_agent_predates_opcode() {
+        let addr = mock_server_with_connect(Response::Error {
+            message: "invalid opcode: 0x19".into(),
+        })
+        .await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        assert!(matches!(
+            driver.screen_info().await,
+            Err(DriverError::CommandFailed(_))
+        ));
+    }
+
     #[tokio::test]
     async fn screenshot_unexpected_response() {
         let addr = mock_server_with_connect(Response::Ok).await;
@@ -847,6 +1103,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn map_agent_not_found_error() {
+        let err = map_client_error(AgentClientError::AgentError(
+            "Element with label 'Log In' and type 'Button' not found".to_string(),
+        ));
+        match err {
+            DriverError::ElementNotFound { selector } => {
+                assert_eq!(selector, "label 'Log In' and type 'Button'")
+            }
+            other => panic!("expected ElementNotFound, got: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn tap_missing_element_is_element_not_found() {
+        let addr = mock_server_with_connect(Response::Error {
+            message: "Element with ID 'missing' not found".into(),
+        })
+        .await;
+        let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+        driver.connect().await.unwrap();
+
+        match driver.tap_element("missing").await {
+            Err(DriverError::ElementNotFound { selector }) => assert_eq!(selector, "ID 'missing'"),
+            other => panic!("expected ElementNotFound, got {other:?}"),
+        }
+    }
+
     #[test]
     fn map_timeout() {
         let err = map_client_error(AgentClientError::Timeout);
@@ -28,12 +28,14 @@
 //! # }
 //! ```
 
+use std::io::Write;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Mutex;
 use std::time::Duration;
 
+use async_trait::async_trait;
 use thiserror::Error;
 
 use tracing::{debug, info, instrument};
@@ -50,6 +52,12 @@ const TEST_CLASS: &str = "QorvexAgentUITests/QorvexAgentTests/testRunAgent";
 const DERIVED_DATA_DIR: &str = ".build";
 const AGENT_BUNDLE_ID: &str = "com.qorvex.agent";
 
+/// Lines of agent output included in a startup error.
+const LOG_TAIL_LINES: usize = 20;
+
+/// Wait before the second readiness attempt; doubles after each miss.
+const INITIAL_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
 // ---------------------------------------------------------------------------
 // Configuration
 // ---------------------------------------------------------------------------
@@ -62,6 +70,9 @@ pub struct AgentLifecycleConfig {
     pub agent_port: u16,
     /// Maximum time to wait for the agent to become ready.
     pub startup_timeout: Duration,
+    /// Longest wait between readiness attempts. The wait starts at 250 ms
+    /// and doubles after each failed attempt up to this cap.
+    pub max_poll_interval: Duration,
     /// Maximum number of launch retries before giving up.
     pub max_retries: u32,
     /// Whether the target is a physical device (`true`) or a simulator (`false`).
@@ -83,6 +94,9 @@ pub struct AgentLifecycleConfig {
     pub development_team: Option<String>,
     /// Override bundle ID for the agent when the default is claimed by another team.
     pub agent_bundle_id: Option<String>,
+    /// Directory for the agent's output log; `None` uses
+    /// [`logs_dir`](crate::session::logs_dir).
+    pub log_dir: Option<PathBuf>,
 }
 
 impl AgentLifecycleConfig {
@@ -90,18 +104,49 @@ impl AgentLifecycleConfig {
     pub fn new(project_dir: PathBuf) -> Self {
         Self {
             project_dir,
-            agent_port: 8080,
+            agent_port: crate::config::DEFAULT_AGENT_PORT,
             startup_timeout: Duration::from_secs(30),
+            max_poll_interval: Duration::from_secs(4),
             max_retries: 3,
             is_physical: false,
             tunnel_address: None,
             direct_host: None,
             development_team: None,
             agent_bundle_id: None,
+            log_dir: None,
         }
     }
 }
 
+/// Returns the wait before the next readiness attempt: double `current`,
+/// capped at `max`.
+pub fn next_poll_interval(current: Duration, max: Duration) -> Duration {
+    current.saturating_mul(2).min(max)
+}
+
+/// Returns the last `lines` non-blank lines of `output`, trimmed.
+fn log_tail(output: &str, lines: usize) -> String {
+    let mut tail: Vec<&str> = output
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .take(lines)
+        .collect();
+    tail.reverse();
+    tail.join("\n").trim().to_string()
+}
+
+/// Outcome of a single readiness probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Readiness {
+    /// The agent answered.
+    Ready,
+    /// Something accepted the connection but did not answer a heartbeat.
+    Listening,
+    /// Nothing accepted the connection.
+    Closed,
+}
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -125,10 +170,29 @@ pub enum AgentLifecycleError {
     #[error("Agent process exited: {0}")]
     SpawnFailed(String),
 
-    /// The agent did not respond to heartbeat within the startup timeout.
+    /// The agent's port opened, but it did not respond to a heartbeat within
+    /// the startup timeout.
     #[error("Agent failed to become ready within timeout")]
     StartupTimeout,
 
+    /// `xcodebuild` was still running at the startup timeout and the agent's
+    /// port never opened — it is still building, installing, or launching.
+    #[error("Agent build did not finish within {waited:?}; xcodebuild is still running")]
+    BuildNotFinished {
+        /// How long readiness was polled.
+        waited: Duration,
+    },
+
+    /// Nothing accepted connections on the agent's port within the startup
+    /// timeout, and no `xcodebuild` process was running to bring it up.
+    #[error("Agent port {port} never opened within {waited:?}")]
+    PortNeverOpened {
+        /// The agent port that was polled.
+        port: u16,
+        /// How long readiness was polled.
+        waited: Duration,
+    },
+
     /// An operation was attempted that requires the agent to be running.
     #[error("Agent is not running")]
     NotRunning,
@@ -183,6 +247,16 @@ impl Drop for AgentLifecycle {
     }
 }
 
+impl std::fmt::Debug for AgentLifecycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentLifecycle")
+            .field("udid", &self.udid)
+            .field("config", &"<AgentLifecycleConfig>")
+            .field("child", &"<Mutex<Option<Child>>>")
+            .finish()
+    }
+}
+
 impl AgentLifecycle {
     /// Create a new lifecycle manager for the given simulator device.
     pub fn new(udid: String, config: AgentLifecycleConfig) -> Self {
@@ -290,7 +364,8 @@ impl AgentLifecycle {
     pub fn spawn_agent(&self) -> Result<(), AgentLifecycleError> {
         let xcodeproj = self.config.project_dir.join(XCODEPROJ);
 
-        let child = Command::new("xcodebuild")
+        let mut command = Command::new("xcodebuild");
+        command
             .args([
                 "test-without-building",
                 "-project",
@@ -311,20 +386,48 @@ impl AgentLifecycle {
             .env(
                 "TEST_RUNNER_QORVEX_PORT",
                 self.config.agent_port.to_string(),
-            )
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::piped())
+            );
+        debug!(
+            port = self.config.agent_port,
+            "passing port to agent via TEST_RUNNER_QORVEX_PORT"
+        );
+        self.spawn_logged(command)
+    }
+
+    /// Path of the log file the agent's stdout and stderr are written to:
+    /// `agent-<udid>.log` in [`AgentLifecycleConfig::log_dir`].
+    ///
+    /// The file is truncated on each spawn, so it holds the output of the
+    /// most recent launch.
+    pub fn log_path(&self) -> PathBuf {
+        let dir = match self.config.log_dir {
+            Some(ref dir) => dir.clone(),
+            None => crate::session::logs_dir(),
+        };
+        dir.join(format!("agent-{}.log", self.udid))
+    }
+
+    /// Spawn `command` as the agent process with its stdout and stderr
+    /// redirected to [`log_path`](Self::log_path).
+    fn spawn_logged(&self, mut command: Command) -> Result<(), AgentLifecycleError> {
+        let path = self.log_path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut log = std::fs::File::create(&path)?;
+        writeln!(log, "$ {:?}", command)?;
+        log.flush()?;
+
+        let child = command
+            .stdout(log.try_clone()?)
+            .stderr(log)
             .spawn()
             .map_err(|e| AgentLifecycleError::LaunchFailed(e.to_string()))?;
 
         let mut guard = self.child.lock().unwrap();
         *guard = Some(child);
 
-        debug!(
-            port = self.config.agent_port,
-            "passing port to agent via TEST_RUNNER_QORVEX_PORT"
-        );
-        info!("agent process spawned");
+        info!(log = %path.display(), "agent process spawned");
         Ok(())
     }
 
@@ -359,109 +462,150 @@ impl AgentLifecycle {
 
     /// Wait for the agent to become ready by polling its TCP port.
     ///
-    /// Attempts to connect via [`AgentClient`] and send a heartbeat every
-    /// 500 ms until either a successful response is received or
+    /// Attempts to connect via [`AgentClient`] and send a heartbeat, backing
+    /// off exponentially between attempts (see [`next_poll_interval`]) until
+    /// either a successful response is received or
     /// [`AgentLifecycleConfig::startup_timeout`] is exceeded.
     ///
     /// # Errors
     ///
-    /// - [`AgentLifecycleError::StartupTimeout`] if the agent does not respond within the timeout
+    /// - [`AgentLifecycleError::SpawnFailed`] if `xcodebuild` exited early
+    /// - [`AgentLifecycleError::BuildNotFinished`] if `xcodebuild` is still
+    ///   running at the deadline but the port never opened
+    /// - [`AgentLifecycleError::PortNeverOpened`] if nothing ever accepted a
+    ///   connection and no `xcodebuild` process is running
+    /// - [`AgentLifecycleError::StartupTimeout`] if the port opened but the
+    ///   agent never answered a heartbeat
     #[instrument(skip(self))]
     pub async fn wait_for_ready(&self) -> Result<(), AgentLifecycleError> {
-        let deadline = tokio::time::Instant::now() + self.config.startup_timeout;
-        let addr = self.agent_addr();
+        self.poll_until_ready(|| self.probe()).await
+    }
 
-        loop {
-            if self.config.is_physical {
-                // For physical devices, try connection methods in order:
-                // 1. Direct host (WiFi/localNetwork — mDNS hostname)
-                // 2. Tunnel address (tunneld)
-                // 3. USB tunnel (usbmuxd) → CoreDevice tunnel (iOS 17+)
-                let reachable = if let Some(ref host) = self.config.direct_host {
-                    let host_port = format!("{}:{}", host, self.config.agent_port);
-                    tokio::net::TcpStream::connect(host_port.as_str())
-                        .await
-                        .is_ok()
-                } else if let Some(ref tunnel_addr) = self.config.tunnel_address {
-                    crate::usb_tunnel::connect_tunneld(tunnel_addr, self.config.agent_port)
-                        .await
-                        .is_ok()
+    /// Probe the agent once: connect (and, on simulators, heartbeat).
+    async fn probe(&self) -> Readiness {
+        if self.config.is_physical {
+            // For physical devices, try connection methods in order:
+            // 1. Direct host (WiFi/localNetwork — mDNS hostname)
+            // 2. Tunnel address (tunneld)
+            // 3. USB tunnel (usbmuxd) → CoreDevice tunnel (iOS 17+)
+            let reachable = if let Some(ref host) = self.config.direct_host {
+                let host_port = format!("{}:{}", host, self.config.agent_port);
+                tokio::net::TcpStream::connect(host_port.as_str())
+                    .await
+                    .is_ok()
+            } else if let Some(ref tunnel_addr) = self.config.tunnel_address {
+                crate::usb_tunnel::connect_tunneld(tunnel_addr, self.config.agent_port)
+                    .await
+                    .is_ok()
+            } else {
+                // Try usbmuxd first, then fall back to native CoreDevice tunnel (iOS 17+).
+                let via_usb = crate::usb_tunnel::connect(&self.udid, self.config.agent_port)
+                    .await
+                    .is_ok();
+                if via_usb {
+                    true
                 } else {
-                    // Try usbmuxd first, then fall back to native CoreDevice tunnel (iOS 17+).
-                    let via_usb = crate::usb_tunnel::connect(&self.udid, self.config.agent_port)
-                        .await
-                        .is_ok();
-                    if via_usb {
-                        true
-                    } else {
-                        crate::core_device_tunnel::connect_coredevice(
-                            &self.udid,
-                            self.config.agent_port,
-                        )
-                        .await
-                        .is_ok()
-                    }
-                };
-                if reachable {
-                    info!("agent ready");
-                    return Ok(());
+                    crate::core_device_tunnel::connect_coredevice(
+                        &self.udid,
+                        self.config.agent_port,
+                    )
+                    .await
+                    .is_ok()
                 }
+            };
+            if reachable {
+                Readiness::Ready
             } else {
-                let mut client = AgentClient::new(addr);
-                if client.connect().await.is_ok() {
-                    if client.heartbeat().await.is_ok() {
-                        client.disconnect();
-                        info!("agent ready");
-                        return Ok(());
-                    }
-                    client.disconnect();
-                }
+                Readiness::Closed
             }
+        } else {
+            let mut client = AgentClient::new(self.agent_addr());
+            if client.connect().await.is_err() {
+                return Readiness::Closed;
+            }
+            let ready = client.heartbeat().await.is_ok();
+            client.disconnect();
+            if ready {
+                Readiness::Ready
+            } else {
+                Readiness::Listening
+            }
+        }
+    }
+
+    /// Run `probe` with exponential backoff until it reports
+    /// [`Readiness::Ready`], `xcodebuild` exits, or the startup timeout passes.
+    async fn poll_until_ready<F, Fut>(&self, mut probe: F) -> Result<(), AgentLifecycleError>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Readiness>,
+    {
+        let started = tokio::time::Instant::now();
+        let deadline = started + self.config.startup_timeout;
+        let mut interval = INITIAL_POLL_INTERVAL;
+        let mut port_opened = false;
+
+        for attempt in 1.. {
+            let readiness = probe().await;
+            if readiness == Readiness::Ready {
+                info!(attempt, elapsed = ?started.elapsed(), "agent ready");
+                return Ok(());
+            }
+            port_opened |= readiness == Readiness::Listening;
 
             // Check if xcodebuild exited early (e.g. build products missing,
             // simulator not booted, signing error). Without this check we
             // silently poll until timeout while the process is already dead.
-            {
-                let mut guard = self.child.lock().unwrap();
-                if let Some(ref mut child) = *guard {
-                    if let Some(status) = child.try_wait().ok().flatten() {
-                        // Collect stderr for diagnostics.
-                        let stderr = child
-                            .stderr
-                            .take()
-                            .and_then(|mut s| {
-                                let mut buf = String::new();
-                                use std::io::Read;
-                                s.read_to_string(&mut buf).ok()?;
-                                Some(buf)
-                            })
-                            .unwrap_or_default();
-                        let detail = if stderr.is_empty() {
-                            format!("exit code {}", status)
-                        } else {
-                            // Truncate to last meaningful lines.
-                            let tail: String = stderr
-                                .lines()
-                                .rev()
-                                .take(20)
-                                .collect::<Vec<_>>()
-                                .into_iter()
-                                .rev()
-                                .collect::<Vec<_>>()
-                                .join("\n");
-                            format!("exit code {} — {}", status, tail.trim())
-                        };
-                        return Err(AgentLifecycleError::SpawnFailed(detail));
-                    }
-                }
+            if let Some(detail) = self.poll_child_exit() {
+                return Err(AgentLifecycleError::SpawnFailed(detail));
             }
 
-            if tokio::time::Instant::now() >= deadline {
-                return Err(AgentLifecycleError::StartupTimeout);
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                let waited = self.config.startup_timeout;
+                return Err(if port_opened {
+                    AgentLifecycleError::StartupTimeout
+                } else if self.child_running() {
+                    AgentLifecycleError::BuildNotFinished { waited }
+                } else {
+                    AgentLifecycleError::PortNeverOpened {
+                        port: self.config.agent_port,
+                        waited,
+                    }
+                });
             }
 
-            tokio::time::sleep(Duration::from_millis(500)).await;
+            let sleep = interval.min(deadline - now);
+            debug!(attempt, ?readiness, next_attempt_in = ?sleep, "agent not ready yet");
+            tokio::time::sleep(sleep).await;
+            interval = next_poll_interval(interval, self.config.max_poll_interval);
         }
+        unreachable!("the readiness loop only exits by returning")
+    }
+
+    /// If the `xcodebuild` child has exited, return a diagnostic string
+    /// (exit status + the last [`LOG_TAIL_LINES`] lines of its output);
+    /// otherwise `None`. The full output is at [`log_path`](Self::log_path).
+    fn poll_child_exit(&self) -> Option<String> {
+        let status = {
+            let mut guard = self.child.lock().unwrap();
+            let child = guard.as_mut()?;
+            child.try_wait().ok().flatten()?
+        };
+        let output = std::fs::read_to_string(self.log_path()).unwrap_or_default();
+        // The first line is the command line written by `spawn_logged`.
+        let output = output.split_once('\n').map_or("", |(_, rest)| rest);
+        let tail = log_tail(output, LOG_TAIL_LINES);
+        Some(if tail.is_empty() {
+            format!("exit code {}", status)
+        } else {
+            format!("exit code {} — {}", status, tail)
+        })
+    }
+
+    /// Returns `true` if a spawned `xcodebuild` child is still running.
+    fn child_running(&self) -> bool {
+        self.child.lock().unwrap().is_some()
     }
 
     /// Check whether the agent XCTest bundle has already been built.
@@ -511,7 +655,8 @@ impl AgentLifecycle {
     ///
     /// - Any error from [`build_agent`](Self::build_agent)
     /// - Any error from [`spawn_agent`](Self::spawn_agent)
-    /// - [`AgentLifecycleError::StartupTimeout`] if all retries are exhausted
+    /// - The last readiness error from [`wait_for_ready`](Self::wait_for_ready)
+    ///   if all retries are exhausted
     #[instrument(skip(self))]
     pub async fn ensure_running(&self) -> Result<(), AgentLifecycleError> {
         if self.is_agent_built() {
@@ -528,9 +673,12 @@ impl AgentLifecycle {
                     info!("agent running after attempt {}", attempt);
                     return Ok(());
                 }
-                Err(AgentLifecycleError::StartupTimeout | AgentLifecycleError::SpawnFailed(_))
-                    if attempt < self.config.max_retries =>
-                {
+                Err(
+                    AgentLifecycleError::StartupTimeout
+                    | AgentLifecycleError::BuildNotFinished { .. }
+                    | AgentLifecycleError::PortNeverOpened { .. }
+                    | AgentLifecycleError::SpawnFailed(_),
+                ) if attempt < self.config.max_retries => {
                     // Terminate and respawn for the next attempt.
                     let _ = self.terminate_agent();
                     self.spawn_agent()?;
@@ -649,6 +797,56 @@ impl AgentLifecycle {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Restart
+// ---------------------------------------------------------------------------
+
+/// The process-control steps of an agent lifecycle that [`restart_agent`]
+/// sequences. Implemented by [`AgentLifecycle`]; tests substitute a stub.
+#[async_trait]
+pub trait AgentProcess: Send + Sync {
+    /// Stop the running agent process.
+    fn terminate_agent(&self) -> Result<(), AgentLifecycleError>;
+    /// Launch a new agent process.
+    fn spawn_agent(&self) -> Result<(), AgentLifecycleError>;
+    /// Wait until the new agent answers.
+    async fn wait_for_ready(&self) -> Result<(), AgentLifecycleError>;
+}
+
+#[async_trait]
+impl AgentProcess for AgentLifecycle {
+    fn terminate_agent(&self) -> Result<(), AgentLifecycleError> {
+        AgentLifecycle::terminate_agent(self)
+    }
+
+    fn spawn_agent(&self) -> Result<(), AgentLifecycleError> {
+        AgentLifecycle::spawn_agent(self)
+    }
+
+    async fn wait_for_ready(&self) -> Result<(), AgentLifecycleError> {
+        AgentLifecycle::wait_for_ready(self).await
+    }
+}
+
+/// Restart the agent: terminate it, spawn a fresh one, and wait for it to
+/// answer.
+///
+/// Termination is best effort (the agent may already be dead, which is often
+/// why it is being restarted); spawn and readiness errors are returned.
+/// Reconnecting a driver to the new agent is left to the caller.
+#[instrument(skip(process))]
+pub async fn restart_agent<P: AgentProcess + ?Sized>(
+    process: &P,
+) -> Result<(), AgentLifecycleError> {
+    if let Err(e) = process.terminate_agent() {
+        debug!(error = %e, "terminating agent before restart failed");
+    }
+    process.spawn_agent()?;
+    process.wait_for_ready().await?;
+    info!("agent restarted");
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -676,12 +874,14 @@ mod tests {
             project_dir: PathBuf::from("/tmp/custom"),
             agent_port: 12345,
             startup_timeout: Duration::from_secs(10),
+            max_poll_interval: Duration::from_secs(2),
             max_retries: 5,
             is_physical: false,
             tunnel_address: None,
             direct_host: None,
             development_team: None,
             agent_bundle_id: None,
+            log_dir: None,
         };
 
         assert_eq!(config.project_dir, PathBuf::from("/tmp/custom"));
@@ -719,6 +919,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn error_display_readiness_causes() {
+        let err = AgentLifecycleError::BuildNotFinished {
+            waited: Duration::from_secs(30),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Agent build did not finish within 30s; xcodebuild is still running"
+        );
+        let err = AgentLifecycleError::PortNeverOpened {
+            port: 8080,
+            waited: Duration::from_secs(30),
+        };
+        assert_eq!(err.to_string(), "Agent port 8080 never opened within 30s");
+    }
+
     #[test]
     fn error_display_not_running() {
         let err = AgentLifecycleError::NotRunning;
@@ -786,12 +1002,14 @@ mod tests {
             project_dir: PathBuf::from("/tmp/agent"),
             agent_port: 5555,
             startup_timeout: Duration::from_secs(15),
+            max_poll_interval: Duration::from_secs(4),
             max_retries: 2,
             is_physical: false,
             tunnel_address: None,
             direct_host: None,
             development_team: None,
             agent_bundle_id: None,
+            log_dir: None,
         };
         let lifecycle = AgentLifecycle::new("ABCD-1234".to_string(), config);
 
@@ -813,12 +1031,14 @@ mod tests {
             // Use a port that (almost certainly) has nothing listening.
             agent_port: 19999,
             startup_timeout: Duration::from_secs(30),
+            max_poll_interval: Duration::from_secs(4),
             max_retries: 3,
             is_physical: false,
             tunnel_address: None,
             direct_host: None,
             development_team: None,
             agent_bundle_id: None,
+            log_dir: None,
         };
         let lifecycle = AgentLifecycle::new("test-udid".to_string(), config);
 
@@ -918,16 +1138,164 @@ mod tests {
             project_dir: PathBuf::from("/tmp/agent"),
             agent_port: 19998,
             startup_timeout: Duration::from_secs(1),
+            max_poll_interval: Duration::from_millis(200),
             max_retries: 3,
             is_physical: false,
             tunnel_address: None,
             direct_host: None,
             development_team: None,
             agent_bundle_id: None,
+            log_dir: None,
         };
         let lifecycle = AgentLifecycle::new("test-udid".to_string(), config);
 
         let result = lifecycle.wait_for_ready().await;
+        // No xcodebuild child was spawned, so this is not a slow build.
+        assert!(matches!(
+            result,
+            Err(AgentLifecycleError::PortNeverOpened { port: 19998, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn failed_agent_output_tail_is_in_spawn_error() {
+        let log_dir = std::env::temp_dir().join(format!("qorvex-agent-log-{}", std::process::id()));
+        let mut config = AgentLifecycleConfig::new(PathBuf::from("/tmp/agent"));
+        config.agent_port = 19996;
+        config.log_dir = Some(log_dir.clone());
+        let lifecycle = AgentLifecycle::new("STUB-UDID".to_string(), config);
+        assert_eq!(lifecycle.log_path(), log_dir.join("agent-STUB-UDID.log"));
+
+        // A stub agent that fails the way xcodebuild does on a shut-down simulator.
+        let mut stub = Command::new("sh");
+        stub.args([
+            "-c",
+            "echo 'Testing started'; echo 'error: Unable to boot the Simulator' >&2; exit 65",
+        ]);
+        lifecycle.spawn_logged(stub).unwrap();
+
+        let err = lifecycle.wait_for_ready().await.unwrap_err();
+        let AgentLifecycleError::SpawnFailed(detail) = err else {
+            panic!("expected SpawnFailed, got {err:?}");
+        };
+        assert!(detail.contains("Testing started\nerror: Unable to boot the Simulator"));
+        // The full output stays in the log.
+        let log = std::fs::read_to_string(lifecycle.log_path()).unwrap();
+        assert!(log.contains("Unable to boot the Simulator"));
+        let _ = std::fs::remove_dir_all(&log_dir);
+    }
+
+    /// Records the restart steps it is asked to perform.
+    #[derive(Default)]
+    struct StubProcess {
+        calls: Mutex<Vec<&'static str>>,
+        fail_terminate: bool,
+        fail_spawn: bool,
+    }
+
+    #[async_trait]
+    impl AgentProcess for StubProcess {
+        fn terminate_agent(&self) -> Result<(), AgentLifecycleError> {
+            self.calls.lock().unwrap().push("terminate");
+            if self.fail_terminate {
+                return Err(AgentLifecycleError::NotRunning);
+            }
+            Ok(())
+        }
+
+        fn spawn_agent(&self) -> Result<(), AgentLifecycleError> {
+            self.calls.lock().unwrap().push("spawn");
+            if self.fail_spawn {
+                return Err(AgentLifecycleError::LaunchFailed("no xcodebuild".into()));
+            }
+            Ok(())
+        }
+
+        async fn wait_for_ready(&self) -> Result<(), AgentLifecycleError> {
+            self.calls.lock().unwrap().push("wait");
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn restart_agent_terminates_spawns_then_waits() {
+        let stub = StubProcess::default();
+        restart_agent(&stub).await.unwrap();
+        assert_eq!(*stub.calls.lock().unwrap(), ["terminate", "spawn", "wait"]);
+
+        // A failed terminate (agent already gone) does not stop the restart.
+        let stub = StubProcess {
+            fail_terminate: true,
+            ..StubProcess::default()
+        };
+        restart_agent(&stub).await.unwrap();
+        assert_eq!(*stub.calls.lock().unwrap(), ["terminate", "spawn", "wait"]);
+    }
+
+    #[tokio::test]
+    async fn restart_agent_stops_when_spawn_fails() {
+        let stub = StubProcess {
+            fail_spawn: true,
+            ..StubProcess::default()
+        };
+        let result = restart_agent(&stub).await;
+        assert!(matches!(result, Err(AgentLifecycleError::LaunchFailed(_))));
+        assert_eq!(*stub.calls.lock().unwrap(), ["terminate", "spawn"]);
+    }
+
+    #[test]
+    fn log_tail_keeps_last_non_blank_lines() {
+        assert_eq!(log_tail("a\n\nb\nc\n  \n", 2), "b\nc");
+        assert_eq!(log_tail("", 5), "");
+    }
+
+    #[test]
+    fn poll_interval_doubles_up_to_cap() {
+        let max = Duration::from_secs(4);
+        let mut interval = INITIAL_POLL_INTERVAL;
+        let mut seen = Vec::new();
+        for _ in 0..6 {
+            seen.push(interval.as_millis());
+            interval = next_poll_interval(interval, max);
+        }
+        assert_eq!(seen, [250, 500, 1000, 2000, 4000, 4000]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn poll_until_ready_backs_off_until_probe_succeeds() {
+        let lifecycle = sim_lifecycle_on_port("test-udid", 19997);
+        let attempts = std::cell::Cell::new(0);
+        let started = tokio::time::Instant::now();
+
+        let result = lifecycle
+            .poll_until_ready(|| {
+                attempts.set(attempts.get() + 1);
+                let ready = attempts.get() >= 4;
+                async move {
+                    if ready {
+                        Readiness::Ready
+                    } else {
+                        Readiness::Closed
+                    }
+                }
+            })
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(attempts.get(), 4);
+        // 250 + 500 + 1000 ms of backoff between the four attempts.
+        assert_eq!(started.elapsed(), Duration::from_millis(1750));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn poll_until_ready_reports_listening_agent_as_startup_timeout() {
+        let mut config = AgentLifecycleConfig::new(PathBuf::from("/tmp/agent"));
+        config.startup_timeout = Duration::from_secs(3);
+        let lifecycle = AgentLifecycle::new("test-udid".to_string(), config);
+
+        let result = lifecycle
+            .poll_until_ready(|| async { Readiness::Listening })
+            .await;
         assert!(matches!(result, Err(AgentLifecycleError::StartupTimeout)));
     }
 }
@@ -32,10 +32,13 @@ use async_trait::async_trait;
 use tokio::sync::Mutex;
 use tracing::{debug, info, instrument, warn};
 
-use crate::agent_client::{AgentClient, AgentClientError};
-use crate::driver::{AutomationDriver, DriverError, TargetInfo};
+use crate::agent_client::{AgentClient, AgentClientError, DEFAULT_READ_TIMEOUT};
+use crate::driver::{
+    find_all_elements, validate_finger_count, AgentState, AutomationDriver, DriverBackend,
+    DriverError, ElementFilter, Orientation, ScreenInfo, TargetInfo,
+};
 use crate::element::UIElement;
-use crate::protocol::{Request, Response};
+use crate::protocol::{Request, Response, ScreenshotFormat, SpecialKey};
 
 /// The padding added to a request's `timeout_ms` to derive the socket read
 /// deadline, so the Rust side always waits strictly longer than the agent's own
@@ -46,22 +49,44 @@ const READ_TIMEOUT_PADDING_MS: u64 = 15_000;
 /// well over 30s to snapshot, so use a generous timeout.
 const DUMP_TREE_TIMEOUT_MS: u64 = 120_000;
 
+/// How many times a request is re-sent after a connection error, recovering
+/// the connection before each attempt, unless set with
+/// [`AgentSession::with_retries`].
+pub const DEFAULT_RETRIES: u32 = 1;
+
 // ---------------------------------------------------------------------------
 // Shared error mapping
 // ---------------------------------------------------------------------------
 
 /// Maps an [`AgentClientError`] to a [`DriverError`].
+///
+/// The agents' "Element with ... not found" errors become
+/// [`DriverError::ElementNotFound`]; every other agent error is
+/// [`DriverError::CommandFailed`].
 pub(crate) fn map_client_error(err: AgentClientError) -> DriverError {
     match err {
         AgentClientError::NotConnected => DriverError::NotConnected,
         AgentClientError::ConnectionFailed(msg) => DriverError::ConnectionLost(msg),
         AgentClientError::Io(e) => DriverError::Io(e),
         AgentClientError::Protocol(e) => DriverError::CommandFailed(e.to_string()),
-        AgentClientError::AgentError(msg) => DriverError::CommandFailed(msg),
+        AgentClientError::AgentError(msg) => match not_found_selector(&msg) {
+            Some(selector) => DriverError::ElementNotFound { selector },
+            None => DriverError::CommandFailed(msg),
+        },
         AgentClientError::Timeout => DriverError::Timeout,
     }
 }
 
+/// Extracts the selector description from an agent's not-found error.
+///
+/// Both agents report a missing element as `Element with <description> not
+/// found`, optionally followed by a timeout note (`within 5000ms (timeout)`).
+fn not_found_selector(message: &str) -> Option<String> {
+    let rest = message.strip_prefix("Element with ")?;
+    let end = rest.rfind(" not found")?;
+    Some(rest[..end].to_string())
+}
+
 /// Checks that the response is [`Response::Ok`] and returns a
 /// [`DriverError::CommandFailed`] if it is not.
 pub(crate) fn expect_ok(response: Response) -> Result<(), DriverError> {
@@ -73,6 +98,18 @@ pub(crate) fn expect_ok(response: Response) -> Result<(), DriverError> {
     }
 }
 
+/// Maps an agent's rejection of an opcode it cannot decode, which means the
+/// agent predates `op`, to [`DriverError::Unsupported`] so callers can tell
+/// an old agent from a failed command. Other errors pass through.
+pub(crate) fn reject_unknown_opcode(op: &str, err: DriverError) -> DriverError {
+    match err {
+        DriverError::CommandFailed(msg) if msg.contains("invalid opcode") => {
+            DriverError::Unsupported(op.to_string())
+        }
+        other => other,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // AgentTransport
 // ---------------------------------------------------------------------------
@@ -98,6 +135,9 @@ pub struct Recovered {
 /// *mechanism* (open a socket; optionally respawn the agent).
 #[async_trait]
 pub trait AgentTransport: Send + Sync + 'static {
+    /// The backend sessions over this transport report.
+    const BACKEND: DriverBackend;
+
     /// Open and heartbeat-verify a fresh [`AgentClient`] for this transport.
     async fn create_client(&self) -> Result<AgentClient, DriverError>;
 
@@ -144,6 +184,14 @@ pub struct AgentSession<T: AgentTransport> {
     pub(crate) recovery_count: AtomicU64,
     /// Remembered target bundle/package so it can be re-sent after recovery.
     pub(crate) target_bundle_id: Mutex<Option<String>>,
+    /// Screen scale factor from the last `screen_info` reply. Only the scale
+    /// is kept: size and orientation change whenever the device rotates.
+    /// Cleared on recovery, which may land on a different device.
+    pub(crate) screen_scale: Mutex<Option<f64>>,
+    /// Read timeout applied to every client this session installs.
+    pub(crate) read_timeout: Duration,
+    /// Recover-and-resend attempts after a connection error.
+    pub(crate) retries: u32,
 }
 
 impl<T: AgentTransport> AgentSession<T> {
@@ -158,9 +206,37 @@ impl<T: AgentTransport> AgentSession<T> {
             client: Mutex::new(None),
             recovery_count: AtomicU64::new(0),
             target_bundle_id: Mutex::new(None),
+            screen_scale: Mutex::new(None),
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            retries: DEFAULT_RETRIES,
         }
     }
 
+    /// Set how long a request may wait for the agent's response before it
+    /// fails with [`DriverError::Timeout`].
+    ///
+    /// Applies to the client installed by `connect` and to every client
+    /// installed by recovery. Requests that carry their own `timeout_ms` are
+    /// padded from that value instead. Defaults to [`DEFAULT_READ_TIMEOUT`].
+    pub fn with_read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    /// Set how many times a request is re-sent after a connection error, each
+    /// attempt preceded by the transport's recovery. `0` fails on the first
+    /// connection error. Defaults to [`DEFAULT_RETRIES`].
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Install `client` as the live connection, applying the read timeout.
+    async fn install_client(&self, mut client: AgentClient) {
+        client.set_read_timeout(self.read_timeout);
+        *self.client.lock().await = Some(client);
+    }
+
     /// Returns the number of successful recovery events since creation.
     ///
     /// The executor polls this to detect a mid-action reconnect and reset its
@@ -190,7 +266,8 @@ impl<T: AgentTransport> AgentSession<T> {
             client,
             restore_target,
         } = self.transport.recover().await?;
-        *self.client.lock().await = Some(client);
+        self.install_client(client).await;
+        *self.screen_scale.lock().await = None;
         if restore_target {
             self.restore_target().await?;
         }
@@ -216,18 +293,22 @@ impl<T: AgentTransport> AgentSession<T> {
         Ok(())
     }
 
-    /// Send a request, retrying once via the transport's recovery ladder on a
-    /// connection error (when the transport opts in).
+    /// Send a request, retrying up to [`retries`](Self::with_retries) times
+    /// via the transport's recovery ladder on a connection error (when the
+    /// transport opts in).
     async fn send(&self, request: &Request) -> Result<Response, DriverError> {
-        let result = self.send_raw(request).await;
-        match &result {
-            Err(e) if Self::is_connection_error(e) && self.transport.recovery_enabled() => {
-                warn!(error = %e, opcode = request.opcode_name(), "connection error, attempting recovery");
-                self.do_recover().await?;
-                self.send_raw(request).await
+        let mut result = self.send_raw(request).await;
+        for _ in 0..self.retries {
+            match &result {
+                Err(e) if Self::is_connection_error(e) && self.transport.recovery_enabled() => {
+                    warn!(error = %e, opcode = request.opcode_name(), "connection error, attempting recovery");
+                    self.do_recover().await?;
+                    result = self.send_raw(request).await;
+                }
+                _ => break,
             }
-            _ => result,
         }
+        result
     }
 
     /// Send a request without recovery wrapping.
@@ -245,23 +326,25 @@ impl<T: AgentTransport> AgentSession<T> {
         client.send(request).await.map_err(map_client_error)
     }
 
-    /// Send a request with a custom read timeout, retrying once via recovery on
-    /// a connection error. When `timeout_ms` is `None`, falls back to
-    /// [`send`](Self::send).
+    /// Send a request with a custom read timeout, retrying via recovery on a
+    /// connection error as [`send`](Self::send) does.
     async fn send_with_read_timeout(
         &self,
         request: &Request,
         timeout_ms: Option<u64>,
     ) -> Result<Response, DriverError> {
-        let result = self.send_raw_with_read_timeout(request, timeout_ms).await;
-        match &result {
-            Err(e) if Self::is_connection_error(e) && self.transport.recovery_enabled() => {
-                warn!(error = %e, opcode = request.opcode_name(), "connection error (with timeout), attempting recovery");
-                self.do_recover().await?;
-                self.send_raw_with_read_timeout(request, timeout_ms).await
+        let mut result = self.send_raw_with_read_timeout(request, timeout_ms).await;
+        for _ in 0..self.retries {
+            match &result {
+                Err(e) if Self::is_connection_error(e) && self.transport.recovery_enabled() => {
+                    warn!(error = %e, opcode = request.opcode_name(), "connection error (with timeout), attempting recovery");
+                    self.do_recover().await?;
+                    result = self.send_raw_with_read_timeout(request, timeout_ms).await;
+                }
+                _ => break,
             }
-            _ => result,
         }
+        result
     }
 
     /// Send a request with a custom read timeout, without recovery wrapping.
@@ -293,10 +376,14 @@ impl<T: AgentTransport> AgentSession<T> {
 
 #[async_trait]
 impl<T: AgentTransport> AutomationDriver for AgentSession<T> {
+    fn backend(&self) -> Option<DriverBackend> {
+        Some(T::BACKEND)
+    }
+
     #[instrument(skip(self), level = "debug")]
     async fn connect(&mut self) -> Result<(), DriverError> {
         let client = self.transport.create_client().await?;
-        *self.client.lock().await = Some(client);
+        self.install_client(client).await;
         Ok(())
     }
 
@@ -304,6 +391,24 @@ impl<T: AgentTransport> AutomationDriver for AgentSession<T> {
         self.client.try_lock().map(|g| g.is_some()).unwrap_or(false)
     }
 
+    /// Times a heartbeat without reconnecting, so a dead connection fails
+    /// instead of being measured together with its recovery. The clock starts
+    /// after the client lock is held, so a concurrent command does not count
+    /// toward the latency.
+    #[instrument(skip(self), level = "debug")]
+    async fn ping(&self) -> Result<Duration, DriverError> {
+        let mut guard = self.client.lock().await;
+        let client = guard.as_mut().ok_or(DriverError::NotConnected)?;
+        let start = Instant::now();
+        let response = client
+            .send(&Request::Heartbeat)
+            .await
+            .map_err(map_client_error)?;
+        let elapsed = start.elapsed();
+        expect_ok(response)?;
+        Ok(elapsed)
+    }
+
     fn recovery_count(&self) -> u64 {
         self.recovery_count.load(Ordering::Relaxed)
     }
@@ -379,6 +484,45 @@ impl<T: AgentTransport> AutomationDriver for AgentSession<T> {
         expect_ok(response)
     }
 
+    #[instrument(skip(self), level = "debug")]
+    async fn multi_tap(&self, finger_count: u8, x: i32, y: i32) -> Result<(), DriverError> {
+        validate_finger_count(finger_count)?;
+        let response = self
+            .send(&Request::MultiTap { finger_count, x, y })
+            .await
+            .map_err(|e| reject_unknown_opcode("multi_tap", e))?;
+        expect_ok(response)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn drag(
+        &self,
+        start_x: i32,
+        start_y: i32,
+        end_x: i32,
+        end_y: i32,
+        hold_duration: f64,
+        move_duration: f64,
+    ) -> Result<(), DriverError> {
+        // The agent replies only once the gesture finishes, so wait at least
+        // as long as it takes.
+        let gesture_ms = ((hold_duration + move_duration) * 1000.0).ceil() as u64;
+        let response = self
+            .send_with_read_timeout(
+                &Request::Drag {
+                    start_x,
+                    start_y,
+                    end_x,
+                    end_y,
+                    hold_duration,
+                    move_duration,
+                },
+                Some(gesture_ms),
+            )
+            .await?;
+        expect_ok(response)
+    }
+
     #[instrument(skip(self), level = "debug")]
     async fn type_text(&self, text: &str) -> Result<(), DriverError> {
         let response = self
@@ -389,6 +533,66 @@ impl<T: AgentTransport> AutomationDriver for AgentSession<T> {
         expect_ok(response)
     }
 
+    #[instrument(skip(self), level = "debug")]
+    async fn send_key(&self, key: SpecialKey) -> Result<(), DriverError> {
+        let response = self.send(&Request::SendKey { key }).await;
+        match response.map_err(|e| reject_unknown_opcode("send_key", e)) {
+            // An agent that predates the opcode can still type the keys that
+            // have a control character.
+            Err(DriverError::Unsupported(op)) => {
+                let text = match key {
+                    SpecialKey::Return => "\n",
+                    SpecialKey::Tab => "\t",
+                    SpecialKey::Delete => "\u{8}",
+                    _ => return Err(DriverError::Unsupported(format!("{op} {key}"))),
+                };
+                self.type_text(text).await
+            }
+            other => expect_ok(other?),
+        }
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn paste(&self) -> Result<(), DriverError> {
+        let response = self
+            .send(&Request::Paste)
+            .await
+            .map_err(|e| reject_unknown_opcode("paste", e))?;
+        expect_ok(response)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn clear_text(&self, selector: &str, by_label: bool) -> Result<(), DriverError> {
+        let request = Request::ClearText {
+            selector: selector.to_string(),
+            by_label,
+        };
+        let response = self
+            .send(&request)
+            .await
+            .map_err(|e| reject_unknown_opcode("clear_text", e))?;
+        expect_ok(response)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn long_press_element(
+        &self,
+        selector: &str,
+        by_label: bool,
+        duration: f64,
+    ) -> Result<(), DriverError> {
+        let request = Request::LongPressElement {
+            selector: selector.to_string(),
+            by_label,
+            duration,
+        };
+        let response = self
+            .send(&request)
+            .await
+            .map_err(|e| reject_unknown_opcode("long_press_element", e))?;
+        expect_ok(response)
+    }
+
     #[instrument(skip(self), level = "debug")]
     async fn dump_tree(&self) -> Result<Vec<UIElement>, DriverError> {
         let response = self
@@ -407,6 +611,72 @@ impl<T: AgentTransport> AutomationDriver for AgentSession<T> {
         }
     }
 
+    #[instrument(skip(self), level = "debug")]
+    async fn dump_tree_filtered(
+        &self,
+        filter: &ElementFilter,
+    ) -> Result<Vec<UIElement>, DriverError> {
+        let request = Request::DumpTreeFiltered {
+            types: filter.types.clone(),
+            visible_only: filter.visible_only,
+            max_depth: filter.max_depth,
+        };
+        let response = self
+            .send_with_read_timeout(&request, Some(DUMP_TREE_TIMEOUT_MS))
+            .await
+            .map_err(|e| reject_unknown_opcode("dump_tree_filtered", e))?;
+        match response {
+            Response::Tree { json } => {
+                let elements: Vec<UIElement> = serde_json::from_str(&json)
+                    .map_err(|e| DriverError::JsonParse(e.to_string()))?;
+                debug!(element_count = elements.len(), "filtered tree dumped");
+                Ok(elements)
+            }
+            other => Err(DriverError::CommandFailed(format!(
+                "unexpected response: {other:?}"
+            ))),
+        }
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn find_all(
+        &self,
+        selector: &str,
+        by_label: bool,
+        element_type: Option<&str>,
+    ) -> Result<Vec<UIElement>, DriverError> {
+        let request = Request::FindAll {
+            selector: selector.to_string(),
+            by_label,
+            element_type: element_type.map(str::to_string),
+        };
+        let response = match self
+            .send_with_read_timeout(&request, Some(DUMP_TREE_TIMEOUT_MS))
+            .await
+            .map_err(|e| reject_unknown_opcode("find_all", e))
+        {
+            // An agent that predates the opcode gets a full dump searched
+            // instead.
+            Err(DriverError::Unsupported(_)) => {
+                debug!("agent lacks find_all; searching the full tree");
+                let tree = self.dump_tree().await?;
+                return Ok(find_all_elements(&tree, selector, by_label, element_type));
+            }
+            other => other?,
+        };
+        match response {
+            Response::Tree { json } => {
+                let elements: Vec<UIElement> = serde_json::from_str(&json)
+                    .map_err(|e| DriverError::JsonParse(e.to_string()))?;
+                debug!(match_count = elements.len(), "elements found");
+                Ok(elements)
+            }
+            other => Err(DriverError::CommandFailed(format!(
+                "unexpected response: {other:?}"
+            ))),
+        }
+    }
+
     async fn get_element_value(&self, identifier: &str) -> Result<Option<String>, DriverError> {
         let response = self
             .send(&Request::GetValue {
@@ -463,9 +733,13 @@ impl<T: AgentTransport> AutomationDriver for AgentSession<T> {
         }
     }
 
-    #[instrument(skip(self), level = "debug")]
     async fn screenshot(&self) -> Result<Vec<u8>, DriverError> {
-        let response = self.send(&Request::Screenshot).await?;
+        self.screenshot_with(ScreenshotFormat::Png).await
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn screenshot_with(&self, format: ScreenshotFormat) -> Result<Vec<u8>, DriverError> {
+        let response = self.send(&Request::Screenshot { format }).await?;
         match response {
             Response::Screenshot { data } => {
                 debug!(bytes = data.len(), "screenshot captured");
@@ -682,4 +956,106 @@ impl<T: AgentTransport> AutomationDriver for AgentSession<T> {
             ))),
         }
     }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn query_state(&self) -> Result<AgentState, DriverError> {
+        match self.send(&Request::GetState).await? {
+            Response::State {
+                target_bundle,
+                foreground_app,
+            } => Ok(AgentState {
+                target_bundle,
+                foreground_app,
+            }),
+            other => Err(DriverError::CommandFailed(format!(
+                "unexpected response: {other:?}"
+            ))),
+        }
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn screen_info(&self) -> Result<ScreenInfo, DriverError> {
+        match self.send(&Request::GetScreenInfo).await? {
+            Response::ScreenInfo {
+                width,
+                height,
+                scale,
+                orientation,
+            } => {
+                let info = ScreenInfo {
+                    width,
+                    height,
+                    scale,
+                    // Face up/down and unknown have no interface orientation
+                    orientation: orientation.parse().ok(),
+                };
+                *self.screen_scale.lock().await = Some(scale);
+                Ok(info)
+            }
+            other => Err(DriverError::CommandFailed(format!(
+                "unexpected response: {other:?}"
+            ))),
+        }
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn set_orientation(&self, orientation: Orientation) -> Result<(), DriverError> {
+        let response = self
+            .send(&Request::SetOrientation {
+                orientation: orientation.wire_name().to_string(),
+            })
+            .await?;
+        expect_ok(response)
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn screen_scale(&self) -> Result<f64, DriverError> {
+        if let Some(scale) = *self.screen_scale.lock().await {
+            return Ok(scale);
+        }
+        Ok(self.screen_info().await?.scale)
+    }
+
+    #[instrument(skip(self, requests), fields(count = requests.len()), level = "debug")]
+    async fn send_batch(
+        &self,
+        requests: Vec<Request>,
+    ) -> Result<Vec<Result<Response, DriverError>>, DriverError> {
+        // The agent replies only once every gesture in the batch finishes.
+        let gesture_secs: f64 = requests
+            .iter()
+            .map(|request| match request {
+                Request::Swipe { duration, .. } => duration.unwrap_or(0.0),
+                Request::LongPress { duration, .. }
+                | Request::LongPressElement { duration, .. } => *duration,
+                Request::Drag {
+                    hold_duration,
+                    move_duration,
+                    ..
+                } => hold_duration + move_duration,
+                _ => 0.0,
+            })
+            .sum();
+        let gesture_ms = (gesture_secs * 1000.0).ceil() as u64;
+        let request = Request::Batch { requests };
+        match self
+            .send_with_read_timeout(&request, (gesture_ms > 0).then_some(gesture_ms))
+            .await
+            .map_err(|e| reject_unknown_opcode("send_batch", e))
+        {
+            Ok(Response::Batch { responses }) => Ok(responses
+                .into_iter()
+                .map(|response| match response {
+                    Response::Error { message } => {
+                        Err(map_client_error(AgentClientError::AgentError(message)))
+                    }
+                    other => Ok(other),
+                })
+                .collect()),
+            Ok(other) => Err(DriverError::CommandFailed(format!(
+                "unexpected response: {other:?}"
+            ))),
+            Err(e) => Err(e),
+        }
+    }
 }
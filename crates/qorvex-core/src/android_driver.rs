@@ -65,7 +65,7 @@ use tokio::sync::Mutex;
 use crate::adb_forward::{AdbForward, AdbForwardError};
 use crate::agent_client::AgentClient;
 use crate::agent_session::{map_client_error, AgentSession, AgentTransport};
-use crate::driver::DriverError;
+use crate::driver::{DriverBackend, DriverError};
 
 /// The default device-side TCP port the Kotlin agent listens on (matches the
 /// agent's `qorvex_port` default in story #84 / ADR-2).
@@ -150,6 +150,8 @@ impl AndroidTransport {
 
 #[async_trait]
 impl AgentTransport for AndroidTransport {
+    const BACKEND: DriverBackend = DriverBackend::Android;
+
     /// Establish (or re-establish) the `adb forward` and open an
     /// [`AgentClient`] against `127.0.0.1:<local_port>`, verified by heartbeat.
     ///
@@ -752,4 +754,17 @@ mod tests {
         // No recovery was attempted.
         assert_eq!(driver.recovery_count(), 0);
     }
+
+    #[tokio::test]
+    async fn not_found_timeout_is_element_not_found() {
+        let driver = driver_with_mock(Response::Error {
+            message: "Element with ID 'submit' not found within 5000ms (timeout)".to_string(),
+        })
+        .await;
+        match driver.tap_element_with_timeout("submit", Some(5000)).await {
+            Err(DriverError::ElementNotFound { selector }) => assert_eq!(selector, "ID 'submit'"),
+            other => panic!("expected ElementNotFound, got {other:?}"),
+        }
+        assert_eq!(driver.recovery_count(), 0);
+    }
 }
@@ -26,6 +26,24 @@ use crate::ipc::qorvex_dir;
 
 const CONFIG_FILENAME: &str = "config.json";
 
+/// Port the Swift agent listens on when nothing else is configured.
+pub const DEFAULT_AGENT_PORT: u16 = 8080;
+
+/// Environment variable that overrides [`QorvexConfig::agent_port`].
+pub const AGENT_PORT_ENV: &str = "QORVEX_AGENT_PORT";
+
+/// Reads [`AGENT_PORT_ENV`], ignoring it when unset or not a valid non-zero
+/// port.
+pub fn agent_port_from_env() -> Option<u16> {
+    std::env::var(AGENT_PORT_ENV)
+        .ok()
+        .and_then(|v| parse_agent_port(&v))
+}
+
+pub(crate) fn parse_agent_port(value: &str) -> Option<u16> {
+    value.trim().parse::<u16>().ok().filter(|&port| port > 0)
+}
+
 /// Persistent qorvex configuration.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct QorvexConfig {
@@ -34,7 +52,8 @@ pub struct QorvexConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub agent_source_dir: Option<PathBuf>,
 
-    /// TCP port the Swift agent listens on. Defaults to 8080 if absent.
+    /// TCP port the Swift agent listens on. Defaults to 8080 if absent;
+    /// [`AGENT_PORT_ENV`] takes precedence when set.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub agent_port: Option<u16>,
 
@@ -117,9 +136,12 @@ impl std::fmt::Display for AndroidConfigError {
 impl std::error::Error for AndroidConfigError {}
 
 impl QorvexConfig {
-    /// Returns the configured agent port, defaulting to 8080.
+    /// Returns the agent port: [`AGENT_PORT_ENV`] if set, then the
+    /// configured `agent_port`, then [`DEFAULT_AGENT_PORT`].
     pub fn agent_port(&self) -> u16 {
-        self.agent_port.unwrap_or(8080)
+        agent_port_from_env()
+            .or(self.agent_port)
+            .unwrap_or(DEFAULT_AGENT_PORT)
     }
 
     /// Load config from `~/.qorvex/config.json`.
@@ -271,6 +293,14 @@ mod tests {
         let _ = config;
     }
 
+    #[test]
+    fn parse_agent_port_rejects_invalid_values() {
+        assert_eq!(parse_agent_port(" 9123 "), Some(9123));
+        assert_eq!(parse_agent_port("0"), None);
+        assert_eq!(parse_agent_port("70000"), None);
+        assert_eq!(parse_agent_port("http"), None);
+    }
+
     #[test]
     fn effective_agent_source_dir_prefers_explicit() {
         let config = QorvexConfig {
@@ -0,0 +1,231 @@
+//! Short summaries of a screen's element tree.
+//!
+//! [`ScreenSummary`] condenses a `GetScreenInfo` result into what a reader
+//! wants first: the screen's title, how many elements of each type it has and
+//! which buttons can be tapped. It backs `qorvex describe` and the REPL's
+//! `describe` command.
+//!
+//! # Example
+//!
+//! ```
+//! use qorvex_core::describe::ScreenSummary;
+//! use qorvex_core::element::UIElement;
+//!
+//! let elements: Vec<UIElement> = serde_json::from_str(r#"[
+//!     {"type": "NavigationBar", "AXLabel": "Settings", "children": [
+//!         {"type": "Button", "AXLabel": "Back"}
+//!     ]}
+//! ]"#).unwrap();
+//! let summary = ScreenSummary::from_elements(&elements);
+//! assert_eq!(summary.title.as_deref(), Some("Settings"));
+//! assert_eq!(summary.buttons, ["Back"]);
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::element::UIElement;
+
+/// A summary of the elements on one screen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenSummary {
+    /// The screen's title: the navigation bar's label, or else the most
+    /// prominent (tallest, then first) labelled `StaticText`.
+    pub title: Option<String>,
+    /// Number of elements in the tree, children included.
+    pub element_count: usize,
+    /// Number of elements of each type; untyped elements count as `Unknown`.
+    pub type_counts: BTreeMap<String, usize>,
+    /// Label (or identifier) of each button that can be tapped, in tree
+    /// order. Disabled and non-hittable buttons are left out.
+    pub buttons: Vec<String>,
+}
+
+impl ScreenSummary {
+    /// Summarizes an element tree.
+    pub fn from_elements(elements: &[UIElement]) -> Self {
+        let mut all = Vec::new();
+        flatten(elements, &mut all);
+
+        let mut type_counts = BTreeMap::new();
+        for elem in &all {
+            let element_type = elem.element_type.as_deref().unwrap_or("Unknown");
+            *type_counts.entry(element_type.to_string()).or_insert(0) += 1;
+        }
+
+        let buttons = all
+            .iter()
+            .filter(|e| e.element_type.as_deref() == Some("Button"))
+            .filter(|e| e.enabled != Some(false) && e.hittable != Some(false))
+            .filter_map(|e| name(e))
+            .map(str::to_string)
+            .collect();
+
+        Self {
+            title: find_title(&all).map(str::to_string),
+            element_count: all.len(),
+            type_counts,
+            buttons,
+        }
+    }
+
+    /// Element types with their counts, most common first.
+    pub fn counts_by_frequency(&self) -> Vec<(&str, usize)> {
+        let mut counts: Vec<_> = self
+            .type_counts
+            .iter()
+            .map(|(t, &n)| (t.as_str(), n))
+            .collect();
+        // Stable sort keeps ties in alphabetical order
+        counts.sort_by_key(|&(_, n)| std::cmp::Reverse(n));
+        counts
+    }
+}
+
+impl std::fmt::Display for ScreenSummary {
+    /// Formats the summary as three lines: title, counts and buttons.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Screen: {}",
+            self.title.as_deref().unwrap_or("(untitled)")
+        )?;
+        let counts: Vec<String> = self
+            .counts_by_frequency()
+            .iter()
+            .map(|(t, n)| format!("{} {}", n, t))
+            .collect();
+        if counts.is_empty() {
+            writeln!(f, "{} elements", self.element_count)?;
+        } else {
+            writeln!(f, "{} elements: {}", self.element_count, counts.join(", "))?;
+        }
+        if self.buttons.is_empty() {
+            write!(f, "Buttons: (none)")
+        } else {
+            write!(f, "Buttons: {}", self.buttons.join(", "))
+        }
+    }
+}
+
+/// Collects every element of the tree, depth first.
+fn flatten<'a>(elements: &'a [UIElement], out: &mut Vec<&'a UIElement>) {
+    for elem in elements {
+        out.push(elem);
+        flatten(&elem.children, out);
+    }
+}
+
+/// The non-empty label of an element, or else its identifier.
+fn name(elem: &UIElement) -> Option<&str> {
+    [&elem.label, &elem.identifier]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .find(|s| !s.is_empty())
+}
+
+fn find_title<'a>(all: &[&'a UIElement]) -> Option<&'a str> {
+    if let Some(title) = all
+        .iter()
+        .filter(|e| e.element_type.as_deref() == Some("NavigationBar"))
+        .find_map(|e| name(e))
+    {
+        return Some(title);
+    }
+    let height = |e: &UIElement| e.frame.as_ref().map_or(0.0, |f| f.height);
+    all.iter()
+        .filter(|e| e.element_type.as_deref() == Some("StaticText"))
+        .filter_map(|e| {
+            e.label
+                .as_deref()
+                .filter(|l| !l.is_empty())
+                .map(|l| (l, height(e)))
+        })
+        // The first of the tallest: `max_by` would keep the last
+        .fold(None, |best: Option<(&str, f64)>, (label, h)| match best {
+            Some((_, best_h)) if best_h >= h => best,
+            _ => Some((label, h)),
+        })
+        .map(|(label, _)| label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Vec<UIElement> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    const LOGIN: &str = r#"[
+        {"type": "Window", "children": [
+            {"type": "StaticText", "AXLabel": "Welcome back",
+             "frame": {"x": 20, "y": 80, "width": 300, "height": 34}},
+            {"type": "StaticText", "AXLabel": "Sign in to continue",
+             "frame": {"x": 20, "y": 120, "width": 300, "height": 20}},
+            {"type": "TextField", "AXUniqueId": "email"},
+            {"type": "SecureTextField", "AXUniqueId": "password"},
+            {"type": "Button", "AXUniqueId": "login-button", "AXLabel": "Log In"},
+            {"type": "Button", "AXUniqueId": "forgot", "AXLabel": ""},
+            {"type": "Button", "AXLabel": "Sign Up", "enabled": false},
+            {"type": "Button", "AXLabel": "Hidden", "hittable": false}
+        ]}
+    ]"#;
+
+    #[test]
+    fn counts_every_element_by_type() {
+        let summary = ScreenSummary::from_elements(&parse(LOGIN));
+        assert_eq!(summary.element_count, 9);
+        assert_eq!(summary.type_counts["Button"], 4);
+        assert_eq!(summary.type_counts["StaticText"], 2);
+        assert_eq!(summary.type_counts["Window"], 1);
+        assert_eq!(
+            summary.counts_by_frequency()[..3],
+            [("Button", 4), ("StaticText", 2), ("SecureTextField", 1)]
+        );
+    }
+
+    #[test]
+    fn title_is_the_tallest_static_text_without_a_navigation_bar() {
+        let summary = ScreenSummary::from_elements(&parse(LOGIN));
+        assert_eq!(summary.title.as_deref(), Some("Welcome back"));
+    }
+
+    #[test]
+    fn navigation_bar_label_wins() {
+        let elements = parse(
+            r#"[
+                {"type": "StaticText", "AXLabel": "Large text",
+                 "frame": {"x": 0, "y": 0, "width": 300, "height": 60}},
+                {"type": "NavigationBar", "AXUniqueId": "Settings"}
+            ]"#,
+        );
+        let summary = ScreenSummary::from_elements(&elements);
+        assert_eq!(summary.title.as_deref(), Some("Settings"));
+    }
+
+    #[test]
+    fn lists_tappable_buttons_by_name() {
+        let summary = ScreenSummary::from_elements(&parse(LOGIN));
+        assert_eq!(summary.buttons, ["Log In", "forgot"]);
+    }
+
+    #[test]
+    fn displays_three_lines() {
+        let summary = ScreenSummary::from_elements(&parse(LOGIN));
+        let text = summary.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "Screen: Welcome back");
+        assert!(
+            lines[1].starts_with("9 elements: 4 Button, 2 StaticText"),
+            "{}",
+            lines[1]
+        );
+        assert_eq!(lines[2], "Buttons: Log In, forgot");
+
+        let empty = ScreenSummary::from_elements(&[]).to_string();
+        assert_eq!(empty, "Screen: (untitled)\n0 elements\nButtons: (none)");
+    }
+}
@@ -12,17 +12,28 @@
 //!
 //! ```no_run
 //! use qorvex_core::driver::DriverConfig;
+//! use std::time::Duration;
 //!
 //! // Use a TCP-based agent (simulator)
 //! let config = DriverConfig::Agent {
 //!     host: "localhost".to_string(),
 //!     port: 9123,
+//!     read_timeout: None,
+//!     retries: None,
+//!     poll_interval: None,
+//!     fuzzy_selectors: None,
+//!     lifecycle: None,
 //! };
 //!
 //! // Use a physical device via USB tunnel
 //! let config = DriverConfig::Device {
 //!     udid: "00008110-001A0C123456789A".to_string(),
 //!     device_port: 8080,
+//!     read_timeout: None,
+//!     retries: None,
+//!     poll_interval: None,
+//!     fuzzy_selectors: None,
+//!     lifecycle: None,
 //! };
 //!
 //! // Use an Android device (emulator or physical) via adb forward
@@ -30,14 +41,47 @@
 //!     serial: "emulator-5554".to_string(),
 //!     local_port: 9123,
 //!     device_port: 8080,
+//!     // Allow slow emulators a minute per request
+//!     read_timeout: Some(Duration::from_secs(60)),
+//!     retries: None,
+//!     poll_interval: None,
+//!     fuzzy_selectors: None,
 //! };
+//!
+//! // Use a simulator or device through Facebook's idb CLI, without the agent
+//! let config = DriverConfig::Idb {
+//!     udid: "12345678-1234-1234-1234-123456789ABC".to_string(),
+//!     poll_interval: None,
+//!     fuzzy_selectors: None,
+//! };
+//! ```
+//!
+//! A config can also be loaded from a TOML or JSON file with
+//! [`DriverConfig::from_file`]:
+//!
+//! ```toml
+//! backend = "agent"
+//! host = "localhost"
+//! port = 8080
+//! read_timeout_ms = 60000
 //! ```
+//!
+//! Build the driver with [`create`], the one entry point for constructing a
+//! backend from a config. A new backend adds a [`DriverBackend`], a
+//! [`DriverConfig`] variant and an arm in `create`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::agent_lifecycle::AgentLifecycle;
 use crate::element::UIElement;
+use crate::protocol::{Request, Response, ScreenshotFormat, SpecialKey};
+use crate::selector::{resolve, resolve_all, Selector};
 
 /// Errors that can occur during automation driver operations.
 ///
@@ -73,19 +117,83 @@ pub enum DriverError {
     /// A USB tunnel operation failed.
     #[error("USB tunnel error: {0}")]
     UsbTunnel(#[from] crate::usb_tunnel::UsbTunnelError),
+
+    /// No element matched the selector.
+    ///
+    /// `selector` describes what was looked up, e.g. `ID 'login-button'`.
+    #[error("Element with {selector} not found")]
+    ElementNotFound {
+        /// The selector, as described by the backend.
+        selector: String,
+    },
+
+    /// The backend does not implement the requested operation.
+    #[error("Unsupported by backend: {0}")]
+    Unsupported(String),
+
+    /// The operation was cancelled before it completed.
+    #[error("Action cancelled")]
+    Cancelled,
+
+    /// A [`DriverConfig`] holds a value no backend can use, such as an empty
+    /// UDID or port 0.
+    #[error("Invalid driver config: {0}")]
+    InvalidConfig(String),
 }
 
 /// Configuration for selecting an automation backend at runtime.
-#[derive(Debug, Clone)]
+///
+/// Serializes with the variant as a `backend` key (`agent`, `device`,
+/// `core-device`, `tunneld`, `android` or `idb`) next to the variant's fields,
+/// and read timeouts and poll intervals as `read_timeout_ms` and
+/// `poll_interval_ms`. Unknown keys are rejected so a typo is not silently
+/// ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "kebab-case", deny_unknown_fields)]
 pub enum DriverConfig {
     /// Use a TCP-based Swift agent for automation (direct connection).
     ///
-    /// Typically used for simulators, where the agent is reachable on localhost.
+    /// Typically used for simulators, where the agent is reachable on localhost,
+    /// and for devices reached by network host name.
     Agent {
         /// The hostname or IP address of the agent.
+        #[serde(default = "default_host")]
         host: String,
         /// The TCP port the agent is listening on.
+        #[serde(default = "default_agent_port")]
         port: u16,
+        /// How long each request waits for a response; `None` uses
+        /// [`DEFAULT_READ_TIMEOUT`](crate::agent_client::DEFAULT_READ_TIMEOUT).
+        #[serde(
+            rename = "read_timeout_ms",
+            default,
+            with = "optional_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        read_timeout: Option<Duration>,
+        /// How many times a request is re-sent after a connection error,
+        /// recovering the connection before each attempt; `None` uses
+        /// [`DEFAULT_RETRIES`](crate::agent_session::DEFAULT_RETRIES).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        retries: Option<u32>,
+        /// Time between polls of wait actions that don't set their own; `None`
+        /// uses [`DEFAULT_POLL_INTERVAL`](crate::executor::DEFAULT_POLL_INTERVAL).
+        #[serde(
+            rename = "poll_interval_ms",
+            default,
+            with = "optional_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        poll_interval: Option<Duration>,
+        /// Whether a selector that matches nothing is retried with the closest
+        /// element (see [`ActionExecutor::with_fuzzy_selectors`](crate::executor::ActionExecutor::with_fuzzy_selectors));
+        /// `None` keeps the executor's setting.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fuzzy_selectors: Option<bool>,
+        /// Restarts the agent after a crash; attached with
+        /// [`with_lifecycle`](DriverConfig::with_lifecycle), never read from a file.
+        #[serde(skip)]
+        lifecycle: Option<Arc<AgentLifecycle>>,
     },
     /// Use a Swift agent on a physical device via USB tunnel.
     ///
@@ -94,7 +202,116 @@ pub enum DriverConfig {
         /// The UDID of the physical device.
         udid: String,
         /// The TCP port the agent is listening on (on the device, typically 8080).
+        #[serde(default = "default_agent_port")]
         device_port: u16,
+        /// How long each request waits for a response; `None` uses the default.
+        #[serde(
+            rename = "read_timeout_ms",
+            default,
+            with = "optional_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        read_timeout: Option<Duration>,
+        /// How many times a request is re-sent after a connection error;
+        /// `None` uses the default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        retries: Option<u32>,
+        /// Time between polls of wait actions that don't set their own; `None`
+        /// uses the default.
+        #[serde(
+            rename = "poll_interval_ms",
+            default,
+            with = "optional_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        poll_interval: Option<Duration>,
+        /// Whether a selector that matches nothing is retried with the closest
+        /// element (see [`ActionExecutor::with_fuzzy_selectors`](crate::executor::ActionExecutor::with_fuzzy_selectors));
+        /// `None` keeps the executor's setting.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fuzzy_selectors: Option<bool>,
+        /// Restarts the agent after a crash; attached with
+        /// [`with_lifecycle`](DriverConfig::with_lifecycle), never read from a file.
+        #[serde(skip)]
+        lifecycle: Option<Arc<AgentLifecycle>>,
+    },
+    /// Use a Swift agent on a physical device via the native CoreDevice
+    /// tunnel (iOS 17+).
+    CoreDevice {
+        /// The UDID of the physical device.
+        udid: String,
+        /// The TCP port the agent is listening on (on the device, typically 8080).
+        #[serde(default = "default_agent_port")]
+        port: u16,
+        /// How long each request waits for a response; `None` uses the default.
+        #[serde(
+            rename = "read_timeout_ms",
+            default,
+            with = "optional_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        read_timeout: Option<Duration>,
+        /// How many times a request is re-sent after a connection error;
+        /// `None` uses the default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        retries: Option<u32>,
+        /// Time between polls of wait actions that don't set their own; `None`
+        /// uses the default.
+        #[serde(
+            rename = "poll_interval_ms",
+            default,
+            with = "optional_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        poll_interval: Option<Duration>,
+        /// Whether a selector that matches nothing is retried with the closest
+        /// element (see [`ActionExecutor::with_fuzzy_selectors`](crate::executor::ActionExecutor::with_fuzzy_selectors));
+        /// `None` keeps the executor's setting.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fuzzy_selectors: Option<bool>,
+        /// Restarts the agent after a crash; attached with
+        /// [`with_lifecycle`](DriverConfig::with_lifecycle), never read from a file.
+        #[serde(skip)]
+        lifecycle: Option<Arc<AgentLifecycle>>,
+    },
+    /// Use a Swift agent on a physical device via a pymobiledevice3 `tunneld`
+    /// tunnel.
+    Tunneld {
+        /// The tunnel address reported by tunneld (typically IPv6 link-local).
+        tunnel_address: String,
+        /// The TCP port the agent is listening on (on the device, typically 8080).
+        #[serde(default = "default_agent_port")]
+        agent_port: u16,
+        /// How long each request waits for a response; `None` uses the default.
+        #[serde(
+            rename = "read_timeout_ms",
+            default,
+            with = "optional_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        read_timeout: Option<Duration>,
+        /// How many times a request is re-sent after a connection error;
+        /// `None` uses the default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        retries: Option<u32>,
+        /// Time between polls of wait actions that don't set their own; `None`
+        /// uses the default.
+        #[serde(
+            rename = "poll_interval_ms",
+            default,
+            with = "optional_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        poll_interval: Option<Duration>,
+        /// Whether a selector that matches nothing is retried with the closest
+        /// element (see [`ActionExecutor::with_fuzzy_selectors`](crate::executor::ActionExecutor::with_fuzzy_selectors));
+        /// `None` keeps the executor's setting.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fuzzy_selectors: Option<bool>,
+        /// Restarts the agent after a crash; attached with
+        /// [`with_lifecycle`](DriverConfig::with_lifecycle), never read from a file.
+        #[serde(skip)]
+        lifecycle: Option<Arc<AgentLifecycle>>,
     },
     /// Use a Kotlin UiAutomator agent on an Android device (emulator or physical)
     /// reached over an `adb forward` TCP tunnel.
@@ -110,231 +327,670 @@ pub enum DriverConfig {
         /// The host-side localhost TCP port that `adb forward` binds.
         local_port: u16,
         /// The agent's TCP port inside the device (typically 8080).
+        #[serde(default = "default_agent_port")]
         device_port: u16,
+        /// How long each request waits for a response; `None` uses the default.
+        #[serde(
+            rename = "read_timeout_ms",
+            default,
+            with = "optional_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        read_timeout: Option<Duration>,
+        /// How many times a request is re-sent after a connection error;
+        /// `None` uses the default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        retries: Option<u32>,
+        /// Time between polls of wait actions that don't set their own; `None`
+        /// uses the default.
+        #[serde(
+            rename = "poll_interval_ms",
+            default,
+            with = "optional_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        poll_interval: Option<Duration>,
+        /// Whether a selector that matches nothing is retried with the closest
+        /// element (see [`ActionExecutor::with_fuzzy_selectors`](crate::executor::ActionExecutor::with_fuzzy_selectors));
+        /// `None` keeps the executor's setting.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fuzzy_selectors: Option<bool>,
+    },
+    /// Drive an iOS simulator or device through the `idb` CLI instead of the
+    /// Swift agent. See [`IdbDriver`](crate::idb_driver::IdbDriver).
+    Idb {
+        /// The UDID of the simulator or device.
+        udid: String,
+        /// Time between polls of wait actions that don't set their own; `None`
+        /// uses the default.
+        #[serde(
+            rename = "poll_interval_ms",
+            default,
+            with = "optional_millis",
+            skip_serializing_if = "Option::is_none"
+        )]
+        poll_interval: Option<Duration>,
+        /// Whether a selector that matches nothing is retried with the closest
+        /// element (see [`ActionExecutor::with_fuzzy_selectors`](crate::executor::ActionExecutor::with_fuzzy_selectors));
+        /// `None` keeps the executor's setting.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fuzzy_selectors: Option<bool>,
     },
 }
 
-/// Returns true if the pattern contains glob wildcard characters (`*` or `?`).
-fn has_wildcard(pattern: &str) -> bool {
-    pattern.contains('*') || pattern.contains('?')
-}
+impl DriverConfig {
+    /// The backend this config builds.
+    pub fn backend(&self) -> DriverBackend {
+        match self {
+            DriverConfig::Agent { .. }
+            | DriverConfig::Device { .. }
+            | DriverConfig::CoreDevice { .. }
+            | DriverConfig::Tunneld { .. } => DriverBackend::Agent,
+            DriverConfig::Android { .. } => DriverBackend::Android,
+            DriverConfig::Idb { .. } => DriverBackend::Idb,
+        }
+    }
 
-/// Matches a string against a glob pattern with `*` (any chars) and `?` (single char).
-///
-/// When the pattern has no wildcards, falls back to exact equality.
-fn glob_match(pattern: &str, text: &str) -> bool {
-    if !has_wildcard(pattern) {
-        return pattern == text;
+    /// Loads a config from a `.toml` or `.json` file, then applies
+    /// environment overrides (see [`with_env_overrides`](Self::with_env_overrides)).
+    ///
+    /// Precedence is environment, then the file, then the field defaults
+    /// (`localhost`, port [`DEFAULT_AGENT_PORT`](crate::config::DEFAULT_AGENT_PORT),
+    /// and the driver and executor defaults for the read timeout, retries and
+    /// poll interval).
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigFileError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigFileError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let config = Self::parse_file(path, &contents)?;
+        Ok(config.with_env_overrides(|name| std::env::var(name).ok()))
     }
 
-    let pat: Vec<char> = pattern.chars().collect();
-    let txt: Vec<char> = text.chars().collect();
-    let (plen, tlen) = (pat.len(), txt.len());
+    /// Parses `contents` in the format named by `path`'s extension.
+    fn parse_file(path: &Path, contents: &str) -> Result<Self, ConfigFileError> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        let parsed = match extension.as_deref() {
+            Some("toml") => toml::from_str(contents).map_err(|e| e.message().to_string()),
+            Some("json") => serde_json::from_str(contents).map_err(|e| e.to_string()),
+            _ => {
+                return Err(ConfigFileError::UnsupportedFormat {
+                    path: path.to_path_buf(),
+                })
+            }
+        };
+        parsed.map_err(|message| ConfigFileError::Parse {
+            path: path.to_path_buf(),
+            message,
+        })
+    }
 
-    // dp[i][j] = pattern[..i] matches text[..j]
-    let mut dp = vec![vec![false; tlen + 1]; plen + 1];
-    dp[0][0] = true;
+    /// Overrides fields from environment variables, read through `var`:
+    /// [`AGENT_PORT_ENV`](crate::config::AGENT_PORT_ENV) sets the agent port,
+    /// [`READ_TIMEOUT_ENV`] the read timeout, [`RETRIES_ENV`] the retries,
+    /// [`POLL_INTERVAL_ENV`] the poll interval and
+    /// [`FUZZY_SELECTORS_ENV`](crate::executor::FUZZY_SELECTORS_ENV) fuzzy
+    /// selectors. Unset or invalid values leave the field as it is.
+    pub fn with_env_overrides(mut self, var: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(port) =
+            var(crate::config::AGENT_PORT_ENV).and_then(|v| crate::config::parse_agent_port(&v))
+        {
+            match &mut self {
+                DriverConfig::Agent { port: p, .. }
+                | DriverConfig::Device { device_port: p, .. }
+                | DriverConfig::CoreDevice { port: p, .. }
+                | DriverConfig::Tunneld { agent_port: p, .. }
+                | DriverConfig::Android { device_port: p, .. } => *p = port,
+                DriverConfig::Idb { .. } => {}
+            }
+        }
+        if let Some(ms) = var(READ_TIMEOUT_ENV)
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|&ms| ms > 0)
+        {
+            match &mut self {
+                DriverConfig::Agent { read_timeout, .. }
+                | DriverConfig::Device { read_timeout, .. }
+                | DriverConfig::CoreDevice { read_timeout, .. }
+                | DriverConfig::Tunneld { read_timeout, .. }
+                | DriverConfig::Android { read_timeout, .. } => {
+                    *read_timeout = Some(Duration::from_millis(ms))
+                }
+                DriverConfig::Idb { .. } => {}
+            }
+        }
+        if let Some(n) = var(RETRIES_ENV).and_then(|v| v.trim().parse::<u32>().ok()) {
+            match &mut self {
+                DriverConfig::Agent { retries, .. }
+                | DriverConfig::Device { retries, .. }
+                | DriverConfig::CoreDevice { retries, .. }
+                | DriverConfig::Tunneld { retries, .. }
+                | DriverConfig::Android { retries, .. } => *retries = Some(n),
+                DriverConfig::Idb { .. } => {}
+            }
+        }
+        if let Some(ms) = var(POLL_INTERVAL_ENV)
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|&ms| ms > 0)
+        {
+            match &mut self {
+                DriverConfig::Agent { poll_interval, .. }
+                | DriverConfig::Device { poll_interval, .. }
+                | DriverConfig::CoreDevice { poll_interval, .. }
+                | DriverConfig::Tunneld { poll_interval, .. }
+                | DriverConfig::Android { poll_interval, .. }
+                | DriverConfig::Idb { poll_interval, .. } => {
+                    *poll_interval = Some(Duration::from_millis(ms))
+                }
+            }
+        }
+        if let Some(enabled) = var(crate::executor::FUZZY_SELECTORS_ENV)
+            .and_then(|v| crate::executor::parse_fuzzy_selectors(&v))
+        {
+            match &mut self {
+                DriverConfig::Agent {
+                    fuzzy_selectors, ..
+                }
+                | DriverConfig::Device {
+                    fuzzy_selectors, ..
+                }
+                | DriverConfig::CoreDevice {
+                    fuzzy_selectors, ..
+                }
+                | DriverConfig::Tunneld {
+                    fuzzy_selectors, ..
+                }
+                | DriverConfig::Android {
+                    fuzzy_selectors, ..
+                }
+                | DriverConfig::Idb {
+                    fuzzy_selectors, ..
+                } => *fuzzy_selectors = Some(enabled),
+            }
+        }
+        self
+    }
 
-    // Leading *'s can match empty text
-    for i in 1..=plen {
-        if pat[i - 1] == '*' {
-            dp[i][0] = dp[i - 1][0];
+    /// The TCP port the agent listens on: `port` for [`Agent`](Self::Agent),
+    /// the device-side port for the device variants and
+    /// [`Android`](Self::Android), and `None` for [`Idb`](Self::Idb), which
+    /// has no agent.
+    pub fn agent_port(&self) -> Option<u16> {
+        match self {
+            DriverConfig::Agent { port, .. } | DriverConfig::CoreDevice { port, .. } => Some(*port),
+            DriverConfig::Device { device_port, .. }
+            | DriverConfig::Android { device_port, .. } => Some(*device_port),
+            DriverConfig::Tunneld { agent_port, .. } => Some(*agent_port),
+            DriverConfig::Idb { .. } => None,
         }
     }
 
-    for i in 1..=plen {
-        for j in 1..=tlen {
-            if pat[i - 1] == '*' {
-                // * matches zero chars (dp[i-1][j]) or one more char (dp[i][j-1])
-                dp[i][j] = dp[i - 1][j] || dp[i][j - 1];
-            } else if pat[i - 1] == '?' || pat[i - 1] == txt[j - 1] {
-                dp[i][j] = dp[i - 1][j - 1];
-            }
+    /// How many times the driver re-sends a request after a connection
+    /// error, if configured. Always `None` for [`Idb`](Self::Idb), which
+    /// keeps no connection.
+    pub fn retries(&self) -> Option<u32> {
+        match self {
+            DriverConfig::Agent { retries, .. }
+            | DriverConfig::Device { retries, .. }
+            | DriverConfig::CoreDevice { retries, .. }
+            | DriverConfig::Tunneld { retries, .. }
+            | DriverConfig::Android { retries, .. } => *retries,
+            DriverConfig::Idb { .. } => None,
         }
     }
 
-    dp[plen][tlen]
-}
+    /// The time between polls of wait actions, if configured. Applied by
+    /// [`ActionExecutor::from_config`](crate::executor::ActionExecutor::from_config).
+    pub fn poll_interval(&self) -> Option<Duration> {
+        match self {
+            DriverConfig::Agent { poll_interval, .. }
+            | DriverConfig::Device { poll_interval, .. }
+            | DriverConfig::CoreDevice { poll_interval, .. }
+            | DriverConfig::Tunneld { poll_interval, .. }
+            | DriverConfig::Android { poll_interval, .. }
+            | DriverConfig::Idb { poll_interval, .. } => *poll_interval,
+        }
+    }
 
-/// Parses a selector string, extracting a trailing `[N]` index if present.
-///
-/// Returns `(base_selector, Some(index))` when the selector ends with `[digits]`
-/// (e.g. `"row[2]"` → `("row", Some(2))`), or `(selector, None)` otherwise.
-///
-/// Only trailing `[digits]` triggers indexing. Non-numeric content, empty
-/// brackets, or no brackets at all are treated as a literal selector with
-/// no index. Negative numbers (e.g. `[-1]`) are also treated as literal.
-fn parse_selector_index(selector: &str) -> (&str, Option<usize>) {
-    if let Some(bracket_pos) = selector.rfind('[') {
-        let after = &selector[bracket_pos + 1..];
-        if let Some(digits) = after.strip_suffix(']') {
-            if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
-                if let Ok(n) = digits.parse::<usize>() {
-                    return (&selector[..bracket_pos], Some(n));
-                }
+    /// Whether fuzzy selectors are turned on or off, if configured. Applied by
+    /// [`ActionExecutor::from_config`](crate::executor::ActionExecutor::from_config).
+    pub fn fuzzy_selectors(&self) -> Option<bool> {
+        match self {
+            DriverConfig::Agent {
+                fuzzy_selectors, ..
+            }
+            | DriverConfig::Device {
+                fuzzy_selectors, ..
+            }
+            | DriverConfig::CoreDevice {
+                fuzzy_selectors, ..
+            }
+            | DriverConfig::Tunneld {
+                fuzzy_selectors, ..
             }
+            | DriverConfig::Android {
+                fuzzy_selectors, ..
+            }
+            | DriverConfig::Idb {
+                fuzzy_selectors, ..
+            } => *fuzzy_selectors,
         }
     }
-    (selector, None)
-}
 
-fn collect_by_identifier(elements: &[UIElement], base: &str, result: &mut Vec<UIElement>) {
-    for element in elements {
-        if element
-            .identifier
-            .as_deref()
-            .is_some_and(|id| glob_match(base, id))
-        {
-            result.push(element.clone());
+    /// Attaches `lifecycle` so the driver built from an iOS agent config can
+    /// restart the agent after a crash. Android and idb configs are returned
+    /// unchanged.
+    pub fn with_lifecycle(mut self, lifecycle: Arc<AgentLifecycle>) -> Self {
+        match &mut self {
+            DriverConfig::Agent { lifecycle: l, .. }
+            | DriverConfig::Device { lifecycle: l, .. }
+            | DriverConfig::CoreDevice { lifecycle: l, .. }
+            | DriverConfig::Tunneld { lifecycle: l, .. } => *l = Some(lifecycle),
+            DriverConfig::Android { .. } | DriverConfig::Idb { .. } => {}
         }
-        collect_by_identifier(&element.children, base, result);
+        self
     }
-}
 
-fn collect_by_label(elements: &[UIElement], base: &str, result: &mut Vec<UIElement>) {
-    for element in elements {
-        if element
-            .label
-            .as_deref()
-            .is_some_and(|l| glob_match(base, l))
-        {
-            result.push(element.clone());
+    /// Checks the values [`create`] relies on: host, UDID, serial and tunnel
+    /// address must not be blank, ports must not be 0, and a read timeout or
+    /// poll interval must be longer than zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DriverError::InvalidConfig`] naming the first bad key.
+    pub fn validate(&self) -> Result<(), DriverError> {
+        fn not_blank(key: &str, value: &str) -> Result<(), DriverError> {
+            if value.trim().is_empty() {
+                return Err(DriverError::InvalidConfig(format!("`{key}` is empty")));
+            }
+            Ok(())
+        }
+        fn not_zero(key: &str, port: u16) -> Result<(), DriverError> {
+            if port == 0 {
+                return Err(DriverError::InvalidConfig(format!("`{key}` is 0")));
+            }
+            Ok(())
         }
-        collect_by_label(&element.children, base, result);
-    }
-}
 
-fn collect_with_type(
-    elements: &[UIElement],
-    base: &str,
-    by_label: bool,
-    element_type: Option<&str>,
-    result: &mut Vec<UIElement>,
-) {
-    for element in elements {
-        let selector_matches = if by_label {
-            element
-                .label
-                .as_deref()
-                .is_some_and(|l| glob_match(base, l))
-        } else {
-            element
-                .identifier
-                .as_deref()
-                .is_some_and(|id| glob_match(base, id))
-        };
-        let type_matches = match element_type {
-            Some(typ) => element.element_type.as_deref() == Some(typ),
-            None => true,
+        let read_timeout = match self {
+            DriverConfig::Agent {
+                host,
+                port,
+                read_timeout,
+                ..
+            } => {
+                not_blank("host", host)?;
+                not_zero("port", *port)?;
+                read_timeout
+            }
+            DriverConfig::Device {
+                udid,
+                device_port,
+                read_timeout,
+                ..
+            } => {
+                not_blank("udid", udid)?;
+                not_zero("device_port", *device_port)?;
+                read_timeout
+            }
+            DriverConfig::CoreDevice {
+                udid,
+                port,
+                read_timeout,
+                ..
+            } => {
+                not_blank("udid", udid)?;
+                not_zero("port", *port)?;
+                read_timeout
+            }
+            DriverConfig::Tunneld {
+                tunnel_address,
+                agent_port,
+                read_timeout,
+                ..
+            } => {
+                not_blank("tunnel_address", tunnel_address)?;
+                not_zero("agent_port", *agent_port)?;
+                read_timeout
+            }
+            DriverConfig::Android {
+                serial,
+                local_port,
+                device_port,
+                read_timeout,
+                ..
+            } => {
+                not_blank("serial", serial)?;
+                not_zero("local_port", *local_port)?;
+                not_zero("device_port", *device_port)?;
+                read_timeout
+            }
+            DriverConfig::Idb { udid, .. } => {
+                not_blank("udid", udid)?;
+                &None
+            }
         };
-        if selector_matches && type_matches {
-            result.push(element.clone());
+        if *read_timeout == Some(Duration::ZERO) {
+            return Err(DriverError::InvalidConfig(
+                "`read_timeout_ms` is 0".to_string(),
+            ));
         }
-        collect_with_type(&element.children, base, by_label, element_type, result);
+        if self.poll_interval() == Some(Duration::ZERO) {
+            return Err(DriverError::InvalidConfig(
+                "`poll_interval_ms` is 0".to_string(),
+            ));
+        }
+        Ok(())
     }
 }
 
-/// Recursively searches a UI element hierarchy for an element matching by identifier.
-///
-/// Supports glob wildcard patterns (`*` and `?`) in the identifier.
-/// Supports `[N]` suffix for 0-based index selection among all matches.
-fn search_by_identifier(elements: &[UIElement], selector: &str) -> Option<UIElement> {
-    let (base, index) = parse_selector_index(selector);
-    if let Some(n) = index {
-        let mut matches = Vec::new();
-        collect_by_identifier(elements, base, &mut matches);
-        return matches.into_iter().nth(n);
+/// Environment variable that overrides a [`DriverConfig`]'s read timeout, in
+/// milliseconds.
+pub const READ_TIMEOUT_ENV: &str = "QORVEX_READ_TIMEOUT";
+
+/// Environment variable that overrides a [`DriverConfig`]'s retries.
+pub const RETRIES_ENV: &str = "QORVEX_RETRIES";
+
+/// Environment variable that overrides a [`DriverConfig`]'s poll interval, in
+/// milliseconds.
+pub const POLL_INTERVAL_ENV: &str = "QORVEX_POLL_INTERVAL";
+
+fn default_host() -> String {
+    "localhost".to_string()
+}
+
+fn default_agent_port() -> u16 {
+    crate::config::DEFAULT_AGENT_PORT
+}
+
+/// Serializes an optional [`Duration`] as whole milliseconds.
+mod optional_millis {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+        value.map(|d| d.as_millis() as u64).serialize(s)
     }
-    // No index: existing DFS first-match behavior (unchanged)
-    for element in elements {
-        if element
-            .identifier
-            .as_deref()
-            .is_some_and(|id| glob_match(base, id))
-        {
-            return Some(element.clone());
-        }
-        if let Some(found) = search_by_identifier(&element.children, selector) {
-            return Some(found);
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(d)?.map(Duration::from_millis))
+    }
+}
+
+/// Errors from [`DriverConfig::from_file`].
+#[derive(Error, Debug)]
+pub enum ConfigFileError {
+    /// The file could not be read.
+    #[error("cannot read {}: {source}", path.display())]
+    Io {
+        /// The config file.
+        path: PathBuf,
+        /// The underlying error.
+        source: std::io::Error,
+    },
+    /// The file is not a valid config: bad syntax, a missing or unknown key,
+    /// or a value of the wrong type.
+    #[error("invalid config {}: {message}", path.display())]
+    Parse {
+        /// The config file.
+        path: PathBuf,
+        /// What was wrong, as reported by the parser.
+        message: String,
+    },
+    /// The file extension is neither `.toml` nor `.json`.
+    #[error("unsupported config format {} (expected .toml or .json)", path.display())]
+    UnsupportedFormat {
+        /// The config file.
+        path: PathBuf,
+    },
+}
+
+/// An automation backend: the driver implementation behind an
+/// [`AutomationDriver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DriverBackend {
+    /// The Swift XCUITest agent
+    /// ([`AgentDriver`](crate::agent_driver::AgentDriver)), on a simulator or
+    /// a physical device.
+    Agent,
+    /// The Kotlin UiAutomator agent
+    /// ([`AndroidDriver`](crate::android_driver::AndroidDriver)).
+    Android,
+    /// Facebook's `idb` CLI ([`IdbDriver`](crate::idb_driver::IdbDriver)),
+    /// with no agent.
+    Idb,
+}
+
+impl DriverBackend {
+    /// All backends, in declaration order.
+    pub const ALL: [DriverBackend; 3] = [
+        DriverBackend::Agent,
+        DriverBackend::Android,
+        DriverBackend::Idb,
+    ];
+
+    /// The backend's name, e.g. `"agent"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DriverBackend::Agent => "agent",
+            DriverBackend::Android => "android",
+            DriverBackend::Idb => "idb",
         }
     }
-    None
 }
 
-/// Recursively searches a UI element hierarchy for an element matching by label.
+impl std::fmt::Display for DriverBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for DriverBackend {
+    type Err = DriverError;
+
+    /// Parses a backend name, ignoring case. Unknown names are
+    /// [`DriverError::Unsupported`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_lowercase();
+        DriverBackend::ALL
+            .into_iter()
+            .find(|b| b.as_str() == normalized)
+            .ok_or_else(|| {
+                DriverError::Unsupported(format!(
+                    "unknown driver backend '{}' (expected one of: {})",
+                    s.trim(),
+                    DriverBackend::ALL.map(DriverBackend::as_str).join(", ")
+                ))
+            })
+    }
+}
+
+/// Builds the driver for `config`, not yet connected.
 ///
-/// Supports glob wildcard patterns (`*` and `?`) in the label.
-/// Supports `[N]` suffix for 0-based index selection among all matches.
-fn search_by_label(elements: &[UIElement], selector: &str) -> Option<UIElement> {
-    let (base, index) = parse_selector_index(selector);
-    if let Some(n) = index {
-        let mut matches = Vec::new();
-        collect_by_label(elements, base, &mut matches);
-        return matches.into_iter().nth(n);
+/// Call [`AutomationDriver::connect`] before use. Code that needs a driver
+/// should come through here rather than naming a backend type, so a new
+/// backend only has to be added in one place.
+///
+/// # Errors
+///
+/// Returns [`DriverError::InvalidConfig`] if `config` fails
+/// [`DriverConfig::validate`].
+pub fn create(config: &DriverConfig) -> Result<Box<dyn AutomationDriver>, DriverError> {
+    use crate::agent_driver::AgentDriver;
+    use crate::android_driver::AndroidDriver;
+    use crate::idb_driver::IdbDriver;
+
+    config.validate()?;
+    let driver: Box<dyn AutomationDriver> = match config {
+        DriverConfig::Agent {
+            host,
+            port,
+            read_timeout,
+            retries,
+            lifecycle,
+            ..
+        } => ios_agent(
+            AgentDriver::direct(host.clone(), *port),
+            *read_timeout,
+            *retries,
+            lifecycle.clone(),
+        ),
+        DriverConfig::Device {
+            udid,
+            device_port,
+            read_timeout,
+            retries,
+            lifecycle,
+            ..
+        } => ios_agent(
+            AgentDriver::usb_device(udid.clone(), *device_port),
+            *read_timeout,
+            *retries,
+            lifecycle.clone(),
+        ),
+        DriverConfig::CoreDevice {
+            udid,
+            port,
+            read_timeout,
+            retries,
+            lifecycle,
+            ..
+        } => ios_agent(
+            AgentDriver::core_device(udid.clone(), *port),
+            *read_timeout,
+            *retries,
+            lifecycle.clone(),
+        ),
+        DriverConfig::Tunneld {
+            tunnel_address,
+            agent_port,
+            read_timeout,
+            retries,
+            lifecycle,
+            ..
+        } => ios_agent(
+            AgentDriver::tunneld(tunnel_address.clone(), *agent_port),
+            *read_timeout,
+            *retries,
+            lifecycle.clone(),
+        ),
+        DriverConfig::Android {
+            serial,
+            local_port,
+            device_port,
+            read_timeout,
+            retries,
+            ..
+        } => Box::new(with_session_settings(
+            AndroidDriver::new(serial.clone(), Some(*local_port), *device_port),
+            *read_timeout,
+            *retries,
+        )),
+        DriverConfig::Idb { udid, .. } => Box::new(IdbDriver::new(udid.clone())),
+    };
+    Ok(driver)
+}
+
+/// Boxes an iOS agent driver with its config's read timeout, retries and
+/// lifecycle.
+fn ios_agent(
+    driver: crate::agent_driver::AgentDriver,
+    read_timeout: Option<Duration>,
+    retries: Option<u32>,
+    lifecycle: Option<Arc<AgentLifecycle>>,
+) -> Box<dyn AutomationDriver> {
+    let driver = with_session_settings(driver, read_timeout, retries);
+    match lifecycle {
+        Some(lifecycle) => Box::new(driver.with_lifecycle(lifecycle)),
+        None => Box::new(driver),
     }
-    // No index: existing DFS first-match behavior (unchanged)
-    for element in elements {
-        if element
-            .label
-            .as_deref()
-            .is_some_and(|l| glob_match(base, l))
-        {
-            return Some(element.clone());
-        }
-        if let Some(found) = search_by_label(&element.children, selector) {
-            return Some(found);
-        }
+}
+
+/// Applies a [`DriverConfig`] read timeout and retries, keeping the driver's
+/// defaults for those not configured.
+fn with_session_settings<T: crate::agent_session::AgentTransport>(
+    driver: crate::agent_session::AgentSession<T>,
+    read_timeout: Option<Duration>,
+    retries: Option<u32>,
+) -> crate::agent_session::AgentSession<T> {
+    let driver = match read_timeout {
+        Some(read_timeout) => driver.with_read_timeout(read_timeout),
+        None => driver,
+    };
+    match retries {
+        Some(retries) => driver.with_retries(retries),
+        None => driver,
     }
-    None
 }
 
-/// Recursively searches a UI element hierarchy by selector (ID or label) with optional type filter.
+/// Returns the first element (depth first) matching by identifier.
 ///
-/// Supports glob wildcard patterns (`*` and `?`) in the selector.
-/// Supports `[N]` suffix for 0-based index selection among all matches.
-fn search_with_type(
+/// Matching follows [`resolve`]: glob wildcards and a trailing `[N]` index
+/// are supported.
+fn search_by_identifier(elements: &[UIElement], selector: &str) -> Option<UIElement> {
+    search_with_type(elements, selector, false, None)
+}
+
+/// Returns the first element (depth first) matching by label.
+fn search_by_label(elements: &[UIElement], selector: &str) -> Option<UIElement> {
+    search_with_type(elements, selector, true, None)
+}
+
+/// Returns the first element (depth first) matching by selector (ID or
+/// label) with an optional type filter.
+pub(crate) fn search_with_type(
     elements: &[UIElement],
     selector: &str,
     by_label: bool,
     element_type: Option<&str>,
 ) -> Option<UIElement> {
-    let (base, index) = parse_selector_index(selector);
-    if let Some(n) = index {
-        let mut matches = Vec::new();
-        collect_with_type(elements, base, by_label, element_type, &mut matches);
-        return matches.into_iter().nth(n);
-    }
-    // No index: existing DFS first-match behavior (unchanged)
-    for element in elements {
-        // Check if this element matches the selector (supports glob wildcards)
-        let selector_matches = if by_label {
-            element
-                .label
-                .as_deref()
-                .is_some_and(|l| glob_match(base, l))
-        } else {
-            element
-                .identifier
-                .as_deref()
-                .is_some_and(|id| glob_match(base, id))
-        };
-
-        // Check if type matches (if type filter is specified)
-        let type_matches = match element_type {
-            Some(typ) => element.element_type.as_deref() == Some(typ),
-            None => true,
-        };
-
-        if selector_matches && type_matches {
-            return Some(element.clone());
-        }
+    let selector =
+        Selector::new(selector, by_label).with_element_type(element_type.map(str::to_string));
+    resolve(elements, &selector).first().map(|e| (*e).clone())
+}
 
-        // Recurse into children
-        if let Some(found) = search_with_type(&element.children, selector, by_label, element_type) {
-            return Some(found);
-        }
+/// Describes a selector the way the agents do in
+/// [`DriverError::ElementNotFound`], e.g. `label 'Log In' and type 'Button'`.
+pub(crate) fn describe_selector(
+    selector: &str,
+    by_label: bool,
+    element_type: Option<&str>,
+) -> String {
+    let kind = if by_label { "label" } else { "ID" };
+    match element_type {
+        Some(t) => format!("{kind} '{selector}' and type '{t}'"),
+        None => format!("{kind} '{selector}'"),
     }
-    None
+}
+
+/// Collects every element in a hierarchy matching a selector, in depth-first order.
+///
+/// Matching follows [`AutomationDriver::find_element_with_type`]: glob
+/// wildcards work in the selector, and a trailing `[N]` keeps only the Nth
+/// match. An empty selector matches every element, so `element_type` alone can
+/// select, e.g., all cells on screen.
+///
+/// This is the client-side equivalent of the agent's find-all search, used in
+/// the default [`AutomationDriver::find_all`] implementation.
+pub fn find_all_elements(
+    elements: &[UIElement],
+    selector: &str,
+    by_label: bool,
+    element_type: Option<&str>,
+) -> Vec<UIElement> {
+    let selector =
+        Selector::new(selector, by_label).with_element_type(element_type.map(str::to_string));
+    // Empty text means "any element", so the type alone can select
+    resolve_all(elements, &selector)
+        .into_iter()
+        .cloned()
+        .collect()
 }
 
 /// Flattens a UI element hierarchy into a list of actionable elements.
@@ -368,6 +1024,132 @@ fn collect_elements(elements: &[UIElement], result: &mut Vec<UIElement>) {
     }
 }
 
+/// Returns the element at a screen point: the smallest element whose frame
+/// contains it.
+///
+/// Frame containment includes edges. When several containing elements have
+/// the same area, the deepest one wins, then the last in document order,
+/// matching which element would usually receive a tap there. Elements without
+/// a frame are skipped, but their children are still searched.
+pub fn element_at_point(elements: &[UIElement], x: f64, y: f64) -> Option<UIElement> {
+    let mut best: Option<(&UIElement, f64, usize)> = None;
+    hit_test(elements, x, y, 0, &mut best);
+    best.map(|(element, _, _)| element.clone())
+}
+
+fn hit_test<'a>(
+    elements: &'a [UIElement],
+    x: f64,
+    y: f64,
+    depth: usize,
+    best: &mut Option<(&'a UIElement, f64, usize)>,
+) {
+    for element in elements {
+        if let Some(frame) = element.frame.as_ref().filter(|f| f.contains_point(x, y)) {
+            let area = frame.width * frame.height;
+            let better = match *best {
+                Some((_, best_area, best_depth)) => {
+                    area < best_area || (area == best_area && depth >= best_depth)
+                }
+                None => true,
+            };
+            if better {
+                *best = Some((element, area, depth));
+            }
+        }
+        hit_test(&element.children, x, y, depth + 1, best);
+    }
+}
+
+/// Criteria for trimming an element hierarchy before it is returned.
+///
+/// The default filter keeps everything. See [`filter_elements`] for how the
+/// criteria combine. Default-valued fields are omitted when serialized.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ElementFilter {
+    /// Keep only elements whose type is one of these. Empty keeps every type.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<String>,
+    /// Keep only elements with a frame of non-zero width and height.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub visible_only: bool,
+    /// Drop elements nested deeper than this; `Some(0)` keeps only the
+    /// top-level elements.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<u32>,
+}
+
+impl ElementFilter {
+    /// Returns `true` if the filter keeps every element.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && !self.visible_only && self.max_depth.is_none()
+    }
+
+    /// Returns `true` if the element itself passes the type and visibility
+    /// criteria (depth is checked by the traversal).
+    pub fn matches(&self, element: &UIElement) -> bool {
+        let type_matches = self.types.is_empty()
+            || element
+                .element_type
+                .as_deref()
+                .is_some_and(|t| self.types.iter().any(|want| want == t));
+        let visible = !self.visible_only
+            || element
+                .frame
+                .as_ref()
+                .is_some_and(|f| f.width > 0.0 && f.height > 0.0);
+        type_matches && visible
+    }
+}
+
+/// Applies an [`ElementFilter`] to an element hierarchy.
+///
+/// Elements deeper than `max_depth` are dropped along with their subtrees.
+/// An element that fails the type or visibility criteria is removed, and its
+/// matching descendants take its place in the parent's child list, so nothing
+/// within depth is lost just because an ancestor was filtered out.
+///
+/// This is the client-side equivalent of the agent's filtered tree dump, used
+/// when the agent does not support [`AutomationDriver::dump_tree_filtered`].
+pub fn filter_elements(elements: &[UIElement], filter: &ElementFilter) -> Vec<UIElement> {
+    let mut result = Vec::new();
+    filter_at_depth(elements, filter, 0, &mut result);
+    result
+}
+
+fn filter_at_depth(
+    elements: &[UIElement],
+    filter: &ElementFilter,
+    depth: u32,
+    result: &mut Vec<UIElement>,
+) {
+    for element in elements {
+        let mut children = Vec::new();
+        if filter.max_depth.is_none_or(|max| depth < max) {
+            filter_at_depth(&element.children, filter, depth + 1, &mut children);
+        }
+        if filter.matches(element) {
+            result.push(UIElement {
+                identifier: element.identifier.clone(),
+                label: element.label.clone(),
+                value: element.value.clone(),
+                element_type: element.element_type.clone(),
+                frame: element.frame.clone(),
+                children,
+                role: element.role.clone(),
+                hittable: element.hittable,
+                enabled: element.enabled,
+                selected: element.selected,
+                focused: element.focused,
+                placeholder: element.placeholder.clone(),
+            });
+        } else {
+            result.extend(children);
+        }
+    }
+}
+
 /// Metadata about the currently targeted application.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TargetInfo {
@@ -378,6 +1160,124 @@ pub struct TargetInfo {
     pub state: String,
 }
 
+/// Automation state held by a running agent, independent of any host session.
+///
+/// Lets a client that reconnects to an already-running agent recover what a
+/// previous client configured.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentState {
+    /// Bundle ID of the app the agent is currently targeting, if one was set.
+    pub target_bundle: Option<String>,
+    /// Bundle ID of the app in the foreground, if the agent can tell.
+    pub foreground_app: Option<String>,
+}
+
+/// Device interface orientation.
+///
+/// Coordinate actions use the coordinate space of the current orientation, so
+/// after rotating, `(0, 0)` is the top-left corner as the user now sees it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Orientation {
+    /// Home button (or bottom edge) at the bottom.
+    Portrait,
+    /// Home button (or bottom edge) at the top.
+    PortraitUpsideDown,
+    /// Rotated so the home button is on the right.
+    LandscapeLeft,
+    /// Rotated so the home button is on the left.
+    LandscapeRight,
+}
+
+impl Orientation {
+    /// All orientations, in declaration order.
+    pub const ALL: [Orientation; 4] = [
+        Orientation::Portrait,
+        Orientation::PortraitUpsideDown,
+        Orientation::LandscapeLeft,
+        Orientation::LandscapeRight,
+    ];
+
+    /// The CLI-facing name, e.g. `"landscape-left"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Orientation::Portrait => "portrait",
+            Orientation::PortraitUpsideDown => "portrait-upside-down",
+            Orientation::LandscapeLeft => "landscape-left",
+            Orientation::LandscapeRight => "landscape-right",
+        }
+    }
+
+    /// The name used on the agent wire protocol, e.g. `"landscape_left"`.
+    pub fn wire_name(self) -> &'static str {
+        match self {
+            Orientation::Portrait => "portrait",
+            Orientation::PortraitUpsideDown => "portrait_upside_down",
+            Orientation::LandscapeLeft => "landscape_left",
+            Orientation::LandscapeRight => "landscape_right",
+        }
+    }
+}
+
+impl std::fmt::Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Orientation {
+    type Err = String;
+
+    /// Parses both the CLI and wire names, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_lowercase().replace('_', "-");
+        Orientation::ALL
+            .into_iter()
+            .find(|o| o.as_str() == normalized)
+            .ok_or_else(|| {
+                format!(
+                    "unknown orientation '{}' (expected 'portrait', 'portrait-upside-down', \
+                     'landscape-left' or 'landscape-right')",
+                    s.trim()
+                )
+            })
+    }
+}
+
+/// Geometry of the device screen.
+///
+/// Element frames and coordinate actions use points; screenshots use pixels.
+/// Multiply a point value by [`scale`](Self::scale) to get pixels. Width and
+/// height follow the current [`orientation`](Self::orientation).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenInfo {
+    /// Screen width in points.
+    pub width: f64,
+    /// Screen height in points.
+    pub height: f64,
+    /// Pixels per point (e.g. `3.0` on a 3x Retina display).
+    pub scale: f64,
+    /// Current orientation; `None` when the device is face up, face down or
+    /// the agent cannot tell.
+    pub orientation: Option<Orientation>,
+}
+
+/// Most fingers a [`multi_tap`](AutomationDriver::multi_tap) may use.
+pub const MAX_TAP_FINGERS: u8 = 5;
+
+/// Checks that a multi-finger tap uses between 1 and [`MAX_TAP_FINGERS`]
+/// fingers.
+pub fn validate_finger_count(finger_count: u8) -> Result<(), DriverError> {
+    if (1..=MAX_TAP_FINGERS).contains(&finger_count) {
+        Ok(())
+    } else {
+        Err(DriverError::CommandFailed(format!(
+            "finger count must be between 1 and {} (got {})",
+            MAX_TAP_FINGERS, finger_count
+        )))
+    }
+}
+
 /// Trait for backend-agnostic iOS Simulator UI automation.
 ///
 /// Implementors provide the core automation capabilities (tapping, swiping,
@@ -408,6 +1308,12 @@ pub struct TargetInfo {
 /// and [`screenshot`](AutomationDriver::screenshot).
 #[async_trait]
 pub trait AutomationDriver: Send + Sync {
+    /// The bundled backend implementing this driver, or `None` (the default)
+    /// for drivers defined outside this crate.
+    fn backend(&self) -> Option<DriverBackend> {
+        None
+    }
+
     /// Establish connection to the automation backend.
     ///
     /// Verifies the backend is available.
@@ -416,6 +1322,9 @@ pub trait AutomationDriver: Send + Sync {
     /// Check if the backend is ready to accept commands.
     fn is_connected(&self) -> bool;
 
+    /// Send a heartbeat and measure its round-trip time.
+    async fn ping(&self) -> Result<Duration, DriverError>;
+
     /// Tap at specific screen coordinates.
     ///
     /// # Arguments
@@ -522,6 +1431,69 @@ pub trait AutomationDriver: Send + Sync {
     /// * `duration` - How long to press in seconds
     async fn long_press(&self, x: i32, y: i32, duration: f64) -> Result<(), DriverError>;
 
+    /// Press and hold an element, resolved by the backend.
+    ///
+    /// Not all backends support this. The default implementation returns
+    /// [`DriverError::Unsupported`]; the executor then falls back to a
+    /// [`long_press`](Self::long_press) at the center of the element's frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The accessibility identifier or label of the element
+    /// * `by_label` - If true, `selector` is matched against labels
+    /// * `duration` - How long to press in seconds
+    async fn long_press_element(
+        &self,
+        selector: &str,
+        by_label: bool,
+        duration: f64,
+    ) -> Result<(), DriverError> {
+        let _ = (selector, by_label, duration);
+        Err(DriverError::Unsupported("long_press_element".to_string()))
+    }
+
+    /// Tap at specific screen coordinates with several fingers at once.
+    ///
+    /// Not all backends support this. The default implementation returns
+    /// [`DriverError::Unsupported`].
+    ///
+    /// # Arguments
+    ///
+    /// * `finger_count` - Number of fingers, 1 to [`MAX_TAP_FINGERS`]
+    /// * `x` - The x-coordinate in screen points
+    /// * `y` - The y-coordinate in screen points
+    async fn multi_tap(&self, finger_count: u8, x: i32, y: i32) -> Result<(), DriverError> {
+        let _ = (finger_count, x, y);
+        Err(DriverError::Unsupported("multi_tap".to_string()))
+    }
+
+    /// Press at a start point, hold, then drag to an end point and release.
+    ///
+    /// Unlike [`swipe`](Self::swipe), the initial hold lets the app recognize
+    /// the gesture as a drag (list reordering, drag and drop).
+    ///
+    /// Not all backends support this. The default implementation returns
+    /// [`DriverError::Unsupported`].
+    ///
+    /// # Arguments
+    ///
+    /// * `start_x`, `start_y` - Where to press, in screen points
+    /// * `end_x`, `end_y` - Where to release, in screen points
+    /// * `hold_duration` - Seconds to hold before moving
+    /// * `move_duration` - Seconds the move takes
+    async fn drag(
+        &self,
+        start_x: i32,
+        start_y: i32,
+        end_x: i32,
+        end_y: i32,
+        hold_duration: f64,
+        move_duration: f64,
+    ) -> Result<(), DriverError> {
+        let _ = (start_x, start_y, end_x, end_y, hold_duration, move_duration);
+        Err(DriverError::Unsupported("drag".to_string()))
+    }
+
     /// Type text into the currently focused element.
     ///
     /// # Arguments
@@ -529,12 +1501,61 @@ pub trait AutomationDriver: Send + Sync {
     /// * `text` - The text to type
     async fn type_text(&self, text: &str) -> Result<(), DriverError>;
 
+    /// Press a special key (Return, Tab, an arrow, ...) on the keyboard.
+    ///
+    /// Not all backends support this. The default implementation returns
+    /// [`DriverError::Unsupported`].
+    async fn send_key(&self, key: SpecialKey) -> Result<(), DriverError> {
+        let _ = key;
+        Err(DriverError::Unsupported("send_key".to_string()))
+    }
+
+    /// Paste the device pasteboard into the currently focused element.
+    ///
+    /// Unlike [`type_text`](Self::type_text), the text arrives in one edit, so
+    /// autocorrect and per-key delays do not apply. The caller fills the
+    /// pasteboard first.
+    ///
+    /// Not all backends support this. The default implementation returns
+    /// [`DriverError::Unsupported`].
+    async fn paste(&self) -> Result<(), DriverError> {
+        Err(DriverError::Unsupported("paste".to_string()))
+    }
+
+    /// Focus an input field and delete all of its text in one step.
+    ///
+    /// Not all backends support this. The default implementation returns
+    /// [`DriverError::Unsupported`]; the executor then falls back to tapping
+    /// the field and typing one delete per character of its current value.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The accessibility identifier or label of the field
+    /// * `by_label` - If true, `selector` is matched against labels
+    async fn clear_text(&self, selector: &str, by_label: bool) -> Result<(), DriverError> {
+        let _ = (selector, by_label);
+        Err(DriverError::Unsupported("clear_text".to_string()))
+    }
+
     /// Get the full UI element hierarchy.
     ///
     /// Returns the root elements of the accessibility tree for the current
     /// screen. Each element may contain nested children.
     async fn dump_tree(&self) -> Result<Vec<UIElement>, DriverError>;
 
+    /// Get the UI element hierarchy, trimmed by `filter` on the backend.
+    ///
+    /// Backends that can filter before shipping the tree should override this.
+    /// The default implementation returns [`DriverError::Unsupported`]; callers
+    /// fall back to [`dump_tree`](Self::dump_tree) plus [`filter_elements`].
+    async fn dump_tree_filtered(
+        &self,
+        filter: &ElementFilter,
+    ) -> Result<Vec<UIElement>, DriverError> {
+        let _ = filter;
+        Err(DriverError::Unsupported("dump_tree_filtered".to_string()))
+    }
+
     /// Get a flattened list of actionable elements.
     ///
     /// Returns all elements from the hierarchy that have either an accessibility
@@ -597,6 +1618,28 @@ pub trait AutomationDriver: Send + Sync {
         Ok(search_with_type(&tree, selector, by_label, element_type))
     }
 
+    /// Find every element matching a selector, with optional type filter.
+    ///
+    /// An empty `selector` matches any element, so a type alone can be used.
+    /// The default implementation calls [`dump_tree`](Self::dump_tree) and
+    /// filters it with [`find_all_elements`]. Backends that support
+    /// server-side search can override this for better performance.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The value to match (accessibility ID or label), or empty
+    /// * `by_label` - If true, match against label; if false, match against ID
+    /// * `element_type` - Optional element type filter
+    async fn find_all(
+        &self,
+        selector: &str,
+        by_label: bool,
+        element_type: Option<&str>,
+    ) -> Result<Vec<UIElement>, DriverError> {
+        let tree = self.dump_tree().await?;
+        Ok(find_all_elements(&tree, selector, by_label, element_type))
+    }
+
     /// Like [`find_element_with_type`], but with a hint for the IPC read timeout.
     ///
     /// The default implementation ignores `read_timeout_ms` and delegates to
@@ -693,6 +1736,18 @@ pub trait AutomationDriver: Send + Sync {
     /// Raw PNG image bytes.
     async fn screenshot(&self) -> Result<Vec<u8>, DriverError>;
 
+    /// Capture a screenshot in the given format.
+    ///
+    /// Use [`ScreenshotFormat::Jpeg`] where the image is only looked at and
+    /// size matters, and PNG where pixels are compared or cropped. The
+    /// default implementation ignores the format and returns
+    /// [`screenshot`](Self::screenshot)'s PNG, as do agents that predate the
+    /// format field, so callers must not assume they got JPEG back.
+    async fn screenshot_with(&self, format: ScreenshotFormat) -> Result<Vec<u8>, DriverError> {
+        let _ = format;
+        self.screenshot().await
+    }
+
     /// Set the target application for accessibility queries.
     ///
     /// Not all backends support this. The default implementation returns
@@ -712,12 +1767,63 @@ pub trait AutomationDriver: Send + Sync {
             "get_target_info not supported by this backend".to_string(),
         ))
     }
+
+    /// Query the agent's own automation state (current target, foreground app).
+    ///
+    /// Not all backends support this. The default implementation returns
+    /// [`DriverError::Unsupported`].
+    async fn query_state(&self) -> Result<AgentState, DriverError> {
+        Err(DriverError::Unsupported("query_state".to_string()))
+    }
+
+    /// Get the screen size, scale factor and orientation.
+    ///
+    /// Not all backends support this. The default implementation returns
+    /// [`DriverError::Unsupported`].
+    async fn screen_info(&self) -> Result<ScreenInfo, DriverError> {
+        Err(DriverError::Unsupported("screen_info".to_string()))
+    }
+
+    /// Get the screen scale factor (points to pixels).
+    ///
+    /// The scale never changes for a device, so backends may answer from a
+    /// cache. The default implementation reads it from
+    /// [`screen_info`](Self::screen_info).
+    async fn screen_scale(&self) -> Result<f64, DriverError> {
+        Ok(self.screen_info().await?.scale)
+    }
+
+    /// Rotate the device to `orientation`.
+    ///
+    /// Not all backends support this. The default implementation returns
+    /// [`DriverError::Unsupported`].
+    async fn set_orientation(&self, _orientation: Orientation) -> Result<(), DriverError> {
+        Err(DriverError::Unsupported("set_orientation".to_string()))
+    }
+
+    /// Send several agent requests in one round trip.
+    ///
+    /// The agent runs the requests in order and stops at the first one that
+    /// fails, so the returned results cover only the requests that ran; when
+    /// one failed, it is the last. The outer error is reserved for failures
+    /// of the round trip itself. Batches cannot be nested.
+    ///
+    /// Not all backends support this. The default implementation returns
+    /// [`DriverError::Unsupported`].
+    async fn send_batch(
+        &self,
+        requests: Vec<Request>,
+    ) -> Result<Vec<Result<Response, DriverError>>, DriverError> {
+        let _ = requests;
+        Err(DriverError::Unsupported("send_batch".to_string()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::element::ElementFrame;
+    use crate::selector::{glob_match, parse_selector_index};
 
     #[test]
     fn test_driver_error_display() {
@@ -735,6 +1841,9 @@ mod tests {
 
         let err = DriverError::JsonParse("unexpected token".to_string());
         assert!(err.to_string().contains("unexpected token"));
+
+        let err = DriverError::Unsupported("dump_tree_filtered".to_string());
+        assert!(err.to_string().contains("dump_tree_filtered"));
     }
 
     #[test]
@@ -742,11 +1851,22 @@ mod tests {
         let config = DriverConfig::Agent {
             host: "localhost".to_string(),
             port: 9123,
+            read_timeout: None,
+            retries: None,
+            poll_interval: None,
+            fuzzy_selectors: None,
+            lifecycle: None,
         };
         match config {
-            DriverConfig::Agent { ref host, port } => {
+            DriverConfig::Agent {
+                ref host,
+                port,
+                read_timeout,
+                ..
+            } => {
                 assert_eq!(host, "localhost");
                 assert_eq!(port, 9123);
+                assert_eq!(read_timeout, None);
             }
             _ => panic!("Expected Agent variant"),
         }
@@ -758,11 +1878,17 @@ mod tests {
         let config = DriverConfig::Device {
             udid: "00008110-001A0C123456789A".to_string(),
             device_port: 8080,
+            read_timeout: None,
+            retries: None,
+            poll_interval: None,
+            fuzzy_selectors: None,
+            lifecycle: None,
         };
         match config {
             DriverConfig::Device {
                 ref udid,
                 device_port,
+                ..
             } => {
                 assert_eq!(udid, "00008110-001A0C123456789A");
                 assert_eq!(device_port, 8080);
@@ -774,16 +1900,23 @@ mod tests {
             serial: "emulator-5554".to_string(),
             local_port: 9123,
             device_port: 8080,
+            read_timeout: Some(Duration::from_secs(60)),
+            retries: None,
+            poll_interval: None,
+            fuzzy_selectors: None,
         };
         match config {
             DriverConfig::Android {
                 ref serial,
                 local_port,
                 device_port,
+                read_timeout,
+                ..
             } => {
                 assert_eq!(serial, "emulator-5554");
                 assert_eq!(local_port, 9123);
                 assert_eq!(device_port, 8080);
+                assert_eq!(read_timeout, Some(Duration::from_secs(60)));
             }
             _ => panic!("Expected Android variant"),
         }
@@ -792,6 +1925,407 @@ mod tests {
         assert!(matches!(cloned, DriverConfig::Android { .. }));
     }
 
+    #[test]
+    fn test_create_builds_the_configured_backend() {
+        let agent = create(&DriverConfig::Agent {
+            host: "localhost".to_string(),
+            port: 9123,
+            read_timeout: None,
+            retries: None,
+            poll_interval: None,
+            fuzzy_selectors: None,
+            lifecycle: None,
+        })
+        .unwrap();
+        assert_eq!(agent.backend(), Some(DriverBackend::Agent));
+        assert!(!agent.is_connected());
+
+        let device = create(&DriverConfig::Device {
+            udid: "00008110-001A0C123456789A".to_string(),
+            device_port: 8080,
+            read_timeout: None,
+            retries: None,
+            poll_interval: None,
+            fuzzy_selectors: None,
+            lifecycle: None,
+        })
+        .unwrap();
+        assert_eq!(device.backend(), Some(DriverBackend::Agent));
+
+        let lifecycle = Arc::new(AgentLifecycle::new(
+            "00008110-001A0C123456789A".to_string(),
+            crate::agent_lifecycle::AgentLifecycleConfig::new(PathBuf::from("/tmp/agent")),
+        ));
+        let configs = [
+            r#"{"backend": "core-device", "udid": "00008110-001A0C123456789A"}"#,
+            r#"{"backend": "tunneld", "tunnel_address": "fd00::1"}"#,
+        ];
+        for json in configs {
+            let config: DriverConfig = serde_json::from_str(json).unwrap();
+            assert_eq!(config.backend(), DriverBackend::Agent);
+            assert_eq!(config.agent_port(), Some(crate::config::DEFAULT_AGENT_PORT));
+            let config = config.with_lifecycle(lifecycle.clone());
+            let driver = create(&config).unwrap();
+            assert_eq!(driver.backend(), Some(DriverBackend::Agent));
+            // Held here, by the config and by the driver
+            assert_eq!(Arc::strong_count(&lifecycle), 3);
+        }
+
+        let config = DriverConfig::Android {
+            serial: "emulator-5554".to_string(),
+            local_port: 9123,
+            device_port: 8080,
+            read_timeout: None,
+            retries: None,
+            poll_interval: None,
+            fuzzy_selectors: None,
+        };
+        assert_eq!(config.backend(), DriverBackend::Android);
+        assert_eq!(
+            create(&config).unwrap().backend(),
+            Some(DriverBackend::Android)
+        );
+
+        let config = DriverConfig::Idb {
+            udid: "12345678-1234-1234-1234-123456789ABC".to_string(),
+            poll_interval: None,
+            fuzzy_selectors: None,
+        };
+        assert_eq!(config.backend(), DriverBackend::Idb);
+        assert_eq!(create(&config).unwrap().backend(), Some(DriverBackend::Idb));
+    }
+
+    #[test]
+    fn test_create_rejects_invalid_config() {
+        let invalid = [
+            (r#"{"backend": "agent", "host": " "}"#, "`host` is empty"),
+            (r#"{"backend": "agent", "port": 0}"#, "`port` is 0"),
+            (r#"{"backend": "device", "udid": ""}"#, "`udid` is empty"),
+            (
+                r#"{"backend": "tunneld", "tunnel_address": "fd00::1", "agent_port": 0}"#,
+                "`agent_port` is 0",
+            ),
+            (
+                r#"{"backend": "android", "serial": "emulator-5554", "local_port": 0}"#,
+                "`local_port` is 0",
+            ),
+            (
+                r#"{"backend": "core-device", "udid": "ABC", "read_timeout_ms": 0}"#,
+                "`read_timeout_ms` is 0",
+            ),
+            (r#"{"backend": "idb", "udid": ""}"#, "`udid` is empty"),
+        ];
+        for (json, expected) in invalid {
+            let config: DriverConfig = serde_json::from_str(json).unwrap();
+            match create(&config) {
+                Err(DriverError::InvalidConfig(msg)) => assert_eq!(msg, expected, "{json}"),
+                Err(e) => panic!("expected InvalidConfig for {json}, got {e}"),
+                Ok(_) => panic!("expected InvalidConfig for {json}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_driver_backend_names() {
+        for backend in DriverBackend::ALL {
+            assert_eq!(backend.as_str().parse::<DriverBackend>().unwrap(), backend);
+        }
+        assert_eq!(
+            " Agent ".parse::<DriverBackend>().unwrap(),
+            DriverBackend::Agent
+        );
+        match "webdriveragent".parse::<DriverBackend>() {
+            Err(DriverError::Unsupported(msg)) => {
+                assert!(
+                    msg.contains("unknown driver backend 'webdriveragent'"),
+                    "{msg}"
+                );
+                assert!(msg.contains("agent, android, idb"), "{msg}");
+            }
+            other => panic!("expected Unsupported, got {other:?}"),
+        }
+    }
+
+    /// Writes `contents` to a uniquely named temp file with `extension`.
+    fn config_file(extension: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "qorvex-driver-config-{}.{extension}",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_from_file_loads_toml_and_json() {
+        let toml = config_file(
+            "toml",
+            "backend = \"agent\"\nhost = \"10.0.0.5\"\nport = 9123\nread_timeout_ms = 60000\n",
+        );
+        let config = DriverConfig::parse_file(&toml, &std::fs::read_to_string(&toml).unwrap());
+        std::fs::remove_file(&toml).unwrap();
+        match config.unwrap() {
+            DriverConfig::Agent {
+                host,
+                port,
+                read_timeout,
+                ..
+            } => {
+                assert_eq!(host, "10.0.0.5");
+                assert_eq!(port, 9123);
+                assert_eq!(read_timeout, Some(Duration::from_secs(60)));
+            }
+            other => panic!("expected Agent, got {other:?}"),
+        }
+
+        let json = config_file(
+            "json",
+            r#"{"backend": "android", "serial": "emulator-5554", "local_port": 9124}"#,
+        );
+        let config = DriverConfig::from_file(&json);
+        std::fs::remove_file(&json).unwrap();
+        match config.unwrap() {
+            DriverConfig::Android {
+                serial, local_port, ..
+            } => {
+                assert_eq!(serial, "emulator-5554");
+                assert_eq!(local_port, 9124);
+            }
+            other => panic!("expected Android, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_config_precedence_env_over_file_over_defaults() {
+        let defaults: DriverConfig = toml::from_str("backend = \"agent\"").unwrap();
+        assert_eq!(
+            defaults.agent_port(),
+            Some(crate::config::DEFAULT_AGENT_PORT)
+        );
+        assert!(matches!(
+            &defaults,
+            DriverConfig::Agent { host, read_timeout: None, .. } if host == "localhost"
+        ));
+
+        let file: DriverConfig =
+            toml::from_str("backend = \"agent\"\nport = 9000\nread_timeout_ms = 1000").unwrap();
+        assert_eq!(file.agent_port(), Some(9000));
+
+        let env = |name: &str| match name {
+            crate::config::AGENT_PORT_ENV => Some("9500".to_string()),
+            READ_TIMEOUT_ENV => Some("2500".to_string()),
+            _ => None,
+        };
+        match file.clone().with_env_overrides(env) {
+            DriverConfig::Agent {
+                port, read_timeout, ..
+            } => {
+                assert_eq!(port, 9500);
+                assert_eq!(read_timeout, Some(Duration::from_millis(2500)));
+            }
+            other => panic!("expected Agent, got {other:?}"),
+        }
+
+        // Invalid environment values leave the file's values alone
+        let invalid = |_: &str| Some("not a number".to_string());
+        assert_eq!(file.with_env_overrides(invalid).agent_port(), Some(9000));
+    }
+
+    #[test]
+    fn test_from_file_rejects_unknown_keys_and_formats() {
+        let typo = Path::new("driver.toml");
+        match DriverConfig::parse_file(typo, "backend = \"agent\"\nprot = 9123\n") {
+            Err(ConfigFileError::Parse { message, .. }) => {
+                assert!(message.contains("unknown field `prot`"), "{message}")
+            }
+            other => panic!("expected Parse, got {other:?}"),
+        }
+        match DriverConfig::parse_file(Path::new("driver.json"), r#"{"backend": "wda"}"#) {
+            Err(ConfigFileError::Parse { message, .. }) => {
+                assert!(message.contains("unknown variant `wda`"), "{message}")
+            }
+            other => panic!("expected Parse, got {other:?}"),
+        }
+        assert!(matches!(
+            DriverConfig::parse_file(Path::new("driver.yaml"), ""),
+            Err(ConfigFileError::UnsupportedFormat { .. })
+        ));
+        assert!(matches!(
+            DriverConfig::from_file("/nonexistent/qorvex-driver.toml"),
+            Err(ConfigFileError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_retries_and_poll_interval_round_trip() {
+        let path = Path::new("driver.toml");
+        let config = DriverConfig::parse_file(
+            path,
+            "backend = \"agent\"\nretries = 3\npoll_interval_ms = 400\n",
+        )
+        .unwrap();
+        assert_eq!(config.retries(), Some(3));
+        assert_eq!(config.poll_interval(), Some(Duration::from_millis(400)));
+
+        let toml = toml::to_string(&config).unwrap();
+        assert!(toml.contains("retries = 3\n"), "{toml}");
+        assert!(toml.contains("poll_interval_ms = 400\n"), "{toml}");
+        let back = DriverConfig::parse_file(path, &toml).unwrap();
+        assert_eq!(back.retries(), Some(3));
+        assert_eq!(back.poll_interval(), Some(Duration::from_millis(400)));
+
+        let env = |name: &str| match name {
+            RETRIES_ENV => Some("0".to_string()),
+            POLL_INTERVAL_ENV => Some("50".to_string()),
+            _ => None,
+        };
+        let overridden = back.with_env_overrides(env);
+        assert_eq!(overridden.retries(), Some(0));
+        assert_eq!(overridden.poll_interval(), Some(Duration::from_millis(50)));
+
+        // Idb keeps no connection to retry, so only the poll interval applies
+        let idb = DriverConfig::parse_file(
+            Path::new("driver.json"),
+            r#"{"backend": "idb", "udid": "ABC", "poll_interval_ms": 200}"#,
+        )
+        .unwrap();
+        assert_eq!(idb.poll_interval(), Some(Duration::from_millis(200)));
+        assert!(matches!(
+            DriverConfig::parse_file(
+                Path::new("driver.json"),
+                r#"{"backend": "idb", "udid": "ABC", "retries": 2}"#,
+            ),
+            Err(ConfigFileError::Parse { .. })
+        ));
+        assert!(matches!(
+            DriverConfig::parse_file(path, "backend = \"agent\"\npoll_interval_ms = 0\n")
+                .unwrap()
+                .validate(),
+            Err(DriverError::InvalidConfig(msg)) if msg.contains("poll_interval_ms")
+        ));
+    }
+
+    #[test]
+    fn test_fuzzy_selectors_round_trip_and_env_override() {
+        let path = Path::new("driver.toml");
+        let config =
+            DriverConfig::parse_file(path, "backend = \"agent\"\nfuzzy_selectors = true\n")
+                .unwrap();
+        assert_eq!(config.fuzzy_selectors(), Some(true));
+        let toml = toml::to_string(&config).unwrap();
+        assert!(toml.contains("fuzzy_selectors = true\n"), "{toml}");
+
+        let env = |value: &'static str| {
+            move |name: &str| {
+                (name == crate::executor::FUZZY_SELECTORS_ENV).then(|| value.to_string())
+            }
+        };
+        assert_eq!(
+            config
+                .clone()
+                .with_env_overrides(env("no"))
+                .fuzzy_selectors(),
+            Some(false)
+        );
+        // Values that are neither on nor off leave the file's setting
+        assert_eq!(
+            config.with_env_overrides(env("maybe")).fuzzy_selectors(),
+            Some(true)
+        );
+
+        let idb = DriverConfig::parse_file(
+            Path::new("driver.json"),
+            r#"{"backend": "idb", "udid": "ABC"}"#,
+        )
+        .unwrap();
+        assert_eq!(idb.fuzzy_selectors(), None);
+        assert_eq!(
+            idb.with_env_overrides(env("1")).fuzzy_selectors(),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_driver_config_serde_roundtrip() {
+        let config = DriverConfig::Device {
+            udid: "00008110-001A0C123456789A".to_string(),
+            device_port: 8080,
+            read_timeout: Some(Duration::from_millis(1500)),
+            retries: None,
+            poll_interval: None,
+            fuzzy_selectors: None,
+            lifecycle: None,
+        };
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "backend": "device",
+                "udid": "00008110-001A0C123456789A",
+                "device_port": 8080,
+                "read_timeout_ms": 1500
+            })
+        );
+        let back: DriverConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(back.agent_port(), Some(8080));
+        let toml = toml::to_string(&DriverConfig::Idb {
+            udid: "ABC".to_string(),
+            poll_interval: None,
+            fuzzy_selectors: None,
+        })
+        .unwrap();
+        assert_eq!(toml, "backend = \"idb\"\nudid = \"ABC\"\n");
+    }
+
+    #[test]
+    fn test_element_at_point_prefers_smallest_frame() {
+        let tree: Vec<UIElement> = serde_json::from_str(
+            r#"[{
+                "AXUniqueId": "screen",
+                "frame": {"x": 0, "y": 0, "width": 390, "height": 844},
+                "children": [
+                    {
+                        "AXUniqueId": "card",
+                        "frame": {"x": 20, "y": 100, "width": 350, "height": 200},
+                        "children": [
+                            {"AXUniqueId": "buy", "frame": {"x": 40, "y": 240, "width": 100, "height": 44}}
+                        ]
+                    },
+                    {"AXUniqueId": "banner", "frame": {"x": 0, "y": 80, "width": 390, "height": 60}}
+                ]
+            }]"#,
+        )
+        .unwrap();
+
+        let id_at = |x, y| element_at_point(&tree, x, y).and_then(|e| e.identifier);
+        assert_eq!(id_at(60.0, 260.0).as_deref(), Some("buy"));
+        assert_eq!(id_at(200.0, 200.0).as_deref(), Some("card"));
+        // The banner overlaps the card but is smaller
+        assert_eq!(id_at(200.0, 120.0).as_deref(), Some("banner"));
+        assert_eq!(id_at(10.0, 500.0).as_deref(), Some("screen"));
+        assert_eq!(id_at(500.0, 500.0), None);
+    }
+
+    #[test]
+    fn test_element_at_point_prefers_deepest_on_equal_frames() {
+        let tree: Vec<UIElement> = serde_json::from_str(
+            r#"[{
+                "AXUniqueId": "cell",
+                "frame": {"x": 0, "y": 100, "width": 390, "height": 44},
+                "children": [
+                    {"type": "Other", "children": [
+                        {"AXUniqueId": "cell-content", "frame": {"x": 0, "y": 100, "width": 390, "height": 44}}
+                    ]}
+                ]
+            }]"#,
+        )
+        .unwrap();
+
+        // The frameless wrapper is skipped, but its child is still found
+        let hit = element_at_point(&tree, 10.0, 120.0).unwrap();
+        assert_eq!(hit.identifier.as_deref(), Some("cell-content"));
+    }
+
     #[test]
     fn test_flatten_elements_basic() {
         let elements = vec![UIElement {
@@ -810,6 +2344,10 @@ mod tests {
                     children: vec![],
                     role: None,
                     hittable: None,
+                    enabled: None,
+                    selected: None,
+                    focused: None,
+                    placeholder: None,
                 },
                 UIElement {
                     identifier: None,
@@ -820,10 +2358,18 @@ mod tests {
                     children: vec![],
                     role: None,
                     hittable: None,
+                    enabled: None,
+                    selected: None,
+                    focused: None,
+                    placeholder: None,
                 },
             ],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }];
 
         let flat = flatten_elements(&elements);
@@ -850,9 +2396,17 @@ mod tests {
                 children: vec![],
                 role: None,
                 hittable: None,
+                enabled: None,
+                selected: None,
+                focused: None,
+                placeholder: None,
             }],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }];
 
         let flat = flatten_elements(&elements);
@@ -901,15 +2455,31 @@ mod tests {
                         children: vec![],
                         role: None,
                         hittable: None,
+                        enabled: None,
+                        selected: None,
+                        focused: None,
+                        placeholder: None,
                     }],
                     role: None,
                     hittable: None,
+                    enabled: None,
+                    selected: None,
+                    focused: None,
+                    placeholder: None,
                 }],
                 role: None,
                 hittable: None,
+                enabled: None,
+                selected: None,
+                focused: None,
+                placeholder: None,
             }],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }];
 
         let flat = flatten_elements(&elements);
@@ -940,6 +2510,10 @@ mod tests {
                     children: vec![],
                     role: None,
                     hittable: None,
+                    enabled: None,
+                    selected: None,
+                    focused: None,
+                    placeholder: None,
                 },
                 UIElement {
                     identifier: None,
@@ -956,13 +2530,25 @@ mod tests {
                         children: vec![],
                         role: None,
                         hittable: None,
+                        enabled: None,
+                        selected: None,
+                        focused: None,
+                        placeholder: None,
                     }],
                     role: None,
                     hittable: None,
+                    enabled: None,
+                    selected: None,
+                    focused: None,
+                    placeholder: None,
                 },
             ],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }];
 
         let flat = flatten_elements(&elements);
@@ -992,9 +2578,17 @@ mod tests {
                 children: vec![],
                 role: None,
                 hittable: None,
+                enabled: None,
+                selected: None,
+                focused: None,
+                placeholder: None,
             }],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }];
 
         let found = search_by_identifier(&elements, "child-btn");
@@ -1022,9 +2616,17 @@ mod tests {
                 children: vec![],
                 role: None,
                 hittable: None,
+                enabled: None,
+                selected: None,
+                focused: None,
+                placeholder: None,
             }],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }];
 
         let found = search_by_identifier(&elements, "login-*");
@@ -1047,6 +2649,10 @@ mod tests {
             children: vec![],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }];
 
         let found = search_by_label(&elements, "Submit");
@@ -1067,6 +2673,10 @@ mod tests {
             children: vec![],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }];
 
         let found = search_by_label(&elements, "Log*");
@@ -1085,6 +2695,10 @@ mod tests {
             children: vec![],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }];
 
         // Match by ID with correct type
@@ -1194,6 +2808,10 @@ mod tests {
             children: vec![],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }
     }
 
@@ -1207,6 +2825,10 @@ mod tests {
             children: vec![],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }
     }
 
@@ -1250,6 +2872,10 @@ mod tests {
                     children: vec![],
                     role: None,
                     hittable: None,
+                    enabled: None,
+                    selected: None,
+                    focused: None,
+                    placeholder: None,
                 },
                 UIElement {
                     identifier: Some("container".to_string()),
@@ -1266,13 +2892,25 @@ mod tests {
                         children: vec![],
                         role: None,
                         hittable: None,
+                        enabled: None,
+                        selected: None,
+                        focused: None,
+                        placeholder: None,
                     }],
                     role: None,
                     hittable: None,
+                    enabled: None,
+                    selected: None,
+                    focused: None,
+                    placeholder: None,
                 },
             ],
             role: None,
             hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
         }];
 
         let row0 = search_by_identifier(&elements, "row[0]");
@@ -1313,6 +2951,10 @@ mod tests {
                 children: vec![],
                 role: None,
                 hittable: None,
+                enabled: None,
+                selected: None,
+                focused: None,
+                placeholder: None,
             },
             UIElement {
                 identifier: Some("btn".to_string()),
@@ -1323,6 +2965,10 @@ mod tests {
                 children: vec![],
                 role: None,
                 hittable: None,
+                enabled: None,
+                selected: None,
+                focused: None,
+                placeholder: None,
             },
         ];
         assert!(search_with_type(&elements, "btn[0]", false, Some("Button")).is_some());
@@ -1330,6 +2976,61 @@ mod tests {
         assert!(search_with_type(&elements, "btn[2]", false, Some("Button")).is_none());
     }
 
+    #[test]
+    fn test_find_all_elements_by_type() {
+        let elements = vec![typed(
+            "table",
+            "Table",
+            10.0,
+            vec![
+                typed(
+                    "row-1",
+                    "Cell",
+                    10.0,
+                    vec![typed("row-1-title", "StaticText", 10.0, vec![])],
+                ),
+                typed("row-2", "Cell", 10.0, vec![]),
+                typed(
+                    "footer",
+                    "Other",
+                    10.0,
+                    vec![typed("row-3", "Cell", 10.0, vec![])],
+                ),
+            ],
+        )];
+        // An empty selector matches by type alone, nested matches included
+        let ids: Vec<_> = find_all_elements(&elements, "", false, Some("Cell"))
+            .into_iter()
+            .map(|e| e.identifier.unwrap())
+            .collect();
+        assert_eq!(ids, ["row-1", "row-2", "row-3"]);
+        // With neither selector nor type, every element matches
+        assert_eq!(find_all_elements(&elements, "", false, None).len(), 6);
+    }
+
+    #[test]
+    fn test_find_all_elements_by_selector() {
+        let elements = vec![
+            typed("row-1", "Cell", 10.0, vec![]),
+            typed("row-2", "Button", 10.0, vec![]),
+            make_labeled("row-3"),
+            typed("row-4", "Cell", 10.0, vec![]),
+        ];
+        assert_eq!(find_all_elements(&elements, "row-*", false, None).len(), 3);
+        assert_eq!(
+            find_all_elements(&elements, "row-*", false, Some("Cell")).len(),
+            2
+        );
+        assert_eq!(find_all_elements(&elements, "row-*", true, None).len(), 1);
+        assert!(find_all_elements(&elements, "missing", false, None).is_empty());
+
+        // An index keeps only that match
+        let nth = find_all_elements(&elements, "row-*[1]", false, Some("Cell"));
+        assert_eq!(nth.len(), 1);
+        assert_eq!(nth[0].identifier.as_deref(), Some("row-4"));
+        assert!(find_all_elements(&elements, "row-*[5]", false, None).is_empty());
+    }
+
     #[test]
     fn test_search_by_identifier_glob_plus_index() {
         // Glob + index: "cell_*[1]" → base="cell_*", index=1
@@ -1343,6 +3044,10 @@ mod tests {
                 children: vec![],
                 role: None,
                 hittable: None,
+                enabled: None,
+                selected: None,
+                focused: None,
+                placeholder: None,
             },
             UIElement {
                 identifier: Some("cell_B".to_string()),
@@ -1353,9 +3058,185 @@ mod tests {
                 children: vec![],
                 role: None,
                 hittable: None,
+                enabled: None,
+                selected: None,
+                focused: None,
+                placeholder: None,
             },
         ];
         let found = search_by_identifier(&elements, "cell_*[1]");
         assert_eq!(found.unwrap().identifier.as_deref(), Some("cell_B"));
     }
+
+    // --- filter_elements tests ---
+
+    fn typed(id: &str, typ: &str, size: f64, children: Vec<UIElement>) -> UIElement {
+        UIElement {
+            identifier: Some(id.to_string()),
+            label: None,
+            value: None,
+            element_type: Some(typ.to_string()),
+            frame: Some(ElementFrame {
+                x: 0.0,
+                y: 0.0,
+                width: size,
+                height: size,
+            }),
+            children,
+            role: None,
+            hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
+        }
+    }
+
+    /// Application > Window > [Button "ok", Other > [Button "hidden" (0x0), StaticText]]
+    fn synthetic_tree() -> Vec<UIElement> {
+        vec![typed(
+            "app",
+            "Application",
+            400.0,
+            vec![typed(
+                "window",
+                "Window",
+                400.0,
+                vec![
+                    typed("ok", "Button", 40.0, vec![]),
+                    typed(
+                        "group",
+                        "Other",
+                        100.0,
+                        vec![
+                            typed("hidden", "Button", 0.0, vec![]),
+                            typed("title", "StaticText", 20.0, vec![]),
+                        ],
+                    ),
+                ],
+            )],
+        )]
+    }
+
+    fn ids(elements: &[UIElement]) -> Vec<String> {
+        flatten_elements(elements)
+            .into_iter()
+            .filter_map(|e| e.identifier)
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_elements_empty_filter_keeps_tree() {
+        let tree = synthetic_tree();
+        let filter = ElementFilter::default();
+        assert!(filter.is_empty());
+        assert_eq!(ids(&filter_elements(&tree, &filter)), ids(&tree));
+    }
+
+    #[test]
+    fn test_filter_elements_by_type_hoists_descendants() {
+        let filter = ElementFilter {
+            types: vec!["Button".to_string()],
+            ..Default::default()
+        };
+        let filtered = filter_elements(&synthetic_tree(), &filter);
+        // Non-matching ancestors are removed; the buttons become top-level.
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(ids(&filtered), vec!["ok", "hidden"]);
+    }
+
+    #[test]
+    fn test_filter_elements_visible_only() {
+        let filter = ElementFilter {
+            types: vec!["Button".to_string()],
+            visible_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&filter_elements(&synthetic_tree(), &filter)),
+            vec!["ok"]
+        );
+    }
+
+    #[test]
+    fn test_filter_elements_max_depth_zero_is_top_level_only() {
+        let filter = ElementFilter {
+            max_depth: Some(0),
+            ..Default::default()
+        };
+        let filtered = filter_elements(&synthetic_tree(), &filter);
+        assert_eq!(filtered.len(), 1);
+        assert!(filtered[0].children.is_empty());
+        assert_eq!(ids(&filtered), vec!["app"]);
+    }
+
+    #[test]
+    fn test_filter_elements_max_depth_counts_original_depth() {
+        // Depth 2 reaches the window's children but not the group's.
+        let filter = ElementFilter {
+            types: vec!["Button".to_string(), "StaticText".to_string()],
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&filter_elements(&synthetic_tree(), &filter)),
+            vec!["ok"]
+        );
+    }
+
+    #[test]
+    fn test_filter_elements_no_match() {
+        let filter = ElementFilter {
+            types: vec!["Switch".to_string()],
+            ..Default::default()
+        };
+        assert!(filter_elements(&synthetic_tree(), &filter).is_empty());
+    }
+
+    #[test]
+    fn test_orientation_from_str() {
+        for orientation in Orientation::ALL {
+            assert_eq!(orientation.as_str().parse::<Orientation>(), Ok(orientation));
+            assert_eq!(
+                orientation.wire_name().parse::<Orientation>(),
+                Ok(orientation)
+            );
+        }
+        assert_eq!(
+            " Landscape-Left ".parse::<Orientation>(),
+            Ok(Orientation::LandscapeLeft)
+        );
+        let err = "sideways".parse::<Orientation>().unwrap_err();
+        assert!(err.contains("sideways"));
+        assert!("face_up".parse::<Orientation>().is_err());
+    }
+
+    #[test]
+    fn test_orientation_serde() {
+        assert_eq!(
+            serde_json::to_string(&Orientation::PortraitUpsideDown).unwrap(),
+            r#""portrait-upside-down""#
+        );
+        let info: ScreenInfo = serde_json::from_str(
+            r#"{"width":844.0,"height":390.0,"scale":3.0,"orientation":"landscape-right"}"#,
+        )
+        .unwrap();
+        assert_eq!(info.orientation, Some(Orientation::LandscapeRight));
+    }
+
+    #[test]
+    fn test_validate_finger_count() {
+        for n in 1..=MAX_TAP_FINGERS {
+            assert!(validate_finger_count(n).is_ok());
+        }
+        for n in [0, 6, u8::MAX] {
+            match validate_finger_count(n) {
+                Err(DriverError::CommandFailed(msg)) => {
+                    assert!(msg.contains("between 1 and 5"), "{msg}");
+                    assert!(msg.contains(&format!("got {n}")), "{msg}");
+                }
+                other => panic!("expected CommandFailed for {n}, got {other:?}"),
+            }
+        }
+    }
 }
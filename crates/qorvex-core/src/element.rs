@@ -45,6 +45,92 @@ pub struct UIElement {
     /// Whether this element is hittable (tappable) as reported by the automation backend.
     #[serde(default)]
     pub hittable: Option<bool>,
+
+    /// Whether the element accepts interaction; `Some(false)` for a disabled control.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// Whether the element is selected, e.g. the current tab or a checked row.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected: Option<bool>,
+
+    /// Whether the element has keyboard focus.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focused: Option<bool>,
+
+    /// Hint text a text field shows while it is empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+}
+
+/// An element attribute that can be read on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElementAttribute {
+    /// The accessibility label.
+    Label,
+    /// The current value, e.g. a text field's contents.
+    Value,
+    /// The hint text of an empty text field.
+    Placeholder,
+    /// The accessibility identifier.
+    Identifier,
+    /// Accessibility traits, derived from the type and state (see
+    /// [`UIElement::traits`]).
+    Traits,
+    /// The frame, in screen points.
+    Frame,
+}
+
+impl ElementAttribute {
+    /// All attributes, in declaration order.
+    pub const ALL: [ElementAttribute; 6] = [
+        ElementAttribute::Label,
+        ElementAttribute::Value,
+        ElementAttribute::Placeholder,
+        ElementAttribute::Identifier,
+        ElementAttribute::Traits,
+        ElementAttribute::Frame,
+    ];
+
+    /// The CLI-facing name, e.g. `"placeholder"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ElementAttribute::Label => "label",
+            ElementAttribute::Value => "value",
+            ElementAttribute::Placeholder => "placeholder",
+            ElementAttribute::Identifier => "identifier",
+            ElementAttribute::Traits => "traits",
+            ElementAttribute::Frame => "frame",
+        }
+    }
+}
+
+impl std::fmt::Display for ElementAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ElementAttribute {
+    type Err = String;
+
+    /// Parses an attribute name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_lowercase();
+        ElementAttribute::ALL
+            .into_iter()
+            .find(|a| a.as_str() == normalized)
+            .ok_or_else(|| {
+                format!(
+                    "unknown attribute '{}' (expected one of: {})",
+                    s.trim(),
+                    ElementAttribute::ALL
+                        .map(ElementAttribute::as_str)
+                        .join(", ")
+                )
+            })
+    }
 }
 
 /// The frame (position and dimensions) of a UI element.
@@ -62,3 +148,464 @@ pub struct ElementFrame {
     /// The height of the element in points.
     pub height: f64,
 }
+
+impl UIElement {
+    /// Returns the center of the element's frame, or `None` if it has no frame.
+    pub fn center(&self) -> Option<(f64, f64)> {
+        self.frame.as_ref().map(ElementFrame::center)
+    }
+
+    /// Returns `true` if the point lies within the element's frame.
+    ///
+    /// Elements without a frame contain no points.
+    pub fn contains_point(&self, x: f64, y: f64) -> bool {
+        self.frame
+            .as_ref()
+            .is_some_and(|frame| frame.contains_point(x, y))
+    }
+
+    /// Formats the element on one line: `[Type] id "label" =value @(x,y)`,
+    /// followed by any notable state (`disabled`, `selected`, `focused`).
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        let elem_type = self.element_type.as_deref().unwrap_or("Unknown");
+        parts.push(format!("[{}]", elem_type));
+        if let Some(ref id) = self.identifier {
+            parts.push(id.clone());
+        }
+        if let Some(ref label) = self.label {
+            parts.push(format!("\"{}\"", label));
+        }
+        if let Some(ref value) = self.value {
+            parts.push(format!("={}", value));
+        }
+        if let Some(ref frame) = self.frame {
+            parts.push(format!("@({:.0},{:.0})", frame.x, frame.y));
+        }
+        if self.enabled == Some(false) {
+            parts.push("disabled".to_string());
+        }
+        if self.selected == Some(true) {
+            parts.push("selected".to_string());
+        }
+        if self.focused == Some(true) {
+            parts.push("focused".to_string());
+        }
+        parts.join(" ")
+    }
+
+    /// Returns the element's accessibility traits, named after
+    /// `UIAccessibilityTraits` (e.g. `"button"`, `"selected"`).
+    ///
+    /// XCUITest does not expose an element's traits, so they are derived from
+    /// its type and state: a trait for the type where one applies, then
+    /// `"selected"` and `"notEnabled"`.
+    pub fn traits(&self) -> Vec<&'static str> {
+        let mut traits = Vec::new();
+        let type_trait = match self.element_type.as_deref() {
+            Some("Button") => Some("button"),
+            Some("Link") => Some("link"),
+            Some("Image" | "Icon") => Some("image"),
+            Some("StaticText") => Some("staticText"),
+            Some("SearchField") => Some("searchField"),
+            Some("Slider" | "Stepper" | "PickerWheel") => Some("adjustable"),
+            Some("Key") => Some("keyboardKey"),
+            Some("TabBar") => Some("tabBar"),
+            _ => None,
+        };
+        traits.extend(type_trait);
+        if self.selected == Some(true) {
+            traits.push("selected");
+        }
+        if self.enabled == Some(false) {
+            traits.push("notEnabled");
+        }
+        traits
+    }
+
+    /// Returns `attribute` as JSON: a string, an array of trait names or a
+    /// frame object, or `null` when the element does not have it.
+    pub fn attribute(&self, attribute: ElementAttribute) -> serde_json::Value {
+        use serde_json::Value;
+        let text = |s: &Option<String>| s.clone().map_or(Value::Null, Value::String);
+        match attribute {
+            ElementAttribute::Label => text(&self.label),
+            ElementAttribute::Value => text(&self.value),
+            ElementAttribute::Placeholder => text(&self.placeholder),
+            ElementAttribute::Identifier => text(&self.identifier),
+            ElementAttribute::Traits => Value::from(self.traits()),
+            ElementAttribute::Frame => self
+                .frame
+                .as_ref()
+                .and_then(|f| serde_json::to_value(f).ok())
+                .unwrap_or(Value::Null),
+        }
+    }
+}
+
+impl ElementFrame {
+    /// Returns the center point of the frame.
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// Returns `true` if the point lies within the frame, edges included.
+    pub fn contains_point(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x <= self.x + self.width && y >= self.y && y <= self.y + self.height
+    }
+
+    /// Returns `true` if the two frames overlap with a non-zero area.
+    ///
+    /// Frames that only share an edge, or that have zero size, do not intersect.
+    pub fn intersects(&self, other: &ElementFrame) -> bool {
+        self.width > 0.0
+            && self.height > 0.0
+            && other.width > 0.0
+            && other.height > 0.0
+            && self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+
+    /// Returns the point `dx_fraction` of the way across the frame and
+    /// `dy_fraction` of the way down it, so `(0.5, 0.5)` is the center.
+    pub fn point_at(&self, dx_fraction: f64, dy_fraction: f64) -> (f64, f64) {
+        (
+            self.x + self.width * dx_fraction,
+            self.y + self.height * dy_fraction,
+        )
+    }
+
+    /// Returns the start and end points, `(start_x, start_y, end_x, end_y)`,
+    /// of a swipe in `direction` ("up", "down", "left" or "right") inside the
+    /// frame.
+    ///
+    /// The swipe runs through the frame's center and covers the middle 60% of
+    /// it, so both ends stay clear of the edges. Returns `None` for an unknown
+    /// direction.
+    pub fn swipe_points(&self, direction: &str) -> Option<(f64, f64, f64, f64)> {
+        let (center_x, center_y) = self.center();
+        let (left, right) = (self.x + self.width * 0.2, self.x + self.width * 0.8);
+        let (top, bottom) = (self.y + self.height * 0.2, self.y + self.height * 0.8);
+        match direction {
+            "up" => Some((center_x, bottom, center_x, top)),
+            "down" => Some((center_x, top, center_x, bottom)),
+            "left" => Some((right, center_y, left, center_y)),
+            "right" => Some((left, center_y, right, center_y)),
+            _ => None,
+        }
+    }
+}
+
+/// Computes a stable content hash over a list of elements.
+///
+/// The hash covers every serialized field, including children, so any change
+/// to the tree yields a different value. It is derived from the JSON encoding
+/// rather than [`std::hash::Hash`], so it does not depend on the Rust version
+/// and survives a serialization round-trip. Returned as 16 lowercase hex digits.
+pub fn hash_elements(elements: &[UIElement]) -> String {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let bytes = serde_json::to_vec(elements).unwrap_or_default();
+    let hash = bytes.iter().fold(FNV_OFFSET, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(FNV_PRIME)
+    });
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(x: f64, y: f64, width: f64, height: f64) -> ElementFrame {
+        ElementFrame {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn element(frame: Option<ElementFrame>) -> UIElement {
+        UIElement {
+            identifier: None,
+            label: None,
+            value: None,
+            element_type: None,
+            frame,
+            children: vec![],
+            role: None,
+            hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
+        }
+    }
+
+    #[test]
+    fn summary_lists_type_id_label_value_and_origin() {
+        let mut elem = element(Some(frame(10.4, 20.6, 100.0, 44.0)));
+        elem.element_type = Some("TextField".into());
+        elem.identifier = Some("username".into());
+        elem.label = Some("User name".into());
+        elem.value = Some("alice".into());
+        elem.focused = Some(true);
+        assert_eq!(
+            elem.summary(),
+            "[TextField] username \"User name\" =alice @(10,21) focused"
+        );
+        assert_eq!(element(None).summary(), "[Unknown]");
+    }
+
+    #[test]
+    fn frame_center() {
+        assert_eq!(frame(10.0, 20.0, 100.0, 40.0).center(), (60.0, 40.0));
+    }
+
+    #[test]
+    fn zero_size_frame_center_is_origin() {
+        assert_eq!(frame(5.0, 7.0, 0.0, 0.0).center(), (5.0, 7.0));
+    }
+
+    #[test]
+    fn contains_point_includes_edges() {
+        let f = frame(0.0, 0.0, 10.0, 10.0);
+        assert!(f.contains_point(0.0, 0.0));
+        assert!(f.contains_point(10.0, 10.0));
+        assert!(f.contains_point(5.0, 5.0));
+        assert!(!f.contains_point(10.1, 5.0));
+        assert!(!f.contains_point(5.0, -0.1));
+    }
+
+    #[test]
+    fn zero_size_frame_contains_only_its_origin() {
+        let f = frame(3.0, 4.0, 0.0, 0.0);
+        assert!(f.contains_point(3.0, 4.0));
+        assert!(!f.contains_point(3.0, 4.5));
+    }
+
+    #[test]
+    fn intersects_overlapping_frames() {
+        let a = frame(0.0, 0.0, 10.0, 10.0);
+        let b = frame(5.0, 5.0, 10.0, 10.0);
+        assert!(a.intersects(&b));
+        assert!(b.intersects(&a));
+    }
+
+    #[test]
+    fn touching_frames_do_not_intersect() {
+        let a = frame(0.0, 0.0, 10.0, 10.0);
+        let b = frame(10.0, 0.0, 10.0, 10.0);
+        assert!(!a.intersects(&b));
+    }
+
+    #[test]
+    fn zero_size_frame_never_intersects() {
+        let a = frame(0.0, 0.0, 10.0, 10.0);
+        let b = frame(5.0, 5.0, 0.0, 0.0);
+        assert!(!a.intersects(&b));
+    }
+
+    #[test]
+    fn point_at_fractions_of_frame() {
+        let slider = frame(20.0, 400.0, 300.0, 40.0);
+        assert_eq!(slider.point_at(0.0, 0.0), (20.0, 400.0));
+        assert_eq!(slider.point_at(0.5, 0.5), slider.center());
+        assert_eq!(slider.point_at(0.75, 0.5), (245.0, 420.0));
+        assert_eq!(slider.point_at(1.0, 1.0), (320.0, 440.0));
+    }
+
+    #[test]
+    fn swipe_points_stay_inside_frame() {
+        let carousel = frame(10.0, 300.0, 400.0, 200.0);
+        for direction in ["up", "down", "left", "right"] {
+            let (start_x, start_y, end_x, end_y) = carousel.swipe_points(direction).unwrap();
+            assert!(carousel.contains_point(start_x, start_y), "{direction}");
+            assert!(carousel.contains_point(end_x, end_y), "{direction}");
+        }
+        // Right to left across the vertical center
+        assert_eq!(
+            carousel.swipe_points("left"),
+            Some((330.0, 400.0, 90.0, 400.0))
+        );
+        assert_eq!(
+            carousel.swipe_points("up"),
+            Some((210.0, 460.0, 210.0, 340.0))
+        );
+        assert_eq!(carousel.swipe_points("sideways"), None);
+    }
+
+    #[test]
+    fn element_without_frame() {
+        let e = element(None);
+        assert_eq!(e.center(), None);
+        assert!(!e.contains_point(0.0, 0.0));
+    }
+
+    #[test]
+    fn element_with_frame() {
+        let e = element(Some(frame(0.0, 100.0, 200.0, 50.0)));
+        assert_eq!(e.center(), Some((100.0, 125.0)));
+        assert!(e.contains_point(200.0, 150.0));
+        assert!(!e.contains_point(200.0, 151.0));
+    }
+
+    #[test]
+    fn state_attributes_default_to_none() {
+        let e: UIElement = serde_json::from_str(r#"{"AXUniqueId":"a","hittable":true}"#).unwrap();
+        assert_eq!((e.enabled, e.selected, e.focused), (None, None, None));
+
+        // Unknown state is left out rather than written as null
+        let json = serde_json::to_string(&e).unwrap();
+        assert!(!json.contains("enabled"), "{json}");
+        assert!(!json.contains("selected"), "{json}");
+        assert!(!json.contains("focused"), "{json}");
+    }
+
+    #[test]
+    fn state_attributes_round_trip() {
+        let e: UIElement = serde_json::from_str(
+            r#"{"AXUniqueId":"save","enabled":false,"selected":true,"focused":false}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            (e.enabled, e.selected, e.focused),
+            (Some(false), Some(true), Some(false))
+        );
+        let parsed: UIElement = serde_json::from_str(&serde_json::to_string(&e).unwrap()).unwrap();
+        assert_eq!(
+            (parsed.enabled, parsed.selected, parsed.focused),
+            (Some(false), Some(true), Some(false))
+        );
+    }
+
+    #[test]
+    fn hash_is_stable_across_round_trip() {
+        let tree = vec![UIElement {
+            identifier: Some("root".into()),
+            label: Some("Root".into()),
+            value: None,
+            element_type: Some("Window".into()),
+            frame: Some(frame(0.0, 0.0, 390.5, 844.25)),
+            children: vec![element(Some(frame(10.0, 20.0, 100.0, 44.0)))],
+            role: None,
+            hittable: Some(true),
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
+        }];
+        let json = serde_json::to_string(&tree).unwrap();
+        let parsed: Vec<UIElement> = serde_json::from_str(&json).unwrap();
+        assert_eq!(hash_elements(&tree), hash_elements(&parsed));
+
+        let reparsed: Vec<UIElement> =
+            serde_json::from_str(&serde_json::to_string(&parsed).unwrap()).unwrap();
+        assert_eq!(hash_elements(&tree), hash_elements(&reparsed));
+    }
+
+    #[test]
+    fn hash_ignores_missing_vs_null_fields() {
+        let sparse: Vec<UIElement> =
+            serde_json::from_str(r#"[{"AXUniqueId":"a","children":[]}]"#).unwrap();
+        let explicit: Vec<UIElement> = serde_json::from_str(
+            r#"[{"AXUniqueId":"a","AXLabel":null,"AXValue":null,"type":null,"frame":null,"children":[],"role":null,"hittable":null}]"#,
+        )
+        .unwrap();
+        assert_eq!(hash_elements(&sparse), hash_elements(&explicit));
+    }
+
+    #[test]
+    fn hash_changes_with_content() {
+        let a = vec![element(Some(frame(0.0, 0.0, 10.0, 10.0)))];
+        let b = vec![element(Some(frame(0.0, 0.0, 10.0, 11.0)))];
+        assert_ne!(hash_elements(&a), hash_elements(&b));
+        assert_eq!(hash_elements(&a).len(), 16);
+    }
+
+    fn text_field() -> UIElement {
+        let mut elem = element(Some(frame(20.0, 100.0, 300.0, 44.0)));
+        elem.element_type = Some("TextField".into());
+        elem.identifier = Some("email".into());
+        elem.label = Some("Email".into());
+        elem.value = Some("alice@example.com".into());
+        elem.placeholder = Some("you@example.com".into());
+        elem
+    }
+
+    #[test]
+    fn attribute_reads_text_fields() {
+        let elem = text_field();
+        assert_eq!(elem.attribute(ElementAttribute::Label), "Email");
+        assert_eq!(elem.attribute(ElementAttribute::Value), "alice@example.com");
+        assert_eq!(
+            elem.attribute(ElementAttribute::Placeholder),
+            "you@example.com"
+        );
+        assert_eq!(elem.attribute(ElementAttribute::Identifier), "email");
+    }
+
+    #[test]
+    fn attribute_reads_frame() {
+        assert_eq!(
+            text_field().attribute(ElementAttribute::Frame),
+            serde_json::json!({ "x": 20.0, "y": 100.0, "width": 300.0, "height": 44.0 })
+        );
+    }
+
+    #[test]
+    fn attribute_reads_traits() {
+        let mut elem = element(None);
+        elem.element_type = Some("Button".into());
+        elem.selected = Some(true);
+        elem.enabled = Some(false);
+        assert_eq!(
+            elem.attribute(ElementAttribute::Traits),
+            serde_json::json!(["button", "selected", "notEnabled"])
+        );
+        assert_eq!(
+            text_field().attribute(ElementAttribute::Traits),
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn missing_attributes_are_null() {
+        let elem = element(None);
+        for attribute in ElementAttribute::ALL {
+            if attribute != ElementAttribute::Traits {
+                assert!(elem.attribute(attribute).is_null(), "{attribute}");
+            }
+        }
+    }
+
+    #[test]
+    fn element_attribute_parses_names() {
+        for attribute in ElementAttribute::ALL {
+            assert_eq!(
+                attribute.as_str().parse::<ElementAttribute>(),
+                Ok(attribute)
+            );
+            assert_eq!(
+                serde_json::to_value(attribute).unwrap(),
+                serde_json::json!(attribute.as_str())
+            );
+        }
+        assert_eq!(
+            " Placeholder ".parse::<ElementAttribute>(),
+            Ok(ElementAttribute::Placeholder)
+        );
+        assert!("hint".parse::<ElementAttribute>().is_err());
+    }
+
+    #[test]
+    fn placeholder_is_omitted_when_absent() {
+        let json = serde_json::to_string(&element(None)).unwrap();
+        assert!(!json.contains("placeholder"), "{json}");
+        let elem: UIElement = serde_json::from_str(r#"{"placeholder": "Search"}"#).unwrap();
+        assert_eq!(elem.placeholder.as_deref(), Some("Search"));
+    }
+}
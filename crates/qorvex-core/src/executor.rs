@@ -8,7 +8,7 @@
 //!
 //! ```no_run
 //! use qorvex_core::executor::ActionExecutor;
-//! use qorvex_core::action::ActionType;
+//! use qorvex_core::action::{ActionType, MatchMode};
 //!
 //! #[tokio::main]
 //! async fn main() {
@@ -19,6 +19,9 @@
 //!         by_label: false,
 //!         element_type: None,
 //!         timeout_ms: Some(5000),
+//!         require_enabled: false,
+//!         match_mode: MatchMode::Exact,
+//!         first: false,
 //!     }).await;
 //!
 //!     if result.success {
@@ -30,10 +33,19 @@
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, info_span, Instrument};
 
-use crate::action::ActionType;
-use crate::driver::{AutomationDriver, DriverError};
+use crate::action::{join_selectors, ActionType, MatchMode, SelectorSpec};
+use crate::driver::{
+    filter_elements, flatten_elements, AutomationDriver, DriverError, ElementFilter,
+};
+use crate::element::{hash_elements, ElementFrame, UIElement};
+use crate::protocol::{Request, Response, ScreenshotFormat, SpecialKey};
+use crate::result_data::{self, IdleData, ResultData, TapIfExistsData, WaitData, WaitForAnyData};
+use crate::screenshot;
+use crate::selector::Selector;
+use crate::simctl::Simctl;
 
 /// Result of executing an action.
 ///
@@ -49,6 +61,8 @@ pub struct ExecutionResult {
     pub screenshot: Option<String>,
     /// Additional data returned by the action (JSON for screen info, element values, etc.).
     pub data: Option<String>,
+    /// Whether the action failed with [`DriverError::ElementNotFound`].
+    pub element_not_found: bool,
 }
 
 impl ExecutionResult {
@@ -59,6 +73,7 @@ impl ExecutionResult {
             message: message.into(),
             screenshot: None,
             data: None,
+            element_not_found: false,
         }
     }
 
@@ -69,6 +84,16 @@ impl ExecutionResult {
             message: message.into(),
             screenshot: None,
             data: None,
+            element_not_found: false,
+        }
+    }
+
+    /// Creates a failure result from a driver error, flagging
+    /// [`DriverError::ElementNotFound`].
+    pub fn from_error(err: DriverError) -> Self {
+        Self {
+            element_not_found: matches!(err, DriverError::ElementNotFound { .. }),
+            ..Self::failure(err.to_string())
         }
     }
 
@@ -83,6 +108,22 @@ impl ExecutionResult {
         self.data = Some(data);
         self
     }
+
+    /// Returns the value `action` read, for [`ActionLog::value`](crate::action::ActionLog::value).
+    ///
+    /// Only a successful `GetValue` on an element that has a value yields one.
+    pub fn read_value(&self, action: &ActionType) -> Option<String> {
+        if !self.success || !matches!(action, ActionType::GetValue { .. }) {
+            return None;
+        }
+        self.data.clone().filter(|data| data != "null")
+    }
+
+    /// Returns how long the action took, for actions whose data reports it
+    /// (see [`result_data::elapsed_ms`]).
+    pub fn elapsed_ms(&self) -> Option<u64> {
+        self.data.as_deref().and_then(result_data::elapsed_ms)
+    }
 }
 
 /// Executes automation actions against a simulator.
@@ -93,12 +134,266 @@ impl ExecutionResult {
 pub struct ActionExecutor {
     /// The automation driver backend.
     driver: Arc<dyn AutomationDriver>,
+    /// Token that aborts the polling loops of wait actions when cancelled.
+    cancel: Option<CancellationToken>,
+    /// UDID of the iOS simulator, for actions that go through simctl.
+    simulator_udid: Option<String>,
+    /// Retry with the closest element when a selector matches nothing.
+    fuzzy_selectors: bool,
+    /// Time between polls of wait actions that don't set their own.
+    poll_interval: Duration,
+}
+
+/// Backspace, which the agent's text input treats as the delete key.
+const DELETE_KEY: &str = "\u{8}";
+
+/// Environment variable that turns [`ActionExecutor::with_fuzzy_selectors`] on
+/// or off in the server, overriding a driver config's `fuzzy_selectors`.
+pub const FUZZY_SELECTORS_ENV: &str = "QORVEX_FUZZY_SELECTORS";
+
+/// Reads [`FUZZY_SELECTORS_ENV`]: `1`, `true` or `yes` enable fuzzy selectors.
+pub fn fuzzy_selectors_from_env() -> bool {
+    std::env::var(FUZZY_SELECTORS_ENV)
+        .ok()
+        .and_then(|v| parse_fuzzy_selectors(&v))
+        .unwrap_or(false)
+}
+
+/// Parses a [`FUZZY_SELECTORS_ENV`] value: `1`, `true` or `yes` turn fuzzy
+/// selectors on, `0`, `false` or `no` off, and anything else is `None`.
+pub(crate) fn parse_fuzzy_selectors(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" => Some(true),
+        "0" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Default time between polls of `WaitFor` and `WaitForNot`.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Returns the selector of actions that fuzzy matching can repair.
+///
+/// `WaitForNot` is left out: a mistyped selector there already succeeds.
+fn element_selector(action: &ActionType) -> Option<Selector> {
+    // Glob patterns are deliberate; don't second-guess them
+    Selector::from_action(action).filter(|s| !s.is_pattern())
+}
+
+/// Returns the selector of a [`MatchMode::Glob`] action, if it is one.
+fn glob_selector(action: &ActionType) -> Option<Selector> {
+    Selector::from_action(action).filter(|s| s.match_mode == MatchMode::Glob)
+}
+
+/// Returns `action` with its element selector replaced by `selector`, matched
+/// exactly.
+fn with_selector(action: ActionType, selector: String) -> ActionType {
+    match action {
+        ActionType::Tap {
+            by_label,
+            element_type,
+            timeout_ms,
+            require_enabled,
+            ..
+        } => ActionType::Tap {
+            selector,
+            by_label,
+            element_type,
+            timeout_ms,
+            require_enabled,
+            match_mode: MatchMode::Exact,
+            first: false,
+        },
+        ActionType::WaitFor {
+            by_label,
+            element_type,
+            timeout_ms,
+            require_stable,
+            poll_interval_ms,
+            ..
+        } => ActionType::WaitFor {
+            selector,
+            by_label,
+            element_type,
+            timeout_ms,
+            require_stable,
+            poll_interval_ms,
+            match_mode: MatchMode::Exact,
+            first: false,
+        },
+        ActionType::GetValue {
+            by_label,
+            element_type,
+            timeout_ms,
+            ..
+        } => ActionType::GetValue {
+            selector,
+            by_label,
+            element_type,
+            timeout_ms,
+            match_mode: MatchMode::Exact,
+            first: false,
+        },
+        ActionType::GetElementScreenshot { by_label, .. } => {
+            ActionType::GetElementScreenshot { selector, by_label }
+        }
+        ActionType::ClearText { by_label, .. } => ActionType::ClearText { selector, by_label },
+        ActionType::LongPressElement {
+            by_label, duration, ..
+        } => ActionType::LongPressElement {
+            selector,
+            by_label,
+            duration,
+        },
+        other => other,
+    }
+}
+
+/// Returns the start and end points of a directional [`ActionType::Swipe`].
+///
+/// Uses reasonable default coordinates for a typical iOS screen: centered
+/// horizontally (195), swiping 600→300 for "up", etc.
+fn swipe_direction_points(direction: &str) -> Option<(i32, i32, i32, i32)> {
+    match direction {
+        "up" => Some((195, 600, 195, 300)),
+        "down" => Some((195, 300, 195, 600)),
+        "left" => Some((300, 420, 90, 420)),
+        "right" => Some((90, 420, 300, 420)),
+        _ => None,
+    }
+}
+
+/// A run of literal text or a special key within [`ActionType::SendKeys`] text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySegment {
+    /// Text to type as-is.
+    Text(String),
+    /// A key to press, written as a `{name}` token.
+    Key(SpecialKey),
+}
+
+/// Splits `send_keys` text into literal text and `{name}` tokens naming a
+/// [`SpecialKey`], e.g. `"user{tab}secret{return}"`.
+///
+/// Key names ignore case. Braces around anything else, such as `{x}` or a
+/// JSON object, are typed as-is.
+pub fn split_key_tokens(text: &str) -> Vec<KeySegment> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        let after = &rest[open + 1..];
+        let token = after.find('}').and_then(|close| {
+            SpecialKey::ALL
+                .into_iter()
+                .find(|k| k.as_str().eq_ignore_ascii_case(&after[..close]))
+                .map(|key| (key, close))
+        });
+        match token {
+            Some((key, close)) => {
+                literal.push_str(&rest[..open]);
+                if !literal.is_empty() {
+                    segments.push(KeySegment::Text(std::mem::take(&mut literal)));
+                }
+                segments.push(KeySegment::Key(key));
+                rest = &after[close + 1..];
+            }
+            None => {
+                literal.push_str(&rest[..=open]);
+                rest = after;
+            }
+        }
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        segments.push(KeySegment::Text(literal));
+    }
+    segments
+}
+
+/// Returns true if `text` contains a `{name}` special-key token.
+fn has_key_tokens(text: &str) -> bool {
+    split_key_tokens(text)
+        .iter()
+        .any(|s| matches!(s, KeySegment::Key(_)))
+}
+
+/// Returns the single agent request `action` maps to, with the message to
+/// report once it succeeds, if it can run inside a [`Request::Batch`].
+///
+/// Only plain gestures and typing qualify; anything that looks up elements,
+/// polls or fails validation runs on its own through
+/// [`execute`](ActionExecutor::execute).
+fn batch_request(action: &ActionType) -> Option<(Request, String)> {
+    match *action {
+        ActionType::TapLocation { x, y } if x >= 0 && y >= 0 => Some((
+            Request::TapCoord { x, y },
+            format!("Tapped at ({}, {})", x, y),
+        )),
+        ActionType::Swipe { ref direction } => {
+            let (start_x, start_y, end_x, end_y) = swipe_direction_points(direction)?;
+            Some((
+                Request::Swipe {
+                    start_x,
+                    start_y,
+                    end_x,
+                    end_y,
+                    duration: Some(0.3),
+                },
+                format!("Swiped {}", direction),
+            ))
+        }
+        ActionType::SwipeCoords {
+            start_x,
+            start_y,
+            end_x,
+            end_y,
+            duration,
+        } if [start_x, start_y, end_x, end_y].iter().all(|&c| c >= 0)
+            && duration.is_none_or(|d| d.is_finite() && d > 0.0) =>
+        {
+            Some((
+                Request::Swipe {
+                    start_x,
+                    start_y,
+                    end_x,
+                    end_y,
+                    duration: duration.or(Some(0.3)),
+                },
+                format!(
+                    "Swiped from ({}, {}) to ({}, {})",
+                    start_x, start_y, end_x, end_y
+                ),
+            ))
+        }
+        ActionType::LongPress { x, y, duration } => Some((
+            Request::LongPress { x, y, duration },
+            format!("Long pressed at ({}, {}) for {:.1}s", x, y, duration),
+        )),
+        ActionType::SendKeys { ref text } if !has_key_tokens(text) => Some((
+            Request::TypeText { text: text.clone() },
+            format!("Sent keys: '{}'", text),
+        )),
+        ActionType::SendKey { key } => Some((Request::SendKey { key }, format!("Pressed {}", key))),
+        _ => None,
+    }
+}
+
+/// Returns true if `result` failed because no element matched the selector.
+///
+/// Backends report this as [`DriverError::ElementNotFound`]; the message
+/// check covers the executor's own wait timeouts.
+fn is_missing_element(result: &ExecutionResult) -> bool {
+    !result.success
+        && (result.element_not_found
+            || result.message.contains("not found")
+            || result.message.contains("waiting for element"))
 }
 
 /// Returns true if the driver error is transient and the action should be retried.
-#[allow(dead_code)]
 fn is_retryable_error(err: &DriverError) -> bool {
     match err {
+        DriverError::ElementNotFound { .. } => true,
         DriverError::CommandFailed(msg) => {
             msg.contains("not found") || msg.contains("not hittable")
         }
@@ -113,7 +408,55 @@ impl ActionExecutor {
     ///
     /// * `driver` - The automation driver to use for executing actions
     pub fn new(driver: Arc<dyn AutomationDriver>) -> Self {
-        Self { driver }
+        Self {
+            driver,
+            cancel: None,
+            simulator_udid: None,
+            fuzzy_selectors: false,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Attaches a cancellation token to this executor.
+    ///
+    /// When the token is cancelled, a running wait action stops polling at its
+    /// next interval and fails with [`DriverError::Cancelled`]. A driver call
+    /// already in flight is allowed to finish so the agent connection stays in
+    /// sync.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Sets the UDID of the iOS simulator the driver is attached to.
+    ///
+    /// Needed by [`ActionType::PasteText`], which fills the pasteboard with
+    /// `simctl`. Leave unset for Android and physical devices.
+    pub fn with_simulator_udid(mut self, udid: Option<String>) -> Self {
+        self.simulator_udid = udid;
+        self
+    }
+
+    /// Controls what happens when a selector matches no element.
+    ///
+    /// The executor looks for the most similar identifier (or label) on
+    /// screen. When `enabled`, the action is retried once with that element;
+    /// otherwise the failure message suggests it ("did you mean ...?").
+    pub fn with_fuzzy_selectors(mut self, enabled: bool) -> Self {
+        self.fuzzy_selectors = enabled;
+        self
+    }
+
+    /// Sets how often `WaitFor` and `WaitForNot` poll for the element, unless
+    /// the action sets its own `poll_interval_ms`. Defaults to
+    /// [`DEFAULT_POLL_INTERVAL`]; [`from_config`](Self::from_config) takes it
+    /// from the config's `poll_interval_ms`.
+    ///
+    /// Slow links such as USB-tunneled devices benefit from a longer
+    /// interval; fast simulators from a shorter one.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
     }
 
     /// Convenience constructor: create an executor using the [`AgentDriver`](crate::agent_driver::AgentDriver) backend.
@@ -141,7 +484,9 @@ impl ActionExecutor {
         Ok(Self::new(Arc::new(driver)))
     }
 
-    /// Create an executor from a [`DriverConfig`](crate::driver::DriverConfig).
+    /// Create an executor from a [`DriverConfig`](crate::driver::DriverConfig),
+    /// building its driver with [`driver::create`](crate::driver::create) and
+    /// polling at the config's poll interval, if it sets one.
     ///
     /// The driver is **not** connected yet. Call [`connect`](Self::connect) before
     /// executing actions, or use [`from_config_connected`](Self::from_config_connected).
@@ -149,51 +494,32 @@ impl ActionExecutor {
     /// # Arguments
     ///
     /// * `config` - The driver configuration specifying which backend to use
-    pub fn from_config(config: crate::driver::DriverConfig) -> Self {
-        match config {
-            crate::driver::DriverConfig::Agent { host, port } => Self::with_agent(host, port),
-            crate::driver::DriverConfig::Device { udid, device_port } => Self::new(Arc::new(
-                crate::agent_driver::AgentDriver::usb_device(udid, device_port),
-            )),
-            crate::driver::DriverConfig::Android {
-                serial,
-                local_port,
-                device_port,
-            } => Self::new(Arc::new(crate::android_driver::AndroidDriver::new(
-                serial,
-                Some(local_port),
-                device_port,
-            ))),
-        }
+    pub fn from_config(
+        config: crate::driver::DriverConfig,
+    ) -> Result<Self, crate::driver::DriverError> {
+        let driver = crate::driver::create(&config)?;
+        Ok(Self::new(Arc::from(driver)).with_config_settings(&config))
     }
 
     /// Like [`from_config`](Self::from_config) but connects immediately.
     pub async fn from_config_connected(
         config: crate::driver::DriverConfig,
     ) -> Result<Self, crate::driver::DriverError> {
-        match config {
-            crate::driver::DriverConfig::Agent { host, port } => {
-                Self::with_agent_connected(host, port).await
-            }
-            crate::driver::DriverConfig::Device { udid, device_port } => {
-                let mut driver = crate::agent_driver::AgentDriver::usb_device(udid, device_port);
-                driver.connect().await?;
-                Ok(Self::new(Arc::new(driver)))
-            }
-            crate::driver::DriverConfig::Android {
-                serial,
-                local_port,
-                device_port,
-            } => {
-                let mut driver = crate::android_driver::AndroidDriver::new(
-                    serial,
-                    Some(local_port),
-                    device_port,
-                );
-                driver.connect().await?;
-                Ok(Self::new(Arc::new(driver)))
-            }
+        let mut driver = crate::driver::create(&config)?;
+        driver.connect().await?;
+        Ok(Self::new(Arc::from(driver)).with_config_settings(&config))
+    }
+
+    /// Applies `config`'s poll interval and fuzzy selectors, keeping the
+    /// current value of each it sets none for.
+    fn with_config_settings(mut self, config: &crate::driver::DriverConfig) -> Self {
+        if let Some(interval) = config.poll_interval() {
+            self = self.with_poll_interval(interval);
         }
+        if let Some(enabled) = config.fuzzy_selectors() {
+            self = self.with_fuzzy_selectors(enabled);
+        }
+        self
     }
 
     /// Returns a reference to the underlying driver.
@@ -220,7 +546,25 @@ impl ActionExecutor {
         let span = info_span!("execute_action", action = action_name);
         async {
             let start = Instant::now();
-            let result = self.execute_inner(action).await;
+            let glob = glob_selector(&action);
+            let resolved = match glob {
+                Some(ref selector) => self.resolve_glob(action, selector).await,
+                None => Ok(action),
+            };
+            let mut result = match resolved {
+                Ok(action) => {
+                    let retry = element_selector(&action).is_some().then(|| action.clone());
+                    let mut result = self.execute_inner(action).await;
+                    if let Some(action) = retry.filter(|_| is_missing_element(&result)) {
+                        result = self.recover_missing_element(action, result).await;
+                    }
+                    result
+                }
+                Err(failed) => failed,
+            };
+            if let Some(selector) = glob.filter(|_| result.success) {
+                result.message = format!("{} (glob match for '{}')", result.message, selector.text);
+            }
             let elapsed = start.elapsed();
             debug!(
                 elapsed_ms = elapsed.as_millis() as u64,
@@ -233,6 +577,213 @@ impl ActionExecutor {
         .await
     }
 
+    /// Executes `actions` in order, sending each run of consecutive plain
+    /// gestures and typing to the agent as one [`Request::Batch`].
+    ///
+    /// Stops at the first failure: the returned results cover the actions
+    /// that ran, the last one being the failure. Actions that need more than a
+    /// single agent request (element lookups, waits) run on their own through
+    /// [`execute`](Self::execute), as does every action when the driver does
+    /// not support batches.
+    pub async fn execute_batch(&self, actions: Vec<ActionType>) -> Vec<ExecutionResult> {
+        let span = info_span!("execute_batch", count = actions.len());
+        async {
+            let mut results = Vec::with_capacity(actions.len());
+            let mut pending = Vec::new();
+            for action in actions {
+                match batch_request(&action) {
+                    Some((request, message)) => pending.push((action, request, message)),
+                    None => {
+                        if !self
+                            .flush_batch(std::mem::take(&mut pending), &mut results)
+                            .await
+                        {
+                            return results;
+                        }
+                        let result = self.execute(action).await;
+                        let failed = !result.success;
+                        results.push(result);
+                        if failed {
+                            return results;
+                        }
+                    }
+                }
+            }
+            self.flush_batch(pending, &mut results).await;
+            results
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Sends the packed actions of [`execute_batch`](Self::execute_batch) in
+    /// one round trip, appending their results. Returns false if one failed.
+    async fn flush_batch(
+        &self,
+        pending: Vec<(ActionType, Request, String)>,
+        results: &mut Vec<ExecutionResult>,
+    ) -> bool {
+        if pending.is_empty() {
+            return true;
+        }
+        let requests = pending.iter().map(|(_, req, _)| req.clone()).collect();
+        let responses = match self.driver.send_batch(requests).await {
+            Ok(responses) => responses,
+            Err(DriverError::Unsupported(_)) => {
+                debug!("batches unsupported; executing one at a time");
+                for (action, _, _) in pending {
+                    let result = self.execute(action).await;
+                    let failed = !result.success;
+                    results.push(result);
+                    if failed {
+                        return false;
+                    }
+                }
+                return true;
+            }
+            Err(e) => {
+                results.push(ExecutionResult::from_error(e));
+                return false;
+            }
+        };
+
+        let count = pending.len();
+        let mut responses = responses.into_iter();
+        for (ran, (_, _, message)) in pending.into_iter().enumerate() {
+            let result = match responses.next() {
+                Some(Ok(Response::Ok)) => ExecutionResult::success(message),
+                Some(Ok(other)) => {
+                    ExecutionResult::failure(format!("unexpected response: {:?}", other))
+                }
+                Some(Err(e)) => ExecutionResult::from_error(e),
+                None => ExecutionResult::failure(format!(
+                    "Agent stopped the batch after {} of {} requests",
+                    ran, count
+                )),
+            };
+            let failed = !result.success;
+            results.push(result);
+            if failed {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Resolves a [`MatchMode::Glob`] action to the one element its pattern
+    /// matches.
+    ///
+    /// Lists the matches with [`find_all`](AutomationDriver::find_all),
+    /// polling until the action's timeout while there are none, and returns
+    /// the action rewritten to select that element exactly. Several matches
+    /// fail the action unless it asks for the first one.
+    async fn resolve_glob(
+        &self,
+        action: ActionType,
+        selector: &Selector,
+    ) -> Result<ActionType, ExecutionResult> {
+        let (timeout_ms, poll_interval_ms, first) = match action {
+            ActionType::Tap {
+                timeout_ms, first, ..
+            }
+            | ActionType::GetValue {
+                timeout_ms, first, ..
+            } => (timeout_ms, None, first),
+            ActionType::WaitFor {
+                timeout_ms,
+                poll_interval_ms,
+                first,
+                ..
+            } => (Some(timeout_ms), poll_interval_ms, first),
+            other => return Ok(other),
+        };
+        let pattern = selector.text.as_str();
+
+        let start = Instant::now();
+        let timeout = Duration::from_millis(timeout_ms.unwrap_or(0));
+        let poll_interval = self.effective_poll_interval(poll_interval_ms);
+        let matches = loop {
+            match self
+                .driver
+                .find_all(pattern, selector.by_label, selector.element_type.as_deref())
+                .await
+            {
+                Ok(matches) if !matches.is_empty() => break matches,
+                Ok(_) if start.elapsed() >= timeout => {
+                    return Err(ExecutionResult::failure(format!(
+                        "No element matches glob '{}'",
+                        pattern
+                    )));
+                }
+                Ok(_) => {}
+                Err(e) => return Err(ExecutionResult::from_error(e)),
+            }
+            if let Err(e) = self.poll_sleep(poll_interval, start, timeout).await {
+                return Err(ExecutionResult::from_error(e));
+            }
+        };
+
+        let selectors: Vec<&str> = matches.iter().filter_map(|e| selector.key(e)).collect();
+        if matches.len() > 1 && !first {
+            return Err(ExecutionResult::failure(format!(
+                "Glob '{}' matches {} elements ({}); narrow the pattern or take the first match",
+                pattern,
+                matches.len(),
+                selectors.join(", ")
+            )));
+        }
+        match selectors.first() {
+            Some(selector) => {
+                debug!(%pattern, %selector, matches = matches.len(), "resolved glob selector");
+                Ok(with_selector(action, selector.to_string()))
+            }
+            None => Err(ExecutionResult::failure(format!(
+                "Element matching glob '{}' has no {} to select it by",
+                pattern,
+                if selector.by_label {
+                    "label"
+                } else {
+                    "identifier"
+                }
+            ))),
+        }
+    }
+
+    /// Handles an action whose selector matched no element.
+    ///
+    /// Finds the most similar selector on screen, then either retries with it
+    /// (see [`with_fuzzy_selectors`](Self::with_fuzzy_selectors)) or adds it
+    /// to the failure message. Returns `failed` unchanged when there is no
+    /// close match or the tree cannot be read.
+    async fn recover_missing_element(
+        &self,
+        action: ActionType,
+        failed: ExecutionResult,
+    ) -> ExecutionResult {
+        let Some(selector) = element_selector(&action) else {
+            return failed;
+        };
+        let Ok(tree) = self.driver.dump_tree().await else {
+            return failed;
+        };
+        let Some(suggestion) = selector.suggest(&tree) else {
+            return failed;
+        };
+        let (selector, suggestion) = (selector.text, suggestion.to_string());
+
+        if !self.fuzzy_selectors {
+            let mut failed = failed;
+            failed.message = format!("{}; did you mean '{}'?", failed.message, suggestion);
+            return failed;
+        }
+        info!(%selector, %suggestion, "retrying with fuzzy selector match");
+        let mut result = self
+            .execute_inner(with_selector(action, suggestion.clone()))
+            .await;
+        result.message = format!("{} (fuzzy match for '{}')", result.message, selector);
+        result
+    }
+
     async fn execute_inner(&self, action: ActionType) -> ExecutionResult {
         match action {
             ActionType::Tap {
@@ -240,28 +791,51 @@ impl ActionExecutor {
                 by_label,
                 ref element_type,
                 timeout_ms,
+                require_enabled,
+                ..
             } => {
                 let start = Instant::now();
 
-                let tap_result = if timeout_ms.is_some() {
-                    // Forward timeout to agent — it handles retry internally.
-                    match element_type {
-                        Some(typ) => {
-                            self.driver
-                                .tap_with_type_with_timeout(selector, by_label, typ, timeout_ms)
-                                .await
-                        }
-                        None if by_label => {
-                            self.driver
-                                .tap_by_label_with_timeout(selector, timeout_ms)
-                                .await
-                        }
-                        None => {
-                            self.driver
-                                .tap_element_with_timeout(selector, timeout_ms)
-                                .await
+                // An element not on screen yet is left to the tap's own retry
+                if require_enabled {
+                    if let Ok(Some(element)) = self
+                        .driver
+                        .find_element_with_type(selector, by_label, element_type.as_deref())
+                        .await
+                    {
+                        if element.enabled == Some(false) {
+                            return ExecutionResult::failure(if by_label {
+                                format!("Element with label '{}' is disabled", selector)
+                            } else {
+                                format!("Element '{}' is disabled", selector)
+                            });
                         }
                     }
+                }
+
+                let tap_result = if let Some(ms) = timeout_ms {
+                    // Forward timeout to agent — it handles retry internally.
+                    let (selector, element_type) = (selector.as_str(), element_type.as_deref());
+                    self.retry_transient(start, Duration::from_millis(ms), || async move {
+                        match element_type {
+                            Some(typ) => {
+                                self.driver
+                                    .tap_with_type_with_timeout(selector, by_label, typ, timeout_ms)
+                                    .await
+                            }
+                            None if by_label => {
+                                self.driver
+                                    .tap_by_label_with_timeout(selector, timeout_ms)
+                                    .await
+                            }
+                            None => {
+                                self.driver
+                                    .tap_element_with_timeout(selector, timeout_ms)
+                                    .await
+                            }
+                        }
+                    })
+                    .await
                 } else {
                     // No timeout — single attempt (no retry)
                     match element_type {
@@ -279,8 +853,7 @@ impl ActionExecutor {
                         } else {
                             format!("Tapped element '{}'", selector)
                         };
-                        ExecutionResult::success(msg)
-                            .with_data(format!(r#"{{"elapsed_ms":{}}}"#, elapsed_ms))
+                        ExecutionResult::success(msg).with_data(WaitData::new(elapsed_ms).to_data())
                     }
                     Err(e) => {
                         ExecutionResult::failure(format!("Timeout after {}ms: {}", elapsed_ms, e))
@@ -299,24 +872,37 @@ impl ActionExecutor {
 
                 match self.driver.tap_location(x, y).await {
                     Ok(_) => ExecutionResult::success(format!("Tapped at ({}, {})", x, y)),
-                    Err(e) => ExecutionResult::failure(e.to_string()),
+                    Err(e) => ExecutionResult::from_error(e),
+                }
+            }
+
+            ActionType::MultiTap { finger_count, x, y } => {
+                if x < 0 || y < 0 {
+                    return ExecutionResult::failure(format!(
+                        "Coordinates must be non-negative (got x={}, y={})",
+                        x, y
+                    ));
+                }
+
+                match self.driver.multi_tap(finger_count, x, y).await {
+                    Ok(_) => ExecutionResult::success(format!(
+                        "Tapped with {} fingers at ({}, {})",
+                        finger_count, x, y
+                    )),
+                    Err(DriverError::Unsupported(_)) => ExecutionResult::failure(
+                        "Multi-finger tap is not supported by this backend",
+                    ),
+                    Err(e) => ExecutionResult::from_error(e),
                 }
             }
 
             ActionType::Swipe { ref direction } => {
-                // Use reasonable default coordinates for a typical iOS screen.
-                // Center horizontally (195), swipe from 600→300 for "up", etc.
-                let (start_x, start_y, end_x, end_y) = match direction.as_str() {
-                    "up" => (195, 600, 195, 300),
-                    "down" => (195, 300, 195, 600),
-                    "left" => (300, 420, 90, 420),
-                    "right" => (90, 420, 300, 420),
-                    _ => {
-                        return ExecutionResult::failure(format!(
-                            "Invalid swipe direction '{}'. Use: up, down, left, right",
-                            direction
-                        ));
-                    }
+                let Some((start_x, start_y, end_x, end_y)) = swipe_direction_points(direction)
+                else {
+                    return ExecutionResult::failure(format!(
+                        "Invalid swipe direction '{}'. Use: up, down, left, right",
+                        direction
+                    ));
                 };
 
                 match self
@@ -325,23 +911,131 @@ impl ActionExecutor {
                     .await
                 {
                     Ok(_) => ExecutionResult::success(format!("Swiped {}", direction)),
-                    Err(e) => ExecutionResult::failure(e.to_string()),
+                    Err(e) => ExecutionResult::from_error(e),
                 }
             }
 
+            ActionType::TapElementOffset {
+                ref selector,
+                by_label,
+                dx_fraction,
+                dy_fraction,
+            } => match self
+                .tap_element_offset(selector, by_label, dx_fraction, dy_fraction)
+                .await
+            {
+                Ok((x, y)) => {
+                    ExecutionResult::success(format!("Tapped '{}' at ({}, {})", selector, x, y))
+                }
+                Err(e) => ExecutionResult::from_error(e),
+            },
+
+            ActionType::SwipeCoords {
+                start_x,
+                start_y,
+                end_x,
+                end_y,
+                duration,
+            } => {
+                if [start_x, start_y, end_x, end_y].iter().any(|&c| c < 0) {
+                    return ExecutionResult::failure("Swipe coordinates must be non-negative");
+                }
+                if let Some(d) = duration.filter(|d| !d.is_finite() || *d <= 0.0) {
+                    return ExecutionResult::failure(format!(
+                        "Swipe duration must be positive (got {})",
+                        d
+                    ));
+                }
+                match self
+                    .driver
+                    .swipe(start_x, start_y, end_x, end_y, duration.or(Some(0.3)))
+                    .await
+                {
+                    Ok(_) => ExecutionResult::success(format!(
+                        "Swiped from ({}, {}) to ({}, {})",
+                        start_x, start_y, end_x, end_y
+                    )),
+                    Err(e) => ExecutionResult::from_error(e),
+                }
+            }
+
+            ActionType::SwipeElement {
+                ref selector,
+                by_label,
+                ref direction,
+            } => match self
+                .swipe_within_element(selector, by_label, direction)
+                .await
+            {
+                Ok(()) => {
+                    ExecutionResult::success(format!("Swiped {} in '{}'", direction, selector))
+                }
+                Err(e) => ExecutionResult::from_error(e),
+            },
+
             ActionType::LongPress { x, y, duration } => {
                 match self.driver.long_press(x, y, duration).await {
                     Ok(_) => ExecutionResult::success(format!(
                         "Long pressed at ({}, {}) for {:.1}s",
                         x, y, duration
                     )),
-                    Err(e) => ExecutionResult::failure(e.to_string()),
+                    Err(e) => ExecutionResult::from_error(e),
                 }
             }
 
-            ActionType::SendKeys { ref text } => match self.driver.type_text(text).await {
+            ActionType::LongPressElement {
+                ref selector,
+                by_label,
+                duration,
+            } => match self.long_press_element(selector, by_label, duration).await {
+                Ok(()) => ExecutionResult::success(format!(
+                    "Long pressed '{}' for {:.1}s",
+                    selector, duration
+                )),
+                Err(e) => ExecutionResult::from_error(e),
+            },
+
+            ActionType::Drag {
+                ref from,
+                ref to,
+                by_label,
+                hold_duration,
+                move_duration,
+            } => match self
+                .drag_element_to_element(from, to, by_label, hold_duration, move_duration)
+                .await
+            {
+                Ok(()) => ExecutionResult::success(format!("Dragged '{}' to '{}'", from, to)),
+                Err(e) => ExecutionResult::from_error(e),
+            },
+
+            ActionType::SendKeys { ref text } => match self.send_keys(text).await {
                 Ok(_) => ExecutionResult::success(format!("Sent keys: '{}'", text)),
-                Err(e) => ExecutionResult::failure(e.to_string()),
+                Err(e) => ExecutionResult::from_error(e),
+            },
+
+            ActionType::SendKey { key } => match self.driver.send_key(key).await {
+                Ok(()) => ExecutionResult::success(format!("Pressed {}", key)),
+                Err(DriverError::Unsupported(_)) => {
+                    ExecutionResult::failure("Special keys are not supported by this backend")
+                }
+                Err(e) => ExecutionResult::from_error(e),
+            },
+
+            ActionType::ClearText {
+                ref selector,
+                by_label,
+            } => match self.clear_text(selector, by_label).await {
+                Ok(()) => ExecutionResult::success(format!("Cleared text of '{}'", selector)),
+                Err(e) => ExecutionResult::from_error(e),
+            },
+
+            ActionType::PasteText { ref text } => match self.paste_text(text).await {
+                Ok(()) => ExecutionResult::success(format!("Pasted: '{}'", text)),
+                Err(DriverError::Unsupported(_)) => {
+                    ExecutionResult::failure("Pasting is not supported by this backend")
+                }
+                Err(e) => ExecutionResult::from_error(e),
             },
 
             ActionType::GetScreenshot => match self.driver.screenshot().await {
@@ -352,15 +1046,69 @@ impl ActionExecutor {
                         .with_screenshot(b64.clone())
                         .with_data(b64)
                 }
-                Err(e) => ExecutionResult::failure(e.to_string()),
+                Err(e) => ExecutionResult::from_error(e),
             },
 
-            ActionType::GetScreenInfo => match self.driver.list_elements().await {
-                Ok(elements) => match serde_json::to_string(&elements) {
-                    Ok(json) => ExecutionResult::success("Screen info retrieved").with_data(json),
-                    Err(e) => ExecutionResult::failure(format!("JSON serialization error: {}", e)),
-                },
-                Err(e) => ExecutionResult::failure(e.to_string()),
+            ActionType::GetElementScreenshot {
+                ref selector,
+                by_label,
+            } => match self.screenshot_element(selector, by_label).await {
+                Ok(bytes) => {
+                    use base64::Engine;
+                    let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+                    ExecutionResult::success(format!("Captured element: {}", selector))
+                        .with_screenshot(b64.clone())
+                        .with_data(b64)
+                }
+                Err(e) => ExecutionResult::from_error(e),
+            },
+
+            ActionType::GetScreenInfo { ref filter, .. } => {
+                match self.screen_elements(filter).await {
+                    Ok(elements) => match serde_json::to_string(&elements) {
+                        Ok(json) => {
+                            ExecutionResult::success("Screen info retrieved").with_data(json)
+                        }
+                        Err(e) => {
+                            ExecutionResult::failure(format!("JSON serialization error: {}", e))
+                        }
+                    },
+                    Err(e) => ExecutionResult::from_error(e),
+                }
+            }
+
+            ActionType::GetAttribute {
+                ref selector,
+                by_label,
+                ref element_type,
+                attribute,
+            } => match self
+                .driver
+                .find_element_with_type(selector, by_label, element_type.as_deref())
+                .await
+            {
+                Ok(Some(element)) => {
+                    let value = element.attribute(attribute);
+                    let target = if by_label {
+                        format!("label '{}'", selector)
+                    } else {
+                        format!("'{}'", selector)
+                    };
+                    let msg = if value.is_null() {
+                        format!("Element {} has no {}", target, attribute)
+                    } else {
+                        format!("Got {} of {}", attribute, target)
+                    };
+                    ExecutionResult::success(msg).with_data(value.to_string())
+                }
+                Ok(None) => ExecutionResult::from_error(DriverError::ElementNotFound {
+                    selector: if by_label {
+                        format!("label '{}'", selector)
+                    } else {
+                        format!("ID '{}'", selector)
+                    },
+                }),
+                Err(e) => ExecutionResult::from_error(e),
             },
 
             ActionType::GetValue {
@@ -368,20 +1116,19 @@ impl ActionExecutor {
                 by_label,
                 ref element_type,
                 timeout_ms,
+                ..
             } => {
                 let start = Instant::now();
 
-                let value_result = if timeout_ms.is_some() {
+                let value_result = if let Some(ms) = timeout_ms {
                     // Forward timeout to agent — it handles retry internally.
-                    self.driver
-                        .get_value_with_timeout(
-                            selector,
-                            by_label,
-                            element_type.as_deref(),
-                            timeout_ms,
-                        )
-                        .await
-                    // Normalize into the same Result<Option<String>> shape
+                    let (selector, element_type) = (selector.as_str(), element_type.as_deref());
+                    self.retry_transient(start, Duration::from_millis(ms), || async move {
+                        self.driver
+                            .get_value_with_timeout(selector, by_label, element_type, timeout_ms)
+                            .await
+                    })
+                    .await
                 } else {
                     // No timeout — single attempt (no retry)
                     match element_type {
@@ -419,20 +1166,133 @@ impl ActionExecutor {
                 }
             }
 
+            ActionType::ElementExists {
+                ref selector,
+                by_label,
+                ref element_type,
+            } => {
+                let found = self
+                    .driver
+                    .find_element_with_type(selector, by_label, element_type.as_deref())
+                    .await;
+                match found {
+                    Ok(element) => {
+                        let exists = element.is_some();
+                        let target = if by_label {
+                            format!("Element with label '{}'", selector)
+                        } else {
+                            format!("Element '{}'", selector)
+                        };
+                        let msg = if exists {
+                            format!("{} exists", target)
+                        } else {
+                            format!("{} does not exist", target)
+                        };
+                        ExecutionResult::success(msg).with_data(exists.to_string())
+                    }
+                    Err(e) => ExecutionResult::from_error(e),
+                }
+            }
+
+            ActionType::TapIfExists {
+                ref selector,
+                by_label,
+                ref element_type,
+            } => {
+                let found = self
+                    .driver
+                    .find_element_with_type(selector, by_label, element_type.as_deref())
+                    .await;
+                let target = if by_label {
+                    format!("element with label '{}'", selector)
+                } else {
+                    format!("element '{}'", selector)
+                };
+                match found {
+                    Ok(Some(_)) => {
+                        // Present, so a single attempt: no retry, as with `--no-wait`
+                        let tap_result = match element_type {
+                            Some(typ) => self.driver.tap_with_type(selector, by_label, typ).await,
+                            None if by_label => self.driver.tap_by_label(selector).await,
+                            None => self.driver.tap_element(selector).await,
+                        };
+                        match tap_result {
+                            Ok(_) => ExecutionResult::success(format!("Tapped {}", target))
+                                .with_data(TapIfExistsData::new(true).to_data()),
+                            Err(e) => ExecutionResult::from_error(e),
+                        }
+                    }
+                    Ok(None) => {
+                        ExecutionResult::success(format!("No {} on screen; nothing tapped", target))
+                            .with_data(TapIfExistsData::new(false).to_data())
+                    }
+                    Err(e) => ExecutionResult::from_error(e),
+                }
+            }
+
+            ActionType::FindAll {
+                ref selector,
+                by_label,
+                ref element_type,
+            } => {
+                let found = self
+                    .driver
+                    .find_all(selector, by_label, element_type.as_deref())
+                    .await;
+                match found {
+                    Ok(elements) => {
+                        let noun = if elements.len() == 1 {
+                            "element"
+                        } else {
+                            "elements"
+                        };
+                        let msg = format!("Found {} {}", elements.len(), noun);
+                        match serde_json::to_string(&elements) {
+                            Ok(json) => ExecutionResult::success(msg).with_data(json),
+                            Err(e) => ExecutionResult::failure(e.to_string()),
+                        }
+                    }
+                    Err(e) => ExecutionResult::from_error(e),
+                }
+            }
+
             ActionType::LogComment { ref message } => {
                 ExecutionResult::success(format!("Logged: {}", message))
             }
 
+            ActionType::Assertion {
+                ref description,
+                passed,
+            } => {
+                if passed {
+                    ExecutionResult::success(format!("Assertion passed: {}", description))
+                } else {
+                    ExecutionResult::failure(format!("Assertion failed: {}", description))
+                }
+            }
+
+            ActionType::Wait { ms } => {
+                let start = Instant::now();
+                let duration = Duration::from_millis(ms);
+                match self.poll_sleep(duration, start, duration).await {
+                    Ok(()) => ExecutionResult::success(format!("Waited {}ms", ms))
+                        .with_data(WaitData::new(start.elapsed().as_millis() as u64).to_data()),
+                    Err(e) => ExecutionResult::from_error(e),
+                }
+            }
+
             ActionType::WaitFor {
                 ref selector,
                 by_label,
                 ref element_type,
                 timeout_ms,
                 require_stable,
+                poll_interval_ms,
+                ..
             } => {
                 let mut start = Instant::now();
                 let timeout = Duration::from_millis(timeout_ms);
-                let poll_interval = Duration::from_millis(100);
+                let poll_interval = self.effective_poll_interval(poll_interval_ms);
                 let stable_polls_required = 3;
                 let mut last_frame: Option<(f64, f64, f64, f64)> = None;
                 let mut stable_count: u32 = 0;
@@ -463,12 +1323,16 @@ impl ActionExecutor {
                                         } else {
                                             format!("Timeout after {}ms: element '{}' exists but is not hittable", elapsed_ms, selector)
                                         };
-                                        return ExecutionResult::failure(msg).with_data(format!(
-                                            r#"{{"elapsed_ms":{}}}"#,
-                                            elapsed_ms
-                                        ));
+                                        return ExecutionResult::failure(msg)
+                                            .with_data(WaitData::new(elapsed_ms).to_data());
+                                    }
+                                    if let Err(e) =
+                                        self.poll_sleep(poll_interval, start, timeout).await
+                                    {
+                                        let elapsed_ms = start.elapsed().as_millis() as u64;
+                                        return ExecutionResult::from_error(e)
+                                            .with_data(WaitData::new(elapsed_ms).to_data());
                                     }
-                                    tokio::time::sleep(poll_interval).await;
                                     continue;
                                 }
 
@@ -495,15 +1359,9 @@ impl ActionExecutor {
                                     } else {
                                         format!("Element '{}' found", selector)
                                     };
-                                    let data = if let Some(ref frame) = element.frame {
-                                        format!(
-                                            r#"{{"elapsed_ms":{},"frame":{{"x":{},"y":{},"width":{},"height":{}}}}}"#,
-                                            elapsed_ms, frame.x, frame.y, frame.width, frame.height
-                                        )
-                                    } else {
-                                        format!(r#"{{"elapsed_ms":{}}}"#, elapsed_ms)
-                                    };
-                                    return ExecutionResult::success(msg).with_data(data);
+                                    let data =
+                                        WaitData::new(elapsed_ms).with_frame(element.frame.clone());
+                                    return ExecutionResult::success(msg).with_data(data.to_data());
                                 }
                             } else {
                                 // Fast path: element exists and is hittable, return immediately.
@@ -515,12 +1373,16 @@ impl ActionExecutor {
                                         } else {
                                             format!("Timeout after {}ms: element '{}' exists but is not hittable", elapsed_ms, selector)
                                         };
-                                        return ExecutionResult::failure(msg).with_data(format!(
-                                            r#"{{"elapsed_ms":{}}}"#,
-                                            elapsed_ms
-                                        ));
+                                        return ExecutionResult::failure(msg)
+                                            .with_data(WaitData::new(elapsed_ms).to_data());
+                                    }
+                                    if let Err(e) =
+                                        self.poll_sleep(poll_interval, start, timeout).await
+                                    {
+                                        let elapsed_ms = start.elapsed().as_millis() as u64;
+                                        return ExecutionResult::from_error(e)
+                                            .with_data(WaitData::new(elapsed_ms).to_data());
                                     }
-                                    tokio::time::sleep(poll_interval).await;
                                     continue;
                                 }
                                 let elapsed_ms = start.elapsed().as_millis() as u64;
@@ -530,7 +1392,7 @@ impl ActionExecutor {
                                     format!("Element '{}' found", selector)
                                 };
                                 return ExecutionResult::success(msg)
-                                    .with_data(format!(r#"{{"elapsed_ms":{}}}"#, elapsed_ms));
+                                    .with_data(WaitData::new(elapsed_ms).to_data());
                             }
                         } else {
                             last_frame = None;
@@ -559,9 +1421,13 @@ impl ActionExecutor {
                             )
                         };
                         return ExecutionResult::failure(msg)
-                            .with_data(format!(r#"{{"elapsed_ms":{}}}"#, elapsed_ms));
+                            .with_data(WaitData::new(elapsed_ms).to_data());
+                    }
+                    if let Err(e) = self.poll_sleep(poll_interval, start, timeout).await {
+                        let elapsed_ms = start.elapsed().as_millis() as u64;
+                        return ExecutionResult::from_error(e)
+                            .with_data(WaitData::new(elapsed_ms).to_data());
                     }
-                    tokio::time::sleep(poll_interval).await;
                 }
             }
 
@@ -570,10 +1436,11 @@ impl ActionExecutor {
                 by_label,
                 ref element_type,
                 timeout_ms,
+                poll_interval_ms,
             } => {
                 let mut start = Instant::now();
                 let timeout = Duration::from_millis(timeout_ms);
-                let poll_interval = Duration::from_millis(100);
+                let poll_interval = self.effective_poll_interval(poll_interval_ms);
                 let mut last_recovery = self.driver.recovery_count();
 
                 loop {
@@ -589,7 +1456,7 @@ impl ActionExecutor {
 
                     match found {
                         Err(e) => {
-                            return ExecutionResult::failure(format!("{}", e));
+                            return ExecutionResult::from_error(e);
                         }
                         Ok(ref opt) => {
                             let element_present =
@@ -602,7 +1469,7 @@ impl ActionExecutor {
                                     format!("Element '{}' not found", selector)
                                 };
                                 return ExecutionResult::success(msg)
-                                    .with_data(format!(r#"{{"elapsed_ms":{}}}"#, elapsed_ms));
+                                    .with_data(WaitData::new(elapsed_ms).to_data());
                             }
                         }
                     }
@@ -624,16 +1491,89 @@ impl ActionExecutor {
                             )
                         };
                         return ExecutionResult::failure(msg)
-                            .with_data(format!(r#"{{"elapsed_ms":{}}}"#, elapsed_ms));
+                            .with_data(WaitData::new(elapsed_ms).to_data());
+                    }
+                    if let Err(e) = self.poll_sleep(poll_interval, start, timeout).await {
+                        let elapsed_ms = start.elapsed().as_millis() as u64;
+                        return ExecutionResult::from_error(e)
+                            .with_data(WaitData::new(elapsed_ms).to_data());
                     }
-                    tokio::time::sleep(poll_interval).await;
                 }
             }
 
+            ActionType::WaitForAny {
+                ref selectors,
+                timeout_ms,
+                poll_interval_ms,
+            } => {
+                self.wait_for_selectors(selectors, false, timeout_ms, poll_interval_ms)
+                    .await
+            }
+
+            ActionType::WaitForAll {
+                ref selectors,
+                timeout_ms,
+                poll_interval_ms,
+            } => {
+                self.wait_for_selectors(selectors, true, timeout_ms, poll_interval_ms)
+                    .await
+            }
+
+            ActionType::WaitForIdle {
+                timeout_ms,
+                stable_ms,
+            } => {
+                let start = Instant::now();
+                let timeout = Duration::from_millis(timeout_ms);
+                let stable = Duration::from_millis(stable_ms);
+                // Poll at least twice per stable window so short windows work
+                let poll_interval = self
+                    .effective_poll_interval(None)
+                    .min(stable / 2)
+                    .max(Duration::from_millis(1));
+                let mut last_hash: Option<String> = None;
+                let mut stable_since = start;
+                let mut changes: u32 = 0;
+
+                loop {
+                    let hash = match self.driver.dump_tree().await {
+                        Ok(tree) => hash_elements(&tree),
+                        Err(e) => return ExecutionResult::from_error(e),
+                    };
+                    if last_hash.as_ref() != Some(&hash) {
+                        if last_hash.is_some() {
+                            changes += 1;
+                        }
+                        stable_since = Instant::now();
+                        last_hash = Some(hash);
+                    }
+
+                    let elapsed_ms = start.elapsed().as_millis() as u64;
+                    if stable_since.elapsed() >= stable {
+                        return ExecutionResult::success(format!(
+                            "Screen idle after {} changes",
+                            changes
+                        ))
+                        .with_data(IdleData::new(elapsed_ms, changes).to_data());
+                    }
+                    if start.elapsed() >= timeout {
+                        return ExecutionResult::failure(format!(
+                            "Timeout after {}ms waiting for the screen to settle ({} changes)",
+                            elapsed_ms, changes
+                        ))
+                        .with_data(IdleData::new(elapsed_ms, changes).to_data());
+                    }
+                    if let Err(e) = self.poll_sleep(poll_interval, start, timeout).await {
+                        let elapsed_ms = start.elapsed().as_millis() as u64;
+                        return ExecutionResult::from_error(e)
+                            .with_data(WaitData::new(elapsed_ms).to_data());
+                    }
+                }
+            }
             ActionType::SetTarget { ref bundle_id } => {
                 match self.driver.set_target(bundle_id).await {
                     Ok(_) => ExecutionResult::success(format!("Target set to '{}'", bundle_id)),
-                    Err(e) => ExecutionResult::failure(e.to_string()),
+                    Err(e) => ExecutionResult::from_error(e),
                 }
             }
 
@@ -646,7 +1586,495 @@ impl ActionExecutor {
             | ActionType::GetTargetInfo => ExecutionResult::failure(
                 "Session management actions must be handled by the session manager",
             ),
+
+            ActionType::SetOrientation { orientation } => {
+                match self.driver.set_orientation(orientation).await {
+                    Ok(()) => {
+                        ExecutionResult::success(format!("Orientation set to {}", orientation))
+                    }
+                    Err(e) => ExecutionResult::from_error(e),
+                }
+            }
+
+            // Appearance and locale go through simctl, which the session
+            // manager drives
+            ActionType::SetAppearance { .. } | ActionType::SetLocale { .. } => {
+                ExecutionResult::failure(
+                    "Device settings actions must be handled by the session manager",
+                )
+            }
+        }
+    }
+
+    /// Types `text`, pressing the keys named by its `{name}` tokens (see
+    /// [`split_key_tokens`]) in between. Text without tokens goes to the
+    /// driver in one [`type_text`](AutomationDriver::type_text) call.
+    ///
+    /// # Errors
+    ///
+    /// - Whatever the driver returns for the first segment that fails; the
+    ///   segments before it have already been typed
+    pub async fn send_keys(&self, text: &str) -> Result<(), DriverError> {
+        let segments = split_key_tokens(text);
+        if !segments.iter().any(|s| matches!(s, KeySegment::Key(_))) {
+            return self.driver.type_text(text).await;
+        }
+        for segment in segments {
+            match segment {
+                KeySegment::Text(text) => self.driver.type_text(&text).await?,
+                KeySegment::Key(key) => self.driver.send_key(key).await?,
+            }
         }
+        Ok(())
+    }
+
+    /// Deletes all text in the input field matching `selector`.
+    ///
+    /// Uses the driver's native [`clear_text`](AutomationDriver::clear_text)
+    /// when available. Otherwise taps the field to focus it and types one
+    /// delete per character of its current value.
+    ///
+    /// # Errors
+    ///
+    /// - [`DriverError::CommandFailed`] if the field is missing or the agent
+    ///   rejects a tap, value query or keystroke
+    pub async fn clear_text(&self, selector: &str, by_label: bool) -> Result<(), DriverError> {
+        match self.driver.clear_text(selector, by_label).await {
+            Err(DriverError::Unsupported(_)) => {
+                debug!(selector, "native clear unsupported; deleting per character");
+                self.clear_text_by_deleting(selector, by_label).await
+            }
+            other => other,
+        }
+    }
+
+    /// Fallback for [`clear_text`](Self::clear_text): focus, then backspace
+    /// over the current value.
+    async fn clear_text_by_deleting(
+        &self,
+        selector: &str,
+        by_label: bool,
+    ) -> Result<(), DriverError> {
+        let value = if by_label {
+            self.driver.get_element_value_by_label(selector).await?
+        } else {
+            self.driver.get_element_value(selector).await?
+        };
+        if by_label {
+            self.driver.tap_by_label(selector).await?;
+        } else {
+            self.driver.tap_element(selector).await?;
+        }
+
+        let count = value.map_or(0, |v| v.chars().count());
+        if count > 0 {
+            self.driver.type_text(&DELETE_KEY.repeat(count)).await?;
+        }
+        Ok(())
+    }
+
+    /// Pastes `text` into the focused element.
+    ///
+    /// Copies the text to the simulator pasteboard with `simctl pbcopy`, then
+    /// has the driver paste it, which avoids the autocorrect and per-key cost
+    /// of typing.
+    ///
+    /// # Errors
+    ///
+    /// - [`DriverError::CommandFailed`] if no simulator UDID is set (see
+    ///   [`with_simulator_udid`](Self::with_simulator_udid)) or simctl fails
+    /// - [`DriverError::Unsupported`] if the driver cannot paste
+    pub async fn paste_text(&self, text: &str) -> Result<(), DriverError> {
+        let udid = self.simulator_udid.clone().ok_or_else(|| {
+            DriverError::CommandFailed(
+                "Pasting text is only supported on iOS simulators".to_string(),
+            )
+        })?;
+        let contents = text.to_string();
+        tokio::task::spawn_blocking(move || Simctl::set_pasteboard(&udid, &contents))
+            .await
+            .map_err(|e| DriverError::CommandFailed(e.to_string()))?
+            .map_err(|e| DriverError::CommandFailed(format!("Failed to set pasteboard: {}", e)))?;
+        self.driver.paste().await
+    }
+
+    /// Drags the element matching `from` onto the element matching `to`.
+    ///
+    /// Resolves both elements' frames and drags between their centers, holding
+    /// for `hold_duration` seconds before a move lasting `move_duration`.
+    ///
+    /// # Errors
+    ///
+    /// - [`DriverError::CommandFailed`] if a duration is negative or not
+    ///   finite, or either element is missing or has no frame
+    pub async fn drag_element_to_element(
+        &self,
+        from: &str,
+        to: &str,
+        by_label: bool,
+        hold_duration: f64,
+        move_duration: f64,
+    ) -> Result<(), DriverError> {
+        for (name, value) in [("hold", hold_duration), ("move", move_duration)] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(DriverError::CommandFailed(format!(
+                    "{} duration must be non-negative (got {})",
+                    name, value
+                )));
+            }
+        }
+
+        let (sx, sy) = self.element_frame(from, by_label).await?.center();
+        let (ex, ey) = self.element_frame(to, by_label).await?.center();
+        self.driver
+            .drag(
+                sx.round() as i32,
+                sy.round() as i32,
+                ex.round() as i32,
+                ey.round() as i32,
+                hold_duration,
+                move_duration,
+            )
+            .await
+    }
+
+    /// Returns the element under a screen point, for debugging coordinate
+    /// taps.
+    ///
+    /// Dumps the tree and returns the smallest element whose frame contains
+    /// the point; see [`element_at_point`](crate::driver::element_at_point).
+    pub async fn element_at_point(&self, x: f64, y: f64) -> Result<Option<UIElement>, DriverError> {
+        let tree = self.driver.dump_tree().await?;
+        Ok(crate::driver::element_at_point(&tree, x, y))
+    }
+
+    /// Taps the point `dx_fraction` across and `dy_fraction` down the frame
+    /// of the matching element, returning the point tapped.
+    ///
+    /// # Errors
+    ///
+    /// - [`DriverError::CommandFailed`] if a fraction is outside `0.0..=1.0`
+    ///   or the element has no frame
+    /// - [`DriverError::ElementNotFound`] if no element matches
+    pub async fn tap_element_offset(
+        &self,
+        selector: &str,
+        by_label: bool,
+        dx_fraction: f64,
+        dy_fraction: f64,
+    ) -> Result<(i32, i32), DriverError> {
+        for (name, value) in [("dx", dx_fraction), ("dy", dy_fraction)] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(DriverError::CommandFailed(format!(
+                    "{} fraction must be between 0 and 1 (got {})",
+                    name, value
+                )));
+            }
+        }
+        let frame = self.element_frame(selector, by_label).await?;
+        let (x, y) = frame.point_at(dx_fraction, dy_fraction);
+        let (x, y) = (x.round() as i32, y.round() as i32);
+        self.driver.tap_location(x, y).await?;
+        Ok((x, y))
+    }
+
+    /// Long presses the matching element for `duration` seconds.
+    ///
+    /// Uses the driver's native
+    /// [`long_press_element`](AutomationDriver::long_press_element) when
+    /// available. Otherwise presses the center of the element's frame, which
+    /// also works on backends that only take coordinates.
+    ///
+    /// # Errors
+    ///
+    /// - [`DriverError::CommandFailed`] if `duration` is not positive or the
+    ///   element has no frame
+    /// - [`DriverError::ElementNotFound`] if no element matches
+    pub async fn long_press_element(
+        &self,
+        selector: &str,
+        by_label: bool,
+        duration: f64,
+    ) -> Result<(), DriverError> {
+        if !(duration.is_finite() && duration > 0.0) {
+            return Err(DriverError::CommandFailed(format!(
+                "long press duration must be positive (got {})",
+                duration
+            )));
+        }
+        match self
+            .driver
+            .long_press_element(selector, by_label, duration)
+            .await
+        {
+            Err(DriverError::Unsupported(_)) => {
+                debug!(
+                    selector,
+                    "native element long press unsupported; pressing frame center"
+                );
+                let (x, y) = self.element_frame(selector, by_label).await?.center();
+                self.driver
+                    .long_press(x.round() as i32, y.round() as i32, duration)
+                    .await
+            }
+            other => other,
+        }
+    }
+
+    /// Swipes in `direction` inside the frame of the matching element.
+    ///
+    /// The swipe covers the middle of the frame, through its center; see
+    /// [`ElementFrame::swipe_points`].
+    ///
+    /// # Errors
+    ///
+    /// - [`DriverError::CommandFailed`] if the direction is invalid or the
+    ///   element has no frame
+    /// - [`DriverError::ElementNotFound`] if no element matches
+    pub async fn swipe_within_element(
+        &self,
+        selector: &str,
+        by_label: bool,
+        direction: &str,
+    ) -> Result<(), DriverError> {
+        if !matches!(direction, "up" | "down" | "left" | "right") {
+            return Err(DriverError::CommandFailed(format!(
+                "Invalid swipe direction '{}'. Use: up, down, left, right",
+                direction
+            )));
+        }
+        let frame = self.element_frame(selector, by_label).await?;
+        let Some((start_x, start_y, end_x, end_y)) = frame.swipe_points(direction) else {
+            unreachable!("direction validated above");
+        };
+        self.driver
+            .swipe(
+                start_x.round() as i32,
+                start_y.round() as i32,
+                end_x.round() as i32,
+                end_y.round() as i32,
+                Some(0.3),
+            )
+            .await
+    }
+
+    /// Finds the matching element and returns its frame, in screen points.
+    async fn element_frame(
+        &self,
+        selector: &str,
+        by_label: bool,
+    ) -> Result<ElementFrame, DriverError> {
+        let element = self
+            .driver
+            .find_element_with_type(selector, by_label, None)
+            .await?
+            .ok_or_else(|| DriverError::ElementNotFound {
+                selector: if by_label {
+                    format!("label '{}'", selector)
+                } else {
+                    format!("ID '{}'", selector)
+                },
+            })?;
+        element.frame.ok_or_else(|| {
+            DriverError::CommandFailed(format!("element has no frame: {}", selector))
+        })
+    }
+
+    /// Captures a screenshot cropped to the frame of the matching element.
+    ///
+    /// Frames are reported in points, so they are converted to pixels with the
+    /// driver's [`screen_info`](AutomationDriver::screen_info) scale. When the
+    /// driver cannot report it, the scale is inferred from the screenshot width
+    /// and the application window's frame. Elements partially off-screen are
+    /// cropped to their visible part.
+    ///
+    /// # Errors
+    ///
+    /// - [`DriverError::CommandFailed`] if the element is missing, has no
+    ///   frame, or is entirely off-screen
+    pub async fn screenshot_element(
+        &self,
+        selector: &str,
+        by_label: bool,
+    ) -> Result<Vec<u8>, DriverError> {
+        // Cropping decodes the image, so insist on lossless PNG
+        let png = self.driver.screenshot_with(ScreenshotFormat::Png).await?;
+        let frame = self.element_frame(selector, by_label).await?;
+
+        let scale = match self.driver.screen_scale().await {
+            Ok(scale) => scale,
+            Err(e) => {
+                // Agents that predate screen info: infer the scale instead
+                debug!(error = %e, "screen info unavailable, inferring scale");
+                let (width, _) = screenshot::png_dimensions(&png)
+                    .map_err(|e| DriverError::CommandFailed(e.to_string()))?;
+                screenshot::infer_scale(width, &self.driver.dump_tree().await?)
+            }
+        };
+        screenshot::crop_to_frame(&png, &frame, scale)
+            .map_err(|e| DriverError::CommandFailed(e.to_string()))
+    }
+
+    /// Polls `selectors` until any of them (or, with `all`, every one of
+    /// them) is present and hittable.
+    ///
+    /// Selectors are looked up in order each poll, so when several appear
+    /// together `WaitForAny` reports the earliest in the list. A lookup that
+    /// fails counts as absent, as in `WaitFor`.
+    async fn wait_for_selectors(
+        &self,
+        selectors: &[SelectorSpec],
+        all: bool,
+        timeout_ms: u64,
+        poll_interval_ms: Option<u64>,
+    ) -> ExecutionResult {
+        if selectors.is_empty() {
+            return ExecutionResult::failure("No selectors to wait for".to_string());
+        }
+        let mut start = Instant::now();
+        let timeout = Duration::from_millis(timeout_ms);
+        let poll_interval = self.effective_poll_interval(poll_interval_ms);
+        let mut last_recovery = self.driver.recovery_count();
+
+        loop {
+            let mut missing = Vec::new();
+            for (index, spec) in selectors.iter().enumerate() {
+                let found = self
+                    .driver
+                    .find_element_with_read_timeout(
+                        &spec.selector,
+                        spec.by_label,
+                        spec.element_type.as_deref(),
+                        Some(timeout_ms),
+                    )
+                    .await;
+                let present = matches!(found, Ok(Some(ref el)) if el.hittable != Some(false));
+                if present && !all {
+                    let elapsed_ms = start.elapsed().as_millis() as u64;
+                    let data = WaitForAnyData::new(elapsed_ms, index, spec.selector.clone());
+                    return ExecutionResult::success(format!("Found {}", spec))
+                        .with_data(data.to_data());
+                }
+                if !present {
+                    missing.push(spec.clone());
+                }
+            }
+            if all && missing.is_empty() {
+                let elapsed_ms = start.elapsed().as_millis() as u64;
+                return ExecutionResult::success(format!("All {} elements found", selectors.len()))
+                    .with_data(WaitData::new(elapsed_ms).to_data());
+            }
+
+            let current_recovery = self.driver.recovery_count();
+            if current_recovery != last_recovery {
+                info!("agent recovered during multi-selector wait, resetting timer");
+                start = Instant::now();
+                last_recovery = current_recovery;
+            }
+            if start.elapsed() >= timeout {
+                let elapsed_ms = start.elapsed().as_millis() as u64;
+                let msg = if all {
+                    format!(
+                        "Timeout after {}ms: still missing {}",
+                        elapsed_ms,
+                        join_selectors(&missing, ", ")
+                    )
+                } else {
+                    format!(
+                        "Timeout after {}ms waiting for any of {}",
+                        elapsed_ms,
+                        join_selectors(selectors, ", ")
+                    )
+                };
+                return ExecutionResult::failure(msg)
+                    .with_data(WaitData::new(elapsed_ms).to_data());
+            }
+            if let Err(e) = self.poll_sleep(poll_interval, start, timeout).await {
+                let elapsed_ms = start.elapsed().as_millis() as u64;
+                return ExecutionResult::from_error(e)
+                    .with_data(WaitData::new(elapsed_ms).to_data());
+            }
+        }
+    }
+
+    /// The poll interval for a wait action: its own `poll_interval_ms`, or the
+    /// executor's default. Never zero, so the loop can't spin.
+    fn effective_poll_interval(&self, poll_interval_ms: Option<u64>) -> Duration {
+        poll_interval_ms
+            .map_or(self.poll_interval, Duration::from_millis)
+            .max(Duration::from_millis(1))
+    }
+
+    /// Repeats `attempt` at the poll interval while it fails with an error
+    /// [`is_retryable_error`] accepts and `timeout` has not elapsed since
+    /// `start`.
+    ///
+    /// The agents already retry until the forwarded timeout, so this only
+    /// repeats attempts that fail early, e.g. on backends that ignore the
+    /// timeout.
+    async fn retry_transient<T, F, Fut>(
+        &self,
+        start: Instant,
+        timeout: Duration,
+        mut attempt: F,
+    ) -> Result<T, DriverError>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, DriverError>>,
+    {
+        loop {
+            match attempt().await {
+                Err(e) if is_retryable_error(&e) && start.elapsed() < timeout => {
+                    self.poll_sleep(self.poll_interval, start, timeout).await?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Sleeps for one poll interval, or returns [`DriverError::Cancelled`] as
+    /// soon as the executor's cancellation token fires.
+    ///
+    /// The sleep is cut short at the wait's deadline (`timeout` after
+    /// `start`), so the last poll happens on time rather than up to an
+    /// interval late.
+    async fn poll_sleep(
+        &self,
+        interval: Duration,
+        start: Instant,
+        timeout: Duration,
+    ) -> Result<(), DriverError> {
+        let interval = interval.min(timeout.saturating_sub(start.elapsed()));
+        match self.cancel {
+            Some(ref token) => tokio::select! {
+                _ = token.cancelled() => Err(DriverError::Cancelled),
+                _ = tokio::time::sleep(interval) => Ok(()),
+            },
+            None => {
+                tokio::time::sleep(interval).await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Fetches the flattened element list for screen info, applying `filter`.
+    ///
+    /// The backend is asked to filter first; if it reports the operation as
+    /// unsupported, a full [`dump_tree`](AutomationDriver::dump_tree) is
+    /// filtered locally instead.
+    async fn screen_elements(&self, filter: &ElementFilter) -> Result<Vec<UIElement>, DriverError> {
+        if filter.is_empty() {
+            return self.driver.list_elements().await;
+        }
+        let tree = match self.driver.dump_tree_filtered(filter).await {
+            Ok(tree) => tree,
+            Err(DriverError::Unsupported(reason)) => {
+                debug!(%reason, "backend cannot filter the tree, filtering locally");
+                filter_elements(&self.driver.dump_tree().await?, filter)
+            }
+            Err(e) => return Err(e),
+        };
+        Ok(flatten_elements(&tree))
     }
 }
 
@@ -668,6 +2096,21 @@ mod tests {
         let result = ExecutionResult::failure("error message");
         assert!(!result.success);
         assert_eq!(result.message, "error message");
+        assert!(!result.element_not_found);
+    }
+
+    #[test]
+    fn test_execution_result_from_element_not_found() {
+        let result = ExecutionResult::from_error(DriverError::ElementNotFound {
+            selector: "ID 'missing'".to_string(),
+        });
+        assert!(!result.success);
+        assert!(result.element_not_found);
+        assert_eq!(result.message, "Element with ID 'missing' not found");
+        assert!(is_missing_element(&result));
+
+        let result = ExecutionResult::from_error(DriverError::Timeout);
+        assert!(!result.element_not_found);
     }
 
     #[test]
@@ -684,6 +2127,28 @@ mod tests {
         assert_eq!(result.data, Some("{\"key\": \"value\"}".to_string()));
     }
 
+    #[test]
+    fn test_execution_result_read_value() {
+        let get_value = ActionType::GetValue {
+            selector: "email".to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
+        };
+        let read = ExecutionResult::success("ok").with_data("user@example.com".to_string());
+        assert_eq!(
+            read.read_value(&get_value).as_deref(),
+            Some("user@example.com")
+        );
+        // No value, a failed read, or another action: nothing to record
+        let empty = ExecutionResult::success("ok").with_data("null".to_string());
+        assert_eq!(empty.read_value(&get_value), None);
+        assert_eq!(ExecutionResult::failure("x").read_value(&get_value), None);
+        assert_eq!(read.read_value(&ActionType::GetScreenshot), None);
+    }
+
     #[test]
     fn test_executor_creation_with_agent() {
         let executor = ActionExecutor::with_agent("localhost", 9800);
@@ -696,8 +2161,13 @@ mod tests {
         let config = DriverConfig::Agent {
             host: "localhost".to_string(),
             port: 9800,
+            read_timeout: None,
+            retries: None,
+            poll_interval: None,
+            fuzzy_selectors: None,
+            lifecycle: None,
         };
-        let executor = ActionExecutor::from_config(config);
+        let executor = ActionExecutor::from_config(config).unwrap();
         assert!(!executor.driver().is_connected());
     }
 
@@ -707,30 +2177,73 @@ mod tests {
         let config = DriverConfig::Device {
             udid: "ABC-123".to_string(),
             device_port: 8080,
+            read_timeout: None,
+            retries: None,
+            poll_interval: None,
+            fuzzy_selectors: None,
+            lifecycle: None,
         };
-        let executor = ActionExecutor::from_config(config);
+        let executor = ActionExecutor::from_config(config).unwrap();
         assert!(!executor.driver().is_connected());
     }
 
     #[test]
     fn test_executor_from_config_android_routes_to_android_driver() {
-        use crate::driver::DriverConfig;
+        use crate::driver::{DriverBackend, DriverConfig};
         // An Android DriverConfig must select the AndroidDriver path through
-        // the executor.rs match (acceptance F1). The match arm is the only
-        // place `DriverConfig::Android` maps, so a successfully-constructed,
-        // unconnected executor proves the route. (A live connection requires a
+        // driver::create (acceptance F1). (A live connection requires a
         // device and is deferred to integration story #90.)
         let config = DriverConfig::Android {
             serial: "emulator-5554".to_string(),
             local_port: 9123,
             device_port: crate::android_driver::DEFAULT_ANDROID_AGENT_PORT,
+            read_timeout: Some(Duration::from_secs(60)),
+            retries: None,
+            poll_interval: None,
+            fuzzy_selectors: None,
         };
-        let executor = ActionExecutor::from_config(config);
+        let executor = ActionExecutor::from_config(config).unwrap();
         assert!(!executor.driver().is_connected());
-        // AndroidDriver overrides recovery_count() (starts at 0); the default
-        // trait impl also returns 0, so this asserts the call is wired, not the
-        // concrete type. Concrete-type selection is covered by android_driver's
-        // own construction tests; here the compile-time match arm guarantees it.
+        assert_eq!(executor.driver().backend(), Some(DriverBackend::Android));
         assert_eq!(executor.driver().recovery_count(), 0);
     }
+
+    #[test]
+    fn test_split_key_tokens() {
+        use KeySegment::{Key, Text};
+        assert_eq!(
+            split_key_tokens("user{tab}secret{Return}"),
+            vec![
+                Text("user".to_string()),
+                Key(SpecialKey::Tab),
+                Text("secret".to_string()),
+                Key(SpecialKey::Return),
+            ]
+        );
+        assert_eq!(
+            split_key_tokens("{up}{up}"),
+            vec![Key(SpecialKey::Up), Key(SpecialKey::Up)]
+        );
+        assert_eq!(split_key_tokens(""), vec![]);
+    }
+
+    #[test]
+    fn test_split_key_tokens_keeps_unknown_braces() {
+        use KeySegment::{Key, Text};
+        assert_eq!(
+            split_key_tokens(r#"{"a": 1}"#),
+            vec![Text(r#"{"a": 1}"#.to_string())]
+        );
+        assert_eq!(
+            split_key_tokens("{{tab}} { tab }"),
+            vec![
+                Text("{".to_string()),
+                Key(SpecialKey::Tab),
+                Text("} { tab }".to_string()),
+            ]
+        );
+        assert_eq!(split_key_tokens("{tab"), vec![Text("{tab".to_string())]);
+        assert!(!has_key_tokens("plain text"));
+        assert!(has_key_tokens("a{escape}"));
+    }
 }
@@ -0,0 +1,200 @@
+//! Fuzzy matching for selectors and completion candidates.
+//!
+//! [`FuzzyFilter`] ranks candidates that contain the query as a subsequence,
+//! which suits completion as the user types. [`suggest`] tolerates typos
+//! (swapped, missing or extra characters) and backs the executor's "did you
+//! mean" hints for selectors that match no element.
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// Lowest [`similarity`] at which [`suggest`] offers a candidate.
+pub const SUGGESTION_THRESHOLD: f64 = 0.75;
+
+/// Fuzzy filter for matching completion candidates.
+pub struct FuzzyFilter {
+    matcher: SkimMatcherV2,
+}
+
+impl Default for FuzzyFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FuzzyFilter {
+    /// Create a new fuzzy filter.
+    pub fn new() -> Self {
+        Self {
+            matcher: SkimMatcherV2::default(),
+        }
+    }
+
+    /// Score a target string against a query.
+    ///
+    /// Returns the score and matched character indices, or None if no match.
+    /// Scoring: base fuzzy score + prefix bonus (+1000) + exact match bonus (+2000)
+    pub fn score(&self, query: &str, target: &str) -> Option<(i64, Vec<usize>)> {
+        if query.is_empty() {
+            // Empty query matches everything with neutral score
+            return Some((0, Vec::new()));
+        }
+
+        let query_lower = query.to_lowercase();
+        let target_lower = target.to_lowercase();
+
+        // Get base fuzzy match
+        let (score, indices) = self.matcher.fuzzy_indices(&target_lower, &query_lower)?;
+
+        // Apply bonuses
+        let mut final_score = score;
+
+        // Exact match bonus
+        if target_lower == query_lower {
+            final_score += 2000;
+        }
+        // Prefix match bonus
+        else if target_lower.starts_with(&query_lower) {
+            final_score += 1000;
+        }
+
+        Some((final_score, indices))
+    }
+}
+
+/// Case-insensitive similarity of two strings, from `0.0` to `1.0`.
+///
+/// One minus the edit distance over the length of the longer string, where
+/// an edit inserts, deletes or substitutes a character or swaps two adjacent
+/// ones. Identical strings score `1.0`.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(&a, &b) as f64 / longest as f64
+}
+
+/// Returns the candidate most similar to `query`, if any reaches
+/// [`SUGGESTION_THRESHOLD`].
+///
+/// Ties go to the earliest candidate. Candidates equal to `query` are
+/// skipped, since a suggestion is only useful when the query did not match.
+pub fn suggest<'a>(query: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let mut best: Option<(&str, f64)> = None;
+    for candidate in candidates {
+        if candidate == query {
+            continue;
+        }
+        let score = similarity(query, candidate);
+        if score >= SUGGESTION_THRESHOLD && best.is_none_or(|(_, s)| score > s) {
+            best = Some((candidate, score));
+        }
+    }
+    best.map(|(candidate, _)| candidate)
+}
+
+/// Optimal string alignment distance: Levenshtein plus adjacent swaps.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    // Rows for i-2, i-1 and i
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_query_matches_all() {
+        let filter = FuzzyFilter::new();
+        assert!(filter.score("", "anything").is_some());
+    }
+
+    #[test]
+    fn test_exact_match_highest_score() {
+        let filter = FuzzyFilter::new();
+        let exact = filter.score("tap_element", "tap_element").unwrap().0;
+        let prefix = filter.score("tap", "tap_element").unwrap().0;
+        let fuzzy = filter.score("taele", "tap_element").unwrap().0;
+
+        assert!(exact > prefix);
+        assert!(prefix > fuzzy);
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let filter = FuzzyFilter::new();
+        assert!(filter.score("TAP", "tap_element").is_some());
+        assert!(filter.score("Tap", "TAP_ELEMENT").is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        let filter = FuzzyFilter::new();
+        // "taele" should match "tap_element"
+        let result = filter.score("taele", "tap_element");
+        assert!(result.is_some());
+    }
+
+    #[test]
+    fn test_no_match() {
+        let filter = FuzzyFilter::new();
+        assert!(filter.score("xyz", "tap_element").is_none());
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("login-btn", "login-btn"), 1.0);
+        assert_eq!(similarity("Login-Btn", "login-btn"), 1.0);
+        assert_eq!(similarity("", ""), 1.0);
+        // One swap in nine characters
+        assert!((similarity("lgoin-btn", "login-btn") - 8.0 / 9.0).abs() < 1e-9);
+        assert_eq!(similarity("abc", "xyz"), 0.0);
+    }
+
+    #[test]
+    fn test_edit_distance_counts_each_kind_of_edit() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(edit_distance(&chars("login"), &chars("lgoin")), 1);
+        assert_eq!(edit_distance(&chars("login"), &chars("logn")), 1);
+        assert_eq!(edit_distance(&chars("login"), &chars("loggin")), 1);
+        assert_eq!(edit_distance(&chars("login"), &chars("lagin")), 1);
+        assert_eq!(edit_distance(&chars("login"), &chars("")), 5);
+        assert_eq!(edit_distance(&chars("café"), &chars("cafe")), 1);
+    }
+
+    #[test]
+    fn test_suggest_picks_closest_candidate() {
+        let candidates = ["logout-btn", "login-btn", "signup-btn"];
+        assert_eq!(suggest("lgoin-btn", candidates), Some("login-btn"));
+        assert_eq!(suggest("LOGIN-BTM", candidates), Some("login-btn"));
+    }
+
+    #[test]
+    fn test_suggest_rejects_distant_candidates() {
+        assert_eq!(suggest("settings", ["login-btn", "signup-btn"]), None);
+        assert_eq!(suggest("lgoin-btn", []), None);
+        // An exact match is not a suggestion
+        assert_eq!(suggest("login-btn", ["login-btn"]), None);
+    }
+}
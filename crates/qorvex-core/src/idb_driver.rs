@@ -0,0 +1,530 @@
+//! `idb` (Facebook's iOS Development Bridge) backend.
+//!
+//! [`IdbDriver`] implements [`AutomationDriver`] by shelling out to the `idb`
+//! CLI instead of talking to the Swift agent, for environments where the agent
+//! cannot be built or installed. Gestures map onto `idb ui tap`, `idb ui
+//! swipe`, `idb ui text` and `idb ui key`; the element tree comes from `idb ui
+//! describe-all --json`, and element actions search that tree client-side.
+//!
+//! # Requirements
+//!
+//! `idb` and its companion must be installed (`pip install fb-idb` and
+//! `brew install facebook/fb/idb-companion`), with `idb` on the `PATH` or
+//! passed to [`IdbDriver::with_binary`]. A missing binary fails with a
+//! [`DriverError::CommandFailed`] saying so.
+//!
+//! # Limitations
+//!
+//! `idb` does not report hittability, so every element counts as hittable, and
+//! it has no equivalent of the agent's server-side waits or target switching.
+//! Each command spawns a process, which makes this backend noticeably slower
+//! than the agent.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use qorvex_core::driver::AutomationDriver;
+//! use qorvex_core::idb_driver::IdbDriver;
+//!
+//! # async fn example() -> Result<(), qorvex_core::driver::DriverError> {
+//! let mut driver = IdbDriver::new("12345678-1234-1234-1234-123456789ABC");
+//! driver.connect().await?;
+//! driver.tap_location(100, 200).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::driver::{describe_selector, AutomationDriver, DriverBackend, DriverError};
+use crate::element::{ElementFrame, UIElement};
+use crate::protocol::SpecialKey;
+
+/// Default name of the `idb` binary, looked up on the `PATH`.
+pub const DEFAULT_IDB_BINARY: &str = "idb";
+
+/// An [`AutomationDriver`] that drives a simulator or device through `idb`.
+pub struct IdbDriver {
+    /// UDID of the target, passed to every command as `--udid`.
+    udid: String,
+    /// The `idb` executable.
+    binary: PathBuf,
+    /// Set once `connect` has reached the target.
+    connected: AtomicBool,
+}
+
+impl IdbDriver {
+    /// Creates a driver for the target with `udid`, using `idb` from the
+    /// `PATH`. Not connected yet.
+    pub fn new(udid: impl Into<String>) -> Self {
+        Self {
+            udid: udid.into(),
+            binary: PathBuf::from(DEFAULT_IDB_BINARY),
+            connected: AtomicBool::new(false),
+        }
+    }
+
+    /// Uses the `idb` executable at `binary` instead of the one on the `PATH`.
+    pub fn with_binary(mut self, binary: impl Into<PathBuf>) -> Self {
+        self.binary = binary.into();
+        self
+    }
+
+    /// The UDID of the target.
+    pub fn udid(&self) -> &str {
+        &self.udid
+    }
+
+    /// Runs `idb <args> --udid <udid>` off the async runtime and returns its
+    /// stdout.
+    async fn run(&self, args: Vec<String>) -> Result<Vec<u8>, DriverError> {
+        let binary = self.binary.clone();
+        let udid = self.udid.clone();
+        tokio::task::spawn_blocking(move || run_idb(&binary, &args, &udid))
+            .await
+            .map_err(|e| DriverError::CommandFailed(format!("idb task failed: {e}")))?
+    }
+
+    /// Looks an element up and returns the center of its frame.
+    async fn element_center(
+        &self,
+        selector: &str,
+        by_label: bool,
+        element_type: Option<&str>,
+    ) -> Result<(i32, i32), DriverError> {
+        let element = self
+            .find_element_with_type(selector, by_label, element_type)
+            .await?
+            .ok_or_else(|| DriverError::ElementNotFound {
+                selector: describe_selector(selector, by_label, element_type),
+            })?;
+        let frame = element.frame.ok_or_else(|| {
+            DriverError::CommandFailed(format!("Element has no frame: {}", selector))
+        })?;
+        let (x, y) = frame.center();
+        Ok((x.round() as i32, y.round() as i32))
+    }
+}
+
+/// Runs one `idb` command, mapping a missing binary and a non-zero exit to
+/// readable errors.
+fn run_idb(binary: &Path, args: &[String], udid: &str) -> Result<Vec<u8>, DriverError> {
+    debug!(?args, udid, "running idb");
+    let output = Command::new(binary)
+        .args(args)
+        .args(["--udid", udid])
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => DriverError::CommandFailed(format!(
+                "idb not found ({}); install it with `pip install fb-idb` and \
+                 `brew install facebook/fb/idb-companion`",
+                binary.display()
+            )),
+            _ => DriverError::Io(e),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(DriverError::CommandFailed(format!(
+            "idb {} failed: {}",
+            args.first().map(String::as_str).unwrap_or_default(),
+            stderr.trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// The HID usage code `idb ui key` expects for `key`.
+fn hid_key_code(key: SpecialKey) -> u8 {
+    match key {
+        SpecialKey::Return => 40,
+        SpecialKey::Escape => 41,
+        SpecialKey::Delete => 42,
+        SpecialKey::Tab => 43,
+        SpecialKey::Right => 79,
+        SpecialKey::Left => 80,
+        SpecialKey::Down => 81,
+        SpecialKey::Up => 82,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// describe-all parsing
+// ---------------------------------------------------------------------------
+
+/// One element of `idb ui describe-all --json` output.
+#[derive(Debug, Deserialize)]
+struct IdbElement {
+    #[serde(rename = "AXUniqueId", default)]
+    identifier: Option<String>,
+    #[serde(rename = "AXLabel", default)]
+    label: Option<String>,
+    /// Usually a string, but numeric for sliders and progress indicators.
+    #[serde(rename = "AXValue", default)]
+    value: Option<serde_json::Value>,
+    #[serde(rename = "type", default)]
+    element_type: Option<String>,
+    #[serde(default)]
+    frame: Option<ElementFrame>,
+    /// The frame as `{{x, y}, {width, height}}`, for output without `frame`.
+    #[serde(rename = "AXFrame", default)]
+    ax_frame: Option<String>,
+    #[serde(default)]
+    role: Option<String>,
+    #[serde(default)]
+    enabled: Option<bool>,
+    /// Present with `describe-all --nested`.
+    #[serde(default)]
+    children: Vec<IdbElement>,
+}
+
+impl From<IdbElement> for UIElement {
+    fn from(e: IdbElement) -> Self {
+        let frame = e
+            .frame
+            .or_else(|| e.ax_frame.as_deref().and_then(parse_ax_frame));
+        UIElement {
+            identifier: non_empty(e.identifier),
+            label: non_empty(e.label),
+            value: match e.value {
+                Some(serde_json::Value::String(s)) => non_empty(Some(s)),
+                Some(serde_json::Value::Null) | None => None,
+                Some(other) => Some(other.to_string()),
+            },
+            element_type: non_empty(e.element_type),
+            frame,
+            children: e.children.into_iter().map(UIElement::from).collect(),
+            role: non_empty(e.role),
+            hittable: None,
+            enabled: e.enabled,
+            selected: None,
+            focused: None,
+            placeholder: None,
+        }
+    }
+}
+
+/// `idb` reports missing identifiers and labels as empty strings.
+fn non_empty(s: Option<String>) -> Option<String> {
+    s.filter(|s| !s.is_empty())
+}
+
+/// Parses an `AXFrame` string such as `{{20, 100.5}, {350, 44}}`.
+fn parse_ax_frame(s: &str) -> Option<ElementFrame> {
+    let numbers: Vec<f64> = s
+        .split(['{', '}', ','])
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    match numbers[..] {
+        [x, y, width, height] => Some(ElementFrame {
+            x,
+            y,
+            width,
+            height,
+        }),
+        _ => None,
+    }
+}
+
+/// Parses `idb ui describe-all --json` output into elements.
+///
+/// Accepts a JSON array, as current `idb` prints, or one JSON object per
+/// line, as older releases did.
+pub fn parse_describe_all(output: &str) -> Result<Vec<UIElement>, DriverError> {
+    let trimmed = output.trim();
+    let elements: Vec<IdbElement> = if trimmed.starts_with('[') {
+        serde_json::from_str(trimmed).map_err(|e| DriverError::JsonParse(e.to_string()))?
+    } else {
+        trimmed
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()
+            .map_err(|e| DriverError::JsonParse(e.to_string()))?
+    };
+    Ok(elements.into_iter().map(UIElement::from).collect())
+}
+
+// ---------------------------------------------------------------------------
+// AutomationDriver impl
+// ---------------------------------------------------------------------------
+
+#[async_trait]
+impl AutomationDriver for IdbDriver {
+    fn backend(&self) -> Option<DriverBackend> {
+        Some(DriverBackend::Idb)
+    }
+
+    /// Checks that `idb` is installed and can reach the target.
+    async fn connect(&mut self) -> Result<(), DriverError> {
+        self.run(vec!["describe".to_string()]).await?;
+        self.connected.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    async fn ping(&self) -> Result<Duration, DriverError> {
+        let start = Instant::now();
+        self.run(vec!["describe".to_string()]).await?;
+        Ok(start.elapsed())
+    }
+
+    async fn tap_location(&self, x: i32, y: i32) -> Result<(), DriverError> {
+        self.run(vec![
+            "ui".to_string(),
+            "tap".to_string(),
+            x.to_string(),
+            y.to_string(),
+        ])
+        .await
+        .map(drop)
+    }
+
+    async fn tap_element(&self, identifier: &str) -> Result<(), DriverError> {
+        let (x, y) = self.element_center(identifier, false, None).await?;
+        self.tap_location(x, y).await
+    }
+
+    async fn tap_by_label(&self, label: &str) -> Result<(), DriverError> {
+        let (x, y) = self.element_center(label, true, None).await?;
+        self.tap_location(x, y).await
+    }
+
+    async fn tap_with_type(
+        &self,
+        selector: &str,
+        by_label: bool,
+        element_type: &str,
+    ) -> Result<(), DriverError> {
+        let (x, y) = self
+            .element_center(selector, by_label, Some(element_type))
+            .await?;
+        self.tap_location(x, y).await
+    }
+
+    async fn swipe(
+        &self,
+        start_x: i32,
+        start_y: i32,
+        end_x: i32,
+        end_y: i32,
+        duration: Option<f64>,
+    ) -> Result<(), DriverError> {
+        let mut args = vec![
+            "ui".to_string(),
+            "swipe".to_string(),
+            start_x.to_string(),
+            start_y.to_string(),
+            end_x.to_string(),
+            end_y.to_string(),
+        ];
+        if let Some(duration) = duration {
+            args.extend(["--duration".to_string(), duration.to_string()]);
+        }
+        self.run(args).await.map(drop)
+    }
+
+    async fn long_press(&self, x: i32, y: i32, duration: f64) -> Result<(), DriverError> {
+        self.run(vec![
+            "ui".to_string(),
+            "tap".to_string(),
+            x.to_string(),
+            y.to_string(),
+            "--duration".to_string(),
+            duration.to_string(),
+        ])
+        .await
+        .map(drop)
+    }
+
+    async fn type_text(&self, text: &str) -> Result<(), DriverError> {
+        self.run(vec!["ui".to_string(), "text".to_string(), text.to_string()])
+            .await
+            .map(drop)
+    }
+
+    async fn send_key(&self, key: SpecialKey) -> Result<(), DriverError> {
+        self.run(vec![
+            "ui".to_string(),
+            "key".to_string(),
+            hid_key_code(key).to_string(),
+        ])
+        .await
+        .map(drop)
+    }
+
+    async fn dump_tree(&self) -> Result<Vec<UIElement>, DriverError> {
+        let stdout = self
+            .run(vec![
+                "ui".to_string(),
+                "describe-all".to_string(),
+                "--json".to_string(),
+            ])
+            .await?;
+        parse_describe_all(&String::from_utf8_lossy(&stdout))
+    }
+
+    async fn get_element_value(&self, identifier: &str) -> Result<Option<String>, DriverError> {
+        Ok(self
+            .find_element(identifier)
+            .await?
+            .and_then(|element| element.value))
+    }
+
+    async fn get_element_value_by_label(&self, label: &str) -> Result<Option<String>, DriverError> {
+        Ok(self
+            .find_element_by_label(label)
+            .await?
+            .and_then(|element| element.value))
+    }
+
+    async fn get_value_with_type(
+        &self,
+        selector: &str,
+        by_label: bool,
+        element_type: &str,
+    ) -> Result<Option<String>, DriverError> {
+        Ok(self
+            .find_element_with_type(selector, by_label, Some(element_type))
+            .await?
+            .and_then(|element| element.value))
+    }
+
+    async fn screenshot(&self) -> Result<Vec<u8>, DriverError> {
+        let path = std::env::temp_dir().join(format!(
+            "qorvex-idb-{}-{}.png",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        self.run(vec![
+            "screenshot".to_string(),
+            path.to_string_lossy().into_owned(),
+        ])
+        .await?;
+        let png = std::fs::read(&path);
+        let _ = std::fs::remove_file(&path);
+        Ok(png?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIBE_ALL: &str = r#"[
+        {"AXFrame": "{{0, 0}, {390, 844}}", "AXUniqueId": null, "frame": {"y": 0, "x": 0, "width": 390, "height": 844},
+         "role_description": "application", "AXLabel": "Settings", "content_required": false, "type": "Application",
+         "title": null, "help": null, "custom_actions": [], "AXValue": null, "enabled": true, "role": "AXApplication", "subrole": null},
+        {"AXFrame": "{{20, 100.5}, {350, 44}}", "AXUniqueId": "login-button", "frame": {"y": 100.5, "x": 20, "width": 350, "height": 44},
+         "role_description": "button", "AXLabel": "Log In", "content_required": false, "type": "Button",
+         "title": null, "help": null, "custom_actions": [], "AXValue": "", "enabled": false, "role": "AXButton", "subrole": null},
+        {"AXFrame": "{{20, 200}, {350, 30}}", "AXUniqueId": "", "role_description": "slider", "AXLabel": "Volume",
+         "type": "Slider", "AXValue": 0.5, "enabled": true, "role": "AXSlider"}
+    ]"#;
+
+    #[test]
+    fn parses_describe_all_elements() {
+        let elements = parse_describe_all(DESCRIBE_ALL).unwrap();
+        assert_eq!(elements.len(), 3);
+
+        let app = &elements[0];
+        assert_eq!(app.identifier, None);
+        assert_eq!(app.label.as_deref(), Some("Settings"));
+        assert_eq!(app.element_type.as_deref(), Some("Application"));
+        assert_eq!(app.frame.as_ref().unwrap().width, 390.0);
+
+        let button = &elements[1];
+        assert_eq!(button.identifier.as_deref(), Some("login-button"));
+        assert_eq!(button.label.as_deref(), Some("Log In"));
+        assert_eq!(button.value, None, "empty values read as none");
+        assert_eq!(button.role.as_deref(), Some("AXButton"));
+        assert_eq!(button.enabled, Some(false));
+        assert_eq!(button.hittable, None);
+        assert_eq!(button.frame.as_ref().unwrap().center(), (195.0, 122.5));
+    }
+
+    #[test]
+    fn describe_all_falls_back_to_ax_frame_and_stringifies_values() {
+        let slider = &parse_describe_all(DESCRIBE_ALL).unwrap()[2];
+        assert_eq!(slider.identifier, None, "empty identifiers read as none");
+        assert_eq!(slider.value.as_deref(), Some("0.5"));
+        let frame = slider.frame.as_ref().unwrap();
+        assert_eq!(
+            (frame.x, frame.y, frame.width, frame.height),
+            (20.0, 200.0, 350.0, 30.0)
+        );
+    }
+
+    #[test]
+    fn parses_nested_and_line_delimited_output() {
+        let nested = r#"[{"type": "Window", "AXLabel": "", "children": [
+            {"type": "Button", "AXUniqueId": "ok", "frame": {"x": 0, "y": 0, "width": 10, "height": 10}}
+        ]}]"#;
+        let elements = parse_describe_all(nested).unwrap();
+        assert_eq!(elements[0].label, None);
+        assert_eq!(elements[0].children[0].identifier.as_deref(), Some("ok"));
+
+        let lines = "{\"type\": \"Button\", \"AXLabel\": \"A\"}\n\n{\"type\": \"Cell\", \"AXLabel\": \"B\"}\n";
+        let labels: Vec<_> = parse_describe_all(lines)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.label.unwrap())
+            .collect();
+        assert_eq!(labels, ["A", "B"]);
+    }
+
+    #[test]
+    fn rejects_malformed_describe_all() {
+        assert!(matches!(
+            parse_describe_all("[{\"type\": "),
+            Err(DriverError::JsonParse(_))
+        ));
+        assert!(matches!(
+            parse_describe_all("idb: command not found"),
+            Err(DriverError::JsonParse(_))
+        ));
+    }
+
+    #[test]
+    fn parses_ax_frame_strings() {
+        let frame = parse_ax_frame("{{-10, 5.5}, {100, 20}}").unwrap();
+        assert_eq!(
+            (frame.x, frame.y, frame.width, frame.height),
+            (-10.0, 5.5, 100.0, 20.0)
+        );
+        assert!(parse_ax_frame("{{1, 2}, {3}}").is_none());
+        assert!(parse_ax_frame("garbage").is_none());
+    }
+
+    #[tokio::test]
+    async fn missing_binary_fails_clearly() {
+        let mut driver = IdbDriver::new("ABC").with_binary("/nonexistent/qorvex-test-idb");
+        match driver.connect().await {
+            Err(DriverError::CommandFailed(msg)) => {
+                assert!(msg.contains("idb not found"), "{msg}");
+                assert!(msg.contains("pip install fb-idb"), "{msg}");
+            }
+            other => panic!("expected CommandFailed, got {other:?}"),
+        }
+        assert!(!driver.is_connected());
+    }
+
+    #[test]
+    fn special_keys_map_to_hid_codes() {
+        assert_eq!(hid_key_code(SpecialKey::Return), 40);
+        assert_eq!(hid_key_code(SpecialKey::Up), 82);
+    }
+}
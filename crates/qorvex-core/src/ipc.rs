@@ -16,6 +16,18 @@
 //! Sockets are created in `~/.qorvex/` with the naming pattern
 //! `qorvex_{session_name}.sock`. Use [`socket_path`] to get the path for a session.
 //!
+//! A server started in multi-session mode instead listens on the single
+//! [`multi_socket_path`] and routes each request by the `session` field of its
+//! [`IpcEnvelope`]. [`IpcClient::connect`] falls back to that socket when no
+//! per-session socket exists.
+//!
+//! # Authentication
+//!
+//! When [`IPC_TOKEN_ENV`] is set for the server, every connection must first
+//! send [`IpcRequest::Auth`] with the same token; other requests are rejected
+//! until it does. [`IpcClient::connect`] reads the same variable and
+//! authenticates automatically.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -36,15 +48,18 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
 
 use tracing::{debug, info_span, Instrument};
 
 use crate::action::{ActionResult, ActionType};
 use crate::executor::ActionExecutor;
-use crate::session::{Session, SessionEvent};
+use crate::session::{Session, SessionEvent, SessionSnapshot};
 
 /// Errors that can occur during IPC operations.
 #[derive(Error, Debug)]
@@ -60,6 +75,39 @@ pub enum IpcError {
     /// The requested session was not found.
     #[error("Session not found")]
     SessionNotFound,
+
+    /// The server rejected the connection's authentication token.
+    #[error("Authentication failed: {0}")]
+    Unauthorized(String),
+}
+
+/// Environment variable holding the shared IPC secret.
+///
+/// When set and non-empty, servers require it from every client and clients
+/// send it on connect.
+pub const IPC_TOKEN_ENV: &str = "QORVEX_IPC_TOKEN";
+
+/// Reads the IPC token from [`IPC_TOKEN_ENV`], treating an empty value as unset.
+pub fn ipc_token() -> Option<String> {
+    std::env::var(IPC_TOKEN_ENV).ok().filter(|t| !t.is_empty())
+}
+
+/// Environment variable overriding the subscribe keepalive interval, in seconds.
+pub const PING_INTERVAL_ENV: &str = "QORVEX_IPC_PING_SECS";
+
+/// Default interval between [`IpcResponse::Ping`] frames on an idle subscription.
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Reads the subscribe keepalive interval from [`PING_INTERVAL_ENV`].
+///
+/// Falls back to [`DEFAULT_PING_INTERVAL`] when unset, zero, or unparseable.
+pub fn ping_interval() -> Duration {
+    std::env::var(PING_INTERVAL_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|&secs| secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_PING_INTERVAL)
 }
 
 /// A physical device connected via USB or network, for use as an IPC data-transfer object.
@@ -132,6 +180,12 @@ impl std::str::FromStr for Platform {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum IpcRequest {
+    /// Authenticate the connection with the shared [`IPC_TOKEN_ENV`] secret.
+    ///
+    /// Must be the first request when the server has a token configured.
+    /// Accepted (and ignored) by servers without one.
+    Auth { token: String },
+
     /// Execute an action on the simulator.
     Execute {
         /// The action to perform.
@@ -139,6 +193,28 @@ pub enum IpcRequest {
         /// Optional free-text tag for log filtering/analysis.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         tag: Option<String>,
+        /// Whether to attach a screenshot to this action's log entry,
+        /// overriding the session's `SetCaptureScreenshots` setting.
+        /// `None` follows the session.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        capture_screenshot: Option<bool>,
+    },
+
+    /// Execute several actions in order, stopping at the first failure.
+    ///
+    /// Runs of coordinate gestures and typing reach the agent as one batch
+    /// frame (see [`ActionExecutor::execute_batch`](crate::executor::ActionExecutor::execute_batch));
+    /// selector taps, waits and other element lookups still cost a round trip
+    /// each. Each action that ran is logged like an [`IpcRequest::Execute`].
+    /// With screenshot capture on, only the last one gets a fresh screenshot,
+    /// since the screen between batched actions is never seen. Answered with
+    /// [`IpcResponse::BatchResult`].
+    ExecuteBatch {
+        /// The actions to perform.
+        actions: Vec<ActionType>,
+        /// Optional free-text tag applied to every logged action.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tag: Option<String>,
     },
 
     /// Subscribe to session events.
@@ -147,12 +223,25 @@ pub enum IpcRequest {
     /// messages whenever the session state changes.
     Subscribe,
 
-    /// Request the current session state.
+    /// Request the session ID and current screenshot.
+    ///
+    /// [`IpcRequest::GetSnapshot`] returns these along with the rest of the
+    /// session's state.
     GetState,
 
+    /// Request a consistent snapshot of the session's state.
+    GetSnapshot,
+
     /// Request the action log history.
     GetLog,
 
+    /// Cancel the action currently executing for any client.
+    ///
+    /// Wait actions stop at their next poll and fail with "Action cancelled".
+    /// Answered with a successful [`IpcResponse::CommandResult`] whether or not
+    /// an action was running.
+    Cancel,
+
     // --- Session Management ---
     /// Start a new automation session.
     StartSession,
@@ -177,10 +266,14 @@ pub enum IpcRequest {
     /// for Android).
     ///
     /// `platform` defaults to [`Platform::Ios`] when omitted (additive).
+    /// With `wait`, an iOS boot does not return until the simulator has
+    /// finished booting; Android emulator boots always wait.
     BootDevice {
         udid: String,
         #[serde(default)]
         platform: Platform,
+        #[serde(default)]
+        wait: bool,
     },
 
     // --- Agent Management ---
@@ -202,6 +295,9 @@ pub enum IpcRequest {
     },
     /// Stop the managed agent process.
     StopAgent,
+    /// Restart the managed agent process and reconnect to it, keeping the
+    /// current target app.
+    RestartAgent,
     /// Connect to agent at a specific host/port.
     Connect { host: String, port: u16 },
 
@@ -214,6 +310,26 @@ pub enum IpcRequest {
     /// Get metadata about the currently targeted application.
     GetTargetInfo,
 
+    /// Get the device screen size, scale factor and orientation.
+    GetDeviceInfo,
+
+    /// Get the smallest element whose frame contains a screen point.
+    ElementAt { x: i32, y: i32 },
+
+    /// Time `count` heartbeat round trips to the automation agent.
+    PingAgent { count: u32 },
+
+    // --- Screen Recording ---
+    /// Start recording the simulator screen to a video file.
+    ///
+    /// `path` should be absolute; the server resolves relative paths against
+    /// its own working directory. The server owns the recording process, so
+    /// it keeps running across client connections until
+    /// [`IpcRequest::StopRecording`] or server shutdown.
+    StartRecording { path: String },
+    /// Stop the active recording and finalize its file.
+    StopRecording,
+
     // --- Configuration ---
     /// Set the target app bundle ID.
     SetTarget { bundle_id: String },
@@ -221,10 +337,27 @@ pub enum IpcRequest {
     SetTimeout { timeout_ms: u64 },
     /// Get the current default wait timeout.
     GetTimeout,
+    /// Turn on or off capturing a screenshot after each executed action for
+    /// the session log.
+    SetCaptureScreenshots { enabled: bool },
 
     // --- On-Demand Fetching ---
     /// Fetch live UI elements from the automation agent.
     FetchElements,
+    /// Read the screen like an `Execute` of [`ActionType::GetScreenInfo`],
+    /// without logging it, for clients that poll the screen.
+    ///
+    /// Answered with [`IpcResponse::ActionResult`], or
+    /// [`IpcResponse::NoChange`] when the tree still hashes to
+    /// `changed_since_hash`.
+    FetchScreenInfo {
+        /// Type/visibility/depth filter; the default keeps everything.
+        #[serde(flatten)]
+        filter: crate::driver::ElementFilter,
+        /// Hash of the tree the client already has, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        changed_since_hash: Option<String>,
+    },
     /// Fetch installed apps/packages on the active device for `set-target`
     /// bundle-id completion. The server picks the source by active platform
     /// (`simctl` for iOS, `adb` for Android).
@@ -235,14 +368,56 @@ pub enum IpcRequest {
     GetSessionInfo,
     /// Get cached elements and devices for client-side tab completion.
     GetCompletionData,
+    /// Check whether the server has a session and a connected automation
+    /// backend, answered with [`IpcResponse::Health`].
+    Health,
 
     // --- Server Lifecycle ---
     /// Request the server to shut down cleanly.
     ///
-    /// The server will stop the agent, remove the socket, and exit.
+    /// The server will stop the agent, remove the socket, and exit. A
+    /// multi-session server given a session name ends only that session.
     Shutdown,
 }
 
+/// The wire form of an [`IpcRequest`], naming the session it is for.
+///
+/// Serializes as the request itself plus an optional `session` key, so
+/// `{"type":"GetState","session":"sim-a"}`. A multi-session server routes on
+/// `session`, using its default session when it is absent; single-session
+/// servers ignore it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcEnvelope {
+    /// The target session name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    /// The request itself.
+    #[serde(flatten)]
+    pub request: IpcRequest,
+}
+
+/// The outcome of one action in an [`IpcResponse::BatchResult`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchActionResult {
+    /// Whether the action succeeded.
+    pub success: bool,
+    /// Human-readable description of the result.
+    pub message: String,
+    /// Additional data returned by the action (JSON).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    /// Whether the action failed because no element matched its selector.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub element_not_found: bool,
+}
+
+impl BatchActionResult {
+    /// Whether every result in `results` succeeded. False for no results.
+    pub fn all_succeeded(results: &[Self]) -> bool {
+        !results.is_empty() && results.iter().all(|r| r.success)
+    }
+}
+
 /// A response sent from server to client over the IPC connection.
 ///
 /// Responses are serialized as JSON with a `type` tag discriminator.
@@ -259,6 +434,26 @@ pub enum IpcResponse {
         screenshot: Option<Arc<String>>,
         /// Additional data returned by the action (JSON).
         data: Option<String>,
+        /// Whether the action failed because no element matched its selector.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        element_not_found: bool,
+    },
+
+    /// Results of an [`IpcRequest::ExecuteBatch`], one per action that ran.
+    ///
+    /// The batch stops at the first failure, so when any result failed it is
+    /// the last one and the remaining actions never ran.
+    BatchResult {
+        /// The results, in action order.
+        results: Vec<BatchActionResult>,
+    },
+
+    /// The screen is unchanged since the hash supplied in
+    /// `GetScreenInfo { changed_since_hash }` or
+    /// [`IpcRequest::FetchScreenInfo`].
+    NoChange {
+        /// Hash of the current element tree (equal to the requested hash).
+        hash: String,
     },
 
     /// Current session state.
@@ -269,6 +464,12 @@ pub enum IpcResponse {
         screenshot: Option<Arc<String>>,
     },
 
+    /// A consistent snapshot of the session's state.
+    Snapshot {
+        /// The session's state, read at one moment.
+        snapshot: SessionSnapshot,
+    },
+
     /// Action log history.
     Log {
         /// All logged actions in chronological order.
@@ -281,6 +482,17 @@ pub enum IpcResponse {
         event: SessionEvent,
     },
 
+    /// Keepalive sent to subscribers while the session is idle.
+    ///
+    /// The first one follows `Subscribe` immediately. The periodic write makes
+    /// a dead connection fail promptly on both ends, and `interval_ms` tells
+    /// the client how long the server may stay silent.
+    Ping {
+        /// The server's ping interval in milliseconds.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        interval_ms: Option<u64>,
+    },
+
     /// An error occurred processing the request.
     Error {
         /// Human-readable error message.
@@ -323,6 +535,13 @@ pub enum IpcResponse {
         device_udid: Option<String>,
         /// Number of actions logged this session.
         action_count: usize,
+        /// Action log entries the session keeps in memory before evicting
+        /// the oldest.
+        #[serde(default)]
+        max_log_entries: usize,
+        /// Most recent log entries that keep their screenshot.
+        #[serde(default)]
+        max_screenshots: usize,
     },
 
     /// Cached completion data for client-side tab completion.
@@ -342,6 +561,18 @@ pub enum IpcResponse {
         apps: Vec<crate::simctl::InstalledApp>,
     },
 
+    /// Whether the server is ready to run actions.
+    Health {
+        /// Whether a session is currently active.
+        session_active: bool,
+        /// Whether an automation backend is wired up and connected.
+        driver_connected: bool,
+        /// Bundle ID (or Android package) the agent targets, if set.
+        agent_target: Option<String>,
+        /// Selected simulator UDID, if any.
+        simulator_udid: Option<String>,
+    },
+
     /// Current timeout value.
     TimeoutValue {
         /// Default timeout in milliseconds.
@@ -352,6 +583,179 @@ pub enum IpcResponse {
     ShutdownAck,
 }
 
+/// Tracks the actions currently executing on behalf of IPC clients so that an
+/// [`IpcRequest::Cancel`] from another connection can abort them.
+///
+/// Cheap to clone; clones share the same state.
+#[derive(Clone, Default)]
+pub struct InFlightActions {
+    inner: Arc<std::sync::Mutex<InFlightState>>,
+}
+
+#[derive(Default)]
+struct InFlightState {
+    token: CancellationToken,
+    running: usize,
+}
+
+/// Registration of a running action, returned by [`InFlightActions::begin`].
+///
+/// Dropping the guard marks the action as finished.
+pub struct InFlightGuard {
+    actions: InFlightActions,
+    token: CancellationToken,
+}
+
+impl InFlightActions {
+    /// Registers a new running action and returns its guard.
+    pub fn begin(&self) -> InFlightGuard {
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        state.running += 1;
+        InFlightGuard {
+            actions: self.clone(),
+            token: state.token.child_token(),
+        }
+    }
+
+    /// Cancels every running action. Returns `false` if none was running.
+    pub fn cancel(&self) -> bool {
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if state.running == 0 {
+            return false;
+        }
+        // Replace the parent so actions started after this are unaffected.
+        std::mem::take(&mut state.token).cancel();
+        true
+    }
+
+    /// Handles an [`IpcRequest::Cancel`].
+    pub fn cancel_response(&self) -> IpcResponse {
+        let message = if self.cancel() {
+            "Cancelled running action"
+        } else {
+            "No action running"
+        };
+        IpcResponse::CommandResult {
+            success: true,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl InFlightGuard {
+    /// The token to pass to [`ActionExecutor::with_cancellation`].
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut state = self.actions.inner.lock().unwrap_or_else(|e| e.into_inner());
+        state.running -= 1;
+    }
+}
+
+/// Streams session events to a subscribed client until it disconnects.
+///
+/// Writes an [`IpcResponse::Ping`] announcing `ping_interval` right away, then
+/// an [`IpcResponse::Event`] for each event and another ping after every
+/// `ping_interval` without one. Returns `Ok` once a write fails or the
+/// session's event channel closes.
+///
+/// # Errors
+///
+/// - [`IpcError::Json`] if an event cannot be serialized
+pub async fn forward_events<W: AsyncWrite + Unpin>(
+    mut rx: broadcast::Receiver<SessionEvent>,
+    writer: &mut W,
+    ping_interval: Duration,
+) -> Result<(), IpcError> {
+    let mut ping = tokio::time::interval(ping_interval);
+    let interval_ms = Some(ping_interval.as_millis().try_into().unwrap_or(u64::MAX));
+    loop {
+        let response = tokio::select! {
+            event = rx.recv() => match event {
+                Ok(event) => {
+                    ping.reset();
+                    IpcResponse::Event { event }
+                }
+                Err(_) => return Ok(()),
+            },
+            _ = ping.tick() => IpcResponse::Ping { interval_ms },
+        };
+        let json = serde_json::to_string(&response)? + "\n";
+        if writer.write_all(json.as_bytes()).await.is_err() {
+            return Ok(());
+        }
+        if writer.flush().await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+/// Per-connection gate enforcing [`IPC_TOKEN_ENV`] authentication.
+///
+/// Create one per client connection and pass every request through
+/// [`check`](Self::check) before handling it.
+pub struct AuthGate {
+    expected: Option<String>,
+    authenticated: bool,
+}
+
+impl AuthGate {
+    /// Creates a gate requiring `expected`, or letting everything through if `None`.
+    pub fn new(expected: Option<String>) -> Self {
+        let authenticated = expected.is_none();
+        Self {
+            expected,
+            authenticated,
+        }
+    }
+
+    /// Checks a request against the gate.
+    ///
+    /// Returns `None` if the request should be handled normally, or the
+    /// response to send instead: the outcome of an [`IpcRequest::Auth`], or an
+    /// error for any other request made before authenticating.
+    pub fn check(&mut self, request: &IpcRequest) -> Option<IpcResponse> {
+        if let IpcRequest::Auth { token } = request {
+            let accepted = match self.expected {
+                Some(ref expected) => tokens_match(expected, token),
+                None => true,
+            };
+            self.authenticated |= accepted;
+            return Some(if accepted {
+                IpcResponse::CommandResult {
+                    success: true,
+                    message: "Authenticated".to_string(),
+                }
+            } else {
+                IpcResponse::Error {
+                    message: "Invalid IPC token".to_string(),
+                }
+            });
+        }
+        if self.authenticated {
+            None
+        } else {
+            Some(IpcResponse::Error {
+                message: format!("Authentication required: set {IPC_TOKEN_ENV}"),
+            })
+        }
+    }
+}
+
+/// Compares tokens without short-circuiting on the first differing byte.
+fn tokens_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
 /// Trait for handling IPC requests.
 ///
 /// Implement this trait to provide custom request handling logic for the IPC server.
@@ -402,6 +806,24 @@ pub fn socket_path(session_name: &str) -> PathBuf {
     qorvex_dir().join(format!("qorvex_{}.sock", session_name))
 }
 
+/// Returns whether `name` can be used as a session name.
+///
+/// Session names end up in socket and log file names, so they are limited to
+/// 1-64 ASCII letters, digits, `-`, `_` and `.`, and may not start with `.`.
+pub fn is_valid_session_name(name: &str) -> bool {
+    (1..=64).contains(&name.len())
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Returns the shared socket path used by a multi-session server
+/// (`~/.qorvex/qorvex.sock`).
+pub fn multi_socket_path() -> PathBuf {
+    qorvex_dir().join("qorvex.sock")
+}
+
 /// Unix socket server for IPC communication.
 ///
 /// The server accepts connections from clients
@@ -419,6 +841,12 @@ pub struct IpcServer {
     /// Optional pluggable request handler. When set, all requests are delegated
     /// to this handler instead of the built-in logic.
     handler: Option<Arc<dyn RequestHandler>>,
+    /// Actions executing on behalf of clients, for [`IpcRequest::Cancel`].
+    in_flight: InFlightActions,
+    /// Token clients must present via [`IpcRequest::Auth`], if any.
+    auth_token: Option<String>,
+    /// Keepalive interval for subscribed clients.
+    ping_interval: Duration,
 }
 
 impl IpcServer {
@@ -436,15 +864,42 @@ impl IpcServer {
     /// # Returns
     ///
     /// A new `IpcServer` instance (not yet running).
+    ///
+    /// The authentication token is read from [`IPC_TOKEN_ENV`].
     pub fn new(session: Arc<Session>, session_name: &str) -> Self {
         Self {
             session,
             socket_path: socket_path(session_name),
             shared_driver: Arc::new(tokio::sync::Mutex::new(None)),
             handler: None,
+            in_flight: InFlightActions::default(),
+            auth_token: ipc_token(),
+            ping_interval: ping_interval(),
         }
     }
 
+    /// Overrides the authentication token read from the environment.
+    ///
+    /// `None` disables authentication.
+    ///
+    /// # Returns
+    ///
+    /// The server instance (builder pattern).
+    pub fn with_auth_token(mut self, token: Option<String>) -> Self {
+        self.auth_token = token;
+        self
+    }
+
+    /// Overrides the subscribe keepalive interval read from [`PING_INTERVAL_ENV`].
+    ///
+    /// # Returns
+    ///
+    /// The server instance (builder pattern).
+    pub fn with_ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = interval;
+        self
+    }
+
     /// Sets a pluggable request handler on this server.
     ///
     /// When a handler is set, all incoming IPC requests are delegated to it
@@ -504,12 +959,23 @@ impl IpcServer {
             let session = self.session.clone();
             let shared_driver = self.shared_driver.clone();
             let handler = self.handler.clone();
+            let in_flight = self.in_flight.clone();
+            let auth = AuthGate::new(self.auth_token.clone());
+            let ping_interval = self.ping_interval;
 
             tokio::spawn(async move {
                 let span = info_span!("ipc_client");
-                let _ = Self::handle_client(stream, session, shared_driver, handler)
-                    .instrument(span)
-                    .await;
+                let _ = Self::handle_client(
+                    stream,
+                    session,
+                    shared_driver,
+                    handler,
+                    in_flight,
+                    auth,
+                    ping_interval,
+                )
+                .instrument(span)
+                .await;
             });
         }
     }
@@ -519,6 +985,9 @@ impl IpcServer {
         session: Arc<Session>,
         shared_driver: Arc<tokio::sync::Mutex<Option<Arc<dyn crate::driver::AutomationDriver>>>>,
         handler: Option<Arc<dyn RequestHandler>>,
+        in_flight: InFlightActions,
+        mut auth: AuthGate,
+        ping_interval: Duration,
     ) -> Result<(), IpcError> {
         let (reader, mut writer) = stream.into_split();
         let mut reader = BufReader::new(reader);
@@ -533,6 +1002,13 @@ impl IpcServer {
 
             let request: IpcRequest = serde_json::from_str(line.trim())?;
 
+            if let Some(response) = auth.check(&request) {
+                let json = serde_json::to_string(&response)? + "\n";
+                writer.write_all(json.as_bytes()).await?;
+                writer.flush().await?;
+                continue;
+            }
+
             if let Some(ref handler) = handler {
                 handler
                     .handle(request, session.clone(), &mut writer)
@@ -542,10 +1018,14 @@ impl IpcServer {
 
             // Fallback: built-in hardcoded logic (backward compatibility)
             match request {
-                IpcRequest::Execute { action, tag } => {
+                IpcRequest::Execute {
+                    action,
+                    tag,
+                    capture_screenshot,
+                } => {
                     debug!(action = %action.name(), "executing action via IPC");
                     // Execute the action using the ActionExecutor
-                    // LogComment doesn't require a driver
+                    // Comments and assertions don't require a driver
                     let response = if let ActionType::LogComment { ref message } = action {
                         let msg = format!("Logged: {}", message);
                         session
@@ -557,14 +1037,42 @@ impl IpcServer {
                             message: msg,
                             screenshot: None,
                             data: None,
+                            element_not_found: false,
+                        }
+                    } else if let ActionType::Assertion {
+                        ref description,
+                        passed,
+                    } = action
+                    {
+                        let msg = if passed {
+                            format!("Assertion passed: {}", description)
+                        } else {
+                            format!("Assertion failed: {}", description)
+                        };
+                        let result = if passed {
+                            ActionResult::Success
+                        } else {
+                            ActionResult::Failure(msg.clone())
+                        };
+                        session.log_action(action, result, None, None, tag).await;
+
+                        IpcResponse::ActionResult {
+                            success: passed,
+                            message: msg,
+                            screenshot: None,
+                            data: None,
+                            element_not_found: false,
                         }
                     } else {
                         let driver_guard = shared_driver.lock().await;
                         match driver_guard.as_ref() {
                             Some(driver) => {
-                                let executor = ActionExecutor::new(driver.clone());
+                                let guard = in_flight.begin();
+                                let executor = ActionExecutor::new(driver.clone())
+                                    .with_cancellation(guard.token());
                                 drop(driver_guard); // release lock before executing
                                 let result = executor.execute(action.clone()).await;
+                                drop(guard);
 
                                 // Log to session
                                 let action_result = if result.success {
@@ -572,17 +1080,20 @@ impl IpcServer {
                                 } else {
                                     ActionResult::Failure(result.message.clone())
                                 };
-                                let duration_ms = result
-                                    .data
-                                    .as_ref()
-                                    .and_then(|d| serde_json::from_str::<serde_json::Value>(d).ok())
-                                    .and_then(|v| v.get("elapsed_ms").and_then(|e| e.as_u64()));
+                                let duration_ms = result.elapsed_ms();
+                                let value = result.read_value(&action);
+                                let screenshot = if capture_screenshot == Some(false) {
+                                    None
+                                } else {
+                                    result.screenshot.clone()
+                                };
                                 session
-                                    .log_action(
+                                    .log_action_with_value(
                                         action,
                                         action_result,
-                                        result.screenshot.clone(),
+                                        screenshot,
                                         duration_ms,
+                                        value,
                                         tag,
                                     )
                                     .await;
@@ -592,6 +1103,7 @@ impl IpcServer {
                                     message: result.message,
                                     screenshot: result.screenshot.map(Arc::new),
                                     data: result.data,
+                                    element_not_found: result.element_not_found,
                                 }
                             }
                             None => IpcResponse::Error {
@@ -608,17 +1120,7 @@ impl IpcServer {
                 IpcRequest::Subscribe => {
                     debug!("client subscribing to events");
                     // Send events as they occur
-                    let mut rx = session.subscribe();
-                    while let Ok(event) = rx.recv().await {
-                        let response = IpcResponse::Event { event };
-                        let json = serde_json::to_string(&response)? + "\n";
-                        if writer.write_all(json.as_bytes()).await.is_err() {
-                            break;
-                        }
-                        if writer.flush().await.is_err() {
-                            break;
-                        }
-                    }
+                    forward_events(session.subscribe(), &mut writer, ping_interval).await?;
                 }
                 IpcRequest::GetState => {
                     debug!("client requesting state");
@@ -630,6 +1132,38 @@ impl IpcServer {
                     writer.write_all(json.as_bytes()).await?;
                     writer.flush().await?;
                 }
+                IpcRequest::GetSnapshot => {
+                    debug!("client requesting snapshot");
+                    let response = IpcResponse::Snapshot {
+                        snapshot: session.snapshot().await,
+                    };
+                    let json = serde_json::to_string(&response)? + "\n";
+                    writer.write_all(json.as_bytes()).await?;
+                    writer.flush().await?;
+                }
+                IpcRequest::Cancel => {
+                    debug!("client cancelling in-flight actions");
+                    let response = in_flight.cancel_response();
+                    let json = serde_json::to_string(&response)? + "\n";
+                    writer.write_all(json.as_bytes()).await?;
+                    writer.flush().await?;
+                }
+                IpcRequest::Health => {
+                    let driver_connected = shared_driver
+                        .lock()
+                        .await
+                        .as_ref()
+                        .is_some_and(|driver| driver.is_connected());
+                    let response = IpcResponse::Health {
+                        session_active: true,
+                        driver_connected,
+                        agent_target: None,
+                        simulator_udid: None,
+                    };
+                    let json = serde_json::to_string(&response)? + "\n";
+                    writer.write_all(json.as_bytes()).await?;
+                    writer.flush().await?;
+                }
                 IpcRequest::GetLog => {
                     debug!("client requesting log");
                     let response = IpcResponse::Log {
@@ -667,11 +1201,17 @@ pub struct IpcClient {
     stream: BufReader<tokio::net::unix::OwnedReadHalf>,
     /// Writer for the socket's write half.
     writer: tokio::net::unix::OwnedWriteHalf,
+    /// Session name stamped on every request, for multi-session servers.
+    session: String,
 }
 
 impl IpcClient {
     /// Connects to an IPC server for the specified session.
     ///
+    /// Uses the session's own socket if a server is listening there, and the
+    /// multi-session socket otherwise. Every request sent through the client
+    /// names `session_name`, so either kind of server serves the same session.
+    ///
     /// # Arguments
     ///
     /// * `session_name` - The name of the session to connect to
@@ -683,14 +1223,46 @@ impl IpcClient {
     /// # Errors
     ///
     /// - [`IpcError::Io`] if the connection fails (e.g., server not running)
+    /// - [`IpcError::Unauthorized`] if the server rejects the token from [`IPC_TOKEN_ENV`]
     pub async fn connect(session_name: &str) -> Result<Self, IpcError> {
-        let path = socket_path(session_name);
-        let stream = UnixStream::connect(&path).await?;
+        Self::connect_with_token(session_name, ipc_token().as_deref()).await
+    }
+
+    /// Connects to an IPC server, authenticating with `token` if given.
+    ///
+    /// Like [`connect`](Self::connect), but with an explicit token instead of
+    /// the one from [`IPC_TOKEN_ENV`].
+    ///
+    /// # Errors
+    ///
+    /// - [`IpcError::Io`] if the connection fails (e.g., server not running)
+    /// - [`IpcError::Unauthorized`] if the server rejects the token
+    pub async fn connect_with_token(
+        session_name: &str,
+        token: Option<&str>,
+    ) -> Result<Self, IpcError> {
+        let stream = match UnixStream::connect(socket_path(session_name)).await {
+            Ok(stream) => stream,
+            Err(e) => match UnixStream::connect(multi_socket_path()).await {
+                Ok(stream) => stream,
+                Err(_) => return Err(e.into()),
+            },
+        };
         let (reader, writer) = stream.into_split();
-        Ok(Self {
+        let mut client = Self {
             stream: BufReader::new(reader),
             writer,
-        })
+            session: session_name.to_string(),
+        };
+        if let Some(token) = token {
+            let request = IpcRequest::Auth {
+                token: token.to_string(),
+            };
+            if let IpcResponse::Error { message } = client.send(&request).await? {
+                return Err(IpcError::Unauthorized(message));
+            }
+        }
+        Ok(client)
     }
 
     /// Sends a request and waits for the response.
@@ -711,9 +1283,7 @@ impl IpcClient {
     /// - [`IpcError::Io`] if the send or receive fails
     /// - [`IpcError::Json`] if serialization or deserialization fails
     pub async fn send(&mut self, request: &IpcRequest) -> Result<IpcResponse, IpcError> {
-        let json = serde_json::to_string(request)? + "\n";
-        self.writer.write_all(json.as_bytes()).await?;
-        self.writer.flush().await?;
+        self.write_request(request).await?;
 
         let mut line = String::new();
         self.stream.read_line(&mut line).await?;
@@ -732,8 +1302,16 @@ impl IpcClient {
     /// - [`IpcError::Io`] if the send fails
     /// - [`IpcError::Json`] if serialization fails
     pub async fn subscribe(&mut self) -> Result<(), IpcError> {
-        let request = IpcRequest::Subscribe;
-        let json = serde_json::to_string(&request)? + "\n";
+        self.write_request(&IpcRequest::Subscribe).await
+    }
+
+    /// Writes one request, addressed to this client's session.
+    async fn write_request(&mut self, request: &IpcRequest) -> Result<(), IpcError> {
+        let envelope = IpcEnvelope {
+            session: Some(self.session.clone()),
+            request: request.clone(),
+        };
+        let json = serde_json::to_string(&envelope)? + "\n";
         self.writer.write_all(json.as_bytes()).await?;
         self.writer.flush().await?;
         Ok(())
@@ -746,7 +1324,8 @@ impl IpcClient {
     ///
     /// # Returns
     ///
-    /// The next [`IpcResponse`] from the server (typically an `Event` variant).
+    /// The next [`IpcResponse`] from the server (typically an `Event` variant,
+    /// or a [`Ping`](IpcResponse::Ping) keepalive to ignore).
     ///
     /// # Errors
     ///
@@ -809,9 +1388,14 @@ mod platform_tests {
         let legacy = r#"{"type":"BootDevice","udid":"ABC-123"}"#;
         let req: IpcRequest = serde_json::from_str(legacy).unwrap();
         match req {
-            IpcRequest::BootDevice { udid, platform } => {
+            IpcRequest::BootDevice {
+                udid,
+                platform,
+                wait,
+            } => {
                 assert_eq!(udid, "ABC-123");
                 assert_eq!(platform, Platform::Ios);
+                assert!(!wait);
             }
             other => panic!("expected BootDevice, got {other:?}"),
         }
@@ -856,4 +1440,62 @@ mod platform_tests {
             }
         ));
     }
+
+    #[test]
+    fn in_flight_cancel_only_affects_running_actions() {
+        let actions = InFlightActions::default();
+        assert!(!actions.cancel());
+
+        let guard = actions.begin();
+        assert!(actions.cancel());
+        assert!(guard.token().is_cancelled());
+        drop(guard);
+
+        let next = actions.begin();
+        assert!(!next.token().is_cancelled());
+        drop(next);
+        assert!(!actions.cancel());
+    }
+
+    #[test]
+    fn envelope_carries_session_alongside_request() {
+        let envelope = IpcEnvelope {
+            session: Some("sim-a".into()),
+            request: IpcRequest::SetTimeout { timeout_ms: 250 },
+        };
+        let value: serde_json::Value = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"type": "SetTimeout", "timeout_ms": 250, "session": "sim-a"})
+        );
+
+        // Single-session servers still read the bare request
+        let request: IpcRequest = serde_json::from_value(value).unwrap();
+        assert!(matches!(
+            request,
+            IpcRequest::SetTimeout { timeout_ms: 250 }
+        ));
+    }
+
+    #[test]
+    fn envelope_session_defaults_to_none() {
+        let envelope: IpcEnvelope = serde_json::from_str(r#"{"type":"Subscribe"}"#).unwrap();
+        assert!(envelope.session.is_none());
+        assert!(matches!(envelope.request, IpcRequest::Subscribe));
+        assert_eq!(
+            serde_json::to_string(&envelope).unwrap(),
+            r#"{"type":"Subscribe"}"#
+        );
+    }
+
+    #[test]
+    fn session_names_are_limited_to_the_socket_charset() {
+        for name in ["default", "sim-a", "test_1a2b3c4d", "ci.run.2"] {
+            assert!(is_valid_session_name(name), "{name}");
+        }
+        let long = "a".repeat(65);
+        for name in ["", "../x", "a/b", ".hidden", "sp ace", "é", long.as_str()] {
+            assert!(!is_valid_session_name(name), "{name}");
+        }
+    }
 }
@@ -9,15 +9,21 @@
 //! ## Modules
 //!
 //! ### Driver abstraction
-//! - [`driver`] - `AutomationDriver` trait, `DriverConfig`, glob matching for element selectors
+//! - [`driver`] - `AutomationDriver` trait, `DriverConfig`, client-side element search
+//! - [`selector`] - Selector matching rules: globs, `[N]` indexes, type filters, suggestions
 //! - [`element`] - Shared `UIElement` and `ElementFrame` types
+//! - [`describe`] - Screen summaries: title, element counts and buttons
 //! - [`protocol`] - Binary wire protocol codec for Rust ↔ Swift agent communication
 //! - [`executor`] - Backend-agnostic action execution engine
+//! - [`result_data`] - Typed, versioned payloads for action result `data`
+//! - [`screenshot`] - Screenshot cropping and point-to-pixel scaling
+//! - [`fuzzy`] - Fuzzy matching and typo suggestions for selectors
 //!
 //! ### Backends
 //! - [`agent_client`] - Low-level async TCP client for the Swift agent
 //! - [`agent_driver`] - `AgentDriver` backend (simulators via TCP, devices via USB tunnel)
 //! - [`android_driver`] - `AndroidDriver` backend (Kotlin agent via `adb forward` TCP tunnel)
+//! - [`idb_driver`] - `IdbDriver` backend (iOS via Facebook's `idb` CLI, no agent)
 //! - [`agent_lifecycle`] - Swift agent install/launch/health-check via `xcrun simctl`
 //! - [`android_lifecycle`] - Kotlin agent Gradle-build/install/`am instrument`/health-poll via `adb`
 //! - [`usb_tunnel`] - Physical device discovery and port forwarding via usbmuxd
@@ -27,6 +33,8 @@
 //! - [`adb_device`] - Wrapper around Android's `adb` CLI for device/emulator control
 //! - [`adb_forward`] - Single `adb forward` TCP tunnel to the on-device Android agent
 //! - [`session`] - Session state management with event broadcasting
+//! - [`screen_recording`] - Screen Recording permission preflight for the live video feed
+//! - [`testing`] - `MockDriver`, a scripted in-memory driver for tests
 //! - [`ipc`] - Unix socket-based IPC for REPL and watcher communication
 //! - [`action`] - Action types and logging for automation operations
 //!
@@ -55,11 +63,19 @@ pub mod android_lifecycle;
 pub mod config;
 pub mod core_device_tunnel;
 pub mod coredevice;
+pub mod describe;
 pub mod driver;
 pub mod element;
 pub mod executor;
+pub mod fuzzy;
+pub mod idb_driver;
 pub mod ipc;
 pub mod protocol;
+pub mod result_data;
+pub mod screen_recording;
+pub mod screenshot;
+pub mod selector;
 pub mod session;
 pub mod simctl;
+pub mod testing;
 pub mod usb_tunnel;
@@ -33,6 +33,7 @@
 //! let decoded = decode_request(&wire[4..]).unwrap();
 //! ```
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 // ---------------------------------------------------------------------------
@@ -90,7 +91,9 @@ pub enum OpCode {
     LongPress = 0x09,
     /// Request a full accessibility tree dump (no payload).
     DumpTree = 0x10,
-    /// Request a screenshot capture (no payload).
+    /// Request a screenshot capture (optional trailing u8 format tag, then a
+    /// u8 JPEG quality for [`ScreenshotFormat::Jpeg`]). No payload asks for
+    /// PNG, which is all agents that predate the format field return.
     Screenshot = 0x11,
     /// Set the target application for accessibility queries (length-prefixed string).
     SetTarget = 0x12,
@@ -108,6 +111,61 @@ pub enum OpCode {
     /// port. An agent that predates this opcode answers with an error, which the
     /// host treats as "identity unknown".
     DeviceUdid = 0x16,
+    /// Request a filtered accessibility tree dump (u32 type count + type
+    /// strings, bool visible_only, optional u32 max_depth). Agents that predate
+    /// this opcode answer with an error; the host then filters a full dump.
+    DumpTreeFiltered = 0x17,
+    /// Ask the agent for its current target and foreground app (no payload).
+    /// The agent replies with [`Response::State`], letting a reconnecting host
+    /// recover the target a previous client set. Agents that predate this
+    /// opcode answer with an error.
+    GetState = 0x18,
+    /// Ask the agent for the screen's size, scale factor and orientation (no
+    /// payload). The agent replies with [`Response::ScreenInfo`]. Agents that
+    /// predate this opcode answer with an error.
+    GetScreenInfo = 0x19,
+    /// Rotate the device (length-prefixed orientation name, e.g.
+    /// `"landscape_left"`). Agents that predate this opcode answer with an
+    /// error.
+    SetOrientation = 0x1A,
+    /// Press, hold, and drag between two points (i32 start_x, start_y, end_x,
+    /// end_y, f64 hold_duration, f64 move_duration). Agents that predate this
+    /// opcode answer with an error.
+    Drag = 0x1B,
+    /// Tap with several fingers at once (u8 finger_count, i32 x, i32 y).
+    /// Agents that predate this opcode answer with an error.
+    MultiTap = 0x1C,
+    /// Paste the pasteboard contents into the focused element (no payload).
+    /// Agents that predate this opcode answer with an error.
+    Paste = 0x1D,
+    /// Clear the text of an input field (string selector, bool by_label).
+    /// Agents that predate this opcode answer with an error.
+    ClearText = 0x1E,
+    /// Find every element matching a selector (selector + by_label + optional
+    /// type); an empty selector matches any element. The agent replies with a
+    /// [`Response::Tree`] holding the matches. Agents that predate this opcode
+    /// answer with an error; the host then searches a full dump.
+    FindAll = 0x1F,
+    /// Several requests in one frame (u32 count, then each request as a full
+    /// length-prefixed frame). The agent runs them in order, stops at the first
+    /// error and replies with a [`Response::Batch`] of the responses produced.
+    /// Agents that predate this opcode answer with an error.
+    Batch = 0x20,
+    /// Start pushing screenshots over this connection (u8 fps, u8 JPEG
+    /// quality). The agent acknowledges with [`Response::Ok`], then sends a
+    /// [`Response::Screenshot`] per frame until it receives
+    /// [`StopStream`](Self::StopStream). Agents that predate this opcode answer
+    /// with an error.
+    StreamScreenshots = 0x21,
+    /// Stop a screenshot stream (no payload). The agent stops sending frames
+    /// and acknowledges with [`Response::Ok`].
+    StopStream = 0x22,
+    /// Press a special key on the keyboard (u8 [`SpecialKey`] code). Agents
+    /// that predate this opcode answer with an error.
+    SendKey = 0x23,
+    /// Long-press an element (selector string, by_label bool, f64 duration
+    /// in seconds). Agents that predate this opcode answer with an error.
+    LongPressElement = 0x25,
     /// Error message from the agent (length-prefixed string).
     Error = 0x99,
     /// Generic response (response-type byte + variable data).
@@ -134,6 +192,20 @@ impl OpCode {
             0x14 => Ok(OpCode::GetTargetInfo),
             0x15 => Ok(OpCode::BridgeHealth),
             0x16 => Ok(OpCode::DeviceUdid),
+            0x17 => Ok(OpCode::DumpTreeFiltered),
+            0x18 => Ok(OpCode::GetState),
+            0x19 => Ok(OpCode::GetScreenInfo),
+            0x1A => Ok(OpCode::SetOrientation),
+            0x1B => Ok(OpCode::Drag),
+            0x1C => Ok(OpCode::MultiTap),
+            0x1D => Ok(OpCode::Paste),
+            0x1E => Ok(OpCode::ClearText),
+            0x1F => Ok(OpCode::FindAll),
+            0x20 => Ok(OpCode::Batch),
+            0x21 => Ok(OpCode::StreamScreenshots),
+            0x22 => Ok(OpCode::StopStream),
+            0x23 => Ok(OpCode::SendKey),
+            0x25 => Ok(OpCode::LongPressElement),
             0x99 => Ok(OpCode::Error),
             0xA0 => Ok(OpCode::Response),
             other => Err(ProtocolError::InvalidOpCode(other)),
@@ -141,6 +213,120 @@ impl OpCode {
     }
 }
 
+// ---------------------------------------------------------------------------
+// SpecialKey
+// ---------------------------------------------------------------------------
+
+/// A keyboard key that has no text of its own, pressed with
+/// [`Request::SendKey`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[repr(u8)]
+pub enum SpecialKey {
+    /// Return (Enter).
+    Return = 0x01,
+    /// Tab.
+    Tab = 0x02,
+    /// Delete (Backspace).
+    Delete = 0x03,
+    /// Escape.
+    Escape = 0x04,
+    /// Up arrow.
+    Up = 0x05,
+    /// Down arrow.
+    Down = 0x06,
+    /// Left arrow.
+    Left = 0x07,
+    /// Right arrow.
+    Right = 0x08,
+}
+
+impl SpecialKey {
+    /// All keys, in declaration order.
+    pub const ALL: [SpecialKey; 8] = [
+        SpecialKey::Return,
+        SpecialKey::Tab,
+        SpecialKey::Delete,
+        SpecialKey::Escape,
+        SpecialKey::Up,
+        SpecialKey::Down,
+        SpecialKey::Left,
+        SpecialKey::Right,
+    ];
+
+    /// The name used by the CLI and in `{name}` tokens, e.g. `"return"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SpecialKey::Return => "return",
+            SpecialKey::Tab => "tab",
+            SpecialKey::Delete => "delete",
+            SpecialKey::Escape => "escape",
+            SpecialKey::Up => "up",
+            SpecialKey::Down => "down",
+            SpecialKey::Left => "left",
+            SpecialKey::Right => "right",
+        }
+    }
+
+    /// Try to convert a wire byte into a `SpecialKey`.
+    pub fn from_u8(byte: u8) -> Result<Self, ProtocolError> {
+        SpecialKey::ALL
+            .into_iter()
+            .find(|k| *k as u8 == byte)
+            .ok_or_else(|| ProtocolError::InvalidPayload(format!("unknown key: 0x{byte:02X}")))
+    }
+}
+
+impl std::fmt::Display for SpecialKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for SpecialKey {
+    type Err = String;
+
+    /// Parses a key name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_lowercase();
+        SpecialKey::ALL
+            .into_iter()
+            .find(|k| k.as_str() == normalized)
+            .ok_or_else(|| {
+                format!(
+                    "unknown key '{}' (expected one of: {})",
+                    s.trim(),
+                    SpecialKey::ALL.map(SpecialKey::as_str).join(", ")
+                )
+            })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ScreenshotFormat
+// ---------------------------------------------------------------------------
+
+/// Image format of a [`Request::Screenshot`].
+///
+/// PNG is lossless, for pixel-exact comparisons and cropping; JPEG is several
+/// times smaller, for screenshots that are only looked at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScreenshotFormat {
+    /// Lossless PNG.
+    #[default]
+    Png,
+    /// JPEG at `quality` (1-100; the agent clamps it).
+    Jpeg { quality: u8 },
+}
+
+impl ScreenshotFormat {
+    /// Wire tag of [`ScreenshotFormat::Png`].
+    const PNG_TAG: u8 = 0x00;
+    /// Wire tag of [`ScreenshotFormat::Jpeg`].
+    const JPEG_TAG: u8 = 0x01;
+}
+
 // ---------------------------------------------------------------------------
 // Request / Response enums
 // ---------------------------------------------------------------------------
@@ -190,8 +376,8 @@ pub enum Request {
     LongPress { x: i32, y: i32, duration: f64 },
     /// Request the full accessibility tree.
     DumpTree,
-    /// Request a screenshot.
-    Screenshot,
+    /// Request a screenshot in the given format.
+    Screenshot { format: ScreenshotFormat },
     /// Set the target application bundle ID for accessibility queries.
     SetTarget { bundle_id: String },
     /// Find a single element matching the selector.
@@ -206,6 +392,58 @@ pub enum Request {
     BridgeHealth,
     /// Ask the agent for the UDID of the device it is running on (simulator-only).
     DeviceUdid,
+    /// Request the accessibility tree, filtered on the agent.
+    DumpTreeFiltered {
+        types: Vec<String>,
+        visible_only: bool,
+        max_depth: Option<u32>,
+    },
+    /// Ask the agent for its current target and foreground app.
+    GetState,
+    /// Ask the agent for the screen size, scale factor and orientation.
+    GetScreenInfo,
+    /// Rotate the device to the named orientation.
+    SetOrientation { orientation: String },
+    /// Press at a start point, hold, then drag to an end point and release.
+    Drag {
+        start_x: i32,
+        start_y: i32,
+        end_x: i32,
+        end_y: i32,
+        /// Seconds to hold at the start point before moving.
+        hold_duration: f64,
+        /// Seconds the move to the end point takes.
+        move_duration: f64,
+    },
+    /// Tap at specific screen coordinates with several fingers at once.
+    MultiTap { finger_count: u8, x: i32, y: i32 },
+    /// Paste the pasteboard contents into the focused element.
+    Paste,
+    /// Focus an input field and delete all of its text.
+    ClearText { selector: String, by_label: bool },
+    /// Find every element matching the selector.
+    FindAll {
+        selector: String,
+        by_label: bool,
+        element_type: Option<String>,
+    },
+    /// Run several requests in one round trip. Batches cannot be nested.
+    Batch { requests: Vec<Request> },
+    /// Push JPEG screenshots at `fps` frames per second until
+    /// [`StopStream`](Self::StopStream). The agent clamps `fps` to 1-30 and
+    /// `quality` to 1-100.
+    StreamScreenshots { fps: u8, quality: u8 },
+    /// Stop a screenshot stream.
+    StopStream,
+    /// Press a special key on the keyboard.
+    SendKey { key: SpecialKey },
+    /// Press and hold the element matching the selector for `duration`
+    /// seconds.
+    LongPressElement {
+        selector: String,
+        by_label: bool,
+        duration: f64,
+    },
 }
 
 impl Request {
@@ -223,12 +461,26 @@ impl Request {
             Request::GetValue { .. } => "get_value",
             Request::LongPress { .. } => "long_press",
             Request::DumpTree => "dump_tree",
-            Request::Screenshot => "screenshot",
+            Request::Screenshot { .. } => "screenshot",
             Request::SetTarget { .. } => "set_target",
             Request::FindElement { .. } => "find_element",
             Request::GetTargetInfo => "get_target_info",
             Request::BridgeHealth => "bridge_health",
             Request::DeviceUdid => "device_udid",
+            Request::DumpTreeFiltered { .. } => "dump_tree_filtered",
+            Request::GetState => "get_state",
+            Request::GetScreenInfo => "get_screen_info",
+            Request::SetOrientation { .. } => "set_orientation",
+            Request::Drag { .. } => "drag",
+            Request::MultiTap { .. } => "multi_tap",
+            Request::Paste => "paste",
+            Request::ClearText { .. } => "clear_text",
+            Request::FindAll { .. } => "find_all",
+            Request::Batch { .. } => "batch",
+            Request::StreamScreenshots { .. } => "stream_screenshots",
+            Request::StopStream => "stop_stream",
+            Request::SendKey { .. } => "send_key",
+            Request::LongPressElement { .. } => "long_press_element",
         }
     }
 }
@@ -244,6 +496,9 @@ enum ResponseType {
     Value = 0x04,
     Element = 0x05,
     TargetInfo = 0x06,
+    State = 0x07,
+    ScreenInfo = 0x08,
+    Batch = 0x09,
 }
 
 impl ResponseType {
@@ -256,6 +511,9 @@ impl ResponseType {
             0x04 => Ok(ResponseType::Value),
             0x05 => Ok(ResponseType::Element),
             0x06 => Ok(ResponseType::TargetInfo),
+            0x07 => Ok(ResponseType::State),
+            0x08 => Ok(ResponseType::ScreenInfo),
+            0x09 => Ok(ResponseType::Batch),
             other => Err(ProtocolError::InvalidPayload(format!(
                 "unknown response type: 0x{other:02X}"
             ))),
@@ -280,6 +538,22 @@ pub enum Response {
     Element { json: String },
     /// JSON-encoded target application metadata.
     TargetInfo { json: String },
+    /// The agent's current target and foreground app bundle IDs, if known.
+    State {
+        target_bundle: Option<String>,
+        foreground_app: Option<String>,
+    },
+    /// The screen size in points, its scale factor (pixels per point) and the
+    /// current interface orientation.
+    ScreenInfo {
+        width: f64,
+        height: f64,
+        scale: f64,
+        orientation: String,
+    },
+    /// One response per request of a [`Request::Batch`] that ran. Shorter than
+    /// the batch when a request failed; the last response is then the error.
+    Batch { responses: Vec<Response> },
 }
 
 // ---------------------------------------------------------------------------
@@ -329,6 +603,20 @@ fn write_optional_u64(buf: &mut Vec<u8>, opt: Option<u64>) {
     }
 }
 
+/// Write an optional u32.
+///
+/// Format: `[u8 flag]` where flag=0 means None, flag=1 means Some followed by
+/// a u32 LE.
+fn write_optional_u32(buf: &mut Vec<u8>, opt: Option<u32>) {
+    match opt {
+        None => buf.push(0u8),
+        Some(v) => {
+            buf.push(1u8);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+}
+
 /// Write a bool as a single `u8` (0 = false, 1 = true).
 fn write_bool(buf: &mut Vec<u8>, v: bool) {
     buf.push(if v { 1u8 } else { 0u8 });
@@ -428,6 +716,16 @@ impl<'a> Cursor<'a> {
         }
     }
 
+    /// Read an optional u32.
+    fn read_optional_u32(&mut self) -> Result<Option<u32>, ProtocolError> {
+        let flag = self.read_u8()?;
+        if flag == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(self.read_u32()?))
+        }
+    }
+
     /// Read an optional trailing u64. Returns None if no bytes remain.
     fn read_optional_trailing_u64(&mut self) -> Result<Option<u64>, ProtocolError> {
         if self.remaining() == 0 {
@@ -563,8 +861,13 @@ pub fn encode_request(req: &Request) -> Vec<u8> {
         Request::DumpTree => {
             payload.push(OpCode::DumpTree as u8);
         }
-        Request::Screenshot => {
+        Request::Screenshot { format } => {
             payload.push(OpCode::Screenshot as u8);
+            // PNG is sent bare so agents without the format field still accept it
+            if let ScreenshotFormat::Jpeg { quality } = format {
+                payload.push(ScreenshotFormat::JPEG_TAG);
+                payload.push(*quality);
+            }
         }
         Request::SetTarget { bundle_id } => {
             payload.push(OpCode::SetTarget as u8);
@@ -589,6 +892,98 @@ pub fn encode_request(req: &Request) -> Vec<u8> {
         Request::DeviceUdid => {
             payload.push(OpCode::DeviceUdid as u8);
         }
+        Request::DumpTreeFiltered {
+            types,
+            visible_only,
+            max_depth,
+        } => {
+            payload.push(OpCode::DumpTreeFiltered as u8);
+            payload.extend_from_slice(&(types.len() as u32).to_le_bytes());
+            for t in types {
+                write_string(&mut payload, t);
+            }
+            write_bool(&mut payload, *visible_only);
+            write_optional_u32(&mut payload, *max_depth);
+        }
+        Request::GetState => {
+            payload.push(OpCode::GetState as u8);
+        }
+        Request::GetScreenInfo => {
+            payload.push(OpCode::GetScreenInfo as u8);
+        }
+        Request::SetOrientation { orientation } => {
+            payload.push(OpCode::SetOrientation as u8);
+            write_string(&mut payload, orientation);
+        }
+        Request::Drag {
+            start_x,
+            start_y,
+            end_x,
+            end_y,
+            hold_duration,
+            move_duration,
+        } => {
+            payload.push(OpCode::Drag as u8);
+            payload.extend_from_slice(&start_x.to_le_bytes());
+            payload.extend_from_slice(&start_y.to_le_bytes());
+            payload.extend_from_slice(&end_x.to_le_bytes());
+            payload.extend_from_slice(&end_y.to_le_bytes());
+            payload.extend_from_slice(&hold_duration.to_le_bytes());
+            payload.extend_from_slice(&move_duration.to_le_bytes());
+        }
+        Request::MultiTap { finger_count, x, y } => {
+            payload.push(OpCode::MultiTap as u8);
+            payload.push(*finger_count);
+            payload.extend_from_slice(&x.to_le_bytes());
+            payload.extend_from_slice(&y.to_le_bytes());
+        }
+        Request::Paste => {
+            payload.push(OpCode::Paste as u8);
+        }
+        Request::ClearText { selector, by_label } => {
+            payload.push(OpCode::ClearText as u8);
+            write_string(&mut payload, selector);
+            write_bool(&mut payload, *by_label);
+        }
+        Request::FindAll {
+            selector,
+            by_label,
+            element_type,
+        } => {
+            payload.push(OpCode::FindAll as u8);
+            write_string(&mut payload, selector);
+            write_bool(&mut payload, *by_label);
+            write_optional_string(&mut payload, element_type);
+        }
+        Request::Batch { requests } => {
+            payload.push(OpCode::Batch as u8);
+            payload.extend_from_slice(&(requests.len() as u32).to_le_bytes());
+            for request in requests {
+                payload.extend_from_slice(&encode_request(request));
+            }
+        }
+        Request::StreamScreenshots { fps, quality } => {
+            payload.push(OpCode::StreamScreenshots as u8);
+            payload.push(*fps);
+            payload.push(*quality);
+        }
+        Request::StopStream => {
+            payload.push(OpCode::StopStream as u8);
+        }
+        Request::SendKey { key } => {
+            payload.push(OpCode::SendKey as u8);
+            payload.push(*key as u8);
+        }
+        Request::LongPressElement {
+            selector,
+            by_label,
+            duration,
+        } => {
+            payload.push(OpCode::LongPressElement as u8);
+            write_string(&mut payload, selector);
+            write_bool(&mut payload, *by_label);
+            payload.extend_from_slice(&duration.to_le_bytes());
+        }
     }
 
     encode_frame(&payload)
@@ -690,7 +1085,24 @@ pub fn decode_request(data: &[u8]) -> Result<Request, ProtocolError> {
 
         OpCode::DumpTree => Ok(Request::DumpTree),
 
-        OpCode::Screenshot => Ok(Request::Screenshot),
+        OpCode::Screenshot => {
+            let format = if cur.remaining() == 0 {
+                ScreenshotFormat::Png
+            } else {
+                match cur.read_u8()? {
+                    ScreenshotFormat::PNG_TAG => ScreenshotFormat::Png,
+                    ScreenshotFormat::JPEG_TAG => ScreenshotFormat::Jpeg {
+                        quality: cur.read_u8()?,
+                    },
+                    other => {
+                        return Err(ProtocolError::InvalidPayload(format!(
+                            "unknown screenshot format: 0x{other:02X}"
+                        )))
+                    }
+                }
+            };
+            Ok(Request::Screenshot { format })
+        }
 
         OpCode::SetTarget => {
             let bundle_id = cur.read_string()?;
@@ -714,6 +1126,113 @@ pub fn decode_request(data: &[u8]) -> Result<Request, ProtocolError> {
 
         OpCode::DeviceUdid => Ok(Request::DeviceUdid),
 
+        OpCode::DumpTreeFiltered => {
+            let count = cur.read_u32()? as usize;
+            let mut types = Vec::new();
+            for _ in 0..count {
+                types.push(cur.read_string()?);
+            }
+            let visible_only = cur.read_bool()?;
+            let max_depth = cur.read_optional_u32()?;
+            Ok(Request::DumpTreeFiltered {
+                types,
+                visible_only,
+                max_depth,
+            })
+        }
+
+        OpCode::GetState => Ok(Request::GetState),
+
+        OpCode::GetScreenInfo => Ok(Request::GetScreenInfo),
+
+        OpCode::SetOrientation => {
+            let orientation = cur.read_string()?;
+            Ok(Request::SetOrientation { orientation })
+        }
+
+        OpCode::Drag => {
+            let start_x = cur.read_i32()?;
+            let start_y = cur.read_i32()?;
+            let end_x = cur.read_i32()?;
+            let end_y = cur.read_i32()?;
+            let hold_duration = cur.read_f64()?;
+            let move_duration = cur.read_f64()?;
+            Ok(Request::Drag {
+                start_x,
+                start_y,
+                end_x,
+                end_y,
+                hold_duration,
+                move_duration,
+            })
+        }
+
+        OpCode::MultiTap => {
+            let finger_count = cur.read_u8()?;
+            let x = cur.read_i32()?;
+            let y = cur.read_i32()?;
+            Ok(Request::MultiTap { finger_count, x, y })
+        }
+
+        OpCode::Paste => Ok(Request::Paste),
+
+        OpCode::ClearText => {
+            let selector = cur.read_string()?;
+            let by_label = cur.read_bool()?;
+            Ok(Request::ClearText { selector, by_label })
+        }
+
+        OpCode::FindAll => {
+            let selector = cur.read_string()?;
+            let by_label = cur.read_bool()?;
+            let element_type = cur.read_optional_string()?;
+            Ok(Request::FindAll {
+                selector,
+                by_label,
+                element_type,
+            })
+        }
+
+        OpCode::Batch => {
+            let count = cur.read_u32()?;
+            let mut requests = Vec::new();
+            for _ in 0..count {
+                let frame = cur.read_bytes()?;
+                let request = decode_request(&frame)?;
+                if matches!(request, Request::Batch { .. }) {
+                    return Err(ProtocolError::InvalidPayload(
+                        "nested batch requests are not supported".to_string(),
+                    ));
+                }
+                requests.push(request);
+            }
+            Ok(Request::Batch { requests })
+        }
+
+        OpCode::StreamScreenshots => {
+            let fps = cur.read_u8()?;
+            let quality = cur.read_u8()?;
+            Ok(Request::StreamScreenshots { fps, quality })
+        }
+
+        OpCode::StopStream => Ok(Request::StopStream),
+
+        OpCode::SendKey => {
+            let key = SpecialKey::from_u8(cur.read_u8()?)?;
+            Ok(Request::SendKey { key })
+        }
+
+        OpCode::LongPressElement => {
+            let selector = cur.read_string()?;
+            let by_label = cur.read_bool()?;
+            let duration = cur.read_f64()?;
+            Ok(Request::LongPressElement {
+                selector,
+                by_label,
+                duration,
+            })
+        }
+
         OpCode::Error | OpCode::Response => Err(ProtocolError::InvalidPayload(format!(
             "opcode 0x{:02X} is not a valid request opcode",
             opcode as u8
@@ -758,6 +1277,33 @@ pub fn encode_response(resp: &Response) -> Vec<u8> {
             payload.push(ResponseType::TargetInfo as u8);
             write_string(&mut payload, json);
         }
+        Response::State {
+            target_bundle,
+            foreground_app,
+        } => {
+            payload.push(ResponseType::State as u8);
+            write_optional_string(&mut payload, target_bundle);
+            write_optional_string(&mut payload, foreground_app);
+        }
+        Response::ScreenInfo {
+            width,
+            height,
+            scale,
+            orientation,
+        } => {
+            payload.push(ResponseType::ScreenInfo as u8);
+            payload.extend_from_slice(&width.to_le_bytes());
+            payload.extend_from_slice(&height.to_le_bytes());
+            payload.extend_from_slice(&scale.to_le_bytes());
+            write_string(&mut payload, orientation);
+        }
+        Response::Batch { responses } => {
+            payload.push(ResponseType::Batch as u8);
+            payload.extend_from_slice(&(responses.len() as u32).to_le_bytes());
+            for response in responses {
+                payload.extend_from_slice(&encode_response(response));
+            }
+        }
     }
 
     encode_frame(&payload)
@@ -805,6 +1351,35 @@ pub fn decode_response(data: &[u8]) -> Result<Response, ProtocolError> {
                     let json = cur.read_string()?;
                     Ok(Response::TargetInfo { json })
                 }
+                ResponseType::State => {
+                    let target_bundle = cur.read_optional_string()?;
+                    let foreground_app = cur.read_optional_string()?;
+                    Ok(Response::State {
+                        target_bundle,
+                        foreground_app,
+                    })
+                }
+                ResponseType::ScreenInfo => {
+                    let width = cur.read_f64()?;
+                    let height = cur.read_f64()?;
+                    let scale = cur.read_f64()?;
+                    let orientation = cur.read_string()?;
+                    Ok(Response::ScreenInfo {
+                        width,
+                        height,
+                        scale,
+                        orientation,
+                    })
+                }
+                ResponseType::Batch => {
+                    let count = cur.read_u32()?;
+                    let mut responses = Vec::new();
+                    for _ in 0..count {
+                        let frame = cur.read_bytes()?;
+                        responses.push(decode_response(&frame)?);
+                    }
+                    Ok(Response::Batch { responses })
+                }
             }
         }
 
@@ -992,7 +1567,36 @@ mod tests {
 
     #[test]
     fn request_screenshot() {
-        round_trip_request(&Request::Screenshot);
+        round_trip_request(&Request::Screenshot {
+            format: ScreenshotFormat::Png,
+        });
+        round_trip_request(&Request::Screenshot {
+            format: ScreenshotFormat::Jpeg { quality: 70 },
+        });
+    }
+
+    #[test]
+    fn screenshot_format_wire_format() {
+        // PNG keeps the bare opcode older agents expect
+        let wire = encode_request(&Request::Screenshot {
+            format: ScreenshotFormat::Png,
+        });
+        assert_eq!(wire, vec![1, 0, 0, 0, 0x11]);
+        let wire = encode_request(&Request::Screenshot {
+            format: ScreenshotFormat::Jpeg { quality: 60 },
+        });
+        assert_eq!(wire, vec![3, 0, 0, 0, 0x11, 0x01, 60]);
+        // An explicit PNG tag is accepted too
+        assert_eq!(
+            decode_request(&[0x11, 0x00]).unwrap(),
+            Request::Screenshot {
+                format: ScreenshotFormat::Png
+            }
+        );
+        assert!(matches!(
+            decode_request(&[0x11, 0x07]),
+            Err(ProtocolError::InvalidPayload(_))
+        ));
     }
 
     #[test]
@@ -1112,6 +1716,344 @@ mod tests {
         assert_eq!(wire, vec![1, 0, 0, 0, 0x16]);
     }
 
+    #[test]
+    fn request_get_state() {
+        round_trip_request(&Request::GetState);
+    }
+
+    #[test]
+    fn get_state_wire_format() {
+        let wire = encode_request(&Request::GetState);
+        // 4-byte header with length=1, then opcode 0x18
+        assert_eq!(wire, vec![1, 0, 0, 0, 0x18]);
+    }
+
+    #[test]
+    fn request_get_screen_info() {
+        round_trip_request(&Request::GetScreenInfo);
+    }
+
+    #[test]
+    fn get_screen_info_wire_format() {
+        let wire = encode_request(&Request::GetScreenInfo);
+        // 4-byte header with length=1, then opcode 0x19
+        assert_eq!(wire, vec![1, 0, 0, 0, 0x19]);
+    }
+
+    #[test]
+    fn request_set_orientation() {
+        round_trip_request(&Request::SetOrientation {
+            orientation: "landscape_left".into(),
+        });
+    }
+
+    #[test]
+    fn set_orientation_wire_format() {
+        let wire = encode_request(&Request::SetOrientation {
+            orientation: "ab".into(),
+        });
+        // length: 1 (opcode) + 4 (len) + 2 ("ab") = 7
+        assert_eq!(wire, vec![7, 0, 0, 0, 0x1A, 2, 0, 0, 0, b'a', b'b']);
+    }
+
+    #[test]
+    fn request_drag() {
+        round_trip_request(&Request::Drag {
+            start_x: 100,
+            start_y: 200,
+            end_x: 100,
+            end_y: 500,
+            hold_duration: 0.5,
+            move_duration: 1.25,
+        });
+    }
+
+    #[test]
+    fn drag_wire_format() {
+        let wire = encode_request(&Request::Drag {
+            start_x: 1,
+            start_y: 2,
+            end_x: 3,
+            end_y: -4,
+            hold_duration: 0.5,
+            move_duration: 0.0,
+        });
+        // length: 1 (opcode) + 4 * 4 (i32) + 2 * 8 (f64) = 33
+        assert_eq!(&wire[..4], &33u32.to_le_bytes());
+        assert_eq!(wire[4], 0x1B);
+        assert_eq!(&wire[5..9], &1i32.to_le_bytes());
+        assert_eq!(&wire[17..21], &(-4i32).to_le_bytes());
+        assert_eq!(&wire[21..29], &0.5f64.to_le_bytes());
+        assert_eq!(&wire[29..], &0.0f64.to_le_bytes());
+    }
+
+    #[test]
+    fn decode_drag_truncated() {
+        let wire = encode_request(&Request::Drag {
+            start_x: 1,
+            start_y: 2,
+            end_x: 3,
+            end_y: 4,
+            hold_duration: 0.5,
+            move_duration: 0.5,
+        });
+        assert_eq!(
+            decode_request(&wire[4..wire.len() - 1]),
+            Err(ProtocolError::InsufficientData)
+        );
+    }
+
+    #[test]
+    fn request_multi_tap() {
+        for finger_count in 1..=5 {
+            round_trip_request(&Request::MultiTap {
+                finger_count,
+                x: 195,
+                y: 422,
+            });
+        }
+    }
+
+    #[test]
+    fn multi_tap_wire_format() {
+        let wire = encode_request(&Request::MultiTap {
+            finger_count: 2,
+            x: 100,
+            y: -1,
+        });
+        // length: 1 (opcode) + 1 (u8) + 2 * 4 (i32) = 10
+        assert_eq!(&wire[..4], &10u32.to_le_bytes());
+        assert_eq!(&wire[4..6], &[0x1C, 2]);
+        assert_eq!(&wire[6..10], &100i32.to_le_bytes());
+        assert_eq!(&wire[10..], &(-1i32).to_le_bytes());
+    }
+
+    #[test]
+    fn request_paste() {
+        round_trip_request(&Request::Paste);
+    }
+
+    #[test]
+    fn paste_wire_format() {
+        let wire = encode_request(&Request::Paste);
+        // 4-byte header with length=1, then opcode 0x1D
+        assert_eq!(wire, vec![1, 0, 0, 0, 0x1D]);
+    }
+
+    #[test]
+    fn request_clear_text() {
+        round_trip_request(&Request::ClearText {
+            selector: "email-field".into(),
+            by_label: false,
+        });
+        round_trip_request(&Request::ClearText {
+            selector: "E-mail ✉️".into(),
+            by_label: true,
+        });
+    }
+
+    #[test]
+    fn request_find_all() {
+        round_trip_request(&Request::FindAll {
+            selector: "row-*".into(),
+            by_label: false,
+            element_type: Some("Cell".into()),
+        });
+        round_trip_request(&Request::FindAll {
+            selector: String::new(),
+            by_label: true,
+            element_type: None,
+        });
+    }
+
+    #[test]
+    fn request_batch() {
+        round_trip_request(&Request::Batch {
+            requests: vec![
+                Request::TapCoord { x: 10, y: 20 },
+                Request::TypeText {
+                    text: "héllo".into(),
+                },
+                Request::Swipe {
+                    start_x: 0,
+                    start_y: 100,
+                    end_x: 0,
+                    end_y: 10,
+                    duration: Some(0.3),
+                },
+            ],
+        });
+        round_trip_request(&Request::Batch { requests: vec![] });
+    }
+
+    #[test]
+    fn batch_wire_format() {
+        let wire = encode_request(&Request::Batch {
+            requests: vec![Request::Heartbeat, Request::Paste],
+        });
+        // length: 1 (opcode) + 4 (count) + 2 * 5 (nested frames) = 15
+        assert_eq!(&wire[..4], &15u32.to_le_bytes());
+        assert_eq!(wire[4], 0x20);
+        assert_eq!(&wire[5..9], &2u32.to_le_bytes());
+        assert_eq!(&wire[9..14], &[1, 0, 0, 0, 0x01]);
+        assert_eq!(&wire[14..], &[1, 0, 0, 0, 0x1D]);
+    }
+
+    #[test]
+    fn decode_batch_rejects_nesting() {
+        let wire = encode_request(&Request::Batch {
+            requests: vec![Request::Batch { requests: vec![] }],
+        });
+        assert!(matches!(
+            decode_request(&wire[4..]),
+            Err(ProtocolError::InvalidPayload(_))
+        ));
+    }
+
+    #[test]
+    fn decode_batch_truncated() {
+        let wire = encode_request(&Request::Batch {
+            requests: vec![Request::TapCoord { x: 1, y: 2 }],
+        });
+        assert_eq!(
+            decode_request(&wire[4..wire.len() - 1]),
+            Err(ProtocolError::InsufficientData)
+        );
+    }
+
+    #[test]
+    fn request_stream_screenshots() {
+        round_trip_request(&Request::StreamScreenshots {
+            fps: 10,
+            quality: 70,
+        });
+        round_trip_request(&Request::StopStream);
+    }
+
+    #[test]
+    fn stream_screenshots_wire_format() {
+        let wire = encode_request(&Request::StreamScreenshots {
+            fps: 15,
+            quality: 80,
+        });
+        assert_eq!(wire, vec![3, 0, 0, 0, 0x21, 15, 80]);
+        let wire = encode_request(&Request::StopStream);
+        assert_eq!(wire, vec![1, 0, 0, 0, 0x22]);
+    }
+
+    #[test]
+    fn request_send_key() {
+        for key in SpecialKey::ALL {
+            round_trip_request(&Request::SendKey { key });
+        }
+    }
+
+    #[test]
+    fn send_key_wire_format() {
+        let wire = encode_request(&Request::SendKey {
+            key: SpecialKey::Tab,
+        });
+        assert_eq!(wire, vec![2, 0, 0, 0, 0x23, 0x02]);
+    }
+
+    #[test]
+    fn request_long_press_element() {
+        round_trip_request(&Request::LongPressElement {
+            selector: "message-bubble".into(),
+            by_label: false,
+            duration: 1.0,
+        });
+        round_trip_request(&Request::LongPressElement {
+            selector: "Hello there".into(),
+            by_label: true,
+            duration: 0.25,
+        });
+    }
+
+    #[test]
+    fn long_press_element_wire_format() {
+        let wire = encode_request(&Request::LongPressElement {
+            selector: "ok".into(),
+            by_label: false,
+            duration: 1.0,
+        });
+        let mut expected = vec![16, 0, 0, 0, 0x25, 2, 0, 0, 0, b'o', b'k', 0];
+        expected.extend_from_slice(&1.0f64.to_le_bytes());
+        assert_eq!(wire, expected);
+    }
+
+    #[test]
+    fn decode_send_key_rejects_unknown_key() {
+        assert!(matches!(
+            decode_request(&[0x23, 0x7F]),
+            Err(ProtocolError::InvalidPayload(_))
+        ));
+    }
+
+    #[test]
+    fn special_key_round_trip() {
+        for key in SpecialKey::ALL {
+            assert_eq!(SpecialKey::from_u8(key as u8), Ok(key));
+            assert_eq!(key.as_str().parse::<SpecialKey>(), Ok(key));
+            let json = serde_json::to_string(&key).unwrap();
+            assert_eq!(json, format!("\"{}\"", key.as_str()));
+            assert_eq!(serde_json::from_str::<SpecialKey>(&json).unwrap(), key);
+        }
+        assert_eq!(" Return ".parse::<SpecialKey>(), Ok(SpecialKey::Return));
+        let err = "enter".parse::<SpecialKey>().unwrap_err();
+        assert!(err.contains("return, tab"), "{err}");
+    }
+
+    #[test]
+    fn response_batch() {
+        round_trip_response(&Response::Batch {
+            responses: vec![
+                Response::Ok,
+                Response::Value {
+                    value: Some("42".into()),
+                },
+                Response::Error {
+                    message: "element not found".into(),
+                },
+            ],
+        });
+        round_trip_response(&Response::Batch { responses: vec![] });
+    }
+
+    #[test]
+    fn request_dump_tree_filtered() {
+        round_trip_request(&Request::DumpTreeFiltered {
+            types: vec!["Button".into(), "TextField".into()],
+            visible_only: true,
+            max_depth: Some(3),
+        });
+    }
+
+    #[test]
+    fn request_dump_tree_filtered_defaults() {
+        round_trip_request(&Request::DumpTreeFiltered {
+            types: vec![],
+            visible_only: false,
+            max_depth: None,
+        });
+    }
+
+    #[test]
+    fn dump_tree_filtered_wire_format() {
+        let wire = encode_request(&Request::DumpTreeFiltered {
+            types: vec!["A".into()],
+            visible_only: true,
+            max_depth: Some(0),
+        });
+        // length: 1 (opcode) + 4 (count) + 5 ("A") + 1 (bool) + 5 (Some(u32)) = 16
+        assert_eq!(&wire[..4], &16u32.to_le_bytes());
+        assert_eq!(wire[4], OpCode::DumpTreeFiltered as u8);
+        assert_eq!(&wire[5..9], &1u32.to_le_bytes());
+        assert_eq!(&wire[9..14], &[1, 0, 0, 0, b'A']);
+        assert_eq!(wire[14], 1);
+        assert_eq!(&wire[15..], &[1, 0, 0, 0, 0]);
+    }
+
     #[test]
     fn response_target_info() {
         round_trip_response(&Response::TargetInfo {
@@ -1119,6 +2061,73 @@ mod tests {
         });
     }
 
+    #[test]
+    fn response_state() {
+        round_trip_response(&Response::State {
+            target_bundle: Some("com.example.app".into()),
+            foreground_app: Some("com.apple.springboard".into()),
+        });
+        round_trip_response(&Response::State {
+            target_bundle: None,
+            foreground_app: None,
+        });
+    }
+
+    #[test]
+    fn response_state_wire_format() {
+        let wire = encode_response(&Response::State {
+            target_bundle: Some("a".into()),
+            foreground_app: None,
+        });
+        // length: 1 (opcode) + 1 (type) + 6 (Some("a")) + 1 (None) = 9
+        assert_eq!(&wire[..4], &9u32.to_le_bytes());
+        assert_eq!(&wire[4..6], &[OpCode::Response as u8, 0x07]);
+        assert_eq!(&wire[6..12], &[1, 1, 0, 0, 0, b'a']);
+        assert_eq!(wire[12], 0);
+    }
+
+    #[test]
+    fn response_screen_info() {
+        round_trip_response(&Response::ScreenInfo {
+            width: 393.0,
+            height: 852.0,
+            scale: 3.0,
+            orientation: "portrait".into(),
+        });
+    }
+
+    #[test]
+    fn response_screen_info_wire_format() {
+        let wire = encode_response(&Response::ScreenInfo {
+            width: 390.0,
+            height: 844.0,
+            scale: 3.0,
+            orientation: "x".into(),
+        });
+        // length: 1 (opcode) + 1 (type) + 3 * 8 (f64) + 5 ("x") = 31
+        assert_eq!(&wire[..4], &31u32.to_le_bytes());
+        assert_eq!(&wire[4..6], &[OpCode::Response as u8, 0x08]);
+        assert_eq!(&wire[6..14], &390.0f64.to_le_bytes());
+        assert_eq!(&wire[14..22], &844.0f64.to_le_bytes());
+        assert_eq!(&wire[22..30], &3.0f64.to_le_bytes());
+        assert_eq!(&wire[30..], &[1, 0, 0, 0, b'x']);
+    }
+
+    #[test]
+    fn decode_screen_info_truncated() {
+        let mut wire = encode_response(&Response::ScreenInfo {
+            width: 390.0,
+            height: 844.0,
+            scale: 3.0,
+            orientation: "portrait".into(),
+        });
+        wire.truncate(20);
+        assert_eq!(
+            decode_response(&wire[4..]),
+            Err(ProtocolError::InsufficientData)
+        );
+    }
+
     // -- Error cases --------------------------------------------------------
 
     #[test]
@@ -1195,7 +2204,8 @@ mod tests {
     fn opcode_round_trip() {
         let codes: Vec<u8> = vec![
             0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x10, 0x11, 0x12, 0x13, 0x14,
-            0x15, 0x16, 0x99, 0xA0,
+            0x15, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x1B, 0x1C, 0x1D, 0x1E, 0x1F, 0x20, 0x21, 0x22,
+            0x23, 0x25, 0x99, 0xA0,
         ];
         for &code in &codes {
             let op = OpCode::from_u8(code).unwrap();
@@ -0,0 +1,235 @@
+//! Typed payloads for the `data` field of action results.
+//!
+//! Actions that report structured results serialize one of these types into
+//! [`ExecutionResult::data`](crate::executor::ExecutionResult::data), which
+//! the server forwards unchanged as the `data` of an `ActionResult`. Read
+//! them back with [`ResultData::from_data`] or [`elapsed_ms`] rather than
+//! picking fields out of the JSON by hand.
+//!
+//! Every payload carries `schema_version`, set to [`SCHEMA_VERSION`] when it
+//! is built. Payloads written before versioning (for example in a reloaded
+//! action log) have none and read back as version `0`.
+//!
+//! # Example
+//!
+//! ```
+//! use qorvex_core::result_data::{self, ResultData, WaitData};
+//!
+//! let data = WaitData::new(120).to_data();
+//! assert_eq!(WaitData::from_data(&data).unwrap().elapsed_ms, 120);
+//! assert_eq!(result_data::elapsed_ms(&data), Some(120));
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::element::ElementFrame;
+
+/// Current version of the payloads in this module.
+///
+/// Bump it when a payload changes in a way older readers would misread.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A typed `data` payload.
+pub trait ResultData: Serialize + DeserializeOwned {
+    /// Serializes the payload for [`ExecutionResult::with_data`](crate::executor::ExecutionResult::with_data).
+    fn to_data(&self) -> String {
+        // Plain structs of numbers and booleans always serialize
+        serde_json::to_string(self).expect("result data serializes to JSON")
+    }
+
+    /// Parses a `data` string, returning `None` if it is not this payload.
+    fn from_data(data: &str) -> Option<Self> {
+        serde_json::from_str(data).ok()
+    }
+}
+
+/// Data of actions that wait or retry: `Tap`, `Wait`, `WaitFor`,
+/// `WaitForNot` and `WaitForAll`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaitData {
+    /// Payload version (see [`SCHEMA_VERSION`]).
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Time the action took, in milliseconds.
+    pub elapsed_ms: u64,
+    /// Frame of the element a `WaitFor` found, if it reported one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame: Option<ElementFrame>,
+}
+
+impl WaitData {
+    /// Creates a payload without a frame.
+    pub fn new(elapsed_ms: u64) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            elapsed_ms,
+            frame: None,
+        }
+    }
+
+    /// Adds the frame of the element that was found.
+    pub fn with_frame(mut self, frame: Option<ElementFrame>) -> Self {
+        self.frame = frame;
+        self
+    }
+}
+
+impl ResultData for WaitData {}
+
+/// Data of `WaitForAny`: which of its selectors was found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaitForAnyData {
+    /// Payload version (see [`SCHEMA_VERSION`]).
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Time the action took, in milliseconds.
+    pub elapsed_ms: u64,
+    /// Position of the matched selector in the action's list.
+    pub index: usize,
+    /// The matched selector value.
+    pub selector: String,
+}
+
+impl WaitForAnyData {
+    /// Creates a payload.
+    pub fn new(elapsed_ms: u64, index: usize, selector: impl Into<String>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            elapsed_ms,
+            index,
+            selector: selector.into(),
+        }
+    }
+}
+
+impl ResultData for WaitForAnyData {}
+
+/// Data of `WaitForIdle`, on success and on timeout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdleData {
+    /// Payload version (see [`SCHEMA_VERSION`]).
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Time spent polling, in milliseconds.
+    pub elapsed_ms: u64,
+    /// How many times the element tree changed while polling.
+    pub changes: u32,
+}
+
+impl IdleData {
+    /// Creates a payload.
+    pub fn new(elapsed_ms: u64, changes: u32) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            elapsed_ms,
+            changes,
+        }
+    }
+}
+
+impl ResultData for IdleData {}
+
+/// Data of `TapIfExists`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TapIfExistsData {
+    /// Payload version (see [`SCHEMA_VERSION`]).
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Whether the element was on screen and tapped.
+    pub tapped: bool,
+}
+
+impl TapIfExistsData {
+    /// Creates a payload.
+    pub fn new(tapped: bool) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            tapped,
+        }
+    }
+}
+
+impl ResultData for TapIfExistsData {}
+
+/// Reads the duration from any payload that carries `elapsed_ms`.
+pub fn elapsed_ms(data: &str) -> Option<u64> {
+    #[derive(Deserialize)]
+    struct Elapsed {
+        elapsed_ms: u64,
+    }
+    serde_json::from_str::<Elapsed>(data)
+        .ok()
+        .map(|e| e.elapsed_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wait_data_round_trips_with_version() {
+        let data = WaitData::new(42).to_data();
+        assert_eq!(data, r#"{"schema_version":1,"elapsed_ms":42}"#);
+        let parsed = WaitData::from_data(&data).unwrap();
+        assert_eq!(parsed.schema_version, SCHEMA_VERSION);
+        assert_eq!(parsed.elapsed_ms, 42);
+        assert!(parsed.frame.is_none());
+    }
+
+    #[test]
+    fn wait_data_carries_frame() {
+        let frame = ElementFrame {
+            x: 10.0,
+            y: 20.0,
+            width: 100.0,
+            height: 44.0,
+        };
+        let data = WaitData::new(5).with_frame(Some(frame)).to_data();
+        let value: serde_json::Value = serde_json::from_str(&data).unwrap();
+        assert_eq!(value["frame"]["width"], 100.0);
+        assert_eq!(WaitData::from_data(&data).unwrap().frame.unwrap().y, 20.0);
+    }
+
+    #[test]
+    fn idle_and_tap_if_exists_round_trip() {
+        let idle = IdleData::from_data(&IdleData::new(900, 2).to_data()).unwrap();
+        assert_eq!((idle.elapsed_ms, idle.changes), (900, 2));
+        let tap = TapIfExistsData::from_data(&TapIfExistsData::new(true).to_data()).unwrap();
+        assert!(tap.tapped);
+        assert_eq!(tap.schema_version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn wait_for_any_data_reports_match() {
+        let data = WaitForAnyData::new(30, 1, "error-banner").to_data();
+        let parsed = WaitForAnyData::from_data(&data).unwrap();
+        assert_eq!(
+            (parsed.index, parsed.selector.as_str()),
+            (1, "error-banner")
+        );
+        assert_eq!(elapsed_ms(&data), Some(30));
+    }
+
+    #[test]
+    fn unversioned_payloads_read_as_version_zero() {
+        let idle = IdleData::from_data(r#"{"elapsed_ms":900,"changes":2}"#).unwrap();
+        assert_eq!(idle.schema_version, 0);
+        assert_eq!(idle.changes, 2);
+    }
+
+    #[test]
+    fn elapsed_ms_reads_any_timed_payload() {
+        assert_eq!(elapsed_ms(&IdleData::new(7, 0).to_data()), Some(7));
+        assert_eq!(elapsed_ms(r#"{"elapsed_ms":12}"#), Some(12));
+        assert_eq!(elapsed_ms(&TapIfExistsData::new(false).to_data()), None);
+        assert_eq!(elapsed_ms("not json"), None);
+        assert_eq!(elapsed_ms(r#"[{"elapsed_ms":1}]"#), None);
+    }
+
+    #[test]
+    fn from_data_rejects_other_payloads() {
+        assert!(TapIfExistsData::from_data(&WaitData::new(1).to_data()).is_none());
+        assert!(IdleData::from_data(r#""text""#).is_none());
+    }
+}
@@ -0,0 +1,108 @@
+//! Screen Recording permission checks for the live video feed.
+//!
+//! `qorvex-streamer` captures the Simulator window with ScreenCaptureKit,
+//! which macOS only allows once the app that launched it (usually the
+//! terminal) has Screen Recording permission. Without it the streamer exits
+//! with [`PERMISSION_DENIED_EXIT_CODE`]. [`preflight`] asks the system up
+//! front so callers can show [`PERMISSION_GUIDANCE`] instead of spawning a
+//! streamer that fails.
+//!
+//! The query never prompts the user. On platforms without the permission it
+//! reports [`ScreenRecordingAccess::Unknown`], which [`preflight`] lets
+//! through so the capture itself decides.
+
+use thiserror::Error;
+
+/// Exit code of `qorvex-streamer` when Screen Recording permission is denied.
+pub const PERMISSION_DENIED_EXIT_CODE: i32 = 2;
+
+/// How to grant the permission, for error messages and `qorvex doctor`.
+pub const PERMISSION_GUIDANCE: &str = "Screen Recording permission required. Grant it to your \
+     terminal in System Settings > Privacy & Security > Screen & System Audio Recording, then \
+     restart the terminal";
+
+/// Whether this process may capture the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenRecordingAccess {
+    /// Permission is granted.
+    Granted,
+    /// Permission has not been granted.
+    Denied,
+    /// The system could not be asked, e.g. when not running on macOS.
+    Unknown,
+}
+
+/// Screen Recording permission is missing.
+#[derive(Debug, Error)]
+#[error("{}", PERMISSION_GUIDANCE)]
+pub struct PermissionDenied;
+
+/// Asks the system whether this process has Screen Recording permission.
+pub fn check() -> ScreenRecordingAccess {
+    check_with(system_query)
+}
+
+/// Like [`check`], with `query` in place of the system call. `query` returns
+/// `None` when it cannot tell.
+pub fn check_with(query: impl FnOnce() -> Option<bool>) -> ScreenRecordingAccess {
+    match query() {
+        Some(true) => ScreenRecordingAccess::Granted,
+        Some(false) => ScreenRecordingAccess::Denied,
+        None => ScreenRecordingAccess::Unknown,
+    }
+}
+
+/// Fails if Screen Recording permission is known to be denied.
+///
+/// # Errors
+///
+/// [`PermissionDenied`], whose message is [`PERMISSION_GUIDANCE`].
+pub fn preflight() -> Result<(), PermissionDenied> {
+    preflight_with(system_query)
+}
+
+/// Like [`preflight`], with `query` in place of the system call.
+pub fn preflight_with(query: impl FnOnce() -> Option<bool>) -> Result<(), PermissionDenied> {
+    match check_with(query) {
+        ScreenRecordingAccess::Denied => Err(PermissionDenied),
+        ScreenRecordingAccess::Granted | ScreenRecordingAccess::Unknown => Ok(()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn system_query() -> Option<bool> {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGPreflightScreenCaptureAccess() -> bool;
+    }
+    // SAFETY: takes no arguments and only reads the permission database;
+    // unlike CGRequestScreenCaptureAccess it never shows a prompt.
+    Some(unsafe { CGPreflightScreenCaptureAccess() })
+}
+
+#[cfg(not(target_os = "macos"))]
+fn system_query() -> Option<bool> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_maps_the_query_result() {
+        assert_eq!(check_with(|| Some(true)), ScreenRecordingAccess::Granted);
+        assert_eq!(check_with(|| Some(false)), ScreenRecordingAccess::Denied);
+        assert_eq!(check_with(|| None), ScreenRecordingAccess::Unknown);
+    }
+
+    #[test]
+    fn preflight_fails_only_when_denied() {
+        assert!(preflight_with(|| Some(true)).is_ok());
+        assert!(preflight_with(|| None).is_ok(), "unknown defers to capture");
+
+        let err = preflight_with(|| Some(false)).unwrap_err();
+        assert_eq!(err.to_string(), PERMISSION_GUIDANCE);
+        assert!(err.to_string().contains("System Settings"));
+    }
+}
@@ -0,0 +1,187 @@
+//! Post-processing of PNG screenshots.
+//!
+//! Screenshots are captured in device pixels, while element frames are in
+//! points. The helpers here convert between the two and crop a screenshot
+//! down to a single element. Cropping needs PNG; screenshots requested as
+//! JPEG (see [`ScreenshotFormat`](crate::protocol::ScreenshotFormat)) are
+//! only stored and displayed.
+
+use image::ImageFormat;
+use thiserror::Error;
+
+use crate::element::{ElementFrame, UIElement};
+
+/// Errors from screenshot post-processing.
+#[derive(Error, Debug)]
+pub enum ScreenshotError {
+    /// The screenshot bytes could not be decoded or re-encoded as PNG.
+    #[error("Invalid screenshot image: {0}")]
+    Image(#[from] image::ImageError),
+
+    /// The crop rectangle lies entirely outside the screenshot.
+    #[error("Element frame is entirely off-screen")]
+    OffScreen,
+}
+
+/// Returns the pixel dimensions of a PNG screenshot.
+pub fn png_dimensions(png: &[u8]) -> Result<(u32, u32), ScreenshotError> {
+    let image = image::load_from_memory_with_format(png, ImageFormat::Png)?;
+    Ok((image.width(), image.height()))
+}
+
+/// File extension for screenshot bytes: `"jpg"` for JPEG, otherwise `"png"`.
+///
+/// Agents may answer a JPEG request with PNG, so the bytes decide.
+pub fn image_extension(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        "jpg"
+    } else {
+        "png"
+    }
+}
+
+/// Estimates the screen scale factor (pixels per point) of a screenshot.
+///
+/// Compares the screenshot width with the widest top-level frame in `tree`,
+/// which is the application window and spans the screen. Rounded to a whole
+/// number, as iOS scale factors are; falls back to `1.0` without a usable frame.
+pub fn infer_scale(image_width: u32, tree: &[UIElement]) -> f64 {
+    let points = tree
+        .iter()
+        .filter_map(|e| e.frame.as_ref())
+        .map(|f| f.width)
+        .fold(0.0_f64, f64::max);
+    if points <= 0.0 {
+        return 1.0;
+    }
+    (f64::from(image_width) / points).round().max(1.0)
+}
+
+/// Crops a PNG screenshot to `frame`, given in points, and returns a new PNG.
+///
+/// `scale` converts points to pixels. The crop rectangle is rounded outward to
+/// whole pixels and clamped to the image, so elements partially off-screen
+/// yield just their visible part.
+///
+/// # Errors
+///
+/// - [`ScreenshotError::Image`] if `png` is not a valid PNG
+/// - [`ScreenshotError::OffScreen`] if no part of the frame is on the image
+pub fn crop_to_frame(
+    png: &[u8],
+    frame: &ElementFrame,
+    scale: f64,
+) -> Result<Vec<u8>, ScreenshotError> {
+    let image = image::load_from_memory_with_format(png, ImageFormat::Png)?;
+    let (width, height) = (f64::from(image.width()), f64::from(image.height()));
+
+    let left = (frame.x * scale).floor().clamp(0.0, width);
+    let top = (frame.y * scale).floor().clamp(0.0, height);
+    let right = ((frame.x + frame.width) * scale).ceil().clamp(0.0, width);
+    let bottom = ((frame.y + frame.height) * scale).ceil().clamp(0.0, height);
+    if right <= left || bottom <= top {
+        return Err(ScreenshotError::OffScreen);
+    }
+
+    let cropped = image.crop_imm(
+        left as u32,
+        top as u32,
+        (right - left) as u32,
+        (bottom - top) as u32,
+    );
+    let mut out = std::io::Cursor::new(Vec::new());
+    cropped.write_to(&mut out, ImageFormat::Png)?;
+    Ok(out.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = image::RgbaImage::from_fn(width, height, |x, y| {
+            image::Rgba([(x % 256) as u8, (y % 256) as u8, 0, 255])
+        });
+        let mut out = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut out, ImageFormat::Png).unwrap();
+        out.into_inner()
+    }
+
+    fn frame(x: f64, y: f64, width: f64, height: f64) -> ElementFrame {
+        ElementFrame {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn window(width: f64) -> UIElement {
+        UIElement {
+            identifier: None,
+            label: None,
+            value: None,
+            element_type: Some("Application".into()),
+            frame: Some(frame(0.0, 0.0, width, 844.0)),
+            children: vec![],
+            role: None,
+            hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
+        }
+    }
+
+    #[test]
+    fn crop_scales_points_to_pixels() {
+        let screenshot = png(300, 600);
+        let cropped = crop_to_frame(&screenshot, &frame(10.0, 20.0, 40.0, 15.0), 3.0).unwrap();
+        assert_eq!(png_dimensions(&cropped).unwrap(), (120, 45));
+
+        // The top-left pixel comes from (30, 60) in the source
+        let image = image::load_from_memory(&cropped).unwrap().to_rgba8();
+        assert_eq!(image.get_pixel(0, 0).0, [30, 60, 0, 255]);
+    }
+
+    #[test]
+    fn crop_rounds_fractional_frames_outward() {
+        let screenshot = png(100, 100);
+        let cropped = crop_to_frame(&screenshot, &frame(10.5, 10.5, 5.0, 5.0), 2.0).unwrap();
+        assert_eq!(png_dimensions(&cropped).unwrap(), (10, 10));
+    }
+
+    #[test]
+    fn crop_clamps_partially_off_screen_frames() {
+        let screenshot = png(200, 100);
+        let cropped = crop_to_frame(&screenshot, &frame(-20.0, 80.0, 60.0, 50.0), 1.0).unwrap();
+        assert_eq!(png_dimensions(&cropped).unwrap(), (40, 20));
+    }
+
+    #[test]
+    fn crop_rejects_frames_entirely_off_screen() {
+        let screenshot = png(200, 100);
+        let result = crop_to_frame(&screenshot, &frame(250.0, 10.0, 20.0, 20.0), 1.0);
+        assert!(matches!(result, Err(ScreenshotError::OffScreen)));
+    }
+
+    #[test]
+    fn crop_rejects_invalid_png() {
+        let result = crop_to_frame(b"not a png", &frame(0.0, 0.0, 1.0, 1.0), 1.0);
+        assert!(matches!(result, Err(ScreenshotError::Image(_))));
+    }
+
+    #[test]
+    fn image_extension_follows_magic_bytes() {
+        assert_eq!(image_extension(&png(1, 1)), "png");
+        assert_eq!(image_extension(&[0xFF, 0xD8, 0xFF, 0xE0]), "jpg");
+        assert_eq!(image_extension(b""), "png");
+    }
+
+    #[test]
+    fn infer_scale_from_window_width() {
+        assert_eq!(infer_scale(1179, &[window(393.0)]), 3.0);
+        assert_eq!(infer_scale(750, &[window(375.0)]), 2.0);
+        assert_eq!(infer_scale(750, &[]), 1.0);
+    }
+}
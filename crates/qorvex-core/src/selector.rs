@@ -0,0 +1,444 @@
+//! Matching element selectors against element trees.
+//!
+//! A [`Selector`] is what an action uses to pick an element: text matched
+//! against the accessibility identifier or label, an optional element type,
+//! and how the text is to be read ([`MatchMode`]). [`resolve`] finds the
+//! elements it selects in a tree ([`resolve_all`] for find-all), and
+//! [`Selector::suggest`] finds a close
+//! match when nothing matched. The client-side driver search, the executor's
+//! glob resolution and its "did you mean" fallback all go through here, so
+//! they agree on what a selector means.
+//!
+//! Matching rules:
+//!
+//! - `*` and `?` in the text are glob wildcards; text without them must
+//!   equal the identifier (or label) exactly.
+//! - A trailing `[N]` picks the Nth match (0-based, depth-first order).
+//! - Empty text matches nothing, so `tap ""` cannot land on the first
+//!   element of the tree. Only [`resolve_all`] reads empty text as "any
+//!   element", so find-all can select by type alone.
+//! - The element type, when set, must equal the element's type exactly.
+//!
+//! # Example
+//!
+//! ```
+//! use qorvex_core::element::UIElement;
+//! use qorvex_core::selector::{resolve, Selector};
+//!
+//! let elements: Vec<UIElement> = serde_json::from_str(r#"[
+//!     {"type": "Cell", "AXUniqueId": "row-1"},
+//!     {"type": "Cell", "AXUniqueId": "row-2"},
+//!     {"type": "Button", "AXUniqueId": "row-add"}
+//! ]"#).unwrap();
+//!
+//! let rows = Selector::new("row-*", false).with_element_type(Some("Cell".to_string()));
+//! assert_eq!(resolve(&elements, &rows).len(), 2);
+//!
+//! let second = Selector::new("row-*[1]", false);
+//! assert_eq!(resolve(&elements, &second)[0].identifier.as_deref(), Some("row-2"));
+//! ```
+
+use crate::action::{ActionType, MatchMode};
+use crate::element::UIElement;
+use crate::fuzzy;
+
+/// What an action selects elements by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    /// Identifier or label to match; may hold wildcards and a `[N]` index.
+    pub text: String,
+    /// Match `text` against the label instead of the identifier.
+    pub by_label: bool,
+    /// Only match elements of this type.
+    pub element_type: Option<String>,
+    /// How the caller asked for `text` to be read.
+    ///
+    /// Both modes match the same elements here. [`MatchMode::Glob`] callers
+    /// additionally expect exactly one match, which the executor enforces
+    /// before acting.
+    pub match_mode: MatchMode,
+}
+
+impl Selector {
+    /// Creates a selector matching `text` by identifier, or by label when
+    /// `by_label` is set.
+    pub fn new(text: impl Into<String>, by_label: bool) -> Self {
+        Self {
+            text: text.into(),
+            by_label,
+            element_type: None,
+            match_mode: MatchMode::Exact,
+        }
+    }
+
+    /// Restricts the selector to one element type.
+    pub fn with_element_type(mut self, element_type: Option<String>) -> Self {
+        self.element_type = element_type;
+        self
+    }
+
+    /// Sets how `text` is read.
+    pub fn with_match_mode(mut self, match_mode: MatchMode) -> Self {
+        self.match_mode = match_mode;
+        self
+    }
+
+    /// Returns the selector of an action that acts on a single element, or
+    /// `None` for other actions.
+    pub fn from_action(action: &ActionType) -> Option<Self> {
+        match action {
+            ActionType::Tap {
+                selector,
+                by_label,
+                element_type,
+                match_mode,
+                ..
+            }
+            | ActionType::WaitFor {
+                selector,
+                by_label,
+                element_type,
+                match_mode,
+                ..
+            }
+            | ActionType::GetValue {
+                selector,
+                by_label,
+                element_type,
+                match_mode,
+                ..
+            } => Some(
+                Self::new(selector.clone(), *by_label)
+                    .with_element_type(element_type.clone())
+                    .with_match_mode(*match_mode),
+            ),
+            ActionType::GetElementScreenshot { selector, by_label }
+            | ActionType::ClearText { selector, by_label }
+            | ActionType::LongPressElement {
+                selector, by_label, ..
+            } => Some(Self::new(selector.clone(), *by_label)),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the text holds glob wildcards.
+    pub fn is_pattern(&self) -> bool {
+        has_wildcard(&self.text)
+    }
+
+    /// The attribute this selector reads from `element`: its label or its
+    /// identifier.
+    pub fn key<'a>(&self, element: &'a UIElement) -> Option<&'a str> {
+        if self.by_label {
+            element.label.as_deref()
+        } else {
+            element.identifier.as_deref()
+        }
+    }
+
+    /// Returns true if `element` itself matches, ignoring any `[N]` index
+    /// (which picks among matches rather than describing one).
+    pub fn matches(&self, element: &UIElement) -> bool {
+        let (base, _) = parse_selector_index(&self.text);
+        self.matches_base(base, false, element)
+    }
+
+    /// Matches `element` against the text without its index. Empty text
+    /// matches only when `any_text` is set.
+    fn matches_base(&self, base: &str, any_text: bool, element: &UIElement) -> bool {
+        let text_matches = if base.is_empty() {
+            any_text
+        } else {
+            self.key(element).is_some_and(|k| glob_match(base, k))
+        };
+        let type_matches = self
+            .element_type
+            .as_deref()
+            .is_none_or(|typ| element.element_type.as_deref() == Some(typ));
+        text_matches && type_matches
+    }
+
+    /// Returns the identifier (or label) in the tree most similar to the
+    /// text, for when the selector matched nothing.
+    ///
+    /// The type filter is not applied, so a suggestion can point at an
+    /// element of another type. See [`fuzzy::suggest`] for the threshold.
+    pub fn suggest<'a>(&self, elements: &'a [UIElement]) -> Option<&'a str> {
+        let mut all = Vec::new();
+        flatten(elements, &mut all);
+        fuzzy::suggest(&self.text, all.into_iter().filter_map(|e| self.key(e)))
+    }
+}
+
+/// Returns every element in the tree that `selector` selects, in
+/// depth-first order.
+///
+/// With a trailing `[N]` in the text, only the Nth match is returned (or
+/// none, when there are fewer).
+pub fn resolve<'a>(elements: &'a [UIElement], selector: &Selector) -> Vec<&'a UIElement> {
+    resolve_with(elements, selector, false)
+}
+
+/// Like [`resolve`], but empty text matches every element, so the type alone
+/// can select (`find-all --type Cell`).
+pub fn resolve_all<'a>(elements: &'a [UIElement], selector: &Selector) -> Vec<&'a UIElement> {
+    resolve_with(elements, selector, true)
+}
+
+fn resolve_with<'a>(
+    elements: &'a [UIElement],
+    selector: &Selector,
+    any_text: bool,
+) -> Vec<&'a UIElement> {
+    let (base, index) = parse_selector_index(&selector.text);
+    let mut matches = Vec::new();
+    collect(elements, selector, base, any_text, &mut matches);
+    match index {
+        Some(n) => matches.into_iter().nth(n).into_iter().collect(),
+        None => matches,
+    }
+}
+
+fn collect<'a>(
+    elements: &'a [UIElement],
+    selector: &Selector,
+    base: &str,
+    any_text: bool,
+    result: &mut Vec<&'a UIElement>,
+) {
+    for element in elements {
+        if selector.matches_base(base, any_text, element) {
+            result.push(element);
+        }
+        collect(&element.children, selector, base, any_text, result);
+    }
+}
+
+fn flatten<'a>(elements: &'a [UIElement], result: &mut Vec<&'a UIElement>) {
+    for element in elements {
+        result.push(element);
+        flatten(&element.children, result);
+    }
+}
+
+/// Returns true if the pattern contains glob wildcard characters (`*` or `?`).
+pub fn has_wildcard(pattern: &str) -> bool {
+    pattern.contains('*') || pattern.contains('?')
+}
+
+/// Matches a string against a glob pattern with `*` (any chars) and `?` (single char).
+///
+/// When the pattern has no wildcards, falls back to exact equality.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    if !has_wildcard(pattern) {
+        return pattern == text;
+    }
+
+    let pat: Vec<char> = pattern.chars().collect();
+    let txt: Vec<char> = text.chars().collect();
+    let (plen, tlen) = (pat.len(), txt.len());
+
+    // dp[i][j] = pattern[..i] matches text[..j]
+    let mut dp = vec![vec![false; tlen + 1]; plen + 1];
+    dp[0][0] = true;
+
+    // Leading *'s can match empty text
+    for i in 1..=plen {
+        if pat[i - 1] == '*' {
+            dp[i][0] = dp[i - 1][0];
+        }
+    }
+
+    for i in 1..=plen {
+        for j in 1..=tlen {
+            if pat[i - 1] == '*' {
+                // * matches zero chars (dp[i-1][j]) or one more char (dp[i][j-1])
+                dp[i][j] = dp[i - 1][j] || dp[i][j - 1];
+            } else if pat[i - 1] == '?' || pat[i - 1] == txt[j - 1] {
+                dp[i][j] = dp[i - 1][j - 1];
+            }
+        }
+    }
+
+    dp[plen][tlen]
+}
+
+/// Parses a selector string, extracting a trailing `[N]` index if present.
+///
+/// Returns `(base_selector, Some(index))` when the selector ends with `[digits]`
+/// (e.g. `"row[2]"` → `("row", Some(2))`), or `(selector, None)` otherwise.
+///
+/// Only trailing `[digits]` triggers indexing. Non-numeric content, empty
+/// brackets, or no brackets at all are treated as a literal selector with
+/// no index. Negative numbers (e.g. `[-1]`) are also treated as literal.
+pub(crate) fn parse_selector_index(selector: &str) -> (&str, Option<usize>) {
+    if let Some(bracket_pos) = selector.rfind('[') {
+        let after = &selector[bracket_pos + 1..];
+        if let Some(digits) = after.strip_suffix(']') {
+            if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+                if let Ok(n) = digits.parse::<usize>() {
+                    return (&selector[..bracket_pos], Some(n));
+                }
+            }
+        }
+    }
+    (selector, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Vec<UIElement> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    const LIST: &str = r#"[
+        {"type": "NavigationBar", "AXUniqueId": "nav", "AXLabel": "Inbox", "children": [
+            {"type": "Button", "AXUniqueId": "compose", "AXLabel": "Compose"}
+        ]},
+        {"type": "Table", "AXUniqueId": "list", "children": [
+            {"type": "Cell", "AXUniqueId": "row-1", "AXLabel": "Lunch"},
+            {"type": "Cell", "AXUniqueId": "row-2", "AXLabel": "Launch", "children": [
+                {"type": "Button", "AXUniqueId": "row-2-delete", "AXLabel": "Delete"}
+            ]},
+            {"type": "Cell", "AXUniqueId": "row-3", "AXLabel": "Lunch"}
+        ]}
+    ]"#;
+
+    fn ids(elements: &[UIElement], selector: &Selector) -> Vec<String> {
+        resolve(elements, selector)
+            .into_iter()
+            .map(|e| e.identifier.clone().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn exact_text_matches_identifier_or_label() {
+        let elements = parse(LIST);
+        assert_eq!(ids(&elements, &Selector::new("row-2", false)), ["row-2"]);
+        assert!(ids(&elements, &Selector::new("row", false)).is_empty());
+        assert_eq!(
+            ids(&elements, &Selector::new("Lunch", true)),
+            ["row-1", "row-3"]
+        );
+        // Identifiers are not labels
+        assert!(ids(&elements, &Selector::new("row-2", true)).is_empty());
+    }
+
+    #[test]
+    fn wildcards_match_in_both_modes() {
+        let elements = parse(LIST);
+        for mode in [MatchMode::Exact, MatchMode::Glob] {
+            let rows = Selector::new("row-?", false).with_match_mode(mode);
+            assert_eq!(
+                ids(&elements, &rows),
+                ["row-1", "row-2", "row-3"],
+                "{:?}",
+                mode
+            );
+            let all_rows = Selector::new("row-*", false).with_match_mode(mode);
+            assert_eq!(ids(&elements, &all_rows).len(), 4, "{:?}", mode);
+            let labels = Selector::new("L*nch", true).with_match_mode(mode);
+            assert_eq!(ids(&elements, &labels).len(), 3, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn type_filter_narrows_matches() {
+        let elements = parse(LIST);
+        let cells = Selector::new("row-*", false).with_element_type(Some("Cell".to_string()));
+        assert_eq!(ids(&elements, &cells), ["row-1", "row-2", "row-3"]);
+        let buttons = Selector::new("row-*", false).with_element_type(Some("Button".to_string()));
+        assert_eq!(ids(&elements, &buttons), ["row-2-delete"]);
+        // Type names are case-sensitive
+        let lower = Selector::new("row-1", false).with_element_type(Some("cell".to_string()));
+        assert!(ids(&elements, &lower).is_empty());
+    }
+
+    #[test]
+    fn empty_text_selects_by_type_alone_only_for_find_all() {
+        let elements = parse(LIST);
+        let buttons = Selector::new("", false).with_element_type(Some("Button".to_string()));
+        let found: Vec<_> = resolve_all(&elements, &buttons)
+            .iter()
+            .filter_map(|e| e.identifier.as_deref())
+            .collect();
+        assert_eq!(found, ["compose", "row-2-delete"]);
+        assert_eq!(resolve_all(&elements, &Selector::new("", true)).len(), 7);
+        // Single-element lookups never match empty text
+        assert!(resolve(&elements, &buttons).is_empty());
+        assert!(resolve(&elements, &Selector::new("", true)).is_empty());
+    }
+
+    #[test]
+    fn index_picks_among_matches_in_depth_first_order() {
+        let elements = parse(LIST);
+        assert_eq!(ids(&elements, &Selector::new("row-*[3]", false)), ["row-3"]);
+        assert_eq!(ids(&elements, &Selector::new("Lunch[1]", true)), ["row-3"]);
+        let typed = Selector::new("[1]", false).with_element_type(Some("Button".to_string()));
+        let found = resolve_all(&elements, &typed);
+        assert_eq!(found[0].identifier.as_deref(), Some("row-2-delete"));
+        assert!(ids(&elements, &Selector::new("row-*[9]", false)).is_empty());
+    }
+
+    #[test]
+    fn matches_ignores_the_index() {
+        let elements = parse(LIST);
+        let row = &elements[1].children[0];
+        assert!(Selector::new("row-1[5]", false).matches(row));
+        assert!(!Selector::new("row-1", false)
+            .with_element_type(Some("Button".to_string()))
+            .matches(row));
+    }
+
+    #[test]
+    fn suggest_offers_the_closest_key() {
+        let elements = parse(LIST);
+        assert_eq!(
+            Selector::new("compse", false).suggest(&elements),
+            Some("compose")
+        );
+        assert_eq!(
+            Selector::new("Delet", true).suggest(&elements),
+            Some("Delete")
+        );
+        assert_eq!(Selector::new("settings", false).suggest(&elements), None);
+    }
+
+    #[test]
+    fn from_action_reads_single_element_actions() {
+        let tap = ActionType::Tap {
+            selector: "row-*".to_string(),
+            by_label: false,
+            element_type: Some("Cell".to_string()),
+            timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Glob,
+            first: true,
+        };
+        let selector = Selector::from_action(&tap).unwrap();
+        assert_eq!(selector.element_type.as_deref(), Some("Cell"));
+        assert_eq!(selector.match_mode, MatchMode::Glob);
+        assert!(selector.is_pattern());
+
+        let clear = ActionType::ClearText {
+            selector: "Email".to_string(),
+            by_label: true,
+        };
+        assert_eq!(
+            Selector::from_action(&clear),
+            Some(Selector::new("Email", true))
+        );
+        assert!(Selector::from_action(&ActionType::GetScreenshot).is_none());
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("hello", "hello"));
+        assert!(!glob_match("hello", "hell"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b?", "a-long-bc"));
+        assert!(!glob_match("a*b?", "a-long-b"));
+    }
+}
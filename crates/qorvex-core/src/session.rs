@@ -18,7 +18,7 @@
 //!
 //! ```no_run
 //! use qorvex_core::session::Session;
-//! use qorvex_core::action::{ActionType, ActionResult};
+//! use qorvex_core::action::{ActionType, ActionResult, MatchMode};
 //!
 //! #[tokio::main]
 //! async fn main() {
@@ -34,6 +34,9 @@
 //!             selector: "button".to_string(),
 //!             by_label: false,
 //!             element_type: None, timeout_ms: None,
+//!             require_enabled: false,
+//!             match_mode: MatchMode::Exact,
+//!             first: false,
 //!         },
 //!         ActionResult::Success,
 //!         None,
@@ -45,9 +48,12 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::VecDeque;
-use std::io::{BufWriter, Write};
-use std::path::PathBuf;
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::{broadcast, Mutex, RwLock};
 use uuid::Uuid;
@@ -55,8 +61,70 @@ use uuid::Uuid;
 use crate::action::{ActionLog, ActionResult, ActionType};
 use crate::ipc::qorvex_dir;
 
-/// Maximum number of action log entries to retain in the ring buffer.
-const MAX_ACTION_LOG_SIZE: usize = 1000;
+/// Default number of action log entries a session retains in memory.
+pub const DEFAULT_MAX_LOG_ENTRIES: usize = 1000;
+
+/// Default number of most recent log entries that keep their screenshot.
+pub const DEFAULT_MAX_SCREENSHOTS: usize = 100;
+
+/// Environment variable that sets [`LogLimits::max_entries`] in the server.
+pub const MAX_LOG_ENTRIES_ENV: &str = "QORVEX_MAX_LOG_ENTRIES";
+
+/// Environment variable that sets [`LogLimits::max_screenshots`] in the server.
+pub const MAX_SCREENSHOTS_ENV: &str = "QORVEX_MAX_SCREENSHOTS";
+
+/// Environment variable that turns off [`Session::set_screenshot_dedup`] in
+/// the server.
+pub const SCREENSHOT_DEDUP_ENV: &str = "QORVEX_SCREENSHOT_DEDUP";
+
+/// Reads [`SCREENSHOT_DEDUP_ENV`]: `0`, `false` or `no` disable screenshot
+/// deduplication, which is otherwise on.
+pub fn screenshot_dedup_from_env() -> bool {
+    std::env::var(SCREENSHOT_DEDUP_ENV)
+        .map(|v| !matches!(v.trim().to_lowercase().as_str(), "0" | "false" | "no"))
+        .unwrap_or(true)
+}
+
+/// How much of the action log a session keeps in memory.
+///
+/// The persistent log file is unaffected; it always has every entry, without
+/// screenshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogLimits {
+    /// Entries kept before the oldest is evicted. At least 1.
+    pub max_entries: usize,
+    /// Most recent entries that keep their screenshot; older entries keep
+    /// their metadata but drop the image. `0` keeps no screenshots.
+    pub max_screenshots: usize,
+}
+
+impl Default for LogLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_MAX_LOG_ENTRIES,
+            max_screenshots: DEFAULT_MAX_SCREENSHOTS,
+        }
+    }
+}
+
+impl LogLimits {
+    /// Reads [`MAX_LOG_ENTRIES_ENV`] and [`MAX_SCREENSHOTS_ENV`], falling back
+    /// to the defaults for unset or invalid values.
+    pub fn from_env() -> Self {
+        let read = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+        };
+        let defaults = Self::default();
+        Self {
+            max_entries: read(MAX_LOG_ENTRIES_ENV)
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.max_entries),
+            max_screenshots: read(MAX_SCREENSHOTS_ENV).unwrap_or(defaults.max_screenshots),
+        }
+    }
+}
 
 /// Returns the logs directory path.
 ///
@@ -71,11 +139,40 @@ pub fn logs_dir() -> PathBuf {
     dir
 }
 
+/// Returns the newest persistent log of `session_name` in `log_dir`, if any.
+///
+/// Log files are named `{session_name}_{%Y%m%d_%H%M%S}.jsonl`, so the newest
+/// is the one with the greatest name.
+pub fn latest_log(log_dir: &Path, session_name: &str) -> Option<PathBuf> {
+    let prefix = format!("{}_", session_name);
+    std::fs::read_dir(log_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let Some(stamp) = name
+                .to_str()
+                .and_then(|n| n.strip_prefix(&prefix))
+                .and_then(|n| n.strip_suffix(".jsonl"))
+            else {
+                return false;
+            };
+            // `%Y%m%d_%H%M%S`, so `a` doesn't pick up the logs of `a_b`
+            stamp.len() == 15
+                && stamp
+                    .char_indices()
+                    .all(|(i, c)| if i == 8 { c == '_' } else { c.is_ascii_digit() })
+        })
+        .map(|entry| entry.path())
+        .max()
+}
+
 /// Events broadcast to watchers when session state changes.
 ///
 /// These events are sent through the session's broadcast channel to notify
 /// connected watchers (such as the TUI) of state changes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
 pub enum SessionEvent {
     /// A new action was logged to the session.
     ActionLogged(ActionLog),
@@ -96,12 +193,33 @@ pub enum SessionEvent {
     Ended,
 }
 
+/// A consistent view of a session's state at one moment.
+///
+/// Returned by [`Session::snapshot`] and sent in
+/// [`IpcResponse::Snapshot`](crate::ipc::IpcResponse::Snapshot), so clients
+/// get every field from a single read instead of several requests that an
+/// action could land between.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// The session's unique identifier.
+    pub session_id: Uuid,
+    /// The device the session drives, if any.
+    pub udid: Option<String>,
+    /// Number of actions in the in-memory log (at most
+    /// [`LogLimits::max_entries`]).
+    pub action_count: usize,
+    /// The most recent screenshot (base64-encoded PNG), if any.
+    pub last_screenshot: Option<Arc<String>>,
+    /// When the session was created.
+    pub started_at: DateTime<Utc>,
+}
+
 /// Shared session state for an automation session.
 ///
 /// The session maintains:
 /// - A unique identifier and creation timestamp
 /// - The target simulator's UDID (if connected)
-/// - A ring buffer of recent actions (up to 1000 entries)
+/// - A ring buffer of recent actions (see [`LogLimits`])
 /// - The current screenshot (if any)
 /// - A broadcast channel for notifying watchers of state changes
 /// - A persistent log file in `~/.qorvex/logs/`
@@ -117,6 +235,9 @@ pub struct Session {
     /// The UDID of the connected simulator, if any.
     pub simulator_udid: Option<String>,
 
+    /// How much of the action log is kept in memory.
+    pub log_limits: LogLimits,
+
     /// Ring buffer of action log entries (private, access via methods).
     action_log: RwLock<VecDeque<ActionLog>>,
 
@@ -128,6 +249,17 @@ pub struct Session {
 
     /// Buffered writer for persistent JSON Lines log file.
     log_writer: Mutex<Option<BufWriter<std::fs::File>>>,
+
+    /// Directory, next to the log file, that logged screenshots are saved to
+    /// as PNG files. `None` when the log file could not be created.
+    screenshot_dir: Option<PathBuf>,
+
+    /// Whether an unchanged screenshot is kept off the event stream.
+    dedup_screenshots: AtomicBool,
+
+    /// Hash of the last screenshot broadcast as
+    /// [`SessionEvent::ScreenshotUpdated`].
+    last_screenshot_hash: std::sync::Mutex<Option<u64>>,
 }
 
 impl Session {
@@ -163,29 +295,89 @@ impl Session {
         session_name: &str,
         log_dir: PathBuf,
     ) -> Arc<Self> {
+        Self::new_with_limits(simulator_udid, session_name, log_dir, LogLimits::default())
+    }
+
+    /// Creates a new session with a custom log directory and in-memory log
+    /// limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `simulator_udid` - Optional UDID of the simulator to associate with this session
+    /// * `session_name` - Name used for the persistent log file
+    /// * `log_dir` - Directory path for persistent log files
+    /// * `log_limits` - How many entries, and how many screenshots, to keep
+    ///
+    /// # Returns
+    ///
+    /// An `Arc<Session>` for safe sharing across async tasks.
+    pub fn new_with_limits(
+        simulator_udid: Option<String>,
+        session_name: &str,
+        log_dir: PathBuf,
+        log_limits: LogLimits,
+    ) -> Arc<Self> {
+        let log_limits = LogLimits {
+            max_entries: log_limits.max_entries.max(1),
+            ..log_limits
+        };
         let (event_tx, _) = broadcast::channel(100);
         let created_at = Utc::now();
 
         std::fs::create_dir_all(&log_dir).ok();
 
         // Create persistent log file
-        let log_writer = {
-            let timestamp = created_at.format("%Y%m%d_%H%M%S");
-            let log_path = log_dir.join(format!("{}_{}.jsonl", session_name, timestamp));
-            std::fs::File::create(&log_path).ok().map(BufWriter::new)
-        };
+        let timestamp = created_at.format("%Y%m%d_%H%M%S");
+        let log_path = log_dir.join(format!("{}_{}.jsonl", session_name, timestamp));
+        let log_writer = std::fs::File::create(&log_path).ok().map(BufWriter::new);
+        let screenshot_dir = log_writer
+            .is_some()
+            .then(|| log_dir.join(format!("{}_{}_screenshots", session_name, timestamp)));
 
         Arc::new(Self {
             id: Uuid::new_v4(),
             created_at,
             simulator_udid,
-            action_log: RwLock::new(VecDeque::with_capacity(MAX_ACTION_LOG_SIZE)),
+            log_limits,
+            action_log: RwLock::new(VecDeque::with_capacity(log_limits.max_entries)),
             current_screenshot: RwLock::new(None),
             event_tx,
             log_writer: Mutex::new(log_writer),
+            screenshot_dir,
+            dedup_screenshots: AtomicBool::new(true),
+            last_screenshot_hash: std::sync::Mutex::new(None),
         })
     }
 
+    /// Sets whether [`SessionEvent::ScreenshotUpdated`] is skipped for a
+    /// screenshot identical to the last one broadcast. On by default, so
+    /// watchers don't decode the same image again.
+    ///
+    /// The screenshot is still stored and logged either way.
+    pub fn set_screenshot_dedup(&self, enabled: bool) {
+        self.dedup_screenshots.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Broadcasts `screenshot` unless deduplication is on and it matches the
+    /// last one broadcast.
+    fn broadcast_screenshot(&self, screenshot: Arc<String>) {
+        let mut hasher = DefaultHasher::new();
+        screenshot.hash(&mut hasher);
+        let hash = hasher.finish();
+        let previous = self
+            .last_screenshot_hash
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(hash);
+        if previous == Some(hash) && self.dedup_screenshots.load(Ordering::Relaxed) {
+            return;
+        }
+        // Ignore send errors - no subscribers is expected
+        let _ = self
+            .event_tx
+            .send(SessionEvent::ScreenshotUpdated(screenshot));
+    }
+
     /// Subscribes to session events.
     ///
     /// Returns a broadcast receiver that will receive [`SessionEvent`]s as they
@@ -222,8 +414,9 @@ impl Session {
     /// # Note
     ///
     /// The action log is maintained as a ring buffer. When the maximum size
-    /// is reached, the oldest entry is removed. Actions are also persisted to
-    /// the JSON Lines log file at `~/.qorvex/logs/`.
+    /// is reached, the oldest entry is removed, and entries older than the
+    /// last [`LogLimits::max_screenshots`] lose their screenshot. Actions are
+    /// also persisted to the JSON Lines log file at `~/.qorvex/logs/`.
     pub async fn log_action(
         &self,
         action: ActionType,
@@ -256,48 +449,43 @@ impl Session {
         self.persist_action_log(log, screenshot_arc).await
     }
 
+    /// Like `log_action`, but also records the value a `GetValue` read.
+    pub async fn log_action_with_value(
+        &self,
+        action: ActionType,
+        result: ActionResult,
+        screenshot: Option<String>,
+        duration_ms: Option<u64>,
+        value: Option<String>,
+        tag: Option<String>,
+    ) -> ActionLog {
+        let screenshot_arc = screenshot.map(Arc::new);
+        let mut log = ActionLog::new(action, result, screenshot_arc.clone(), duration_ms, tag);
+        log.value = value;
+        self.persist_action_log(log, screenshot_arc).await
+    }
+
     async fn persist_action_log(
         &self,
-        log: ActionLog,
+        mut log: ActionLog,
         screenshot_arc: Option<Arc<String>>,
     ) -> ActionLog {
-        // Update action log with ring buffer behavior
-        {
-            let mut action_log = self.action_log.write().await;
-            if action_log.len() >= MAX_ACTION_LOG_SIZE {
-                action_log.pop_front();
-            }
-            action_log.push_back(log.clone());
+        if let Some(ref ss) = screenshot_arc {
+            log.screenshot_path = self.save_screenshot(log.id, ss);
         }
-
-        // Write to persistent log file (without screenshot to keep file size manageable)
         {
-            let mut writer_guard = self.log_writer.lock().await;
-            if let Some(ref mut writer) = *writer_guard {
-                let file_log = ActionLog {
-                    id: log.id,
-                    timestamp: log.timestamp,
-                    action: log.action.clone(),
-                    result: log.result.clone(),
-                    screenshot: None,
-                    duration_ms: log.duration_ms,
-                    wait_ms: log.wait_ms,
-                    tap_ms: log.tap_ms,
-                    tag: log.tag.clone(),
-                };
-                if let Ok(json) = serde_json::to_string(&file_log) {
-                    let _ = writeln!(writer, "{}", json);
-                    let _ = writer.flush();
-                }
+            // Update the log and the screenshot under the log's lock, so a
+            // snapshot never sees the entry without its screenshot
+            let mut action_log = self.action_log.write().await;
+            self.push_entry_locked(&mut action_log, log.clone());
+            if let Some(ref ss) = screenshot_arc {
+                *self.current_screenshot.write().await = Some(ss.clone());
             }
         }
+        self.write_entry(&log).await;
 
-        // Update screenshot if provided
-        if let Some(ref ss) = screenshot_arc {
-            *self.current_screenshot.write().await = Some(ss.clone());
-            let _ = self
-                .event_tx
-                .send(SessionEvent::ScreenshotUpdated(ss.clone()));
+        if let Some(ss) = screenshot_arc {
+            self.broadcast_screenshot(ss);
         }
 
         // Broadcast action (ignore if no subscribers)
@@ -306,11 +494,121 @@ impl Session {
         log
     }
 
-    /// Returns all action log entries.
+    /// Appends `log` to the in-memory ring buffer.
+    async fn push_entry(&self, log: ActionLog) {
+        let mut action_log = self.action_log.write().await;
+        self.push_entry_locked(&mut action_log, log);
+    }
+
+    /// Appends `log` to the ring buffer, whose lock the caller holds.
+    fn push_entry_locked(&self, action_log: &mut VecDeque<ActionLog>, log: ActionLog) {
+        while action_log.len() >= self.log_limits.max_entries {
+            action_log.pop_front();
+        }
+        action_log.push_back(log);
+        // Older entries keep their metadata but drop the screenshot
+        let trimmed = action_log
+            .len()
+            .saturating_sub(self.log_limits.max_screenshots);
+        if let Some(entry) = trimmed.checked_sub(1).and_then(|i| action_log.get_mut(i)) {
+            entry.screenshot = None;
+        }
+    }
+
+    /// Appends `log` to the persistent log file, without its screenshot data
+    /// to keep the file small; `screenshot_path` points at the image instead.
+    async fn write_entry(&self, log: &ActionLog) {
+        let mut writer_guard = self.log_writer.lock().await;
+        if let Some(ref mut writer) = *writer_guard {
+            let file_log = ActionLog {
+                screenshot: None,
+                ..log.clone()
+            };
+            if let Ok(json) = serde_json::to_string(&file_log) {
+                let _ = writeln!(writer, "{}", json);
+                let _ = writer.flush();
+            }
+        }
+    }
+
+    /// Saves a base64 screenshot as `{id}.png` (or `{id}.jpg` for JPEG) in
+    /// the screenshot directory, returning its path. Returns `None` if it
+    /// cannot be decoded or written.
+    fn save_screenshot(&self, id: Uuid, screenshot: &str) -> Option<String> {
+        use base64::Engine;
+        let dir = self.screenshot_dir.as_ref()?;
+        let image = base64::engine::general_purpose::STANDARD
+            .decode(screenshot)
+            .ok()?;
+        std::fs::create_dir_all(dir).ok()?;
+        let extension = crate::screenshot::image_extension(&image);
+        let path = dir.join(format!("{}.{}", id, extension));
+        std::fs::write(&path, image).ok()?;
+        Some(path.to_string_lossy().into_owned())
+    }
+
+    /// Reads the entries of a persistent log file.
+    ///
+    /// Screenshots saved next to the log are read back into
+    /// [`ActionLog::screenshot`]; a missing image leaves it `None`. Lines
+    /// that do not parse, such as one cut short by a crash, are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or read.
+    pub fn read_log(path: &Path) -> std::io::Result<Vec<ActionLog>> {
+        use base64::Engine;
+        let reader = BufReader::new(std::fs::File::open(path)?);
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let Ok(mut log) = serde_json::from_str::<ActionLog>(&line?) else {
+                continue;
+            };
+            log.screenshot = log
+                .screenshot_path
+                .as_ref()
+                .and_then(|p| std::fs::read(p).ok())
+                .map(|image| Arc::new(base64::engine::general_purpose::STANDARD.encode(image)));
+            entries.push(log);
+        }
+        Ok(entries)
+    }
+
+    /// Adds previously logged entries to this session, oldest first.
+    ///
+    /// The entries go through the same [`LogLimits`] as new ones and are
+    /// copied into this session's log file, so a later resume finds them
+    /// again. No events are broadcast.
+    pub async fn restore_log(&self, entries: Vec<ActionLog>) {
+        for log in entries {
+            self.write_entry(&log).await;
+            self.push_entry(log).await;
+        }
+    }
+
+    /// Rehydrates the action log from a persistent log file, returning the
+    /// number of entries read.
+    ///
+    /// Combines [`read_log`](Self::read_log) and
+    /// [`restore_log`](Self::restore_log). When the file may be the one this
+    /// session writes to, read it before creating the session instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or read.
+    pub async fn load_log(&self, path: &Path) -> std::io::Result<usize> {
+        let entries = Self::read_log(path)?;
+        let count = entries.len();
+        self.restore_log(entries).await;
+        Ok(count)
+    }
+
+    /// Returns the retained action log entries.
     ///
     /// # Returns
     ///
-    /// A `Vec<ActionLog>` containing all logged actions in chronological order.
+    /// A `Vec<ActionLog>` containing the logged actions still within
+    /// [`LogLimits::max_entries`], in chronological order.
     /// This is a copy of the internal log, so modifications do not affect the session.
     pub async fn get_action_log(&self) -> Vec<ActionLog> {
         self.action_log.read().await.iter().cloned().collect()
@@ -325,6 +623,23 @@ impl Session {
     pub async fn get_screenshot(&self) -> Option<Arc<String>> {
         self.current_screenshot.read().await.clone()
     }
+
+    /// Returns the session's state as one consistent [`SessionSnapshot`].
+    ///
+    /// The action count and screenshot are read together, so the snapshot
+    /// never shows a logged action without the screenshot logged with it.
+    pub async fn snapshot(&self) -> SessionSnapshot {
+        // Same lock order as `persist_action_log`: log, then screenshot
+        let action_log = self.action_log.read().await;
+        let last_screenshot = self.current_screenshot.read().await.clone();
+        SessionSnapshot {
+            session_id: self.id,
+            udid: self.simulator_udid.clone(),
+            action_count: action_log.len(),
+            last_screenshot,
+            started_at: self.created_at,
+        }
+    }
 }
 
 impl std::fmt::Debug for Session {
@@ -333,10 +648,13 @@ impl std::fmt::Debug for Session {
             .field("id", &self.id)
             .field("created_at", &self.created_at)
             .field("simulator_udid", &self.simulator_udid)
+            .field("log_limits", &self.log_limits)
             .field("action_log", &"<RwLock<VecDeque<ActionLog>>>")
             .field("current_screenshot", &"<RwLock<Option<Arc<String>>>>")
             .field("event_tx", &"<broadcast::Sender>")
             .field("log_writer", &"<Mutex<Option<BufWriter<File>>>>")
+            .field("screenshot_dir", &self.screenshot_dir)
+            .field("dedup_screenshots", &self.dedup_screenshots)
             .finish()
     }
 }
@@ -353,13 +671,12 @@ impl Session {
     ///
     /// # Events
     ///
-    /// Broadcasts a [`SessionEvent::ScreenshotUpdated`] event to all subscribers.
+    /// Broadcasts a [`SessionEvent::ScreenshotUpdated`] event to all
+    /// subscribers, unless the screenshot is unchanged (see
+    /// [`Session::set_screenshot_dedup`]).
     pub async fn update_screenshot(&self, screenshot: String) {
         let screenshot_arc = Arc::new(screenshot);
         *self.current_screenshot.write().await = Some(screenshot_arc.clone());
-        // Ignore send errors - no subscribers is expected
-        let _ = self
-            .event_tx
-            .send(SessionEvent::ScreenshotUpdated(screenshot_arc));
+        self.broadcast_screenshot(screenshot_arc);
     }
 }
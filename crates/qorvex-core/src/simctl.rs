@@ -1,7 +1,8 @@
 //! Interface to Apple's `xcrun simctl` command-line tool.
 //!
 //! This module provides a Rust wrapper around the iOS Simulator control tool,
-//! enabling device listing, screenshot capture, and simulator boot.
+//! enabling device listing, screenshot capture, screen recording, and
+//! simulator boot and shutdown.
 //!
 //! # Requirements
 //!
@@ -26,33 +27,178 @@
 //! ```
 
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 /// Errors that can occur when interacting with simctl.
+///
+/// Failed invocations are classified from simctl's exit code and stderr (see
+/// [`SimctlError::from_failure`]) so callers can react to specific conditions,
+/// e.g. treat [`SimctlError::AlreadyBooted`] as success.
 #[derive(Error, Debug)]
 pub enum SimctlError {
-    /// A simctl command failed to execute successfully.
-    #[error("Command execution failed: {0}")]
-    CommandFailed(String),
+    /// `xcrun` or `simctl` is unavailable (Xcode not installed or not selected).
+    #[error("Xcode command-line tools not found; install Xcode and run `xcode-select -s`")]
+    XcodeNotFound,
+
+    /// simctl does not know the requested device.
+    #[error("Simulator not found: {0}")]
+    DeviceNotFound(String),
+
+    /// The simulator is already booted.
+    #[error("Simulator is already booted")]
+    AlreadyBooted,
+
+    /// The simulator is already shut down.
+    #[error("Simulator is already shut down")]
+    AlreadyShutdown,
+
+    /// The requested device type is not known to simctl.
+    #[error("Unknown device type '{requested}'; available: {}", .available.join(", "))]
+    UnknownDeviceType {
+        /// The device type that was asked for.
+        requested: String,
+        /// Short identifiers of the installed device types (e.g. `iPhone-15`).
+        available: Vec<String>,
+    },
+
+    /// The requested runtime is not installed or not available.
+    #[error("Unknown runtime '{requested}'; available: {}", .available.join(", "))]
+    UnknownRuntime {
+        /// The runtime that was asked for.
+        requested: String,
+        /// Short identifiers of the available runtimes (e.g. `iOS-17-5`).
+        available: Vec<String>,
+    },
 
     /// No simulator is currently in the "Booted" state.
     #[error("No booted simulator found")]
     NoBootedSimulator,
 
-    /// Failed to parse JSON output from simctl.
-    #[error("JSON parse error: {0}")]
-    JsonParse(#[from] serde_json::Error),
+    /// The app is not installed on the simulator.
+    #[error("App not installed: {0}")]
+    AppNotInstalled(String),
+
+    /// A simctl command failed for a reason not covered by another variant.
+    #[error("Command execution failed ({}): {stderr}", exit_code_label(*.code))]
+    CommandFailed {
+        /// The process exit code, or `None` if it was terminated by a signal.
+        code: Option<i32>,
+        /// The trimmed stderr output of the command.
+        stderr: String,
+    },
+
+    /// The simulator did not finish booting before the timeout elapsed.
+    #[error("Timed out waiting for simulator to boot: {0}")]
+    Timeout(String),
+
+    /// simctl output could not be parsed.
+    #[error("Failed to parse simctl output: {0}")]
+    ParseError(String),
+
+    /// A language or region is not a valid BCP-47 subtag.
+    #[error("Invalid locale: {0}")]
+    InvalidLocale(String),
 
     /// An I/O error occurred while executing the command.
     #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+    Io(std::io::Error),
+}
+
+impl SimctlError {
+    /// Classifies a failed simctl invocation from its exit code and stderr.
+    pub fn from_failure(code: Option<i32>, stderr: &str) -> Self {
+        let stderr = stderr.trim();
+        if stderr.contains("current state: Booted") {
+            return SimctlError::AlreadyBooted;
+        }
+        if stderr.contains("current state: Shutdown") {
+            return SimctlError::AlreadyShutdown;
+        }
+        if stderr.contains("No devices are booted") {
+            return SimctlError::NoBootedSimulator;
+        }
+        if stderr.contains("xcrun: error") || stderr.contains("xcode-select: error") {
+            return SimctlError::XcodeNotFound;
+        }
+        if let Some(pos) = stderr.find("Invalid device") {
+            let rest = &stderr[pos..];
+            let device = rest
+                .split_once(": ")
+                .map(|(_, device)| device.lines().next().unwrap_or(device).trim())
+                .unwrap_or(rest);
+            return SimctlError::DeviceNotFound(device.to_string());
+        }
+        SimctlError::CommandFailed {
+            code,
+            stderr: stderr.to_string(),
+        }
+    }
+
+    /// Classifies the output of a failed command.
+    fn from_output(output: &Output) -> Self {
+        Self::from_failure(
+            output.status.code(),
+            &String::from_utf8_lossy(&output.stderr),
+        )
+    }
+}
+
+impl From<std::io::Error> for SimctlError {
+    fn from(e: std::io::Error) -> Self {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            SimctlError::XcodeNotFound
+        } else {
+            SimctlError::Io(e)
+        }
+    }
+}
+
+impl From<serde_json::Error> for SimctlError {
+    fn from(e: serde_json::Error) -> Self {
+        SimctlError::ParseError(e.to_string())
+    }
 }
 
+fn exit_code_label(code: Option<i32>) -> String {
+    match code {
+        Some(code) => format!("exit code {}", code),
+        None => "terminated by signal".to_string(),
+    }
+}
+
+/// Default time [`Simctl::wait_for_boot`] callers allow a simulator to boot.
+pub const DEFAULT_BOOT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long [`RecordingHandle::stop`] waits for simctl to finalize the video
+/// before killing it.
+pub const RECORDING_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a new recording is watched for an immediate failure, such as an
+/// unknown device, before it is considered started.
+const RECORDING_STARTUP_CHECK: Duration = Duration::from_millis(300);
+
 /// Represents an iOS Simulator device.
 ///
 /// This struct contains information about a simulator device as reported
 /// by `xcrun simctl list devices -j`.
+///
+/// # Example
+///
+/// ```
+/// use qorvex_core::simctl::Simctl;
+///
+/// let json = br#"{"devices": {"com.apple.CoreSimulator.SimRuntime.iOS-18-2": [
+///     {"udid": "A1B2", "name": "iPhone 16", "state": "Booted", "isAvailable": true}
+/// ]}}"#;
+/// let devices = Simctl::parse_device_list(json).unwrap();
+/// assert_eq!(devices[0].runtime_name().as_deref(), Some("iOS 18.2"));
+/// assert!(devices[0].is_available);
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulatorDevice {
     /// The unique device identifier (UDID) for this simulator.
@@ -67,11 +213,153 @@ pub struct SimulatorDevice {
     /// The device type identifier (e.g., "com.apple.CoreSimulator.SimDeviceType.iPhone-15-Pro").
     #[serde(rename = "deviceTypeIdentifier")]
     pub device_type: Option<String>,
+
+    /// The runtime identifier the device belongs to (e.g.,
+    /// "com.apple.CoreSimulator.SimRuntime.iOS-17-0"). `simctl` groups
+    /// devices by runtime rather than listing it per device, so this is
+    /// filled in from the group key when parsing.
+    #[serde(default)]
+    pub runtime: Option<String>,
+
+    /// Whether the device can be booted. `simctl` marks devices unavailable
+    /// when their runtime is missing; older versions that omit the field
+    /// read as available.
+    #[serde(rename = "isAvailable", default = "available_by_default")]
+    pub is_available: bool,
+}
+
+fn available_by_default() -> bool {
+    true
+}
+
+impl SimulatorDevice {
+    /// The runtime as a readable name, e.g. "iOS 17.0" for
+    /// `com.apple.CoreSimulator.SimRuntime.iOS-17-0`.
+    pub fn runtime_name(&self) -> Option<String> {
+        let id = self.runtime.as_deref()?;
+        let short = id.rsplit('.').next().unwrap_or(id);
+        Some(match short.split_once('-') {
+            Some((platform, version)) => format!("{} {}", platform, version.replace('-', ".")),
+            None => short.to_string(),
+        })
+    }
+
+    /// Whether the device's runtime matches `filter`, such as "iOS-17",
+    /// "iOS 17.2" or "watchOS".
+    ///
+    /// The filter's platform and version parts must be a prefix of the
+    /// runtime's, compared case-insensitively, so "iOS-17" matches iOS 17.0
+    /// and 17.5 but not iOS 1.7 or 170.
+    pub fn runtime_matches(&self, filter: &str) -> bool {
+        let Some(name) = self.runtime_name() else {
+            return false;
+        };
+        let parts = |s: &str| -> Vec<String> {
+            s.split([' ', '-', '.'])
+                .filter(|p| !p.is_empty())
+                .map(str::to_ascii_lowercase)
+                .collect()
+        };
+        let (wanted, have) = (parts(filter), parts(&name));
+        !wanted.is_empty() && have.starts_with(&wanted)
+    }
+}
+
+/// Which simulators `list-devices` keeps. Every set filter must match.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceFilter {
+    /// Keep devices whose runtime matches (see [`SimulatorDevice::runtime_matches`]).
+    pub runtime: Option<String>,
+    /// Keep devices whose runtime is installed.
+    pub available_only: bool,
+    /// Keep booted devices.
+    pub booted_only: bool,
+}
+
+impl DeviceFilter {
+    /// Whether `device` passes every filter that is set.
+    pub fn matches(&self, device: &SimulatorDevice) -> bool {
+        self.runtime
+            .as_deref()
+            .is_none_or(|runtime| device.runtime_matches(runtime))
+            && (!self.available_only || device.is_available)
+            && (!self.booted_only || device.state == "Booted")
+    }
+
+    /// Keeps the devices that pass, in order.
+    pub fn apply(&self, mut devices: Vec<SimulatorDevice>) -> Vec<SimulatorDevice> {
+        devices.retain(|d| self.matches(d));
+        devices
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct DeviceList {
-    devices: std::collections::HashMap<String, Vec<SimulatorDevice>>,
+    // Ordered by runtime so "the first booted device" is stable between calls
+    devices: std::collections::BTreeMap<String, Vec<SimulatorDevice>>,
+}
+
+/// A kind of simulator device, as reported by `xcrun simctl list devicetypes -j`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceType {
+    /// The human-readable name (e.g., "iPhone 15").
+    pub name: String,
+
+    /// The identifier (e.g., "com.apple.CoreSimulator.SimDeviceType.iPhone-15").
+    pub identifier: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceTypeList {
+    devicetypes: Vec<DeviceType>,
+}
+
+/// A simulator runtime, as reported by `xcrun simctl list runtimes -j`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Runtime {
+    /// The human-readable name (e.g., "iOS 17.5").
+    pub name: String,
+
+    /// The identifier (e.g., "com.apple.CoreSimulator.SimRuntime.iOS-17-5").
+    pub identifier: String,
+
+    /// Whether the runtime can be used; `None` when simctl does not say.
+    #[serde(rename = "isAvailable", default)]
+    pub is_available: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuntimeList {
+    runtimes: Vec<Runtime>,
+}
+
+/// Returns the last component of a CoreSimulator identifier
+/// (`com.apple.CoreSimulator.SimDeviceType.iPhone-15` → `iPhone-15`).
+fn short_identifier(identifier: &str) -> &str {
+    identifier.rsplit('.').next().unwrap_or(identifier)
+}
+
+/// Finds the identifier matching `requested` among `(name, identifier)`
+/// pairs. The full identifier, its short form, or the name all match,
+/// ignoring case. On a miss, returns the short identifiers to choose from.
+fn resolve_identifier<'a>(
+    requested: &str,
+    candidates: impl Iterator<Item = (&'a str, &'a str)> + Clone,
+) -> Result<String, Vec<String>> {
+    let requested = requested.trim();
+    candidates
+        .clone()
+        .find(|(name, identifier)| {
+            [*identifier, short_identifier(identifier), *name]
+                .iter()
+                .any(|form| form.eq_ignore_ascii_case(requested))
+        })
+        .map(|(_, identifier)| identifier.to_string())
+        .ok_or_else(|| {
+            candidates
+                .map(|(_, identifier)| short_identifier(identifier).to_string())
+                .collect()
+        })
 }
 
 /// An application installed on a simulator device.
@@ -85,6 +373,169 @@ pub struct InstalledApp {
     pub app_type: String,
 }
 
+impl InstalledApp {
+    /// Whether the app was installed by the user rather than shipped with
+    /// the OS.
+    pub fn is_user(&self) -> bool {
+        self.app_type == "User"
+    }
+}
+
+/// One app in `simctl listapps` output; other keys are ignored.
+#[derive(Deserialize)]
+struct AppEntry {
+    #[serde(rename = "CFBundleIdentifier")]
+    bundle_id: Option<String>,
+    #[serde(rename = "CFBundleDisplayName")]
+    display_name: Option<String>,
+    #[serde(rename = "CFBundleName")]
+    name: Option<String>,
+    #[serde(rename = "ApplicationType")]
+    app_type: Option<String>,
+}
+
+/// Simulator UI appearance, as set by `xcrun simctl ui <udid> appearance`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Appearance {
+    /// Light mode.
+    Light,
+    /// Dark mode.
+    Dark,
+}
+
+impl Appearance {
+    /// The value simctl expects on its command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Appearance::Light => "light",
+            Appearance::Dark => "dark",
+        }
+    }
+}
+
+impl std::fmt::Display for Appearance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Appearance {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "light" => Ok(Appearance::Light),
+            "dark" => Ok(Appearance::Dark),
+            other => Err(format!(
+                "unknown appearance '{other}' (expected 'light' or 'dark')"
+            )),
+        }
+    }
+}
+
+/// Which container [`Simctl::app_container`] resolves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerKind {
+    /// The `.app` bundle.
+    #[default]
+    App,
+    /// The data container (Documents, Library, tmp).
+    Data,
+    /// The shared app group container.
+    Groups,
+}
+
+impl ContainerKind {
+    /// The value simctl expects on its command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ContainerKind::App => "app",
+            ContainerKind::Data => "data",
+            ContainerKind::Groups => "groups",
+        }
+    }
+}
+
+/// Dynamic Type content size category, as set by
+/// `xcrun simctl ui <udid> content_size`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContentSize {
+    ExtraSmall,
+    Small,
+    Medium,
+    Large,
+    ExtraLarge,
+    ExtraExtraLarge,
+    ExtraExtraExtraLarge,
+    AccessibilityMedium,
+    AccessibilityLarge,
+    AccessibilityExtraLarge,
+    AccessibilityExtraExtraLarge,
+    AccessibilityExtraExtraExtraLarge,
+}
+
+impl ContentSize {
+    /// Every category, smallest first.
+    pub const ALL: [ContentSize; 12] = [
+        ContentSize::ExtraSmall,
+        ContentSize::Small,
+        ContentSize::Medium,
+        ContentSize::Large,
+        ContentSize::ExtraLarge,
+        ContentSize::ExtraExtraLarge,
+        ContentSize::ExtraExtraExtraLarge,
+        ContentSize::AccessibilityMedium,
+        ContentSize::AccessibilityLarge,
+        ContentSize::AccessibilityExtraLarge,
+        ContentSize::AccessibilityExtraExtraLarge,
+        ContentSize::AccessibilityExtraExtraExtraLarge,
+    ];
+
+    /// The value simctl expects on its command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ContentSize::ExtraSmall => "extra-small",
+            ContentSize::Small => "small",
+            ContentSize::Medium => "medium",
+            ContentSize::Large => "large",
+            ContentSize::ExtraLarge => "extra-large",
+            ContentSize::ExtraExtraLarge => "extra-extra-large",
+            ContentSize::ExtraExtraExtraLarge => "extra-extra-extra-large",
+            ContentSize::AccessibilityMedium => "accessibility-medium",
+            ContentSize::AccessibilityLarge => "accessibility-large",
+            ContentSize::AccessibilityExtraLarge => "accessibility-extra-large",
+            ContentSize::AccessibilityExtraExtraLarge => "accessibility-extra-extra-large",
+            ContentSize::AccessibilityExtraExtraExtraLarge => {
+                "accessibility-extra-extra-extra-large"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ContentSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ContentSize {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|size| size.as_str() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown content size '{s}' (expected one of: {})",
+                    Self::ALL.map(ContentSize::as_str).join(", ")
+                )
+            })
+    }
+}
+
 /// Wrapper for `xcrun simctl` commands.
 ///
 /// Provides static methods for interacting with iOS Simulator devices.
@@ -99,35 +550,32 @@ impl Simctl {
     ///
     /// # Returns
     ///
-    /// A `Vec<SimulatorDevice>` containing all available simulators,
-    /// regardless of their state or iOS version.
+    /// A `Vec<SimulatorDevice>` containing every simulator, regardless of
+    /// its state or iOS version; check `is_available` before booting one.
     ///
     /// # Errors
     ///
-    /// - [`SimctlError::Io`] if the command fails to execute
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
     /// - [`SimctlError::CommandFailed`] if simctl returns a non-zero exit code
-    /// - [`SimctlError::JsonParse`] if the output cannot be parsed as JSON
+    /// - [`SimctlError::ParseError`] if the output cannot be parsed as JSON
     pub fn list_devices() -> Result<Vec<SimulatorDevice>, SimctlError> {
         let output = Command::new("xcrun")
             .args(["simctl", "list", "devices", "-j"])
             .output()?;
 
         if !output.status.success() {
-            return Err(SimctlError::CommandFailed(
-                String::from_utf8_lossy(&output.stderr).to_string(),
-            ));
+            return Err(SimctlError::from_output(&output));
         }
 
-        let device_list: DeviceList = serde_json::from_slice(&output.stdout)?;
-        let devices: Vec<SimulatorDevice> = device_list.devices.into_values().flatten().collect();
-
-        Ok(devices)
+        Self::parse_device_list(&output.stdout)
     }
 
     /// Returns the UDID of the first booted simulator.
     ///
     /// Searches through all available devices and returns the UDID of the
-    /// first one found with state "Booted".
+    /// first one found with state "Booted". When several simulators are
+    /// booted this is the first of [`Self::booted_udids`]; callers that did
+    /// not pick a device explicitly should warn in that case.
     ///
     /// # Returns
     ///
@@ -138,14 +586,28 @@ impl Simctl {
     /// - [`SimctlError::NoBootedSimulator`] if no simulator is currently booted
     /// - Any errors from [`Self::list_devices`]
     pub fn get_booted_udid() -> Result<String, SimctlError> {
-        let devices = Self::list_devices()?;
-        devices
+        Self::booted_udids()?
             .into_iter()
-            .find(|d| d.state == "Booted")
-            .map(|d| d.udid)
+            .next()
             .ok_or(SimctlError::NoBootedSimulator)
     }
 
+    /// Returns the UDIDs of every booted simulator.
+    ///
+    /// Devices are ordered by runtime identifier, then as `simctl` lists
+    /// them. The list is empty when nothing is booted.
+    ///
+    /// # Errors
+    ///
+    /// - Any errors from [`Self::list_devices`]
+    pub fn booted_udids() -> Result<Vec<String>, SimctlError> {
+        let devices = Self::list_devices()?;
+        Ok(Self::find_booted_devices(&devices)
+            .into_iter()
+            .map(|d| d.udid.clone())
+            .collect())
+    }
+
     /// Takes a screenshot of the simulator screen.
     ///
     /// Captures the current display of the specified simulator and returns
@@ -172,9 +634,7 @@ impl Simctl {
             .output()?;
 
         if !output.status.success() {
-            return Err(SimctlError::CommandFailed(
-                String::from_utf8_lossy(&output.stderr).to_string(),
-            ));
+            return Err(SimctlError::from_output(&output));
         }
 
         let bytes = std::fs::read(&temp_path)?;
@@ -182,11 +642,31 @@ impl Simctl {
         Ok(bytes)
     }
 
+    /// Starts recording the simulator screen to `out`.
+    ///
+    /// Spawns `xcrun simctl io <udid> recordVideo <out>`, which records until
+    /// it receives `SIGINT`. Call [`RecordingHandle::stop`] to finalize the
+    /// file; dropping the handle kills the recorder and leaves the file
+    /// unusable.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::DeviceNotFound`] if no simulator has this UDID
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::CommandFailed`] if the recorder exits right away
+    pub fn start_recording(udid: &str, out: &Path) -> Result<RecordingHandle, SimctlError> {
+        let mut command = Command::new("xcrun");
+        command
+            .args(["simctl", "io", udid, "recordVideo", "--force"])
+            .arg(out);
+        RecordingHandle::spawn(command, out)
+    }
+
     /// Boots a simulator device.
     ///
     /// Starts the specified simulator. If the simulator is already booted,
-    /// this method returns successfully (the "already booted" state is not
-    /// treated as an error).
+    /// this returns [`SimctlError::AlreadyBooted`], which callers that only
+    /// need a running simulator should treat as success.
     ///
     /// # Arguments
     ///
@@ -194,19 +674,437 @@ impl Simctl {
     ///
     /// # Errors
     ///
-    /// - [`SimctlError::Io`] if the command fails to execute
-    /// - [`SimctlError::CommandFailed`] if simctl returns an error (except for "already booted")
+    /// - [`SimctlError::AlreadyBooted`] if the simulator is already booted
+    /// - [`SimctlError::DeviceNotFound`] if no simulator has this UDID
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::CommandFailed`] if simctl fails for another reason
     pub fn boot(udid: &str) -> Result<(), SimctlError> {
         let output = Command::new("xcrun")
             .args(["simctl", "boot", udid])
             .output()?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            // Already booted is not an error
-            if !stderr.contains("current state: Booted") {
-                return Err(SimctlError::CommandFailed(stderr.to_string()));
+            return Err(SimctlError::from_output(&output));
+        }
+        Ok(())
+    }
+
+    /// Shuts down a simulator device.
+    ///
+    /// Shutting down a simulator that is already shut down succeeds, so test
+    /// harnesses can call this unconditionally during teardown.
+    ///
+    /// # Arguments
+    ///
+    /// * `udid` - The unique device identifier of the simulator to shut down
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::DeviceNotFound`] if no simulator has this UDID
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::CommandFailed`] if simctl fails for another reason
+    pub fn shutdown(udid: &str) -> Result<(), SimctlError> {
+        Self::run_shutdown(udid)
+    }
+
+    /// Shuts down every booted simulator (`xcrun simctl shutdown all`).
+    ///
+    /// Succeeds when no simulator is booted.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::CommandFailed`] if simctl fails for another reason
+    pub fn shutdown_all() -> Result<(), SimctlError> {
+        Self::run_shutdown("all")
+    }
+
+    fn run_shutdown(target: &str) -> Result<(), SimctlError> {
+        let output = Command::new("xcrun")
+            .args(["simctl", "shutdown", target])
+            .output()?;
+
+        if !output.status.success() {
+            return Self::ignore_already_shutdown(Err(SimctlError::from_output(&output)));
+        }
+        Ok(())
+    }
+
+    /// Treats [`SimctlError::AlreadyShutdown`] as success.
+    fn ignore_already_shutdown(result: Result<(), SimctlError>) -> Result<(), SimctlError> {
+        match result {
+            Err(SimctlError::AlreadyShutdown) => Ok(()),
+            other => other,
+        }
+    }
+
+    /// Creates a new simulator and returns its UDID.
+    ///
+    /// `device_type` and `runtime` are checked against `xcrun simctl list`
+    /// first; each may be a full identifier, its short form (`iPhone-15`,
+    /// `iOS-17-5`), or the display name (`iPhone 15`, `iOS 17.5`).
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name for the new simulator
+    /// * `device_type` - The device type to create
+    /// * `runtime` - The runtime to install on it
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::UnknownDeviceType`] if no device type matches, listing the options
+    /// - [`SimctlError::UnknownRuntime`] if no available runtime matches, listing the options
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::CommandFailed`] if simctl fails for another reason
+    /// - [`SimctlError::ParseError`] if simctl does not print a UDID
+    pub fn create_device(
+        name: &str,
+        device_type: &str,
+        runtime: &str,
+    ) -> Result<String, SimctlError> {
+        let device_type = Self::resolve_device_type(device_type, &Self::list_device_types()?)?;
+        let runtime = Self::resolve_runtime(runtime, &Self::list_runtimes()?)?;
+
+        let output = Command::new("xcrun")
+            .args(["simctl", "create", name, &device_type, &runtime])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(SimctlError::from_output(&output));
+        }
+        Self::parse_created_udid(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Deletes a simulator.
+    ///
+    /// # Arguments
+    ///
+    /// * `udid` - The unique device identifier of the simulator to delete
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::DeviceNotFound`] if no simulator has this UDID
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::CommandFailed`] if simctl fails for another reason
+    pub fn delete_device(udid: &str) -> Result<(), SimctlError> {
+        let output = Command::new("xcrun")
+            .args(["simctl", "delete", udid])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(SimctlError::from_output(&output));
+        }
+        Ok(())
+    }
+
+    /// Lists the device types simulators can be created with.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::CommandFailed`] if simctl returns a non-zero exit code
+    /// - [`SimctlError::ParseError`] if the output cannot be parsed as JSON
+    pub fn list_device_types() -> Result<Vec<DeviceType>, SimctlError> {
+        let output = Command::new("xcrun")
+            .args(["simctl", "list", "devicetypes", "-j"])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(SimctlError::from_output(&output));
+        }
+        Self::parse_device_types(&output.stdout)
+    }
+
+    /// Lists the installed simulator runtimes.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::CommandFailed`] if simctl returns a non-zero exit code
+    /// - [`SimctlError::ParseError`] if the output cannot be parsed as JSON
+    pub fn list_runtimes() -> Result<Vec<Runtime>, SimctlError> {
+        let output = Command::new("xcrun")
+            .args(["simctl", "list", "runtimes", "-j"])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(SimctlError::from_output(&output));
+        }
+        Self::parse_runtimes(&output.stdout)
+    }
+
+    /// Blocks until a simulator has finished booting.
+    ///
+    /// [`Self::boot`] returns once the boot is issued, before the device can
+    /// run apps. This runs `xcrun simctl bootstatus <udid>`, which waits for
+    /// the boot to complete, and polls it every 200 ms until it exits or the
+    /// timeout elapses. The device must already be booting (or booted).
+    ///
+    /// # Arguments
+    ///
+    /// * `udid` - The unique device identifier of the simulator
+    /// * `timeout` - Maximum time to wait for the boot to complete
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::Timeout`] if the device has not booted in time
+    /// - [`SimctlError::DeviceNotFound`] if no simulator has this UDID
+    /// - [`SimctlError::CommandFailed`] if `bootstatus` fails for another reason
+    pub fn wait_for_boot(udid: &str, timeout: Duration) -> Result<(), SimctlError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let mut child = Command::new("xcrun")
+                .args(["simctl", "bootstatus", udid])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+
+            while child.try_wait()?.is_none() {
+                if Instant::now() >= deadline {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(SimctlError::Timeout(format!(
+                        "device '{}' did not finish booting within {:?}",
+                        udid, timeout
+                    )));
+                }
+                std::thread::sleep(Duration::from_millis(200));
+            }
+
+            let output = child.wait_with_output()?;
+            if !output.status.success() {
+                return Err(SimctlError::from_output(&output));
+            }
+            // Fall back to the device list in case this Xcode words the
+            // completion line differently.
+            if Self::parse_bootstatus(&String::from_utf8_lossy(&output.stdout))
+                || Self::list_devices()?
+                    .iter()
+                    .any(|d| d.udid == udid && d.state == "Booted")
+            {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(SimctlError::Timeout(format!(
+                    "device '{}' did not finish booting within {:?}",
+                    udid, timeout
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+    }
+
+    /// Returns true if `simctl bootstatus` output reports a completed boot.
+    ///
+    /// `bootstatus` prints progress lines ("Waiting on System App", ...) and
+    /// ends with either "Device already booted, nothing to wait for." or
+    /// "Finished" / "Device booted in ..." once the boot completes.
+    pub fn parse_bootstatus(output: &str) -> bool {
+        output.lines().map(str::trim).any(|line| {
+            line.contains("already booted")
+                || line.starts_with("Device booted")
+                || line == "Finished"
+        })
+    }
+
+    /// Switches a simulator between light and dark mode.
+    ///
+    /// Runs `xcrun simctl ui <udid> appearance <light|dark>`. The device must
+    /// be booted.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::DeviceNotFound`] if no simulator has this UDID
+    /// - [`SimctlError::CommandFailed`] if simctl returns an error
+    pub fn set_appearance(udid: &str, appearance: Appearance) -> Result<(), SimctlError> {
+        Self::run_ui(udid, "appearance", appearance.as_str())
+    }
+
+    /// Sets the Dynamic Type content size category of a simulator.
+    ///
+    /// Runs `xcrun simctl ui <udid> content_size <size>`. The device must be
+    /// booted.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::DeviceNotFound`] if no simulator has this UDID
+    /// - [`SimctlError::CommandFailed`] if simctl returns an error
+    pub fn set_content_size(udid: &str, size: ContentSize) -> Result<(), SimctlError> {
+        Self::run_ui(udid, "content_size", size.as_str())
+    }
+
+    /// Sets the language and region of a simulator, then reboots it.
+    ///
+    /// Writes `AppleLanguages` and `AppleLocale` to the device's global
+    /// defaults with `xcrun simctl spawn <udid> defaults write`. The system
+    /// only reads them at boot, so the device is shut down and booted again,
+    /// waiting up to [`DEFAULT_BOOT_TIMEOUT`]. Apps (and the agent) running
+    /// before the reboot are gone and must be relaunched. The device must be
+    /// booted.
+    ///
+    /// # Arguments
+    ///
+    /// * `udid` - The unique device identifier of the simulator
+    /// * `language` - A BCP-47 language subtag, optionally with a script (`fr`, `zh-Hans`)
+    /// * `region` - A BCP-47 region subtag (`FR`, `419`)
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::InvalidLocale`] if `language` or `region` is malformed
+    /// - [`SimctlError::DeviceNotFound`] if no simulator has this UDID
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::Timeout`] if the device does not finish rebooting
+    /// - [`SimctlError::CommandFailed`] if simctl fails for another reason
+    pub fn set_locale(udid: &str, language: &str, region: &str) -> Result<(), SimctlError> {
+        let (languages, locale) = Self::locale_identifiers(language, region)?;
+        Self::run_defaults_write(udid, "AppleLanguages", "-array", &languages)?;
+        Self::run_defaults_write(udid, "AppleLocale", "-string", &locale)?;
+        Self::shutdown(udid)?;
+        Self::boot(udid)?;
+        Self::wait_for_boot(udid, DEFAULT_BOOT_TIMEOUT)
+    }
+
+    /// Validates a language and region and returns the `AppleLanguages`
+    /// entry (`fr-FR`) and `AppleLocale` identifier (`fr_FR`) for them.
+    ///
+    /// The language is a two- or three-letter subtag, optionally followed by
+    /// a four-letter script (`zh-Hans`); the region is two letters or three
+    /// digits. Case is normalized, so `FR` and `fr` are both accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SimctlError::InvalidLocale`] naming the malformed subtag.
+    pub fn locale_identifiers(
+        language: &str,
+        region: &str,
+    ) -> Result<(String, String), SimctlError> {
+        let (primary, script) = match language.trim().split_once(['-', '_']) {
+            Some((primary, script)) => (primary, Some(script)),
+            None => (language.trim(), None),
+        };
+        if !(2..=3).contains(&primary.len()) || !primary.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(SimctlError::InvalidLocale(format!(
+                "language '{language}' must be a 2 or 3 letter code such as 'fr'"
+            )));
+        }
+        let mut language = primary.to_ascii_lowercase();
+        if let Some(script) = script {
+            if script.len() != 4 || !script.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(SimctlError::InvalidLocale(format!(
+                    "script '{script}' must be 4 letters such as 'Hans'"
+                )));
             }
+            language.push('-');
+            language.push_str(&script[..1].to_ascii_uppercase());
+            language.push_str(&script[1..].to_ascii_lowercase());
+        }
+
+        let region = region.trim();
+        let valid_region = (region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()))
+            || (region.len() == 3 && region.chars().all(|c| c.is_ascii_digit()));
+        if !valid_region {
+            return Err(SimctlError::InvalidLocale(format!(
+                "region '{region}' must be 2 letters or 3 digits such as 'FR'"
+            )));
+        }
+        let region = region.to_ascii_uppercase();
+
+        Ok((
+            format!("{language}-{region}"),
+            format!("{}_{region}", language.replace('-', "_")),
+        ))
+    }
+
+    fn run_defaults_write(
+        udid: &str,
+        key: &str,
+        kind: &str,
+        value: &str,
+    ) -> Result<(), SimctlError> {
+        let output = Command::new("xcrun")
+            .args(Self::defaults_write_args(udid, key, kind, value))
+            .output()?;
+
+        if !output.status.success() {
+            return Err(SimctlError::from_output(&output));
+        }
+        Ok(())
+    }
+
+    /// Builds the `xcrun` arguments that write a global default on a device.
+    fn defaults_write_args<'a>(
+        udid: &'a str,
+        key: &'a str,
+        kind: &'a str,
+        value: &'a str,
+    ) -> [&'a str; 9] {
+        [
+            "simctl",
+            "spawn",
+            udid,
+            "defaults",
+            "write",
+            ".GlobalPreferences",
+            key,
+            kind,
+            value,
+        ]
+    }
+
+    fn run_ui(udid: &str, option: &str, value: &str) -> Result<(), SimctlError> {
+        let output = Command::new("xcrun")
+            .args(Self::ui_args(udid, option, value))
+            .output()?;
+
+        if !output.status.success() {
+            return Err(SimctlError::from_output(&output));
+        }
+        Ok(())
+    }
+
+    /// Builds the `xcrun` arguments for a `simctl ui` option.
+    fn ui_args<'a>(udid: &'a str, option: &'a str, value: &'a str) -> [&'a str; 5] {
+        ["simctl", "ui", udid, option, value]
+    }
+
+    /// Replaces the contents of a simulator's pasteboard.
+    ///
+    /// Runs `xcrun simctl pbcopy <udid>` and writes `text` to its stdin as
+    /// UTF-8, so any Unicode text (including emoji) is copied verbatim. The
+    /// device must be booted.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::DeviceNotFound`] if no simulator has this UDID
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::CommandFailed`] if simctl returns an error
+    pub fn set_pasteboard(udid: &str, text: &str) -> Result<(), SimctlError> {
+        let mut command = Command::new("xcrun");
+        command.args(Self::pbcopy_args(udid));
+        Self::run_with_input(command, text.as_bytes())
+    }
+
+    /// Builds the `xcrun` arguments for `simctl pbcopy`.
+    fn pbcopy_args(udid: &str) -> [&str; 3] {
+        ["simctl", "pbcopy", udid]
+    }
+
+    /// Runs `command` with `input` on its stdin and waits for it to exit.
+    fn run_with_input(mut command: Command, input: &[u8]) -> Result<(), SimctlError> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // Dropping stdin closes the pipe, which ends the input
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(input)?;
+        }
+        let output = child.wait_with_output()?;
+
+        if !output.status.success() {
+            return Err(SimctlError::from_output(&output));
         }
         Ok(())
     }
@@ -223,7 +1121,7 @@ impl Simctl {
     ///
     /// # Errors
     ///
-    /// - [`SimctlError::Io`] if the command fails to execute
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
     /// - [`SimctlError::CommandFailed`] if simctl returns an error
     pub fn launch_app(udid: &str, bundle_id: &str) -> Result<(), SimctlError> {
         let output = Command::new("xcrun")
@@ -231,9 +1129,7 @@ impl Simctl {
             .output()?;
 
         if !output.status.success() {
-            return Err(SimctlError::CommandFailed(
-                String::from_utf8_lossy(&output.stderr).to_string(),
-            ));
+            return Err(SimctlError::from_output(&output));
         }
         Ok(())
     }
@@ -252,7 +1148,7 @@ impl Simctl {
     ///
     /// # Errors
     ///
-    /// - [`SimctlError::Io`] if the command fails to execute
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
     /// - [`SimctlError::CommandFailed`] if simctl returns an error (except for "not running")
     pub fn terminate_app(udid: &str, bundle_id: &str) -> Result<(), SimctlError> {
         let output = Command::new("xcrun")
@@ -263,17 +1159,81 @@ impl Simctl {
             let stderr = String::from_utf8_lossy(&output.stderr);
             // App not running is not an error
             if !stderr.contains("not running") {
-                return Err(SimctlError::CommandFailed(stderr.to_string()));
+                return Err(SimctlError::from_failure(output.status.code(), &stderr));
             }
         }
         Ok(())
     }
 
+    /// Resolves the on-disk path of an installed app's container.
+    ///
+    /// Runs `xcrun simctl get_app_container <udid> <bundle_id> <kind>`. For
+    /// [`ContainerKind::Groups`], returns the first app group's container.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::AppNotInstalled`] if the app is not on the simulator
+    /// - [`SimctlError::DeviceNotFound`] if no simulator has this UDID
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::ParseError`] if simctl prints no path
+    pub fn app_container(
+        udid: &str,
+        bundle_id: &str,
+        kind: ContainerKind,
+    ) -> Result<PathBuf, SimctlError> {
+        let output = Command::new("xcrun")
+            .args([
+                "simctl",
+                "get_app_container",
+                udid,
+                bundle_id,
+                kind.as_str(),
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(Self::container_error(
+                bundle_id,
+                SimctlError::from_output(&output),
+            ));
+        }
+
+        Self::parse_container_path(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// simctl reports a missing app as a generic failure, so any failure not
+    /// otherwise classified means the app is not installed.
+    fn container_error(bundle_id: &str, error: SimctlError) -> SimctlError {
+        match error {
+            SimctlError::CommandFailed { .. } => {
+                SimctlError::AppNotInstalled(bundle_id.to_string())
+            }
+            e => e,
+        }
+    }
+
+    /// Extracts the path from `simctl get_app_container` output.
+    ///
+    /// The output is the path on one line, or `<group id>\t<path>` lines for
+    /// app groups; the first path is returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::ParseError`] if the output has no path
+    pub fn parse_container_path(output: &str) -> Result<PathBuf, SimctlError> {
+        output
+            .lines()
+            .map(|line| line.split_once('\t').map_or(line, |(_, path)| path).trim())
+            .find(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .ok_or_else(|| SimctlError::ParseError("no container path in output".to_string()))
+    }
+
     /// Lists installed apps on a booted simulator.
     ///
-    /// Runs `xcrun simctl listapps <udid>` and pipes the output through
-    /// `plutil -convert json -o - -- -` to convert from plist to JSON.
-    /// Returns apps sorted with User apps first, then alphabetical by bundle_id.
+    /// Runs `xcrun simctl listapps <udid>` and parses the property list it
+    /// prints. Returns apps sorted with User apps first, then alphabetical by
+    /// bundle_id.
     ///
     /// # Arguments
     ///
@@ -281,49 +1241,43 @@ impl Simctl {
     ///
     /// # Errors
     ///
-    /// - [`SimctlError::Io`] if the command fails to execute
-    /// - [`SimctlError::CommandFailed`] if simctl or plutil returns an error
-    /// - [`SimctlError::JsonParse`] if the JSON output cannot be parsed
+    /// - [`SimctlError::DeviceNotFound`] if no simulator has this UDID
+    /// - [`SimctlError::XcodeNotFound`] if `xcrun` is unavailable
+    /// - [`SimctlError::CommandFailed`] if simctl returns an error
+    /// - [`SimctlError::ParseError`] if the output is not a valid plist
     pub fn list_apps(udid: &str) -> Result<Vec<InstalledApp>, SimctlError> {
-        let simctl_output = Command::new("xcrun")
+        let output = Command::new("xcrun")
             .args(["simctl", "listapps", udid])
             .output()?;
 
-        if !simctl_output.status.success() {
-            return Err(SimctlError::CommandFailed(
-                String::from_utf8_lossy(&simctl_output.stderr).to_string(),
-            ));
+        if !output.status.success() {
+            return Err(SimctlError::from_output(&output));
         }
 
-        let plutil_output = Command::new("plutil")
-            .args(["-convert", "json", "-o", "-", "--", "-"])
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .spawn()
-            .and_then(|mut child| {
-                use std::io::Write;
-                if let Some(ref mut stdin) = child.stdin {
-                    stdin.write_all(&simctl_output.stdout)?;
-                }
-                // Drop stdin to signal EOF
-                child.stdin.take();
-                child.wait_with_output()
-            })?;
-
-        if !plutil_output.status.success() {
-            return Err(SimctlError::CommandFailed(
-                String::from_utf8_lossy(&plutil_output.stderr).to_string(),
-            ));
-        }
+        Self::parse_app_plist(&output.stdout)
+    }
 
-        Self::parse_app_list(&plutil_output.stdout)
+    /// Parses `simctl listapps` output into a sorted vector of installed apps.
+    ///
+    /// simctl prints an old-style (OpenStep) plist; XML and binary plists are
+    /// accepted too. The plist is a dictionary keyed by bundle ID, where each
+    /// value contains `CFBundleIdentifier`, `CFBundleDisplayName`, and
+    /// `ApplicationType`.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::ParseError`] if the plist is invalid
+    pub fn parse_app_plist(plist: &[u8]) -> Result<Vec<InstalledApp>, SimctlError> {
+        let entries: HashMap<String, AppEntry> = plist::from_bytes(plist)
+            .map_err(|e| SimctlError::ParseError(format!("invalid app list plist: {e}")))?;
+        Ok(Self::collect_apps(entries))
     }
 
-    /// Parses app list JSON from plutil output into a sorted vector of installed apps.
+    /// Parses app list JSON (`listapps` converted by `plutil`) into a sorted
+    /// vector of installed apps.
     ///
-    /// The JSON is a dictionary keyed by bundle ID, where each value contains
-    /// `CFBundleIdentifier`, `CFBundleDisplayName`, and `ApplicationType`.
+    /// The JSON has the same layout as the plist read by
+    /// [`parse_app_plist`](Self::parse_app_plist).
     ///
     /// # Arguments
     ///
@@ -331,67 +1285,144 @@ impl Simctl {
     ///
     /// # Errors
     ///
-    /// - [`SimctlError::JsonParse`] if the JSON is invalid
+    /// - [`SimctlError::ParseError`] if the JSON is invalid
     pub fn parse_app_list(json: &[u8]) -> Result<Vec<InstalledApp>, SimctlError> {
-        let map: std::collections::HashMap<String, serde_json::Value> =
-            serde_json::from_slice(json)?;
+        let entries: HashMap<String, AppEntry> = serde_json::from_slice(json)?;
+        Ok(Self::collect_apps(entries))
+    }
 
-        let mut apps: Vec<InstalledApp> = map
+    /// Converts parsed `listapps` entries into apps, User apps first, then
+    /// alphabetical by bundle_id. Entries without a bundle ID are skipped.
+    fn collect_apps(entries: HashMap<String, AppEntry>) -> Vec<InstalledApp> {
+        let mut apps: Vec<InstalledApp> = entries
             .into_values()
             .filter_map(|entry| {
-                let bundle_id = entry.get("CFBundleIdentifier")?.as_str()?.to_string();
-                let display_name = entry
-                    .get("CFBundleDisplayName")
-                    .and_then(|v| v.as_str())
-                    .or_else(|| entry.get("CFBundleName").and_then(|v| v.as_str()))
-                    .unwrap_or("")
-                    .to_string();
-                let app_type = entry
-                    .get("ApplicationType")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown")
-                    .to_string();
                 Some(InstalledApp {
-                    bundle_id,
-                    display_name,
-                    app_type,
+                    bundle_id: entry.bundle_id?,
+                    display_name: entry.display_name.or(entry.name).unwrap_or_default(),
+                    app_type: entry.app_type.unwrap_or_else(|| "Unknown".to_string()),
                 })
             })
             .collect();
 
-        // Sort: User apps first, then alphabetical by bundle_id
         apps.sort_by(|a, b| {
-            let a_is_user = a.app_type == "User";
-            let b_is_user = b.app_type == "User";
-            b_is_user
-                .cmp(&a_is_user)
+            b.is_user()
+                .cmp(&a.is_user())
                 .then(a.bundle_id.cmp(&b.bundle_id))
         });
+        apps
+    }
+
+    /// Parses device list JSON into a flat vector of devices.
+    ///
+    /// This method is exposed primarily for testing purposes. It takes
+    /// raw JSON bytes (as returned by `simctl list devices -j`) and
+    /// returns a flattened list of all devices.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - Raw JSON bytes from simctl output
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<SimulatorDevice>` containing all devices from the JSON.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::ParseError`] if the JSON is invalid or has unexpected structure
+    pub fn parse_device_list(json: &[u8]) -> Result<Vec<SimulatorDevice>, SimctlError> {
+        let device_list: DeviceList = serde_json::from_slice(json)?;
+        let devices = device_list
+            .devices
+            .into_iter()
+            .flat_map(|(runtime, devices)| {
+                devices.into_iter().map(move |mut device| {
+                    device.runtime.get_or_insert_with(|| runtime.clone());
+                    device
+                })
+            })
+            .collect();
+        Ok(devices)
+    }
 
-        Ok(apps)
+    /// Parses `simctl list devicetypes -j` output.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::ParseError`] if the JSON is invalid or has unexpected structure
+    pub fn parse_device_types(json: &[u8]) -> Result<Vec<DeviceType>, SimctlError> {
+        let list: DeviceTypeList = serde_json::from_slice(json)?;
+        Ok(list.devicetypes)
+    }
+
+    /// Parses `simctl list runtimes -j` output.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::ParseError`] if the JSON is invalid or has unexpected structure
+    pub fn parse_runtimes(json: &[u8]) -> Result<Vec<Runtime>, SimctlError> {
+        let list: RuntimeList = serde_json::from_slice(json)?;
+        Ok(list.runtimes)
+    }
+
+    /// Returns the identifier of the device type matching `requested`.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::UnknownDeviceType`] if none matches
+    pub fn resolve_device_type(
+        requested: &str,
+        device_types: &[DeviceType],
+    ) -> Result<String, SimctlError> {
+        let candidates = device_types
+            .iter()
+            .map(|t| (t.name.as_str(), t.identifier.as_str()));
+        resolve_identifier(requested, candidates).map_err(|available| {
+            SimctlError::UnknownDeviceType {
+                requested: requested.to_string(),
+                available,
+            }
+        })
     }
 
-    /// Parses device list JSON into a flat vector of devices.
-    ///
-    /// This method is exposed primarily for testing purposes. It takes
-    /// raw JSON bytes (as returned by `simctl list devices -j`) and
-    /// returns a flattened list of all devices.
-    ///
-    /// # Arguments
+    /// Returns the identifier of the available runtime matching `requested`.
     ///
-    /// * `json` - Raw JSON bytes from simctl output
+    /// Runtimes simctl reports as unavailable are never matched.
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// A `Vec<SimulatorDevice>` containing all devices from the JSON.
+    /// - [`SimctlError::UnknownRuntime`] if none matches
+    pub fn resolve_runtime(requested: &str, runtimes: &[Runtime]) -> Result<String, SimctlError> {
+        let candidates = runtimes
+            .iter()
+            .filter(|r| r.is_available != Some(false))
+            .map(|r| (r.name.as_str(), r.identifier.as_str()));
+        resolve_identifier(requested, candidates).map_err(|available| SimctlError::UnknownRuntime {
+            requested: requested.to_string(),
+            available,
+        })
+    }
+
+    /// Extracts the UDID `simctl create` prints on success.
     ///
     /// # Errors
     ///
-    /// - [`SimctlError::JsonParse`] if the JSON is invalid or has unexpected structure
-    pub fn parse_device_list(json: &[u8]) -> Result<Vec<SimulatorDevice>, SimctlError> {
-        let device_list: DeviceList = serde_json::from_slice(json)?;
-        let devices: Vec<SimulatorDevice> = device_list.devices.into_values().flatten().collect();
-        Ok(devices)
+    /// - [`SimctlError::ParseError`] if the output is not a single UDID
+    pub fn parse_created_udid(stdout: &str) -> Result<String, SimctlError> {
+        let udid = stdout.trim();
+        let is_udid = udid.len() == 36
+            && udid.char_indices().all(|(i, c)| match i {
+                8 | 13 | 18 | 23 => c == '-',
+                _ => c.is_ascii_hexdigit(),
+            });
+        if is_udid {
+            Ok(udid.to_string())
+        } else {
+            Err(SimctlError::ParseError(format!(
+                "expected a UDID from simctl create, got {:?}",
+                udid
+            )))
+        }
     }
 
     /// Finds the first booted device in a list.
@@ -410,6 +1441,126 @@ impl Simctl {
     pub fn find_booted_device(devices: &[SimulatorDevice]) -> Option<&SimulatorDevice> {
         devices.iter().find(|d| d.state == "Booted")
     }
+
+    /// Finds every booted device in a list, in list order.
+    pub fn find_booted_devices(devices: &[SimulatorDevice]) -> Vec<&SimulatorDevice> {
+        devices.iter().filter(|d| d.state == "Booted").collect()
+    }
+}
+
+/// A running `simctl io recordVideo` process.
+///
+/// Returned by [`Simctl::start_recording`]. The recorder is killed when the
+/// handle is dropped, so it never outlives its owner.
+#[derive(Debug)]
+pub struct RecordingHandle {
+    child: Child,
+    path: PathBuf,
+}
+
+impl RecordingHandle {
+    /// Spawns `command` as the recorder for `path`.
+    pub(crate) fn spawn(mut command: Command, path: &Path) -> Result<Self, SimctlError> {
+        let child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let mut handle = Self {
+            child,
+            path: path.to_path_buf(),
+        };
+
+        let deadline = Instant::now() + RECORDING_STARTUP_CHECK;
+        while Instant::now() < deadline {
+            if let Some(status) = handle.child.try_wait()? {
+                return Err(SimctlError::from_failure(
+                    status.code(),
+                    &handle.read_stderr(),
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        Ok(handle)
+    }
+
+    /// The file being recorded.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The recorder's process ID.
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Stops the recording and waits for the video file to be written.
+    ///
+    /// Sends `SIGINT`, which makes simctl finalize the file, and kills the
+    /// recorder if it has not exited within [`RECORDING_STOP_TIMEOUT`].
+    /// Returns the path of the finished video.
+    ///
+    /// # Errors
+    ///
+    /// - [`SimctlError::CommandFailed`] if the recorder failed or had to be killed
+    /// - [`SimctlError::Io`] if the process could not be signalled
+    pub fn stop(self) -> Result<PathBuf, SimctlError> {
+        self.stop_within(RECORDING_STOP_TIMEOUT)
+    }
+
+    /// [`stop`](Self::stop) with a custom grace period.
+    pub(crate) fn stop_within(mut self, timeout: Duration) -> Result<PathBuf, SimctlError> {
+        if self.child.try_wait()?.is_none() {
+            // SAFETY: `kill` has no memory-safety preconditions; the pid is
+            // our own unreaped child, so it cannot have been reused.
+            if unsafe { libc::kill(self.child.id() as libc::pid_t, libc::SIGINT) } != 0 {
+                return Err(SimctlError::Io(std::io::Error::last_os_error()));
+            }
+        }
+
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            if let Some(status) = self.child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                self.kill();
+                return Err(SimctlError::CommandFailed {
+                    code: None,
+                    stderr: format!("recording did not stop within {:?} and was killed", timeout),
+                });
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        };
+
+        if !status.success() {
+            return Err(SimctlError::from_failure(
+                status.code(),
+                &self.read_stderr(),
+            ));
+        }
+        Ok(self.path.clone())
+    }
+
+    /// Kills the recorder without finalizing the file.
+    pub fn kill(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+
+    fn read_stderr(&mut self) -> String {
+        let mut stderr = String::new();
+        if let Some(mut pipe) = self.child.stderr.take() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        stderr
+    }
+}
+
+impl Drop for RecordingHandle {
+    fn drop(&mut self) {
+        self.kill();
+    }
 }
 
 #[cfg(test)]
@@ -444,6 +1595,75 @@ mod tests {
         }
     }"#;
 
+    const TWO_BOOTED_DEVICES: &str = r#"{
+        "devices": {
+            "com.apple.CoreSimulator.SimRuntime.iOS-17-0": [
+                {
+                    "udid": "A1B2C3D4-E5F6-7890-ABCD-EF1234567890",
+                    "name": "iPhone 15 Pro",
+                    "state": "Booted"
+                },
+                {
+                    "udid": "B2C3D4E5-F6A7-8901-BCDE-F12345678901",
+                    "name": "iPhone 15",
+                    "state": "Shutdown"
+                }
+            ],
+            "com.apple.CoreSimulator.SimRuntime.iOS-16-4": [
+                {
+                    "udid": "C3D4E5F6-A7B8-9012-CDEF-123456789012",
+                    "name": "iPhone 14",
+                    "state": "Booted"
+                }
+            ]
+        }
+    }"#;
+
+    // Captured from `xcrun simctl list devices --json` with Xcode 16.2
+    const XCODE_16_DEVICE_LIST: &str = r#"{
+      "devices" : {
+        "com.apple.CoreSimulator.SimRuntime.iOS-18-2" : [
+          {
+            "lastBootedAt" : "2025-01-14T09:12:44Z",
+            "dataPath" : "/Users/dev/Library/Developer/CoreSimulator/Devices/5C2E6F1A-8E4B-4F0A-9D3C-2B7A1E6D9F40/data",
+            "dataPathSize" : 2311843840,
+            "logPath" : "/Users/dev/Library/Logs/CoreSimulator/5C2E6F1A-8E4B-4F0A-9D3C-2B7A1E6D9F40",
+            "udid" : "5C2E6F1A-8E4B-4F0A-9D3C-2B7A1E6D9F40",
+            "isAvailable" : true,
+            "logPathSize" : 479232,
+            "deviceTypeIdentifier" : "com.apple.CoreSimulator.SimDeviceType.iPhone-16-Pro",
+            "state" : "Booted",
+            "name" : "iPhone 16 Pro"
+          }
+        ],
+        "com.apple.CoreSimulator.SimRuntime.iOS-16-4" : [
+          {
+            "dataPath" : "/Users/dev/Library/Developer/CoreSimulator/Devices/0B8D5E2C-7A41-4C6F-B1E9-3D2F8A6C4E17/data",
+            "dataPathSize" : 18014208,
+            "logPath" : "/Users/dev/Library/Logs/CoreSimulator/0B8D5E2C-7A41-4C6F-B1E9-3D2F8A6C4E17",
+            "udid" : "0B8D5E2C-7A41-4C6F-B1E9-3D2F8A6C4E17",
+            "isAvailable" : false,
+            "availabilityError" : "runtime profile not found using \"System\" match policy",
+            "deviceTypeIdentifier" : "com.apple.CoreSimulator.SimDeviceType.iPhone-14",
+            "state" : "Shutdown",
+            "name" : "iPhone 14"
+          }
+        ],
+        "com.apple.CoreSimulator.SimRuntime.watchOS-11-2" : [
+          {
+            "dataPath" : "/Users/dev/Library/Developer/CoreSimulator/Devices/9E3A1C7B-2D5F-4B8E-A6C0-F41D7E2B5A93/data",
+            "dataPathSize" : 13107200,
+            "logPath" : "/Users/dev/Library/Logs/CoreSimulator/9E3A1C7B-2D5F-4B8E-A6C0-F41D7E2B5A93",
+            "udid" : "9E3A1C7B-2D5F-4B8E-A6C0-F41D7E2B5A93",
+            "isAvailable" : true,
+            "deviceTypeIdentifier" : "com.apple.CoreSimulator.SimDeviceType.Apple-Watch-Series-10-46mm",
+            "state" : "Shutdown",
+            "name" : "Apple Watch Series 10 (46mm)"
+          }
+        ]
+      }
+    }"#;
+
     const EMPTY_DEVICE_LIST: &str = r#"{"devices": {}}"#;
 
     const NO_BOOTED_DEVICES: &str = r#"{
@@ -486,8 +1706,8 @@ mod tests {
 
         assert!(result.is_err());
         match result {
-            Err(SimctlError::JsonParse(_)) => {} // Expected
-            Err(e) => panic!("Expected JsonParse error, got: {:?}", e),
+            Err(SimctlError::ParseError(_)) => {} // Expected
+            Err(e) => panic!("Expected ParseError, got: {:?}", e),
             Ok(_) => panic!("Expected error, got Ok"),
         }
     }
@@ -520,6 +1740,117 @@ mod tests {
         assert!(booted.is_none());
     }
 
+    #[test]
+    fn test_parse_xcode_16_device_list() {
+        let devices = Simctl::parse_device_list(XCODE_16_DEVICE_LIST.as_bytes()).unwrap();
+        assert_eq!(devices.len(), 3);
+
+        let iphone = devices.iter().find(|d| d.name == "iPhone 16 Pro").unwrap();
+        assert_eq!(iphone.udid, "5C2E6F1A-8E4B-4F0A-9D3C-2B7A1E6D9F40");
+        assert_eq!(iphone.state, "Booted");
+        assert!(iphone.is_available);
+        assert_eq!(
+            iphone.device_type.as_deref(),
+            Some("com.apple.CoreSimulator.SimDeviceType.iPhone-16-Pro")
+        );
+        assert_eq!(
+            iphone.runtime.as_deref(),
+            Some("com.apple.CoreSimulator.SimRuntime.iOS-18-2")
+        );
+        assert_eq!(iphone.runtime_name().as_deref(), Some("iOS 18.2"));
+
+        let stale = devices.iter().find(|d| d.name == "iPhone 14").unwrap();
+        assert!(!stale.is_available);
+        assert_eq!(stale.runtime_name().as_deref(), Some("iOS 16.4"));
+
+        let watch = devices
+            .iter()
+            .find(|d| d.name.starts_with("Apple Watch"))
+            .unwrap();
+        assert_eq!(watch.runtime_name().as_deref(), Some("watchOS 11.2"));
+    }
+
+    fn filtered(filter: DeviceFilter) -> Vec<String> {
+        let devices = Simctl::parse_device_list(XCODE_16_DEVICE_LIST.as_bytes()).unwrap();
+        filter.apply(devices).into_iter().map(|d| d.name).collect()
+    }
+
+    #[test]
+    fn test_device_filter_default_keeps_everything() {
+        assert_eq!(filtered(DeviceFilter::default()).len(), 3);
+    }
+
+    #[test]
+    fn test_device_filter_by_runtime() {
+        let by = |runtime: &str| {
+            filtered(DeviceFilter {
+                runtime: Some(runtime.to_string()),
+                ..Default::default()
+            })
+        };
+        assert_eq!(by("iOS-18"), ["iPhone 16 Pro"]);
+        assert_eq!(by("ios 16.4"), ["iPhone 14"]);
+        assert_eq!(by("iOS").len(), 2);
+        assert_eq!(by("watchOS-11-2"), ["Apple Watch Series 10 (46mm)"]);
+        assert!(by("iOS-1").is_empty());
+        assert!(by("tvOS").is_empty());
+    }
+
+    #[test]
+    fn test_device_filter_available_and_booted() {
+        let available = filtered(DeviceFilter {
+            available_only: true,
+            ..Default::default()
+        });
+        assert_eq!(available, ["iPhone 16 Pro", "Apple Watch Series 10 (46mm)"]);
+        let booted = filtered(DeviceFilter {
+            booted_only: true,
+            ..Default::default()
+        });
+        assert_eq!(booted, ["iPhone 16 Pro"]);
+    }
+
+    #[test]
+    fn test_device_filters_combine() {
+        let ios_available = filtered(DeviceFilter {
+            runtime: Some("iOS".to_string()),
+            available_only: true,
+            booted_only: false,
+        });
+        assert_eq!(ios_available, ["iPhone 16 Pro"]);
+        let watch_booted = filtered(DeviceFilter {
+            runtime: Some("watchOS".to_string()),
+            available_only: true,
+            booted_only: true,
+        });
+        assert!(watch_booted.is_empty());
+    }
+
+    #[test]
+    fn test_devices_without_is_available_read_as_available() {
+        let devices = Simctl::parse_device_list(NO_BOOTED_DEVICES.as_bytes()).unwrap();
+        assert!(devices[0].is_available);
+    }
+
+    #[test]
+    fn test_find_booted_devices_with_two_booted() {
+        let devices = Simctl::parse_device_list(TWO_BOOTED_DEVICES.as_bytes()).unwrap();
+        let udids: Vec<&str> = Simctl::find_booted_devices(&devices)
+            .iter()
+            .map(|d| d.udid.as_str())
+            .collect();
+
+        // Runtimes are ordered, so the iOS 16.4 device comes first
+        assert_eq!(
+            udids,
+            [
+                "C3D4E5F6-A7B8-9012-CDEF-123456789012",
+                "A1B2C3D4-E5F6-7890-ABCD-EF1234567890"
+            ]
+        );
+        assert_eq!(Simctl::find_booted_device(&devices).unwrap().udid, udids[0]);
+    }
+
     #[test]
     fn test_find_booted_device_empty_list() {
         let devices: Vec<SimulatorDevice> = vec![];
@@ -566,8 +1897,12 @@ mod tests {
 
     #[test]
     fn test_simctl_error_display() {
-        let cmd_err = SimctlError::CommandFailed("test error".to_string());
+        let cmd_err = SimctlError::CommandFailed {
+            code: Some(1),
+            stderr: "test error".to_string(),
+        };
         assert!(cmd_err.to_string().contains("test error"));
+        assert!(cmd_err.to_string().contains("exit code 1"));
 
         let no_booted = SimctlError::NoBootedSimulator;
         assert!(no_booted.to_string().contains("No booted simulator"));
@@ -581,9 +1916,8 @@ mod tests {
         // Should fail because the simulator doesn't exist
         assert!(result.is_err());
         match result {
-            Err(SimctlError::CommandFailed(msg)) => {
-                // The error message should indicate the device wasn't found
-                assert!(!msg.is_empty() || msg.is_empty()); // Accept any error message
+            Err(SimctlError::DeviceNotFound(device)) => {
+                assert_eq!(device, "invalid-udid-that-does-not-exist");
             }
             Err(e) => {
                 // IO errors are also acceptable (e.g., if simctl behaves differently)
@@ -625,6 +1959,60 @@ mod tests {
         assert_eq!(apps[2].bundle_id, "com.apple.mobilesafari");
     }
 
+    #[test]
+    fn test_parse_app_plist_fixture() {
+        let plist = include_bytes!("../tests/fixtures/simctl_listapps.plist");
+        let apps = Simctl::parse_app_plist(plist).expect("Should parse listapps output");
+
+        let summary: Vec<_> = apps
+            .iter()
+            .map(|a| {
+                (
+                    a.bundle_id.as_str(),
+                    a.display_name.as_str(),
+                    a.app_type.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("com.example.todo", "Todo \u{2013} Lists", "User"),
+                ("com.apple.Preferences", "Settings", "System"),
+                ("com.apple.mobilesafari", "Safari", "System"),
+                // No display name, so CFBundleName is used
+                ("com.apple.webapp", "Web App", "System"),
+            ]
+        );
+        assert!(apps[0].is_user());
+        assert!(!apps[1].is_user());
+    }
+
+    #[test]
+    fn test_parse_app_plist_xml() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>com.example.myapp</key>
+    <dict>
+        <key>ApplicationType</key><string>User</string>
+        <key>CFBundleIdentifier</key><string>com.example.myapp</string>
+        <key>CFBundleName</key><string>MyApp</string>
+    </dict>
+</dict>
+</plist>"#;
+        let apps = Simctl::parse_app_plist(xml).unwrap();
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].display_name, "MyApp");
+    }
+
+    #[test]
+    fn test_parse_app_plist_invalid() {
+        let result = Simctl::parse_app_plist(b"{ \"unterminated\" = ");
+        assert!(matches!(result, Err(SimctlError::ParseError(_))));
+    }
+
     #[test]
     fn test_parse_app_list_empty() {
         let apps = Simctl::parse_app_list(b"{}").expect("Should parse empty object");
@@ -651,10 +2039,463 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_container_path_trims_whitespace() {
+        let output = "  /Users/me/Library/Developer/CoreSimulator/Devices/A1B2/data/Containers/Data/Application/C3D4 \n\n";
+        assert_eq!(
+            Simctl::parse_container_path(output).unwrap(),
+            Path::new(
+                "/Users/me/Library/Developer/CoreSimulator/Devices/A1B2/data/Containers/Data/Application/C3D4"
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_container_path_app_groups() {
+        let output = "group.com.example.shared\t/Devices/A1B2/data/Containers/Shared/AppGroup/E5F6\n\
+                      group.com.example.other\t/Devices/A1B2/data/Containers/Shared/AppGroup/0789\n";
+        assert_eq!(
+            Simctl::parse_container_path(output).unwrap(),
+            Path::new("/Devices/A1B2/data/Containers/Shared/AppGroup/E5F6")
+        );
+    }
+
+    #[test]
+    fn test_parse_container_path_empty() {
+        assert!(matches!(
+            Simctl::parse_container_path(" \n"),
+            Err(SimctlError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_container_error_for_missing_app() {
+        let stderr = "An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=2):\nNo such file or directory\n";
+        let err = Simctl::container_error(
+            "com.example.nope",
+            SimctlError::from_failure(Some(2), stderr),
+        );
+        assert!(
+            matches!(err, SimctlError::AppNotInstalled(ref id) if id == "com.example.nope"),
+            "{err:?}"
+        );
+
+        // Other failures keep their classification
+        let err = Simctl::container_error(
+            "com.example.nope",
+            SimctlError::from_failure(Some(148), "Invalid device: NOPE\n"),
+        );
+        assert!(matches!(err, SimctlError::DeviceNotFound(_)), "{err:?}");
+    }
+
+    #[test]
+    fn test_container_kind_as_str() {
+        assert_eq!(ContainerKind::App.as_str(), "app");
+        assert_eq!(ContainerKind::Data.as_str(), "data");
+        assert_eq!(ContainerKind::Groups.as_str(), "groups");
+    }
+
     #[test]
     fn test_boot_with_invalid_udid() {
         let result = Simctl::boot("invalid-udid-that-does-not-exist");
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_classify_already_booted() {
+        let stderr = "An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):\nUnable to boot device in current state: Booted\n";
+        assert!(matches!(
+            SimctlError::from_failure(Some(149), stderr),
+            SimctlError::AlreadyBooted
+        ));
+    }
+
+    #[test]
+    fn test_classify_already_shutdown() {
+        let stderr = "An error was encountered processing the command (domain=com.apple.CoreSimulator.SimError, code=405):\nUnable to shutdown device in current state: Shutdown\n";
+        assert!(matches!(
+            SimctlError::from_failure(Some(149), stderr),
+            SimctlError::AlreadyShutdown
+        ));
+    }
+
+    #[test]
+    fn test_shutdown_is_idempotent() {
+        assert!(Simctl::ignore_already_shutdown(Err(SimctlError::AlreadyShutdown)).is_ok());
+        assert!(Simctl::ignore_already_shutdown(Ok(())).is_ok());
+        // Other failures are still reported
+        let err = Simctl::ignore_already_shutdown(Err(SimctlError::from_failure(
+            Some(148),
+            "Invalid device: NOPE\n",
+        )));
+        assert!(
+            matches!(err, Err(SimctlError::DeviceNotFound(_))),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn test_shutdown_with_invalid_udid() {
+        assert!(Simctl::shutdown("invalid-udid-that-does-not-exist").is_err());
+    }
+
+    const SAMPLE_DEVICE_TYPES: &str = r#"{
+        "devicetypes": [
+            { "name": "iPhone 15", "identifier": "com.apple.CoreSimulator.SimDeviceType.iPhone-15", "productFamily": "iPhone" },
+            { "name": "iPad Air (5th generation)", "identifier": "com.apple.CoreSimulator.SimDeviceType.iPad-Air-5th-generation" }
+        ]
+    }"#;
+
+    const SAMPLE_RUNTIMES: &str = r#"{
+        "runtimes": [
+            { "name": "iOS 17.5", "identifier": "com.apple.CoreSimulator.SimRuntime.iOS-17-5", "version": "17.5", "isAvailable": true },
+            { "name": "iOS 16.4", "identifier": "com.apple.CoreSimulator.SimRuntime.iOS-16-4", "version": "16.4", "isAvailable": false }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_created_udid() {
+        assert_eq!(
+            Simctl::parse_created_udid("7F1A2B3C-4D5E-6F70-8192-A3B4C5D6E7F8\n").unwrap(),
+            "7F1A2B3C-4D5E-6F70-8192-A3B4C5D6E7F8"
+        );
+        for bad in ["", "not-a-udid\n", "7F1A2B3C4D5E6F708192A3B4C5D6E7F8XXXX"] {
+            assert!(
+                matches!(
+                    Simctl::parse_created_udid(bad),
+                    Err(SimctlError::ParseError(_))
+                ),
+                "{bad:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_device_type_accepts_short_id_and_name() {
+        let types = Simctl::parse_device_types(SAMPLE_DEVICE_TYPES.as_bytes()).unwrap();
+        let expected = "com.apple.CoreSimulator.SimDeviceType.iPhone-15";
+        for requested in ["iPhone-15", "iphone 15", expected] {
+            assert_eq!(
+                Simctl::resolve_device_type(requested, &types).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_device_type_lists_options_on_mismatch() {
+        let types = Simctl::parse_device_types(SAMPLE_DEVICE_TYPES.as_bytes()).unwrap();
+        let err = Simctl::resolve_device_type("iPhone-99", &types).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown device type 'iPhone-99'; available: iPhone-15, iPad-Air-5th-generation"
+        );
+    }
+
+    #[test]
+    fn test_resolve_runtime_skips_unavailable() {
+        let runtimes = Simctl::parse_runtimes(SAMPLE_RUNTIMES.as_bytes()).unwrap();
+        assert_eq!(
+            Simctl::resolve_runtime("iOS-17-5", &runtimes).unwrap(),
+            "com.apple.CoreSimulator.SimRuntime.iOS-17-5"
+        );
+        match Simctl::resolve_runtime("iOS 16.4", &runtimes) {
+            Err(SimctlError::UnknownRuntime { available, .. }) => {
+                assert_eq!(available, ["iOS-17-5"])
+            }
+            other => panic!("Expected UnknownRuntime, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_classify_invalid_device() {
+        let stderr = "Invalid device: 00000000-0000-0000-0000-000000000000\n";
+        match SimctlError::from_failure(Some(148), stderr) {
+            SimctlError::DeviceNotFound(device) => {
+                assert_eq!(device, "00000000-0000-0000-0000-000000000000")
+            }
+            e => panic!("Expected DeviceNotFound, got: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_classify_xcode_not_found() {
+        let stderr =
+            "xcrun: error: unable to find utility \"simctl\", not a developer tool or in PATH\n";
+        assert!(matches!(
+            SimctlError::from_failure(Some(72), stderr),
+            SimctlError::XcodeNotFound
+        ));
+
+        let stderr = "xcrun: error: invalid active developer path (/Library/Developer/CommandLineTools), missing xcrun at: /Library/Developer/CommandLineTools/usr/bin/xcrun\n";
+        assert!(matches!(
+            SimctlError::from_failure(Some(1), stderr),
+            SimctlError::XcodeNotFound
+        ));
+    }
+
+    #[test]
+    fn test_classify_no_devices_booted() {
+        assert!(matches!(
+            SimctlError::from_failure(Some(149), "No devices are booted.\n"),
+            SimctlError::NoBootedSimulator
+        ));
+    }
+
+    #[test]
+    fn test_classify_other_failure() {
+        let stderr = "An error was encountered processing the command (domain=NSPOSIXErrorDomain, code=60):\nOperation timed out\n";
+        match SimctlError::from_failure(Some(60), stderr) {
+            SimctlError::CommandFailed { code, stderr } => {
+                assert_eq!(code, Some(60));
+                assert!(stderr.ends_with("Operation timed out"));
+            }
+            e => panic!("Expected CommandFailed, got: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_io_not_found_is_xcode_not_found() {
+        let err: SimctlError = std::io::Error::from(std::io::ErrorKind::NotFound).into();
+        assert!(matches!(err, SimctlError::XcodeNotFound));
+    }
+
+    #[test]
+    fn test_parse_bootstatus_already_booted() {
+        let output = "Monitoring boot status for iPhone 15 Pro (A1B2C3D4-E5F6-7890-ABCD-EF1234567890).\nDevice already booted, nothing to wait for.\n";
+        assert!(Simctl::parse_bootstatus(output));
+    }
+
+    #[test]
+    fn test_parse_bootstatus_finished() {
+        let output =
+            "Monitoring boot status for iPhone 15 (B2C3D4E5-F6A7-8901-BCDE-F12345678901).\n\
+            Waiting on Data Migration\n\
+            Waiting on System App\n\
+            Finished\n\
+            Device booted in 14.21 seconds\n";
+        assert!(Simctl::parse_bootstatus(output));
+    }
+
+    #[test]
+    fn test_parse_bootstatus_in_progress() {
+        let output =
+            "Monitoring boot status for iPhone 15 (B2C3D4E5-F6A7-8901-BCDE-F12345678901).\n\
+            Waiting on Data Migration\n";
+        assert!(!Simctl::parse_bootstatus(output));
+        assert!(!Simctl::parse_bootstatus(""));
+    }
+
+    #[test]
+    fn test_wait_for_boot_with_invalid_udid() {
+        let result =
+            Simctl::wait_for_boot("invalid-udid-that-does-not-exist", Duration::from_secs(5));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_appearance_ui_args() {
+        assert_eq!(
+            Simctl::ui_args("UDID-1", "appearance", Appearance::Dark.as_str()),
+            ["simctl", "ui", "UDID-1", "appearance", "dark"]
+        );
+        assert_eq!(
+            Simctl::ui_args(
+                "UDID-1",
+                "content_size",
+                ContentSize::AccessibilityLarge.as_str()
+            ),
+            [
+                "simctl",
+                "ui",
+                "UDID-1",
+                "content_size",
+                "accessibility-large"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_locale_defaults_write_args() {
+        let (languages, locale) = Simctl::locale_identifiers("fr", "FR").unwrap();
+        assert_eq!(
+            Simctl::defaults_write_args("UDID-1", "AppleLanguages", "-array", &languages),
+            [
+                "simctl",
+                "spawn",
+                "UDID-1",
+                "defaults",
+                "write",
+                ".GlobalPreferences",
+                "AppleLanguages",
+                "-array",
+                "fr-FR"
+            ]
+        );
+        assert_eq!(
+            Simctl::defaults_write_args("UDID-1", "AppleLocale", "-string", &locale)[6..],
+            ["AppleLocale", "-string", "fr_FR"]
+        );
+    }
+
+    #[test]
+    fn test_locale_identifiers_normalize_case() {
+        assert_eq!(
+            Simctl::locale_identifiers("FR", "fr").unwrap(),
+            ("fr-FR".to_string(), "fr_FR".to_string())
+        );
+        assert_eq!(
+            Simctl::locale_identifiers("zh-hans", "cn").unwrap(),
+            ("zh-Hans-CN".to_string(), "zh_Hans_CN".to_string())
+        );
+        assert_eq!(
+            Simctl::locale_identifiers("es", "419").unwrap(),
+            ("es-419".to_string(), "es_419".to_string())
+        );
+    }
+
+    #[test]
+    fn test_locale_identifiers_reject_malformed_subtags() {
+        for (language, region) in [
+            ("french", "FR"),
+            ("f", "FR"),
+            ("fr1", "FR"),
+            ("zh-Han", "CN"),
+            ("fr", "FRA"),
+            ("fr", "4"),
+            ("fr", ""),
+        ] {
+            let err = Simctl::locale_identifiers(language, region).unwrap_err();
+            assert!(
+                matches!(err, SimctlError::InvalidLocale(_)),
+                "{language} {region}: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_appearance_from_str() {
+        assert_eq!("dark".parse::<Appearance>(), Ok(Appearance::Dark));
+        assert_eq!(" Light ".parse::<Appearance>(), Ok(Appearance::Light));
+        let err = "sepia".parse::<Appearance>().unwrap_err();
+        assert!(err.contains("sepia"));
+        assert!(err.contains("'light' or 'dark'"));
+    }
+
+    #[test]
+    fn test_content_size_from_str() {
+        for size in ContentSize::ALL {
+            assert_eq!(size.as_str().parse::<ContentSize>(), Ok(size));
+        }
+        assert_eq!(
+            "Extra-Large".parse::<ContentSize>(),
+            Ok(ContentSize::ExtraLarge)
+        );
+        assert!("huge".parse::<ContentSize>().is_err());
+    }
+
+    #[test]
+    fn test_appearance_serde() {
+        assert_eq!(
+            serde_json::to_string(&Appearance::Dark).unwrap(),
+            r#""dark""#
+        );
+        assert_eq!(
+            serde_json::to_string(&ContentSize::ExtraExtraLarge).unwrap(),
+            r#""extra-extra-large""#
+        );
+    }
+
+    #[test]
+    fn test_pbcopy_args() {
+        assert_eq!(
+            Simctl::pbcopy_args("UDID-1"),
+            ["simctl", "pbcopy", "UDID-1"]
+        );
+    }
+
+    #[test]
+    fn pasteboard_input_is_written_as_utf8() {
+        let out =
+            std::env::temp_dir().join(format!("qorvex-pasteboard-{}.txt", std::process::id()));
+        let text = "long text with emojis 🎉 — naïve café, 日本語";
+        Simctl::run_with_input(stub_recorder(r#"cat > "$0""#, &out), text.as_bytes()).unwrap();
+
+        assert_eq!(std::fs::read(&out).unwrap(), text.as_bytes());
+        let _ = std::fs::remove_file(&out);
+    }
+
+    #[test]
+    fn pasteboard_failure_is_classified() {
+        let out = Path::new("/dev/null");
+        let script = "cat > /dev/null; echo 'Invalid device: NOPE' >&2; exit 1";
+        let err = Simctl::run_with_input(stub_recorder(script, out), b"text").unwrap_err();
+        assert!(
+            matches!(err, SimctlError::DeviceNotFound(ref d) if d == "NOPE"),
+            "{err:?}"
+        );
+    }
+
+    /// A recorder stand-in: runs `script` under `sh`, with the output path as `$0`.
+    fn stub_recorder(script: &str, out: &Path) -> Command {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script).arg(out);
+        command
+    }
+
+    fn temp_video(tag: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("qorvex-recording-{tag}-{}.mov", std::process::id()))
+    }
+
+    fn is_running(pid: u32) -> bool {
+        unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
+    }
+
+    #[test]
+    fn recording_stop_lets_recorder_finalize() {
+        let out = temp_video("stop");
+        let script = r#"trap 'echo finalized > "$0"; exit 0' INT; while :; do sleep 0.05; done"#;
+        let handle = RecordingHandle::spawn(stub_recorder(script, &out), &out).unwrap();
+        let pid = handle.pid();
+
+        assert_eq!(handle.stop().unwrap(), out);
+        assert_eq!(std::fs::read_to_string(&out).unwrap().trim(), "finalized");
+        assert!(!is_running(pid));
+        let _ = std::fs::remove_file(&out);
+    }
+
+    #[test]
+    fn recording_stop_kills_unresponsive_recorder() {
+        let out = temp_video("stuck");
+        let script = "trap '' INT; while :; do sleep 0.05; done";
+        let handle = RecordingHandle::spawn(stub_recorder(script, &out), &out).unwrap();
+        let pid = handle.pid();
+
+        let err = handle.stop_within(Duration::from_millis(200)).unwrap_err();
+        assert!(err.to_string().contains("was killed"), "{err}");
+        assert!(!is_running(pid));
+    }
+
+    #[test]
+    fn recording_drop_kills_recorder() {
+        let out = temp_video("drop");
+        let handle = RecordingHandle::spawn(stub_recorder("sleep 30", &out), &out).unwrap();
+        let pid = handle.pid();
+        assert!(is_running(pid));
+
+        drop(handle);
+        assert!(!is_running(pid));
+    }
+
+    #[test]
+    fn recording_that_exits_immediately_fails_to_start() {
+        let out = temp_video("bad");
+        let script = "echo 'Invalid device: NOPE' >&2; exit 1";
+        let err = RecordingHandle::spawn(stub_recorder(script, &out), &out).unwrap_err();
+        assert!(
+            matches!(err, SimctlError::DeviceNotFound(ref d) if d == "NOPE"),
+            "{err:?}"
+        );
+    }
 }
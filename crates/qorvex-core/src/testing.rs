@@ -0,0 +1,654 @@
+//! In-memory [`AutomationDriver`] for testing code built on qorvex.
+//!
+//! [`MockDriver`] needs no agent, simulator or TCP connection. It serves
+//! scripted element trees, values and screenshots, and records every call so a
+//! test can assert on what its code under test did. Because it implements the
+//! trait, it can back an [`ActionExecutor`](crate::executor::ActionExecutor)
+//! as well as code that calls the driver directly.
+//!
+//! # Scripting
+//!
+//! - **Trees** queue up with [`MockDriver::with_tree`]. Each
+//!   [`dump_tree`](AutomationDriver::dump_tree), including the ones the
+//!   default `find_element*` methods make, serves the next tree, and the last
+//!   one repeats, so a wait that polls sees the screen change.
+//! - **Element taps and value reads** resolve against the tree served last
+//!   (the first queued one before any dump) and fail with
+//!   [`DriverError::ElementNotFound`] when nothing matches.
+//! - **Values** set with [`MockDriver::with_value`] take precedence over the
+//!   elements' own `value`.
+//! - **Screenshots** queue up like trees.
+//! - **Failures** injected with [`MockDriver::fail_next`] are returned by the
+//!   next call, which is still recorded.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//! use qorvex_core::driver::AutomationDriver;
+//! use qorvex_core::testing::{element, MockCall, MockDriver};
+//!
+//! # async fn example() {
+//! let driver = Arc::new(
+//!     MockDriver::new()
+//!         .with_element(element("login-button", "Log In", "Button"))
+//!         .expect_tap("login-button"),
+//! );
+//!
+//! driver.tap_element("login-button").await.unwrap();
+//! driver.type_text("hello").await.unwrap();
+//!
+//! driver.verify();
+//! assert_eq!(
+//!     driver.calls().last(),
+//!     Some(&MockCall::TypeText { text: "hello".to_string() })
+//! );
+//! # }
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::driver::{
+    describe_selector, search_with_type, AutomationDriver, DriverBackend, DriverError,
+};
+use crate::element::{ElementFrame, UIElement};
+use crate::protocol::SpecialKey;
+
+/// A call made to a [`MockDriver`], with its arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockCall {
+    /// [`AutomationDriver::connect`].
+    Connect,
+    /// [`AutomationDriver::ping`].
+    Ping,
+    /// [`AutomationDriver::tap_location`].
+    TapLocation { x: i32, y: i32 },
+    /// [`AutomationDriver::tap_element`].
+    TapElement { identifier: String },
+    /// [`AutomationDriver::tap_by_label`].
+    TapByLabel { label: String },
+    /// [`AutomationDriver::tap_with_type`].
+    TapWithType {
+        selector: String,
+        by_label: bool,
+        element_type: String,
+    },
+    /// [`AutomationDriver::swipe`].
+    Swipe {
+        start_x: i32,
+        start_y: i32,
+        end_x: i32,
+        end_y: i32,
+        duration: Option<f64>,
+    },
+    /// [`AutomationDriver::long_press`].
+    LongPress { x: i32, y: i32, duration: f64 },
+    /// [`AutomationDriver::type_text`].
+    TypeText { text: String },
+    /// [`AutomationDriver::send_key`].
+    SendKey { key: SpecialKey },
+    /// [`AutomationDriver::dump_tree`].
+    DumpTree,
+    /// Any of the `get_*value*` methods.
+    GetValue {
+        selector: String,
+        by_label: bool,
+        element_type: Option<String>,
+    },
+    /// [`AutomationDriver::screenshot`].
+    Screenshot,
+}
+
+impl MockCall {
+    /// The selector of an element tap, or `None` for any other call.
+    pub fn tapped_selector(&self) -> Option<&str> {
+        match self {
+            MockCall::TapElement { identifier } => Some(identifier),
+            MockCall::TapByLabel { label } => Some(label),
+            MockCall::TapWithType { selector, .. } => Some(selector),
+            _ => None,
+        }
+    }
+}
+
+/// Builds an element for a scripted tree. Empty strings leave the field unset.
+///
+/// The element gets a 100x44 frame at the origin; set the public fields for
+/// anything else.
+pub fn element(identifier: &str, label: &str, element_type: &str) -> UIElement {
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+    UIElement {
+        identifier: non_empty(identifier),
+        label: non_empty(label),
+        value: None,
+        element_type: non_empty(element_type),
+        frame: Some(ElementFrame {
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 44.0,
+        }),
+        children: Vec::new(),
+        role: None,
+        hittable: Some(true),
+        enabled: Some(true),
+        selected: None,
+        focused: None,
+        placeholder: None,
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    /// Trees not yet served; the last one is never removed.
+    trees: VecDeque<Vec<UIElement>>,
+    /// The tree the last `dump_tree` served.
+    served: Option<Vec<UIElement>>,
+    values: HashMap<String, String>,
+    /// Screenshots not yet served; the last one is never removed.
+    screenshots: VecDeque<Vec<u8>>,
+    calls: Vec<MockCall>,
+    expected_taps: Vec<String>,
+    next_error: Option<DriverError>,
+    connected: bool,
+}
+
+impl MockState {
+    /// Takes the front of `queue`, or clones it if it is the last entry.
+    fn next_from<T: Clone>(queue: &mut VecDeque<T>) -> Option<T> {
+        if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        }
+    }
+
+    /// The tree element lookups resolve against.
+    fn current_tree(&self) -> &[UIElement] {
+        self.served
+            .as_deref()
+            .or(self.trees.front().map(Vec::as_slice))
+            .unwrap_or_default()
+    }
+
+    fn lookup(
+        &self,
+        selector: &str,
+        by_label: bool,
+        element_type: Option<&str>,
+    ) -> Result<UIElement, DriverError> {
+        search_with_type(self.current_tree(), selector, by_label, element_type).ok_or_else(|| {
+            DriverError::ElementNotFound {
+                selector: describe_selector(selector, by_label, element_type),
+            }
+        })
+    }
+}
+
+/// A scripted, recording [`AutomationDriver`]; see the [module docs](self).
+pub struct MockDriver {
+    backend: DriverBackend,
+    state: Mutex<MockState>,
+}
+
+impl Default for MockDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockDriver {
+    /// Creates a driver with an empty screen, reporting itself as the agent
+    /// backend. Not connected until [`connect`](AutomationDriver::connect).
+    pub fn new() -> Self {
+        Self {
+            backend: DriverBackend::Agent,
+            state: Mutex::new(MockState::default()),
+        }
+    }
+
+    /// Reports `backend` from [`AutomationDriver::backend`], for code that
+    /// branches on it.
+    pub fn with_backend(mut self, backend: DriverBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Queues `tree` to be served after the trees queued before it.
+    pub fn with_tree(self, tree: Vec<UIElement>) -> Self {
+        self.lock().trees.push_back(tree);
+        self
+    }
+
+    /// Adds `element` as a root of the last queued tree, queueing a tree if
+    /// there is none.
+    pub fn with_element(self, element: UIElement) -> Self {
+        {
+            let mut state = self.lock();
+            match state.trees.back_mut() {
+                Some(tree) => tree.push(element),
+                None => state.trees.push_back(vec![element]),
+            }
+        }
+        self
+    }
+
+    /// Answers value reads for `selector`, by identifier or label, with
+    /// `value`, whatever the tree holds.
+    pub fn with_value(self, selector: impl Into<String>, value: impl Into<String>) -> Self {
+        self.lock().values.insert(selector.into(), value.into());
+        self
+    }
+
+    /// Queues `bytes` to be served by a screenshot after the ones queued
+    /// before it.
+    pub fn with_screenshot(self, bytes: Vec<u8>) -> Self {
+        self.lock().screenshots.push_back(bytes);
+        self
+    }
+
+    /// Expects a tap on an element with `selector`, after any taps expected
+    /// before it. Checked by [`verify`](Self::verify).
+    pub fn expect_tap(self, selector: impl Into<String>) -> Self {
+        self.lock().expected_taps.push(selector.into());
+        self
+    }
+
+    /// Makes the next call fail with `error`.
+    pub fn fail_next(&self, error: DriverError) {
+        self.lock().next_error = Some(error);
+    }
+
+    /// Every call made so far, in order.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.lock().calls.clone()
+    }
+
+    /// Forgets the calls made so far.
+    pub fn clear_calls(&self) {
+        self.lock().calls.clear();
+    }
+
+    /// Panics unless the expected taps were made, in order. Other calls may
+    /// come between them.
+    pub fn verify(&self) {
+        let state = self.lock();
+        let mut tapped = state.calls.iter().filter_map(MockCall::tapped_selector);
+        for expected in &state.expected_taps {
+            if !tapped.any(|selector| selector == expected) {
+                let taps: Vec<_> = state
+                    .calls
+                    .iter()
+                    .filter_map(MockCall::tapped_selector)
+                    .collect();
+                panic!(
+                    "expected a tap on '{}' (expected taps: {:?}, actual taps: {:?})",
+                    expected, state.expected_taps, taps
+                );
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records `call` and returns the injected failure, if any.
+    fn record(&self, call: MockCall) -> Result<std::sync::MutexGuard<'_, MockState>, DriverError> {
+        let mut state = self.lock();
+        state.calls.push(call);
+        match state.next_error.take() {
+            Some(error) => Err(error),
+            None => Ok(state),
+        }
+    }
+
+    fn tap(
+        &self,
+        call: MockCall,
+        selector: &str,
+        by_label: bool,
+        element_type: Option<&str>,
+    ) -> Result<(), DriverError> {
+        self.record(call)?
+            .lookup(selector, by_label, element_type)
+            .map(drop)
+    }
+
+    fn value(
+        &self,
+        selector: &str,
+        by_label: bool,
+        element_type: Option<&str>,
+    ) -> Result<Option<String>, DriverError> {
+        let state = self.record(MockCall::GetValue {
+            selector: selector.to_string(),
+            by_label,
+            element_type: element_type.map(str::to_string),
+        })?;
+        if let Some(value) = state.values.get(selector) {
+            return Ok(Some(value.clone()));
+        }
+        Ok(state.lookup(selector, by_label, element_type)?.value)
+    }
+}
+
+#[async_trait]
+impl AutomationDriver for MockDriver {
+    fn backend(&self) -> Option<DriverBackend> {
+        Some(self.backend)
+    }
+
+    async fn connect(&mut self) -> Result<(), DriverError> {
+        self.record(MockCall::Connect)?.connected = true;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.lock().connected
+    }
+
+    async fn ping(&self) -> Result<Duration, DriverError> {
+        self.record(MockCall::Ping).map(drop)?;
+        Ok(Duration::ZERO)
+    }
+
+    async fn tap_location(&self, x: i32, y: i32) -> Result<(), DriverError> {
+        self.record(MockCall::TapLocation { x, y }).map(drop)
+    }
+
+    async fn tap_element(&self, identifier: &str) -> Result<(), DriverError> {
+        let call = MockCall::TapElement {
+            identifier: identifier.to_string(),
+        };
+        self.tap(call, identifier, false, None)
+    }
+
+    async fn tap_by_label(&self, label: &str) -> Result<(), DriverError> {
+        let call = MockCall::TapByLabel {
+            label: label.to_string(),
+        };
+        self.tap(call, label, true, None)
+    }
+
+    async fn tap_with_type(
+        &self,
+        selector: &str,
+        by_label: bool,
+        element_type: &str,
+    ) -> Result<(), DriverError> {
+        let call = MockCall::TapWithType {
+            selector: selector.to_string(),
+            by_label,
+            element_type: element_type.to_string(),
+        };
+        self.tap(call, selector, by_label, Some(element_type))
+    }
+
+    async fn swipe(
+        &self,
+        start_x: i32,
+        start_y: i32,
+        end_x: i32,
+        end_y: i32,
+        duration: Option<f64>,
+    ) -> Result<(), DriverError> {
+        self.record(MockCall::Swipe {
+            start_x,
+            start_y,
+            end_x,
+            end_y,
+            duration,
+        })
+        .map(drop)
+    }
+
+    async fn long_press(&self, x: i32, y: i32, duration: f64) -> Result<(), DriverError> {
+        self.record(MockCall::LongPress { x, y, duration })
+            .map(drop)
+    }
+
+    async fn type_text(&self, text: &str) -> Result<(), DriverError> {
+        self.record(MockCall::TypeText {
+            text: text.to_string(),
+        })
+        .map(drop)
+    }
+
+    async fn send_key(&self, key: SpecialKey) -> Result<(), DriverError> {
+        self.record(MockCall::SendKey { key }).map(drop)
+    }
+
+    async fn dump_tree(&self) -> Result<Vec<UIElement>, DriverError> {
+        let mut state = self.record(MockCall::DumpTree)?;
+        let tree = MockState::next_from(&mut state.trees).unwrap_or_default();
+        state.served = Some(tree.clone());
+        Ok(tree)
+    }
+
+    async fn get_element_value(&self, identifier: &str) -> Result<Option<String>, DriverError> {
+        self.value(identifier, false, None)
+    }
+
+    async fn get_element_value_by_label(&self, label: &str) -> Result<Option<String>, DriverError> {
+        self.value(label, true, None)
+    }
+
+    async fn get_value_with_type(
+        &self,
+        selector: &str,
+        by_label: bool,
+        element_type: &str,
+    ) -> Result<Option<String>, DriverError> {
+        self.value(selector, by_label, Some(element_type))
+    }
+
+    async fn screenshot(&self) -> Result<Vec<u8>, DriverError> {
+        let mut state = self.record(MockCall::Screenshot)?;
+        MockState::next_from(&mut state.screenshots).ok_or_else(|| {
+            DriverError::CommandFailed("MockDriver has no screenshot scripted".to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::action::{ActionType, MatchMode};
+    use crate::executor::ActionExecutor;
+
+    fn tap_action(selector: &str) -> ActionType {
+        ActionType::Tap {
+            selector: selector.to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_selector_finds_nothing() {
+        let driver = MockDriver::new().with_element(element("ok", "OK", "Button"));
+        assert!(driver.find_element("").await.unwrap().is_none());
+        assert!(driver.find_element_by_label("").await.unwrap().is_none());
+        assert!(driver.tap_element("").await.is_err());
+        // find-all still selects by type alone
+        assert_eq!(
+            driver
+                .find_all("", false, Some("Button"))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn records_calls_in_order() {
+        let mut driver = MockDriver::new().with_element(element("ok", "OK", "Button"));
+        assert!(!driver.is_connected());
+        driver.connect().await.unwrap();
+        assert!(driver.is_connected());
+
+        driver.tap_location(10, 20).await.unwrap();
+        driver.tap_by_label("OK").await.unwrap();
+        driver.send_key(SpecialKey::Return).await.unwrap();
+
+        assert_eq!(
+            driver.calls(),
+            vec![
+                MockCall::Connect,
+                MockCall::TapLocation { x: 10, y: 20 },
+                MockCall::TapByLabel {
+                    label: "OK".to_string()
+                },
+                MockCall::SendKey {
+                    key: SpecialKey::Return
+                },
+            ]
+        );
+        driver.clear_calls();
+        assert!(driver.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn serves_trees_in_order_and_repeats_the_last() {
+        let driver = MockDriver::new()
+            .with_tree(vec![element("spinner", "", "ActivityIndicator")])
+            .with_tree(vec![element("welcome", "Welcome", "StaticText")]);
+
+        assert!(driver.find_element("spinner").await.unwrap().is_some());
+        assert!(driver.find_element("spinner").await.unwrap().is_none());
+        assert!(driver.find_element("welcome").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn taps_resolve_against_the_current_tree() {
+        let driver = MockDriver::new().with_element(element("ok", "OK", "Button"));
+
+        driver.tap_with_type("OK", true, "Button").await.unwrap();
+        match driver.tap_element("cancel").await {
+            Err(DriverError::ElementNotFound { selector }) => assert_eq!(selector, "ID 'cancel'"),
+            other => panic!("expected ElementNotFound, got {other:?}"),
+        }
+        // The failed tap is still recorded
+        assert_eq!(driver.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn canned_values_override_the_tree() {
+        let mut field = element("email", "Email", "TextField");
+        field.value = Some("from tree".to_string());
+        let driver = MockDriver::new()
+            .with_element(field)
+            .with_value("Email", "canned");
+
+        assert_eq!(
+            driver.get_element_value("email").await.unwrap().as_deref(),
+            Some("from tree")
+        );
+        assert_eq!(
+            driver
+                .get_element_value_by_label("Email")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("canned")
+        );
+        assert!(driver.get_element_value("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn serves_screenshots_and_injected_failures() {
+        let driver = MockDriver::new();
+        assert!(driver.screenshot().await.is_err());
+
+        let driver = MockDriver::new()
+            .with_screenshot(vec![1])
+            .with_screenshot(vec![2]);
+        assert_eq!(driver.screenshot().await.unwrap(), vec![1]);
+        assert_eq!(driver.screenshot().await.unwrap(), vec![2]);
+        assert_eq!(driver.screenshot().await.unwrap(), vec![2]);
+
+        driver.fail_next(DriverError::ConnectionLost("reset".to_string()));
+        assert!(matches!(
+            driver.type_text("x").await,
+            Err(DriverError::ConnectionLost(_))
+        ));
+        driver.type_text("x").await.unwrap();
+        assert_eq!(
+            driver
+                .calls()
+                .iter()
+                .filter(|c| **c == MockCall::Screenshot)
+                .count(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_checks_expected_taps_in_order() {
+        let driver = MockDriver::new()
+            .with_tree(vec![element("a", "", ""), element("b", "", "")])
+            .expect_tap("a")
+            .expect_tap("b");
+        driver.tap_element("a").await.unwrap();
+        driver.type_text("between").await.unwrap();
+        driver.tap_element("b").await.unwrap();
+        driver.verify();
+
+        let out_of_order = MockDriver::new()
+            .with_tree(vec![element("a", "", ""), element("b", "", "")])
+            .expect_tap("a")
+            .expect_tap("b");
+        out_of_order.tap_element("b").await.unwrap();
+        out_of_order.tap_element("a").await.unwrap();
+        let panic =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| out_of_order.verify()));
+        assert!(panic.is_err());
+    }
+
+    #[tokio::test]
+    async fn backs_an_executor() {
+        let driver = Arc::new(
+            MockDriver::new()
+                .with_element(element("login", "Log In", "Button"))
+                .with_value("login", "ready")
+                .expect_tap("login"),
+        );
+        let executor = ActionExecutor::new(driver.clone());
+
+        assert!(executor.execute(tap_action("login")).await.success);
+        let value = executor
+            .execute(ActionType::GetValue {
+                selector: "login".to_string(),
+                by_label: false,
+                element_type: None,
+                timeout_ms: None,
+                match_mode: MatchMode::Exact,
+                first: false,
+            })
+            .await;
+        assert_eq!(value.data.as_deref(), Some("ready"));
+
+        let missing = executor.execute(tap_action("signup")).await;
+        assert!(!missing.success);
+        assert!(
+            missing.message.contains("ID 'signup' not found"),
+            "{}",
+            missing.message
+        );
+
+        driver.verify();
+        assert_eq!(driver.backend(), Some(DriverBackend::Agent));
+    }
+}
@@ -5,6 +5,12 @@
 //! establishes a tunnel to the Swift agent's TCP port on the device and returns
 //! a stream that can be used with [`AgentClient::from_stream`](crate::agent_client::AgentClient::from_stream).
 //!
+//! Devices paired over WiFi are also listed by usbmuxd, as a separate record
+//! with a network connection type. [`connect_with`] takes a [`ConnectionKind`]
+//! to force one path or the other. When a UDID is reachable both ways and no
+//! kind is requested, the USB record is preferred: it is faster and does not
+//! drop when the device sleeps.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -26,6 +32,7 @@
 
 use std::fmt;
 use std::net::IpAddr;
+use std::time::Duration;
 
 use idevice::usbmuxd::{Connection, UsbmuxdConnection};
 use thiserror::Error;
@@ -54,6 +61,10 @@ pub enum UsbTunnelError {
     /// The tunnel connection returned no usable socket.
     #[error("tunnel socket unavailable")]
     NoSocket,
+
+    /// The tunnel could not be established within the given time.
+    #[error("tunnel connection timed out after {0:?}")]
+    Timeout(Duration),
 }
 
 impl From<idevice::IdeviceError> for UsbTunnelError {
@@ -108,10 +119,74 @@ impl From<Connection> for DeviceConnection {
     }
 }
 
+/// The usbmuxd connection path to use when tunneling to a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionKind {
+    /// Tunnel over the USB cable.
+    Usb,
+    /// Tunnel over the network (WiFi-paired device).
+    Network,
+}
+
+impl ConnectionKind {
+    /// Returns `true` if `connection` is a usbmuxd record of this kind.
+    pub fn matches(&self, connection: &DeviceConnection) -> bool {
+        matches!(
+            (self, connection),
+            (ConnectionKind::Usb, DeviceConnection::Usb)
+                | (ConnectionKind::Network, DeviceConnection::Network(_))
+        )
+    }
+}
+
+impl fmt::Display for ConnectionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionKind::Usb => write!(f, "USB"),
+            ConnectionKind::Network => write!(f, "Network"),
+        }
+    }
+}
+
+/// Pick the usbmuxd record to tunnel through for `udid`.
+///
+/// A device paired over WiFi and plugged in shows up as two records with the
+/// same UDID. With `kind` set, only records of that kind are considered. With
+/// `kind` unset, a USB record wins over a network one, which in turn wins over
+/// a record of unknown type.
+pub fn select_device<'a>(
+    devices: &'a [PhysicalDevice],
+    udid: &str,
+    kind: Option<ConnectionKind>,
+) -> Result<&'a PhysicalDevice, UsbTunnelError> {
+    let mut candidates = devices.iter().filter(|d| d.udid == udid);
+    let selected = match kind {
+        Some(kind) => candidates.find(|d| kind.matches(&d.connection)),
+        None => candidates.min_by_key(|d| match d.connection {
+            DeviceConnection::Usb => 0,
+            DeviceConnection::Network(_) => 1,
+            DeviceConnection::Unknown(_) => 2,
+        }),
+    };
+    selected.ok_or_else(|| match kind {
+        Some(kind) => UsbTunnelError::DeviceNotFound(format!("{udid} ({kind})")),
+        None => UsbTunnelError::DeviceNotFound(udid.to_string()),
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Public API
 // ---------------------------------------------------------------------------
 
+/// Default time allowed for [`connect`] to establish a tunnel.
+///
+/// Network tunnels are noticeably slower to set up than USB ones; callers
+/// forcing [`ConnectionKind::Network`] should use [`NETWORK_CONNECT_TIMEOUT`].
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time allowed to establish a tunnel over [`ConnectionKind::Network`].
+pub const NETWORK_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// List all physical iOS devices currently connected via usbmuxd.
 ///
 /// Returns an empty list if no devices are connected or usbmuxd is unavailable.
@@ -142,19 +217,61 @@ pub async fn list_devices() -> Result<Vec<PhysicalDevice>, UsbTunnelError> {
 /// implements [`AgentStream`] and can be passed to
 /// [`AgentClient::from_stream`](crate::agent_client::AgentClient::from_stream).
 ///
+/// If the device is reachable over both USB and WiFi, the USB path is used.
+/// See [`connect_with`] to force a connection kind or change the timeout.
+///
 /// # Arguments
 ///
 /// * `udid` - The UDID of the target device
 /// * `port` - The TCP port on the device to tunnel to (e.g., 8080 for the agent)
 pub async fn connect(udid: &str, port: u16) -> Result<Box<dyn AgentStream>, UsbTunnelError> {
+    connect_with(udid, port, None, DEFAULT_CONNECT_TIMEOUT).await
+}
+
+/// Establish a tunnel to a device port over a specific connection kind.
+///
+/// Like [`connect`], but `kind` restricts which usbmuxd record is used (see
+/// [`select_device`]) and the whole setup is bounded by `timeout`. Returns
+/// [`UsbTunnelError::Timeout`] if the tunnel is not up in time.
+///
+/// # Arguments
+///
+/// * `udid` - The UDID of the target device
+/// * `port` - The TCP port on the device to tunnel to
+/// * `kind` - Force USB or network, or `None` to prefer USB
+/// * `timeout` - Upper bound on discovery plus tunnel setup
+pub async fn connect_with(
+    udid: &str,
+    port: u16,
+    kind: Option<ConnectionKind>,
+    timeout: Duration,
+) -> Result<Box<dyn AgentStream>, UsbTunnelError> {
+    tokio::time::timeout(timeout, connect_inner(udid, port, kind))
+        .await
+        .map_err(|_| UsbTunnelError::Timeout(timeout))?
+}
+
+async fn connect_inner(
+    udid: &str,
+    port: u16,
+    kind: Option<ConnectionKind>,
+) -> Result<Box<dyn AgentStream>, UsbTunnelError> {
     let mut muxd = UsbmuxdConnection::default()
         .await
         .map_err(|e| UsbTunnelError::UsbmuxdUnavailable(e.to_string()))?;
 
-    let device = muxd
-        .get_device(udid)
+    let devices: Vec<PhysicalDevice> = muxd
+        .get_devices()
         .await
-        .map_err(|_| UsbTunnelError::DeviceNotFound(udid.to_string()))?;
+        .map_err(|e| UsbTunnelError::UsbmuxdUnavailable(e.to_string()))?
+        .into_iter()
+        .map(|d| PhysicalDevice {
+            udid: d.udid,
+            device_id: d.device_id,
+            connection: d.connection_type.into(),
+        })
+        .collect();
+    let device = select_device(&devices, udid, kind)?;
 
     let idevice = muxd
         .connect_to_device(device.device_id, port, "qorvex")
@@ -300,6 +417,83 @@ mod tests {
 
         let err = UsbTunnelError::NoSocket;
         assert!(err.to_string().contains("socket unavailable"));
+
+        let err = UsbTunnelError::Timeout(Duration::from_secs(3));
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    fn record(udid: &str, device_id: u32, connection: DeviceConnection) -> PhysicalDevice {
+        PhysicalDevice {
+            udid: udid.into(),
+            device_id,
+            connection,
+        }
+    }
+
+    fn wifi() -> DeviceConnection {
+        DeviceConnection::Network("192.168.1.50".parse().unwrap())
+    }
+
+    #[test]
+    fn select_device_prefers_usb_when_reachable_both_ways() {
+        let devices = vec![
+            record("DEV1", 7, wifi()),
+            record("DEV1", 3, DeviceConnection::Usb),
+        ];
+        let d = select_device(&devices, "DEV1", None).unwrap();
+        assert_eq!(d.device_id, 3);
+    }
+
+    #[test]
+    fn select_device_falls_back_to_network() {
+        let devices = vec![
+            record("OTHER", 1, DeviceConnection::Usb),
+            record("DEV1", 7, wifi()),
+        ];
+        let d = select_device(&devices, "DEV1", None).unwrap();
+        assert_eq!(d.device_id, 7);
+    }
+
+    #[test]
+    fn select_device_forced_network_skips_usb() {
+        let devices = vec![
+            record("DEV1", 3, DeviceConnection::Usb),
+            record("DEV1", 7, wifi()),
+        ];
+        let d = select_device(&devices, "DEV1", Some(ConnectionKind::Network)).unwrap();
+        assert_eq!(d.device_id, 7);
+    }
+
+    #[test]
+    fn select_device_forced_usb_without_usb_record_fails() {
+        let devices = vec![record("DEV1", 7, wifi())];
+        let err = select_device(&devices, "DEV1", Some(ConnectionKind::Usb)).unwrap_err();
+        match err {
+            UsbTunnelError::DeviceNotFound(msg) => {
+                assert!(msg.contains("DEV1"));
+                assert!(msg.contains("USB"));
+            }
+            other => panic!("expected DeviceNotFound, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn select_device_unknown_connection_only_without_kind() {
+        let devices = vec![record("DEV1", 9, DeviceConnection::Unknown("?".into()))];
+        assert_eq!(select_device(&devices, "DEV1", None).unwrap().device_id, 9);
+        assert!(matches!(
+            select_device(&devices, "DEV1", Some(ConnectionKind::Network)),
+            Err(UsbTunnelError::DeviceNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn select_device_unknown_udid() {
+        let devices = vec![record("DEV1", 3, DeviceConnection::Usb)];
+        assert!(matches!(
+            select_device(&devices, "NOPE", None),
+            Err(UsbTunnelError::DeviceNotFound(_))
+        ));
     }
 
     #[test]
@@ -48,7 +48,9 @@ mod common;
 
 use common::{connected_android_executor, connected_executor};
 
-use qorvex_core::action::ActionType;
+use qorvex_core::action::{ActionType, MatchMode, SelectorSpec};
+use qorvex_core::driver::ElementFilter;
+use qorvex_core::element::ElementAttribute;
 use qorvex_core::executor::ExecutionResult;
 use qorvex_core::protocol::Response;
 
@@ -127,6 +129,9 @@ async fn parity_tap_by_id() {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         vec![Response::Ok, Response::Ok],
     )
@@ -143,6 +148,9 @@ async fn parity_tap_by_label() {
             by_label: true,
             element_type: None,
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         vec![Response::Ok, Response::Ok],
     )
@@ -159,6 +167,9 @@ async fn parity_tap_with_type() {
             by_label: true,
             element_type: Some("Button".to_string()),
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         vec![Response::Ok, Response::Ok],
     )
@@ -189,6 +200,70 @@ async fn parity_swipe() {
     .await;
 }
 
+// --- TapElementOffset ---
+#[tokio::test]
+async fn parity_tap_element_offset() {
+    // One find for the element's frame, then a tap at the computed point.
+    run_parity(
+        "tap-element-offset",
+        ActionType::TapElementOffset {
+            selector: "controls_tap_button".to_string(),
+            by_label: false,
+            dx_fraction: 0.8,
+            dy_fraction: 0.5,
+        },
+        vec![
+            Response::Ok,
+            Response::Element {
+                json: r#"{"AXUniqueId":"controls_tap_button","type":"Button","frame":{"x":20,"y":200,"width":350,"height":50}}"#
+                    .to_string(),
+            },
+            Response::Ok,
+        ],
+    )
+    .await;
+}
+
+// --- SwipeCoords ---
+#[tokio::test]
+async fn parity_swipe_coords() {
+    run_parity(
+        "swipe-coords",
+        ActionType::SwipeCoords {
+            start_x: 300,
+            start_y: 400,
+            end_x: 50,
+            end_y: 400,
+            duration: Some(0.5),
+        },
+        vec![Response::Ok, Response::Ok],
+    )
+    .await;
+}
+
+// --- SwipeElement ---
+#[tokio::test]
+async fn parity_swipe_element() {
+    // One find for the element's frame, then a swipe inside it.
+    run_parity(
+        "swipe-element",
+        ActionType::SwipeElement {
+            selector: "controls_tap_button".to_string(),
+            by_label: false,
+            direction: "left".to_string(),
+        },
+        vec![
+            Response::Ok,
+            Response::Element {
+                json: r#"{"AXUniqueId":"controls_tap_button","type":"Button","frame":{"x":20,"y":200,"width":350,"height":50}}"#
+                    .to_string(),
+            },
+            Response::Ok,
+        ],
+    )
+    .await;
+}
+
 // --- LongPress ---
 #[tokio::test]
 async fn parity_long_press() {
@@ -204,6 +279,33 @@ async fn parity_long_press() {
     .await;
 }
 
+// --- LongPressElement ---
+#[tokio::test]
+async fn parity_long_press_element() {
+    // Neither agent answers the element opcode here, so both fall back to a
+    // find for the frame and a coordinate long press at its center.
+    run_parity(
+        "long-press-element",
+        ActionType::LongPressElement {
+            selector: "controls_tap_button".to_string(),
+            by_label: false,
+            duration: 1.0,
+        },
+        vec![
+            Response::Ok,
+            Response::Error {
+                message: "invalid opcode: 0x25".to_string(),
+            },
+            Response::Element {
+                json: r#"{"AXUniqueId":"controls_tap_button","type":"Button","frame":{"x":20,"y":200,"width":350,"height":50}}"#
+                    .to_string(),
+            },
+            Response::Ok,
+        ],
+    )
+    .await;
+}
+
 // --- SendKeys (type text) ---
 #[tokio::test]
 async fn parity_send_keys() {
@@ -232,7 +334,10 @@ async fn parity_get_screen_info() {
     }]"#;
     run_parity(
         "get-screen-info",
-        ActionType::GetScreenInfo,
+        ActionType::GetScreenInfo {
+            filter: ElementFilter::default(),
+            changed_since_hash: None,
+        },
         vec![
             Response::Ok,
             Response::Tree {
@@ -253,6 +358,8 @@ async fn parity_get_value() {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         vec![
             Response::Ok,
@@ -274,12 +381,42 @@ async fn parity_get_value_none() {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         vec![Response::Ok, Response::Value { value: None }],
     )
     .await;
 }
 
+// --- GetAttribute (placeholder) ---
+#[tokio::test]
+async fn parity_get_attribute() {
+    let element = r#"{
+        "AXUniqueId": "text_email_field",
+        "type": "EditText",
+        "placeholder": "you@example.com",
+        "frame": {"x": 0.0, "y": 0.0, "width": 200.0, "height": 40.0},
+        "children": []
+    }"#;
+    run_parity(
+        "get-attribute",
+        ActionType::GetAttribute {
+            selector: "text_email_field".to_string(),
+            by_label: false,
+            element_type: None,
+            attribute: ElementAttribute::Placeholder,
+        },
+        vec![
+            Response::Ok,
+            Response::Element {
+                json: element.to_string(),
+            },
+        ],
+    )
+    .await;
+}
+
 // --- GetScreenshot ---
 #[tokio::test]
 async fn parity_screenshot() {
@@ -312,6 +449,9 @@ async fn parity_wait_for() {
             element_type: None,
             timeout_ms: 5_000,
             require_stable: false,
+            poll_interval_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         vec![
             Response::Ok,
@@ -334,6 +474,99 @@ async fn parity_wait_for_not() {
             by_label: false,
             element_type: None,
             timeout_ms: 5_000,
+            poll_interval_ms: None,
+        },
+        vec![
+            Response::Ok,
+            Response::Element {
+                json: "null".to_string(),
+            },
+        ],
+    )
+    .await;
+}
+
+// --- WaitForAny / WaitForAll ---
+#[tokio::test]
+async fn parity_wait_for_any() {
+    // First selector absent, second present → reports the second on both backends.
+    run_parity(
+        "wait-for-any",
+        ActionType::WaitForAny {
+            selectors: vec![
+                SelectorSpec {
+                    selector: "missing_label".to_string(),
+                    by_label: false,
+                    element_type: None,
+                },
+                SelectorSpec {
+                    selector: "dynamic_brief_label".to_string(),
+                    by_label: false,
+                    element_type: None,
+                },
+            ],
+            timeout_ms: 5_000,
+            poll_interval_ms: None,
+        },
+        vec![
+            Response::Ok,
+            Response::Element {
+                json: "null".to_string(),
+            },
+            Response::Element {
+                json: r#"{"AXUniqueId": "dynamic_brief_label", "hittable": true, "children": []}"#
+                    .to_string(),
+            },
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn parity_wait_for_all() {
+    run_parity(
+        "wait-for-all",
+        ActionType::WaitForAll {
+            selectors: vec![
+                SelectorSpec {
+                    selector: "dynamic_brief_label".to_string(),
+                    by_label: false,
+                    element_type: None,
+                },
+                SelectorSpec {
+                    selector: "submit_button".to_string(),
+                    by_label: false,
+                    element_type: None,
+                },
+            ],
+            timeout_ms: 5_000,
+            poll_interval_ms: None,
+        },
+        vec![
+            Response::Ok,
+            Response::Element {
+                json: r#"{"AXUniqueId": "dynamic_brief_label", "hittable": true, "children": []}"#
+                    .to_string(),
+            },
+            Response::Element {
+                json: r#"{"AXUniqueId": "submit_button", "hittable": true, "children": []}"#
+                    .to_string(),
+            },
+        ],
+    )
+    .await;
+}
+
+// --- ElementExists ---
+#[tokio::test]
+async fn parity_element_exists() {
+    // One find per check; absence is a successful "false" on both backends.
+    run_parity(
+        "element-exists",
+        ActionType::ElementExists {
+            selector: "dynamic_brief_label".to_string(),
+            by_label: false,
+            element_type: None,
         },
         vec![
             Response::Ok,
@@ -345,6 +578,74 @@ async fn parity_wait_for_not() {
     .await;
 }
 
+// --- FindAll ---
+#[tokio::test]
+async fn parity_find_all() {
+    // The Kotlin agent has no find-all opcode, so both backends take the
+    // dump-and-filter fallback here.
+    run_parity(
+        "find-all",
+        ActionType::FindAll {
+            selector: String::new(),
+            by_label: false,
+            element_type: Some("Button".to_string()),
+        },
+        vec![
+            Response::Ok,
+            Response::Error {
+                message: "invalid opcode: 0x1F".to_string(),
+            },
+            Response::Tree {
+                json: r#"[{"AXUniqueId":"controls_tap_button","type":"Button","children":[]}]"#
+                    .to_string(),
+            },
+        ],
+    )
+    .await;
+}
+
+// --- WaitForIdle ---
+#[tokio::test]
+async fn parity_wait_for_idle() {
+    // With no stable window the first dump already counts as idle.
+    run_parity(
+        "wait-for-idle",
+        ActionType::WaitForIdle {
+            timeout_ms: 1000,
+            stable_ms: 0,
+        },
+        vec![
+            Response::Ok,
+            Response::Tree {
+                json: r#"[{"AXUniqueId":"controls_tap_button","children":[]}]"#.to_string(),
+            },
+        ],
+    )
+    .await;
+}
+
+// --- TapIfExists ---
+#[tokio::test]
+async fn parity_tap_if_exists() {
+    // One find, then a single tap attempt when the element is there.
+    run_parity(
+        "tap-if-exists",
+        ActionType::TapIfExists {
+            selector: "controls_tap_button".to_string(),
+            by_label: false,
+            element_type: None,
+        },
+        vec![
+            Response::Ok,
+            Response::Element {
+                json: r#"{"AXUniqueId":"controls_tap_button","type":"Button"}"#.to_string(),
+            },
+            Response::Ok,
+        ],
+    )
+    .await;
+}
+
 // --- SetTarget ---
 #[tokio::test]
 async fn parity_set_target() {
@@ -418,6 +719,26 @@ async fn parity_log_comment() {
     .await;
 }
 
+// --- Assertion (driver-independent, must behave identically) ---
+#[tokio::test]
+async fn parity_assertion() {
+    run_parity(
+        "assertion",
+        ActionType::Assertion {
+            description: "parity check".to_string(),
+            passed: true,
+        },
+        vec![Response::Ok],
+    )
+    .await;
+}
+
+// --- Wait (driver-independent, must behave identically) ---
+#[tokio::test]
+async fn parity_wait() {
+    run_parity("wait", ActionType::Wait { ms: 20 }, vec![Response::Ok]).await;
+}
+
 // --- Error propagation parity (element-not-found) ---
 #[tokio::test]
 async fn parity_error_propagation() {
@@ -428,12 +749,19 @@ async fn parity_error_propagation() {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         vec![
             Response::Ok,
             Response::Error {
                 message: "element not found".to_string(),
             },
+            // The executor then reads the tree looking for a similar selector.
+            Response::Tree {
+                json: "[]".to_string(),
+            },
         ],
     )
     .await;
@@ -454,19 +782,32 @@ fn matrix_covers_every_action_type() {
     let covered_via_driver = [
         "tap",
         "tap_location",
+        "tap_element_offset",
         "swipe",
+        "swipe_coords",
+        "swipe_element",
         "long_press",
+        "long_press_element",
         "send_keys",
         "get_screen_info",
         "get_value",
+        "get_attribute",
         "get_screenshot",
         "wait_for",
         "wait_for_not",
+        "wait_for_any",
+        "wait_for_all",
+        "element_exists",
+        "tap_if_exists",
+        "find_all",
+        "wait_for_idle",
         "set_target",
         "start_target",
         "stop_target",
         "get_target_info",
         "log_comment",
+        "assertion",
+        "wait",
     ];
 
     // Session/REPL control actions: no agent protocol round-trip, so they are
@@ -474,26 +815,63 @@ fn matrix_covers_every_action_type() {
     // construction — the executor handles them backend-agnostically).
     let session_control = ["start_session", "end_session", "quit"];
 
+    // Actions with no Android counterpart: appearance and locale are applied
+    // through simctl by the server, pasting fills the pasteboard through simctl, and
+    // only the Swift agent rotates, drags, taps with several fingers,
+    // deletes text with backspace keystrokes and presses special keys.
+    let ios_only = [
+        "set_appearance",
+        "set_locale",
+        "set_orientation",
+        "drag",
+        "multi_tap",
+        "paste_text",
+        "clear_text",
+        "send_key",
+    ];
+
     // Exhaustive match: adding a new ActionType variant fails to compile until
     // it is classified here, guaranteeing the matrix stays complete.
     fn classify(a: &ActionType) -> &'static str {
         match a {
             ActionType::Tap { .. }
             | ActionType::TapLocation { .. }
+            | ActionType::TapElementOffset { .. }
             | ActionType::Swipe { .. }
+            | ActionType::SwipeCoords { .. }
+            | ActionType::SwipeElement { .. }
             | ActionType::LongPress { .. }
+            | ActionType::LongPressElement { .. }
             | ActionType::SendKeys { .. }
-            | ActionType::GetScreenInfo
+            | ActionType::GetScreenInfo { .. }
             | ActionType::GetValue { .. }
+            | ActionType::GetAttribute { .. }
             | ActionType::GetScreenshot
+            | ActionType::GetElementScreenshot { .. }
             | ActionType::WaitFor { .. }
             | ActionType::WaitForNot { .. }
+            | ActionType::WaitForAny { .. }
+            | ActionType::WaitForAll { .. }
+            | ActionType::ElementExists { .. }
+            | ActionType::TapIfExists { .. }
+            | ActionType::FindAll { .. }
+            | ActionType::WaitForIdle { .. }
             | ActionType::SetTarget { .. }
             | ActionType::StartTarget
             | ActionType::StopTarget
             | ActionType::GetTargetInfo
-            | ActionType::LogComment { .. } => "driver",
+            | ActionType::LogComment { .. }
+            | ActionType::Assertion { .. }
+            | ActionType::Wait { .. } => "driver",
             ActionType::StartSession | ActionType::EndSession | ActionType::Quit => "session",
+            ActionType::SetAppearance { .. }
+            | ActionType::SetLocale { .. }
+            | ActionType::SetOrientation { .. }
+            | ActionType::Drag { .. }
+            | ActionType::MultiTap { .. }
+            | ActionType::PasteText { .. }
+            | ActionType::ClearText { .. }
+            | ActionType::SendKey { .. } => "ios_only",
         }
     }
 
@@ -509,10 +887,10 @@ fn matrix_covers_every_action_type() {
         "quit must be a session-control action"
     );
 
-    // Total action count is the sum of the two disjoint classes.
+    // Total action count is the sum of the disjoint classes.
     assert_eq!(
-        covered_via_driver.len() + session_control.len(),
-        18,
+        covered_via_driver.len() + session_control.len() + ios_only.len(),
+        39,
         "ActionType matrix size changed — update the parity matrix and this list"
     );
 }
@@ -5,7 +5,7 @@
 //! the TCP agent protocol, IPC layer, and full session stack.
 
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -15,7 +15,9 @@ use qorvex_core::agent_driver::AgentDriver;
 use qorvex_core::driver::AutomationDriver;
 use qorvex_core::executor::ActionExecutor;
 use qorvex_core::ipc::{IpcClient, IpcServer};
-use qorvex_core::protocol::{encode_response, read_frame_length, Response};
+use qorvex_core::protocol::{
+    decode_request, encode_response, read_frame_length, Request, Response,
+};
 use qorvex_core::session::Session;
 
 // ---------------------------------------------------------------------------
@@ -26,8 +28,19 @@ use qorvex_core::session::Session;
 /// of request/response pairs. The first response is always consumed by the
 /// heartbeat that `AgentDriver::connect()` sends.
 pub async fn mock_agent(responses: Vec<Response>) -> SocketAddr {
+    recording_mock_agent(responses).await.0
+}
+
+/// Requests received by a [`recording_mock_agent`], in arrival order.
+pub type RequestLog = Arc<Mutex<Vec<Request>>>;
+
+/// Like [`mock_agent`], but also decodes and records every request it answers
+/// so tests can assert on what the driver sent.
+pub async fn recording_mock_agent(responses: Vec<Response>) -> (SocketAddr, RequestLog) {
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
+    let log = RequestLog::default();
+    let recorded = Arc::clone(&log);
 
     tokio::spawn(async move {
         let (mut stream, _) = listener.accept().await.unwrap();
@@ -39,6 +52,10 @@ pub async fn mock_agent(responses: Vec<Response>) -> SocketAddr {
             let len = read_frame_length(&header) as usize;
             let mut payload = vec![0u8; len];
             stream.read_exact(&mut payload).await.unwrap();
+            recorded
+                .lock()
+                .unwrap()
+                .push(decode_request(&payload).unwrap());
 
             // Send the canned response.
             let resp_bytes = encode_response(&response);
@@ -63,7 +80,7 @@ pub async fn mock_agent(responses: Vec<Response>) -> SocketAddr {
         drain_until_client_closes(&mut stream).await;
     });
 
-    addr
+    (addr, log)
 }
 
 /// Read and discard bytes until the peer closes the connection (EOF) or errors.
@@ -91,6 +108,17 @@ pub async fn connected_executor(responses: Vec<Response>) -> ActionExecutor {
     ActionExecutor::new(Arc::new(driver))
 }
 
+/// Like [`connected_executor`], but also returns the log of requests the mock
+/// agent received (starting with the connect heartbeat).
+pub async fn connected_recording_executor(
+    responses: Vec<Response>,
+) -> (ActionExecutor, RequestLog) {
+    let (addr, log) = recording_mock_agent(responses).await;
+    let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port());
+    driver.connect().await.unwrap();
+    (ActionExecutor::new(Arc::new(driver)), log)
+}
+
 /// Convenience: create an **AndroidDriver** connected to the mock, ready to use
 /// in an ActionExecutor. Mirrors [`connected_executor`] but exercises the
 /// Android backend path (story #90 parity harness).
@@ -259,11 +287,16 @@ impl TestHarness {
     /// 4. Starts an IPC server for the session and sets the driver on it.
     /// 5. Waits briefly for the IPC socket to become available.
     pub async fn start(responses: Vec<Response>) -> Self {
+        Self::start_on_device(None, responses).await
+    }
+
+    /// Like [`TestHarness::start`], with the session on the device `udid`.
+    pub async fn start_on_device(udid: Option<&str>, responses: Vec<Response>) -> Self {
         let session_name = unique_session_name();
 
         // Use a temp directory for log files so tests don't pollute ~/.qorvex/logs.
         let tmp_dir = std::env::temp_dir().join(format!("qorvex_test_{}", &session_name));
-        let session = Session::new_with_log_dir(None, &session_name, tmp_dir);
+        let session = Session::new_with_log_dir(udid.map(str::to_string), &session_name, tmp_dir);
 
         // Stand up a mock agent and connect a driver.
         let addr = mock_agent(responses).await;
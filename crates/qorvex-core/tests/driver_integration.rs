@@ -10,13 +10,19 @@ mod common;
 
 use std::sync::Arc;
 
-use common::connected_executor;
+use common::{connected_executor, connected_recording_executor};
 
-use qorvex_core::action::ActionType;
+use qorvex_core::action::{ActionType, MatchMode, SelectorSpec};
 use qorvex_core::agent_driver::AgentDriver;
-use qorvex_core::driver::AutomationDriver;
+use qorvex_core::driver::{AutomationDriver, DriverConfig, ElementFilter, Orientation};
+use qorvex_core::element::{ElementAttribute, UIElement};
 use qorvex_core::executor::ActionExecutor;
-use qorvex_core::protocol::Response;
+use qorvex_core::protocol::{
+    encode_response, read_frame_length, Request, Response, ScreenshotFormat, SpecialKey,
+};
+use qorvex_core::result_data::{
+    IdleData, ResultData, TapIfExistsData, WaitForAnyData, SCHEMA_VERSION,
+};
 
 // ---------------------------------------------------------------------------
 // 1. Tap element by identifier
@@ -36,6 +42,9 @@ async fn test_executor_tap_element_via_agent_driver() {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         })
         .await;
 
@@ -64,6 +73,9 @@ async fn test_executor_tap_by_label_via_agent_driver() {
             by_label: true,
             element_type: None,
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         })
         .await;
 
@@ -129,7 +141,12 @@ async fn test_executor_get_screen_info_via_agent_driver() {
     ])
     .await;
 
-    let result = executor.execute(ActionType::GetScreenInfo).await;
+    let result = executor
+        .execute(ActionType::GetScreenInfo {
+            filter: ElementFilter::default(),
+            changed_since_hash: None,
+        })
+        .await;
 
     assert!(
         result.success,
@@ -144,6 +161,55 @@ async fn test_executor_get_screen_info_via_agent_driver() {
     );
 }
 
+#[tokio::test]
+async fn test_executor_get_screen_info_filter_falls_back_when_unsupported() {
+    let tree_json = r#"[{
+        "AXUniqueId": "window",
+        "type": "Window",
+        "frame": {"x": 0, "y": 0, "width": 390, "height": 844},
+        "children": [
+            {"AXUniqueId": "btn1", "type": "Button",
+             "frame": {"x": 10, "y": 20, "width": 100, "height": 44}, "children": []},
+            {"AXUniqueId": "title", "type": "StaticText",
+             "frame": {"x": 10, "y": 80, "width": 100, "height": 20}, "children": []}
+        ]
+    }]"#;
+
+    let executor = connected_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Error {
+            message: "decode error: invalid opcode: 0x17".to_string(),
+        }, // DumpTreeFiltered (old agent)
+        Response::Tree {
+            json: tree_json.to_string(),
+        }, // DumpTree fallback
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::GetScreenInfo {
+            filter: ElementFilter {
+                types: vec!["Button".to_string()],
+                ..Default::default()
+            },
+            changed_since_hash: None,
+        })
+        .await;
+
+    assert!(
+        result.success,
+        "filtered get-screen-info should fall back: {}",
+        result.message
+    );
+    let data = result.data.expect("should have data");
+    assert!(data.contains("btn1"), "data should contain the button");
+    assert!(
+        !data.contains("title"),
+        "non-buttons should be filtered out"
+    );
+    assert!(!data.contains("window"), "ancestors should be filtered out");
+}
+
 // ---------------------------------------------------------------------------
 // 5. GetValue
 // ---------------------------------------------------------------------------
@@ -164,6 +230,8 @@ async fn test_executor_get_value_via_agent_driver() {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
         })
         .await;
 
@@ -283,6 +351,9 @@ async fn test_executor_handles_agent_error() {
         Response::Error {
             message: "element not found".to_string(),
         }, // Error response to Tap
+        Response::Tree {
+            json: "[]".to_string(),
+        }, // DumpTree, looking for a similar selector
     ])
     .await;
 
@@ -292,6 +363,9 @@ async fn test_executor_handles_agent_error() {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         })
         .await;
 
@@ -366,6 +440,9 @@ async fn test_executor_tap_with_type_via_agent_driver() {
             by_label: true,
             element_type: Some("Button".to_string()),
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         })
         .await;
 
@@ -396,6 +473,8 @@ async fn test_executor_get_value_by_label_via_agent_driver() {
             by_label: true,
             element_type: None,
             timeout_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
         })
         .await;
 
@@ -426,6 +505,8 @@ async fn test_executor_get_value_none_via_agent_driver() {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
         })
         .await;
 
@@ -437,3 +518,1811 @@ async fn test_executor_get_value_none_via_agent_driver() {
     let data = result.data.expect("should have data");
     assert_eq!(data, "null");
 }
+
+// ---------------------------------------------------------------------------
+// 14. GetElementScreenshot crops to the element frame in pixels, inferring
+//     the scale when the agent cannot report it
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_element_screenshot_via_agent_driver() {
+    // A 3x-scale screenshot of a 100x200pt screen.
+    let screenshot = {
+        let image = image::RgbaImage::new(300, 600);
+        let mut out = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut out, image::ImageFormat::Png).unwrap();
+        out.into_inner()
+    };
+
+    let executor = connected_executor(vec![
+        Response::Ok,                              // heartbeat
+        Response::Screenshot { data: screenshot }, // Screenshot
+        Response::Element {
+            json: r#"{"AXUniqueId":"login-button","frame":{"x":10,"y":20,"width":40,"height":15}}"#
+                .to_string(),
+        }, // FindElement
+        Response::Error {
+            message: "unknown opcode".to_string(),
+        }, // GetScreenInfo (older agent)
+        Response::Tree {
+            json: r#"[{"type":"Application","frame":{"x":0,"y":0,"width":100,"height":200}}]"#
+                .to_string(),
+        }, // DumpTree
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::GetElementScreenshot {
+            selector: "login-button".to_string(),
+            by_label: false,
+        })
+        .await;
+
+    assert!(
+        result.success,
+        "element screenshot should succeed: {}",
+        result.message
+    );
+    let png = {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD
+            .decode(result.screenshot.expect("should have screenshot data"))
+            .unwrap()
+    };
+    assert_eq!(
+        qorvex_core::screenshot::png_dimensions(&png).unwrap(),
+        (120, 45)
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 15. GetElementScreenshot uses the scale reported by the agent
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_element_screenshot_uses_screen_info_scale() {
+    // A 2x-scale screenshot; the window frame alone would suggest 3x.
+    let screenshot = {
+        let image = image::RgbaImage::new(300, 600);
+        let mut out = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut out, image::ImageFormat::Png).unwrap();
+        out.into_inner()
+    };
+
+    let executor = connected_executor(vec![
+        Response::Ok,                              // heartbeat
+        Response::Screenshot { data: screenshot }, // Screenshot
+        Response::Element {
+            json: r#"{"AXUniqueId":"login-button","frame":{"x":10,"y":20,"width":40,"height":15}}"#
+                .to_string(),
+        }, // FindElement
+        Response::ScreenInfo {
+            width: 150.0,
+            height: 300.0,
+            scale: 2.0,
+            orientation: "portrait".to_string(),
+        }, // GetScreenInfo
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::GetElementScreenshot {
+            selector: "login-button".to_string(),
+            by_label: false,
+        })
+        .await;
+
+    assert!(
+        result.success,
+        "element screenshot should succeed: {}",
+        result.message
+    );
+    let png = {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD
+            .decode(result.screenshot.expect("should have screenshot data"))
+            .unwrap()
+    };
+    assert_eq!(
+        qorvex_core::screenshot::png_dimensions(&png).unwrap(),
+        (80, 30)
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 16. SetOrientation rotates and invalidates the cached screen info
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_set_orientation_refreshes_screen_info() {
+    let executor = connected_executor(vec![
+        Response::Ok, // heartbeat
+        Response::ScreenInfo {
+            width: 390.0,
+            height: 844.0,
+            scale: 3.0,
+            orientation: "portrait".to_string(),
+        }, // GetScreenInfo
+        Response::Ok, // SetOrientation
+        Response::ScreenInfo {
+            width: 844.0,
+            height: 390.0,
+            scale: 3.0,
+            orientation: "landscape_left".to_string(),
+        }, // GetScreenInfo after rotating
+    ])
+    .await;
+
+    let before = executor.driver().screen_info().await.unwrap();
+    assert_eq!(before.orientation, Some(Orientation::Portrait));
+
+    let result = executor
+        .execute(ActionType::SetOrientation {
+            orientation: Orientation::LandscapeLeft,
+        })
+        .await;
+    assert!(
+        result.success,
+        "rotation should succeed: {}",
+        result.message
+    );
+    assert_eq!(result.message, "Orientation set to landscape-left");
+
+    let after = executor.driver().screen_info().await.unwrap();
+    assert_eq!(after.orientation, Some(Orientation::LandscapeLeft));
+    assert_eq!((after.width, after.height), (844.0, 390.0));
+}
+
+// ---------------------------------------------------------------------------
+// 17. Drag resolves both elements and drags between their centers
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_drag_between_element_centers() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Element {
+            json: r#"{"AXUniqueId":"row-1","frame":{"x":0,"y":100,"width":390,"height":44}}"#
+                .to_string(),
+        }, // FindElement (from)
+        Response::Element {
+            json: r#"{"AXUniqueId":"row-4","frame":{"x":0,"y":232,"width":390,"height":45}}"#
+                .to_string(),
+        }, // FindElement (to)
+        Response::Ok, // Drag
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::Drag {
+            from: "row-1".to_string(),
+            to: "row-4".to_string(),
+            by_label: false,
+            hold_duration: 0.5,
+            move_duration: 1.0,
+        })
+        .await;
+
+    assert!(result.success, "drag should succeed: {}", result.message);
+    assert_eq!(result.message, "Dragged 'row-1' to 'row-4'");
+    assert_eq!(
+        requests.lock().unwrap().last(),
+        Some(&Request::Drag {
+            start_x: 195,
+            start_y: 122,
+            end_x: 195,
+            end_y: 255,
+            hold_duration: 0.5,
+            move_duration: 1.0,
+        })
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 18. Drag rejects negative durations before contacting the agent
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_drag_rejects_negative_durations() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+    ])
+    .await;
+
+    for (hold_duration, move_duration) in [(-0.1, 0.5), (0.5, -1.0), (f64::NAN, 0.5)] {
+        let result = executor
+            .execute(ActionType::Drag {
+                from: "a".to_string(),
+                to: "b".to_string(),
+                by_label: false,
+                hold_duration,
+                move_duration,
+            })
+            .await;
+        assert!(!result.success);
+        assert!(
+            result.message.contains("must be non-negative"),
+            "unexpected message: {}",
+            result.message
+        );
+    }
+    // Only the connect heartbeat reached the agent
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+// ---------------------------------------------------------------------------
+// 19. Drag fails when the drop target is missing
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_drag_missing_target_element() {
+    let executor = connected_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Element {
+            json: r#"{"AXLabel":"Milk","frame":{"x":0,"y":100,"width":390,"height":44}}"#
+                .to_string(),
+        }, // FindElement (from)
+        Response::Element {
+            json: "null".to_string(),
+        }, // FindElement (to)
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::Drag {
+            from: "Milk".to_string(),
+            to: "Eggs".to_string(),
+            by_label: true,
+            hold_duration: 0.5,
+            move_duration: 0.5,
+        })
+        .await;
+
+    assert!(!result.success);
+    assert!(
+        result
+            .message
+            .contains("Element with label 'Eggs' not found"),
+        "unexpected message: {}",
+        result.message
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 20. PasteText needs a simulator for the pasteboard
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_paste_text_requires_simulator() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::PasteText {
+            text: "long text with emojis 🎉".to_string(),
+        })
+        .await;
+
+    assert!(!result.success);
+    assert!(
+        result.message.contains("only supported on iOS simulators"),
+        "unexpected message: {}",
+        result.message
+    );
+    // Nothing is pasted when the pasteboard could not be filled
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+// ---------------------------------------------------------------------------
+// 21. ClearText uses the agent's native clear when available
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_clear_text_native() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Ok, // ClearText
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::ClearText {
+            selector: "email-field".to_string(),
+            by_label: false,
+        })
+        .await;
+
+    assert!(result.success, "clear failed: {}", result.message);
+    assert_eq!(
+        requests.lock().unwrap().last(),
+        Some(&Request::ClearText {
+            selector: "email-field".to_string(),
+            by_label: false,
+        })
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 22. ClearText falls back to one delete per character on older agents
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_clear_text_fallback_deletes_each_character() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Error {
+            message: "invalid opcode: 0x1E".to_string(),
+        }, // ClearText
+        Response::Value {
+            value: Some("me@café.io 🎉".to_string()),
+        }, // GetValue
+        Response::Ok, // TapByLabel
+        Response::Ok, // TypeText
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::ClearText {
+            selector: "Email".to_string(),
+            by_label: true,
+        })
+        .await;
+
+    assert!(result.success, "clear failed: {}", result.message);
+    let requests = requests.lock().unwrap();
+    assert_eq!(
+        requests[3],
+        Request::TapByLabel {
+            label: "Email".to_string(),
+            timeout_ms: None,
+        }
+    );
+    // 12 characters, counted as characters rather than UTF-8 bytes
+    assert_eq!(
+        requests[4],
+        Request::TypeText {
+            text: "\u{8}".repeat(12)
+        }
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 23. A mistyped selector fails with a suggestion from the screen
+// ---------------------------------------------------------------------------
+
+const LOGIN_TREE: &str = r#"[{
+    "AXUniqueId": "login-form",
+    "type": "Other",
+    "children": [
+        {"AXUniqueId": "logout-link", "AXLabel": "Log out", "type": "Link", "children": []},
+        {"AXUniqueId": "login-btn", "AXLabel": "Log in", "type": "Button", "children": []}
+    ]
+}]"#;
+
+#[tokio::test]
+async fn test_executor_missing_selector_suggests_closest_match() {
+    let executor = connected_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Error {
+            message: "Element with identifier 'lgoin-btn' not found".to_string(),
+        }, // TapElement
+        Response::Tree {
+            json: LOGIN_TREE.to_string(),
+        }, // DumpTree
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::Tap {
+            selector: "lgoin-btn".to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
+        })
+        .await;
+
+    assert!(!result.success);
+    assert!(
+        result.message.ends_with("did you mean 'login-btn'?"),
+        "unexpected message: {}",
+        result.message
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 24. With fuzzy selectors enabled, the closest match is used instead
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_fuzzy_selectors_retry_with_closest_match() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Error {
+            message: "Element with label 'Lgo in' not found".to_string(),
+        }, // TapByLabel
+        Response::Tree {
+            json: LOGIN_TREE.to_string(),
+        }, // DumpTree
+        Response::Ok, // TapByLabel (retry)
+    ])
+    .await;
+    let executor = executor.with_fuzzy_selectors(true);
+
+    let result = executor
+        .execute(ActionType::Tap {
+            selector: "Lgo in".to_string(),
+            by_label: true,
+            element_type: None,
+            timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
+        })
+        .await;
+
+    assert!(result.success, "tap failed: {}", result.message);
+    assert!(
+        result.message.contains("fuzzy match for 'Lgo in'"),
+        "unexpected message: {}",
+        result.message
+    );
+    assert_eq!(
+        requests.lock().unwrap().last(),
+        Some(&Request::TapByLabel {
+            label: "Log in".to_string(),
+            timeout_ms: None,
+        })
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 25. Without a close match the original failure is kept
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_missing_selector_without_close_match() {
+    let executor = connected_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Error {
+            message: "Element with identifier 'settings' not found".to_string(),
+        }, // TapElement
+        Response::Tree {
+            json: LOGIN_TREE.to_string(),
+        }, // DumpTree
+    ])
+    .await
+    .with_fuzzy_selectors(true);
+
+    let result = executor
+        .execute(ActionType::Tap {
+            selector: "settings".to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
+        })
+        .await;
+
+    assert!(!result.success);
+    assert!(
+        result.message.ends_with("'settings' not found"),
+        "unexpected message: {}",
+        result.message
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 26. ElementExists reports presence as a boolean without failing
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_element_exists_reports_boolean() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Element {
+            json: r#"{"AXUniqueId":"login-button","type":"Button"}"#.to_string(),
+        }, // FindElement (present)
+        Response::Element {
+            json: "null".to_string(),
+        }, // FindElement (absent)
+    ])
+    .await;
+
+    let exists = |selector: &str| ActionType::ElementExists {
+        selector: selector.to_string(),
+        by_label: false,
+        element_type: Some("Button".to_string()),
+    };
+
+    let result = executor.execute(exists("login-button")).await;
+    assert!(result.success, "exists should succeed: {}", result.message);
+    assert_eq!(result.data.as_deref(), Some("true"));
+    assert_eq!(result.message, "Element 'login-button' exists");
+
+    let result = executor.execute(exists("logout-button")).await;
+    assert!(
+        result.success,
+        "absence is not a failure: {}",
+        result.message
+    );
+    assert_eq!(result.data.as_deref(), Some("false"));
+    assert_eq!(result.message, "Element 'logout-button' does not exist");
+
+    // A single lookup per check, with the type filter passed through
+    assert_eq!(
+        requests.lock().unwrap()[1],
+        Request::FindElement {
+            selector: "login-button".to_string(),
+            by_label: false,
+            element_type: Some("Button".to_string()),
+        }
+    );
+    assert_eq!(requests.lock().unwrap().len(), 3);
+}
+
+// ---------------------------------------------------------------------------
+// 27. Wait actions poll at the requested interval, clamped to the deadline
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_wait_for_not_polls_at_interval() {
+    let present = || Response::Element {
+        json: r#"{"AXUniqueId":"spinner","type":"ActivityIndicator"}"#.to_string(),
+    };
+    let mut responses = vec![Response::Ok]; // heartbeat
+    responses.extend(std::iter::repeat_with(present).take(8)); // FindElement polls
+    let (executor, requests) = connected_recording_executor(responses).await;
+
+    let start = std::time::Instant::now();
+    let result = executor
+        .execute(ActionType::WaitForNot {
+            selector: "spinner".to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: 1000,
+            poll_interval_ms: Some(300),
+        })
+        .await;
+    let elapsed = start.elapsed();
+
+    assert!(!result.success, "spinner never disappears");
+    assert!(
+        result.message.contains("to disappear"),
+        "{}",
+        result.message
+    );
+    // Polls at 0, 300, 600 and 900ms, then a final one at the 1000ms deadline
+    // rather than a full interval later
+    let polls = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|r| matches!(r, Request::FindElement { .. }))
+        .count();
+    assert_eq!(polls, 5);
+    assert!(
+        elapsed < std::time::Duration::from_millis(1250),
+        "{elapsed:?}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 28. WaitForIdle succeeds once the tree stops changing
+// ---------------------------------------------------------------------------
+
+/// A one-element tree whose label is `label`.
+fn tree(label: &str) -> Response {
+    Response::Tree {
+        json: format!(r#"[{{"AXUniqueId":"status","AXLabel":"{label}","children":[]}}]"#),
+    }
+}
+
+#[tokio::test]
+async fn test_executor_wait_for_idle_after_tree_settles() {
+    let mut responses = vec![
+        Response::Ok,
+        tree("Loading"),
+        tree("Loading."),
+        tree("Done"),
+    ];
+    responses.extend(std::iter::repeat_with(|| tree("Done")).take(8));
+    let (executor, requests) = connected_recording_executor(responses).await;
+    let executor = executor.with_poll_interval(std::time::Duration::from_millis(40));
+
+    let result = executor
+        .execute(ActionType::WaitForIdle {
+            timeout_ms: 5000,
+            stable_ms: 100,
+        })
+        .await;
+
+    assert!(result.success, "screen settles: {}", result.message);
+    let data = IdleData::from_data(result.data.as_deref().unwrap()).unwrap();
+    assert_eq!(data.changes, 2);
+    assert_eq!(data.schema_version, SCHEMA_VERSION);
+    // Three changing dumps, then at least two more of the settled tree
+    let dumps = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|r| matches!(r, Request::DumpTree))
+        .count();
+    assert!(dumps >= 5, "{dumps} dumps");
+}
+
+// ---------------------------------------------------------------------------
+// 29. WaitForIdle times out with the number of changes it saw
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_wait_for_idle_timeout_reports_churn() {
+    let mut responses = vec![Response::Ok];
+    responses.extend((0..40).map(|i| tree(&format!("{i}%"))));
+    let (executor, requests) = connected_recording_executor(responses).await;
+    let executor = executor.with_poll_interval(std::time::Duration::from_millis(20));
+
+    let result = executor
+        .execute(ActionType::WaitForIdle {
+            timeout_ms: 200,
+            stable_ms: 1000,
+        })
+        .await;
+
+    assert!(!result.success, "screen never settles");
+    // Every dump after the first was a change
+    let dumps = requests.lock().unwrap().len() - 1; // minus the heartbeat
+    assert!(dumps > 2, "{dumps} dumps");
+    let data = IdleData::from_data(result.data.as_deref().unwrap()).unwrap();
+    assert_eq!(data.changes as usize, dumps - 1);
+    assert!(
+        result
+            .message
+            .ends_with(&format!("to settle ({} changes)", dumps - 1)),
+        "{}",
+        result.message
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 30. Tap with require_enabled refuses a disabled element
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_tap_require_enabled() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Element {
+            json: r#"{"AXUniqueId":"submit","type":"Button","enabled":false}"#.to_string(),
+        }, // FindElement (disabled)
+        Response::Element {
+            json: r#"{"AXUniqueId":"submit","type":"Button","enabled":true}"#.to_string(),
+        }, // FindElement (enabled)
+        Response::Ok, // TapElement
+    ])
+    .await;
+
+    let tap = ActionType::Tap {
+        selector: "submit".to_string(),
+        by_label: false,
+        element_type: None,
+        timeout_ms: Some(1000),
+        require_enabled: true,
+        match_mode: MatchMode::Exact,
+        first: false,
+    };
+
+    let result = executor.execute(tap.clone()).await;
+    assert!(!result.success, "disabled element must not be tapped");
+    assert_eq!(result.message, "Element 'submit' is disabled");
+    assert_eq!(requests.lock().unwrap().len(), 2, "no tap was sent");
+
+    let result = executor.execute(tap).await;
+    assert!(
+        result.success,
+        "enabled element is tapped: {}",
+        result.message
+    );
+    assert!(matches!(
+        requests.lock().unwrap().last(),
+        Some(Request::TapElement { .. })
+    ));
+}
+
+// ---------------------------------------------------------------------------
+// 31. FindAll asks the agent, falling back to a tree search on older agents
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_find_all() {
+    let cells = r#"[
+        {"AXUniqueId":"row-1","type":"Cell","children":[]},
+        {"AXUniqueId":"row-2","type":"Cell","children":[]}
+    ]"#;
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Tree {
+            json: cells.to_string(),
+        }, // FindAll
+        Response::Error {
+            message: "invalid opcode: 0x1F".to_string(),
+        }, // FindAll on an agent without the opcode
+        Response::Tree {
+            json: format!(
+                r#"[{{"AXUniqueId":"table","type":"Table","children":{}}}]"#,
+                cells
+            ),
+        }, // DumpTree
+    ])
+    .await;
+
+    let find_cells = ActionType::FindAll {
+        selector: String::new(),
+        by_label: false,
+        element_type: Some("Cell".to_string()),
+    };
+
+    let result = executor.execute(find_cells.clone()).await;
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.message, "Found 2 elements");
+    assert!(matches!(
+        requests.lock().unwrap().last(),
+        Some(Request::FindAll { element_type: Some(t), .. }) if t == "Cell"
+    ));
+
+    let result = executor.execute(find_cells).await;
+    assert!(result.success, "{}", result.message);
+    let found: Vec<UIElement> = serde_json::from_str(result.data.as_deref().unwrap()).unwrap();
+    let ids: Vec<_> = found
+        .iter()
+        .filter_map(|e| e.identifier.as_deref())
+        .collect();
+    assert_eq!(ids, ["row-1", "row-2"], "the table itself is filtered out");
+    assert!(matches!(
+        requests.lock().unwrap().last(),
+        Some(Request::DumpTree)
+    ));
+}
+
+// ---------------------------------------------------------------------------
+// 32. Glob selectors resolve to exactly one element before acting
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_glob_selector() {
+    let one = r#"[{"AXUniqueId":"row-2","type":"Cell","children":[]}]"#;
+    let two = r#"[
+        {"AXUniqueId":"row-1","type":"Cell","children":[]},
+        {"AXUniqueId":"row-2","type":"Cell","children":[]}
+    ]"#;
+    let tree = |json: &str| Response::Tree {
+        json: json.to_string(),
+    };
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        tree(one),    // FindAll, single match
+        Response::Ok, // TapElement
+        tree(two),    // FindAll, two matches
+        tree(two),    // FindAll, two matches with first
+        Response::Ok, // TapElement
+        tree("[]"),   // FindAll, no match
+    ])
+    .await;
+
+    let tap = |first: bool| ActionType::Tap {
+        selector: "row-*".to_string(),
+        by_label: false,
+        element_type: None,
+        timeout_ms: None,
+        require_enabled: false,
+        match_mode: MatchMode::Glob,
+        first,
+    };
+    let last_tapped = || match requests.lock().unwrap().last() {
+        Some(Request::TapElement { selector, .. }) => Some(selector.clone()),
+        _ => None,
+    };
+
+    let result = executor.execute(tap(false)).await;
+    assert!(result.success, "{}", result.message);
+    assert_eq!(
+        result.message,
+        "Tapped element 'row-2' (glob match for 'row-*')"
+    );
+    assert_eq!(last_tapped().as_deref(), Some("row-2"));
+
+    let result = executor.execute(tap(false)).await;
+    assert!(!result.success);
+    assert!(
+        result.message.contains("matches 2 elements (row-1, row-2)"),
+        "{}",
+        result.message
+    );
+    assert!(matches!(
+        requests.lock().unwrap().last(),
+        Some(Request::FindAll { .. })
+    ));
+
+    let result = executor.execute(tap(true)).await;
+    assert!(result.success, "{}", result.message);
+    assert_eq!(last_tapped().as_deref(), Some("row-1"));
+
+    let result = executor.execute(tap(false)).await;
+    assert!(!result.success);
+    assert_eq!(result.message, "No element matches glob 'row-*'");
+}
+
+// ---------------------------------------------------------------------------
+// 33. TapIfExists taps a present element and skips an absent one
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_tap_if_exists() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Element {
+            json: r#"{"AXLabel":"Not Now","type":"Button"}"#.to_string(),
+        }, // FindElement (present)
+        Response::Ok, // TapByLabel
+        Response::Element {
+            json: "null".to_string(),
+        }, // FindElement (absent)
+    ])
+    .await;
+
+    let dismiss = ActionType::TapIfExists {
+        selector: "Not Now".to_string(),
+        by_label: true,
+        element_type: None,
+    };
+
+    let result = executor.execute(dismiss.clone()).await;
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.message, "Tapped element with label 'Not Now'");
+    let data = TapIfExistsData::from_data(result.data.as_deref().unwrap()).unwrap();
+    assert!(data.tapped);
+    assert_eq!(
+        requests.lock().unwrap()[2],
+        Request::TapByLabel {
+            label: "Not Now".to_string(),
+            timeout_ms: None,
+        }
+    );
+
+    let result = executor.execute(dismiss).await;
+    assert!(
+        result.success,
+        "absence is not a failure: {}",
+        result.message
+    );
+    assert_eq!(
+        result.message,
+        "No element with label 'Not Now' on screen; nothing tapped"
+    );
+    let data = TapIfExistsData::from_data(result.data.as_deref().unwrap()).unwrap();
+    assert!(!data.tapped);
+    // Only the lookup went out; nothing was tapped
+    assert_eq!(requests.lock().unwrap().len(), 4);
+}
+
+// ---------------------------------------------------------------------------
+// 34. SwipeElement swipes inside the element's frame
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_swipe_element() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Element {
+            json: r#"{"AXUniqueId":"carousel","frame":{"x":0,"y":300,"width":390,"height":200}}"#
+                .to_string(),
+        }, // FindElement
+        Response::Ok, // Swipe
+        Response::Element {
+            json: "null".to_string(),
+        }, // FindElement (missing)
+        Response::Element {
+            json: r#"{"AXUniqueId":"carousel"}"#.to_string(),
+        }, // FindElement (no frame)
+    ])
+    .await;
+
+    let swipe = |direction: &str| ActionType::SwipeElement {
+        selector: "carousel".to_string(),
+        by_label: false,
+        direction: direction.to_string(),
+    };
+
+    let result = executor.execute(swipe("left")).await;
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.message, "Swiped left in 'carousel'");
+    assert_eq!(
+        requests.lock().unwrap().last(),
+        Some(&Request::Swipe {
+            start_x: 312,
+            start_y: 400,
+            end_x: 78,
+            end_y: 400,
+            duration: Some(0.3),
+        })
+    );
+
+    let result = executor.execute(swipe("left")).await;
+    assert!(!result.success);
+    assert!(
+        result
+            .message
+            .contains("Element with ID 'carousel' not found"),
+        "unexpected message: {}",
+        result.message
+    );
+
+    let result = executor.execute(swipe("left")).await;
+    assert!(!result.success);
+    assert!(
+        result.message.contains("element has no frame: carousel"),
+        "unexpected message: {}",
+        result.message
+    );
+
+    // An invalid direction fails before the lookup
+    let result = executor.execute(swipe("sideways")).await;
+    assert!(!result.success);
+    assert!(result
+        .message
+        .contains("Invalid swipe direction 'sideways'"));
+    assert_eq!(requests.lock().unwrap().len(), 5);
+}
+
+// ---------------------------------------------------------------------------
+// 35. SwipeCoords swipes between explicit points and validates its input
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_swipe_coords() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Ok, // Swipe
+    ])
+    .await;
+
+    let swipe = |start_x: i32, duration: Option<f64>| ActionType::SwipeCoords {
+        start_x,
+        start_y: 400,
+        end_x: 50,
+        end_y: 400,
+        duration,
+    };
+
+    let result = executor.execute(swipe(300, Some(0.8))).await;
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.message, "Swiped from (300, 400) to (50, 400)");
+    assert_eq!(
+        requests.lock().unwrap().last(),
+        Some(&Request::Swipe {
+            start_x: 300,
+            start_y: 400,
+            end_x: 50,
+            end_y: 400,
+            duration: Some(0.8),
+        })
+    );
+
+    let result = executor.execute(swipe(-1, None)).await;
+    assert!(!result.success);
+    assert!(result.message.contains("must be non-negative"));
+    for duration in [0.0, -0.5, f64::NAN] {
+        let result = executor.execute(swipe(300, Some(duration))).await;
+        assert!(!result.success);
+        assert!(
+            result.message.contains("duration must be positive"),
+            "unexpected message: {}",
+            result.message
+        );
+    }
+    // Invalid input never reached the agent
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+// ---------------------------------------------------------------------------
+// 36. TapElementOffset taps a fraction of the way across the element's frame
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_tap_element_offset() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Element {
+            json:
+                r#"{"AXUniqueId":"volume-slider","frame":{"x":20,"y":400,"width":300,"height":40}}"#
+                    .to_string(),
+        }, // FindElement
+        Response::Ok, // TapCoord
+    ])
+    .await;
+
+    let tap = |dx_fraction: f64, dy_fraction: f64| ActionType::TapElementOffset {
+        selector: "volume-slider".to_string(),
+        by_label: false,
+        dx_fraction,
+        dy_fraction,
+    };
+
+    let result = executor.execute(tap(0.8, 0.5)).await;
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.message, "Tapped 'volume-slider' at (260, 420)");
+    assert_eq!(
+        requests.lock().unwrap().last(),
+        Some(&Request::TapCoord { x: 260, y: 420 })
+    );
+
+    for (dx, dy) in [(1.2, 0.5), (0.5, -0.1), (f64::NAN, 0.5)] {
+        let result = executor.execute(tap(dx, dy)).await;
+        assert!(!result.success);
+        assert!(
+            result.message.contains("fraction must be between 0 and 1"),
+            "unexpected message: {}",
+            result.message
+        );
+    }
+    // Out-of-range fractions fail before the lookup
+    assert_eq!(requests.lock().unwrap().len(), 3);
+}
+
+// ---------------------------------------------------------------------------
+// 37. execute_batch packs consecutive gestures into one Batch frame
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_batch_packs_gestures() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Batch {
+            responses: vec![Response::Ok, Response::Ok],
+        }, // TapCoord + TypeText
+    ])
+    .await;
+
+    let results = executor
+        .execute_batch(vec![
+            ActionType::TapLocation { x: 10, y: 20 },
+            ActionType::SendKeys {
+                text: "hi".to_string(),
+            },
+            ActionType::LogComment {
+                message: "checkpoint".to_string(),
+            },
+        ])
+        .await;
+
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|r| r.success));
+    assert_eq!(results[0].message, "Tapped at (10, 20)");
+    assert_eq!(results[1].message, "Sent keys: 'hi'");
+    assert_eq!(
+        requests.lock().unwrap().last(),
+        Some(&Request::Batch {
+            requests: vec![
+                Request::TapCoord { x: 10, y: 20 },
+                Request::TypeText {
+                    text: "hi".to_string()
+                },
+            ],
+        })
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 38. execute_batch stops at the first failure and returns partial results
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_batch_stops_at_first_failure() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Batch {
+            responses: vec![
+                Response::Ok,
+                Response::Error {
+                    message: "Swipe failed: boom".to_string(),
+                },
+            ],
+        },
+    ])
+    .await;
+
+    let results = executor
+        .execute_batch(vec![
+            ActionType::TapLocation { x: 10, y: 20 },
+            ActionType::Swipe {
+                direction: "up".to_string(),
+            },
+            ActionType::TapLocation { x: 30, y: 40 },
+            ActionType::Tap {
+                selector: "never-reached".to_string(),
+                by_label: false,
+                element_type: None,
+                timeout_ms: None,
+                require_enabled: false,
+                match_mode: MatchMode::Exact,
+                first: false,
+            },
+        ])
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].success);
+    assert!(!results[1].success);
+    assert!(results[1].message.contains("Swipe failed: boom"));
+    // Heartbeat plus the one batch; the tap after it was never sent
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+// ---------------------------------------------------------------------------
+// 39. execute_batch falls back to one request per action on older agents
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_batch_falls_back_without_opcode() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Error {
+            message: "invalid opcode: 0x20".to_string(),
+        }, // Batch
+        Response::Ok, // TapCoord
+        Response::Ok, // LongPress
+    ])
+    .await;
+
+    let results = executor
+        .execute_batch(vec![
+            ActionType::TapLocation { x: 10, y: 20 },
+            ActionType::LongPress {
+                x: 5,
+                y: 6,
+                duration: 0.5,
+            },
+        ])
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|r| r.success));
+    let requests = requests.lock().unwrap();
+    assert_eq!(
+        requests[2..],
+        [
+            Request::TapCoord { x: 10, y: 20 },
+            Request::LongPress {
+                x: 5,
+                y: 6,
+                duration: 0.5
+            },
+        ]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 40. send-keys presses {name} tokens as special keys between text
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_send_keys_with_key_tokens() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Ok, // TypeText
+        Response::Ok, // SendKey
+        Response::Ok, // TypeText
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::SendKeys {
+            text: "user{tab}secret".to_string(),
+        })
+        .await;
+    assert!(result.success, "send-keys failed: {}", result.message);
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(
+        requests[1..],
+        [
+            Request::TypeText {
+                text: "user".to_string()
+            },
+            Request::SendKey {
+                key: SpecialKey::Tab
+            },
+            Request::TypeText {
+                text: "secret".to_string()
+            },
+        ]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 41. send-key types a control character on agents without the opcode
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_send_key_falls_back_without_opcode() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Error {
+            message: "invalid opcode: 0x23".to_string(),
+        }, // SendKey (return)
+        Response::Ok, // TypeText
+        Response::Error {
+            message: "invalid opcode: 0x23".to_string(),
+        }, // SendKey (escape)
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::SendKey {
+            key: SpecialKey::Return,
+        })
+        .await;
+    assert!(result.success, "send-key failed: {}", result.message);
+    assert_eq!(result.message, "Pressed return");
+
+    // Escape has no control character the old agent can type
+    let result = executor
+        .execute(ActionType::SendKey {
+            key: SpecialKey::Escape,
+        })
+        .await;
+    assert!(!result.success);
+    assert_eq!(
+        result.message,
+        "Special keys are not supported by this backend"
+    );
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(
+        requests[2],
+        Request::TypeText {
+            text: "\n".to_string()
+        }
+    );
+    assert_eq!(requests.len(), 4);
+}
+
+// ---------------------------------------------------------------------------
+// 42. get-attribute reads one field of the found element as JSON
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_get_attribute() {
+    let element = r#"{
+        "AXUniqueId": "email",
+        "AXLabel": "Email",
+        "type": "TextField",
+        "placeholder": "you@example.com",
+        "frame": {"x": 20.0, "y": 100.0, "width": 300.0, "height": 44.0},
+        "children": []
+    }"#;
+    let executor = connected_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Element {
+            json: element.to_string(),
+        },
+        Response::Element {
+            json: element.to_string(),
+        },
+        Response::Element {
+            json: "null".to_string(),
+        },
+    ])
+    .await;
+
+    let get = |attribute| ActionType::GetAttribute {
+        selector: "email".to_string(),
+        by_label: false,
+        element_type: None,
+        attribute,
+    };
+
+    let result = executor.execute(get(ElementAttribute::Placeholder)).await;
+    assert!(result.success, "get-attribute failed: {}", result.message);
+    assert_eq!(result.data.as_deref(), Some("\"you@example.com\""));
+    assert_eq!(result.message, "Got placeholder of 'email'");
+
+    // A field the element lacks reads as null
+    let result = executor.execute(get(ElementAttribute::Value)).await;
+    assert!(result.success);
+    assert_eq!(result.data.as_deref(), Some("null"));
+    assert_eq!(result.message, "Element 'email' has no value");
+
+    let result = executor.execute(get(ElementAttribute::Label)).await;
+    assert!(!result.success);
+    assert!(result.element_not_found, "{}", result.message);
+}
+
+// ---------------------------------------------------------------------------
+// 43. Assertion records the script's verdict without touching the driver
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_assertion_ignores_driver() {
+    // Not connected: an assertion must never reach the driver
+    let driver = AgentDriver::new("127.0.0.1".to_string(), 1);
+    let executor = ActionExecutor::new(Arc::new(driver));
+
+    let result = executor
+        .execute(ActionType::Assertion {
+            description: "welcome banner is shown".to_string(),
+            passed: true,
+        })
+        .await;
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.message, "Assertion passed: welcome banner is shown");
+
+    let result = executor
+        .execute(ActionType::Assertion {
+            description: "welcome banner is shown".to_string(),
+            passed: false,
+        })
+        .await;
+    assert!(!result.success);
+    assert_eq!(result.message, "Assertion failed: welcome banner is shown");
+}
+
+// ---------------------------------------------------------------------------
+// 44. WaitForAny reports the first selector that appears
+// ---------------------------------------------------------------------------
+
+fn spec(selector: &str) -> SelectorSpec {
+    SelectorSpec {
+        selector: selector.to_string(),
+        by_label: false,
+        element_type: None,
+    }
+}
+
+#[tokio::test]
+async fn test_executor_wait_for_any_reports_first_match() {
+    let absent = || Response::Element {
+        json: "null".to_string(),
+    };
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        absent(),     // poll 1: home-view
+        absent(),     // poll 1: error-banner
+        absent(),     // poll 1: onboarding
+        absent(),     // poll 2: home-view
+        Response::Element {
+            json: r#"{"AXUniqueId":"error-banner","hittable":true}"#.to_string(),
+        }, // poll 2: error-banner
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::WaitForAny {
+            selectors: vec![spec("home-view"), spec("error-banner"), spec("onboarding")],
+            timeout_ms: 5000,
+            poll_interval_ms: Some(10),
+        })
+        .await;
+
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.message, "Found error-banner");
+    let data = WaitForAnyData::from_data(result.data.as_deref().unwrap()).unwrap();
+    assert_eq!((data.index, data.selector.as_str()), (1, "error-banner"));
+
+    // The match ends the poll: onboarding is not looked up a second time
+    let looked_up: Vec<String> = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|r| match r {
+            Request::FindElement { selector, .. } => Some(selector.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        looked_up,
+        [
+            "home-view",
+            "error-banner",
+            "onboarding",
+            "home-view",
+            "error-banner"
+        ]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 45. WaitForAll succeeds only once every selector is present
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_wait_for_all_requires_every_selector() {
+    let present = |id: &str| Response::Element {
+        json: format!(r#"{{"AXUniqueId":"{}","hittable":true}}"#, id),
+    };
+    let executor = connected_executor(vec![
+        Response::Ok,     // heartbeat
+        present("title"), // poll 1: title
+        Response::Element {
+            json: "null".to_string(),
+        }, // poll 1: save-button
+        present("title"), // poll 2: title
+        present("save-button"), // poll 2: save-button
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::WaitForAll {
+            selectors: vec![spec("title"), spec("save-button")],
+            timeout_ms: 5000,
+            poll_interval_ms: Some(10),
+        })
+        .await;
+
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.message, "All 2 elements found");
+
+    // An empty list has nothing to wait for
+    let result = executor
+        .execute(ActionType::WaitForAll {
+            selectors: vec![],
+            timeout_ms: 5000,
+            poll_interval_ms: None,
+        })
+        .await;
+    assert!(!result.success);
+}
+
+// ---------------------------------------------------------------------------
+// 46. screenshot_with asks the agent for the requested image format
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_screenshot_with_requests_each_format() {
+    let png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    let jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0];
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Screenshot { data: png.clone() },
+        Response::Screenshot { data: jpeg.clone() },
+    ])
+    .await;
+    let driver = executor.driver();
+
+    assert_eq!(
+        driver.screenshot_with(ScreenshotFormat::Png).await.unwrap(),
+        png
+    );
+    assert_eq!(
+        driver
+            .screenshot_with(ScreenshotFormat::Jpeg { quality: 70 })
+            .await
+            .unwrap(),
+        jpeg
+    );
+
+    let formats: Vec<ScreenshotFormat> = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|r| match r {
+            Request::Screenshot { format } => Some(*format),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        formats,
+        [
+            ScreenshotFormat::Png,
+            ScreenshotFormat::Jpeg { quality: 70 }
+        ]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// 47. long-press by selector, falling back to the frame center on old agents
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_executor_long_press_element() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Ok, // LongPressElement
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::LongPressElement {
+            selector: "message-bubble".to_string(),
+            by_label: false,
+            duration: 1.0,
+        })
+        .await;
+    assert!(result.success, "long press failed: {}", result.message);
+    assert_eq!(result.message, "Long pressed 'message-bubble' for 1.0s");
+    assert!(requests.lock().unwrap().iter().any(|r| matches!(
+        r,
+        Request::LongPressElement { selector, by_label: false, duration }
+            if selector == "message-bubble" && *duration == 1.0
+    )));
+}
+
+#[tokio::test]
+async fn test_executor_long_press_element_falls_back_to_frame_center() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Error {
+            message: "decode error: invalid opcode: 0x25".to_string(),
+        },
+        Response::Element {
+            json: r#"{"AXLabel":"Hello there","type":"Cell","frame":{"x":20,"y":200,"width":350,"height":51}}"#
+                .to_string(),
+        },
+        Response::Ok, // LongPress
+    ])
+    .await;
+
+    let result = executor
+        .execute(ActionType::LongPressElement {
+            selector: "Hello there".to_string(),
+            by_label: true,
+            duration: 0.5,
+        })
+        .await;
+    assert!(result.success, "long press failed: {}", result.message);
+
+    // Center of the frame, rounded: (20 + 350 / 2, 200 + 51 / 2)
+    let presses: Vec<(i32, i32, f64)> = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|r| match r {
+            Request::LongPress { x, y, duration } => Some((*x, *y, *duration)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(presses, [(195, 226, 0.5)]);
+}
+
+#[tokio::test]
+async fn test_executor_long_press_element_rejects_bad_durations() {
+    let (executor, requests) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+    ])
+    .await;
+
+    for duration in [0.0, -1.0, f64::NAN] {
+        let result = executor
+            .execute(ActionType::LongPressElement {
+                selector: "message-bubble".to_string(),
+                by_label: false,
+                duration,
+            })
+            .await;
+        assert!(!result.success);
+        assert!(
+            result.message.contains("must be positive"),
+            "{}",
+            result.message
+        );
+    }
+    assert!(!requests.lock().unwrap().iter().any(|r| matches!(
+        r,
+        Request::LongPressElement { .. } | Request::LongPress { .. }
+    )));
+}
+
+// ---------------------------------------------------------------------------
+// 48. A config file's poll_interval_ms and retries reach the executor and driver
+// ---------------------------------------------------------------------------
+
+/// Writes a TOML driver config for an agent at `addr`, plus `extra` keys.
+fn agent_config(addr: std::net::SocketAddr, extra: &str) -> DriverConfig {
+    let path = std::env::temp_dir().join(format!("qorvex-{}.toml", uuid::Uuid::new_v4()));
+    let contents = format!(
+        "backend = \"agent\"\nhost = \"{}\"\nport = {}\n{extra}",
+        addr.ip(),
+        addr.port()
+    );
+    std::fs::write(&path, contents).unwrap();
+    let config = DriverConfig::from_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    config.unwrap()
+}
+
+#[tokio::test]
+async fn test_config_poll_interval_sets_wait_cadence() {
+    let present = || Response::Element {
+        json: r#"{"AXUniqueId":"spinner","type":"ActivityIndicator"}"#.to_string(),
+    };
+    let mut responses = vec![Response::Ok]; // heartbeat
+    responses.extend(std::iter::repeat_with(present).take(8)); // FindElement polls
+    let (addr, requests) = common::recording_mock_agent(responses).await;
+    let config = agent_config(addr, "poll_interval_ms = 300\n");
+    let executor = ActionExecutor::from_config_connected(config).await.unwrap();
+
+    let result = executor
+        .execute(ActionType::WaitForNot {
+            selector: "spinner".to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: 1000,
+            poll_interval_ms: None,
+        })
+        .await;
+
+    assert!(!result.success, "spinner never disappears");
+    // Polls at 0, 300, 600 and 900ms and at the 1000ms deadline
+    let polls = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|r| matches!(r, Request::FindElement { .. }))
+        .count();
+    assert_eq!(polls, 5);
+}
+
+#[tokio::test]
+async fn test_config_retries_limits_reconnects() {
+    use qorvex_core::agent_lifecycle::{AgentLifecycle, AgentLifecycleConfig};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Reads one request frame, or returns false once the client is gone.
+    async fn read_request(stream: &mut tokio::net::TcpStream) -> bool {
+        let mut header = [0u8; 4];
+        if stream.read_exact(&mut header).await.is_err() {
+            return false;
+        }
+        let mut payload = vec![0u8; read_frame_length(&header) as usize];
+        stream.read_exact(&mut payload).await.is_ok()
+    }
+
+    // Answers the heartbeat of every connection; the first `drops`
+    // connections then drop the next request, the rest answer it.
+    async fn flaky_agent(drops: usize) -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for connection in 0.. {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+                while read_request(&mut stream).await {
+                    stream
+                        .write_all(&encode_response(&Response::Ok))
+                        .await
+                        .unwrap();
+                    if connection < drops && read_request(&mut stream).await {
+                        break; // drop the connection
+                    }
+                }
+            }
+        });
+        addr
+    }
+
+    let lifecycle = || {
+        Arc::new(AgentLifecycle::new(
+            "FAKE-UDID".to_string(),
+            AgentLifecycleConfig::new(std::path::PathBuf::from("/nonexistent")),
+        ))
+    };
+
+    // Two dropped requests: two retries reconnect twice and succeed
+    let addr = flaky_agent(2).await;
+    let config = agent_config(addr, "retries = 2\n").with_lifecycle(lifecycle());
+    let mut driver = qorvex_core::driver::create(&config).unwrap();
+    driver.connect().await.unwrap();
+    driver.tap_location(50, 50).await.unwrap();
+    assert_eq!(driver.recovery_count(), 2);
+
+    // No retries: the first dropped request fails without reconnecting
+    let addr = flaky_agent(1).await;
+    let config = agent_config(addr, "retries = 0\n").with_lifecycle(lifecycle());
+    let mut driver = qorvex_core::driver::create(&config).unwrap();
+    driver.connect().await.unwrap();
+    assert!(driver.tap_location(50, 50).await.is_err());
+    assert_eq!(driver.recovery_count(), 0);
+}
+
+// ---------------------------------------------------------------------------
+// 49. Tap and get-value with a timeout retry while the element is not found
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_tap_with_timeout_retries_not_found() {
+    let not_found = || Response::Error {
+        message: "Element with ID 'login-button' not found".into(),
+    };
+    let (executor, log) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        not_found(),
+        not_found(),
+        Response::Ok,
+    ])
+    .await;
+    let executor = executor.with_poll_interval(std::time::Duration::from_millis(10));
+
+    let result = executor
+        .execute(ActionType::Tap {
+            selector: "login-button".to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: Some(2000),
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
+        })
+        .await;
+
+    assert!(result.success, "tap failed: {}", result.message);
+    let taps = log
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|r| matches!(r, Request::TapElement { .. }))
+        .count();
+    assert_eq!(taps, 3);
+}
+
+#[tokio::test]
+async fn test_get_value_with_timeout_retries_not_found() {
+    let (executor, log) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Error {
+            message: "Element with ID 'email' not found".into(),
+        },
+        Response::Value {
+            value: Some("a@b.c".into()),
+        },
+    ])
+    .await;
+    let executor = executor.with_poll_interval(std::time::Duration::from_millis(10));
+
+    let result = executor
+        .execute(ActionType::GetValue {
+            selector: "email".to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: Some(2000),
+            match_mode: MatchMode::Exact,
+            first: false,
+        })
+        .await;
+
+    assert!(result.success, "get-value failed: {}", result.message);
+    assert_eq!(log.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_tap_with_timeout_does_not_retry_other_errors() {
+    let (executor, log) = connected_recording_executor(vec![
+        Response::Ok, // heartbeat
+        Response::Error {
+            message: "Agent is busy".into(),
+        },
+    ])
+    .await;
+    let executor = executor.with_poll_interval(std::time::Duration::from_millis(10));
+
+    let result = executor
+        .execute(ActionType::Tap {
+            selector: "login-button".to_string(),
+            by_label: false,
+            element_type: None,
+            timeout_ms: Some(2000),
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
+        })
+        .await;
+
+    assert!(!result.success);
+    assert_eq!(log.lock().unwrap().len(), 2);
+}
@@ -12,7 +12,8 @@ use std::time::Duration;
 use tokio::time::timeout;
 
 use common::TestHarness;
-use qorvex_core::action::ActionType;
+use qorvex_core::action::{ActionType, MatchMode};
+use qorvex_core::driver::ElementFilter;
 use qorvex_core::ipc::{IpcRequest, IpcResponse};
 use qorvex_core::protocol::Response;
 use qorvex_core::session::SessionEvent;
@@ -38,8 +39,12 @@ async fn test_tap_via_ipc_to_mock_agent() {
                 by_label: false,
                 element_type: None,
                 timeout_ms: None,
+                require_enabled: false,
+                match_mode: MatchMode::Exact,
+                first: false,
             },
             tag: None,
+            capture_screenshot: None,
         })
         .await
         .unwrap();
@@ -81,6 +86,7 @@ async fn test_screenshot_via_ipc_to_mock_agent() {
         .send(&IpcRequest::Execute {
             action: ActionType::GetScreenshot,
             tag: None,
+            capture_screenshot: None,
         })
         .await
         .unwrap();
@@ -96,6 +102,7 @@ async fn test_screenshot_via_ipc_to_mock_agent() {
             message,
             screenshot,
             data,
+            ..
         } => {
             assert!(success, "screenshot should succeed: {}", message);
             // The executor base64-encodes the raw PNG bytes.
@@ -146,8 +153,12 @@ async fn test_screen_info_via_ipc_to_mock_agent() {
 
     let response = client
         .send(&IpcRequest::Execute {
-            action: ActionType::GetScreenInfo,
+            action: ActionType::GetScreenInfo {
+                filter: ElementFilter::default(),
+                changed_since_hash: None,
+            },
             tag: None,
+            capture_screenshot: None,
         })
         .await
         .unwrap();
@@ -193,8 +204,12 @@ async fn test_action_logged_after_ipc_execute() {
                 by_label: false,
                 element_type: None,
                 timeout_ms: None,
+                require_enabled: false,
+                match_mode: MatchMode::Exact,
+                first: false,
             },
             tag: None,
+            capture_screenshot: None,
         })
         .await
         .unwrap();
@@ -242,6 +257,7 @@ async fn test_screenshot_event_broadcasts_via_full_stack() {
         .send(&IpcRequest::Execute {
             action: ActionType::GetScreenshot,
             tag: None,
+            capture_screenshot: None,
         })
         .await
         .unwrap();
@@ -294,8 +310,12 @@ async fn test_multiple_sequential_actions_via_ipc() {
                 by_label: false,
                 element_type: None,
                 timeout_ms: None,
+                require_enabled: false,
+                match_mode: MatchMode::Exact,
+                first: false,
             },
             tag: None,
+            capture_screenshot: None,
         })
         .await
         .unwrap();
@@ -311,6 +331,7 @@ async fn test_multiple_sequential_actions_via_ipc() {
                 text: "admin".to_string(),
             },
             tag: None,
+            capture_screenshot: None,
         })
         .await
         .unwrap();
@@ -324,6 +345,7 @@ async fn test_multiple_sequential_actions_via_ipc() {
         .send(&IpcRequest::Execute {
             action: ActionType::GetScreenshot,
             tag: None,
+            capture_screenshot: None,
         })
         .await
         .unwrap();
@@ -358,3 +380,127 @@ async fn test_multiple_sequential_actions_via_ipc() {
         other => panic!("Expected Log response, got {:?}", other),
     }
 }
+
+// =============================================================================
+// 7. Cancel a long wait from a second client
+// =============================================================================
+
+#[tokio::test]
+async fn test_cancel_in_flight_wait_from_second_client() {
+    // Heartbeat, then enough "not found" polls to outlast the cancel.
+    let mut responses = vec![Response::Ok];
+    responses.extend((0..100).map(|_| Response::Element {
+        json: "null".to_string(),
+    }));
+    let harness = TestHarness::start(responses).await;
+
+    let mut waiter = harness.connect_client().await;
+    let wait = tokio::spawn(async move {
+        waiter
+            .send(&IpcRequest::Execute {
+                action: ActionType::WaitFor {
+                    selector: "never-appears".to_string(),
+                    by_label: false,
+                    element_type: None,
+                    timeout_ms: 30_000,
+                    require_stable: false,
+                    poll_interval_ms: None,
+                    match_mode: MatchMode::Exact,
+                    first: false,
+                },
+                tag: None,
+                capture_screenshot: None,
+            })
+            .await
+            .unwrap()
+    });
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let mut canceller = harness.connect_client().await;
+    match canceller.send(&IpcRequest::Cancel).await.unwrap() {
+        IpcResponse::CommandResult { success, message } => {
+            assert!(success);
+            assert_eq!(message, "Cancelled running action");
+        }
+        other => panic!("Expected CommandResult, got {:?}", other),
+    }
+
+    let response = timeout(Duration::from_secs(2), wait)
+        .await
+        .expect("wait-for should stop promptly after cancel")
+        .unwrap();
+    match response {
+        IpcResponse::ActionResult {
+            success, message, ..
+        } => {
+            assert!(!success);
+            assert_eq!(message, "Action cancelled");
+        }
+        other => panic!("Expected ActionResult, got {:?}", other),
+    }
+
+    // Nothing is running any more: cancel is a benign no-op.
+    match canceller.send(&IpcRequest::Cancel).await.unwrap() {
+        IpcResponse::CommandResult { success, message } => {
+            assert!(success);
+            assert_eq!(message, "No action running");
+        }
+        other => panic!("Expected CommandResult, got {:?}", other),
+    }
+}
+
+// =============================================================================
+// 8. Snapshot reflects logged actions and the device
+// =============================================================================
+
+#[tokio::test]
+async fn test_snapshot_reflects_logged_actions_and_device() {
+    let png_data = vec![0x89, 0x50, 0x4E, 0x47];
+    let harness = TestHarness::start_on_device(
+        Some("SIM-UDID-1"),
+        vec![
+            Response::Ok, // heartbeat
+            Response::Ok, // tap
+            Response::Screenshot {
+                data: png_data.clone(),
+            },
+        ],
+    )
+    .await;
+    let mut client = harness.connect_client().await;
+
+    let snapshot = match client.send(&IpcRequest::GetSnapshot).await.unwrap() {
+        IpcResponse::Snapshot { snapshot } => snapshot,
+        other => panic!("Expected Snapshot response, got {:?}", other),
+    };
+    assert_eq!(snapshot.session_id, harness.session.id);
+    assert_eq!(snapshot.udid.as_deref(), Some("SIM-UDID-1"));
+    assert_eq!(snapshot.action_count, 0);
+    assert!(snapshot.last_screenshot.is_none());
+    assert_eq!(snapshot.started_at, harness.session.created_at);
+
+    for action in [
+        ActionType::TapLocation { x: 1, y: 2 },
+        ActionType::GetScreenshot,
+    ] {
+        let _ = client
+            .send(&IpcRequest::Execute {
+                action,
+                tag: None,
+                capture_screenshot: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let snapshot = match client.send(&IpcRequest::GetSnapshot).await.unwrap() {
+        IpcResponse::Snapshot { snapshot } => snapshot,
+        other => panic!("Expected Snapshot response, got {:?}", other),
+    };
+    assert_eq!(snapshot.action_count, 2);
+    let expected_b64 = {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD.encode(&png_data)
+    };
+    assert_eq!(snapshot.last_screenshot.as_deref(), Some(&expected_b64));
+}
@@ -11,9 +11,9 @@ use std::time::Duration;
 
 use common::{programmable_mock_agent, MockBehavior};
 
-use qorvex_core::action::ActionType;
+use qorvex_core::action::{ActionType, MatchMode};
 use qorvex_core::agent_driver::AgentDriver;
-use qorvex_core::driver::AutomationDriver;
+use qorvex_core::driver::{AutomationDriver, DriverError};
 use qorvex_core::executor::ActionExecutor;
 use qorvex_core::protocol::Response;
 
@@ -34,6 +34,9 @@ fn tap_action() -> ActionType {
         by_label: false,
         element_type: None,
         timeout_ms: None,
+        require_enabled: false,
+        match_mode: MatchMode::Exact,
+        first: false,
     }
 }
 
@@ -75,7 +78,7 @@ async fn test_agent_drops_connection_mid_session() {
 #[tokio::test]
 async fn test_agent_hangs_triggers_timeout() {
     // Wrap the entire test in a timeout to prevent CI hangs. The AgentClient
-    // has a 30-second READ_TIMEOUT, so we allow up to 45 seconds.
+    // has a 30-second DEFAULT_READ_TIMEOUT, so we allow up to 45 seconds.
     let outcome = tokio::time::timeout(Duration::from_secs(45), async {
         let executor = programmable_executor(vec![
             MockBehavior::Respond(Response::Ok), // heartbeat
@@ -99,6 +102,27 @@ async fn test_agent_hangs_triggers_timeout() {
     );
 }
 
+#[tokio::test]
+async fn test_agent_hang_fails_at_configured_read_timeout() {
+    let addr = programmable_mock_agent(vec![
+        MockBehavior::Respond(Response::Ok), // heartbeat
+        MockBehavior::Hang,                  // action: agent never responds
+    ])
+    .await;
+    let mut driver = AgentDriver::new(addr.ip().to_string(), addr.port())
+        .with_read_timeout(Duration::from_millis(200));
+    driver.connect().await.unwrap();
+
+    let started = std::time::Instant::now();
+    let result = driver.tap_location(10, 20).await;
+    assert!(
+        matches!(result, Err(DriverError::Timeout)),
+        "expected Timeout, got {result:?}"
+    );
+    // Well under the 30-second default, so the configured value was used.
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
 // ---------------------------------------------------------------------------
 // 3. Agent sends garbage bytes instead of a valid response
 // ---------------------------------------------------------------------------
@@ -229,6 +253,7 @@ async fn test_wait_for_not_succeeds_on_transient_error() {
         by_label: false,
         element_type: None,
         timeout_ms: 5000,
+        poll_interval_ms: None,
     };
 
     let result = executor.execute(action).await;
@@ -269,3 +294,34 @@ async fn test_connection_drop_without_lifecycle_no_recovery() {
         result.message
     );
 }
+
+// ---------------------------------------------------------------------------
+// 9. Ping measures the heartbeat round trip, including agent delay
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_ping_measures_agent_delay() {
+    let executor = programmable_executor(vec![
+        MockBehavior::Respond(Response::Ok), // heartbeat
+        MockBehavior::Delay(Duration::from_millis(50), Response::Ok), // ping
+    ])
+    .await;
+
+    let latency = executor.driver().ping().await.expect("ping should succeed");
+    assert!(
+        latency >= Duration::from_millis(50),
+        "latency should include the 50ms agent delay, got {:?}",
+        latency
+    );
+}
+
+#[tokio::test]
+async fn test_ping_after_drop_returns_error() {
+    let executor = programmable_executor(vec![
+        MockBehavior::Respond(Response::Ok), // heartbeat
+        MockBehavior::Drop,                  // ping: drop connection
+    ])
+    .await;
+
+    assert!(executor.driver().ping().await.is_err());
+}
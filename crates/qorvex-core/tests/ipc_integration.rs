@@ -2,6 +2,7 @@
 //!
 //! Tests cover:
 //! - IPC server/client connection
+//! - Token authentication
 //! - Message serialization/deserialization (JSON-over-newlines protocol)
 //! - Session event broadcasting
 //! - Action logging and retrieval
@@ -14,9 +15,10 @@ use tokio::time::timeout;
 
 use common::unique_session_name;
 
-use qorvex_core::action::{ActionResult, ActionType};
-use qorvex_core::ipc::{IpcClient, IpcRequest, IpcResponse, IpcServer};
-use qorvex_core::session::{Session, SessionEvent};
+use qorvex_core::action::{ActionResult, ActionType, MatchMode};
+use qorvex_core::driver::ElementFilter;
+use qorvex_core::ipc::{IpcClient, IpcError, IpcRequest, IpcResponse, IpcServer};
+use qorvex_core::session::{latest_log, LogLimits, Session, SessionEvent};
 
 /// Helper to start the IPC server in a background task
 async fn start_server(session: Arc<Session>, session_name: &str) -> tokio::task::JoinHandle<()> {
@@ -74,6 +76,117 @@ async fn test_multiple_clients_can_connect() {
     assert!(client2.is_ok(), "Second client should connect");
 }
 
+// =============================================================================
+// IPC Authentication Tests
+// =============================================================================
+
+/// Helper to start an IPC server requiring the given token
+async fn start_server_with_token(
+    session: Arc<Session>,
+    session_name: &str,
+    token: &str,
+) -> tokio::task::JoinHandle<()> {
+    let server = IpcServer::new(session, session_name).with_auth_token(Some(token.to_string()));
+    tokio::spawn(async move {
+        let _ = server.run().await;
+    })
+}
+
+#[tokio::test]
+async fn test_ipc_auth_accepts_matching_token() {
+    let session_name = unique_session_name();
+    let session = Session::new(None, "test");
+
+    let _server_handle = start_server_with_token(session, &session_name, "s3cret").await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let mut client = IpcClient::connect_with_token(&session_name, Some("s3cret"))
+        .await
+        .expect("Client with matching token should connect");
+
+    let response = client.send(&IpcRequest::GetState).await.unwrap();
+    assert!(
+        matches!(response, IpcResponse::State { .. }),
+        "Authenticated client should be served, got {:?}",
+        response
+    );
+}
+
+#[tokio::test]
+async fn test_ipc_auth_rejects_wrong_token() {
+    let session_name = unique_session_name();
+    let session = Session::new(None, "test");
+
+    let _server_handle = start_server_with_token(session, &session_name, "s3cret").await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let result = IpcClient::connect_with_token(&session_name, Some("wrong")).await;
+    match result {
+        Err(IpcError::Unauthorized(message)) => {
+            assert!(message.contains("Invalid IPC token"), "got: {}", message)
+        }
+        Err(e) => panic!("Expected Unauthorized, got {:?}", e),
+        Ok(_) => panic!("Client with wrong token should be rejected"),
+    }
+}
+
+#[tokio::test]
+async fn test_ipc_auth_rejects_requests_before_auth() {
+    let session_name = unique_session_name();
+    let session = Session::new(None, "test");
+
+    let _server_handle = start_server_with_token(session, &session_name, "s3cret").await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let mut client = IpcClient::connect_with_token(&session_name, None)
+        .await
+        .unwrap();
+
+    let response = client.send(&IpcRequest::GetState).await.unwrap();
+    match response {
+        IpcResponse::Error { message } => {
+            assert!(
+                message.contains("Authentication required"),
+                "got: {}",
+                message
+            )
+        }
+        other => panic!("Expected Error before auth, got {:?}", other),
+    }
+
+    // Authenticating on the same connection unlocks it
+    let response = client
+        .send(&IpcRequest::Auth {
+            token: "s3cret".to_string(),
+        })
+        .await
+        .unwrap();
+    assert!(matches!(
+        response,
+        IpcResponse::CommandResult { success: true, .. }
+    ));
+    let response = client.send(&IpcRequest::GetState).await.unwrap();
+    assert!(matches!(response, IpcResponse::State { .. }));
+}
+
+#[tokio::test]
+async fn test_ipc_auth_ignored_when_server_has_no_token() {
+    let session_name = unique_session_name();
+    let session = Session::new(None, "test");
+
+    let server = IpcServer::new(session, &session_name).with_auth_token(None);
+    let _server_handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let mut client = IpcClient::connect_with_token(&session_name, Some("anything"))
+        .await
+        .expect("Server without a token should accept any client");
+    let response = client.send(&IpcRequest::GetState).await.unwrap();
+    assert!(matches!(response, IpcResponse::State { .. }));
+}
+
 // =============================================================================
 // Message Serialization/Deserialization Tests (JSON-over-newlines protocol)
 // =============================================================================
@@ -86,8 +199,12 @@ fn test_ipc_request_execute_serialization() {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         tag: None,
+        capture_screenshot: None,
     };
 
     let json = serde_json::to_string(&request).unwrap();
@@ -149,6 +266,7 @@ fn test_ipc_response_action_result_serialization() {
         message: "Tapped element".to_string(),
         screenshot: Some(Arc::new("base64data".to_string())),
         data: None,
+        element_not_found: false,
     };
 
     let json = serde_json::to_string(&response).unwrap();
@@ -259,24 +377,35 @@ fn test_all_action_types_serialization() {
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         ActionType::Tap {
             selector: "Sign In".to_string(),
             by_label: true,
             element_type: Some("Button".to_string()),
             timeout_ms: None,
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         ActionType::TapLocation { x: 100, y: 200 },
         ActionType::LogComment {
             message: "test".to_string(),
         },
         ActionType::GetScreenshot,
-        ActionType::GetScreenInfo,
+        ActionType::GetScreenInfo {
+            filter: ElementFilter::default(),
+            changed_since_hash: None,
+        },
         ActionType::GetValue {
             selector: "field".to_string(),
             by_label: false,
             element_type: None,
             timeout_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         ActionType::SendKeys {
             text: "hello".to_string(),
@@ -287,6 +416,9 @@ fn test_all_action_types_serialization() {
             element_type: None,
             timeout_ms: 5000,
             require_stable: true,
+            poll_interval_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
         },
         ActionType::StartSession,
         ActionType::EndSession,
@@ -427,6 +559,59 @@ async fn test_action_with_screenshot_broadcasts_two_events() {
     assert!(matches!(event2, SessionEvent::ActionLogged(_)));
 }
 
+#[tokio::test]
+async fn test_session_skips_unchanged_screenshot_events() {
+    let session = Session::new(None, "test");
+    let mut receiver = session.subscribe();
+
+    session.update_screenshot("same_image".to_string()).await;
+    session.update_screenshot("same_image".to_string()).await;
+    // A logged action with the same image is still logged, without the event
+    session
+        .log_action(
+            ActionType::GetScreenshot,
+            ActionResult::Success,
+            Some("same_image".to_string()),
+            None,
+            None,
+        )
+        .await;
+
+    let event = timeout(Duration::from_millis(100), receiver.recv())
+        .await
+        .expect("Should receive first event")
+        .expect("Should receive event");
+    assert!(matches!(event, SessionEvent::ScreenshotUpdated(_)));
+    let event = timeout(Duration::from_millis(100), receiver.recv())
+        .await
+        .expect("Should receive second event")
+        .expect("Should receive event");
+    assert!(matches!(event, SessionEvent::ActionLogged(_)));
+    assert!(receiver.try_recv().is_err());
+
+    // A different image is broadcast again
+    session.update_screenshot("other_image".to_string()).await;
+    let event = receiver
+        .try_recv()
+        .expect("Changed screenshot should broadcast");
+    assert!(matches!(event, SessionEvent::ScreenshotUpdated(data) if *data == "other_image"));
+}
+
+#[tokio::test]
+async fn test_session_screenshot_dedup_can_be_disabled() {
+    let session = Session::new(None, "test");
+    session.set_screenshot_dedup(false);
+    let mut receiver = session.subscribe();
+
+    session.update_screenshot("same_image".to_string()).await;
+    session.update_screenshot("same_image".to_string()).await;
+
+    for _ in 0..2 {
+        let event = receiver.try_recv().expect("Each update should broadcast");
+        assert!(matches!(event, SessionEvent::ScreenshotUpdated(_)));
+    }
+}
+
 // =============================================================================
 // Action Logging and Retrieval Tests
 // =============================================================================
@@ -452,6 +637,9 @@ async fn test_session_logs_actions() {
                 by_label: false,
                 element_type: None,
                 timeout_ms: None,
+                require_enabled: false,
+                match_mode: MatchMode::Exact,
+                first: false,
             },
             ActionResult::Success,
             None,
@@ -484,6 +672,143 @@ async fn test_session_logs_actions() {
     assert!(matches!(logs[2].result, ActionResult::Failure(_)));
 }
 
+#[tokio::test]
+async fn test_session_log_limits_evict_and_trim_screenshots() {
+    let session_name = unique_session_name();
+    let log_dir = std::env::temp_dir().join(format!("qorvex_test_{}", session_name));
+    let limits = LogLimits {
+        max_entries: 5,
+        max_screenshots: 2,
+    };
+    let session = Session::new_with_limits(None, &session_name, log_dir.clone(), limits);
+    assert_eq!(session.log_limits, limits);
+
+    for i in 0..8 {
+        session
+            .log_action(
+                ActionType::LogComment {
+                    message: format!("entry {}", i),
+                },
+                ActionResult::Success,
+                Some(format!("screenshot {}", i)),
+                None,
+                None,
+            )
+            .await;
+    }
+
+    // Only the newest five entries remain, oldest first
+    let logs = session.get_action_log().await;
+    let messages: Vec<_> = logs
+        .iter()
+        .map(|log| match &log.action {
+            ActionType::LogComment { message } => message.as_str(),
+            other => panic!("unexpected action {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        messages,
+        ["entry 3", "entry 4", "entry 5", "entry 6", "entry 7"]
+    );
+
+    // Only the newest two keep their screenshot
+    let screenshots: Vec<_> = logs
+        .iter()
+        .map(|log| log.screenshot.as_deref().map(String::as_str))
+        .collect();
+    assert_eq!(
+        screenshots,
+        [None, None, None, Some("screenshot 6"), Some("screenshot 7")]
+    );
+
+    let _ = std::fs::remove_dir_all(&log_dir);
+}
+
+#[tokio::test]
+async fn test_session_log_reloads_from_disk() {
+    let session_name = unique_session_name();
+    let log_dir = std::env::temp_dir().join(format!("qorvex_test_{}", session_name));
+    let session = Session::new_with_log_dir(None, &session_name, log_dir.clone());
+
+    // "iVBORw0KGgo=" is the base64 of the PNG signature
+    session
+        .log_action(
+            ActionType::GetScreenshot,
+            ActionResult::Success,
+            Some("iVBORw0KGgo=".to_string()),
+            Some(12),
+            Some("first".to_string()),
+        )
+        .await;
+    session
+        .log_action(
+            ActionType::SendKeys {
+                text: "hello".to_string(),
+            },
+            ActionResult::Failure("No keyboard".to_string()),
+            None,
+            None,
+            None,
+        )
+        .await;
+    let original = session.get_action_log().await;
+
+    // The log file references the screenshot by path instead of embedding it
+    let path = latest_log(&log_dir, &session_name).expect("log file written");
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains("iVBORw0KGgo="));
+    let screenshot_path = original[0].screenshot_path.clone().unwrap();
+    assert!(contents.contains(&screenshot_path));
+    assert_eq!(
+        std::fs::read(&screenshot_path).unwrap(),
+        b"\x89PNG\r\n\x1a\n"
+    );
+
+    // A new session rehydrates the same entries, screenshot included
+    let resumed_dir = log_dir.join("resumed");
+    let resumed = Session::new_with_log_dir(None, &session_name, resumed_dir.clone());
+    assert_eq!(resumed.load_log(&path).await.unwrap(), 2);
+    let reloaded = resumed.get_action_log().await;
+    assert_eq!(reloaded.len(), original.len());
+    for (a, b) in original.iter().zip(&reloaded) {
+        assert_eq!(
+            serde_json::to_value(a).unwrap(),
+            serde_json::to_value(b).unwrap()
+        );
+    }
+
+    // ...and copies them into its own log, so a later resume finds them
+    let copied = Session::read_log(&latest_log(&resumed_dir, &session_name).unwrap()).unwrap();
+    assert_eq!(copied.len(), 2);
+    assert_eq!(copied[0].id, original[0].id);
+
+    let _ = std::fs::remove_dir_all(&log_dir);
+}
+
+#[test]
+fn test_latest_log_picks_newest_of_session() {
+    let log_dir = std::env::temp_dir().join(format!("qorvex_test_{}", unique_session_name()));
+    std::fs::create_dir_all(&log_dir).unwrap();
+    for name in [
+        "app_20260101_090000.jsonl",
+        "app_20260102_090000.jsonl",
+        "app_extra_20260301_090000.jsonl",
+        "app_20260103_090000.txt",
+    ] {
+        std::fs::write(log_dir.join(name), "").unwrap();
+    }
+    assert_eq!(
+        latest_log(&log_dir, "app"),
+        Some(log_dir.join("app_20260102_090000.jsonl"))
+    );
+    assert_eq!(
+        latest_log(&log_dir, "app_extra"),
+        Some(log_dir.join("app_extra_20260301_090000.jsonl"))
+    );
+    assert_eq!(latest_log(&log_dir, "other"), None);
+    let _ = std::fs::remove_dir_all(&log_dir);
+}
+
 #[tokio::test]
 async fn test_session_stores_and_retrieves_screenshot() {
     let session = Session::new(None, "test");
@@ -659,6 +984,7 @@ async fn test_ipc_execute_action_request() {
                 message: "test comment".to_string(),
             },
             tag: None,
+            capture_screenshot: None,
         })
         .await
         .unwrap();
@@ -682,6 +1008,54 @@ async fn test_ipc_execute_action_request() {
     }
 }
 
+#[tokio::test]
+async fn test_ipc_execute_assertion_logs_outcome() {
+    let session_name = unique_session_name();
+    let session = Session::new(None, "test");
+
+    let _server_handle = start_server(session.clone(), &session_name).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let mut client = IpcClient::connect(&session_name).await.unwrap();
+
+    for passed in [true, false] {
+        let response = client
+            .send(&IpcRequest::Execute {
+                action: ActionType::Assertion {
+                    description: "banner shown".to_string(),
+                    passed,
+                },
+                tag: None,
+                capture_screenshot: None,
+            })
+            .await
+            .unwrap();
+        match response {
+            IpcResponse::ActionResult {
+                success, message, ..
+            } => {
+                assert_eq!(success, passed);
+                assert!(message.contains("banner shown"), "{}", message);
+            }
+            _ => panic!("Expected ActionResult response, got {:?}", response),
+        }
+    }
+
+    // Both outcomes are logged as assertions, the failed one as a failure
+    let logs = session.get_action_log().await;
+    assert_eq!(logs.len(), 2);
+    assert_eq!(
+        logs[0].action.assertion_summary().as_deref(),
+        Some("✓ banner shown")
+    );
+    assert!(matches!(logs[0].result, ActionResult::Success));
+    assert_eq!(
+        logs[1].action.assertion_summary().as_deref(),
+        Some("✗ banner shown")
+    );
+    assert!(matches!(logs[1].result, ActionResult::Failure(_)));
+}
+
 #[tokio::test]
 async fn test_ipc_execute_action_without_simulator_returns_error() {
     let session_name = unique_session_name();
@@ -700,8 +1074,12 @@ async fn test_ipc_execute_action_without_simulator_returns_error() {
                 by_label: false,
                 element_type: None,
                 timeout_ms: None,
+                require_enabled: false,
+                match_mode: MatchMode::Exact,
+                first: false,
             },
             tag: None,
+            capture_screenshot: None,
         })
         .await
         .unwrap();
@@ -734,6 +1112,7 @@ async fn test_ipc_multiple_requests_same_client() {
                 message: "test".to_string(),
             },
             tag: None,
+            capture_screenshot: None,
         })
         .await
         .unwrap();
@@ -748,6 +1127,67 @@ async fn test_ipc_multiple_requests_same_client() {
     }
 }
 
+#[tokio::test]
+async fn test_ipc_subscribe_pings_idle_session() {
+    let session_name = unique_session_name();
+    let session = Session::new(None, "test");
+
+    let server = IpcServer::new(session.clone(), &session_name)
+        .with_ping_interval(Duration::from_millis(50));
+    let _server_handle = tokio::spawn(async move {
+        let _ = server.run().await;
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let mut client = IpcClient::connect(&session_name).await.unwrap();
+    client.subscribe().await.unwrap();
+
+    // Nothing happens in the session, so only keepalives arrive, each
+    // announcing the server's interval
+    for _ in 0..2 {
+        let response = timeout(Duration::from_secs(1), client.read_event())
+            .await
+            .expect("Should receive a ping within timeout")
+            .unwrap();
+        assert!(
+            matches!(
+                response,
+                IpcResponse::Ping {
+                    interval_ms: Some(50)
+                }
+            ),
+            "Expected Ping, got {:?}",
+            response
+        );
+    }
+
+    // Events still come through between pings
+    session
+        .log_action(
+            ActionType::GetScreenshot,
+            ActionResult::Success,
+            None,
+            None,
+            None,
+        )
+        .await;
+    let event = loop {
+        let response = timeout(Duration::from_secs(1), client.read_event())
+            .await
+            .expect("Should receive the event within timeout")
+            .unwrap();
+        if !matches!(response, IpcResponse::Ping { .. }) {
+            break response;
+        }
+    };
+    assert!(matches!(
+        event,
+        IpcResponse::Event {
+            event: SessionEvent::ActionLogged(_)
+        }
+    ));
+}
+
 // =============================================================================
 // Persistent Log File Tests
 // =============================================================================
@@ -782,6 +1222,9 @@ async fn test_session_creates_persistent_log_file() {
                 by_label: false,
                 element_type: None,
                 timeout_ms: None,
+                require_enabled: false,
+                match_mode: MatchMode::Exact,
+                first: false,
             },
             ActionResult::Success,
             None,
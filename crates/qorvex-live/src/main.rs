@@ -5,15 +5,20 @@
 // reroute non-Press events to the catch-all arm. Allow it crate-wide here.
 #![allow(clippy::collapsible_match)]
 
+mod stream;
+
 use clap::Parser;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
+        MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
@@ -21,21 +26,26 @@ use ratatui::{
 };
 use ratatui_image::{picker::Picker, protocol::StatefulProtocol, StatefulImage};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
 use tracing_subscriber::EnvFilter;
 
 use qorvex_core::action::ActionLog;
+use qorvex_core::action::ActionType;
 use qorvex_core::adb_device::Adb;
+use qorvex_core::driver::ScreenInfo;
+use qorvex_core::element::UIElement;
 use qorvex_core::ipc::Platform;
-use qorvex_core::ipc::{IpcClient, IpcResponse};
+use qorvex_core::ipc::{IpcClient, IpcRequest, IpcResponse, DEFAULT_PING_INTERVAL};
+use qorvex_core::screen_recording;
 use qorvex_core::session::SessionEvent;
 use qorvex_core::simctl::Simctl;
+use stream::{FrameReader, FrameStats};
 
 /// Target platform for the monitored session (CLI-facing; maps to
 /// [`qorvex_core::ipc::Platform`]).
@@ -95,6 +105,14 @@ struct Args {
     /// Duration in seconds for batch mode (exit after this many seconds)
     #[arg(long)]
     duration: Option<u64>,
+
+    /// In batch mode, save periodic screenshots to this directory
+    #[arg(long, requires = "batch")]
+    screenshot_dir: Option<PathBuf>,
+
+    /// Seconds between batch-mode screenshots (default: 5)
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+    screenshot_interval: u64,
 }
 
 /// Maximum number of consecutive IPC connection failures before giving up
@@ -103,6 +121,12 @@ const MAX_IPC_RETRIES: u32 = 10;
 const IPC_RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
 /// Maximum delay between retry attempts
 const IPC_RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+/// Missed server pings after which the subscription is treated as dead
+const IPC_MISSED_PINGS: u32 = 3;
+/// How often the element inspector re-fetches elements while it is open
+const ELEMENT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How long the crosshair stays on a clicked point
+const TAP_MARKER_DURATION: Duration = Duration::from_millis(600);
 
 #[derive(Debug, Clone, PartialEq)]
 enum StreamerStatus {
@@ -116,9 +140,21 @@ enum StreamerStatus {
 enum AppEvent {
     SessionEvent(SessionEvent),
     ScreenshotReady(Vec<u8>),
-    StreamerFrame(Vec<u8>),
+    StreamerFrame(stream::Frame),
     StreamerStatus(StreamerStatus),
     ImageReady(StatefulProtocol, u32, u32),
+    ElementsReady(Vec<UIElement>),
+    ScreenInfoReady(ScreenInfo),
+}
+
+/// What the main loop should do after a key press.
+#[derive(Debug, PartialEq)]
+enum KeyOutcome {
+    None,
+    Quit,
+    Refresh,
+    /// The inspector was opened; fetch elements right away.
+    FetchElements,
 }
 
 struct App {
@@ -137,9 +173,22 @@ struct App {
     should_quit: bool,
     streamer_active: bool,
     streamer_status: StreamerStatus,
+    /// Frame rate and drops of the current streamer connection.
+    frame_stats: FrameStats,
     image_picker: Picker,
     image_state: Option<StatefulProtocol>,
     image_pixel_size: Option<(u32, u32)>,
+    /// Whether the element inspector panel is shown (toggled with `e`).
+    inspector_visible: bool,
+    /// Actionable elements on screen, as last fetched for the inspector.
+    elements: Vec<UIElement>,
+    inspector_state: ListState,
+    /// Device screen size in points, used to map clicks on the image to taps.
+    screen_info: Option<ScreenInfo>,
+    /// Where the screenshot was last drawn, in terminal cells.
+    image_area: Option<Rect>,
+    /// Cell of the last click-to-tap and when it happened, for the crosshair.
+    tap_marker: Option<(u16, u16, Instant)>,
 }
 
 impl App {
@@ -151,6 +200,16 @@ impl App {
         // iOS-only (no Android streamer — arch decision 3), so `simulator_udid`
         // is only set for iOS.
         let screenshot_source = Self::resolve_source(platform);
+        Self::with_source(session_name, platform, screenshot_source, picker)
+    }
+
+    /// Build the app around an already-resolved screenshot source.
+    fn with_source(
+        session_name: String,
+        platform: Platform,
+        screenshot_source: Option<ScreenshotSource>,
+        picker: Picker,
+    ) -> Self {
         let simulator_udid = match &screenshot_source {
             Some(ScreenshotSource::Ios(udid)) => Some(udid.clone()),
             _ => None,
@@ -167,9 +226,16 @@ impl App {
             should_quit: false,
             streamer_active: false,
             streamer_status: StreamerStatus::Disconnected,
+            frame_stats: FrameStats::default(),
             image_picker: picker,
             image_state: None,
             image_pixel_size: None,
+            inspector_visible: false,
+            elements: Vec::new(),
+            inspector_state: ListState::default(),
+            screen_info: None,
+            image_area: None,
+            tap_marker: None,
         }
     }
 
@@ -206,6 +272,64 @@ impl App {
     fn set_image_state(&mut self, state: StatefulProtocol) {
         self.image_state = Some(state);
     }
+
+    /// Replace the inspector's elements, keeping the selection in range.
+    fn set_elements(&mut self, elements: Vec<UIElement>) {
+        self.elements = elements;
+        let selected = match self.inspector_state.selected() {
+            _ if self.elements.is_empty() => None,
+            Some(i) => Some(i.min(self.elements.len() - 1)),
+            None => Some(0),
+        };
+        self.inspector_state.select(selected);
+    }
+
+    /// Map a click on terminal cell (`col`, `row`) to the device point to tap,
+    /// and mark the cell with a crosshair. Returns `None` for clicks outside
+    /// the screenshot, or before the screenshot and screen size are known.
+    fn handle_click(&mut self, col: u16, row: u16) -> Option<(i32, i32)> {
+        let point = cell_to_device(
+            col,
+            row,
+            self.image_area?,
+            self.image_pixel_size?,
+            self.image_picker.font_size(),
+            self.screen_info.as_ref()?,
+        )?;
+        self.tap_marker = Some((col, row, Instant::now()));
+        Some(point)
+    }
+
+    /// Apply a key press. Up/Down scroll the inspector while it is open, and
+    /// the action log otherwise.
+    fn handle_key(&mut self, code: KeyCode) -> KeyOutcome {
+        let (state, len) = if self.inspector_visible {
+            (&mut self.inspector_state, self.elements.len())
+        } else {
+            (&mut self.list_state, self.action_log.len())
+        };
+        match code {
+            KeyCode::Char('q') => return KeyOutcome::Quit,
+            KeyCode::Char('r') => return KeyOutcome::Refresh,
+            KeyCode::Char('e') => {
+                self.inspector_visible = !self.inspector_visible;
+                if self.inspector_visible {
+                    return KeyOutcome::FetchElements;
+                }
+            }
+            KeyCode::Up => {
+                let i = state.selected().unwrap_or(0);
+                state.select(Some(i.saturating_sub(1)));
+            }
+            KeyCode::Down => {
+                let i = state.selected().unwrap_or(0);
+                let max = len.saturating_sub(1);
+                state.select(Some((i + 1).min(max)));
+            }
+            _ => {}
+        }
+        KeyOutcome::None
+    }
 }
 
 /// Max pixel dimensions to feed into ratatui-image's resize protocol.
@@ -265,15 +389,20 @@ fn spawn_decode_base64_task(
     }
 }
 
+/// Capture a PNG screenshot from the platform-appropriate source (iOS
+/// `simctl` screenshot or Android `adb` screencap). Blocks on the subprocess.
+fn capture_screenshot(source: ScreenshotSource) -> Result<Vec<u8>, String> {
+    match source {
+        ScreenshotSource::Ios(udid) => Simctl::screenshot(&udid).map_err(|e| e.to_string()),
+        ScreenshotSource::Android(serial) => Adb::screencap(&serial).map_err(|e| e.to_string()),
+    }
+}
+
 /// Spawn a blocking task to capture a screenshot from the platform-appropriate
 /// source (iOS `simctl` screenshot or Android `adb` screencap).
 fn spawn_screenshot_task(source: ScreenshotSource, tx: mpsc::Sender<AppEvent>) {
     tokio::spawn(async move {
-        let result = tokio::task::spawn_blocking(move || match source {
-            ScreenshotSource::Ios(udid) => Simctl::screenshot(&udid).map_err(|e| e.to_string()),
-            ScreenshotSource::Android(serial) => Adb::screencap(&serial).map_err(|e| e.to_string()),
-        })
-        .await;
+        let result = tokio::task::spawn_blocking(move || capture_screenshot(source)).await;
 
         if let Ok(Ok(bytes)) = result {
             let _ = tx.send(AppEvent::ScreenshotReady(bytes)).await;
@@ -281,6 +410,112 @@ fn spawn_screenshot_task(source: ScreenshotSource, tx: mpsc::Sender<AppEvent>) {
     });
 }
 
+/// Spawn a task that fetches the current actionable elements over IPC.
+///
+/// Uses `FetchElements`, which reads the tree without logging an action, so
+/// polling from the inspector does not show up in the session log. Returns
+/// false (and does nothing) if a fetch is already in flight.
+fn spawn_elements_task(
+    session_name: &str,
+    tx: mpsc::Sender<AppEvent>,
+    fetching: &Arc<AtomicBool>,
+) -> bool {
+    if fetching
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return false;
+    }
+    let flag = fetching.clone();
+    let session_name = session_name.to_string();
+    tokio::spawn(async move {
+        if let Ok(mut client) = IpcClient::connect(&session_name).await {
+            if let Ok(IpcResponse::CompletionData { elements, .. }) =
+                client.send(&IpcRequest::FetchElements).await
+            {
+                let _ = tx.send(AppEvent::ElementsReady(elements)).await;
+            }
+        }
+        flag.store(false, Ordering::SeqCst);
+    });
+    true
+}
+
+/// Spawn a task that fetches the device screen size over IPC.
+fn spawn_screen_info_task(session_name: &str, tx: mpsc::Sender<AppEvent>) {
+    let session_name = session_name.to_string();
+    tokio::spawn(async move {
+        let Ok(mut client) = IpcClient::connect(&session_name).await else {
+            return;
+        };
+        if let Ok(IpcResponse::ActionResult {
+            success: true,
+            data: Some(data),
+            ..
+        }) = client.send(&IpcRequest::GetDeviceInfo).await
+        {
+            if let Ok(info) = serde_json::from_str::<ScreenInfo>(&data) {
+                let _ = tx.send(AppEvent::ScreenInfoReady(info)).await;
+            }
+        }
+    });
+}
+
+/// Spawn a task that taps the device at (`x`, `y`) points over IPC. The tap
+/// is logged like any other action, so it shows up in the action log.
+fn spawn_tap_task(session_name: &str, x: i32, y: i32) {
+    let session_name = session_name.to_string();
+    tokio::spawn(async move {
+        if let Ok(mut client) = IpcClient::connect(&session_name).await {
+            let request = IpcRequest::Execute {
+                action: ActionType::TapLocation { x, y },
+                tag: None,
+                capture_screenshot: None,
+            };
+            if let Err(e) = client.send(&request).await {
+                tracing::warn!(error = %e, "click-to-tap failed");
+            }
+        }
+    });
+}
+
+/// Map terminal cell (`col`, `row`) to a device point.
+///
+/// The screenshot is drawn at the top-left of `area`, scaled down (never up)
+/// to fit while keeping its aspect ratio, the way ratatui-image's default
+/// `Resize::Fit` does. The centre of the clicked cell is located within the
+/// drawn image, and that fraction of the image is applied to the screen size
+/// in points. Working in fractions keeps the result independent of the
+/// device scale factor and of the thumbnailing done before decoding.
+/// Returns `None` for cells outside the drawn image.
+fn cell_to_device(
+    col: u16,
+    row: u16,
+    area: Rect,
+    image_px: (u32, u32),
+    font_size: (u16, u16),
+    screen: &ScreenInfo,
+) -> Option<(i32, i32)> {
+    let (img_w, img_h) = (image_px.0 as f64, image_px.1 as f64);
+    let (cell_w, cell_h) = (font_size.0.max(1) as f64, font_size.1.max(1) as f64);
+    if img_w <= 0.0 || img_h <= 0.0 || col < area.x || row < area.y {
+        return None;
+    }
+    let ratio = (area.width as f64 * cell_w / img_w)
+        .min(area.height as f64 * cell_h / img_h)
+        .min(1.0);
+    let (drawn_w, drawn_h) = (img_w * ratio, img_h * ratio);
+
+    let px = ((col - area.x) as f64 + 0.5) * cell_w;
+    let py = ((row - area.y) as f64 + 0.5) * cell_h;
+    if px >= drawn_w || py >= drawn_h {
+        return None;
+    }
+    let x = px / drawn_w * screen.width;
+    let y = py / drawn_h * screen.height;
+    Some((x.round() as i32, y.round() as i32))
+}
+
 fn spawn_streamer_task(
     session_name: &str,
     udid: &str,
@@ -312,6 +547,16 @@ fn spawn_streamer_task(
         };
 
         tracing::info!(path = %bin_path.display(), "found qorvex-streamer binary");
+
+        // Without the permission the streamer would only start to exit again
+        if let Err(e) = screen_recording::preflight() {
+            let _ = tx
+                .send(AppEvent::StreamerStatus(StreamerStatus::NotAvailable(
+                    e.to_string(),
+                )))
+                .await;
+            return;
+        }
         let _ = tx
             .send(AppEvent::StreamerStatus(StreamerStatus::Connecting))
             .await;
@@ -369,8 +614,8 @@ fn spawn_streamer_task(
             // Check if child has exited
             if let Ok(Some(status)) = child.try_wait() {
                 let stderr_msg = read_child_stderr(&mut child).await;
-                let msg = if status.code() == Some(2) {
-                    "Screen recording permission required. Grant in System Settings > Privacy > Screen Recording".into()
+                let msg = if status.code() == Some(screen_recording::PERMISSION_DENIED_EXIT_CODE) {
+                    screen_recording::PERMISSION_GUIDANCE.into()
                 } else if !stderr_msg.is_empty() {
                     stderr_msg
                 } else {
@@ -395,18 +640,37 @@ fn spawn_streamer_task(
             .send(AppEvent::StreamerStatus(StreamerStatus::Connected))
             .await;
 
+        // The first read tells a streamer with frame metadata from an older one
+        let reader = tokio::select! {
+            _ = cancel.cancelled() => {
+                let _ = child.kill().await;
+                return;
+            }
+            result = FrameReader::new(tokio::io::BufReader::new(stream)) => result,
+        };
+        let mut reader = match reader {
+            Ok(reader) => reader,
+            Err(e) => {
+                tracing::warn!(error = %e, "unreadable streamer header");
+                let _ = tx
+                    .send(AppEvent::StreamerStatus(StreamerStatus::Disconnected))
+                    .await;
+                let _ = child.kill().await;
+                return;
+            }
+        };
+
         // Read frame loop
-        let mut reader = tokio::io::BufReader::new(stream);
         loop {
             tokio::select! {
                 _ = cancel.cancelled() => {
                     let _ = child.kill().await;
                     return;
                 }
-                result = read_frame(&mut reader) => {
+                result = reader.read_frame() => {
                     match result {
-                        Ok(bytes) => {
-                            let _ = tx.send(AppEvent::StreamerFrame(bytes)).await;
+                        Ok(frame) => {
+                            let _ = tx.send(AppEvent::StreamerFrame(frame)).await;
                         }
                         Err(_) => {
                             let _ = tx.send(AppEvent::StreamerStatus(StreamerStatus::Disconnected)).await;
@@ -438,20 +702,6 @@ async fn read_child_stderr(child: &mut tokio::process::Child) -> String {
     }
 }
 
-async fn read_frame<R: tokio::io::AsyncRead + Unpin>(reader: &mut R) -> std::io::Result<Vec<u8>> {
-    use tokio::io::AsyncReadExt;
-    let len = reader.read_u32_le().await? as usize;
-    if len == 0 || len > 10_000_000 {
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            "invalid frame length",
-        ));
-    }
-    let mut buf = vec![0u8; len];
-    reader.read_exact(&mut buf).await?;
-    Ok(buf)
-}
-
 async fn which_streamer() -> Option<PathBuf> {
     // Check PATH (use tokio::process to avoid blocking the async runtime)
     if let Ok(output) = tokio::process::Command::new("which")
@@ -507,12 +757,112 @@ async fn which_streamer() -> Option<PathBuf> {
     None
 }
 
+/// When batch-mode screenshots are due: the first at start, then one every
+/// `interval`. Ticks missed while a capture ran long are skipped rather than
+/// taken back to back.
+struct CaptureSchedule {
+    next: Instant,
+    interval: Duration,
+    index: u64,
+}
+
+impl CaptureSchedule {
+    fn new(start: Instant, interval: Duration) -> Self {
+        Self {
+            next: start,
+            interval,
+            index: 0,
+        }
+    }
+
+    /// If a capture is due at `now`, returns its index and schedules the
+    /// next one.
+    fn take(&mut self, now: Instant) -> Option<u64> {
+        if now < self.next {
+            return None;
+        }
+        while self.next <= now {
+            self.next += self.interval;
+        }
+        self.index += 1;
+        Some(self.index - 1)
+    }
+}
+
+/// File name for batch-mode screenshot `index` taken at `at`, e.g.
+/// `00007-20260220T100001.250Z.png`. The zero-padded index keeps the files in
+/// capture order when sorted by name.
+fn screenshot_filename(index: u64, at: chrono::DateTime<chrono::Utc>) -> String {
+    format!("{index:05}-{}.png", at.format("%Y%m%dT%H%M%S%.3fZ"))
+}
+
+/// Create the screenshot directory and check it is writable, so a bad path
+/// fails at startup instead of at the first capture.
+fn prepare_screenshot_dir(dir: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(".qorvex-live-write-test");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+/// Capture one batch-mode screenshot into `dir`, re-resolving the device if
+/// none was available yet. Failures are reported on stderr and skipped.
+async fn save_batch_screenshot(
+    dir: &Path,
+    index: u64,
+    platform: Platform,
+    source: &mut Option<ScreenshotSource>,
+) {
+    if source.is_none() {
+        *source = App::resolve_source(platform);
+    }
+    let Some(src) = source.clone() else {
+        eprintln!("No device available for screenshot {}", index);
+        return;
+    };
+    let at = chrono::Utc::now();
+    match tokio::task::spawn_blocking(move || capture_screenshot(src)).await {
+        Ok(Ok(bytes)) => {
+            let path = dir.join(screenshot_filename(index, at));
+            if let Err(e) = tokio::fs::write(&path, bytes).await {
+                eprintln!("Failed to write {}: {}", path.display(), e);
+            }
+        }
+        Ok(Err(e)) => eprintln!("Screenshot {} failed: {}", index, e),
+        Err(e) => eprintln!("Screenshot {} failed: {}", index, e),
+    }
+}
+
 /// Run in batch mode: connect to IPC, print session events as JSONL to stdout, exit after duration.
+///
+/// With `--screenshot-dir`, also saves a screenshot every
+/// `--screenshot-interval` seconds.
 async fn run_batch(args: Args) -> io::Result<()> {
     use tokio::io::AsyncWriteExt;
 
     let session_name = &args.session;
     let duration = args.duration.map(Duration::from_secs);
+    let platform = Platform::from(args.platform);
+
+    if let Some(ref dir) = args.screenshot_dir {
+        if let Err(e) = prepare_screenshot_dir(dir) {
+            eprintln!(
+                "Screenshot directory {} is not writable: {}",
+                dir.display(),
+                e
+            );
+            return Err(e);
+        }
+    }
+    let mut schedule = args.screenshot_dir.as_ref().map(|_| {
+        CaptureSchedule::new(
+            Instant::now(),
+            Duration::from_secs(args.screenshot_interval),
+        )
+    });
+    let mut source = schedule
+        .as_ref()
+        .and_then(|_| App::resolve_source(platform));
 
     // Connect to IPC
     let mut client = match qorvex_core::ipc::IpcClient::connect(session_name).await {
@@ -552,6 +902,14 @@ async fn run_batch(args: Args) -> io::Result<()> {
                 std::future::pending::<()>().await;
             }
         };
+        let next_capture = schedule.as_ref().map(|s| s.next);
+        let capture_fut = async {
+            if let Some(at) = next_capture {
+                tokio::time::sleep_until(at.into()).await;
+            } else {
+                std::future::pending::<()>().await;
+            }
+        };
 
         tokio::select! {
             result = client.read_event() => {
@@ -581,6 +939,13 @@ async fn run_batch(args: Args) -> io::Result<()> {
                 eprintln!("Duration elapsed, exiting.");
                 break;
             }
+            _ = capture_fut => {
+                if let (Some(schedule), Some(dir)) = (schedule.as_mut(), args.screenshot_dir.as_deref()) {
+                    if let Some(index) = schedule.take(Instant::now()) {
+                        save_batch_screenshot(dir, index, platform, &mut source).await;
+                    }
+                }
+            }
             _ = tokio::signal::ctrl_c() => {
                 eprintln!("Interrupted, exiting.");
                 break;
@@ -626,6 +991,8 @@ async fn main() -> io::Result<()> {
     if let Some(source) = app.screenshot_source.clone() {
         spawn_screenshot_task(source, event_tx.clone());
     }
+    // Screen size for click-to-tap
+    spawn_screen_info_task(&app.session_name, event_tx.clone());
 
     // Create cancellation token for graceful shutdown
     let cancel_token = CancellationToken::new();
@@ -650,19 +1017,26 @@ async fn main() -> io::Result<()> {
                     retry_count = 0;
 
                     if client.subscribe().await.is_ok() {
+                        // The server pings idle subscribers, so silence means
+                        // the link is gone. Its first ping, sent right away,
+                        // carries the interval it actually uses.
+                        let mut idle_timeout = DEFAULT_PING_INTERVAL * IPC_MISSED_PINGS;
                         loop {
                             tokio::select! {
                                 _ = ipc_cancel.cancelled() => {
                                     break;
                                 }
-                                result = client.read_event() => {
+                                result = tokio::time::timeout(idle_timeout, client.read_event()) => {
                                     match result {
-                                        Ok(IpcResponse::Event { event }) => {
+                                        Ok(Ok(IpcResponse::Event { event })) => {
                                             if ipc_tx.send(AppEvent::SessionEvent(event)).await.is_err() {
                                                 break;
                                             }
                                         }
-                                        Err(_) => break,
+                                        Ok(Ok(IpcResponse::Ping { interval_ms: Some(ms) })) => {
+                                            idle_timeout = Duration::from_millis(ms) * IPC_MISSED_PINGS;
+                                        }
+                                        Ok(Err(_)) | Err(_) => break,
                                         _ => {}
                                     }
                                 }
@@ -717,6 +1091,9 @@ async fn main() -> io::Result<()> {
 
     // Guard to prevent multiple concurrent decode tasks
     let decoding = Arc::new(AtomicBool::new(false));
+    // Guard and clock for the inspector's element polling
+    let fetching = Arc::new(AtomicBool::new(false));
+    let mut last_fetch = tokio::time::Instant::now();
     let mut needs_redraw = true;
 
     // Main loop
@@ -750,12 +1127,16 @@ async fn main() -> io::Result<()> {
                         latest_screenshot = Some(bytes);
                     }
                 }
-                AppEvent::StreamerFrame(bytes) => {
-                    latest_frame = Some(bytes);
+                AppEvent::StreamerFrame(frame) => {
+                    app.frame_stats.record(frame.meta);
+                    latest_frame = Some(frame.jpeg);
                     app.streamer_active = true;
                 }
                 AppEvent::StreamerStatus(status) => {
                     let changed = app.streamer_status != status;
+                    if status == StreamerStatus::Connecting {
+                        app.frame_stats = FrameStats::default();
+                    }
                     app.streamer_status = status;
                     if matches!(
                         app.streamer_status,
@@ -772,6 +1153,15 @@ async fn main() -> io::Result<()> {
                     app.image_pixel_size = Some((w, h));
                     needs_redraw = true;
                 }
+                AppEvent::ElementsReady(elements) => {
+                    if app.inspector_visible {
+                        app.set_elements(elements);
+                        needs_redraw = true;
+                    }
+                }
+                AppEvent::ScreenInfoReady(info) => {
+                    app.screen_info = Some(info);
+                }
             }
         }
         // Decode only the latest frame/screenshot (streamer frames take priority).
@@ -784,6 +1174,21 @@ async fn main() -> io::Result<()> {
             spawn_decode_base64_task(&b64, app.image_picker.clone(), event_tx.clone(), &decoding);
         }
 
+        if app.inspector_visible
+            && last_fetch.elapsed() >= ELEMENT_POLL_INTERVAL
+            && spawn_elements_task(&app.session_name, event_tx.clone(), &fetching)
+        {
+            last_fetch = tokio::time::Instant::now();
+        }
+
+        if app
+            .tap_marker
+            .is_some_and(|(_, _, at)| at.elapsed() >= TAP_MARKER_DURATION)
+        {
+            app.tap_marker = None;
+            needs_redraw = true;
+        }
+
         if needs_redraw {
             terminal.draw(|f| ui(f, &mut app))?;
             needs_redraw = false;
@@ -795,30 +1200,43 @@ async fn main() -> io::Result<()> {
                 Event::Key(key) => {
                     if key.kind == KeyEventKind::Press {
                         needs_redraw = true;
-                        match key.code {
-                            KeyCode::Char('q') => {
+                        match app.handle_key(key.code) {
+                            KeyOutcome::Quit => {
                                 // Cancel the IPC task before quitting
                                 cancel_token.cancel();
                                 app.should_quit = true;
                             }
-                            KeyCode::Char('r') => {
+                            KeyOutcome::Refresh => {
                                 // Trigger non-blocking screenshot refresh. Re-query
                                 // the device source if none was bound at startup
                                 // (e.g. device not ready yet) so the feed recovers.
                                 if let Some(source) = app.refreshed_source() {
                                     spawn_screenshot_task(source, event_tx.clone());
                                 }
+                                // The orientation may have changed
+                                spawn_screen_info_task(&app.session_name, event_tx.clone());
                             }
-                            KeyCode::Up => {
-                                let i = app.list_state.selected().unwrap_or(0);
-                                app.list_state.select(Some(i.saturating_sub(1)));
-                            }
-                            KeyCode::Down => {
-                                let i = app.list_state.selected().unwrap_or(0);
-                                let max = app.action_log.len().saturating_sub(1);
-                                app.list_state.select(Some((i + 1).min(max)));
+                            KeyOutcome::FetchElements => {
+                                if spawn_elements_task(
+                                    &app.session_name,
+                                    event_tx.clone(),
+                                    &fetching,
+                                ) {
+                                    last_fetch = tokio::time::Instant::now();
+                                }
                             }
-                            _ => {}
+                            KeyOutcome::None => {}
+                        }
+                    }
+                }
+                Event::Mouse(mouse) => {
+                    if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+                        if let Some((x, y)) = app.handle_click(mouse.column, mouse.row) {
+                            spawn_tap_task(&app.session_name, x, y);
+                            needs_redraw = true;
+                        } else if app.screen_info.is_none() {
+                            // Not known yet (e.g. the server started after us)
+                            spawn_screen_info_task(&app.session_name, event_tx.clone());
                         }
                     }
                 }
@@ -876,7 +1294,10 @@ fn ui(f: &mut Frame, app: &mut App) {
 
     // Left: Simulator screenshot
     let sim_title = match &app.streamer_status {
-        StreamerStatus::Connected => " Simulator (live) ".to_string(),
+        StreamerStatus::Connected => match app.frame_stats.summary() {
+            Some(stats) => format!(" Simulator (live, {stats}) "),
+            None => " Simulator (live) ".to_string(),
+        },
         StreamerStatus::Connecting => " Simulator (connecting...) ".to_string(),
         StreamerStatus::Disconnected => " Simulator ".to_string(),
         StreamerStatus::NotAvailable(reason) => format!(" Simulator ({reason}) "),
@@ -896,25 +1317,47 @@ fn ui(f: &mut Frame, app: &mut App) {
     if let Some(ref mut state) = app.image_state {
         let image = StatefulImage::default();
         f.render_stateful_widget(image, inner, state);
+        app.image_area = Some(inner);
+        if let Some((col, row, _)) = app.tap_marker {
+            if inner.contains((col, row).into()) {
+                let marker = Paragraph::new("+")
+                    .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+                f.render_widget(marker, Rect::new(col, row, 1, 1));
+            }
+        }
     } else {
         let placeholder =
             Paragraph::new("No screenshot").style(Style::default().fg(Color::DarkGray));
         f.render_widget(placeholder, inner);
     }
 
-    // Right: Action log
+    // Right: action log, with the element inspector below it when open
+    let (log_area, inspector_area) = if app.inspector_visible {
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[1]);
+        (right[0], Some(right[1]))
+    } else {
+        (chunks[1], None)
+    };
+
     let log_block = Block::default()
-        .title(" Action Log (q=quit, r=refresh, arrow-up/down=scroll) ")
+        .title(" Action Log (q=quit, r=refresh, e=elements, arrow-up/down=scroll) ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
-    let inner_width = log_block.inner(chunks[1]).width as usize;
+    let inner_width = log_block.inner(log_area).width as usize;
 
     let items: Vec<ListItem> = app
         .action_log
         .iter()
         .map(|log| {
             let timestamp = log.timestamp.format("%H:%M:%S%.3f").to_string();
+            if let Some(summary) = log.action.assertion_summary() {
+                let passed = matches!(log.result, qorvex_core::action::ActionResult::Success);
+                return ListItem::new(assertion_line(timestamp, summary, passed));
+            }
             let action_desc = format!("{:?}", log.action);
             let result = match &log.result {
                 qorvex_core::action::ActionResult::Success => "success",
@@ -970,7 +1413,69 @@ fn ui(f: &mut Frame, app: &mut App) {
         .block(log_block)
         .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
 
-    f.render_stateful_widget(list, chunks[1], &mut app.list_state);
+    f.render_stateful_widget(list, log_area, &mut app.list_state);
+
+    if let Some(area) = inspector_area {
+        render_inspector(f, app, area);
+    }
+}
+
+/// An action log line for an assertion: a green check or red cross with
+/// what was checked, in place of the action's details.
+fn assertion_line(timestamp: String, summary: String, passed: bool) -> Line<'static> {
+    let color = if passed { Color::Green } else { Color::Red };
+    Line::from(vec![
+        Span::styled(timestamp, Style::default().fg(Color::Yellow)),
+        Span::raw(" "),
+        Span::styled(
+            summary,
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+    ])
+}
+
+/// Render the element inspector: one line per actionable element, with its
+/// frame size after the origin shown by [`UIElement::summary`].
+fn render_inspector(f: &mut Frame, app: &mut App, area: Rect) {
+    let block = Block::default()
+        .title(format!(
+            " Elements ({}) (e=close, arrow-up/down=scroll) ",
+            app.elements.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta));
+
+    if app.elements.is_empty() {
+        let placeholder = Paragraph::new("No elements")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        f.render_widget(placeholder, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .elements
+        .iter()
+        .map(|elem| {
+            let mut spans = vec![Span::styled(
+                elem.summary(),
+                Style::default().fg(Color::White),
+            )];
+            if let Some(ref frame) = elem.frame {
+                spans.push(Span::styled(
+                    format!(" {:.0}x{:.0}", frame.width, frame.height),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    f.render_stateful_widget(list, area, &mut app.inspector_state);
 }
 
 #[cfg(test)]
@@ -986,6 +1491,58 @@ mod tests {
         assert!(!args.no_streamer);
     }
 
+    #[test]
+    fn test_args_screenshot_dir_requires_batch() {
+        assert!(Args::try_parse_from(["qorvex-live", "--screenshot-dir", "shots"]).is_err());
+        let args = Args::parse_from(["qorvex-live", "--batch", "--screenshot-dir", "shots"]);
+        assert_eq!(args.screenshot_dir, Some(PathBuf::from("shots")));
+        assert_eq!(args.screenshot_interval, 5);
+        assert!(Args::try_parse_from(["qorvex-live", "--screenshot-interval", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_assertion_line_colors_by_outcome() {
+        let line = assertion_line(
+            "09:15:00.000".to_string(),
+            "✗ banner shown".to_string(),
+            false,
+        );
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "09:15:00.000 ✗ banner shown");
+        assert_eq!(line.spans[2].style.fg, Some(Color::Red));
+        let line = assertion_line(String::new(), "✓ ok".to_string(), true);
+        assert_eq!(line.spans[2].style.fg, Some(Color::Green));
+    }
+
+    #[test]
+    fn test_screenshot_filename_sorts_by_index() {
+        use chrono::TimeZone;
+        let at = chrono::Utc
+            .with_ymd_and_hms(2026, 2, 20, 10, 0, 1)
+            .unwrap()
+            .checked_add_signed(chrono::Duration::milliseconds(250))
+            .unwrap();
+        assert_eq!(screenshot_filename(7, at), "00007-20260220T100001.250Z.png");
+        assert!(screenshot_filename(9, at) < screenshot_filename(10, at));
+    }
+
+    #[test]
+    fn test_capture_schedule_skips_missed_ticks() {
+        let start = Instant::now();
+        let secs = Duration::from_secs;
+        let mut schedule = CaptureSchedule::new(start, secs(5));
+
+        // Due immediately, then every interval
+        assert_eq!(schedule.take(start), Some(0));
+        assert_eq!(schedule.take(start + secs(4)), None);
+        assert_eq!(schedule.take(start + secs(5)), Some(1));
+
+        // A capture that overran two ticks does not cause a burst
+        assert_eq!(schedule.take(start + secs(17)), Some(2));
+        assert_eq!(schedule.take(start + secs(18)), None);
+        assert_eq!(schedule.next, start + secs(20));
+    }
+
     #[test]
     fn test_args_batch_mode() {
         let args = Args::parse_from(["qorvex-live", "--batch", "--duration", "5", "-s", "test"]);
@@ -993,4 +1550,86 @@ mod tests {
         assert_eq!(args.duration, Some(5));
         assert_eq!(args.session, "test");
     }
+
+    fn test_app() -> App {
+        App::with_source("test".into(), Platform::Ios, None, Picker::halfblocks())
+    }
+
+    fn element(id: &str) -> UIElement {
+        serde_json::from_value(serde_json::json!({ "AXUniqueId": id, "type": "Button" })).unwrap()
+    }
+
+    fn screen(width: f64, height: f64) -> ScreenInfo {
+        ScreenInfo {
+            width,
+            height,
+            scale: 3.0,
+            orientation: None,
+        }
+    }
+
+    #[test]
+    fn test_cell_to_device_maps_cell_centres() {
+        // A 300x600 px image in 8x16 px cells, drawn unscaled in a 40x40 cell
+        // area at (1, 1): it covers 37.5 columns and 37.5 rows
+        let area = Rect::new(1, 1, 40, 40);
+        let iphone = screen(393.0, 852.0);
+        let map = |col, row| cell_to_device(col, row, area, (300, 600), (8, 16), &iphone);
+
+        // Centre of the first cell: (4, 8) px of 300x600
+        assert_eq!(map(1, 1), Some((5, 11)));
+        // Centre of the middle cell: (148, 296) px
+        assert_eq!(map(19, 19), Some((194, 420)));
+        // Beyond the drawn image, and outside the area
+        assert_eq!(map(39, 10), None);
+        assert_eq!(map(10, 39), None);
+        assert_eq!(map(0, 10), None);
+    }
+
+    #[test]
+    fn test_cell_to_device_accounts_for_downscaling() {
+        // A 1200x1800 px image fit into 20x20 cells of 10x20 px (200x400 px):
+        // scaled by 1/6 to 200x300 px, so only the top 15 rows hold image
+        let area = Rect::new(0, 0, 20, 20);
+        let tablet = screen(800.0, 1200.0);
+        let map = |col, row| cell_to_device(col, row, area, (1200, 1800), (10, 20), &tablet);
+
+        // Cell (10, 7) centre is (105, 150) px of 200x300
+        assert_eq!(map(10, 7), Some((420, 600)));
+        assert_eq!(map(19, 14), Some((780, 1160)));
+        assert_eq!(map(0, 15), None);
+    }
+
+    #[test]
+    fn test_e_toggles_inspector() {
+        let mut app = test_app();
+        assert!(!app.inspector_visible);
+        // Opening asks for an immediate fetch; closing does not
+        assert_eq!(
+            app.handle_key(KeyCode::Char('e')),
+            KeyOutcome::FetchElements
+        );
+        assert!(app.inspector_visible);
+        assert_eq!(app.handle_key(KeyCode::Char('e')), KeyOutcome::None);
+        assert!(!app.inspector_visible);
+        assert_eq!(app.handle_key(KeyCode::Char('q')), KeyOutcome::Quit);
+        assert_eq!(app.handle_key(KeyCode::Char('r')), KeyOutcome::Refresh);
+    }
+
+    #[test]
+    fn test_arrows_scroll_inspector_while_open() {
+        let mut app = test_app();
+        app.handle_key(KeyCode::Char('e'));
+        app.set_elements(vec![element("a"), element("b")]);
+        assert_eq!(app.inspector_state.selected(), Some(0));
+
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.inspector_state.selected(), Some(1));
+        assert_eq!(app.list_state.selected(), None);
+
+        // A shorter refresh keeps the selection in range
+        app.set_elements(vec![element("a")]);
+        assert_eq!(app.inspector_state.selected(), Some(0));
+    }
 }
@@ -0,0 +1,284 @@
+//! Reading JPEG frames from `qorvex-streamer`'s Unix socket.
+//!
+//! Streamers that send frame metadata open each connection with a header:
+//! the magic bytes `QVXS` and a version byte. Version 2 frames are
+//! `[u32 LE length][u64 LE sequence][u64 LE timestamp_ns][JPEG]`, where the
+//! length counts only the JPEG bytes, the sequence numbers every captured
+//! frame (so frames skipped under backpressure leave gaps) and the timestamp
+//! is the capture time on the host's monotonic clock.
+//!
+//! Older streamers send no header and frame each JPEG with just its length.
+//! The magic read as a length is far above [`MAX_FRAME_LEN`], so the two
+//! cannot be confused.
+
+use std::collections::VecDeque;
+use std::io;
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// First bytes of a stream that carries frame metadata.
+pub const STREAM_MAGIC: [u8; 4] = *b"QVXS";
+/// The stream version this reader understands after [`STREAM_MAGIC`].
+pub const STREAM_VERSION: u8 = 2;
+/// Largest JPEG accepted; anything bigger means the stream is out of sync.
+pub const MAX_FRAME_LEN: usize = 10_000_000;
+
+/// Window over which [`FrameStats::fps`] counts frames.
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+/// One frame from the streamer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub jpeg: Vec<u8>,
+    /// Capture metadata; `None` from streamers without a stream header.
+    pub meta: Option<FrameMeta>,
+}
+
+/// Capture metadata of a version 2 frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameMeta {
+    /// Number of the frame among all frames captured on this connection.
+    pub sequence: u64,
+    /// Capture time in nanoseconds on the streamer's monotonic clock.
+    pub timestamp_ns: u64,
+}
+
+/// Reads frames in whichever format the streamer speaks.
+pub struct FrameReader<R> {
+    reader: R,
+    extended: bool,
+    /// Length of the first legacy frame, read while probing for the header.
+    pending_len: Option<u32>,
+}
+
+impl<R: AsyncRead + Unpin> FrameReader<R> {
+    /// Reads the stream header, if any, and returns a reader positioned at
+    /// the first frame.
+    ///
+    /// # Errors
+    ///
+    /// [`io::ErrorKind::InvalidData`] for a stream version other than
+    /// [`STREAM_VERSION`], or any error reading the socket.
+    pub async fn new(mut reader: R) -> io::Result<Self> {
+        let mut head = [0u8; 4];
+        reader.read_exact(&mut head).await?;
+        if head != STREAM_MAGIC {
+            return Ok(Self {
+                reader,
+                extended: false,
+                pending_len: Some(u32::from_le_bytes(head)),
+            });
+        }
+        let version = reader.read_u8().await?;
+        if version != STREAM_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported streamer stream version {version}"),
+            ));
+        }
+        Ok(Self {
+            reader,
+            extended: true,
+            pending_len: None,
+        })
+    }
+
+    /// Reads the next frame.
+    ///
+    /// # Errors
+    ///
+    /// [`io::ErrorKind::InvalidData`] for an empty or oversized frame, or any
+    /// error reading the socket.
+    pub async fn read_frame(&mut self) -> io::Result<Frame> {
+        let len = match self.pending_len.take() {
+            Some(len) => len,
+            None => self.reader.read_u32_le().await?,
+        } as usize;
+        if len == 0 || len > MAX_FRAME_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid frame length",
+            ));
+        }
+        let meta = if self.extended {
+            Some(FrameMeta {
+                sequence: self.reader.read_u64_le().await?,
+                timestamp_ns: self.reader.read_u64_le().await?,
+            })
+        } else {
+            None
+        };
+        let mut jpeg = vec![0u8; len];
+        self.reader.read_exact(&mut jpeg).await?;
+        Ok(Frame { jpeg, meta })
+    }
+}
+
+/// Frame rate and dropped-frame count of the live feed.
+#[derive(Debug, Default)]
+pub struct FrameStats {
+    last_sequence: Option<u64>,
+    dropped: u64,
+    /// Capture times of the frames within [`FPS_WINDOW`] of the newest.
+    recent: VecDeque<Duration>,
+    /// Stands in for the capture clock when frames carry no metadata.
+    started: Option<Instant>,
+}
+
+impl FrameStats {
+    /// Counts a frame received now.
+    pub fn record(&mut self, meta: Option<FrameMeta>) {
+        let at = match meta {
+            Some(meta) => {
+                if let Some(last) = self.last_sequence {
+                    if meta.sequence > last {
+                        self.dropped += meta.sequence - last - 1;
+                    } else {
+                        // A reconnected streamer numbers from the start again
+                        self.recent.clear();
+                    }
+                }
+                self.last_sequence = Some(meta.sequence);
+                Duration::from_nanos(meta.timestamp_ns)
+            }
+            None => self.started.get_or_insert_with(Instant::now).elapsed(),
+        };
+        if self.recent.back().is_some_and(|&newest| at < newest) {
+            self.recent.clear();
+        }
+        self.recent.push_back(at);
+        while self
+            .recent
+            .front()
+            .is_some_and(|&oldest| at - oldest > FPS_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+    }
+
+    /// Frames per second over the last second of capture; `None` until two
+    /// frames have arrived.
+    pub fn fps(&self) -> Option<f64> {
+        let (first, last) = (self.recent.front()?, self.recent.back()?);
+        let span = (*last - *first).as_secs_f64();
+        (span > 0.0).then(|| (self.recent.len() - 1) as f64 / span)
+    }
+
+    /// Frames the streamer captured but never sent, from sequence gaps.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Whether the frames so far carried sequence numbers.
+    pub fn has_sequences(&self) -> bool {
+        self.last_sequence.is_some()
+    }
+
+    /// A short summary for the panel title, e.g. `14.8 fps, 3 dropped`.
+    pub fn summary(&self) -> Option<String> {
+        let fps = self.fps()?;
+        Some(if self.has_sequences() {
+            format!("{fps:.1} fps, {} dropped", self.dropped())
+        } else {
+            format!("{fps:.1} fps")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extended_frame(sequence: u64, timestamp_ns: u64, jpeg: &[u8]) -> Vec<u8> {
+        let mut bytes = (jpeg.len() as u32).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&sequence.to_le_bytes());
+        bytes.extend_from_slice(&timestamp_ns.to_le_bytes());
+        bytes.extend_from_slice(jpeg);
+        bytes
+    }
+
+    fn meta(sequence: u64, millis: u64) -> Option<FrameMeta> {
+        Some(FrameMeta {
+            sequence,
+            timestamp_ns: millis * 1_000_000,
+        })
+    }
+
+    #[tokio::test]
+    async fn reads_extended_frames_after_the_header() {
+        let mut stream = STREAM_MAGIC.to_vec();
+        stream.push(STREAM_VERSION);
+        stream.extend(extended_frame(7, 1_500_000_000, &[0xFF, 0xD8, 0xFF]));
+        stream.extend(extended_frame(9, 1_566_000_000, &[0xFF, 0xD9]));
+
+        let mut reader = FrameReader::new(stream.as_slice()).await.unwrap();
+        let frame = reader.read_frame().await.unwrap();
+        assert_eq!(frame.jpeg, [0xFF, 0xD8, 0xFF]);
+        assert_eq!(
+            frame.meta,
+            Some(FrameMeta {
+                sequence: 7,
+                timestamp_ns: 1_500_000_000
+            })
+        );
+        let frame = reader.read_frame().await.unwrap();
+        assert_eq!(frame.jpeg, [0xFF, 0xD9]);
+        assert_eq!(frame.meta.unwrap().sequence, 9);
+        assert!(reader.read_frame().await.is_err(), "stream ended");
+    }
+
+    #[tokio::test]
+    async fn reads_legacy_frames_without_a_header() {
+        let mut stream = 3u32.to_le_bytes().to_vec();
+        stream.extend_from_slice(&[1, 2, 3]);
+        stream.extend_from_slice(&1u32.to_le_bytes());
+        stream.push(4);
+
+        let mut reader = FrameReader::new(stream.as_slice()).await.unwrap();
+        let first = reader.read_frame().await.unwrap();
+        assert_eq!((first.jpeg, first.meta), (vec![1, 2, 3], None));
+        assert_eq!(reader.read_frame().await.unwrap().jpeg, [4]);
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_versions_and_bad_lengths() {
+        let mut stream = STREAM_MAGIC.to_vec();
+        stream.push(3);
+        let err = FrameReader::new(stream.as_slice()).await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut stream = STREAM_MAGIC.to_vec();
+        stream.push(STREAM_VERSION);
+        stream.extend_from_slice(&0u32.to_le_bytes());
+        let mut reader = FrameReader::new(stream.as_slice()).await.unwrap();
+        let err = reader.read_frame().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn stats_count_sequence_gaps_as_drops() {
+        let mut stats = FrameStats::default();
+        stats.record(meta(1, 0));
+        stats.record(meta(2, 100));
+        stats.record(meta(5, 200));
+        assert_eq!(stats.dropped(), 2);
+
+        // A restarted streamer numbers from 0 again without counting drops
+        stats.record(meta(0, 10));
+        stats.record(meta(1, 110));
+        assert_eq!(stats.dropped(), 2);
+    }
+
+    #[test]
+    fn stats_measure_fps_over_the_last_second() {
+        let mut stats = FrameStats::default();
+        assert_eq!(stats.fps(), None);
+        for i in 0..=30 {
+            stats.record(meta(i, i * 100));
+        }
+        // Frames 20-30 span exactly one second at 10 fps
+        assert_eq!(stats.fps(), Some(10.0));
+        assert_eq!(stats.summary().as_deref(), Some("10.0 fps, 0 dropped"));
+    }
+}
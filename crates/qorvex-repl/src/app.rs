@@ -1,6 +1,9 @@
 //! Application state and event handling.
 
 use std::collections::VecDeque;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::time::Instant;
 
 use ratatui::layout::Rect;
@@ -8,17 +11,26 @@ use ratatui::text::Line;
 use tokio::sync::mpsc;
 use tui_input::Input;
 
-use qorvex_core::action::ActionType;
+use qorvex_core::action::{ActionType, MatchMode};
 use qorvex_core::adb_device::AndroidDevice;
+use qorvex_core::describe::ScreenSummary;
+use qorvex_core::driver::ElementFilter;
 use qorvex_core::element::UIElement;
-use qorvex_core::ipc::{socket_path, IpcClient, IpcRequest, IpcResponse, Platform};
+use qorvex_core::ipc::{
+    socket_path, BatchActionResult, IpcClient, IpcError, IpcRequest, IpcResponse, Platform,
+};
 use qorvex_core::simctl::{InstalledApp, SimulatorDevice};
 
 use crate::completion::commands::ArgCompletion;
 use crate::completion::{
     parse_completion_context, CandidateKind, CompletionContext, CompletionState,
 };
-use crate::format::{format_command, format_device, format_element, format_result};
+use crate::format::{
+    format_booted_warning, format_command, format_device, format_element, format_result,
+};
+use crate::history::{history_path, History, MAX_HISTORY};
+use crate::retry::{parse_retry, Retry};
+use crate::script::{keep_going_flag, Script};
 
 /// Maximum number of lines to keep in output history.
 const MAX_OUTPUT_HISTORY: usize = 1000;
@@ -125,6 +137,10 @@ pub struct App {
     // --- IPC client ---
     /// Session name.
     pub session_name: String,
+    /// Agent port passed to a server this REPL spawns (`--agent-port`).
+    pub agent_port: Option<u16>,
+    /// Driver config file passed to a server this REPL spawns (`--config`).
+    pub server_config: Option<PathBuf>,
     /// IPC client connection to qorvex-server.
     client: Option<IpcClient>,
 
@@ -171,6 +187,16 @@ pub struct App {
     cmd_result_rx: Option<mpsc::Receiver<(CommandResult, IpcClient)>>,
     /// Receiver for startup result (deferred server connect + session start).
     startup_rx: Option<mpsc::Receiver<StartupResult>>,
+
+    // --- Input history ---
+    /// Executed commands, persisted across runs and recalled with Up/Down.
+    pub history: History,
+
+    // --- Scripted input ---
+    /// Commands started by `run` or `:source`, while they run.
+    script: Option<Script>,
+    /// Commands collected in `:source` paste mode, until `:end`.
+    source_buffer: Option<Script>,
 }
 
 /// Number of connect attempts while waiting for a freshly-spawned server to
@@ -184,12 +210,24 @@ const CONNECT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_mil
 /// The server cleans up any stale socket and rebinds on startup, so it is safe
 /// to call even if a stale socket file is present. Returns the spawn error if
 /// the binary can't be launched, so callers can fail fast instead of polling.
-fn spawn_server(session_name: &str) -> std::io::Result<()> {
+/// `agent_port` is forwarded as `--agent-port` and `config` as `--config` when
+/// set.
+fn spawn_server(
+    session_name: &str,
+    agent_port: Option<u16>,
+    config: Option<&Path>,
+) -> std::io::Result<()> {
     let log_dir = qorvex_core::session::logs_dir();
     let log_file = std::fs::File::create(log_dir.join("qorvex-server-launch.log")).ok();
 
     let mut cmd = std::process::Command::new("qorvex-server");
     cmd.args(["-s", session_name]);
+    if let Some(port) = agent_port {
+        cmd.args(["--agent-port", &port.to_string()]);
+    }
+    if let Some(config) = config {
+        cmd.arg("--config").arg(config);
+    }
     if let Some(f) = log_file {
         cmd.stdout(
             f.try_clone()
@@ -212,7 +250,11 @@ fn spawn_server(session_name: &str) -> std::io::Result<()> {
 /// 2. Otherwise spawn the server (it removes any stale socket and rebinds).
 /// 3. Retry the connect with backoff until the server finishes binding
 ///    (~10s budget) instead of failing on a single one-shot attempt.
-async fn ensure_connected(session_name: &str) -> Result<IpcClient, qorvex_core::ipc::IpcError> {
+async fn ensure_connected(
+    session_name: &str,
+    agent_port: Option<u16>,
+    config: Option<&Path>,
+) -> Result<IpcClient, qorvex_core::ipc::IpcError> {
     // A live server is already listening — connect immediately.
     if let Ok(client) = IpcClient::connect(session_name).await {
         return Ok(client);
@@ -221,7 +263,7 @@ async fn ensure_connected(session_name: &str) -> Result<IpcClient, qorvex_core::
     // No live server (missing or stale socket). Spawn one; it rebinds cleanly.
     // A spawn failure (e.g. binary not on PATH) is terminal — fail fast instead
     // of polling the full budget for a server that will never appear.
-    spawn_server(session_name)?;
+    spawn_server(session_name, agent_port, config)?;
 
     // Poll the actual connection until the freshly-spawned server is accepting.
     let mut last_err = None;
@@ -292,6 +334,8 @@ impl App {
             output_area: None,
             should_quit: false,
             session_name,
+            agent_port: None,
+            server_config: None,
             client: None,
             cached_elements: Vec::new(),
             cached_devices: Vec::new(),
@@ -311,6 +355,9 @@ impl App {
             processing_start: None,
             cmd_result_rx: None,
             startup_rx: None,
+            history: History::load(history_path(), MAX_HISTORY),
+            script: None,
+            source_buffer: None,
         };
 
         app.add_output(Line::from("Type 'help' for available commands."));
@@ -320,11 +367,17 @@ impl App {
     }
 
     /// Create a new App with blocking server startup (for batch mode).
-    pub async fn new_blocking(session_name: String) -> Self {
+    pub async fn new_blocking(
+        session_name: String,
+        agent_port: Option<u16>,
+        server_config: Option<PathBuf>,
+    ) -> Self {
         let mut app = Self::new(session_name.clone());
+        app.agent_port = agent_port;
+        app.server_config = server_config;
 
         let sock = socket_path(&session_name);
-        match ensure_connected(&session_name).await {
+        match ensure_connected(&session_name, agent_port, app.server_config.as_deref()).await {
             Ok(mut c) => {
                 app.add_output(Line::from(format!(
                     "Connected to server | Session: {} | Socket: {:?}",
@@ -348,6 +401,9 @@ impl App {
                     android_devices,
                 }) = c.send(&IpcRequest::GetCompletionData).await
                 {
+                    if let Some(warning) = format_booted_warning(&devices) {
+                        app.add_output(warning);
+                    }
                     app.cached_elements = elements;
                     app.cached_devices = devices;
                     app.cached_android_devices = android_devices;
@@ -373,6 +429,8 @@ impl App {
     /// Call this after the first TUI frame so the user sees the spinner.
     pub fn startup(&mut self) {
         let session_name = self.session_name.clone();
+        let agent_port = self.agent_port;
+        let server_config = self.server_config.clone();
         let (tx, rx) = mpsc::channel(1);
         self.startup_rx = Some(rx);
         self.is_processing = true;
@@ -388,7 +446,9 @@ impl App {
             // Ensure server is running and accepting connections, retrying the
             // connect while the freshly-spawned server finishes binding.
             let sock = socket_path(&session_name);
-            let client = match ensure_connected(&session_name).await {
+            let client = match ensure_connected(&session_name, agent_port, server_config.as_deref())
+                .await
+            {
                 Ok(mut c) => {
                     messages.push(Line::from(format!(
                         "Connected to server | Session: {} | Socket: {:?}",
@@ -417,6 +477,7 @@ impl App {
                         android_devices,
                     }) = c.send(&IpcRequest::GetCompletionData).await
                     {
+                        messages.extend(format_booted_warning(&devices));
                         cached_elements = elements;
                         cached_devices = devices;
                         cached_android_devices = android_devices;
@@ -486,14 +547,14 @@ impl App {
         let (context, _prefix) = parse_completion_context(&input);
         let needs_elements = matches!(
             &context,
-            CompletionContext::Argument { command, arg_index }
+            CompletionContext::Argument { command, arg_index, .. }
             if command.args.get(*arg_index).map(|a| a.completion == ArgCompletion::ElementSelector).unwrap_or(false)
         );
 
         // Detect BundleId context to trigger app list fetch
         let needs_apps = matches!(
             &context,
-            CompletionContext::Argument { command, arg_index }
+            CompletionContext::Argument { command, arg_index, .. }
             if command.args.get(*arg_index).map(|a| a.completion == ArgCompletion::BundleId).unwrap_or(false)
         );
 
@@ -589,15 +650,44 @@ impl App {
     /// Begin executing a command. Returns immediately — the result arrives via cmd_result_rx.
     pub fn execute_command(&mut self) {
         let input = self.input.value().trim().to_string();
+        if self.source_buffer.is_some() {
+            self.input = Input::default();
+            self.completion.hide();
+            if input.is_empty() {
+                return;
+            }
+            self.add_output(format_command(&input));
+            if input == ":end" {
+                if let Some(script) = self.source_buffer.take() {
+                    self.start_script(script);
+                }
+            } else if let Some(ref mut paste) = self.source_buffer {
+                paste.push(&input);
+            }
+            return;
+        }
         if input.is_empty() {
             return;
         }
+        if self.script.is_none() {
+            self.history.push(&input);
+        }
 
         // Add command to output
         self.add_output(format_command(&input));
 
+        let (retries, command) = match parse_retry(&input) {
+            Ok(parsed) => parsed,
+            Err(message) => {
+                self.input = Input::default();
+                self.completion.hide();
+                self.add_output(format_result(false, &message));
+                return;
+            }
+        };
+
         // Parse and handle local commands synchronously
-        let (cmd, args) = parse_command(&input);
+        let (cmd, args) = parse_command(command);
         match cmd.as_str() {
             "help" => {
                 self.show_help();
@@ -609,6 +699,36 @@ impl App {
                 self.should_quit = true;
                 return;
             }
+            "run" | ":source" => {
+                self.input = Input::default();
+                self.completion.hide();
+                if self.script.is_some() {
+                    self.add_output(format_result(false, "A script is already running"));
+                    return;
+                }
+                if !retries.is_empty() {
+                    self.add_output(format_result(false, "retry cannot wrap run or :source"));
+                    return;
+                }
+                let keep_going = keep_going_flag(&args.positional);
+                if cmd == ":source" {
+                    self.source_buffer = Some(Script::new(Vec::<String>::new(), keep_going));
+                    self.add_output(Line::from("Paste commands, then :end to run them in order"));
+                    return;
+                }
+                let Some(path) = args.positional.iter().find(|a| !a.starts_with('-')) else {
+                    self.add_output(format_result(false, "run requires a file: run <path> [-k]"));
+                    return;
+                };
+                match Script::from_file(std::path::Path::new(path), keep_going) {
+                    Ok(script) => self.start_script(script),
+                    Err(e) => self.add_output(format_result(
+                        false,
+                        &format!("Failed to read {}: {}", path, e),
+                    )),
+                }
+                return;
+            }
             _ => {}
         }
 
@@ -626,6 +746,7 @@ impl App {
             "boot-device" => IpcRequest::BootDevice {
                 udid: args.positional.first().cloned().unwrap_or_default(),
                 platform: platform_from_args(&args),
+                wait: args.wait,
             },
             "start-agent" => IpcRequest::StartAgent {
                 project_dir: args.positional.first().cloned(),
@@ -633,6 +754,7 @@ impl App {
                 java_home: qorvex_core::android_lifecycle::client_java_home_override(),
             },
             "stop-agent" => IpcRequest::StopAgent,
+            "restart-agent" => IpcRequest::RestartAgent,
             "set-target" => IpcRequest::SetTarget {
                 bundle_id: args.positional.first().cloned().unwrap_or_default(),
             },
@@ -659,209 +781,34 @@ impl App {
                 }
             }
             "get-session-info" => IpcRequest::GetSessionInfo,
-            "get-screenshot" => IpcRequest::Execute {
-                action: ActionType::GetScreenshot,
-                tag: None,
-            },
-            "list-elements" | "get-screen-info" => IpcRequest::Execute {
-                action: ActionType::GetScreenInfo,
-                tag: None,
-            },
-            "tap" => {
-                let selector = args
-                    .positional
-                    .first()
-                    .map(|s| s.to_string())
-                    .unwrap_or_default();
-                if selector.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "tap requires a selector: tap <selector>",
-                    ));
+            "batch" => match parse_batch(command) {
+                Ok(actions) => IpcRequest::ExecuteBatch { actions, tag: None },
+                Err(e) => {
+                    self.add_output(format_result(false, &e));
                     self.input = Input::default();
                     self.completion.hide();
                     return;
                 }
-                let by_label = args.label;
-                let element_type = args.element_type.clone();
-                let timeout_ms = if args.no_wait {
-                    None
-                } else {
-                    Some(args.timeout.unwrap_or(5000))
-                };
-                IpcRequest::Execute {
-                    action: ActionType::Tap {
-                        selector,
-                        by_label,
-                        element_type,
-                        timeout_ms,
-                    },
-                    tag: None,
-                }
-            }
-            "swipe" => IpcRequest::Execute {
-                action: ActionType::Swipe {
-                    direction: args
-                        .positional
-                        .first()
-                        .map(|s| s.to_lowercase())
-                        .unwrap_or_else(|| "up".to_string()),
-                },
-                tag: None,
             },
-            "tap-location" => {
-                if args.positional.len() < 2 {
-                    self.add_output(format_result(
-                        false,
-                        "tap-location requires 2 arguments: tap-location <x> <y>",
-                    ));
-                    self.input = Input::default();
-                    self.completion.hide();
-                    return;
-                }
-                match (
-                    args.positional[0].parse::<i32>(),
-                    args.positional[1].parse::<i32>(),
-                ) {
-                    (Ok(x), Ok(y)) if x >= 0 && y >= 0 => IpcRequest::Execute {
-                        action: ActionType::TapLocation { x, y },
-                        tag: None,
-                    },
-                    _ => {
-                        self.add_output(format_result(false, "Invalid coordinates"));
-                        self.input = Input::default();
-                        self.completion.hide();
-                        return;
-                    }
-                }
-            }
-            "wait-for" => {
-                let selector = args
-                    .positional
-                    .first()
-                    .map(|s| s.to_string())
-                    .unwrap_or_default();
-                if selector.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "wait-for requires a selector: wait-for <selector>",
-                    ));
-                    self.input = Input::default();
-                    self.completion.hide();
-                    return;
-                }
-                let timeout_ms = args.timeout.unwrap_or(5000);
-                let by_label = args.label;
-                let element_type = args.element_type.clone();
-                IpcRequest::Execute {
-                    action: ActionType::WaitFor {
-                        selector,
-                        by_label,
-                        element_type,
-                        timeout_ms,
-                        require_stable: true,
-                    },
-                    tag: None,
-                }
-            }
-            "wait-for-not" => {
-                let selector = args
-                    .positional
-                    .first()
-                    .map(|s| s.to_string())
-                    .unwrap_or_default();
-                if selector.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "wait-for-not requires a selector: wait-for-not <selector>",
-                    ));
-                    self.input = Input::default();
-                    self.completion.hide();
-                    return;
-                }
-                let timeout_ms = args.timeout.unwrap_or(5000);
-                let by_label = args.label;
-                let element_type = args.element_type.clone();
-                IpcRequest::Execute {
-                    action: ActionType::WaitForNot {
-                        selector,
-                        by_label,
-                        element_type,
-                        timeout_ms,
-                    },
-                    tag: None,
-                }
-            }
-            "send-keys" => {
-                let text = args.positional.join(" ");
-                if text.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "send-keys requires text: send-keys <text>",
-                    ));
-                    self.input = Input::default();
-                    self.completion.hide();
-                    return;
-                }
-                IpcRequest::Execute {
-                    action: ActionType::SendKeys { text },
+            _ => match parse_action(&cmd, &args) {
+                Some(Ok(action)) => IpcRequest::Execute {
+                    action,
                     tag: None,
-                }
-            }
-            "get-value" => {
-                let selector = args
-                    .positional
-                    .first()
-                    .map(|s| s.to_string())
-                    .unwrap_or_default();
-                if selector.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "get-value requires a selector: get-value <selector>",
-                    ));
+                    capture_screenshot: None,
+                },
+                Some(Err(e)) => {
+                    self.add_output(format_result(false, &e));
                     self.input = Input::default();
                     self.completion.hide();
                     return;
                 }
-                let by_label = args.label;
-                let element_type = args.element_type.clone();
-                let timeout_ms = if args.no_wait {
-                    None
-                } else {
-                    Some(args.timeout.unwrap_or(5000))
-                };
-                IpcRequest::Execute {
-                    action: ActionType::GetValue {
-                        selector,
-                        by_label,
-                        element_type,
-                        timeout_ms,
-                    },
-                    tag: None,
-                }
-            }
-            "log-comment" => {
-                let message = args.positional.join(" ");
-                if message.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "log-comment requires a message: log-comment <message>",
-                    ));
+                None => {
+                    self.add_output(format_result(false, &format!("Unknown command: {}", cmd)));
                     self.input = Input::default();
                     self.completion.hide();
                     return;
                 }
-                IpcRequest::Execute {
-                    action: ActionType::LogComment { message },
-                    tag: None,
-                }
-            }
-            _ => {
-                self.add_output(format_result(false, &format!("Unknown command: {}", cmd)));
-                self.input = Input::default();
-                self.completion.hide();
-                return;
-            }
+            },
         };
 
         // Check we have a client
@@ -883,7 +830,7 @@ impl App {
 
         tokio::spawn(async move {
             let mut client = client;
-            let result = match client.send(&request).await {
+            let result = match send_with_retries(&mut client, &request, &retries).await {
                 Ok(response) => Ok((response, client)),
                 Err(e) => Err((format!("IPC error: {}", e), client)),
             };
@@ -926,6 +873,21 @@ impl App {
         }
     }
 
+    /// Replace the input with the previous history entry.
+    pub fn history_prev(&mut self) {
+        if let Some(entry) = self.history.prev(self.input.value()) {
+            self.input = Input::new(entry.to_string());
+        }
+    }
+
+    /// Replace the input with the next history entry, or the text being typed
+    /// before navigation began.
+    pub fn history_next(&mut self) {
+        if let Some(entry) = self.history.next() {
+            self.input = Input::new(entry.to_string());
+        }
+    }
+
     /// Scroll output up (away from bottom).
     pub fn scroll_up(&mut self) {
         self.output_scroll_position = self.output_scroll_position.saturating_add(1);
@@ -1033,12 +995,85 @@ impl App {
                 self.processing_start = None;
 
                 // Display the result
-                match result.result {
-                    Ok(response) => self.display_response(&result.cmd, response),
-                    Err(err_msg) => self.add_output(format_result(false, &err_msg)),
-                }
+                let success = match result.result {
+                    Ok(response) => {
+                        let success = response_succeeded(&response);
+                        self.display_response(&result.cmd, response);
+                        success
+                    }
+                    Err(err_msg) => {
+                        self.add_output(format_result(false, &err_msg));
+                        false
+                    }
+                };
 
                 self.cmd_result_rx = None;
+                if self.script.is_some() && self.script_command_done(success) {
+                    self.advance_script();
+                }
+            }
+        }
+    }
+
+    /// Begin running `script`, one command at a time.
+    fn start_script(&mut self, script: Script) {
+        self.script = Some(script);
+        self.advance_script();
+    }
+
+    /// Run script commands until one is waiting on the server or the script
+    /// ends. Commands sent to the server continue from
+    /// [`check_command_result`](Self::check_command_result).
+    fn advance_script(&mut self) {
+        while !self.is_processing && !self.should_quit {
+            let Some(line) = self.script.as_mut().and_then(Script::next_line) else {
+                self.finish_script();
+                return;
+            };
+            self.input = Input::new(line.clone());
+            self.execute_command();
+            if !self.is_processing {
+                // Answered locally: only `help` succeeds without the server
+                let success = parse_command(&line).0 == "help";
+                if !self.script_command_done(success) {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Record a script command's outcome. Returns false, ending the script,
+    /// if it failed and the script does not keep going.
+    fn script_command_done(&mut self, success: bool) -> bool {
+        let Some(ref mut script) = self.script else {
+            return false;
+        };
+        if script.record(success) {
+            return true;
+        }
+        let message = format!(
+            "Script stopped at command {}: {} not run",
+            script.ran,
+            script.remaining()
+        );
+        self.script = None;
+        self.add_output(format_result(false, &message));
+        false
+    }
+
+    /// Report the totals of a script that ran to the end, listing each
+    /// failed command with its line number.
+    fn finish_script(&mut self) {
+        if let Some(script) = self.script.take() {
+            self.add_output(format_result(
+                script.failed == 0,
+                &format!(
+                    "Script finished: {} commands, {} failed",
+                    script.ran, script.failed
+                ),
+            ));
+            for (line, command) in &script.failures {
+                self.add_output(Line::from(format!("  line {}: {}", line, command)));
             }
         }
     }
@@ -1058,18 +1093,25 @@ impl App {
         }
     }
 
-    pub(crate) async fn process_command(&mut self, input: &str) {
-        let (cmd, args) = parse_command(input);
+    pub(crate) async fn process_command(&mut self, input: &str) -> bool {
+        let (retries, command) = match parse_retry(input) {
+            Ok(parsed) => parsed,
+            Err(message) => {
+                self.add_output(format_result(false, &message));
+                return false;
+            }
+        };
+        let (cmd, args) = parse_command(command);
 
         // Local-only commands
         match cmd.as_str() {
             "help" => {
                 self.show_help();
-                return;
+                return true;
             }
             "quit" => {
                 self.should_quit = true;
-                return;
+                return true;
             }
             _ => {}
         }
@@ -1088,6 +1130,7 @@ impl App {
             "boot-device" => IpcRequest::BootDevice {
                 udid: args.positional.first().cloned().unwrap_or_default(),
                 platform: platform_from_args(&args),
+                wait: args.wait,
             },
             "start-agent" => IpcRequest::StartAgent {
                 project_dir: args.positional.first().cloned(),
@@ -1095,6 +1138,7 @@ impl App {
                 java_home: qorvex_core::android_lifecycle::client_java_home_override(),
             },
             "stop-agent" => IpcRequest::StopAgent,
+            "restart-agent" => IpcRequest::RestartAgent,
             "set-target" => IpcRequest::SetTarget {
                 bundle_id: args.positional.first().cloned().unwrap_or_default(),
             },
@@ -1113,208 +1157,52 @@ impl App {
                                 false,
                                 "set-timeout requires a number in milliseconds",
                             ));
-                            return;
+                            return false;
                         }
                     }
                 }
             }
             "get-session-info" => IpcRequest::GetSessionInfo,
-            "get-screenshot" => IpcRequest::Execute {
-                action: ActionType::GetScreenshot,
-                tag: None,
-            },
-            "list-elements" | "get-screen-info" => IpcRequest::Execute {
-                action: ActionType::GetScreenInfo,
-                tag: None,
-            },
-            "tap" => {
-                let selector = args
-                    .positional
-                    .first()
-                    .map(|s| s.to_string())
-                    .unwrap_or_default();
-                if selector.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "tap requires a selector: tap <selector>",
-                    ));
-                    return;
-                }
-                let by_label = args.label;
-                let element_type = args.element_type.clone();
-                let timeout_ms = if args.no_wait {
-                    None
-                } else {
-                    Some(args.timeout.unwrap_or(5000))
-                };
-                IpcRequest::Execute {
-                    action: ActionType::Tap {
-                        selector,
-                        by_label,
-                        element_type,
-                        timeout_ms,
-                    },
-                    tag: None,
+            "batch" => match parse_batch(command) {
+                Ok(actions) => IpcRequest::ExecuteBatch { actions, tag: None },
+                Err(e) => {
+                    self.add_output(format_result(false, &e));
+                    return false;
                 }
-            }
-            "swipe" => IpcRequest::Execute {
-                action: ActionType::Swipe {
-                    direction: args
-                        .positional
-                        .first()
-                        .map(|s| s.to_lowercase())
-                        .unwrap_or_else(|| "up".to_string()),
-                },
-                tag: None,
             },
-            "tap-location" => {
-                if args.positional.len() < 2 {
-                    self.add_output(format_result(
-                        false,
-                        "tap-location requires 2 arguments: tap-location <x> <y>",
-                    ));
-                    return;
-                }
-                match (
-                    args.positional[0].parse::<i32>(),
-                    args.positional[1].parse::<i32>(),
-                ) {
-                    (Ok(x), Ok(y)) if x >= 0 && y >= 0 => IpcRequest::Execute {
-                        action: ActionType::TapLocation { x, y },
-                        tag: None,
-                    },
-                    _ => {
-                        self.add_output(format_result(false, "Invalid coordinates"));
-                        return;
-                    }
-                }
-            }
-            "wait-for" => {
-                let selector = args
-                    .positional
-                    .first()
-                    .map(|s| s.to_string())
-                    .unwrap_or_default();
-                if selector.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "wait-for requires a selector: wait-for <selector>",
-                    ));
-                    return;
-                }
-                let timeout_ms = args.timeout.unwrap_or(5000);
-                let by_label = args.label;
-                let element_type = args.element_type.clone();
-                IpcRequest::Execute {
-                    action: ActionType::WaitFor {
-                        selector,
-                        by_label,
-                        element_type,
-                        timeout_ms,
-                        require_stable: true,
-                    },
+            _ => match parse_action(&cmd, &args) {
+                Some(Ok(action)) => IpcRequest::Execute {
+                    action,
                     tag: None,
+                    capture_screenshot: None,
+                },
+                Some(Err(e)) => {
+                    self.add_output(format_result(false, &e));
+                    return false;
                 }
-            }
-            "wait-for-not" => {
-                let selector = args
-                    .positional
-                    .first()
-                    .map(|s| s.to_string())
-                    .unwrap_or_default();
-                if selector.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "wait-for-not requires a selector: wait-for-not <selector>",
-                    ));
-                    return;
-                }
-                let timeout_ms = args.timeout.unwrap_or(5000);
-                let by_label = args.label;
-                let element_type = args.element_type.clone();
-                IpcRequest::Execute {
-                    action: ActionType::WaitForNot {
-                        selector,
-                        by_label,
-                        element_type,
-                        timeout_ms,
-                    },
-                    tag: None,
-                }
-            }
-            "send-keys" => {
-                let text = args.positional.join(" ");
-                if text.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "send-keys requires text: send-keys <text>",
-                    ));
-                    return;
-                }
-                IpcRequest::Execute {
-                    action: ActionType::SendKeys { text },
-                    tag: None,
-                }
-            }
-            "get-value" => {
-                let selector = args
-                    .positional
-                    .first()
-                    .map(|s| s.to_string())
-                    .unwrap_or_default();
-                if selector.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "get-value requires a selector: get-value <selector>",
-                    ));
-                    return;
-                }
-                let by_label = args.label;
-                let element_type = args.element_type.clone();
-                let timeout_ms = if args.no_wait {
-                    None
-                } else {
-                    Some(args.timeout.unwrap_or(5000))
-                };
-                IpcRequest::Execute {
-                    action: ActionType::GetValue {
-                        selector,
-                        by_label,
-                        element_type,
-                        timeout_ms,
-                    },
-                    tag: None,
-                }
-            }
-            "log-comment" => {
-                let message = args.positional.join(" ");
-                if message.is_empty() {
-                    self.add_output(format_result(
-                        false,
-                        "log-comment requires a message: log-comment <message>",
-                    ));
-                    return;
-                }
-                IpcRequest::Execute {
-                    action: ActionType::LogComment { message },
-                    tag: None,
+                None => {
+                    self.add_output(format_result(false, &format!("Unknown command: {}", cmd)));
+                    return false;
                 }
-            }
-            _ => {
-                self.add_output(format_result(false, &format!("Unknown command: {}", cmd)));
-                return;
-            }
+            },
         };
 
         // Send request and display response
         let Some(ref mut client) = self.client else {
             self.add_output(format_result(false, "Not connected to server"));
-            return;
+            return false;
         };
 
-        match client.send(&request).await {
-            Ok(response) => self.display_response(&cmd, response),
-            Err(e) => self.add_output(format_result(false, &format!("IPC error: {}", e))),
+        match send_with_retries(client, &request, &retries).await {
+            Ok(response) => {
+                let success = response_succeeded(&response);
+                self.display_response(&cmd, response);
+                success
+            }
+            Err(e) => {
+                self.add_output(format_result(false, &format!("IPC error: {}", e)));
+                false
+            }
         }
     }
 
@@ -1347,6 +1235,21 @@ impl App {
                     }
                     self.add_output(format_result(success, &message));
                 }
+                "describe" => {
+                    if success {
+                        if let Some(ref data) = data {
+                            if let Ok(elements) = serde_json::from_str::<Vec<UIElement>>(data) {
+                                let summary = ScreenSummary::from_elements(&elements);
+                                self.cached_elements = elements;
+                                for line in summary.to_string().lines() {
+                                    self.add_output(Line::from(line.to_string()));
+                                }
+                                return;
+                            }
+                        }
+                    }
+                    self.add_output(format_result(success, &message));
+                }
                 "get-value" => {
                     if success {
                         let value = data.unwrap_or_else(|| "(null)".to_string());
@@ -1444,11 +1347,17 @@ impl App {
                 active,
                 device_udid,
                 action_count,
+                max_log_entries,
+                max_screenshots,
             } => {
                 if active {
                     self.add_output(Line::from(format!("Session: {} (active)", session_name)));
                     self.add_output(Line::from(format!("Device: {:?}", device_udid)));
                     self.add_output(Line::from(format!("Actions: {}", action_count)));
+                    self.add_output(Line::from(format!(
+                        "Log cap: {} entries, screenshots on the last {}",
+                        max_log_entries, max_screenshots
+                    )));
                 } else {
                     self.add_output(Line::from(format!("Session: {} (inactive)", session_name)));
                 }
@@ -1487,6 +1396,11 @@ impl App {
                 // Refresh the completion cache from the freshly listed devices.
                 self.cached_android_devices = devices;
             }
+            IpcResponse::BatchResult { results } => {
+                for result in &results {
+                    self.add_output(format_result(result.success, &result.message));
+                }
+            }
             IpcResponse::Error { message } => {
                 self.add_output(format_result(false, &message));
             }
@@ -1506,9 +1420,10 @@ impl App {
             "  list-devices [--platform ios|android]    List available devices",
             "  list-physical-devices    List connected physical devices",
             "  use-device <udid>        Select a device by UDID (simulator or physical)",
-            "  boot-device <udid> [--platform ios|android]  Boot a device",
+            "  boot-device <udid> [--platform ios|android] [--wait]  Boot a device",
             "  start-agent [path] [--platform ios|android]  Connect to / build+launch agent",
             "  stop-agent               Stop managed agent process",
+            "  restart-agent            Restart managed agent, keeping the target",
             "  set-target <bundle_id>   Set target app for automation",
             "  get-target-info          Get target app metadata",
             "  start-target             Launch the target application",
@@ -1518,13 +1433,17 @@ impl App {
             "Screen:",
             "  get-screenshot           Capture a screenshot (base64 PNG)",
             "  get-screen-info          Get UI hierarchy",
+            "  describe                 Summarize the screen: title, counts, buttons",
             "",
             "UI:",
             "  list-elements            List all UI elements",
             "  tap <sel> [--label] [--type T] [--no-wait] [--timeout ms]",
             "  swipe [direction]        Swipe: up, down, left, right",
             "  tap-location <x> <y>    Tap at screen coordinates",
+            "  swipe-coords <x1> <y1> <x2> <y2>",
+            "                           Swipe between two points",
             "  get-value <sel> [--label] [--type T] [--no-wait]",
+            "  wait <ms>                Pause for a fixed time",
             "  wait-for <sel> [--label] [--type T] [--timeout ms]",
             "  wait-for-not <sel> [--label] [--type T] [--timeout ms]",
             "",
@@ -1532,6 +1451,19 @@ impl App {
             "  send-keys <text>         Send keyboard input",
             "  log-comment <message>    Log a comment to the session",
             "",
+            "Scripts:",
+            "  run <path> [-k]          Run the commands in a file, one per line",
+            "  :source [-k]             Paste commands, then :end to run them",
+            "                           (stops at the first failure; -k or",
+            "                           --continue-on-error keeps going and",
+            "                           lists every failure by line at the end)",
+            "  retry <N> [--delay <ms>] <command>",
+            "                           Re-run a command up to N times until it",
+            "                           succeeds (prefixes can be nested)",
+            "  batch <command> ; <command> ...",
+            "                           Send several action commands in one",
+            "                           request (stops at the first failure)",
+            "",
             "General:",
             "  help                     Show this help message",
             "  quit                     Exit the REPL",
@@ -1554,6 +1486,8 @@ pub(crate) struct ParsedArgs {
     /// `--platform ios|android` selector for device/agent commands.
     /// `None` (omitted) means the iOS default (additive).
     pub platform: Option<String>,
+    /// `--wait`: block until the booted device is ready (`boot-device`).
+    pub wait: bool,
 }
 
 /// Tokenize input using shell-style rules: split on whitespace, respect double quotes.
@@ -1604,6 +1538,188 @@ pub(crate) fn platform_from_args(args: &ParsedArgs) -> Platform {
         .unwrap_or_default()
 }
 
+/// Builds the action for a command that runs through `IpcRequest::Execute`.
+///
+/// Returns `None` if `cmd` is not an action command, or the usage error if
+/// its arguments are invalid.
+pub(crate) fn parse_action(cmd: &str, args: &ParsedArgs) -> Option<Result<ActionType, String>> {
+    let selector = || {
+        args.positional
+            .first()
+            .cloned()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| format!("{cmd} requires a selector: {cmd} <selector>"))
+    };
+    let action = match cmd {
+        "get-screenshot" => Ok(ActionType::GetScreenshot),
+        "list-elements" | "get-screen-info" | "describe" => Ok(ActionType::GetScreenInfo {
+            filter: ElementFilter::default(),
+            changed_since_hash: None,
+        }),
+        "tap" => selector().map(|selector| ActionType::Tap {
+            selector,
+            by_label: args.label,
+            element_type: args.element_type.clone(),
+            timeout_ms: (!args.no_wait).then(|| args.timeout.unwrap_or(5000)),
+            require_enabled: false,
+            match_mode: MatchMode::Exact,
+            first: false,
+        }),
+        "swipe" => Ok(ActionType::Swipe {
+            direction: args
+                .positional
+                .first()
+                .map(|s| s.to_lowercase())
+                .unwrap_or_else(|| "up".to_string()),
+        }),
+        "tap-location" => {
+            if args.positional.len() < 2 {
+                Err("tap-location requires 2 arguments: tap-location <x> <y>".to_string())
+            } else {
+                match (
+                    args.positional[0].parse::<i32>(),
+                    args.positional[1].parse::<i32>(),
+                ) {
+                    (Ok(x), Ok(y)) if x >= 0 && y >= 0 => Ok(ActionType::TapLocation { x, y }),
+                    _ => Err("Invalid coordinates".to_string()),
+                }
+            }
+        }
+        "wait" => parse_wait(&args.positional),
+        "swipe-coords" => parse_swipe_coords(&args.positional),
+        "wait-for" => selector().map(|selector| ActionType::WaitFor {
+            selector,
+            by_label: args.label,
+            element_type: args.element_type.clone(),
+            timeout_ms: args.timeout.unwrap_or(5000),
+            require_stable: true,
+            poll_interval_ms: None,
+            match_mode: MatchMode::Exact,
+            first: false,
+        }),
+        "wait-for-not" => selector().map(|selector| ActionType::WaitForNot {
+            selector,
+            by_label: args.label,
+            element_type: args.element_type.clone(),
+            timeout_ms: args.timeout.unwrap_or(5000),
+            poll_interval_ms: None,
+        }),
+        "send-keys" => {
+            let text = args.positional.join(" ");
+            if text.is_empty() {
+                Err("send-keys requires text: send-keys <text>".to_string())
+            } else {
+                Ok(ActionType::SendKeys { text })
+            }
+        }
+        "get-value" => selector().map(|selector| ActionType::GetValue {
+            selector,
+            by_label: args.label,
+            element_type: args.element_type.clone(),
+            timeout_ms: (!args.no_wait).then(|| args.timeout.unwrap_or(5000)),
+            match_mode: MatchMode::Exact,
+            first: false,
+        }),
+        "log-comment" => {
+            let message = args.positional.join(" ");
+            if message.is_empty() {
+                Err("log-comment requires a message: log-comment <message>".to_string())
+            } else {
+                Ok(ActionType::LogComment { message })
+            }
+        }
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// Builds the actions for `batch <command> ; <command> ...`.
+///
+/// Every command must be an action command (see [`parse_action`]).
+pub(crate) fn parse_batch(input: &str) -> Result<Vec<ActionType>, String> {
+    let rest = input.trim_start().strip_prefix("batch").unwrap_or(input);
+    let commands: Vec<&str> = rest
+        .split(';')
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .collect();
+    if commands.is_empty() {
+        return Err("batch requires commands: batch <command> ; <command> ...".to_string());
+    }
+    commands
+        .into_iter()
+        .map(|command| {
+            let (cmd, args) = parse_command(command);
+            parse_action(&cmd, &args)
+                .unwrap_or_else(|| Err(format!("{} cannot run in a batch", cmd)))
+        })
+        .collect()
+}
+
+/// Builds the action for `wait <ms>`.
+pub(crate) fn parse_wait(positional: &[String]) -> Result<ActionType, String> {
+    match positional {
+        [ms] => ms
+            .parse()
+            .map(|ms| ActionType::Wait { ms })
+            .map_err(|_| format!("Invalid duration '{}': expected milliseconds", ms)),
+        _ => Err("wait requires 1 argument: wait <ms>".into()),
+    }
+}
+
+/// Builds the action for `swipe-coords <x1> <y1> <x2> <y2>`.
+pub(crate) fn parse_swipe_coords(positional: &[String]) -> Result<ActionType, String> {
+    let [x1, y1, x2, y2] = positional else {
+        return Err("swipe-coords requires 4 arguments: swipe-coords <x1> <y1> <x2> <y2>".into());
+    };
+    let coord = |s: &String| s.parse::<i32>().ok().filter(|&c| c >= 0);
+    match (coord(x1), coord(y1), coord(x2), coord(y2)) {
+        (Some(start_x), Some(start_y), Some(end_x), Some(end_y)) => Ok(ActionType::SwipeCoords {
+            start_x,
+            start_y,
+            end_x,
+            end_y,
+            duration: None,
+        }),
+        _ => Err("Invalid coordinates".into()),
+    }
+}
+
+/// Sends `request`, re-sending it per `retries` (outermost first) while the
+/// response reports failure. IPC errors are returned at once.
+fn send_with_retries<'a>(
+    client: &'a mut IpcClient,
+    request: &'a IpcRequest,
+    retries: &'a [Retry],
+) -> Pin<Box<dyn Future<Output = Result<IpcResponse, IpcError>> + Send + 'a>> {
+    Box::pin(async move {
+        let Some((retry, inner)) = retries.split_first() else {
+            return client.send(request).await;
+        };
+        let mut attempt = 1;
+        loop {
+            let response = send_with_retries(client, request, inner).await?;
+            if response_succeeded(&response) || attempt >= retry.attempts {
+                return Ok(response);
+            }
+            attempt += 1;
+            tokio::time::sleep(retry.delay).await;
+        }
+    })
+}
+
+/// Whether a server response reports success.
+fn response_succeeded(response: &IpcResponse) -> bool {
+    match response {
+        IpcResponse::CommandResult { success, .. } | IpcResponse::ActionResult { success, .. } => {
+            *success
+        }
+        IpcResponse::BatchResult { results } => BatchActionResult::all_succeeded(results),
+        IpcResponse::Error { .. } => false,
+        _ => true,
+    }
+}
+
 /// Parse a command string into command name and parsed arguments.
 pub(crate) fn parse_command(input: &str) -> (String, ParsedArgs) {
     let tokens = shell_tokenize(input);
@@ -1616,6 +1732,7 @@ pub(crate) fn parse_command(input: &str) -> (String, ParsedArgs) {
         timeout: None,
         element_type: None,
         platform: None,
+        wait: false,
     };
 
     let mut iter = tokens.into_iter().skip(1);
@@ -1634,6 +1751,7 @@ pub(crate) fn parse_command(input: &str) -> (String, ParsedArgs) {
             "--platform" => {
                 args.platform = iter.next();
             }
+            "--wait" => args.wait = true,
             _ => args.positional.push(tok),
         }
     }
@@ -1759,6 +1877,63 @@ mod tests {
         assert_eq!(args.positional, vec!["hello", "world"]);
     }
 
+    #[test]
+    fn test_parse_wait() {
+        let (cmd, args) = parse_command("wait 500");
+        assert_eq!(cmd, "wait");
+        assert!(matches!(
+            parse_wait(&args.positional),
+            Ok(ActionType::Wait { ms: 500 })
+        ));
+        for input in ["wait", "wait soon", "wait -5", "wait 1 2"] {
+            let (_, args) = parse_command(input);
+            assert!(parse_wait(&args.positional).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_parse_swipe_coords() {
+        let (_, args) = parse_command("swipe-coords 300 400 50 410");
+        assert!(matches!(
+            parse_swipe_coords(&args.positional),
+            Ok(ActionType::SwipeCoords {
+                start_x: 300,
+                start_y: 400,
+                end_x: 50,
+                end_y: 410,
+                duration: None,
+            })
+        ));
+        for input in [
+            "swipe-coords 300 400 50",
+            "swipe-coords 300 400 -50 410",
+            "swipe-coords a 400 50 410",
+        ] {
+            let (_, args) = parse_command(input);
+            assert!(parse_swipe_coords(&args.positional).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let actions = parse_batch("batch tap-location 10 20 ; swipe up;send-keys \"a b\"").unwrap();
+        assert!(matches!(
+            actions.as_slice(),
+            [
+                ActionType::TapLocation { x: 10, y: 20 },
+                ActionType::Swipe { direction },
+                ActionType::SendKeys { text },
+            ] if direction == "up" && text == "a b"
+        ));
+        assert!(parse_batch("batch").is_err());
+        assert!(parse_batch("batch tap-location 10 ; swipe up").is_err());
+        // Only action commands can be batched
+        assert_eq!(
+            parse_batch("batch swipe up ; start-target").unwrap_err(),
+            "start-target cannot run in a batch"
+        );
+    }
+
     // --- shutdown / socket cleanup tests ---
 
     /// Verify that `shutdown()` sends `IpcRequest::Shutdown` and clears the client.
@@ -1804,6 +1979,8 @@ mod tests {
             output_area: None,
             should_quit: false,
             session_name: session_name.clone(),
+            agent_port: None,
+            server_config: None,
             client: Some(client),
             cached_elements: Vec::new(),
             cached_devices: Vec::new(),
@@ -1823,6 +2000,9 @@ mod tests {
             processing_start: None,
             cmd_result_rx: None,
             startup_rx: None,
+            history: History::default(),
+            script: None,
+            source_buffer: None,
         };
 
         assert!(app.client.is_some(), "Client should be set before shutdown");
@@ -1850,6 +2030,8 @@ mod tests {
             output_area: None,
             should_quit: false,
             session_name: "nonexistent".to_string(),
+            agent_port: None,
+            server_config: None,
             client: None,
             cached_elements: Vec::new(),
             cached_devices: Vec::new(),
@@ -1869,10 +2051,328 @@ mod tests {
             processing_start: None,
             cmd_result_rx: None,
             startup_rx: None,
+            history: History::default(),
+            script: None,
+            source_buffer: None,
         };
 
         // Should not panic or error
         app.shutdown().await;
         assert!(app.client.is_none());
     }
+
+    /// An app with no server connection, so every command that needs the
+    /// server fails locally.
+    fn offline_app() -> App {
+        App {
+            input: Input::default(),
+            completion: CompletionState::default(),
+            output_history: std::collections::VecDeque::new(),
+            output_scroll_position: 0,
+            selection: SelectionState::default(),
+            output_area: None,
+            should_quit: false,
+            session_name: "offline".to_string(),
+            agent_port: None,
+            server_config: None,
+            client: None,
+            cached_elements: Vec::new(),
+            cached_devices: Vec::new(),
+            cached_android_devices: Vec::new(),
+            cached_apps: Vec::new(),
+            app_update_rx: None,
+            app_fetch_trigger_tx: None,
+            apps_loading: false,
+            apps_fetch_started_at: None,
+            element_update_rx: None,
+            fetch_trigger_tx: None,
+            active_fetch_command: None,
+            elements_loading: false,
+            fetch_started_at: None,
+            is_processing: false,
+            processing_label: String::new(),
+            processing_start: None,
+            cmd_result_rx: None,
+            startup_rx: None,
+            history: History::default(),
+            script: None,
+            source_buffer: None,
+        }
+    }
+
+    fn submit(app: &mut App, input: &str) {
+        app.input = Input::new(input.to_string());
+        app.execute_command();
+    }
+
+    fn output_text(app: &App) -> Vec<String> {
+        app.output_history
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    fn script_file(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "qorvex-repl-script-{}-{}.txt",
+            name,
+            std::process::id()
+        ));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// Serves one connection on a fresh session socket, answering successive
+    /// requests with `responses` (repeating the last). Returns the session
+    /// name and a handle yielding how many requests arrived.
+    async fn scripted_server(
+        name: &str,
+        responses: Vec<IpcResponse>,
+    ) -> (String, tokio::task::JoinHandle<usize>) {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let session_name = format!("repl_{}_{}", name, std::process::id());
+        let sock = socket_path(&session_name);
+        let _ = std::fs::remove_file(&sock);
+        let listener = tokio::net::UnixListener::bind(&sock).unwrap();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let mut count = 0;
+            while let Ok(Some(_)) = lines.next_line().await {
+                let response = &responses[count.min(responses.len() - 1)];
+                count += 1;
+                let mut json = serde_json::to_string(response).unwrap();
+                json.push('\n');
+                write.write_all(json.as_bytes()).await.unwrap();
+            }
+            let _ = std::fs::remove_file(&sock);
+            count
+        });
+        (session_name, handle)
+    }
+
+    fn command_result(success: bool) -> IpcResponse {
+        IpcResponse::CommandResult {
+            success,
+            message: if success { "done" } else { "not yet" }.to_string(),
+        }
+    }
+
+    /// Runs `command` in batch mode against a server that fails twice and
+    /// then succeeds, returning the outcome and the number of attempts.
+    async fn run_flaky(name: &str, command: &str) -> (bool, usize) {
+        let responses = vec![
+            command_result(false),
+            command_result(false),
+            command_result(true),
+        ];
+        let (session_name, server) = scripted_server(name, responses).await;
+        let mut app = offline_app();
+        app.client = Some(IpcClient::connect(&session_name).await.unwrap());
+
+        let success = app.process_command(command).await;
+        app.client = None;
+        (success, server.await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_retry_resends_until_success() {
+        assert_eq!(
+            run_flaky("retry-ok", "retry 3 --delay 1 tap login").await,
+            (true, 3)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_attempts() {
+        assert_eq!(
+            run_flaky("retry-out", "retry 2 tap login").await,
+            (false, 2)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_sends_one_request() {
+        let result = |success, message: &str| BatchActionResult {
+            success,
+            message: message.to_string(),
+            data: None,
+            element_not_found: false,
+        };
+        let responses = vec![IpcResponse::BatchResult {
+            results: vec![
+                result(true, "Tapped at (10, 20)"),
+                result(false, "Swipe failed"),
+            ],
+        }];
+        let (session_name, server) = scripted_server("batch", responses).await;
+        let mut app = offline_app();
+        app.client = Some(IpcClient::connect(&session_name).await.unwrap());
+
+        let success = app
+            .process_command("batch tap-location 10 20 ; swipe up")
+            .await;
+        assert!(!success);
+        let output = output_text(&app);
+        assert!(output[output.len() - 2].contains("Tapped at (10, 20)"));
+        assert!(output[output.len() - 1].contains("Swipe failed"));
+        app.client = None;
+        assert_eq!(server.await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_nested_retry_reruns_inner_block() {
+        // The inner block fails both attempts, the outer one retries it
+        assert_eq!(
+            run_flaky("retry-nested", "retry 2 retry 2 tap login").await,
+            (true, 3)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_in_tui_command() {
+        let responses = vec![command_result(false), command_result(true)];
+        let (session_name, server) = scripted_server("retry-tui", responses).await;
+        let mut app = offline_app();
+        app.client = Some(IpcClient::connect(&session_name).await.unwrap());
+
+        submit(&mut app, "retry 2 start-target");
+        while app.is_processing {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            app.check_command_result();
+        }
+        assert!(output_text(&app).last().unwrap().contains("done"));
+        app.client = None;
+        assert_eq!(server.await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_report_counts_passed_and_failed() {
+        let mut app = offline_app();
+        let mut report = crate::script::RunReport::default();
+        for command in ["help", "tap missing", "wait-for", "help"] {
+            let success = app.process_command(command).await;
+            let output = output_text(&app);
+            app.output_history.clear();
+            report.record(command, success, std::time::Duration::ZERO, output);
+        }
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            (json["total"].as_u64(), json["passed"].as_u64()),
+            (Some(4), Some(2))
+        );
+        assert_eq!(json["failed"], 2);
+        assert_eq!(json["results"][1]["success"], false);
+        assert!(json["results"][1]["output"][0]
+            .as_str()
+            .unwrap()
+            .contains("Not connected to server"));
+        assert!(json["results"][2]["output"][0]
+            .as_str()
+            .unwrap()
+            .contains("wait-for requires a selector"));
+    }
+
+    #[test]
+    fn test_run_stops_at_first_failure() {
+        let path = script_file("stop", "help\n# comment\ntap missing\nhelp\n");
+        let mut app = offline_app();
+        submit(&mut app, &format!("run {}", path.display()));
+
+        let output = output_text(&app);
+        assert!(output.iter().any(|l| l.contains("Not connected to server")));
+        assert!(
+            output
+                .last()
+                .unwrap()
+                .contains("Script stopped at command 2: 1 not run"),
+            "{output:?}"
+        );
+        assert!(app.script.is_none());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_run_keep_going_runs_every_command() {
+        let path = script_file("keep-going", "help\ntap missing\nhelp\n");
+        let mut app = offline_app();
+        submit(&mut app, &format!("run {} -k", path.display()));
+
+        let output = output_text(&app);
+        assert!(
+            output[output.len() - 2].contains("Script finished: 3 commands, 1 failed"),
+            "{output:?}"
+        );
+        assert_eq!(output.last().unwrap(), "  line 2: tap missing");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_run_continue_on_error_reports_every_failure() {
+        let path = script_file(
+            "continue-on-error",
+            "tap missing
+help
+
+wait-for login-button
+get-value
+help
+",
+        );
+        let mut app = offline_app();
+        submit(
+            &mut app,
+            &format!("run {} --continue-on-error", path.display()),
+        );
+
+        let output = output_text(&app);
+        let summary = output
+            .iter()
+            .position(|l| l.contains("Script finished: 5 commands, 3 failed"))
+            .unwrap_or_else(|| panic!("no summary in {output:?}"));
+        assert_eq!(
+            output[summary + 1..],
+            [
+                "  line 1: tap missing",
+                "  line 4: wait-for login-button",
+                "  line 5: get-value",
+            ]
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_run_reports_missing_file() {
+        let mut app = offline_app();
+        submit(&mut app, "run /nonexistent/qorvex-script.txt");
+        assert!(output_text(&app)
+            .last()
+            .unwrap()
+            .contains("Failed to read /nonexistent/qorvex-script.txt"));
+    }
+
+    #[test]
+    fn test_source_paste_mode_runs_on_end() {
+        let mut app = offline_app();
+        submit(&mut app, ":source -k");
+        submit(&mut app, "tap missing");
+        submit(&mut app, "help");
+        // Nothing runs until :end
+        assert!(!output_text(&app)
+            .iter()
+            .any(|l| l.contains("Not connected to server")));
+        submit(&mut app, ":end");
+
+        let output = output_text(&app);
+        assert!(output.iter().any(|l| l.contains("Not connected to server")));
+        assert!(
+            output[output.len() - 2].contains("Script finished: 2 commands, 1 failed"),
+            "{output:?}"
+        );
+        assert_eq!(output.last().unwrap(), "  line 1: tap missing");
+        assert!(app.source_buffer.is_none());
+    }
 }
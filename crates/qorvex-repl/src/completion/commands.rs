@@ -1,7 +1,7 @@
 //! Command definitions for the REPL.
 
-use super::fuzzy::FuzzyFilter;
 use super::{Candidate, CandidateKind};
+use qorvex_core::fuzzy::FuzzyFilter;
 
 /// A command definition with metadata for completion and help.
 #[derive(Debug, Clone)]
@@ -55,6 +55,20 @@ pub struct OptionSpec {
     pub description: &'static str,
 }
 
+/// Shared `--keep-going` option for script commands.
+const KEEP_GOING_OPTION: OptionSpec = OptionSpec {
+    flag: "--keep-going",
+    takes_value: false,
+    description: "Keep running after a failed command (also -k)",
+};
+
+/// `--continue-on-error`, an alias of [`KEEP_GOING_OPTION`].
+const CONTINUE_ON_ERROR_OPTION: OptionSpec = OptionSpec {
+    flag: "--continue-on-error",
+    takes_value: false,
+    description: "Same as --keep-going",
+};
+
 /// Shared `--platform` option for device/agent commands.
 const PLATFORM_OPTION: OptionSpec = OptionSpec {
     flag: "--platform",
@@ -112,7 +126,14 @@ pub static COMMANDS: &[CommandDef] = &[
             name: "udid",
             completion: ArgCompletion::DeviceUdid,
         }],
-        options: &[PLATFORM_OPTION],
+        options: &[
+            PLATFORM_OPTION,
+            OptionSpec {
+                flag: "--wait",
+                takes_value: false,
+                description: "Wait until the device has finished booting",
+            },
+        ],
     },
     CommandDef {
         name: "start-agent",
@@ -129,6 +150,12 @@ pub static COMMANDS: &[CommandDef] = &[
         args: &[],
         options: &[],
     },
+    CommandDef {
+        name: "restart-agent",
+        description: "Restart managed agent, keeping the target",
+        args: &[],
+        options: &[],
+    },
     CommandDef {
         name: "set-target",
         description: "Set target app bundle ID",
@@ -178,6 +205,12 @@ pub static COMMANDS: &[CommandDef] = &[
         args: &[],
         options: &[],
     },
+    CommandDef {
+        name: "describe",
+        description: "Summarize the screen",
+        args: &[],
+        options: &[],
+    },
     // UI commands
     CommandDef {
         name: "list-elements",
@@ -239,6 +272,38 @@ pub static COMMANDS: &[CommandDef] = &[
         ],
         options: &[],
     },
+    CommandDef {
+        name: "wait",
+        description: "Pause for a fixed time",
+        args: &[ArgSpec {
+            name: "ms",
+            completion: ArgCompletion::None,
+        }],
+        options: &[],
+    },
+    CommandDef {
+        name: "swipe-coords",
+        description: "Swipe between two points",
+        args: &[
+            ArgSpec {
+                name: "x1",
+                completion: ArgCompletion::None,
+            },
+            ArgSpec {
+                name: "y1",
+                completion: ArgCompletion::None,
+            },
+            ArgSpec {
+                name: "x2",
+                completion: ArgCompletion::None,
+            },
+            ArgSpec {
+                name: "y2",
+                completion: ArgCompletion::None,
+            },
+        ],
+        options: &[],
+    },
     CommandDef {
         name: "get-value",
         description: "Get an element's value",
@@ -333,6 +398,50 @@ pub static COMMANDS: &[CommandDef] = &[
         }],
         options: &[],
     },
+    // Scripts
+    CommandDef {
+        name: "run",
+        description: "Run the commands in a file",
+        args: &[ArgSpec {
+            name: "path",
+            completion: ArgCompletion::None,
+        }],
+        options: &[KEEP_GOING_OPTION, CONTINUE_ON_ERROR_OPTION],
+    },
+    CommandDef {
+        name: ":source",
+        description: "Paste commands, then :end to run them",
+        args: &[],
+        options: &[KEEP_GOING_OPTION, CONTINUE_ON_ERROR_OPTION],
+    },
+    CommandDef {
+        name: "retry",
+        description: "Re-run a command until it succeeds",
+        args: &[
+            ArgSpec {
+                name: "attempts",
+                completion: ArgCompletion::None,
+            },
+            ArgSpec {
+                name: "command",
+                completion: ArgCompletion::None,
+            },
+        ],
+        options: &[OptionSpec {
+            flag: "--delay",
+            takes_value: true,
+            description: "Milliseconds to wait between attempts",
+        }],
+    },
+    CommandDef {
+        name: "batch",
+        description: "Send several action commands in one request",
+        args: &[ArgSpec {
+            name: "commands",
+            completion: ArgCompletion::None,
+        }],
+        options: &[],
+    },
     // General commands
     CommandDef {
         name: "help",
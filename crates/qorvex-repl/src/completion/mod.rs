@@ -1,14 +1,13 @@
 //! Completion logic for the REPL.
 
 pub mod commands;
-pub mod fuzzy;
 
 use qorvex_core::adb_device::AndroidDevice;
 use qorvex_core::element::UIElement;
+use qorvex_core::fuzzy::FuzzyFilter;
 use qorvex_core::simctl::{InstalledApp, SimulatorDevice};
 
 use self::commands::{commands_matching, find_command, ArgCompletion, CommandDef};
-use self::fuzzy::FuzzyFilter;
 use crate::app::shell_tokenize;
 
 /// The context in which completion is being performed.
@@ -20,6 +19,9 @@ pub enum CompletionContext {
     Argument {
         command: &'static CommandDef,
         arg_index: usize,
+        /// Element type from a `Type:` prefix on a selector argument
+        /// (`tap Button:log`), restricting the candidates to that type.
+        element_type: Option<String>,
     },
     /// Completing a flag/option for a command.
     Option { command: &'static CommandDef },
@@ -113,16 +115,23 @@ impl CompletionState {
 
         self.candidates = match context {
             CompletionContext::Command => commands_matching(&prefix),
-            CompletionContext::Argument { command, arg_index } => {
+            CompletionContext::Argument {
+                command,
+                arg_index,
+                element_type,
+            } => {
                 let arg_cands = if let Some(arg_spec) = command.args.get(arg_index) {
                     match arg_spec.completion {
                         ArgCompletion::ElementId => element_candidates(&prefix, cached_elements),
                         ArgCompletion::ElementLabel => {
                             element_label_candidates(&prefix, cached_elements)
                         }
-                        ArgCompletion::ElementSelector => {
-                            element_selector_candidates(&prefix, cached_elements, command.name)
-                        }
+                        ArgCompletion::ElementSelector => element_selector_candidates(
+                            &prefix,
+                            cached_elements,
+                            command.name,
+                            element_type.as_deref(),
+                        ),
                         ArgCompletion::DeviceUdid => {
                             device_candidates(&prefix, cached_devices, cached_android_devices)
                         }
@@ -176,7 +185,7 @@ pub fn parse_completion_context(input: &str) -> (CompletionContext, String) {
 
     if let Some(cmd) = find_command(cmd_name) {
         // Get the prefix being typed (text after last space)
-        let prefix = trimmed.rsplit(' ').next().unwrap_or("").to_string();
+        let mut prefix = trimmed.rsplit(' ').next().unwrap_or("").to_string();
 
         // If prefix starts with "--", we're completing a flag
         if prefix.starts_with('-') {
@@ -213,10 +222,24 @@ pub fn parse_completion_context(input: &str) -> (CompletionContext, String) {
             positional_count.saturating_sub(1)
         };
 
+        // A selector argument may start with `Type:` to filter by element type
+        let is_selector = cmd
+            .args
+            .get(arg_index)
+            .is_some_and(|a| a.completion == ArgCompletion::ElementSelector);
+        let split = split_type_prefix(&prefix)
+            .filter(|_| is_selector)
+            .map(|(element_type, rest)| (element_type.to_string(), rest.to_string()));
+        let element_type = split.map(|(element_type, rest)| {
+            prefix = rest;
+            element_type
+        });
+
         return (
             CompletionContext::Argument {
                 command: cmd,
                 arg_index,
+                element_type,
             },
             prefix,
         );
@@ -226,6 +249,23 @@ pub fn parse_completion_context(input: &str) -> (CompletionContext, String) {
     (CompletionContext::Command, trimmed.to_string())
 }
 
+/// Splits a `Type:rest` selector prefix into the element type and the rest.
+///
+/// The type must be a single capitalized word (`Button`, `StaticText`), so
+/// ordinary selectors containing a colon are left alone.
+fn split_type_prefix(prefix: &str) -> Option<(&str, &str)> {
+    let (element_type, rest) = prefix.split_once(':')?;
+    let starts_upper = element_type
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_uppercase());
+    if starts_upper && element_type.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Some((element_type, rest))
+    } else {
+        None
+    }
+}
+
 /// Generate element ID completion candidates with fuzzy matching.
 fn element_candidates(prefix: &str, elements: &[UIElement]) -> Vec<Candidate> {
     let filter = FuzzyFilter::new();
@@ -466,6 +506,7 @@ fn element_selector_candidates(
     prefix: &str,
     elements: &[UIElement],
     _command_name: &str,
+    element_type: Option<&str>,
 ) -> Vec<Candidate> {
     use std::collections::HashMap;
 
@@ -486,8 +527,16 @@ fn element_selector_candidates(
         }
     }
 
+    // Uniqueness is counted over every element, since selectors match any type
     let mut candidates: Vec<Candidate> = elements
         .iter()
+        .filter(|elem| {
+            element_type.is_none_or(|t| {
+                elem.element_type
+                    .as_deref()
+                    .is_some_and(|e| e.eq_ignore_ascii_case(t))
+            })
+        })
         .filter_map(|elem| {
             let id = elem.identifier.as_deref();
             let label = elem.label.as_deref().filter(|l| !l.is_empty());
@@ -636,6 +685,8 @@ mod tests {
             name: name.to_string(),
             state: "Booted".to_string(),
             device_type: None,
+            runtime: None,
+            is_available: true,
         }
     }
 
@@ -663,4 +714,82 @@ mod tests {
         let by_model = device_candidates("pixel", &sims, &droids);
         assert!(by_model.iter().any(|c| c.text == "emulator-5554"));
     }
+
+    fn element(id: &str, label: &str, element_type: &str) -> UIElement {
+        UIElement {
+            identifier: Some(id.to_string()),
+            label: Some(label.to_string()),
+            value: None,
+            element_type: Some(element_type.to_string()),
+            frame: None,
+            children: vec![],
+            role: None,
+            hittable: None,
+            enabled: None,
+            selected: None,
+            focused: None,
+            placeholder: None,
+        }
+    }
+
+    #[test]
+    fn type_prefix_sets_element_type_and_strips_prefix() {
+        let (context, prefix) = parse_completion_context("tap Button:log");
+        match context {
+            CompletionContext::Argument {
+                command,
+                arg_index,
+                element_type,
+            } => {
+                assert_eq!(command.name, "tap");
+                assert_eq!(arg_index, 0);
+                assert_eq!(element_type.as_deref(), Some("Button"));
+            }
+            other => panic!("expected Argument, got {other:?}"),
+        }
+        assert_eq!(prefix, "log");
+
+        // Lowercase words and non-selector arguments keep the colon
+        for input in ["tap note:1", "set-target com.example:app"] {
+            let (context, prefix) = parse_completion_context(input);
+            assert!(
+                matches!(
+                    context,
+                    CompletionContext::Argument {
+                        element_type: None,
+                        ..
+                    }
+                ),
+                "{input}"
+            );
+            assert!(prefix.contains(':'), "{input}");
+        }
+    }
+
+    #[test]
+    fn type_prefix_filters_selector_candidates() {
+        let elements = vec![
+            element("login-button", "Log In", "Button"),
+            element("login-title", "Login", "StaticText"),
+            element("logout-button", "Log Out", "Button"),
+        ];
+
+        let all = element_selector_candidates("log", &elements, "tap", None);
+        assert_eq!(all.len(), 3);
+
+        let buttons = element_selector_candidates("login", &elements, "tap", Some("button"));
+        let texts: Vec<&str> = buttons.iter().map(|c| c.text.as_str()).collect();
+        assert!(!texts.contains(&"login-title"), "{texts:?}");
+        // Fuzzy scoring still ranks the remaining text
+        assert_eq!(texts.first(), Some(&"login-button"));
+
+        // The popup shows only the typed type
+        let mut state = CompletionState::default();
+        state.update("tap Button:", &elements, &[], &[], &[], false);
+        assert_eq!(state.candidates.len(), 2);
+        assert!(state
+            .candidates
+            .iter()
+            .all(|c| c.description.starts_with("Button")));
+    }
 }
@@ -1,7 +1,7 @@
 //! Pretty formatters for output display.
 
 use qorvex_core::element::UIElement;
-use qorvex_core::simctl::SimulatorDevice;
+use qorvex_core::simctl::{Simctl, SimulatorDevice};
 use ratatui::text::{Line, Span};
 
 use crate::ui::theme::Theme;
@@ -71,6 +71,29 @@ pub fn format_device(dev: &SimulatorDevice) -> Line<'static> {
     ])
 }
 
+/// Format a warning that several simulators are booted, or `None` if at
+/// most one is.
+///
+/// The server targets the first booted simulator until `use-device` picks
+/// one, so the warning names it.
+pub fn format_booted_warning(devices: &[SimulatorDevice]) -> Option<Line<'static>> {
+    let booted = Simctl::find_booted_devices(devices);
+    if booted.len() < 2 {
+        return None;
+    }
+    Some(Line::from(vec![
+        Span::styled("warning", Theme::error()),
+        Span::styled(
+            format!(
+                ": {} simulators are booted; using {}. Run use-device <udid> to choose one.",
+                booted.len(),
+                booted[0].udid
+            ),
+            Theme::muted(),
+        ),
+    ]))
+}
+
 /// Format a result status.
 pub fn format_result(success: bool, message: &str) -> Line<'static> {
     if success {
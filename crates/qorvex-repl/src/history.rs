@@ -0,0 +1,234 @@
+//! Persistent input history.
+//!
+//! Executed commands are appended to `~/.qorvex/repl_history`, one per line,
+//! and loaded again on startup so Up/Down can recall them across runs.
+
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// File name of the history file inside `qorvex_dir()`.
+pub const HISTORY_FILE: &str = "repl_history";
+
+/// Maximum number of commands kept in the history.
+pub const MAX_HISTORY: usize = 1000;
+
+/// Returns the path of the history file.
+pub fn history_path() -> PathBuf {
+    qorvex_core::ipc::qorvex_dir().join(HISTORY_FILE)
+}
+
+/// Command history with Up/Down navigation.
+#[derive(Debug)]
+pub struct History {
+    /// Commands, oldest first.
+    entries: VecDeque<String>,
+    /// Maximum number of entries kept.
+    max_len: usize,
+    /// File the history is persisted to; `None` keeps it in memory only.
+    path: Option<PathBuf>,
+    /// Entry currently recalled into the input, if navigating.
+    index: Option<usize>,
+    /// Input the user was typing before navigation started.
+    draft: String,
+    /// Leave `send-keys` commands out, so typed secrets never reach disk.
+    pub exclude_send_keys: bool,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            max_len: MAX_HISTORY,
+            path: None,
+            index: None,
+            draft: String::new(),
+            exclude_send_keys: false,
+        }
+    }
+}
+
+impl History {
+    /// Loads the history from `path`, keeping the newest `max_len` entries.
+    ///
+    /// A missing or unreadable file yields an empty history that is still
+    /// saved to `path`.
+    pub fn load(path: PathBuf, max_len: usize) -> Self {
+        let mut history = Self {
+            max_len,
+            ..Self::default()
+        };
+        if let Ok(contents) = std::fs::read_to_string(&path) {
+            for line in contents.lines() {
+                history.add(line);
+            }
+        }
+        history.path = Some(path);
+        history
+    }
+
+    /// Records an executed command and ends any navigation.
+    ///
+    /// Blank commands, repeats of the previous command, and (with
+    /// [`exclude_send_keys`](Self::exclude_send_keys)) `send-keys` commands
+    /// are not recorded.
+    pub fn push(&mut self, command: &str) {
+        self.reset();
+        let command = command.trim();
+        if self.exclude_send_keys && command.split_whitespace().next() == Some("send-keys") {
+            return;
+        }
+        let before = self.entries.len();
+        if !self.add(command) {
+            return;
+        }
+        if let Some(ref path) = self.path {
+            // Persisting is best effort; a failed write only loses history
+            let _ = if self.entries.len() > before {
+                append(path, command)
+            } else {
+                // The cap evicted an entry, so the file is rewritten
+                save(path, &self.entries)
+            };
+        }
+    }
+
+    /// Recalls the previous (older) entry, returning the text for the input.
+    ///
+    /// `current` is the input being edited; it is restored once navigation
+    /// moves past the newest entry again. Stays on the oldest entry.
+    pub fn prev(&mut self, current: &str) -> Option<&str> {
+        let index = match self.index {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+            Some(index) => index.saturating_sub(1),
+        };
+        self.index = Some(index);
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Recalls the next (newer) entry, returning the text for the input.
+    ///
+    /// Past the newest entry, returns the input saved when navigation began.
+    /// Returns `None` when not navigating.
+    pub fn next(&mut self) -> Option<&str> {
+        let index = self.index?;
+        if index + 1 < self.entries.len() {
+            self.index = Some(index + 1);
+            self.entries.get(index + 1).map(String::as_str)
+        } else {
+            self.index = None;
+            Some(&self.draft)
+        }
+    }
+
+    /// Ends navigation without changing the entries.
+    pub fn reset(&mut self) {
+        self.index = None;
+        self.draft.clear();
+    }
+
+    /// Adds an entry in memory, applying de-duplication and the cap.
+    /// Returns false if the entry was skipped.
+    fn add(&mut self, command: &str) -> bool {
+        let command = command.trim();
+        if command.is_empty() || self.entries.back().map(String::as_str) == Some(command) {
+            return false;
+        }
+        self.entries.push_back(command.to_string());
+        while self.entries.len() > self.max_len {
+            self.entries.pop_front();
+        }
+        true
+    }
+}
+
+/// Appends one entry to the history file.
+fn append(path: &Path, command: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", command)
+}
+
+/// Rewrites the history file with `entries`.
+fn save(path: &Path, entries: &VecDeque<String>) -> io::Result<()> {
+    let mut contents = String::new();
+    for entry in entries {
+        contents.push_str(entry);
+        contents.push('\n');
+    }
+    std::fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "qorvex-repl-history-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_history_round_trips_through_file() {
+        let path = temp_path("round-trip");
+        let mut history = History::load(path.clone(), MAX_HISTORY);
+        history.push("tap login-button");
+        history.push("tap login-button");
+        history.push("  get-value username  ");
+        history.push("");
+
+        let loaded = History::load(path.clone(), MAX_HISTORY);
+        assert_eq!(loaded.entries, ["tap login-button", "get-value username"]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_history_cap_rewrites_file() {
+        let path = temp_path("cap");
+        let mut history = History::load(path.clone(), 2);
+        for command in ["a", "b", "c"] {
+            history.push(command);
+        }
+        assert_eq!(history.entries, ["b", "c"]);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "b\nc\n");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_history_excludes_send_keys() {
+        let mut history = History {
+            exclude_send_keys: true,
+            ..History::default()
+        };
+        history.push("send-keys hunter2");
+        history.push("tap submit");
+        assert_eq!(history.entries, ["tap submit"]);
+    }
+
+    #[test]
+    fn test_history_navigation_bounds() {
+        let mut history = History::default();
+        assert_eq!(history.prev("draft"), None);
+        assert_eq!(history.next(), None);
+
+        history.push("first");
+        history.push("second");
+        assert_eq!(history.prev("draft"), Some("second"));
+        assert_eq!(history.prev("second"), Some("first"));
+        // Stays on the oldest entry
+        assert_eq!(history.prev("first"), Some("first"));
+        assert_eq!(history.next(), Some("second"));
+        // Past the newest entry, the draft comes back and navigation ends
+        assert_eq!(history.next(), Some("draft"));
+        assert_eq!(history.next(), None);
+    }
+}
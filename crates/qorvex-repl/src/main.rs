@@ -3,12 +3,17 @@
 mod app;
 mod completion;
 mod format;
+mod history;
+mod retry;
+mod script;
 mod ui;
 
 use std::io;
-use std::time::Duration;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use ratatui::{
     backend::CrosstermBackend,
     crossterm::{
@@ -25,6 +30,16 @@ use tracing_subscriber::EnvFilter;
 use tui_input::backend::crossterm::EventHandler;
 
 use app::App;
+use script::RunReport;
+
+/// How batch mode reports results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Print each command's output as plain text
+    Text,
+    /// Print command output to stderr and a JSON summary of the run to stdout
+    Json,
+}
 
 #[derive(Parser, Debug)]
 #[command(name = "qorvex-repl")]
@@ -37,10 +52,28 @@ struct Args {
     /// Run in batch mode: read commands from stdin, print results to stdout
     #[arg(long)]
     batch: bool,
+
+    /// Batch mode output: plain text, or a final JSON summary of the run
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, requires = "batch")]
+    output: OutputFormat,
+
+    /// Leave send-keys commands out of the input history
+    #[arg(long)]
+    no_send_keys_history: bool,
+
+    /// TCP port the agent listens on, when the REPL launches the server
+    /// (overrides QORVEX_AGENT_PORT and config.json; default 8080)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    agent_port: Option<u16>,
+
+    /// Driver config file (TOML or JSON) passed to the server when the REPL
+    /// launches it
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
 }
 
 #[tokio::main]
-async fn main() -> io::Result<()> {
+async fn main() -> io::Result<ExitCode> {
     let log_dir = qorvex_core::session::logs_dir();
     let file_appender = tracing_appender::rolling::daily(&log_dir, "qorvex-repl.log");
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
@@ -55,7 +88,7 @@ async fn main() -> io::Result<()> {
     let args = Args::parse();
 
     if args.batch {
-        return run_batch(args.session).await;
+        return run_batch(args.session, args.agent_port, args.config, args.output).await;
     }
 
     // Setup terminal
@@ -67,6 +100,9 @@ async fn main() -> io::Result<()> {
 
     // Create app (fast — no blocking I/O)
     let mut app = App::new(args.session);
+    app.history.exclude_send_keys = args.no_send_keys_history;
+    app.agent_port = args.agent_port;
+    app.server_config = args.config;
 
     // Main loop
     let result = run_app(&mut terminal, &mut app).await;
@@ -83,7 +119,7 @@ async fn main() -> io::Result<()> {
     )?;
     terminal.show_cursor()?;
 
-    result
+    result.map(|()| ExitCode::SUCCESS)
 }
 
 /// Convert mouse (column, row) screen coordinates to a TextPosition in the output buffer.
@@ -160,15 +196,29 @@ fn mouse_to_text_position(column: u16, row: u16, app: &App) -> Option<app::TextP
 ///
 /// No terminal setup (raw mode, alternate screen). Connects to IPC server,
 /// processes each line as a command, prints output as plain text, exits on EOF.
-async fn run_batch(session: String) -> io::Result<()> {
+/// With [`OutputFormat::Json`] the text goes to stderr and stdout gets one
+/// [`RunReport`]; the exit code is then 1 if any command failed.
+async fn run_batch(
+    session: String,
+    agent_port: Option<u16>,
+    config: Option<PathBuf>,
+    output: OutputFormat,
+) -> io::Result<ExitCode> {
     use tokio::io::{AsyncBufReadExt, BufReader};
 
-    let mut app = App::new_blocking(session).await;
+    let run_started = Instant::now();
+    let mut report = RunReport::default();
+    let print = |text: &str| match output {
+        OutputFormat::Text => println!("{}", text),
+        OutputFormat::Json => eprintln!("{}", text),
+    };
+
+    let mut app = App::new_blocking(session, agent_port, config).await;
 
     // Drain and print startup messages
     for line in app.output_history.drain(..) {
         let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
-        println!("{}", text);
+        print(&text);
     }
 
     let stdin = BufReader::new(tokio::io::stdin());
@@ -183,17 +233,21 @@ async fn run_batch(session: String) -> io::Result<()> {
             break;
         }
 
-        app.process_command(&line).await;
+        let started = Instant::now();
+        let success = app.process_command(&line).await;
 
         // Drain output and print as plain text
+        let mut command_output = Vec::new();
         for output_line in app.output_history.drain(..) {
             let text: String = output_line
                 .spans
                 .iter()
                 .map(|s| s.content.as_ref())
                 .collect();
-            println!("{}", text);
+            print(&text);
+            command_output.push(text);
         }
+        report.record(&line, success, started.elapsed(), command_output);
 
         if app.should_quit {
             break;
@@ -203,7 +257,16 @@ async fn run_batch(session: String) -> io::Result<()> {
     // Shut down the server so it removes its socket file
     app.shutdown().await;
 
-    Ok(())
+    if output == OutputFormat::Text {
+        return Ok(ExitCode::SUCCESS);
+    }
+    report.duration_ms = run_started.elapsed().as_millis() as u64;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(if report.success() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
 }
 
 async fn run_app(
@@ -285,9 +348,15 @@ async fn run_app(
                                     app.should_quit = true;
                                 }
                                 KeyCode::Up => {
-                                    app.scroll_up();
+                                    app.history_prev();
                                 }
                                 KeyCode::Down => {
+                                    app.history_next();
+                                }
+                                KeyCode::PageUp => {
+                                    app.scroll_up();
+                                }
+                                KeyCode::PageDown => {
                                     app.scroll_down();
                                 }
                                 KeyCode::Tab => {
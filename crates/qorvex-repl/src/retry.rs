@@ -0,0 +1,115 @@
+//! The `retry <N> [--delay <ms>] <command>` prefix.
+//!
+//! Prefixes can be nested: `retry 3 retry 2 tap x` re-runs the inner
+//! `retry 2 tap x` up to three times.
+
+use std::time::Duration;
+
+/// One `retry` prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retry {
+    /// Maximum number of attempts, at least 1.
+    pub attempts: u32,
+    /// Pause between attempts.
+    pub delay: Duration,
+}
+
+/// Splits the leading `retry` prefixes off `input`.
+///
+/// Returns the prefixes, outermost first, and the command they wrap. Input
+/// without a prefix comes back unchanged with no prefixes.
+pub fn parse_retry(input: &str) -> Result<(Vec<Retry>, &str), String> {
+    let mut retries = Vec::new();
+    let mut rest = input.trim();
+    while let Some(after) = strip_word(rest, "retry") {
+        let (count, after) = next_word(after);
+        let attempts = count
+            .parse::<u32>()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| format!("retry needs a positive attempt count, got '{}'", count))?;
+        let (delay, after) = match strip_word(after, "--delay") {
+            Some(after) => {
+                let (ms, after) = next_word(after);
+                let ms = ms
+                    .parse::<u64>()
+                    .map_err(|_| format!("--delay needs milliseconds, got '{}'", ms))?;
+                (Duration::from_millis(ms), after)
+            }
+            None => (Duration::ZERO, after),
+        };
+        retries.push(Retry { attempts, delay });
+        rest = after;
+    }
+    if !retries.is_empty() && rest.is_empty() {
+        return Err("retry requires a command: retry <N> [--delay <ms>] <command>".to_string());
+    }
+    Ok((retries, rest))
+}
+
+/// Returns the text after `word` if `input` starts with it as a whole word.
+fn strip_word<'a>(input: &'a str, word: &str) -> Option<&'a str> {
+    let (first, rest) = next_word(input);
+    (first == word).then_some(rest)
+}
+
+/// Splits off the first whitespace-separated word.
+fn next_word(input: &str) -> (&str, &str) {
+    let input = input.trim_start();
+    match input.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim_start()),
+        None => (input, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_without_prefix() {
+        assert_eq!(parse_retry("tap login"), Ok((vec![], "tap login")));
+        // Only a leading whole word counts
+        assert_eq!(parse_retry("retrying x"), Ok((vec![], "retrying x")));
+    }
+
+    #[test]
+    fn test_parse_retry_with_delay() {
+        let (retries, command) = parse_retry("retry 3 --delay 500 tap \"Log In\" -l").unwrap();
+        assert_eq!(
+            retries,
+            [Retry {
+                attempts: 3,
+                delay: Duration::from_millis(500)
+            }]
+        );
+        assert_eq!(command, "tap \"Log In\" -l");
+    }
+
+    #[test]
+    fn test_parse_nested_retry() {
+        let (retries, command) = parse_retry("retry 3 retry 2 --delay 10 wait-for x").unwrap();
+        assert_eq!(
+            retries,
+            [
+                Retry {
+                    attempts: 3,
+                    delay: Duration::ZERO
+                },
+                Retry {
+                    attempts: 2,
+                    delay: Duration::from_millis(10)
+                },
+            ]
+        );
+        assert_eq!(command, "wait-for x");
+    }
+
+    #[test]
+    fn test_parse_retry_errors() {
+        assert!(parse_retry("retry 0 tap x").is_err());
+        assert!(parse_retry("retry many tap x").is_err());
+        assert!(parse_retry("retry 2 --delay soon tap x").is_err());
+        assert!(parse_retry("retry 2").is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Command sequences run from a file (`run`) or a paste (`:source`), and the
+//! summary of a batch run.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// A sequence of REPL commands being run one after another.
+#[derive(Debug, Default)]
+pub struct Script {
+    /// Commands not yet run, in order, with their 1-based line numbers.
+    lines: VecDeque<(usize, String)>,
+    /// Number of lines pushed so far, including skipped ones.
+    line_count: usize,
+    /// The command most recently taken by [`next_line`](Self::next_line).
+    current: Option<(usize, String)>,
+    /// Keep running after a failed command instead of stopping.
+    pub keep_going: bool,
+    /// Number of commands run so far.
+    pub ran: usize,
+    /// Number of commands that failed.
+    pub failed: usize,
+    /// Line number and text of each failed command, in order.
+    pub failures: Vec<(usize, String)>,
+}
+
+impl Script {
+    /// Creates a script from REPL input lines.
+    ///
+    /// Blank lines and lines starting with `#` are skipped.
+    pub fn new<I, S>(lines: I, keep_going: bool) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut script = Self {
+            keep_going,
+            ..Self::default()
+        };
+        for line in lines {
+            script.push(line.as_ref());
+        }
+        script
+    }
+
+    /// Appends a command, skipping blank and comment lines.
+    pub fn push(&mut self, line: &str) {
+        self.line_count += 1;
+        let line = line.trim();
+        if !line.is_empty() && !line.starts_with('#') {
+            self.lines.push_back((self.line_count, line.to_string()));
+        }
+    }
+
+    /// Reads a script file, one REPL command per line.
+    pub fn from_file(path: &Path, keep_going: bool) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Self::new(contents.lines(), keep_going))
+    }
+
+    /// Takes the next command to run.
+    pub fn next_line(&mut self) -> Option<String> {
+        self.current = self.lines.pop_front();
+        self.current.as_ref().map(|(_, line)| line.clone())
+    }
+
+    /// Records the outcome of the command just run.
+    ///
+    /// Returns false if the script should stop: the command failed and
+    /// [`keep_going`](Self::keep_going) is off.
+    pub fn record(&mut self, success: bool) -> bool {
+        self.ran += 1;
+        if !success {
+            self.failed += 1;
+            if let Some(current) = self.current.take() {
+                self.failures.push(current);
+            }
+        }
+        success || self.keep_going
+    }
+
+    /// Number of commands not yet run.
+    pub fn remaining(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+/// Outcome of one command in a batch run.
+#[derive(Debug, Serialize)]
+pub struct CommandReport {
+    /// The command as entered.
+    pub command: String,
+    /// Whether the command succeeded.
+    pub success: bool,
+    /// Time the command took, in milliseconds.
+    pub duration_ms: u64,
+    /// Output lines the command printed.
+    pub output: Vec<String>,
+}
+
+/// Summary of a batch run, printed by `--output json`.
+#[derive(Debug, Default, Serialize)]
+pub struct RunReport {
+    /// Number of commands run.
+    pub total: usize,
+    /// Number of commands that succeeded.
+    pub passed: usize,
+    /// Number of commands that failed.
+    pub failed: usize,
+    /// Time the whole run took, in milliseconds.
+    pub duration_ms: u64,
+    /// Each command's outcome, in order.
+    pub results: Vec<CommandReport>,
+}
+
+impl RunReport {
+    /// Records the outcome of one command.
+    pub fn record(&mut self, command: &str, success: bool, elapsed: Duration, output: Vec<String>) {
+        self.total += 1;
+        if success {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.results.push(CommandReport {
+            command: command.to_string(),
+            success,
+            duration_ms: elapsed.as_millis() as u64,
+            output,
+        });
+    }
+
+    /// Whether every command succeeded.
+    pub fn success(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+/// Returns true if `args` ask to keep going after a failure (`-k`,
+/// `--keep-going` or `--continue-on-error`).
+pub fn keep_going_flag(args: &[String]) -> bool {
+    args.iter()
+        .any(|a| matches!(a.as_str(), "-k" | "--keep-going" | "--continue-on-error"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_skips_blank_and_comment_lines() {
+        let mut script = Script::new(["tap a", "", "  # note", "  swipe up  "], false);
+        assert_eq!(script.next_line().as_deref(), Some("tap a"));
+        assert_eq!(script.next_line().as_deref(), Some("swipe up"));
+        assert_eq!(script.next_line(), None);
+    }
+
+    #[test]
+    fn test_script_stops_on_failure_unless_keep_going() {
+        let mut script = Script::new(["a", "b"], false);
+        assert!(script.record(true));
+        assert!(!script.record(false));
+        assert_eq!((script.ran, script.failed), (2, 1));
+
+        let mut script = Script::new(["a", "b"], true);
+        assert!(script.record(false));
+        assert_eq!((script.ran, script.failed), (1, 1));
+    }
+
+    #[test]
+    fn test_script_records_failures_with_line_numbers() {
+        let mut script = Script::new(["# setup", "a", "", "b", "c"], true);
+        for success in [false, true, false] {
+            script.next_line();
+            script.record(success);
+        }
+        assert_eq!(
+            script.failures,
+            [(2, "a".to_string()), (5, "c".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_keep_going_flag_aliases() {
+        for flag in ["-k", "--keep-going", "--continue-on-error"] {
+            assert!(keep_going_flag(&["x.txt".into(), flag.into()]));
+        }
+        assert!(!keep_going_flag(&["x.txt".into()]));
+    }
+
+    #[test]
+    fn test_run_report_counts_and_json() {
+        let mut report = RunReport::default();
+        report.record("help", true, Duration::from_millis(2), vec![]);
+        report.record(
+            "tap missing",
+            false,
+            Duration::from_millis(40),
+            vec!["fail: Element 'missing' not found".into()],
+        );
+        report.duration_ms = 42;
+        assert!(!report.success());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["total"], 2);
+        assert_eq!(json["passed"], 1);
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["duration_ms"], 42);
+        assert_eq!(json["results"][1]["command"], "tap missing");
+        assert_eq!(json["results"][1]["success"], false);
+        assert_eq!(json["results"][1]["duration_ms"], 40);
+    }
+}
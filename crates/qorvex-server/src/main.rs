@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use clap::Parser;
@@ -9,9 +10,15 @@ use tokio::sync::Mutex;
 use tracing::{debug, info, info_span, Instrument};
 
 mod server;
+mod sessions;
 use server::ServerState;
+use sessions::Sessions;
 
-use qorvex_core::ipc::{socket_path, IpcError, IpcRequest, IpcResponse};
+use qorvex_core::driver::DriverConfig;
+use qorvex_core::ipc::{
+    forward_events, ipc_token, multi_socket_path, ping_interval, socket_path, AuthGate,
+    IpcEnvelope, IpcError, IpcRequest, IpcResponse,
+};
 
 #[derive(Parser)]
 #[command(name = "qorvex-server")]
@@ -20,11 +27,32 @@ struct Args {
     /// Session name for IPC socket
     #[arg(short, long, default_value = "default", env = "QORVEX_SESSION")]
     session: String,
+
+    /// Serve all sessions from one shared socket, routing each request by
+    /// its session name (`--session` becomes the default for requests without one)
+    #[arg(long)]
+    multi: bool,
+
+    /// Reload each session's previous action log (newest log file for its
+    /// name) when it starts
+    #[arg(long)]
+    resume: bool,
+
+    /// TCP port the agent listens on (overrides QORVEX_AGENT_PORT, --config
+    /// and `agent_port` in ~/.qorvex/config.json; default 8080)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    agent_port: Option<u16>,
+
+    /// Driver config file (TOML or JSON) with backend = "agent"; its host,
+    /// port and read timeout apply, and --agent-port overrides the port
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
+    let driver_config = args.config.as_deref().map(load_driver_config).transpose()?;
 
     // Setup logging. Honor RUST_LOG when set; otherwise default to info.
     let log_dir = qorvex_core::session::logs_dir();
@@ -37,12 +65,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with_ansi(false)
         .init();
 
-    info!(session = %args.session, "Starting qorvex-server");
+    info!(
+        session = %args.session,
+        multi = args.multi,
+        resume = args.resume,
+        agent_port = ?args.agent_port,
+        config = ?args.config,
+        "Starting qorvex-server"
+    );
 
-    let state = Arc::new(Mutex::new(ServerState::new(args.session.clone())));
+    let (sessions, sock_path) = if args.multi {
+        (
+            Sessions::multi(args.session.clone())
+                .with_resume(args.resume)
+                .with_driver_config(driver_config)
+                .with_agent_port(args.agent_port),
+            multi_socket_path(),
+        )
+    } else {
+        let mut state = ServerState::new(args.session.clone());
+        state.resume = args.resume;
+        if let Some(config) = driver_config {
+            state.set_driver_config(config);
+        }
+        if let Some(port) = args.agent_port {
+            state.set_agent_port(port);
+        }
+        (Sessions::single(state), socket_path(&args.session))
+    };
+    let sessions = Arc::new(sessions);
 
     // Remove existing socket
-    let sock_path = socket_path(&args.session);
     let _ = std::fs::remove_file(&sock_path);
 
     let listener = UnixListener::bind(&sock_path)?;
@@ -54,7 +107,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut sigterm = signal(SignalKind::terminate())?;
 
     tokio::select! {
-        result = run_accept_loop(&listener, state.clone(), shutdown_tx.clone()) => {
+        result = run_accept_loop(&listener, sessions.clone(), shutdown_tx.clone()) => {
             if let Err(e) = result {
                 info!(error = %e, "Accept loop exited");
             }
@@ -70,23 +123,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    cleanup(state, &sock_path).await;
+    cleanup(sessions, &sock_path).await;
 
     Ok(())
 }
 
+/// Loads the `--config` file, accepting only an `agent` backend: the server
+/// selects devices with use-device and boot-device, so a config naming one
+/// would be ignored.
+fn load_driver_config(path: &std::path::Path) -> Result<DriverConfig, Box<dyn std::error::Error>> {
+    let config = DriverConfig::from_file(path)?;
+    config.validate()?;
+    if !matches!(config, DriverConfig::Agent { .. }) {
+        return Err(format!(
+            "{}: qorvex-server only accepts backend = \"agent\"; select devices with \
+             use-device or boot-device",
+            path.display()
+        )
+        .into());
+    }
+    Ok(config)
+}
+
 async fn run_accept_loop(
     listener: &UnixListener,
-    state: Arc<Mutex<ServerState>>,
+    sessions: Arc<Sessions>,
     shutdown_tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
 ) -> Result<(), IpcError> {
+    let auth_token = ipc_token();
+    if auth_token.is_some() {
+        info!("IPC token authentication enabled");
+    }
+    let ping_interval = ping_interval();
     loop {
         let (stream, _) = listener.accept().await?;
-        let state = state.clone();
+        let sessions = sessions.clone();
         let shutdown_tx = shutdown_tx.clone();
+        let auth = AuthGate::new(auth_token.clone());
         tokio::spawn(async move {
             let span = info_span!("ipc_client");
-            if let Err(e) = handle_client(stream, state, shutdown_tx)
+            if let Err(e) = handle_client(stream, sessions, shutdown_tx, auth, ping_interval)
                 .instrument(span)
                 .await
             {
@@ -96,22 +172,26 @@ async fn run_accept_loop(
     }
 }
 
-async fn cleanup(state: Arc<Mutex<ServerState>>, sock_path: &std::path::Path) {
+async fn cleanup(sessions: Arc<Sessions>, sock_path: &std::path::Path) {
     info!("Cleaning up");
-    {
-        let _s = state.lock().await;
-        // AgentLifecycle::Drop will kill the agent child process
+    for slot in sessions.drain() {
+        let mut s = slot.state.lock().await;
+        // Finalize a recording left running; AgentLifecycle::Drop will kill
+        // the agent child process
+        s.stop_recording();
     }
     // drop state so ServerState destructors run
-    drop(state);
+    drop(sessions);
     let _ = std::fs::remove_file(sock_path);
     info!("Server stopped");
 }
 
 async fn handle_client(
     stream: tokio::net::UnixStream,
-    state: Arc<Mutex<ServerState>>,
+    sessions: Arc<Sessions>,
     shutdown_tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+    mut auth: AuthGate,
+    ping_interval: std::time::Duration,
 ) -> Result<(), IpcError> {
     let (reader, mut writer) = stream.into_split();
     let mut reader = BufReader::new(reader);
@@ -124,38 +204,64 @@ async fn handle_client(
             break;
         }
 
-        let request: IpcRequest = serde_json::from_str(line.trim())?;
+        let IpcEnvelope { session, request } = serde_json::from_str(line.trim())?;
+
+        if let Some(response) = auth.check(&request) {
+            let json = serde_json::to_string(&response)? + "\n";
+            writer.write_all(json.as_bytes()).await?;
+            writer.flush().await?;
+            continue;
+        }
+
+        if let IpcRequest::Shutdown = request {
+            let json = serde_json::to_string(&IpcResponse::ShutdownAck)? + "\n";
+            // A multi-session server ends only the named session and keeps
+            // serving the others; a request without a name stops the server
+            if let Some(name) = session.as_deref().filter(|_| sessions.is_multi()) {
+                if let Some(slot) = sessions.remove(name) {
+                    slot.state.lock().await.stop_recording();
+                }
+                info!(session = %name, "Session shut down by client");
+                writer.write_all(json.as_bytes()).await?;
+                writer.flush().await?;
+                return Ok(());
+            }
+            info!("Shutdown requested by client");
+            writer.write_all(json.as_bytes()).await?;
+            writer.flush().await?;
+            if let Some(tx) = shutdown_tx.lock().await.take() {
+                let _ = tx.send(());
+            }
+            return Ok(());
+        }
+
+        // Only requests that reach a session resolve (and maybe create) its slot
+        let slot = match sessions.slot(session.as_deref()).await {
+            Ok(slot) => slot,
+            Err(message) => {
+                let json = serde_json::to_string(&IpcResponse::Error { message })? + "\n";
+                writer.write_all(json.as_bytes()).await?;
+                writer.flush().await?;
+                continue;
+            }
+        };
 
         match request {
-            IpcRequest::Shutdown => {
-                info!("Shutdown requested by client");
-                let response = IpcResponse::ShutdownAck;
+            IpcRequest::Cancel => {
+                // Handled without the state lock, which the running action holds
+                let response = slot.in_flight.cancel_response();
                 let json = serde_json::to_string(&response)? + "\n";
                 writer.write_all(json.as_bytes()).await?;
                 writer.flush().await?;
-                if let Some(tx) = shutdown_tx.lock().await.take() {
-                    let _ = tx.send(());
-                }
-                return Ok(());
             }
             IpcRequest::Subscribe => {
                 // Subscribe is streaming — get session and stream events
                 let session = {
-                    let s = state.lock().await;
+                    let s = slot.state.lock().await;
                     s.session.clone()
                 };
                 if let Some(session) = session {
-                    let mut rx = session.subscribe();
-                    while let Ok(event) = rx.recv().await {
-                        let response = IpcResponse::Event { event };
-                        let json = serde_json::to_string(&response)? + "\n";
-                        if writer.write_all(json.as_bytes()).await.is_err() {
-                            break;
-                        }
-                        if writer.flush().await.is_err() {
-                            break;
-                        }
-                    }
+                    forward_events(session.subscribe(), &mut writer, ping_interval).await?;
                 } else {
                     let response = IpcResponse::Error {
                         message: "No active session".to_string(),
@@ -167,7 +273,7 @@ async fn handle_client(
             }
             other => {
                 let response = {
-                    let mut s = state.lock().await;
+                    let mut s = slot.state.lock().await;
                     s.handle_request(other).await
                 };
                 let json = serde_json::to_string(&response)? + "\n";
@@ -178,3 +284,230 @@ async fn handle_client(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qorvex_core::action::ActionType;
+    use qorvex_core::session::{Session, SessionEvent};
+    use std::time::Duration;
+    use tokio::io::AsyncBufReadExt;
+    use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+    use tokio::net::UnixStream;
+
+    /// Start a multi-session server with started sessions `a` and `b`.
+    async fn start_multi_server(tag: &str) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("qorvex-server-{}-{}.sock", std::process::id(), tag));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let sessions = Sessions::multi("a".into());
+        for name in ["a", "b"] {
+            let mut state = ServerState::new(name.into());
+            state.session = Some(Session::new(None, name));
+            sessions.insert(state);
+        }
+        let sessions = Arc::new(sessions);
+        let shutdown_tx = Arc::new(Mutex::new(None));
+        tokio::spawn(async move {
+            let _ = run_accept_loop(&listener, sessions, shutdown_tx).await;
+        });
+        path
+    }
+
+    async fn connect(path: &std::path::Path) -> (BufReader<OwnedReadHalf>, OwnedWriteHalf) {
+        let (reader, writer) = UnixStream::connect(path).await.unwrap().into_split();
+        (BufReader::new(reader), writer)
+    }
+
+    async fn send(writer: &mut OwnedWriteHalf, session: Option<&str>, request: IpcRequest) {
+        let envelope = IpcEnvelope {
+            session: session.map(str::to_string),
+            request,
+        };
+        let json = serde_json::to_string(&envelope).unwrap() + "\n";
+        writer.write_all(json.as_bytes()).await.unwrap();
+    }
+
+    async fn recv(reader: &mut BufReader<OwnedReadHalf>) -> IpcResponse {
+        let mut line = String::new();
+        tokio::time::timeout(Duration::from_secs(2), reader.read_line(&mut line))
+            .await
+            .expect("response within timeout")
+            .unwrap();
+        serde_json::from_str(line.trim()).unwrap()
+    }
+
+    async fn request(
+        path: &std::path::Path,
+        session: Option<&str>,
+        request: IpcRequest,
+    ) -> IpcResponse {
+        let (mut reader, mut writer) = connect(path).await;
+        send(&mut writer, session, request).await;
+        recv(&mut reader).await
+    }
+
+    fn comment(message: &str) -> IpcRequest {
+        IpcRequest::Execute {
+            action: ActionType::LogComment {
+                message: message.into(),
+            },
+            tag: None,
+            capture_screenshot: None,
+        }
+    }
+
+    fn logged_comments(response: IpcResponse) -> Vec<String> {
+        match response {
+            IpcResponse::Log { entries } => entries
+                .into_iter()
+                .filter_map(|e| match e.action {
+                    ActionType::LogComment { message } => Some(message),
+                    _ => None,
+                })
+                .collect(),
+            other => panic!("expected Log, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn multi_routes_requests_by_session() {
+        let path = start_multi_server("route").await;
+
+        request(&path, Some("a"), comment("to a")).await;
+        request(&path, Some("b"), comment("to b")).await;
+        // No session field falls back to the default session
+        request(&path, None, comment("to default")).await;
+
+        let a = logged_comments(request(&path, Some("a"), IpcRequest::GetLog).await);
+        let b = logged_comments(request(&path, Some("b"), IpcRequest::GetLog).await);
+        assert_eq!(a, vec!["to a", "to default"]);
+        assert_eq!(b, vec!["to b"]);
+
+        // An unknown name gets its own fresh state
+        match request(&path, Some("c"), IpcRequest::GetSessionInfo).await {
+            IpcResponse::SessionInfo {
+                session_name,
+                active,
+                ..
+            } => {
+                assert_eq!(session_name, "c");
+                assert!(!active);
+            }
+            other => panic!("expected SessionInfo, got {other:?}"),
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn multi_events_reach_only_matching_subscriber() {
+        let path = start_multi_server("events").await;
+
+        let (mut a_reader, mut a_writer) = connect(&path).await;
+        send(&mut a_writer, Some("a"), IpcRequest::Subscribe).await;
+        let (mut b_reader, mut b_writer) = connect(&path).await;
+        send(&mut b_writer, Some("b"), IpcRequest::Subscribe).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        request(&path, Some("b"), comment("for b")).await;
+        request(&path, Some("a"), comment("for a")).await;
+
+        for (reader, expected) in [(&mut a_reader, "for a"), (&mut b_reader, "for b")] {
+            // Each subscription opens with a keepalive announcing the interval
+            assert!(matches!(
+                recv(reader).await,
+                IpcResponse::Ping {
+                    interval_ms: Some(_)
+                }
+            ));
+            match recv(reader).await {
+                IpcResponse::Event {
+                    event: SessionEvent::ActionLogged(log),
+                } => assert!(
+                    matches!(log.action, ActionType::LogComment { ref message } if message == expected),
+                    "subscriber got {:?}, expected {expected}",
+                    log.action
+                ),
+                other => panic!("expected ActionLogged, got {other:?}"),
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn multi_rejects_invalid_session_names() {
+        let path = start_multi_server("badname").await;
+
+        for name in ["../escape", "a/b", ""] {
+            match request(&path, Some(name), IpcRequest::GetSessionInfo).await {
+                IpcResponse::Error { message } => {
+                    assert!(message.starts_with("Invalid session name"), "{message}")
+                }
+                other => panic!("expected Error for {name:?}, got {other:?}"),
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn multi_shutdown_ends_only_the_named_session() {
+        let path = start_multi_server("shutdown").await;
+        request(&path, Some("a"), comment("kept")).await;
+        request(&path, Some("b"), comment("dropped")).await;
+
+        assert!(matches!(
+            request(&path, Some("b"), IpcRequest::Shutdown).await,
+            IpcResponse::ShutdownAck
+        ));
+
+        // The server still serves `a`, and `b` starts over as a fresh state
+        let a = logged_comments(request(&path, Some("a"), IpcRequest::GetLog).await);
+        assert_eq!(a, vec!["kept"]);
+        match request(&path, Some("b"), IpcRequest::GetSessionInfo).await {
+            IpcResponse::SessionInfo { active, .. } => assert!(!active),
+            other => panic!("expected SessionInfo, got {other:?}"),
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn multi_refuses_sessions_beyond_the_limit() {
+        let sessions = Sessions::multi("s0".into());
+        for i in 0..sessions::MAX_SESSIONS {
+            sessions.insert(ServerState::new(format!("s{i}")));
+        }
+
+        let message = sessions.slot(Some("extra")).await.err().unwrap();
+        assert!(message.starts_with("Too many sessions"), "{message}");
+        // Existing sessions are still reachable
+        assert!(sessions.slot(Some("s3")).await.is_ok());
+        sessions.remove("s3");
+        assert!(sessions.slot(Some("extra")).await.is_ok());
+    }
+
+    #[test]
+    fn driver_config_accepts_only_the_agent_backend() {
+        let path =
+            std::env::temp_dir().join(format!("qorvex-server-config-{}.toml", std::process::id()));
+        std::fs::write(&path, "backend = \"agent\"\nhost = \"10.0.0.5\"\n").unwrap();
+        let agent = load_driver_config(&path);
+        std::fs::write(&path, "backend = \"device\"\nudid = \"ABC\"\n").unwrap();
+        let device = load_driver_config(&path);
+        std::fs::write(&path, "backend = \"agent\"\nport = 0\n").unwrap();
+        let zero_port = load_driver_config(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(agent, Ok(DriverConfig::Agent { host, .. }) if host == "10.0.0.5"));
+        let message = device.unwrap_err().to_string();
+        assert!(
+            message.contains("only accepts backend = \"agent\""),
+            "{message}"
+        );
+        assert_eq!(
+            zero_port.unwrap_err().to_string(),
+            "Invalid driver config: `port` is 0"
+        );
+    }
+}
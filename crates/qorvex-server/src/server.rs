@@ -6,21 +6,55 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use qorvex_core::action::{ActionResult, ActionType};
 use qorvex_core::adb_device::Adb;
 use qorvex_core::adb_forward::AdbForward;
-use qorvex_core::agent_driver::AgentDriver;
-use qorvex_core::agent_lifecycle::{AgentLifecycle, AgentLifecycleConfig};
-use qorvex_core::android_driver::AndroidDriver;
+use qorvex_core::agent_lifecycle::{
+    restart_agent, AgentLifecycle, AgentLifecycleConfig, AgentLifecycleError,
+};
 use qorvex_core::android_lifecycle::{AndroidLifecycle, AndroidLifecycleConfig};
 use qorvex_core::config::QorvexConfig;
-use qorvex_core::driver::{flatten_elements, AutomationDriver};
-use qorvex_core::executor::ActionExecutor;
-use qorvex_core::ipc::{IpcRequest, IpcResponse, Platform};
-use qorvex_core::session::Session;
-use qorvex_core::simctl::{Simctl, SimulatorDevice};
+use qorvex_core::driver::{
+    self, flatten_elements, AutomationDriver, DriverConfig, DriverError, ElementFilter,
+};
+use qorvex_core::element::{hash_elements, UIElement};
+use qorvex_core::executor::{fuzzy_selectors_from_env, ActionExecutor, DEFAULT_POLL_INTERVAL};
+use qorvex_core::ipc::{BatchActionResult, InFlightActions, IpcRequest, IpcResponse, Platform};
+use qorvex_core::protocol::ScreenshotFormat;
+use qorvex_core::session::{
+    latest_log, logs_dir, screenshot_dedup_from_env, LogLimits, Session, SessionSnapshot,
+};
+use qorvex_core::simctl::{
+    Appearance, RecordingHandle, Simctl, SimctlError, SimulatorDevice, DEFAULT_BOOT_TIMEOUT,
+};
+
+/// Format of the screenshots attached to logged actions. They are only
+/// viewed (in the log and by watchers such as `qorvex-live`), so JPEG keeps
+/// them small; `screenshot` still returns lossless PNG.
+const LOG_SCREENSHOT_FORMAT: ScreenshotFormat = ScreenshotFormat::Jpeg { quality: 70 };
+
+/// The screenshot to attach to a logged action: the one it returned, or else
+/// a fresh [`LOG_SCREENSHOT_FORMAT`] capture (`None` if that fails).
+async fn log_screenshot(
+    driver: &Arc<dyn AutomationDriver>,
+    returned: Option<String>,
+) -> Option<String> {
+    if returned.is_some() {
+        return returned;
+    }
+    match driver.screenshot_with(LOG_SCREENSHOT_FORMAT).await {
+        Ok(bytes) => {
+            use base64::Engine;
+            Some(base64::engine::general_purpose::STANDARD.encode(bytes))
+        }
+        Err(e) => {
+            debug!(error = %e, "screenshot for the action log failed");
+            None
+        }
+    }
+}
 
 /// Backend state for the automation server.
 ///
@@ -41,18 +75,22 @@ pub struct ServerState {
     pub target_bundle_id: Option<String>,
     pub default_timeout_ms: u64,
     pub agent_port: u16,
+    /// The `--config` driver config, if one was given. Its host replaces the
+    /// loopback address for simulator agents and its read timeout applies to
+    /// every driver this state builds; see [`set_driver_config`](Self::set_driver_config).
+    pub driver_config: Option<DriverConfig>,
     pub is_physical_device: bool,
     /// The tunnel address for CoreDevice devices (from tunneld), if available.
     pub tunnel_address: Option<String>,
     /// Whether the selected physical device should use the native CoreDevice tunnel.
     ///
     /// Set to `true` when a device is selected via the CoreDevice path (not usbmuxd,
-    /// not tunneld). When `true` and `tunnel_address` is `None`, `AgentDriver::core_device()`
-    /// is used instead of `AgentDriver::usb_device()`.
+    /// not tunneld). When `true` and `tunnel_address` is `None`, a
+    /// [`DriverConfig::CoreDevice`] is used instead of a [`DriverConfig::Device`].
     pub use_core_device: bool,
     /// mDNS hostname for direct TCP connection to a WiFi (localNetwork) device.
     ///
-    /// When set, `AgentDriver::direct(hostname, port)` is used instead of any
+    /// When set, a [`DriverConfig::Agent`] with this host is used instead of any
     /// tunnel approach. Typical value: `"Hillbilly.local"`.
     pub direct_host: Option<String>,
 
@@ -67,15 +105,33 @@ pub struct ServerState {
     ///
     /// `handle_start_agent_android` establishes this forward to pick a free
     /// loopback port and passes the bound port to both the lifecycle (for the
-    /// health poll) and the [`AndroidDriver`]. The server keeps it for the
-    /// session lifetime so the rule the driver and health-poll depend on is
-    /// **not** torn down by [`AdbForward::drop`] when the start handler
+    /// health poll) and the
+    /// [`AndroidDriver`](qorvex_core::android_driver::AndroidDriver). The
+    /// server keeps it for the session lifetime so the rule the driver and
+    /// health-poll depend on is **not** torn down by [`AdbForward::drop`] when the start handler
     /// returns. The driver re-issues the same `tcp:<port>` rule idempotently on
     /// `connect`/recovery and owns its own forward; both removals (here on
     /// `stop-agent`, and the driver's on drop) are idempotent no-ops against
     /// adb, so there is no competing-removal hazard. Released in
     /// `handle_stop_agent`.
     pub android_forward: Option<AdbForward>,
+    /// Last `GetScreenInfo` payload and its element hash for the current
+    /// session, used to answer `changed_since_hash` requests without
+    /// re-hashing an identical tree. Cleared when a session starts or ends.
+    pub screen_hash_cache: Option<(String, String)>,
+    /// Actions currently executing, shared with the connection handlers so an
+    /// `IpcRequest::Cancel` can reach them without waiting for this state's lock.
+    pub in_flight: InFlightActions,
+    /// The screen recording started by `StartRecording`, if one is running.
+    /// Owned here so it outlives the CLI invocation that started it.
+    pub recording: Option<RecordingHandle>,
+    /// Whether `StartSession` reloads the session's previous action log
+    /// (`--resume`).
+    pub resume: bool,
+    /// Whether logged actions carry a screenshot of the screen after they ran
+    /// (`set-screenshots`). Off by default: each capture costs an extra agent
+    /// round trip per action.
+    pub capture_screenshots: bool,
 }
 
 impl ServerState {
@@ -85,10 +141,15 @@ impl ServerState {
         let agent_port = config.agent_port();
         let cached_devices = Simctl::list_devices().unwrap_or_default();
         let cached_android_devices = Adb::list_devices().unwrap_or_default();
-        let simulator_udid = Simctl::get_booted_udid().ok();
-        let executor = simulator_udid
-            .as_ref()
-            .map(|_| ActionExecutor::with_agent("localhost".to_string(), agent_port));
+        let booted = Simctl::find_booted_devices(&cached_devices);
+        if booted.len() > 1 {
+            warn!(
+                count = booted.len(),
+                using = %booted[0].udid,
+                "Several simulators are booted; use-device selects another"
+            );
+        }
+        let simulator_udid = booted.first().map(|d| d.udid.clone());
 
         info!(
             session = %session_name,
@@ -97,18 +158,19 @@ impl ServerState {
             "ServerState initialised"
         );
 
-        Self {
+        let mut state = Self {
             session_name,
             session: None,
             simulator_udid,
             shared_driver: Arc::new(tokio::sync::Mutex::new(None)),
-            executor,
+            executor: None,
             agent_lifecycle: None,
             cached_devices,
             cached_android_devices,
             target_bundle_id: None,
             default_timeout_ms: 5000,
             agent_port,
+            driver_config: None,
             is_physical_device: false,
             tunnel_address: None,
             use_core_device: false,
@@ -116,7 +178,14 @@ impl ServerState {
             android_serial: None,
             android_lifecycle: None,
             android_forward: None,
-        }
+            screen_hash_cache: None,
+            in_flight: InFlightActions::default(),
+            recording: None,
+            resume: false,
+            capture_screenshots: false,
+        };
+        state.reset_executor();
+        state
     }
 
     /// Handle a single IPC request and return a response.
@@ -133,17 +202,23 @@ impl ServerState {
             IpcRequest::ListDevices { platform } => self.handle_list_devices(platform),
             IpcRequest::ListPhysicalDevices => self.handle_list_physical_devices().await,
             IpcRequest::UseDevice { udid } => self.handle_use_device(&udid).await,
-            IpcRequest::BootDevice { udid, platform } => {
-                self.handle_boot_device(&udid, platform).await
-            }
+            IpcRequest::BootDevice {
+                udid,
+                platform,
+                wait,
+            } => self.handle_boot_device(&udid, platform, wait).await,
 
             // ── Agent Management ────────────────────────────────────────
             IpcRequest::StartAgent {
                 project_dir,
                 platform,
                 java_home,
-            } => self.handle_start_agent(project_dir, platform, java_home).await,
+            } => {
+                self.handle_start_agent(project_dir, platform, java_home)
+                    .await
+            }
             IpcRequest::StopAgent => self.handle_stop_agent(),
+            IpcRequest::RestartAgent => self.handle_restart_agent().await,
             IpcRequest::Connect { host, port } => self.handle_connect(&host, port).await,
 
             // ── Target App Lifecycle ────────────────────────────────────
@@ -152,6 +227,13 @@ impl ServerState {
 
             // ── Target Info ─────────────────────────────────────────────
             IpcRequest::GetTargetInfo => self.handle_get_target_info().await,
+            IpcRequest::GetDeviceInfo => self.handle_get_device_info().await,
+            IpcRequest::ElementAt { x, y } => self.handle_element_at(x, y).await,
+            IpcRequest::PingAgent { count } => self.handle_ping_agent(count).await,
+
+            // ── Screen Recording ────────────────────────────────────────
+            IpcRequest::StartRecording { path } => self.handle_start_recording(&path),
+            IpcRequest::StopRecording => self.handle_stop_recording(),
 
             // ── Configuration ───────────────────────────────────────────
             IpcRequest::SetTarget { bundle_id } => self.handle_set_target(&bundle_id).await,
@@ -165,9 +247,23 @@ impl ServerState {
             IpcRequest::GetTimeout => IpcResponse::TimeoutValue {
                 timeout_ms: self.default_timeout_ms,
             },
+            IpcRequest::SetCaptureScreenshots { enabled } => {
+                self.capture_screenshots = enabled;
+                IpcResponse::CommandResult {
+                    success: true,
+                    message: format!("Screenshot capture {}", if enabled { "on" } else { "off" }),
+                }
+            }
 
             // ── On-Demand Fetching ──────────────────────────────────────
             IpcRequest::FetchElements => self.handle_fetch_elements().await,
+            IpcRequest::FetchScreenInfo {
+                filter,
+                changed_since_hash,
+            } => {
+                self.handle_fetch_screen_info(filter, changed_since_hash)
+                    .await
+            }
             IpcRequest::FetchApps => self.handle_fetch_apps().await,
 
             // ── Info ────────────────────────────────────────────────────
@@ -177,13 +273,23 @@ impl ServerState {
                 devices: self.cached_devices.clone(),
                 android_devices: self.cached_android_devices.clone(),
             },
+            IpcRequest::Health => self.handle_health().await,
 
             // ── Execute ─────────────────────────────────────────────────
-            IpcRequest::Execute { action, tag } => self.handle_execute(action, tag).await,
+            IpcRequest::Execute {
+                action,
+                tag,
+                capture_screenshot,
+            } => self.handle_execute(action, tag, capture_screenshot).await,
+            IpcRequest::ExecuteBatch { actions, tag } => {
+                self.handle_execute_batch(actions, tag).await
+            }
 
             // ── State / Log (forwarded from session) ────────────────────
             IpcRequest::GetState => self.handle_get_state().await,
+            IpcRequest::GetSnapshot => self.handle_get_snapshot().await,
             IpcRequest::GetLog => self.handle_get_log().await,
+            IpcRequest::Cancel => self.in_flight.cancel_response(),
 
             // ── Subscribe — should not reach here ───────────────────────
             IpcRequest::Subscribe => IpcResponse::Error {
@@ -195,15 +301,45 @@ impl ServerState {
             IpcRequest::Shutdown => IpcResponse::Error {
                 message: "Shutdown is handled by the server loop".to_string(),
             },
+
+            // ── Auth — should not reach here ────────────────────────────
+            IpcRequest::Auth { .. } => IpcResponse::Error {
+                message: "Auth is handled by the server loop".to_string(),
+            },
         }
     }
 
     // ── Session ─────────────────────────────────────────────────────────
 
     async fn handle_start_session(&mut self) -> IpcResponse {
-        let session = Session::new(self.simulator_udid.clone(), &self.session_name);
+        let log_dir = logs_dir();
+        // Read the previous log before the new session creates its own file,
+        // which has the same name if both started within the same second
+        let previous = self
+            .resume
+            .then(|| latest_log(&log_dir, &self.session_name))
+            .flatten()
+            .map(|path| (Session::read_log(&path), path));
+        let session = Session::new_with_limits(
+            self.simulator_udid.clone(),
+            &self.session_name,
+            log_dir,
+            LogLimits::from_env(),
+        );
+        session.set_screenshot_dedup(screenshot_dedup_from_env());
+        match previous {
+            Some((Ok(entries), path)) => {
+                info!(path = %path.display(), entries = entries.len(), "Resumed action log");
+                session.restore_log(entries).await;
+            }
+            Some((Err(e), path)) => {
+                warn!(path = %path.display(), error = %e, "Failed to read previous action log");
+            }
+            None => {}
+        }
         self.session = Some(session.clone());
         self.shared_driver = Arc::new(tokio::sync::Mutex::new(None));
+        self.screen_hash_cache = None;
 
         info!(session_name = %self.session_name, "Session started");
 
@@ -256,28 +392,13 @@ impl ServerState {
 
         match lifecycle.ensure_agent_ready().await {
             Ok(()) => {
-                let mut driver = if self.is_physical_device {
-                    if let Some(ref addr) = self.tunnel_address {
-                        AgentDriver::tunneld(addr.clone(), self.agent_port)
-                            .with_lifecycle(lifecycle.clone())
-                    } else if let Some(ref host) = self.direct_host {
-                        AgentDriver::direct(host.clone(), self.agent_port)
-                            .with_lifecycle(lifecycle.clone())
-                    } else if self.use_core_device {
-                        AgentDriver::core_device(udid.clone(), self.agent_port)
-                            .with_lifecycle(lifecycle.clone())
-                    } else {
-                        AgentDriver::usb_device(udid.clone(), self.agent_port)
-                            .with_lifecycle(lifecycle.clone())
-                    }
-                } else {
-                    AgentDriver::direct("127.0.0.1", self.agent_port)
-                        .with_lifecycle(lifecycle.clone())
-                };
+                let config = self
+                    .ios_driver_config(&udid)
+                    .with_lifecycle(lifecycle.clone());
                 self.agent_lifecycle = Some(lifecycle);
-                match driver.connect().await {
-                    Ok(()) => {
-                        self.set_executor_with_driver(Arc::new(driver)).await;
+                match connect_driver(&config).await {
+                    Ok(driver) => {
+                        self.set_executor_with_driver(Arc::from(driver)).await;
                         info!("Agent started and connected");
                         IpcResponse::CommandResult {
                             success: true,
@@ -297,7 +418,7 @@ impl ServerState {
                 info!(error = %e, "Auto-start agent failed");
                 IpcResponse::CommandResult {
                     success: false,
-                    message: format!("Failed to start agent: {}", e),
+                    message: agent_failure("start", &lifecycle, &e),
                 }
             }
         }
@@ -305,6 +426,7 @@ impl ServerState {
 
     fn handle_end_session(&mut self) -> IpcResponse {
         self.session = None;
+        self.screen_hash_cache = None;
 
         IpcResponse::CommandResult {
             success: true,
@@ -437,10 +559,7 @@ impl ServerState {
             self.use_core_device = false;
             self.direct_host = None;
             self.simulator_udid = Some(udid.to_string());
-            self.executor = Some(ActionExecutor::with_agent(
-                "localhost".to_string(),
-                self.agent_port,
-            ));
+            self.reset_executor();
             return IpcResponse::CommandResult {
                 success: true,
                 message: format!("Using simulator {}", udid),
@@ -519,9 +638,34 @@ impl ServerState {
         }
     }
 
-    async fn handle_boot_device(&mut self, udid: &str, platform: Platform) -> IpcResponse {
+    async fn handle_boot_device(
+        &mut self,
+        udid: &str,
+        platform: Platform,
+        wait: bool,
+    ) -> IpcResponse {
         match platform {
-            Platform::Ios => self.handle_boot_device_ios(udid),
+            Platform::Ios => {
+                let response = self.handle_boot_device_ios(udid);
+                if !wait || !matches!(response, IpcResponse::CommandResult { success: true, .. }) {
+                    return response;
+                }
+                let udid = strip_quotes(udid).to_string();
+                let waited = tokio::task::spawn_blocking(move || {
+                    Simctl::wait_for_boot(&udid, DEFAULT_BOOT_TIMEOUT)
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| r.map_err(|e| e.to_string()));
+                match waited {
+                    Ok(()) => response,
+                    Err(e) => IpcResponse::CommandResult {
+                        success: false,
+                        message: format!("Device did not finish booting: {e}"),
+                    },
+                }
+            }
+            // Emulator boots already block until `sys.boot_completed`.
             Platform::Android => self.handle_boot_device_android(udid).await,
         }
     }
@@ -541,12 +685,9 @@ impl ServerState {
             };
         }
         match Simctl::boot(udid) {
-            Ok(_) => {
+            Ok(()) | Err(SimctlError::AlreadyBooted) => {
                 self.simulator_udid = Some(udid.to_string());
-                self.executor = Some(ActionExecutor::with_agent(
-                    "localhost".to_string(),
-                    self.agent_port,
-                ));
+                self.reset_executor();
                 // Switching to iOS retires any active Android selection so
                 // device/agent selection is mutually exclusive. Terminate the
                 // Android agent and release its forward to avoid orphaned
@@ -650,13 +791,16 @@ impl ServerState {
         };
         match platform {
             Platform::Ios => self.handle_start_agent_ios(project_dir).await,
-            Platform::Android => self.handle_start_agent_android(project_dir, java_home).await,
+            Platform::Android => {
+                self.handle_start_agent_android(project_dir, java_home)
+                    .await
+            }
         }
     }
 
     /// Start the Android (Kotlin) agent: validate config, build/install/launch
     /// via Gradle + `am instrument`, establish the `adb forward` tunnel, and
-    /// connect an [`AndroidDriver`].
+    /// connect an [`AndroidDriver`](qorvex_core::android_driver::AndroidDriver).
     ///
     /// Missing or invalid Android config yields a clear validation error here
     /// (spec F3) rather than a downstream Gradle/adb crash.
@@ -753,10 +897,18 @@ impl ServerState {
                 // iOS executor so device/agent selection is mutually exclusive.
                 self.android_forward = Some(forward);
                 self.executor = None;
-                let mut driver = AndroidDriver::new(serial.clone(), Some(local_port), device_port);
-                match driver.connect().await {
-                    Ok(()) => {
-                        self.set_executor_with_driver(Arc::new(driver)).await;
+                let config = DriverConfig::Android {
+                    serial: serial.clone(),
+                    local_port,
+                    device_port,
+                    read_timeout: self.read_timeout(),
+                    retries: self.retries(),
+                    poll_interval: None,
+                    fuzzy_selectors: None,
+                };
+                match connect_driver(&config).await {
+                    Ok(driver) => {
+                        self.set_executor_with_driver(Arc::from(driver)).await;
                         IpcResponse::CommandResult {
                             success: true,
                             message: "Android agent started and connected".to_string(),
@@ -803,28 +955,13 @@ impl ServerState {
 
             match lifecycle.ensure_running().await {
                 Ok(()) => {
-                    let mut driver = if self.is_physical_device {
-                        if let Some(ref addr) = self.tunnel_address {
-                            AgentDriver::tunneld(addr.clone(), self.agent_port)
-                                .with_lifecycle(lifecycle.clone())
-                        } else if let Some(ref host) = self.direct_host {
-                            AgentDriver::direct(host.clone(), self.agent_port)
-                                .with_lifecycle(lifecycle.clone())
-                        } else if self.use_core_device {
-                            AgentDriver::core_device(udid.clone(), self.agent_port)
-                                .with_lifecycle(lifecycle.clone())
-                        } else {
-                            AgentDriver::usb_device(udid.clone(), self.agent_port)
-                                .with_lifecycle(lifecycle.clone())
-                        }
-                    } else {
-                        AgentDriver::direct("127.0.0.1", self.agent_port)
-                            .with_lifecycle(lifecycle.clone())
-                    };
+                    let config = self
+                        .ios_driver_config(&udid)
+                        .with_lifecycle(lifecycle.clone());
                     self.agent_lifecycle = Some(lifecycle);
-                    match driver.connect().await {
-                        Ok(()) => {
-                            self.set_executor_with_driver(Arc::new(driver)).await;
+                    match connect_driver(&config).await {
+                        Ok(driver) => {
+                            self.set_executor_with_driver(Arc::from(driver)).await;
                             IpcResponse::CommandResult {
                                 success: true,
                                 message: "Agent started and connected".to_string(),
@@ -838,7 +975,7 @@ impl ServerState {
                 }
                 Err(e) => IpcResponse::CommandResult {
                     success: false,
-                    message: format!("Failed to start agent: {}", e),
+                    message: agent_failure("start", &lifecycle, &e),
                 },
             }
         } else {
@@ -858,28 +995,13 @@ impl ServerState {
 
                 match lifecycle.ensure_agent_ready().await {
                     Ok(()) => {
-                        let mut driver = if self.is_physical_device {
-                            if let Some(ref addr) = self.tunnel_address {
-                                AgentDriver::tunneld(addr.clone(), self.agent_port)
-                                    .with_lifecycle(lifecycle.clone())
-                            } else if let Some(ref host) = self.direct_host {
-                                AgentDriver::direct(host.clone(), self.agent_port)
-                                    .with_lifecycle(lifecycle.clone())
-                            } else if self.use_core_device {
-                                AgentDriver::core_device(udid.clone(), self.agent_port)
-                                    .with_lifecycle(lifecycle.clone())
-                            } else {
-                                AgentDriver::usb_device(udid.clone(), self.agent_port)
-                                    .with_lifecycle(lifecycle.clone())
-                            }
-                        } else {
-                            AgentDriver::direct("127.0.0.1", self.agent_port)
-                                .with_lifecycle(lifecycle.clone())
-                        };
+                        let config = self
+                            .ios_driver_config(&udid)
+                            .with_lifecycle(lifecycle.clone());
                         self.agent_lifecycle = Some(lifecycle);
-                        match driver.connect().await {
-                            Ok(()) => {
-                                self.set_executor_with_driver(Arc::new(driver)).await;
+                        match connect_driver(&config).await {
+                            Ok(driver) => {
+                                self.set_executor_with_driver(Arc::from(driver)).await;
                                 IpcResponse::CommandResult {
                                     success: true,
                                     message: "Agent started and connected".to_string(),
@@ -893,7 +1015,7 @@ impl ServerState {
                     }
                     Err(e) => IpcResponse::CommandResult {
                         success: false,
-                        message: format!("Failed to start agent: {}", e),
+                        message: agent_failure("start", &lifecycle, &e),
                     },
                 }
             } else {
@@ -910,22 +1032,11 @@ impl ServerState {
 
                 match lifecycle.wait_for_ready().await {
                     Ok(()) => {
-                        let mut driver = if self.is_physical_device {
-                            if let Some(ref addr) = self.tunnel_address {
-                                AgentDriver::tunneld(addr.clone(), self.agent_port)
-                            } else if let Some(ref host) = self.direct_host {
-                                AgentDriver::direct(host.clone(), self.agent_port)
-                            } else if self.use_core_device {
-                                AgentDriver::core_device(udid.clone(), self.agent_port)
-                            } else {
-                                AgentDriver::usb_device(udid.clone(), self.agent_port)
-                            }
-                        } else {
-                            AgentDriver::direct("127.0.0.1", self.agent_port)
-                        };
-                        match driver.connect().await {
-                            Ok(()) => {
-                                self.set_executor_with_driver(Arc::new(driver)).await;
+                        // The agent is not ours to restart, so no lifecycle
+                        let config = self.ios_driver_config(&udid);
+                        match connect_driver(&config).await {
+                            Ok(driver) => {
+                                self.set_executor_with_driver(Arc::from(driver)).await;
                                 IpcResponse::CommandResult {
                                     success: true,
                                     message: "Agent connected".to_string(),
@@ -979,11 +1090,97 @@ impl ServerState {
         }
     }
 
+    /// Restart the managed iOS agent and reconnect the driver to it. The
+    /// current target app is re-sent to the new agent.
+    async fn handle_restart_agent(&mut self) -> IpcResponse {
+        let Some(lifecycle) = self.agent_lifecycle.clone() else {
+            let message = if self.android_lifecycle.is_some() {
+                "restart-agent is not supported for Android agents; use stop-agent and start-agent"
+            } else {
+                "No managed agent to restart; start one with start-agent"
+            };
+            return IpcResponse::CommandResult {
+                success: false,
+                message: message.to_string(),
+            };
+        };
+        let Some(udid) = self.simulator_udid.clone() else {
+            return IpcResponse::CommandResult {
+                success: false,
+                message: "No simulator selected. Use UseDevice or BootDevice first.".to_string(),
+            };
+        };
+
+        let started = std::time::Instant::now();
+        if let Err(e) = restart_agent(lifecycle.as_ref()).await {
+            warn!(error = %e, "agent restart failed");
+            return IpcResponse::CommandResult {
+                success: false,
+                message: format!(
+                    "{} (after {:.1}s)",
+                    agent_failure("restart", &lifecycle, &e),
+                    started.elapsed().as_secs_f64()
+                ),
+            };
+        }
+
+        let config = self.ios_driver_config(&udid).with_lifecycle(lifecycle);
+        let driver = match connect_driver(&config).await {
+            Ok(driver) => driver,
+            Err(e) => {
+                return IpcResponse::CommandResult {
+                    success: false,
+                    message: format!(
+                        "Agent restarted but connection failed after {:.1}s: {}",
+                        started.elapsed().as_secs_f64(),
+                        e
+                    ),
+                };
+            }
+        };
+        // The new agent starts without a target; re-send the current one.
+        let restored = match self.target_bundle_id.clone() {
+            Some(bundle_id) => Some(driver.set_target(&bundle_id).await.map(|()| bundle_id)),
+            None => None,
+        };
+        self.set_executor_with_driver(Arc::from(driver)).await;
+        let elapsed = started.elapsed().as_secs_f64();
+        info!(elapsed_s = elapsed, "agent restarted and reconnected");
+        match restored {
+            None => IpcResponse::CommandResult {
+                success: true,
+                message: format!("Agent restarted in {:.1}s", elapsed),
+            },
+            Some(Ok(bundle_id)) => IpcResponse::CommandResult {
+                success: true,
+                message: format!(
+                    "Agent restarted in {:.1}s; target {} restored",
+                    elapsed, bundle_id
+                ),
+            },
+            Some(Err(e)) => IpcResponse::CommandResult {
+                success: false,
+                message: format!(
+                    "Agent restarted in {:.1}s but restoring the target failed: {}",
+                    elapsed, e
+                ),
+            },
+        }
+    }
+
     async fn handle_connect(&mut self, host: &str, port: u16) -> IpcResponse {
-        let mut driver = AgentDriver::direct(host, port);
-        match driver.connect().await {
-            Ok(()) => {
-                self.set_executor_with_driver(Arc::new(driver)).await;
+        let config = DriverConfig::Agent {
+            host: host.to_string(),
+            port,
+            read_timeout: self.read_timeout(),
+            retries: self.retries(),
+            poll_interval: None,
+            fuzzy_selectors: None,
+            lifecycle: None,
+        };
+        match connect_driver(&config).await {
+            Ok(driver) => {
+                self.set_executor_with_driver(Arc::from(driver)).await;
                 IpcResponse::CommandResult {
                     success: true,
                     message: format!("Connected to {}:{}", host, port),
@@ -1097,6 +1294,148 @@ impl ServerState {
         response
     }
 
+    async fn handle_set_appearance(
+        &self,
+        appearance: Appearance,
+        tag: Option<String>,
+    ) -> IpcResponse {
+        let result = if self.android_serial.is_some() {
+            Err("Appearance switching is only supported on iOS simulators".to_string())
+        } else if let Some(ref udid) = self.simulator_udid {
+            Simctl::set_appearance(udid, appearance).map_err(|e| e.to_string())
+        } else {
+            Err("No device selected.".to_string())
+        };
+        let (success, message, action_result) = match result {
+            Ok(()) => (
+                true,
+                format!("Appearance set to {}", appearance),
+                ActionResult::Success,
+            ),
+            Err(e) => {
+                let msg = format!("Failed to set appearance: {}", e);
+                (false, msg.clone(), ActionResult::Failure(msg))
+            }
+        };
+        self.log_action(
+            ActionType::SetAppearance { appearance },
+            action_result,
+            None,
+            tag,
+        )
+        .await;
+        IpcResponse::ActionResult {
+            success,
+            message,
+            screenshot: None,
+            data: None,
+            element_not_found: false,
+        }
+    }
+
+    async fn handle_set_locale(
+        &self,
+        language: String,
+        region: String,
+        tag: Option<String>,
+    ) -> IpcResponse {
+        let result = if self.android_serial.is_some() || self.is_physical_device {
+            Err("Locale switching is only supported on iOS simulators".to_string())
+        } else if let Some(ref udid) = self.simulator_udid {
+            let (udid, lang, reg) = (udid.clone(), language.clone(), region.clone());
+            // Reboots the simulator, which can take a while
+            tokio::task::spawn_blocking(move || Simctl::set_locale(&udid, &lang, &reg))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| r.map_err(|e| e.to_string()))
+        } else {
+            Err("No device selected.".to_string())
+        };
+        let (success, message, action_result) = match result {
+            Ok(()) => (
+                true,
+                format!(
+                    "Locale set to {}-{}; the simulator rebooted, so relaunch the app and restart the agent",
+                    language, region
+                ),
+                ActionResult::Success,
+            ),
+            Err(e) => {
+                let msg = format!("Failed to set locale: {}", e);
+                (false, msg.clone(), ActionResult::Failure(msg))
+            }
+        };
+        self.log_action(
+            ActionType::SetLocale { language, region },
+            action_result,
+            None,
+            tag,
+        )
+        .await;
+        IpcResponse::ActionResult {
+            success,
+            message,
+            screenshot: None,
+            data: None,
+            element_not_found: false,
+        }
+    }
+
+    fn handle_start_recording(&mut self, path: &str) -> IpcResponse {
+        let result = if let Some(ref handle) = self.recording {
+            Err(format!("Already recording to {}", handle.path().display()))
+        } else if self.android_serial.is_some() || self.is_physical_device {
+            Err("Screen recording is only supported on iOS simulators".to_string())
+        } else if let Some(ref udid) = self.simulator_udid {
+            Simctl::start_recording(udid, std::path::Path::new(path)).map_err(|e| e.to_string())
+        } else {
+            Err("No device selected.".to_string())
+        };
+        match result {
+            Ok(handle) => {
+                info!(path = %path, "Recording started");
+                self.recording = Some(handle);
+                IpcResponse::CommandResult {
+                    success: true,
+                    message: format!("Recording to {}", path),
+                }
+            }
+            Err(e) => IpcResponse::CommandResult {
+                success: false,
+                message: format!("Failed to start recording: {}", e),
+            },
+        }
+    }
+
+    fn handle_stop_recording(&mut self) -> IpcResponse {
+        let Some(handle) = self.recording.take() else {
+            return IpcResponse::CommandResult {
+                success: false,
+                message: "No recording in progress".to_string(),
+            };
+        };
+        match handle.stop() {
+            Ok(path) => IpcResponse::CommandResult {
+                success: true,
+                message: format!("Recording saved to {}", path.display()),
+            },
+            Err(e) => IpcResponse::CommandResult {
+                success: false,
+                message: format!("Failed to stop recording: {}", e),
+            },
+        }
+    }
+
+    /// Finalize any running recording, for server shutdown.
+    pub fn stop_recording(&mut self) {
+        if let Some(handle) = self.recording.take() {
+            match handle.stop() {
+                Ok(path) => info!(path = %path.display(), "Recording saved on shutdown"),
+                Err(e) => debug!(error = %e, "recording did not stop cleanly"),
+            }
+        }
+    }
+
     async fn handle_stop_target(&self) -> IpcResponse {
         let Some(ref bundle_id) = self.target_bundle_id else {
             return IpcResponse::CommandResult {
@@ -1166,6 +1505,7 @@ impl ServerState {
                     message: format!("{} ({})", info.display_name, info.bundle_id),
                     screenshot: None,
                     data: Some(json),
+                    element_not_found: false,
                 }
             }
             Err(e) => IpcResponse::CommandResult {
@@ -1175,6 +1515,134 @@ impl ServerState {
         }
     }
 
+    async fn handle_get_device_info(&self) -> IpcResponse {
+        let driver = if let Some(guard) = self.shared_driver.lock().await.as_ref() {
+            guard.clone()
+        } else if let Some(executor) = &self.executor {
+            executor.driver().clone()
+        } else {
+            return IpcResponse::CommandResult {
+                success: false,
+                message: "No automation backend connected.".to_string(),
+            };
+        };
+        match driver.screen_info().await {
+            Ok(info) => {
+                let json = serde_json::to_string(&info).unwrap_or_default();
+                IpcResponse::ActionResult {
+                    success: true,
+                    message: format!(
+                        "{}x{} @{}x ({})",
+                        info.width,
+                        info.height,
+                        info.scale,
+                        info.orientation
+                            .map_or_else(|| "unknown".to_string(), |o| o.to_string())
+                    ),
+                    screenshot: None,
+                    data: Some(json),
+                    element_not_found: false,
+                }
+            }
+            Err(e) => IpcResponse::CommandResult {
+                success: false,
+                message: format!("device-info failed: {}", e),
+            },
+        }
+    }
+
+    async fn handle_element_at(&self, x: i32, y: i32) -> IpcResponse {
+        let driver = if let Some(guard) = self.shared_driver.lock().await.as_ref() {
+            guard.clone()
+        } else if let Some(executor) = &self.executor {
+            executor.driver().clone()
+        } else {
+            return IpcResponse::CommandResult {
+                success: false,
+                message: "No automation backend connected.".to_string(),
+            };
+        };
+        match ActionExecutor::new(driver)
+            .element_at_point(f64::from(x), f64::from(y))
+            .await
+        {
+            Ok(Some(mut element)) => {
+                // The hit alone; a container's subtree can be the whole screen
+                element.children.clear();
+                IpcResponse::ActionResult {
+                    success: true,
+                    message: element.summary(),
+                    screenshot: None,
+                    data: serde_json::to_string(&element).ok(),
+                    element_not_found: false,
+                }
+            }
+            Ok(None) => IpcResponse::ActionResult {
+                success: false,
+                message: format!("No element at ({}, {})", x, y),
+                screenshot: None,
+                data: None,
+                element_not_found: false,
+            },
+            Err(e) => IpcResponse::CommandResult {
+                success: false,
+                message: format!("element-at failed: {}", e),
+            },
+        }
+    }
+
+    async fn handle_ping_agent(&self, count: u32) -> IpcResponse {
+        let driver = if let Some(guard) = self.shared_driver.lock().await.as_ref() {
+            guard.clone()
+        } else if let Some(executor) = &self.executor {
+            executor.driver().clone()
+        } else {
+            return IpcResponse::CommandResult {
+                success: false,
+                message: "No automation backend connected.".to_string(),
+            };
+        };
+        let mut samples = Vec::with_capacity(count.max(1) as usize);
+        for _ in 0..count.max(1) {
+            match driver.ping().await {
+                Ok(rtt) => samples.push(rtt.as_secs_f64() * 1000.0),
+                Err(e) => {
+                    return IpcResponse::CommandResult {
+                        success: false,
+                        message: format!("ping failed: {}", e),
+                    }
+                }
+            }
+        }
+        let avg = samples.iter().sum::<f64>() / samples.len() as f64;
+        let min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = samples.iter().copied().fold(0.0, f64::max);
+        let message = if samples.len() == 1 {
+            format!("Agent latency: {:.1}ms", avg)
+        } else {
+            format!(
+                "Agent latency: avg {:.1}ms, min {:.1}ms, max {:.1}ms ({} pings)",
+                avg,
+                min,
+                max,
+                samples.len()
+            )
+        };
+        let data = serde_json::json!({
+            "samples": samples.len(),
+            "avg_ms": avg,
+            "min_ms": min,
+            "max_ms": max,
+        });
+        IpcResponse::ActionResult {
+            success: true,
+            message,
+            screenshot: None,
+            data: Some(data.to_string()),
+            element_not_found: false,
+        }
+    }
+
     // ── On-Demand Fetching ──────────────────────────────────────────────
 
     async fn handle_fetch_elements(&self) -> IpcResponse {
@@ -1207,6 +1675,44 @@ impl ServerState {
         }
     }
 
+    /// Read the screen through the executor without logging the read.
+    async fn handle_fetch_screen_info(
+        &mut self,
+        filter: ElementFilter,
+        changed_since_hash: Option<String>,
+    ) -> IpcResponse {
+        let Some(executor) = self.action_executor().await else {
+            return IpcResponse::Error {
+                message: "No automation backend connected".to_string(),
+            };
+        };
+        let guard = self.in_flight.begin();
+        let result = executor
+            .with_cancellation(guard.token())
+            .execute(ActionType::GetScreenInfo {
+                filter,
+                changed_since_hash: None,
+            })
+            .await;
+        drop(guard);
+
+        if let (true, Some(data), Some(expected)) =
+            (result.success, result.data.as_deref(), changed_since_hash)
+        {
+            let hash = self.screen_hash(data);
+            if hash == expected {
+                return IpcResponse::NoChange { hash };
+            }
+        }
+        IpcResponse::ActionResult {
+            success: result.success,
+            message: result.message,
+            screenshot: None,
+            data: result.data,
+            element_not_found: result.element_not_found,
+        }
+    }
+
     /// Fetch installed apps for `set-target` completion, picking the source by
     /// active platform. Android selection clears `simulator_udid`, so an active
     /// `android_serial` means the device is Android and we enumerate packages
@@ -1243,20 +1749,50 @@ impl ServerState {
                     active: true,
                     device_udid: self.simulator_udid.clone(),
                     action_count: action_log.len(),
+                    max_log_entries: session.log_limits.max_entries,
+                    max_screenshots: session.log_limits.max_screenshots,
                 }
             }
-            None => IpcResponse::SessionInfo {
-                session_name: self.session_name.clone(),
-                active: false,
-                device_udid: self.simulator_udid.clone(),
-                action_count: 0,
-            },
+            None => {
+                // The limits the next session will get
+                let limits = LogLimits::from_env();
+                IpcResponse::SessionInfo {
+                    session_name: self.session_name.clone(),
+                    active: false,
+                    device_udid: self.simulator_udid.clone(),
+                    action_count: 0,
+                    max_log_entries: limits.max_entries,
+                    max_screenshots: limits.max_screenshots,
+                }
+            }
+        }
+    }
+
+    /// Report whether actions can run: a session is active and the driver
+    /// actions would use is connected.
+    async fn handle_health(&self) -> IpcResponse {
+        let driver = match self.shared_driver.lock().await.as_ref() {
+            Some(driver) => Some(driver.clone()),
+            None => self.executor.as_ref().map(|e| e.driver().clone()),
+        };
+        IpcResponse::Health {
+            session_active: self.session.is_some(),
+            driver_connected: driver.is_some_and(|d| d.is_connected()),
+            agent_target: self.target_bundle_id.clone(),
+            simulator_udid: self.simulator_udid.clone(),
         }
     }
 
     // ── Execute ──────────────────────────────────────────────────────────
 
-    async fn handle_execute(&mut self, action: ActionType, tag: Option<String>) -> IpcResponse {
+    /// Execute an action and log it. `capture_screenshot` overrides
+    /// [`Self::capture_screenshots`] for this action only.
+    async fn handle_execute(
+        &mut self,
+        action: ActionType,
+        tag: Option<String>,
+        capture_screenshot: Option<bool>,
+    ) -> IpcResponse {
         debug!(action = %action.name(), "executing action");
 
         // LogComment doesn't require a driver
@@ -1269,25 +1805,66 @@ impl ServerState {
                 message: msg,
                 screenshot: None,
                 data: None,
+                element_not_found: false,
             };
         }
 
-        let driver_guard = self.shared_driver.lock().await;
-        let driver_opt = driver_guard.clone();
-        drop(driver_guard);
-
-        // Prefer the shared driver (set when agent connects); fall back to executor's driver.
-        let executor = if let Some(driver) = driver_opt {
-            Some(ActionExecutor::new(driver))
-        } else {
-            self.executor
-                .as_ref()
-                .map(|e| ActionExecutor::new(e.driver().clone()))
+        // Neither do assertions: the script already decided the outcome
+        if let ActionType::Assertion {
+            ref description,
+            passed,
+        } = action
+        {
+            let (result, message) = if passed {
+                (
+                    ActionResult::Success,
+                    format!("Assertion passed: {}", description),
+                )
+            } else {
+                let message = format!("Assertion failed: {}", description);
+                (ActionResult::Failure(message.clone()), message)
+            };
+            self.log_action(action, result, None, tag).await;
+            return IpcResponse::ActionResult {
+                success: passed,
+                message,
+                screenshot: None,
+                data: None,
+                element_not_found: false,
+            };
+        }
+
+        // Appearance is a simctl setting, not an agent command
+        if let ActionType::SetAppearance { appearance } = action {
+            return self.handle_set_appearance(appearance, tag).await;
+        }
+        if let ActionType::SetLocale { language, region } = action {
+            return self.handle_set_locale(language, region, tag).await;
+        }
+
+        let changed_since_hash = match action {
+            ActionType::GetScreenInfo {
+                ref changed_since_hash,
+                ..
+            } => changed_since_hash.clone(),
+            _ => None,
         };
 
-        match executor {
+        match self.action_executor().await {
             Some(executor) => {
-                let result = executor.execute(action.clone()).await;
+                let driver = executor.driver().clone();
+                let guard = self.in_flight.begin();
+                let result = executor
+                    .with_cancellation(guard.token())
+                    .execute(action.clone())
+                    .await;
+                drop(guard);
+                let screen_hash =
+                    if result.success && matches!(action, ActionType::GetScreenInfo { .. }) {
+                        result.data.as_deref().map(|data| self.screen_hash(data))
+                    } else {
+                        None
+                    };
 
                 // Log to session
                 let action_result = if result.success {
@@ -1302,19 +1879,35 @@ impl ServerState {
                     }
                 }
 
-                let duration_ms = result
-                    .data
-                    .as_ref()
-                    .and_then(|d| serde_json::from_str::<serde_json::Value>(d).ok())
-                    .and_then(|v| v.get("elapsed_ms").and_then(|e| e.as_u64()));
-                self.log_action(action, action_result, duration_ms, tag)
-                    .await;
+                let duration_ms = result.elapsed_ms();
+                let value = result.read_value(&action);
+                let screenshot = if capture_screenshot.unwrap_or(self.capture_screenshots) {
+                    log_screenshot(&driver, result.screenshot.clone()).await
+                } else {
+                    None
+                };
+                self.log_action_with_value(
+                    action,
+                    action_result,
+                    duration_ms,
+                    value,
+                    screenshot,
+                    tag,
+                )
+                .await;
+
+                if let (Some(hash), Some(expected)) = (screen_hash, changed_since_hash) {
+                    if hash == expected {
+                        return IpcResponse::NoChange { hash };
+                    }
+                }
 
                 IpcResponse::ActionResult {
                     success: result.success,
                     message: result.message,
                     screenshot: result.screenshot.map(Arc::new),
                     data: result.data,
+                    element_not_found: result.element_not_found,
                 }
             }
             None => IpcResponse::Error {
@@ -1323,6 +1916,123 @@ impl ServerState {
         }
     }
 
+    /// Run `actions` through [`ActionExecutor::execute_batch`], logging each
+    /// action that ran.
+    async fn handle_execute_batch(
+        &mut self,
+        actions: Vec<ActionType>,
+        tag: Option<String>,
+    ) -> IpcResponse {
+        debug!(count = actions.len(), "executing batch");
+        if actions.is_empty() {
+            return IpcResponse::Error {
+                message: "Batch has no actions".to_string(),
+            };
+        }
+        if let Some(action) = actions.iter().find(|a| {
+            matches!(
+                a,
+                ActionType::SetAppearance { .. } | ActionType::SetLocale { .. }
+            )
+        }) {
+            return IpcResponse::Error {
+                message: format!("{} cannot run in a batch", action.name()),
+            };
+        }
+        let Some(executor) = self.action_executor().await else {
+            return IpcResponse::Error {
+                message: "No automation backend connected".to_string(),
+            };
+        };
+
+        let driver = executor.driver().clone();
+        let guard = self.in_flight.begin();
+        let results = executor
+            .with_cancellation(guard.token())
+            .execute_batch(actions.clone())
+            .await;
+        drop(guard);
+
+        // The batch runs in one agent frame, so the screen between its
+        // actions is never seen: only the last logged action gets a fresh
+        // capture, the others keep any screenshot they returned themselves
+        let last = results.len().saturating_sub(1);
+        let mut batch = Vec::with_capacity(results.len());
+        for (index, (action, result)) in actions.into_iter().zip(results).enumerate() {
+            let action_result = if result.success {
+                ActionResult::Success
+            } else {
+                ActionResult::Failure(result.message.clone())
+            };
+            if result.success {
+                if let ActionType::SetTarget { ref bundle_id } = action {
+                    self.target_bundle_id = Some(bundle_id.clone());
+                }
+            }
+            let duration_ms = result.elapsed_ms();
+            let value = result.read_value(&action);
+            let screenshot = match (self.capture_screenshots, index == last) {
+                (true, true) => log_screenshot(&driver, result.screenshot.clone()).await,
+                (true, false) => result.screenshot.clone(),
+                (false, _) => None,
+            };
+            self.log_action_with_value(
+                action,
+                action_result,
+                duration_ms,
+                value,
+                screenshot,
+                tag.clone(),
+            )
+            .await;
+            batch.push(BatchActionResult {
+                success: result.success,
+                message: result.message,
+                data: result.data,
+                element_not_found: result.element_not_found,
+            });
+        }
+        IpcResponse::BatchResult { results: batch }
+    }
+
+    /// An executor for the connected backend, configured with the server's
+    /// settings, or `None` when no backend is connected.
+    async fn action_executor(&self) -> Option<ActionExecutor> {
+        // Prefer the shared driver (set when agent connects); fall back to executor's driver.
+        let driver = match self.shared_driver.lock().await.clone() {
+            Some(driver) => driver,
+            None => self.executor.as_ref()?.driver().clone(),
+        };
+
+        // simctl only reaches simulators
+        let simulator_udid = if self.android_serial.is_none() && !self.is_physical_device {
+            self.simulator_udid.clone()
+        } else {
+            None
+        };
+
+        Some(
+            ActionExecutor::new(driver)
+                .with_simulator_udid(simulator_udid)
+                .with_fuzzy_selectors(self.fuzzy_selectors())
+                .with_poll_interval(self.poll_interval()),
+        )
+    }
+
+    /// Hash the element tree in a `GetScreenInfo` payload, reusing the cached
+    /// hash when the payload is identical to the previous one.
+    fn screen_hash(&mut self, data: &str) -> String {
+        if let Some((cached_data, hash)) = &self.screen_hash_cache {
+            if cached_data == data {
+                return hash.clone();
+            }
+        }
+        let elements: Vec<UIElement> = serde_json::from_str(data).unwrap_or_default();
+        let hash = hash_elements(&elements);
+        self.screen_hash_cache = Some((data.to_string(), hash.clone()));
+        hash
+    }
+
     // ── State / Log ──────────────────────────────────────────────────────
 
     async fn handle_get_state(&self) -> IpcResponse {
@@ -1337,6 +2047,25 @@ impl ServerState {
         }
     }
 
+    async fn handle_get_snapshot(&self) -> IpcResponse {
+        match &self.session {
+            Some(session) => {
+                let snapshot = session.snapshot().await;
+                IpcResponse::Snapshot {
+                    snapshot: SessionSnapshot {
+                        // The session keeps the device it started on; report
+                        // the one selected now
+                        udid: self.simulator_udid.clone(),
+                        ..snapshot
+                    },
+                }
+            }
+            None => IpcResponse::Error {
+                message: "No active session".to_string(),
+            },
+        }
+    }
+
     async fn handle_get_log(&self) -> IpcResponse {
         match &self.session {
             Some(session) => IpcResponse::Log {
@@ -1348,14 +2077,177 @@ impl ServerState {
         }
     }
 
+    /// Override the agent port from config (`--agent-port`), re-pointing the
+    /// executor created for a booted simulator at the new port.
+    pub fn set_agent_port(&mut self, port: u16) {
+        self.agent_port = port;
+        if self.executor.is_some() {
+            self.reset_executor();
+        }
+    }
+
+    /// Apply a `--config` driver config: its agent port, its host for
+    /// simulator agents, its read timeout and retries for every driver this
+    /// state builds, and its poll interval for every executor. The server
+    /// picks devices itself, so only an
+    /// [`Agent`](DriverConfig::Agent) config is accepted (checked at startup).
+    pub fn set_driver_config(&mut self, config: DriverConfig) {
+        let port = config.agent_port();
+        self.driver_config = Some(config);
+        match port {
+            Some(port) => self.set_agent_port(port),
+            None if self.executor.is_some() => self.reset_executor(),
+            None => {}
+        }
+    }
+
+    /// The read timeout from [`driver_config`](Self::driver_config), if any.
+    fn read_timeout(&self) -> Option<std::time::Duration> {
+        match self.driver_config {
+            Some(DriverConfig::Agent { read_timeout, .. }) => read_timeout,
+            _ => None,
+        }
+    }
+
+    /// The retries from [`driver_config`](Self::driver_config), if any.
+    fn retries(&self) -> Option<u32> {
+        self.driver_config.as_ref().and_then(DriverConfig::retries)
+    }
+
+    /// The wait poll interval from [`driver_config`](Self::driver_config), or
+    /// the executor's default. Applied to every executor this state builds,
+    /// so the drivers' configs leave it unset.
+    fn poll_interval(&self) -> std::time::Duration {
+        self.driver_config
+            .as_ref()
+            .and_then(DriverConfig::poll_interval)
+            .unwrap_or(DEFAULT_POLL_INTERVAL)
+    }
+
+    /// Whether fuzzy selectors are on: `fuzzy_selectors` from
+    /// [`driver_config`](Self::driver_config), which already reflects
+    /// `QORVEX_FUZZY_SELECTORS`, or else the variable itself.
+    fn fuzzy_selectors(&self) -> bool {
+        self.driver_config
+            .as_ref()
+            .and_then(DriverConfig::fuzzy_selectors)
+            .unwrap_or_else(fuzzy_selectors_from_env)
+    }
+
     // ── Helpers ──────────────────────────────────────────────────────────
 
+    /// Point the executor, not yet connected, at the agent of the selected
+    /// device, or drop it when none is selected.
+    fn reset_executor(&mut self) {
+        self.executor = self.simulator_udid.as_deref().and_then(|udid| {
+            ActionExecutor::from_config(self.ios_driver_config(udid))
+                .inspect_err(|e| warn!(error = %e, "Cannot build the agent driver"))
+                .ok()
+        });
+    }
+
+    /// The driver config for the iOS agent on `udid` over the selected
+    /// connection: tunneld, direct host, CoreDevice, usbmuxd, or simulator
+    /// loopback (or the `--config` host). Callers that manage the agent
+    /// attach its lifecycle with [`DriverConfig::with_lifecycle`] for crash
+    /// recovery.
+    fn ios_driver_config(&self, udid: &str) -> DriverConfig {
+        let port = self.agent_port;
+        let read_timeout = self.read_timeout();
+        let retries = self.retries();
+        if !self.is_physical_device {
+            let host = match &self.driver_config {
+                Some(DriverConfig::Agent { host, .. }) => host.clone(),
+                _ => "127.0.0.1".to_string(),
+            };
+            return DriverConfig::Agent {
+                host,
+                port,
+                read_timeout,
+                retries,
+                poll_interval: None,
+                fuzzy_selectors: None,
+                lifecycle: None,
+            };
+        }
+        if let Some(ref tunnel_address) = self.tunnel_address {
+            DriverConfig::Tunneld {
+                tunnel_address: tunnel_address.clone(),
+                agent_port: port,
+                read_timeout,
+                retries,
+                poll_interval: None,
+                fuzzy_selectors: None,
+                lifecycle: None,
+            }
+        } else if let Some(ref host) = self.direct_host {
+            DriverConfig::Agent {
+                host: host.clone(),
+                port,
+                read_timeout,
+                retries,
+                poll_interval: None,
+                fuzzy_selectors: None,
+                lifecycle: None,
+            }
+        } else if self.use_core_device {
+            DriverConfig::CoreDevice {
+                udid: udid.to_string(),
+                port,
+                read_timeout,
+                retries,
+                poll_interval: None,
+                fuzzy_selectors: None,
+                lifecycle: None,
+            }
+        } else {
+            DriverConfig::Device {
+                udid: udid.to_string(),
+                device_port: port,
+                read_timeout,
+                retries,
+                poll_interval: None,
+                fuzzy_selectors: None,
+                lifecycle: None,
+            }
+        }
+    }
+
     /// Set the executor and update the shared driver so IPC clients reuse the same connection.
     pub async fn set_executor_with_driver(&mut self, driver: Arc<dyn AutomationDriver>) {
+        self.adopt_agent_target(driver.as_ref()).await;
         self.executor = Some(ActionExecutor::new(driver.clone()));
         *self.shared_driver.lock().await = Some(driver);
     }
 
+    /// Recover the target from an agent that outlived the client which set it.
+    ///
+    /// Only applies when this server has no target of its own. Agents that
+    /// predate `GetState` answer with an error, which is ignored.
+    async fn adopt_agent_target(&mut self, driver: &dyn AutomationDriver) {
+        if self.target_bundle_id.is_some() {
+            return;
+        }
+        let target = match driver.query_state().await {
+            Ok(state) => state.target_bundle,
+            Err(e) => {
+                debug!(error = %e, "agent state unavailable");
+                return;
+            }
+        };
+        let Some(bundle_id) = target else {
+            return;
+        };
+        // Re-issue it so the driver restores it after a reconnect
+        match driver.set_target(&bundle_id).await {
+            Ok(()) => {
+                info!(bundle_id = %bundle_id, "Recovered target from running agent");
+                self.target_bundle_id = Some(bundle_id);
+            }
+            Err(e) => debug!(error = %e, "failed to re-apply recovered target"),
+        }
+    }
+
     /// Log an action to the current session.
     pub async fn log_action(
         &self,
@@ -1363,16 +2255,54 @@ impl ServerState {
         result: ActionResult,
         duration_ms: Option<u64>,
         tag: Option<String>,
+    ) {
+        self.log_action_with_value(action, result, duration_ms, None, None, tag)
+            .await;
+    }
+
+    /// Log an action to the current session along with the value it read and
+    /// the screenshot captured after it, if any.
+    pub async fn log_action_with_value(
+        &self,
+        action: ActionType,
+        result: ActionResult,
+        duration_ms: Option<u64>,
+        value: Option<String>,
+        screenshot: Option<String>,
+        tag: Option<String>,
     ) {
         if let Some(session) = &self.session {
-            let screenshot = None;
             session
-                .log_action(action, result, screenshot, duration_ms, tag)
+                .log_action_with_value(action, result, screenshot, duration_ms, value, tag)
                 .await;
         }
     }
 }
 
+/// Builds the driver for `config` through [`driver::create`] and connects it.
+async fn connect_driver(config: &DriverConfig) -> Result<Box<dyn AutomationDriver>, DriverError> {
+    let mut driver = driver::create(config)?;
+    driver.connect().await?;
+    Ok(driver)
+}
+
+/// Message for a failed agent start (or restart, per `action`). Failures
+/// after the agent was spawned also point at its output log.
+fn agent_failure(action: &str, lifecycle: &AgentLifecycle, e: &AgentLifecycleError) -> String {
+    match e {
+        AgentLifecycleError::SpawnFailed(_)
+        | AgentLifecycleError::StartupTimeout
+        | AgentLifecycleError::BuildNotFinished { .. }
+        | AgentLifecycleError::PortNeverOpened { .. } => format!(
+            "Failed to {} agent: {}\nAgent log: {}",
+            action,
+            e,
+            lifecycle.log_path().display()
+        ),
+        _ => format!("Failed to {} agent: {}", action, e),
+    }
+}
+
 /// Validates a UDID format.
 ///
 /// Accepts:
@@ -1431,6 +2361,7 @@ fn strip_quotes(s: &str) -> &str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use qorvex_core::driver::DriverBackend;
 
     /// Build an `AndroidLifecycle` pointing at a dummy project — `new` does no
     /// device I/O, so this is safe with no emulator/adb present. `terminate_agent`
@@ -1441,6 +2372,122 @@ mod tests {
         Arc::new(AndroidLifecycle::new("emulator-5554".into(), config))
     }
 
+    /// Failures after the agent was spawned point at its log; earlier ones
+    /// (nothing was logged yet) do not.
+    #[test]
+    fn agent_failure_points_at_log_after_spawn() {
+        let mut config = AgentLifecycleConfig::new(PathBuf::from("/tmp/agent"));
+        config.log_dir = Some(PathBuf::from("/tmp/qorvex-logs"));
+        let lifecycle = AgentLifecycle::new("SIM-UDID".into(), config);
+
+        let spawned = AgentLifecycleError::SpawnFailed("exit code 65 — boom".into());
+        assert_eq!(
+            agent_failure("start", &lifecycle, &spawned),
+            "Failed to start agent: Agent process exited: exit code 65 — boom\n\
+             Agent log: /tmp/qorvex-logs/agent-SIM-UDID.log"
+        );
+        let build = AgentLifecycleError::BuildFailed("no scheme".into());
+        assert_eq!(
+            agent_failure("restart", &lifecycle, &build),
+            "Failed to restart agent: Failed to build agent: no scheme"
+        );
+    }
+
+    /// The port from `--agent-port` is the one agent drivers connect to, for
+    /// simulators and physical devices alike.
+    #[test]
+    fn agent_port_reaches_connection_target() {
+        let mut state = ServerState::new("test".into());
+        state.set_agent_port(9123);
+        let lifecycle = Arc::new(AgentLifecycle::new(
+            "SIM-UDID".into(),
+            AgentLifecycleConfig::new(PathBuf::from("/tmp/agent")),
+        ));
+
+        let driver = driver::create(
+            &state
+                .ios_driver_config("SIM-UDID")
+                .with_lifecycle(lifecycle.clone()),
+        )
+        .unwrap();
+        assert_eq!(driver.backend(), Some(DriverBackend::Agent));
+        assert!(matches!(
+            state.ios_driver_config("SIM-UDID"),
+            DriverConfig::Agent { host, port: 9123, .. } if host == "127.0.0.1"
+        ));
+
+        state.is_physical_device = true;
+        state.tunnel_address = None;
+        state.direct_host = None;
+        state.use_core_device = false;
+        assert!(matches!(
+            state.ios_driver_config("DEVICE-UDID"),
+            DriverConfig::Device { udid, device_port: 9123, .. } if udid == "DEVICE-UDID"
+        ));
+
+        state.use_core_device = true;
+        assert!(matches!(
+            state.ios_driver_config("DEVICE-UDID"),
+            DriverConfig::CoreDevice { udid, port: 9123, .. } if udid == "DEVICE-UDID"
+        ));
+        state.direct_host = Some("iphone.local".into());
+        assert!(matches!(
+            state.ios_driver_config("DEVICE-UDID"),
+            DriverConfig::Agent { host, port: 9123, .. } if host == "iphone.local"
+        ));
+        state.tunnel_address = Some("fd00::1".into());
+        let config = state
+            .ios_driver_config("DEVICE-UDID")
+            .with_lifecycle(lifecycle);
+        assert!(matches!(
+            &config,
+            DriverConfig::Tunneld { tunnel_address, agent_port: 9123, lifecycle: Some(_), .. }
+                if tunnel_address == "fd00::1"
+        ));
+    }
+
+    /// A `--config` host replaces the simulator loopback, its read timeout and
+    /// retries reach every iOS connection kind, and its poll interval every
+    /// executor.
+    #[test]
+    fn driver_config_reaches_ios_driver_configs() {
+        let mut state = ServerState::new("test".into());
+        state.set_driver_config(DriverConfig::Agent {
+            host: "10.0.0.5".into(),
+            port: 9200,
+            read_timeout: Some(std::time::Duration::from_secs(60)),
+            retries: Some(3),
+            poll_interval: Some(std::time::Duration::from_millis(400)),
+            fuzzy_selectors: Some(true),
+            lifecycle: None,
+        });
+        assert_eq!(state.agent_port, 9200);
+        assert_eq!(state.poll_interval(), std::time::Duration::from_millis(400));
+        assert!(state.fuzzy_selectors());
+        assert!(matches!(
+            state.ios_driver_config("SIM-UDID"),
+            DriverConfig::Agent { host, port: 9200, read_timeout: Some(t), retries: Some(3), .. }
+                if host == "10.0.0.5" && t.as_secs() == 60
+        ));
+
+        state.is_physical_device = true;
+        state.tunnel_address = None;
+        state.direct_host = None;
+        state.use_core_device = false;
+        assert!(matches!(
+            state.ios_driver_config("DEVICE-UDID"),
+            DriverConfig::Device { device_port: 9200, read_timeout: Some(t), retries: Some(3), .. }
+                if t.as_secs() == 60
+        ));
+
+        // --agent-port, applied after the config, wins
+        state.set_agent_port(9300);
+        assert_eq!(
+            state.ios_driver_config("DEVICE-UDID").agent_port(),
+            Some(9300)
+        );
+    }
+
     /// `stop-agent` must terminate the Android lifecycle (not just iOS) and
     /// clear the stored forward, returning success when an Android agent was
     /// present (finding #2).
@@ -1463,6 +2510,22 @@ mod tests {
         assert!(state.android_forward.is_none());
     }
 
+    /// `restart-agent` needs a managed lifecycle to restart.
+    #[tokio::test]
+    async fn restart_agent_without_lifecycle_is_failure() {
+        let mut state = ServerState::new("test".into());
+        state.agent_lifecycle = None;
+        state.android_lifecycle = None;
+
+        match state.handle_request(IpcRequest::RestartAgent).await {
+            IpcResponse::CommandResult { success, message } => {
+                assert!(!success);
+                assert!(message.contains("No managed agent to restart"), "{message}");
+            }
+            other => panic!("expected CommandResult, got {other:?}"),
+        }
+    }
+
     /// `stop-agent` with nothing running returns failure (no managed agent).
     #[test]
     fn stop_agent_no_agent_is_failure() {
@@ -1546,4 +2609,468 @@ mod tests {
         let state = ServerState::new("test".into());
         assert!(state.android_forward.is_none());
     }
+
+    /// The screen hash matches `hash_elements` on the parsed payload, is
+    /// served from the cache for an identical payload, and is dropped when
+    /// the session ends.
+    #[test]
+    fn screen_hash_cache_reuses_and_resets() {
+        let mut state = ServerState::new("test".into());
+        let data = r#"[{"AXUniqueId":"btn","AXLabel":"OK"}]"#;
+        let elements: Vec<UIElement> = serde_json::from_str(data).unwrap();
+
+        let hash = state.screen_hash(data);
+        assert_eq!(hash, hash_elements(&elements));
+        assert_eq!(
+            state.screen_hash_cache,
+            Some((data.to_string(), hash.clone()))
+        );
+        assert_eq!(state.screen_hash(data), hash);
+
+        assert_ne!(state.screen_hash("[]"), hash);
+
+        state.handle_end_session();
+        assert!(state.screen_hash_cache.is_none());
+    }
+
+    /// Recording needs an iOS simulator, and stopping needs a recording.
+    #[test]
+    fn recording_requires_simulator_and_active_recording() {
+        let mut state = ServerState::new("test".into());
+        state.android_serial = Some("emulator-5554".into());
+        match state.handle_start_recording("/tmp/out.mov") {
+            IpcResponse::CommandResult { success, message } => {
+                assert!(!success);
+                assert!(message.contains("only supported on iOS simulators"));
+            }
+            other => panic!("expected CommandResult, got {other:?}"),
+        }
+        assert!(state.recording.is_none());
+
+        match state.handle_stop_recording() {
+            IpcResponse::CommandResult { success, message } => {
+                assert!(!success);
+                assert_eq!(message, "No recording in progress");
+            }
+            other => panic!("expected CommandResult, got {other:?}"),
+        }
+    }
+
+    /// Serve a fake agent that reports `target` from `GetState`, acks every
+    /// other request, and records the requests it saw.
+    async fn mock_agent_with_target(
+        target: Option<&str>,
+    ) -> (
+        std::net::SocketAddr,
+        Arc<std::sync::Mutex<Vec<qorvex_core::protocol::Request>>>,
+    ) {
+        use qorvex_core::protocol::{
+            decode_request, encode_response, read_frame_length, Request, Response,
+        };
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let target = target.map(str::to_string);
+        let log = seen.clone();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut header = [0u8; 4];
+            while stream.read_exact(&mut header).await.is_ok() {
+                let mut payload = vec![0u8; read_frame_length(&header) as usize];
+                stream.read_exact(&mut payload).await.unwrap();
+                let request = decode_request(&payload).unwrap();
+                let response = match request {
+                    Request::GetState => Response::State {
+                        target_bundle: target.clone(),
+                        foreground_app: None,
+                    },
+                    Request::Screenshot {
+                        format: ScreenshotFormat::Png,
+                    } => Response::Screenshot {
+                        data: vec![0x89, b'P', b'N', b'G'],
+                    },
+                    Request::Screenshot {
+                        format: ScreenshotFormat::Jpeg { .. },
+                    } => Response::Screenshot {
+                        data: vec![0xFF, 0xD8, 0xFF],
+                    },
+                    Request::DumpTree | Request::DumpTreeFiltered { .. } => Response::Tree {
+                        json: "[]".to_string(),
+                    },
+                    Request::Batch { ref requests } => Response::Batch {
+                        responses: vec![Response::Ok; requests.len()],
+                    },
+                    _ => Response::Ok,
+                };
+                log.lock().unwrap().push(request);
+                stream.write_all(&encode_response(&response)).await.unwrap();
+            }
+        });
+        (addr, seen)
+    }
+
+    /// Connecting to an agent that already has a target adopts it and
+    /// re-applies it through the new driver.
+    #[tokio::test]
+    async fn connect_recovers_target_from_agent() {
+        let (addr, seen) = mock_agent_with_target(Some("com.example.app")).await;
+        let mut state = ServerState::new("test".into());
+        state.target_bundle_id = None;
+
+        let resp = state.handle_connect("127.0.0.1", addr.port()).await;
+        assert!(matches!(
+            resp,
+            IpcResponse::CommandResult { success: true, .. }
+        ));
+        assert_eq!(state.target_bundle_id.as_deref(), Some("com.example.app"));
+        assert!(seen.lock().unwrap().iter().any(|r| matches!(
+            r,
+            qorvex_core::protocol::Request::SetTarget { bundle_id } if bundle_id == "com.example.app"
+        )));
+    }
+
+    /// `Health` reports the driver only once one is connected, along with the
+    /// target it adopted.
+    #[tokio::test]
+    async fn health_reflects_connected_driver() {
+        let mut state = ServerState::new("test".into());
+        state.executor = None;
+        state.target_bundle_id = None;
+        state.simulator_udid = Some("SIM-1".into());
+        match state.handle_request(IpcRequest::Health).await {
+            IpcResponse::Health {
+                session_active,
+                driver_connected,
+                agent_target,
+                simulator_udid,
+            } => {
+                assert!(!session_active);
+                assert!(!driver_connected);
+                assert_eq!(agent_target, None);
+                assert_eq!(simulator_udid.as_deref(), Some("SIM-1"));
+            }
+            other => panic!("expected Health, got {other:?}"),
+        }
+
+        let (addr, _) = mock_agent_with_target(Some("com.example.app")).await;
+        state.handle_connect("127.0.0.1", addr.port()).await;
+        match state.handle_request(IpcRequest::Health).await {
+            IpcResponse::Health {
+                driver_connected,
+                agent_target,
+                ..
+            } => {
+                assert!(driver_connected);
+                assert_eq!(agent_target.as_deref(), Some("com.example.app"));
+            }
+            other => panic!("expected Health, got {other:?}"),
+        }
+    }
+
+    /// A target already held by the server wins over the agent's.
+    #[tokio::test]
+    async fn connect_keeps_existing_target() {
+        let (addr, seen) = mock_agent_with_target(Some("com.example.other")).await;
+        let mut state = ServerState::new("test".into());
+        state.target_bundle_id = Some("com.example.mine".into());
+
+        state.handle_connect("127.0.0.1", addr.port()).await;
+        assert_eq!(state.target_bundle_id.as_deref(), Some("com.example.mine"));
+        assert!(!seen
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|r| matches!(r, qorvex_core::protocol::Request::GetState)));
+    }
+
+    /// `Wait` sleeps for about the requested time and is logged with its
+    /// duration like any other action, so it can be converted and replayed.
+    #[tokio::test]
+    async fn wait_sleeps_and_is_logged() {
+        let (addr, _) = mock_agent_with_target(None).await;
+        let log_dir = std::env::temp_dir().join(format!("qorvex-wait-{}", std::process::id()));
+        let mut state = ServerState::new("test".into());
+        state.handle_connect("127.0.0.1", addr.port()).await;
+        state.session = Some(Session::new_with_log_dir(None, "test", log_dir.clone()));
+
+        let start = std::time::Instant::now();
+        let resp = state
+            .handle_request(IpcRequest::Execute {
+                action: ActionType::Wait { ms: 100 },
+                tag: Some("settle".into()),
+                capture_screenshot: None,
+            })
+            .await;
+        let elapsed = start.elapsed();
+        assert!(
+            matches!(resp, IpcResponse::ActionResult { success: true, ref message, .. } if message == "Waited 100ms"),
+            "{resp:?}"
+        );
+        assert!(
+            elapsed >= std::time::Duration::from_millis(100),
+            "{elapsed:?}"
+        );
+        assert!(elapsed < std::time::Duration::from_secs(2), "{elapsed:?}");
+
+        match state.handle_request(IpcRequest::GetLog).await {
+            IpcResponse::Log { entries } => {
+                let entry = entries.last().expect("wait is logged");
+                assert!(matches!(entry.action, ActionType::Wait { ms: 100 }));
+                assert!(matches!(entry.result, ActionResult::Success));
+                assert!(entry.duration_ms.is_some_and(|ms| ms >= 100));
+                assert_eq!(entry.tag.as_deref(), Some("settle"));
+            }
+            other => panic!("expected Log, got {other:?}"),
+        }
+        let _ = std::fs::remove_dir_all(&log_dir);
+    }
+
+    /// `SetCaptureScreenshots` decides whether actions logged after it carry
+    /// a screenshot.
+    #[tokio::test]
+    async fn capture_screenshots_toggle_applies_to_logged_actions() {
+        let (addr, seen) = mock_agent_with_target(None).await;
+        let log_dir =
+            std::env::temp_dir().join(format!("qorvex-screenshots-{}", std::process::id()));
+        let mut state = ServerState::new("test".into());
+        state.handle_connect("127.0.0.1", addr.port()).await;
+        state.session = Some(Session::new_with_log_dir(None, "test", log_dir.clone()));
+        let tap = || IpcRequest::Execute {
+            action: ActionType::TapLocation { x: 10, y: 20 },
+            tag: None,
+            capture_screenshot: None,
+        };
+
+        let resp = state
+            .handle_request(IpcRequest::SetCaptureScreenshots { enabled: true })
+            .await;
+        assert!(
+            matches!(resp, IpcResponse::CommandResult { success: true, ref message } if message == "Screenshot capture on"),
+            "{resp:?}"
+        );
+        state.handle_request(tap()).await;
+        state
+            .handle_request(IpcRequest::SetCaptureScreenshots { enabled: false })
+            .await;
+        assert!(!state.capture_screenshots);
+        state.handle_request(tap()).await;
+
+        match state.handle_request(IpcRequest::GetLog).await {
+            IpcResponse::Log { entries } => {
+                let taps: Vec<_> = entries
+                    .iter()
+                    .filter(|e| matches!(e.action, ActionType::TapLocation { .. }))
+                    .collect();
+                assert_eq!(taps.len(), 2);
+                assert!(taps[0].screenshot.is_some());
+                assert!(taps[1].screenshot.is_none());
+            }
+            other => panic!("expected Log, got {other:?}"),
+        }
+        let screenshots = seen
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| {
+                matches!(
+                    r,
+                    qorvex_core::protocol::Request::Screenshot {
+                        format: LOG_SCREENSHOT_FORMAT
+                    }
+                )
+            })
+            .count();
+        assert_eq!(screenshots, 1, "log screenshots are requested as JPEG");
+        let _ = std::fs::remove_dir_all(&log_dir);
+    }
+
+    /// `capture_screenshot: Some(false)` skips the screenshot for one action
+    /// without changing the session setting.
+    #[tokio::test]
+    async fn execute_can_skip_screenshot_for_one_action() {
+        let (addr, _) = mock_agent_with_target(None).await;
+        let log_dir =
+            std::env::temp_dir().join(format!("qorvex-no-screenshot-{}", std::process::id()));
+        let mut state = ServerState::new("test".into());
+        state.handle_connect("127.0.0.1", addr.port()).await;
+        state.session = Some(Session::new_with_log_dir(None, "test", log_dir.clone()));
+        state.capture_screenshots = true;
+        let tap = |capture_screenshot| IpcRequest::Execute {
+            action: ActionType::TapLocation { x: 10, y: 20 },
+            tag: None,
+            capture_screenshot,
+        };
+
+        state.handle_request(tap(Some(false))).await;
+        state.handle_request(tap(None)).await;
+        assert!(state.capture_screenshots);
+
+        match state.handle_request(IpcRequest::GetLog).await {
+            IpcResponse::Log { entries } => {
+                let taps: Vec<_> = entries
+                    .iter()
+                    .filter(|e| matches!(e.action, ActionType::TapLocation { .. }))
+                    .collect();
+                assert_eq!(taps.len(), 2);
+                assert!(taps[0].screenshot.is_none());
+                assert!(taps[1].screenshot.is_some());
+            }
+            other => panic!("expected Log, got {other:?}"),
+        }
+        let _ = std::fs::remove_dir_all(&log_dir);
+    }
+
+    /// `ExecuteBatch` sends consecutive gestures in one batch frame and logs
+    /// every action with the batch's tag.
+    #[tokio::test]
+    async fn execute_batch_sends_one_frame_and_logs_each_action() {
+        let (addr, seen) = mock_agent_with_target(None).await;
+        let log_dir = std::env::temp_dir().join(format!("qorvex-batch-{}", std::process::id()));
+        let mut state = ServerState::new("test".into());
+        state.handle_connect("127.0.0.1", addr.port()).await;
+        state.session = Some(Session::new_with_log_dir(None, "test", log_dir.clone()));
+        seen.lock().unwrap().clear();
+
+        let resp = state
+            .handle_request(IpcRequest::ExecuteBatch {
+                actions: vec![
+                    ActionType::TapLocation { x: 10, y: 20 },
+                    ActionType::Swipe {
+                        direction: "up".into(),
+                    },
+                ],
+                tag: Some("login".into()),
+            })
+            .await;
+        match resp {
+            IpcResponse::BatchResult { results } => {
+                assert_eq!(results.len(), 2);
+                assert!(BatchActionResult::all_succeeded(&results));
+                assert_eq!(results[0].message, "Tapped at (10, 20)");
+            }
+            other => panic!("expected BatchResult, got {other:?}"),
+        }
+        assert!(matches!(
+            seen.lock().unwrap().as_slice(),
+            [qorvex_core::protocol::Request::Batch { requests }] if requests.len() == 2
+        ));
+
+        match state.handle_request(IpcRequest::GetLog).await {
+            IpcResponse::Log { entries } => {
+                assert_eq!(entries.len(), 2);
+                assert!(matches!(entries[0].action, ActionType::TapLocation { .. }));
+                assert!(matches!(entries[1].action, ActionType::Swipe { .. }));
+                assert!(entries
+                    .iter()
+                    .all(|e| e.tag.as_deref() == Some("login") && e.screenshot.is_none()));
+            }
+            other => panic!("expected Log, got {other:?}"),
+        }
+
+        let resp = state
+            .handle_request(IpcRequest::ExecuteBatch {
+                actions: vec![],
+                tag: None,
+            })
+            .await;
+        assert!(matches!(resp, IpcResponse::Error { .. }), "{resp:?}");
+
+        // With screenshots on, the screen after the batch goes on its last action
+        state
+            .handle_request(IpcRequest::SetCaptureScreenshots { enabled: true })
+            .await;
+        state
+            .handle_request(IpcRequest::ExecuteBatch {
+                actions: vec![
+                    ActionType::TapLocation { x: 1, y: 2 },
+                    ActionType::TapLocation { x: 3, y: 4 },
+                ],
+                tag: Some("shots".into()),
+            })
+            .await;
+        match state.handle_request(IpcRequest::GetLog).await {
+            IpcResponse::Log { entries } => {
+                let shots: Vec<_> = entries
+                    .iter()
+                    .filter(|e| e.tag.as_deref() == Some("shots"))
+                    .map(|e| e.screenshot.is_some())
+                    .collect();
+                assert_eq!(shots, vec![false, true]);
+            }
+            other => panic!("expected Log, got {other:?}"),
+        }
+        let _ = std::fs::remove_dir_all(&log_dir);
+    }
+
+    /// `FetchScreenInfo` reads the screen, answering `NoChange` for a known
+    /// hash, and logs nothing.
+    #[tokio::test]
+    async fn fetch_screen_info_is_not_logged() {
+        let (addr, _) = mock_agent_with_target(None).await;
+        let log_dir =
+            std::env::temp_dir().join(format!("qorvex-fetch-screen-{}", std::process::id()));
+        let mut state = ServerState::new("test".into());
+        state.handle_connect("127.0.0.1", addr.port()).await;
+        state.session = Some(Session::new_with_log_dir(None, "test", log_dir.clone()));
+        let fetch = |changed_since_hash| IpcRequest::FetchScreenInfo {
+            filter: ElementFilter::default(),
+            changed_since_hash,
+        };
+
+        let resp = state.handle_request(fetch(None)).await;
+        let data = match resp {
+            IpcResponse::ActionResult {
+                success: true,
+                data: Some(data),
+                ..
+            } => data,
+            other => panic!("expected ActionResult, got {other:?}"),
+        };
+        let elements: Vec<UIElement> = serde_json::from_str(&data).unwrap();
+        let resp = state
+            .handle_request(fetch(Some(hash_elements(&elements))))
+            .await;
+        assert!(matches!(resp, IpcResponse::NoChange { .. }), "{resp:?}");
+
+        match state.handle_request(IpcRequest::GetLog).await {
+            IpcResponse::Log { entries } => assert!(entries.is_empty(), "{entries:?}"),
+            other => panic!("expected Log, got {other:?}"),
+        }
+        let _ = std::fs::remove_dir_all(&log_dir);
+    }
+
+    /// `GetSnapshot` reports the device selected now, which `use-device` may
+    /// have changed since the session started.
+    #[tokio::test]
+    async fn snapshot_reports_selected_device() {
+        let log_dir = std::env::temp_dir().join(format!("qorvex-snapshot-{}", std::process::id()));
+        let mut state = ServerState::new("test".into());
+        state.session = Some(Session::new_with_log_dir(
+            Some("SIM-OLD".into()),
+            "test",
+            log_dir.clone(),
+        ));
+        state.simulator_udid = Some("SIM-NEW".into());
+        state
+            .log_action(
+                ActionType::LogComment {
+                    message: "hello".into(),
+                },
+                ActionResult::Success,
+                None,
+                None,
+            )
+            .await;
+
+        match state.handle_request(IpcRequest::GetSnapshot).await {
+            IpcResponse::Snapshot { snapshot } => {
+                assert_eq!(snapshot.udid.as_deref(), Some("SIM-NEW"));
+                assert_eq!(snapshot.action_count, 1);
+            }
+            other => panic!("expected Snapshot, got {other:?}"),
+        }
+        let _ = std::fs::remove_dir_all(&log_dir);
+    }
 }
@@ -0,0 +1,190 @@
+//! Routing of IPC requests to per-session server state.
+//!
+//! A server normally owns exactly one session, named on the command line.
+//! In multi-session mode (`--multi`) it instead keeps one [`ServerState`] per
+//! session name seen in an [`IpcEnvelope`](qorvex_core::ipc::IpcEnvelope),
+//! creating each on first use, up to [`MAX_SESSIONS`]. Names must pass
+//! [`is_valid_session_name`], since they become log file names.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tracing::info;
+
+use qorvex_core::driver::DriverConfig;
+use qorvex_core::ipc::{is_valid_session_name, InFlightActions};
+
+use crate::server::ServerState;
+
+/// Most sessions a multi-session server holds at once; requests naming a new
+/// session beyond this are refused until one is shut down.
+pub const MAX_SESSIONS: usize = 32;
+
+/// One session's state plus the handles its connections use without locking it.
+#[derive(Clone)]
+pub struct SessionSlot {
+    pub state: Arc<Mutex<ServerState>>,
+    /// Cloned out of the state so `Cancel` never waits on the state lock.
+    pub in_flight: InFlightActions,
+}
+
+impl SessionSlot {
+    fn new(state: ServerState) -> Self {
+        let in_flight = state.in_flight.clone();
+        Self {
+            state: Arc::new(Mutex::new(state)),
+            in_flight,
+        }
+    }
+}
+
+/// The sessions served by this process, keyed by name.
+pub struct Sessions {
+    /// Session used for requests that name none (and for every request in
+    /// single-session mode).
+    default_name: String,
+    /// Whether requests are routed by their `session` field.
+    multi: bool,
+    /// Whether states created on first use get [`ServerState::resume`] set.
+    resume: bool,
+    /// Agent port given on the command line, applied to states created on
+    /// first use.
+    agent_port: Option<u16>,
+    /// Driver config given with `--config`, applied to states created on
+    /// first use before `agent_port`.
+    driver_config: Option<DriverConfig>,
+    slots: std::sync::Mutex<HashMap<String, SessionSlot>>,
+}
+
+impl Sessions {
+    /// Serves only `state`, whatever session a request names.
+    pub fn single(state: ServerState) -> Self {
+        let sessions = Self {
+            default_name: state.session_name.clone(),
+            multi: false,
+            resume: state.resume,
+            agent_port: Some(state.agent_port),
+            driver_config: state.driver_config.clone(),
+            slots: Default::default(),
+        };
+        sessions.insert(state);
+        sessions
+    }
+
+    /// Routes requests by session name, falling back to `default_name`.
+    pub fn multi(default_name: String) -> Self {
+        Self {
+            default_name,
+            multi: true,
+            resume: false,
+            agent_port: None,
+            driver_config: None,
+            slots: Default::default(),
+        }
+    }
+
+    /// Sets [`ServerState::resume`] on the states created for new session
+    /// names.
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Calls [`ServerState::set_agent_port`] on the states created for new
+    /// session names; `None` keeps the port from the environment or config.
+    pub fn with_agent_port(mut self, agent_port: Option<u16>) -> Self {
+        self.agent_port = agent_port;
+        self
+    }
+
+    /// Calls [`ServerState::set_driver_config`] on the states created for new
+    /// session names.
+    pub fn with_driver_config(mut self, driver_config: Option<DriverConfig>) -> Self {
+        self.driver_config = driver_config;
+        self
+    }
+
+    /// Adds (or replaces) the slot for `state.session_name`.
+    pub fn insert(&self, state: ServerState) {
+        let name = state.session_name.clone();
+        self.lock().insert(name, SessionSlot::new(state));
+    }
+
+    /// Whether requests are routed by their `session` field.
+    pub fn is_multi(&self) -> bool {
+        self.multi
+    }
+
+    /// Returns the slot for a request addressed to `requested`.
+    ///
+    /// In multi-session mode an unknown name gets a fresh [`ServerState`];
+    /// invalid names and names beyond [`MAX_SESSIONS`] are refused with the
+    /// message to send back.
+    pub async fn slot(&self, requested: Option<&str>) -> Result<SessionSlot, String> {
+        let name = match requested {
+            Some(name) if self.multi => name,
+            _ => self.default_name.as_str(),
+        };
+        if let Some(slot) = self.lock().get(name) {
+            return Ok(slot.clone());
+        }
+        if !is_valid_session_name(name) {
+            return Err(format!(
+                "Invalid session name '{name}': use 1-64 letters, digits, '-', '_' or '.'"
+            ));
+        }
+        self.check_capacity()?;
+
+        // Device discovery shells out to simctl/adb, so keep it off the runtime
+        let owned = name.to_string();
+        let mut state = tokio::task::spawn_blocking(move || ServerState::new(owned))
+            .await
+            .expect("ServerState::new panicked");
+        state.resume = self.resume;
+        if let Some(config) = &self.driver_config {
+            state.set_driver_config(config.clone());
+        }
+        if let Some(port) = self.agent_port {
+            state.set_agent_port(port);
+        }
+
+        let mut slots = self.lock();
+        if let Some(slot) = slots.get(name) {
+            return Ok(slot.clone());
+        }
+        // Another connection may have filled the last place during discovery
+        if slots.len() >= MAX_SESSIONS {
+            return Err(too_many_sessions());
+        }
+        info!(session = %name, "Created session state");
+        let slot = SessionSlot::new(state);
+        slots.insert(name.to_string(), slot.clone());
+        Ok(slot)
+    }
+
+    /// Removes and returns the slot for `name`, if there is one.
+    pub fn remove(&self, name: &str) -> Option<SessionSlot> {
+        self.lock().remove(name)
+    }
+
+    /// Removes and returns every slot, for shutdown.
+    pub fn drain(&self) -> Vec<SessionSlot> {
+        self.lock().drain().map(|(_, slot)| slot).collect()
+    }
+
+    fn check_capacity(&self) -> Result<(), String> {
+        if self.lock().len() >= MAX_SESSIONS {
+            return Err(too_many_sessions());
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, SessionSlot>> {
+        self.slots.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn too_many_sessions() -> String {
+    format!("Too many sessions (at most {MAX_SESSIONS}); shut one down first")
+}
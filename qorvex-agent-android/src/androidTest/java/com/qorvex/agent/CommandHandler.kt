@@ -69,7 +69,7 @@ class CommandHandler {
             is AgentRequest.GetValue ->
                 handleGetValue(request.selector, request.byLabel, request.elementType, request.timeoutMs)
             is AgentRequest.DumpTree -> handleDumpTree()
-            is AgentRequest.Screenshot -> handleScreenshot()
+            is AgentRequest.Screenshot -> handleScreenshot(request.jpegQuality)
             is AgentRequest.SetTarget -> handleSetTarget(request.bundleId)
             is AgentRequest.FindElement ->
                 handleFindElement(request.selector, request.byLabel, request.elementType)
@@ -469,7 +469,7 @@ class CommandHandler {
 
     // -- Screenshot ---------------------------------------------------------
 
-    private fun handleScreenshot(): AgentResponse {
+    private fun handleScreenshot(jpegQuality: Int?): AgentResponse {
         // `takeScreenshot` is the one capture path that does not go through
         // [rootNode], so it can't lean on its window-list fallback. If the a11y
         // connection is stale it can return null; re-assert it, let the UI
@@ -484,9 +484,13 @@ class CommandHandler {
             uiAutomation.takeScreenshot()
         }) ?: return AgentResponse.Error("Screenshot failed: no bitmap produced")
         val out = ByteArrayOutputStream()
-        val ok = bitmap.compress(android.graphics.Bitmap.CompressFormat.PNG, 100, out)
+        val ok = if (jpegQuality != null) {
+            bitmap.compress(android.graphics.Bitmap.CompressFormat.JPEG, jpegQuality.coerceIn(1, 100), out)
+        } else {
+            bitmap.compress(android.graphics.Bitmap.CompressFormat.PNG, 100, out)
+        }
         bitmap.recycle()
-        if (!ok) return AgentResponse.Error("Screenshot failed: PNG compression failed")
+        if (!ok) return AgentResponse.Error("Screenshot failed: image compression failed")
         return AgentResponse.Screenshot(out.toByteArray())
     }
 
@@ -12,6 +12,8 @@
 //   frame                   = boundsInScreen { x=left, y=top, width, height }
 //   role                    = full className (FQCN, advisory)
 //   hittable                = isEnabled && isVisibleToUser
+//   enabled / selected / focused = isEnabled / isSelected / isFocused
+//   placeholder             = hintText if non-empty (API 26+)
 //   children                = recursive getChild(i)
 //
 // ADR-1 amendment (task 107): hittable dropped the `isClickable` term. iOS
@@ -197,6 +199,14 @@ object NodeMapper {
             children = children,
             role = role(node),
             hittable = hittable(node),
+            enabled = node.isEnabled,
+            selected = node.isSelected,
+            focused = node.isFocused,
+            placeholder = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+                node.hintText?.toString()?.ifEmpty { null }
+            } else {
+                null
+            },
         )
     }
 
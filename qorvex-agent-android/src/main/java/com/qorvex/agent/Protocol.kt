@@ -86,7 +86,8 @@ sealed class AgentRequest {
     ) : AgentRequest()
     data class LongPress(val x: Int, val y: Int, val duration: Double) : AgentRequest()
     object DumpTree : AgentRequest()
-    object Screenshot : AgentRequest()
+    /** A screenshot; JPEG at [jpegQuality] when set, otherwise PNG. */
+    data class Screenshot(val jpegQuality: Int? = null) : AgentRequest()
     data class SetTarget(val bundleId: String) : AgentRequest()
     data class FindElement(
         val selector: String,
@@ -261,7 +262,19 @@ fun decodeRequest(data: ByteArray): AgentRequest {
 
         OpCode.DUMP_TREE -> AgentRequest.DumpTree
 
-        OpCode.SCREENSHOT -> AgentRequest.Screenshot
+        OpCode.SCREENSHOT -> {
+            // No payload (older hosts) means PNG; otherwise a format tag, then
+            // the JPEG quality for tag 0x01.
+            if (cursor.remaining == 0) {
+                AgentRequest.Screenshot()
+            } else {
+                when (val tag = cursor.readUInt8()) {
+                    0x00 -> AgentRequest.Screenshot()
+                    0x01 -> AgentRequest.Screenshot(jpegQuality = cursor.readUInt8())
+                    else -> throw ProtocolException.InvalidPayload("unknown screenshot format: $tag")
+                }
+            }
+        }
 
         OpCode.SET_TARGET -> AgentRequest.SetTarget(cursor.readString())
 
@@ -12,6 +12,10 @@
 //   children   -> [UIElement]  (array, default [])
 //   role       -> role         (string, optional)
 //   hittable   -> hittable     (bool, optional)
+//   enabled    -> enabled      (bool, optional)
+//   selected   -> selected     (bool, optional)
+//   focused    -> focused      (bool, optional)
+//   placeholder -> placeholder (string, optional)
 
 package com.qorvex.agent
 
@@ -40,6 +44,10 @@ data class UIElementJSON(
     val children: List<UIElementJSON>,
     val role: String?,
     val hittable: Boolean?,
+    val enabled: Boolean? = null,
+    val selected: Boolean? = null,
+    val focused: Boolean? = null,
+    val placeholder: String? = null,
 ) {
     /** Serialize this element (and its subtree) to a JSON object string. */
     fun toJson(): String {
@@ -70,10 +78,11 @@ data class UIElementJSON(
         }
         sb.append(']')
         first = appendStringField(sb, first, "role", role)
-        if (hittable != null) {
-            appendKeySep(sb, first, "hittable")
-            sb.append(if (hittable) "true" else "false")
-        }
+        first = appendBoolField(sb, first, "hittable", hittable)
+        first = appendBoolField(sb, first, "enabled", enabled)
+        first = appendBoolField(sb, first, "selected", selected)
+        first = appendBoolField(sb, first, "focused", focused)
+        appendStringField(sb, first, "placeholder", placeholder)
         sb.append('}')
     }
 
@@ -104,6 +113,18 @@ data class UIElementJSON(
         return false
     }
 
+    private fun appendBoolField(
+        sb: StringBuilder,
+        first: Boolean,
+        key: String,
+        value: Boolean?,
+    ): Boolean {
+        if (value == null) return first
+        appendKeySep(sb, first, key)
+        sb.append(if (value) "true" else "false")
+        return false
+    }
+
     companion object {
         /** Format a Double as a finite JSON number (NaN/Inf become 0.0). */
         private fun numToJson(d: Double): String {
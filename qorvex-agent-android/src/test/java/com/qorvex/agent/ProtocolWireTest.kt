@@ -240,6 +240,14 @@ class ProtocolWireTest {
         assertTrue(decodeRequest(byteArrayOf(0x14)) is AgentRequest.GetTargetInfo)
     }
 
+    @Test
+    fun decodeScreenshotFormat() {
+        // Matches Rust screenshot_format_wire_format.
+        assertEquals(null, (decodeRequest(byteArrayOf(0x11)) as AgentRequest.Screenshot).jpegQuality)
+        assertEquals(null, (decodeRequest(byteArrayOf(0x11, 0x00)) as AgentRequest.Screenshot).jpegQuality)
+        assertEquals(60, (decodeRequest(byteArrayOf(0x11, 0x01, 60)) as AgentRequest.Screenshot).jpegQuality)
+    }
+
     @Test
     fun decodeBridgeHealth() {
         // Opcode 0x15, no payload — matches Rust bridge_health_wire_format.
@@ -56,6 +56,28 @@ class SerializerTest {
         assertTrue("hittable false present", json.contains("\"hittable\":false"))
     }
 
+    @Test
+    fun emitsStateFieldsOnlyWhenKnown() {
+        val el = UIElementJSON(
+            axUniqueId = "save",
+            axLabel = null,
+            axValue = null,
+            type = "Button",
+            frame = null,
+            children = emptyList(),
+            role = null,
+            hittable = null,
+            enabled = false,
+            selected = true,
+        )
+        val json = el.toJson()
+        assertTrue("enabled false present", json.contains("\"enabled\":false"))
+        assertTrue("selected present", json.contains("\"selected\":true"))
+        assertTrue("focused omitted when null", !json.contains("focused"))
+        assertTrue("hittable omitted when null", !json.contains("hittable"))
+        assertTrue("fields comma-separated", json.endsWith("[],\"enabled\":false,\"selected\":true}"))
+    }
+
     @Test
     fun nestedChildrenSerialize() {
         val child = UIElementJSON(
@@ -101,6 +123,27 @@ class SerializerTest {
         assertTrue("newline escaped", json.contains("\\n"))
     }
 
+    @Test
+    fun placeholderSerializesAfterState() {
+        val el = UIElementJSON(
+            axUniqueId = "email",
+            axLabel = null,
+            axValue = null,
+            type = "EditText",
+            frame = null,
+            children = emptyList(),
+            role = null,
+            hittable = null,
+            focused = false,
+            placeholder = "you@example.com",
+        )
+        val json = el.toJson()
+        assertTrue(
+            "placeholder last",
+            json.endsWith("\"focused\":false,\"placeholder\":\"you@example.com\"}"),
+        )
+    }
+
     @Test
     fun emptyTreeIsEmptyArray() {
         assertEquals("[]", serializeTree(emptyList()))
@@ -13,6 +13,8 @@ final class AgentServer {
     private let queue = DispatchQueue(label: "com.qorvex.agent.server")
     private var lastActivityDate = Date()
     private var watchdogTimer: DispatchSourceTimer?
+    private var streamTimer: DispatchSourceTimer?
+    private var frameInFlight = false
 
     init(port: UInt16, handler: CommandHandler) {
         self.port = port
@@ -62,6 +64,7 @@ final class AgentServer {
     /// Stop the server and close any active connection.
     func stop() {
         stopWatchdog()
+        stopStream()
         activeConnection?.cancel()
         activeConnection = nil
         listener?.cancel()
@@ -103,6 +106,7 @@ final class AgentServer {
             NSLog("[qorvex-agent] Replacing existing connection")
             existing.cancel()
         }
+        stopStream()
         activeConnection = connection
         startWatchdog(for: connection)
 
@@ -128,6 +132,7 @@ final class AgentServer {
     private func connectionEnded(_ connection: NWConnection) {
         if activeConnection === connection {
             stopWatchdog()
+            stopStream()
             activeConnection = nil
         }
     }
@@ -197,9 +202,7 @@ final class AgentServer {
             let response: AgentResponse
             do {
                 let request = try decodeRequest(from: payloadData)
-                response = DispatchQueue.main.sync {
-                    self.handler.handle(request)
-                }
+                response = self.respond(to: request, on: connection)
             } catch {
                 NSLog("[qorvex-agent] Decode error: %@", "\(error)")
                 response = .error(message: "decode error: \(error)")
@@ -212,6 +215,66 @@ final class AgentServer {
         }
     }
 
+    /// Handle a request. Screenshot streams run here because they push frames
+    /// on the connection instead of replying once.
+    private func respond(to request: AgentRequest, on connection: NWConnection) -> AgentResponse {
+        switch request {
+        case .streamScreenshots(let fps, let quality):
+            startStream(fps: fps, quality: quality, on: connection)
+            return .ok
+        case .stopStream:
+            stopStream()
+            return .ok
+        default:
+            // Replies would interleave with frames the host reads as a stream.
+            if streamTimer != nil {
+                return .error(message: "screenshot stream active; send StopStream first")
+            }
+            return DispatchQueue.main.sync {
+                self.handler.handle(request)
+            }
+        }
+    }
+
+    // MARK: - Screenshot streaming
+
+    /// Push a JPEG frame every 1/fps seconds until `stopStream`. The first frame
+    /// follows the acknowledgement, which is sent before the timer first fires.
+    /// A frame is skipped while the previous one is still being sent.
+    private func startStream(fps: UInt8, quality: UInt8, on connection: NWConnection) {
+        stopStream()
+        let interval = 1.0 / Double(min(max(fps, 1), 30))
+        let quality = min(max(quality, 1), 100)
+
+        let timer = DispatchSource.makeTimerSource(queue: queue)
+        timer.schedule(deadline: .now() + interval, repeating: interval)
+        timer.setEventHandler { [weak self] in
+            guard let self = self, !self.frameInFlight else { return }
+            self.lastActivityDate = Date()
+            let frame = DispatchQueue.main.sync {
+                self.handler.captureStreamFrame(quality: quality)
+            }
+            self.frameInFlight = true
+            self.sendResponse(frame, on: connection) {
+                self.frameInFlight = false
+            }
+            if case .error = frame {
+                self.stopStream()
+            }
+        }
+        timer.resume()
+        streamTimer = timer
+        NSLog("[qorvex-agent] Screenshot stream started (%.1f fps)", 1.0 / interval)
+    }
+
+    private func stopStream() {
+        guard let timer = streamTimer else { return }
+        timer.cancel()
+        streamTimer = nil
+        frameInFlight = false
+        NSLog("[qorvex-agent] Screenshot stream stopped")
+    }
+
     // MARK: - Sending responses
 
     private func sendResponse(
@@ -46,6 +46,8 @@ func parseSelectorIndex(_ selector: String) -> (base: String, index: Int?) {
 
 final class CommandHandler {
     private var app: XCUIApplication
+    /// Bundle ID from the last `setTarget`, kept across host connections.
+    private var targetBundleId: String?
 
     init(app: XCUIApplication) {
         self.app = app
@@ -73,6 +75,9 @@ final class CommandHandler {
         case .typeText(let text):
             return handleTypeText(text: text)
 
+        case .sendKey(let key):
+            return handleSendKey(key)
+
         case .swipe(let startX, let startY, let endX, let endY, let duration):
             return handleSwipe(
                 startX: startX, startY: startY,
@@ -83,13 +88,19 @@ final class CommandHandler {
         case .longPress(let x, let y, let duration):
             return handleLongPress(x: x, y: y, duration: duration)
 
+        case .longPressElement(let selector, let byLabel, let duration):
+            return handleLongPressElement(selector: selector, byLabel: byLabel, duration: duration)
+
         case .getValue(let selector, let byLabel, let elementType, let timeoutMs):
             return handleGetValue(selector: selector, byLabel: byLabel, elementType: elementType, timeoutMs: timeoutMs)
 
         case .dumpTree:
             return handleDumpTree()
 
-        case .screenshot:
+        case .screenshot(let jpegQuality):
+            if let quality = jpegQuality {
+                return captureStreamFrame(quality: quality)
+            }
             return handleScreenshot()
 
         case .setTarget(let bundleId):
@@ -103,6 +114,52 @@ final class CommandHandler {
 
         case .deviceUdid:
             return handleDeviceUdid()
+
+        case .dumpTreeFiltered(let types, let visibleOnly, let maxDepth):
+            return handleDumpTree(filter: TreeFilter(
+                types: Set(types),
+                visibleOnly: visibleOnly,
+                maxDepth: maxDepth
+            ))
+
+        case .getState:
+            return handleGetState()
+
+        case .getScreenInfo:
+            return handleGetScreenInfo()
+
+        case .setOrientation(let name):
+            return handleSetOrientation(name: name)
+
+        case .drag(let startX, let startY, let endX, let endY, let holdDuration, let moveDuration):
+            return handleDrag(
+                startX: startX, startY: startY,
+                endX: endX, endY: endY,
+                holdDuration: holdDuration, moveDuration: moveDuration
+            )
+
+        case .multiTap(let fingerCount, let x, let y):
+            return handleMultiTap(fingerCount: fingerCount, x: x, y: y)
+
+        case .paste:
+            return handlePaste()
+
+        case .clearText(let selector, let byLabel):
+            return handleClearText(selector: selector, byLabel: byLabel)
+
+        case .findAll(let selector, let byLabel, let elementType):
+            return handleDumpTree(matcher: ElementMatcher(
+                selector: selector,
+                byLabel: byLabel,
+                elementType: elementType
+            ))
+
+        case .batch(let requests):
+            return handleBatch(requests)
+
+        case .streamScreenshots, .stopStream:
+            // AgentServer runs streams on the connection itself.
+            return .error(message: "screenshot streams cannot be batched")
         }
     }
 
@@ -304,6 +361,102 @@ final class CommandHandler {
         return .ok
     }
 
+    // MARK: - Special keys
+
+    private func handleSendKey(_ key: SpecialKey) -> AgentResponse {
+        // XCUIKeyboardKey values are strings typeText understands as key presses.
+        let keyboardKey: XCUIKeyboardKey
+        switch key {
+        case .returnKey: keyboardKey = .return
+        case .tab: keyboardKey = .tab
+        case .delete: keyboardKey = .delete
+        case .escape: keyboardKey = .escape
+        case .up: keyboardKey = .upArrow
+        case .down: keyboardKey = .downArrow
+        case .left: keyboardKey = .leftArrow
+        case .right: keyboardKey = .rightArrow
+        }
+        return handleTypeText(text: keyboardKey.rawValue)
+    }
+
+    // MARK: - Paste
+
+    private func handlePaste() -> AgentResponse {
+        // The host has already filled the pasteboard. Paste through the edit
+        // menu of the focused field, which works without a hardware keyboard.
+        let focusedElement = app.descendants(matching: .any).matching(
+            NSPredicate(format: "hasKeyboardFocus == YES")
+        ).firstMatch
+
+        var errorMsg: String?
+        var objcError: NSError?
+        let caught = QVXTryCatch({
+            guard focusedElement.exists else {
+                errorMsg = "No focused text field; tap a text field first"
+                return
+            }
+            focusedElement.press(forDuration: 1.0)
+            let pasteItem = self.app.menuItems["Paste"]
+            if pasteItem.waitForExistence(timeout: 2.0) {
+                pasteItem.tap()
+            } else {
+                errorMsg = "Paste menu did not appear"
+            }
+        }, &objcError)
+        if !caught {
+            let msg = objcError?.localizedDescription ?? "Unknown ObjC exception"
+            return .error(message: "Paste failed: \(msg)")
+        }
+        if let errorMsg = errorMsg {
+            return .error(message: errorMsg)
+        }
+        return .ok
+    }
+
+    // MARK: - Clear text
+
+    private func handleClearText(selector: String, byLabel: Bool) -> AgentResponse {
+        let (base, index) = parseSelectorIndex(selector)
+        let field = byLabel ? "label" : "identifier"
+        let query = app.descendants(matching: .any).matching(
+            NSPredicate(format: "%K == %@", field, base)
+        )
+        let element = index.map { query.element(boundBy: $0) } ?? query.firstMatch
+
+        var errorMsg: String?
+        var objcError: NSError?
+        let caught = QVXTryCatch({
+            guard element.exists else {
+                errorMsg = "Element with \(field) '\(selector)' not found"
+                return
+            }
+            element.tap()
+            // An empty field reports its placeholder as the value
+            let value = element.value as? String ?? ""
+            if value.isEmpty || value == element.placeholderValue {
+                return
+            }
+            // Select All replaces the text in one edit; without the menu,
+            // delete one character at a time from the end.
+            element.press(forDuration: 1.0)
+            let selectAll = self.app.menuItems["Select All"]
+            if selectAll.waitForExistence(timeout: 1.0) {
+                selectAll.tap()
+                element.typeText(XCUIKeyboardKey.delete.rawValue)
+            } else {
+                element.typeText(String(repeating: XCUIKeyboardKey.delete.rawValue, count: value.count))
+            }
+        }, &objcError)
+        if !caught {
+            let msg = objcError?.localizedDescription ?? "Unknown ObjC exception"
+            return .error(message: "ClearText failed: \(msg)")
+        }
+        if let errorMsg = errorMsg {
+            return .error(message: errorMsg)
+        }
+        return .ok
+    }
+
     // MARK: - Swipe
 
     private func handleSwipe(
@@ -337,6 +490,86 @@ final class CommandHandler {
         return .ok
     }
 
+    // MARK: - Drag
+
+    private func handleDrag(
+        startX: Int32, startY: Int32,
+        endX: Int32, endY: Int32,
+        holdDuration: Double, moveDuration: Double
+    ) -> AgentResponse {
+        let startCoord = app.coordinate(
+            withNormalizedOffset: CGVector(dx: 0, dy: 0)
+        ).withOffset(CGVector(dx: Double(startX), dy: Double(startY)))
+
+        let endCoord = app.coordinate(
+            withNormalizedOffset: CGVector(dx: 0, dy: 0)
+        ).withOffset(CGVector(dx: Double(endX), dy: Double(endY)))
+
+        let dx = Double(endX - startX)
+        let dy = Double(endY - startY)
+        let distance = sqrt(dx * dx + dy * dy)
+        // A zero move duration means "as fast as possible"
+        let velocity = moveDuration > 0
+            ? XCUIGestureVelocity(distance / moveDuration)
+            : .fast
+        var objcError: NSError?
+        let caught = QVXTryCatch({
+            startCoord.press(forDuration: holdDuration, thenDragTo: endCoord,
+                             withVelocity: velocity,
+                             thenHoldForDuration: 0.1)
+        }, &objcError)
+        if !caught {
+            let msg = objcError?.localizedDescription ?? "Unknown ObjC exception"
+            return .error(message: "Drag failed: \(msg)")
+        }
+        return .ok
+    }
+
+    // MARK: - Multi-finger tap
+
+    private func handleMultiTap(fingerCount: UInt8, x: Int32, y: Int32) -> AgentResponse {
+        guard (1...5).contains(fingerCount) else {
+            return .error(message: "Finger count must be between 1 and 5 (got \(fingerCount))")
+        }
+        // XCUICoordinate has no multi-touch tap, so the touches go to the
+        // center of the deepest element under the point instead.
+        let point = CGPoint(x: Double(x), y: Double(y))
+        var objcError: NSError?
+        let caught = QVXTryCatch({
+            let target = self.element(at: point) ?? self.app
+            target.tap(withNumberOfTaps: 1, numberOfTouches: Int(fingerCount))
+        }, &objcError)
+        if !caught {
+            let msg = objcError?.localizedDescription ?? "Unknown ObjC exception"
+            return .error(message: "Multi-finger tap failed: \(msg)")
+        }
+        return .ok
+    }
+
+    /// Resolve the deepest element whose frame contains `point`, matched by
+    /// identifier or label. Returns nil if no such element can be addressed.
+    private func element(at point: CGPoint) -> XCUIElement? {
+        guard let root = try? app.snapshot() else { return nil }
+        var hit: (any XCUIElementSnapshot)?
+        var candidates: [any XCUIElementSnapshot] = [root]
+        while let current = candidates.popLast() {
+            guard current.frame.contains(point) else { continue }
+            hit = current
+            // Later siblings are drawn on top, so search them first
+            candidates = Array(current.children)
+        }
+        guard let snap = hit else { return nil }
+
+        let query = app.descendants(matching: snap.elementType)
+        if !snap.identifier.isEmpty {
+            return query.matching(identifier: snap.identifier).firstMatch
+        }
+        if !snap.label.isEmpty {
+            return query.matching(NSPredicate(format: "label == %@", snap.label)).firstMatch
+        }
+        return nil
+    }
+
     // MARK: - Long press
 
     private func handleLongPress(x: Int32, y: Int32, duration: Double) -> AgentResponse {
@@ -354,6 +587,33 @@ final class CommandHandler {
         return .ok
     }
 
+    private func handleLongPressElement(selector: String, byLabel: Bool, duration: Double) -> AgentResponse {
+        let (base, index) = parseSelectorIndex(selector)
+        let field = byLabel ? "label" : "identifier"
+        let query = app.descendants(matching: .any).matching(
+            NSPredicate(format: "%K == %@", field, base)
+        )
+        let element = index.map { query.element(boundBy: $0) } ?? query.firstMatch
+
+        var errorMsg: String?
+        var objcError: NSError?
+        let caught = QVXTryCatch({
+            guard element.exists else {
+                errorMsg = "Element with \(field) '\(selector)' not found"
+                return
+            }
+            element.press(forDuration: duration)
+        }, &objcError)
+        if !caught {
+            let msg = objcError?.localizedDescription ?? "Unknown ObjC exception"
+            return .error(message: "Long press failed: \(msg)")
+        }
+        if let errorMsg = errorMsg {
+            return .error(message: errorMsg)
+        }
+        return .ok
+    }
+
     // MARK: - Get value
 
     private func handleGetValue(selector: String, byLabel: Bool, elementType: String?, timeoutMs: UInt64?) -> AgentResponse {
@@ -410,7 +670,9 @@ final class CommandHandler {
 
     // MARK: - Dump tree
 
-    private func handleDumpTree() -> AgentResponse {
+    /// Serialize the accessibility tree, trimmed by `filter` or reduced to the
+    /// elements `matcher` selects when either is given.
+    private func handleDumpTree(filter: TreeFilter? = nil, matcher: ElementMatcher? = nil) -> AgentResponse {
         var snapshot: XCUIElementSnapshot?
         var objcError: NSError?
         let caught = QVXTryCatch({
@@ -435,6 +697,19 @@ final class CommandHandler {
             return .tree(json: "[]")
         }
 
+        let selected = matcher.map { $0.collect([tree]) } ?? filter.map { $0.apply([tree]) }
+        if let selected = selected {
+            do {
+                let jsonData = try JSONEncoder().encode(selected)
+                guard let json = String(data: jsonData, encoding: .utf8) else {
+                    return .error(message: "Failed to encode tree as UTF-8")
+                }
+                return .tree(json: json)
+            } catch {
+                return .error(message: "JSON encoding failed: \(error)")
+            }
+        }
+
         do {
             let jsonData = try JSONEncoder().encode(tree)
             guard let json = String(data: jsonData, encoding: .utf8) else {
@@ -469,11 +744,103 @@ final class CommandHandler {
         return .screenshot(data: data)
     }
 
+    /// Capture one JPEG frame, for a screenshot stream or a JPEG screenshot
+    /// request. JPEG keeps frames small enough to push several per second.
+    func captureStreamFrame(quality: UInt8) -> AgentResponse {
+        var jpegData: Data?
+        var objcError: NSError?
+        let caught = QVXTryCatch({
+            let screenshot = XCUIScreen.main.screenshot()
+            jpegData = screenshot.image.jpegData(compressionQuality: CGFloat(quality) / 100)
+        }, &objcError)
+
+        if !caught {
+            let msg = objcError?.localizedDescription ?? "Unknown ObjC exception"
+            return .error(message: "Screenshot failed: \(msg)")
+        }
+
+        guard let data = jpegData else {
+            return .error(message: "Screenshot failed: no JPEG data produced")
+        }
+
+        return .screenshot(data: data)
+    }
+
     // MARK: - Set target app
 
     private func handleSetTarget(bundleId: String) -> AgentResponse {
         app = XCUIApplication(bundleIdentifier: bundleId)
         disableQuiescenceWaiting(app)
+        targetBundleId = bundleId
+        return .ok
+    }
+
+    // MARK: - Get state
+
+    /// Reports the target set by a previous host connection, so a host that
+    /// reconnects can pick it up instead of falling back to springboard.
+    private func handleGetState() -> AgentResponse {
+        let foregroundApp = app.state == .runningForeground ? targetBundleId : nil
+        return .state(targetBundle: targetBundleId, foregroundApp: foregroundApp)
+    }
+
+    // MARK: - Get screen info
+
+    /// Reports the screen size in points, the scale factor and the current
+    /// orientation, so the host can convert element frames to screenshot pixels.
+    private func handleGetScreenInfo() -> AgentResponse {
+        let screen = UIScreen.main
+        var size = screen.bounds.size
+        var objcError: NSError?
+        // The target app's frame follows its interface orientation; the runner's
+        // own screen bounds may not.
+        _ = QVXTryCatch({
+            let frame = self.app.frame
+            if frame.width > 0 && frame.height > 0 {
+                size = frame.size
+            }
+        }, &objcError)
+
+        return .screenInfo(
+            width: Double(size.width),
+            height: Double(size.height),
+            scale: Double(screen.scale),
+            orientation: orientationName(XCUIDevice.shared.orientation)
+        )
+    }
+
+    private func orientationName(_ orientation: UIDeviceOrientation) -> String {
+        switch orientation {
+        case .portrait: return "portrait"
+        case .portraitUpsideDown: return "portrait_upside_down"
+        case .landscapeLeft: return "landscape_left"
+        case .landscapeRight: return "landscape_right"
+        case .faceUp: return "face_up"
+        case .faceDown: return "face_down"
+        default: return "unknown"
+        }
+    }
+
+    // MARK: - Set orientation
+
+    private func handleSetOrientation(name: String) -> AgentResponse {
+        let orientation: UIDeviceOrientation
+        switch name {
+        case "portrait": orientation = .portrait
+        case "portrait_upside_down": orientation = .portraitUpsideDown
+        case "landscape_left": orientation = .landscapeLeft
+        case "landscape_right": orientation = .landscapeRight
+        default: return .error(message: "Unknown orientation: \(name)")
+        }
+
+        var objcError: NSError?
+        let caught = QVXTryCatch({
+            XCUIDevice.shared.orientation = orientation
+        }, &objcError)
+        if !caught {
+            let msg = objcError?.localizedDescription ?? "Unknown ObjC exception"
+            return .error(message: "Set orientation failed: \(msg)")
+        }
         return .ok
     }
 
@@ -588,7 +955,11 @@ final class CommandHandler {
                     frame: serialized.frame,
                     children: serialized.children,
                     role: serialized.role,
-                    hittable: isHittable
+                    hittable: isHittable,
+                    enabled: serialized.enabled,
+                    selected: serialized.selected,
+                    focused: serialized.focused,
+                    placeholder: serialized.placeholder
                 )
                 do {
                     let jsonData = try JSONEncoder().encode(serialized)
@@ -617,7 +988,11 @@ final class CommandHandler {
                     frame: frameJSON,
                     children: [],
                     role: nil,
-                    hittable: isHittable
+                    hittable: isHittable,
+                    enabled: element.isEnabled,
+                    selected: element.isSelected,
+                    focused: element.hasFocus,
+                    placeholder: element.placeholderValue.flatMap { $0.isEmpty ? nil : $0 }
                 )
                 do {
                     let jsonData = try JSONEncoder().encode(minimal)
@@ -639,6 +1014,20 @@ final class CommandHandler {
         return result ?? .error(message: "FindElement produced no result")
     }
 
+    // MARK: - Batch
+
+    /// Run the requests in order, stopping after the first error so the host
+    /// can tell which request failed from the number of responses.
+    private func handleBatch(_ requests: [AgentRequest]) -> AgentResponse {
+        var responses: [AgentResponse] = []
+        for request in requests {
+            let response = handle(request)
+            responses.append(response)
+            if case .error = response { break }
+        }
+        return .batch(responses: responses)
+    }
+
     // MARK: - Poll helper
 
     /// Poll until an element matching `query` satisfies `action`, with timeout.
@@ -714,7 +1103,11 @@ final class CommandHandler {
             frame: frameJSON,
             children: children,
             role: nil,
-            hittable: nil
+            hittable: nil,
+            enabled: snapshot.isEnabled,
+            selected: snapshot.isSelected,
+            focused: snapshot.hasFocus,
+            placeholder: snapshot.placeholderValue.flatMap { $0.isEmpty ? nil : $0 }
         )
     }
 
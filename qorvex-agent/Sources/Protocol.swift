@@ -25,6 +25,20 @@ enum OpCode: UInt8 {
     case findElement = 0x13
     case getTargetInfo = 0x14
     case deviceUdid = 0x16
+    case dumpTreeFiltered = 0x17
+    case getState   = 0x18
+    case getScreenInfo = 0x19
+    case setOrientation = 0x1A
+    case drag       = 0x1B
+    case multiTap   = 0x1C
+    case paste      = 0x1D
+    case clearText  = 0x1E
+    case findAll    = 0x1F
+    case batch      = 0x20
+    case streamScreenshots = 0x21
+    case stopStream = 0x22
+    case sendKey    = 0x23
+    case longPressElement = 0x25
     case error      = 0x99
     case response   = 0xA0
 }
@@ -40,6 +54,23 @@ enum ResponseType: UInt8 {
     case value      = 0x04
     case element    = 0x05
     case targetInfo = 0x06
+    case state      = 0x07
+    case screenInfo = 0x08
+    case batch      = 0x09
+}
+
+// MARK: - Special keys
+
+/// Keys pressed by `sendKey`, matching the Rust `SpecialKey` enum.
+enum SpecialKey: UInt8 {
+    case returnKey = 0x01
+    case tab       = 0x02
+    case delete    = 0x03
+    case escape    = 0x04
+    case up        = 0x05
+    case down      = 0x06
+    case left      = 0x07
+    case right     = 0x08
 }
 
 // MARK: - Request
@@ -55,12 +86,27 @@ enum AgentRequest {
     case swipe(startX: Int32, startY: Int32, endX: Int32, endY: Int32, duration: Double?)
     case getValue(selector: String, byLabel: Bool, elementType: String?, timeoutMs: UInt64?)
     case longPress(x: Int32, y: Int32, duration: Double)
+    case longPressElement(selector: String, byLabel: Bool, duration: Double)
     case dumpTree
-    case screenshot
+    /// PNG when `jpegQuality` is nil, otherwise JPEG at that quality.
+    case screenshot(jpegQuality: UInt8?)
     case setTarget(bundleId: String)
     case findElement(selector: String, byLabel: Bool, elementType: String?)
     case getTargetInfo
     case deviceUdid
+    case dumpTreeFiltered(types: [String], visibleOnly: Bool, maxDepth: UInt32?)
+    case getState
+    case getScreenInfo
+    case setOrientation(name: String)
+    case drag(startX: Int32, startY: Int32, endX: Int32, endY: Int32, holdDuration: Double, moveDuration: Double)
+    case multiTap(fingerCount: UInt8, x: Int32, y: Int32)
+    case paste
+    case clearText(selector: String, byLabel: Bool)
+    case findAll(selector: String, byLabel: Bool, elementType: String?)
+    case batch(requests: [AgentRequest])
+    case streamScreenshots(fps: UInt8, quality: UInt8)
+    case stopStream
+    case sendKey(key: SpecialKey)
 }
 
 // MARK: - Response
@@ -74,6 +120,9 @@ enum AgentResponse {
     case value(String?)
     case element(json: String)
     case targetInfo(json: String)
+    case state(targetBundle: String?, foregroundApp: String?)
+    case screenInfo(width: Double, height: Double, scale: Double, orientation: String)
+    case batch(responses: [AgentResponse])
 }
 
 // MARK: - Protocol errors
@@ -169,6 +218,16 @@ final class ProtocolCursor {
         return bytes.withUnsafeBytes { $0.loadUnaligned(as: UInt64.self).littleEndian }
     }
 
+    /// Read length-prefixed raw bytes: [u32 LE byte_count][bytes].
+    func readBytes() throws -> Data {
+        let byteCount = Int(try readUInt32())
+        guard remaining >= byteCount else { throw ProtocolError.insufficientData }
+        let start = data.startIndex + position
+        let slice = data[start..<start + byteCount]
+        position += byteCount
+        return Data(slice)
+    }
+
     /// Read an optional string: [u8 flag] then optional [string].
     func readOptionalString() throws -> String? {
         let flag = try readUInt8()
@@ -247,11 +306,28 @@ func decodeRequest(from data: Data) throws -> AgentRequest {
         let duration = try cursor.readFloat64()
         return .longPress(x: x, y: y, duration: duration)
 
+    case .longPressElement:
+        let selector = try cursor.readString()
+        let byLabel = try cursor.readBool()
+        let duration = try cursor.readFloat64()
+        return .longPressElement(selector: selector, byLabel: byLabel, duration: duration)
+
     case .dumpTree:
         return .dumpTree
 
     case .screenshot:
-        return .screenshot
+        // No payload (older hosts) means PNG; otherwise a format tag, then the
+        // JPEG quality for tag 0x01.
+        guard cursor.remaining > 0 else { return .screenshot(jpegQuality: nil) }
+        let format = try cursor.readUInt8()
+        switch format {
+        case 0x00:
+            return .screenshot(jpegQuality: nil)
+        case 0x01:
+            return .screenshot(jpegQuality: try cursor.readUInt8())
+        default:
+            throw ProtocolError.invalidPayload("unknown screenshot format: \(format)")
+        }
 
     case .setTarget:
         let bundleId = try cursor.readString()
@@ -269,6 +345,85 @@ func decodeRequest(from data: Data) throws -> AgentRequest {
     case .deviceUdid:
         return .deviceUdid
 
+    case .dumpTreeFiltered:
+        let count = Int(try cursor.readUInt32())
+        var types: [String] = []
+        for _ in 0..<count {
+            types.append(try cursor.readString())
+        }
+        let visibleOnly = try cursor.readBool()
+        let hasMaxDepth = try cursor.readBool()
+        let maxDepth: UInt32? = hasMaxDepth ? try cursor.readUInt32() : nil
+        return .dumpTreeFiltered(types: types, visibleOnly: visibleOnly, maxDepth: maxDepth)
+
+    case .getState:
+        return .getState
+
+    case .getScreenInfo:
+        return .getScreenInfo
+
+    case .setOrientation:
+        let name = try cursor.readString()
+        return .setOrientation(name: name)
+
+    case .drag:
+        let startX = try cursor.readInt32()
+        let startY = try cursor.readInt32()
+        let endX = try cursor.readInt32()
+        let endY = try cursor.readInt32()
+        let holdDuration = try cursor.readFloat64()
+        let moveDuration = try cursor.readFloat64()
+        return .drag(startX: startX, startY: startY, endX: endX, endY: endY,
+                     holdDuration: holdDuration, moveDuration: moveDuration)
+
+    case .multiTap:
+        let fingerCount = try cursor.readUInt8()
+        let x = try cursor.readInt32()
+        let y = try cursor.readInt32()
+        return .multiTap(fingerCount: fingerCount, x: x, y: y)
+
+    case .paste:
+        return .paste
+
+    case .clearText:
+        let selector = try cursor.readString()
+        let byLabel = try cursor.readBool()
+        return .clearText(selector: selector, byLabel: byLabel)
+
+    case .findAll:
+        let selector = try cursor.readString()
+        let byLabel = try cursor.readBool()
+        let elementType = try cursor.readOptionalString()
+        return .findAll(selector: selector, byLabel: byLabel, elementType: elementType)
+
+    case .batch:
+        // Each request is a full frame: [u32 LE length][opcode][payload].
+        let count = try cursor.readUInt32()
+        var requests: [AgentRequest] = []
+        for _ in 0..<count {
+            let request = try decodeRequest(from: try cursor.readBytes())
+            if case .batch = request {
+                throw ProtocolError.invalidPayload("nested batch requests are not supported")
+            }
+            requests.append(request)
+        }
+        return .batch(requests: requests)
+
+    case .streamScreenshots:
+        let fps = try cursor.readUInt8()
+        let quality = try cursor.readUInt8()
+        return .streamScreenshots(fps: fps, quality: quality)
+
+    case .stopStream:
+        return .stopStream
+
+    case .sendKey:
+        let raw = try cursor.readUInt8()
+        guard let key = SpecialKey(rawValue: raw) else {
+            throw ProtocolError.invalidPayload(String(format: "unknown key: 0x%02X", raw))
+        }
+        return .sendKey(key: key)
+
     case .error, .response:
         throw ProtocolError.invalidPayload(
             String(format: "opcode 0x%02X is not a valid request opcode", rawOpCode)
@@ -311,6 +466,26 @@ func encodeResponse(_ response: AgentResponse) -> Data {
     case .targetInfo(let json):
         payload.append(ResponseType.targetInfo.rawValue)
         writeString(&payload, json)
+
+    case .state(let targetBundle, let foregroundApp):
+        payload.append(ResponseType.state.rawValue)
+        writeOptionalString(&payload, targetBundle)
+        writeOptionalString(&payload, foregroundApp)
+
+    case .screenInfo(let width, let height, let scale, let orientation):
+        payload.append(ResponseType.screenInfo.rawValue)
+        writeFloat64(&payload, width)
+        writeFloat64(&payload, height)
+        writeFloat64(&payload, scale)
+        writeString(&payload, orientation)
+
+    case .batch(let responses):
+        payload.append(ResponseType.batch.rawValue)
+        var count = UInt32(responses.count).littleEndian
+        payload.append(Data(bytes: &count, count: 4))
+        for response in responses {
+            payload.append(encodeResponse(response))
+        }
     }
 
     return encodeFrame(payload)
@@ -348,6 +523,12 @@ private func writeBytes(_ buf: inout Data, _ data: Data) {
     buf.append(data)
 }
 
+/// Write a Double as 8 LE bytes.
+private func writeFloat64(_ buf: inout Data, _ value: Double) {
+    var bits = value.bitPattern.littleEndian
+    buf.append(Data(bytes: &bits, count: 8))
+}
+
 /// Write an optional string: [u8 flag] then optional length-prefixed string.
 private func writeOptionalString(_ buf: inout Data, _ opt: String?) {
     if let s = opt {
@@ -14,6 +14,8 @@ import Foundation
 /// - `frame` -> frame
 /// - `children` -> children
 /// - `role` -> role
+/// - `hittable`, `enabled`, `selected`, `focused` -> element state
+/// - `placeholder` -> placeholder
 struct UIElementJSON: Codable {
     let AXUniqueId: String?
     let AXLabel: String?
@@ -23,6 +25,10 @@ struct UIElementJSON: Codable {
     let children: [UIElementJSON]
     let role: String?
     let hittable: Bool?
+    let enabled: Bool?
+    let selected: Bool?
+    let focused: Bool?
+    let placeholder: String?
 }
 
 /// JSON representation of an element's frame (position and size in screen points).
@@ -32,3 +38,99 @@ struct FrameJSON: Codable {
     let width: Double
     let height: Double
 }
+
+/// Type/visibility/depth filter applied to a serialized tree before it is sent,
+/// matching `ElementFilter` / `filter_elements` in qorvex-core/src/driver.rs.
+///
+/// Elements deeper than `maxDepth` are dropped with their subtrees. An element
+/// that fails the type or visibility check is removed and its matching
+/// descendants take its place.
+struct TreeFilter {
+    /// Element type names to keep; empty keeps every type.
+    let types: Set<String>
+    /// Keep only elements with a frame of non-zero width and height.
+    let visibleOnly: Bool
+    /// Maximum depth to descend to; 0 keeps only the top-level elements.
+    let maxDepth: UInt32?
+
+    func matches(_ element: UIElementJSON) -> Bool {
+        if !types.isEmpty {
+            guard let type = element.type, types.contains(type) else { return false }
+        }
+        if visibleOnly {
+            guard let frame = element.frame, frame.width > 0, frame.height > 0 else { return false }
+        }
+        return true
+    }
+
+    func apply(_ elements: [UIElementJSON], depth: UInt32 = 0) -> [UIElementJSON] {
+        var result: [UIElementJSON] = []
+        for element in elements {
+            let children = maxDepth.map { depth < $0 } ?? true
+                ? apply(element.children, depth: depth + 1)
+                : []
+            if matches(element) {
+                result.append(UIElementJSON(
+                    AXUniqueId: element.AXUniqueId,
+                    AXLabel: element.AXLabel,
+                    AXValue: element.AXValue,
+                    type: element.type,
+                    frame: element.frame,
+                    children: children,
+                    role: element.role,
+                    hittable: element.hittable,
+                    enabled: element.enabled,
+                    selected: element.selected,
+                    focused: element.focused,
+                    placeholder: element.placeholder
+                ))
+            } else {
+                result.append(contentsOf: children)
+            }
+        }
+        return result
+    }
+}
+
+/// Selector search over a serialized tree, matching `find_all_elements` in
+/// qorvex-core/src/driver.rs.
+///
+/// Collects every matching element in depth-first order. An empty selector
+/// matches any element; `*` and `?` act as wildcards; a trailing `[N]` keeps
+/// only the Nth match.
+struct ElementMatcher {
+    let selector: String
+    let byLabel: Bool
+    let elementType: String?
+
+    func collect(_ elements: [UIElementJSON]) -> [UIElementJSON] {
+        let (base, index) = parseSelectorIndex(selector)
+        var matches: [UIElementJSON] = []
+        collect(elements, base: base, into: &matches)
+        guard let n = index else { return matches }
+        return n < matches.count ? [matches[n]] : []
+    }
+
+    private func collect(_ elements: [UIElementJSON], base: String, into matches: inout [UIElementJSON]) {
+        for element in elements {
+            if isMatch(element, base: base) {
+                matches.append(element)
+            }
+            collect(element.children, base: base, into: &matches)
+        }
+    }
+
+    private func isMatch(_ element: UIElementJSON, base: String) -> Bool {
+        if let elementType = elementType, element.type != elementType {
+            return false
+        }
+        if base.isEmpty {
+            return true
+        }
+        guard let text = byLabel ? element.AXLabel : element.AXUniqueId else { return false }
+        if base.contains("*") || base.contains("?") {
+            return NSPredicate(format: "SELF LIKE %@", base).evaluate(with: text)
+        }
+        return text == base
+    }
+}
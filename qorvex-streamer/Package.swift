@@ -10,6 +10,11 @@ let package = Package(
         .executableTarget(
             name: "qorvex-streamer",
             path: "Sources"
+        ),
+        .testTarget(
+            name: "qorvex-streamerTests",
+            dependencies: ["qorvex-streamer"],
+            path: "Tests"
         )
     ]
 )
@@ -0,0 +1,94 @@
+// AdaptiveQuality.swift
+// Picks the JPEG quality and frame rate of the live stream from socket
+// backpressure: steps them down while the consumer falls behind and back up
+// once it keeps pace.
+
+import Foundation
+
+struct AdaptiveQuality {
+    /// Allowed ranges. Adaptation starts at the upper bounds.
+    struct Bounds: Equatable {
+        var quality: ClosedRange<Int>
+        var fps: ClosedRange<Int>
+    }
+
+    static let qualityStep = 10
+    static let fpsStep = 5
+
+    let bounds: Bounds
+    /// Frames per evaluation window.
+    let windowSize: Int
+    /// Clean windows in a row before stepping back up.
+    let recoverAfter: Int
+
+    private(set) var quality: Int
+    private(set) var fps: Int
+
+    private var frames = 0
+    private var skipped = 0
+    private var cleanWindows = 0
+
+    init(bounds: Bounds, windowSize: Int = 15, recoverAfter: Int = 2) {
+        self.bounds = bounds
+        self.windowSize = windowSize
+        self.recoverAfter = recoverAfter
+        self.quality = bounds.quality.upperBound
+        self.fps = bounds.fps.upperBound
+    }
+
+    /// Records one captured frame; `skipped` when it was dropped because the
+    /// previous write had not finished. Returns true when `quality` or `fps`
+    /// changed.
+    mutating func record(skipped frameSkipped: Bool) -> Bool {
+        frames += 1
+        if frameSkipped { skipped += 1 }
+        guard frames >= windowSize else { return false }
+
+        // More than a quarter skipped is lag; anything between that and a
+        // clean window holds the current settings.
+        let lagging = skipped * 4 > windowSize
+        let clean = skipped == 0
+        frames = 0
+        skipped = 0
+
+        if lagging {
+            cleanWindows = 0
+            return stepDown()
+        }
+        guard clean else {
+            cleanWindows = 0
+            return false
+        }
+        cleanWindows += 1
+        guard cleanWindows >= recoverAfter else { return false }
+        cleanWindows = 0
+        return stepUp()
+    }
+
+    /// Quality goes first; the frame rate only drops once quality is at its
+    /// floor, since fewer frames make the view feel slower.
+    private mutating func stepDown() -> Bool {
+        if quality > bounds.quality.lowerBound {
+            quality = max(quality - Self.qualityStep, bounds.quality.lowerBound)
+            return true
+        }
+        if fps > bounds.fps.lowerBound {
+            fps = max(fps - Self.fpsStep, bounds.fps.lowerBound)
+            return true
+        }
+        return false
+    }
+
+    /// Undoes `stepDown` in reverse order.
+    private mutating func stepUp() -> Bool {
+        if fps < bounds.fps.upperBound {
+            fps = min(fps + Self.fpsStep, bounds.fps.upperBound)
+            return true
+        }
+        if quality < bounds.quality.upperBound {
+            quality = min(quality + Self.qualityStep, bounds.quality.upperBound)
+            return true
+        }
+        return false
+    }
+}
@@ -0,0 +1,115 @@
+// FfmpegWriter.swift
+// Encodes JPEG frames to an MP4 file by piping them into an ffmpeg subprocess.
+//
+// Frame format on ffmpeg's stdin: complete JPEG images written back to back,
+// with no length prefix or separator (unlike the socket framing). ffmpeg's
+// `image2pipe` demuxer splits the stream on JPEG markers and `-framerate`
+// stamps each image 1/fps seconds after the previous one, so frames skipped
+// under backpressure shorten the video instead of stalling it.
+
+import Foundation
+
+enum FfmpegWriterError: Error, CustomStringConvertible {
+    case launchFailed(path: String, underlying: Error)
+
+    var description: String {
+        switch self {
+        case .launchFailed(let path, let underlying):
+            return "Failed to launch \(path): \(underlying)"
+        }
+    }
+}
+
+final class FfmpegWriter: FrameSink {
+    /// Checked after `$PATH`, which is minimal when launched from a GUI or daemon.
+    static let fallbackDirectories = ["/opt/homebrew/bin", "/usr/local/bin"]
+
+    private let process: Process
+    private let stdin: FileHandle
+    private let lock = NSLock()
+    private var closed = false
+
+    /// Locate an executable `ffmpeg` on `path` or in `fallbackDirectories`.
+    static func locate(
+        path: String? = ProcessInfo.processInfo.environment["PATH"],
+        fallbackDirectories: [String] = FfmpegWriter.fallbackDirectories
+    ) -> URL? {
+        let directories = (path ?? "").split(separator: ":").map(String.init) + fallbackDirectories
+        for directory in directories where !directory.isEmpty {
+            let candidate = URL(fileURLWithPath: directory).appendingPathComponent("ffmpeg")
+            if FileManager.default.isExecutableFile(atPath: candidate.path) {
+                return candidate
+            }
+        }
+        return nil
+    }
+
+    /// ffmpeg arguments that read a JPEG stream from stdin and write an H.264 MP4.
+    static func arguments(outputPath: String, fps: Int) -> [String] {
+        return [
+            "-hide_banner", "-loglevel", "error",
+            "-f", "image2pipe", "-c:v", "mjpeg", "-framerate", String(fps), "-i", "-",
+            // libx264 with yuv420p needs even dimensions
+            "-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+            "-c:v", "libx264", "-pix_fmt", "yuv420p", "-movflags", "+faststart",
+            "-y", outputPath,
+        ]
+    }
+
+    /// Launch `ffmpeg` encoding to `outputPath`.
+    convenience init(ffmpeg: URL, outputPath: String, fps: Int) throws {
+        try self.init(executable: ffmpeg, arguments: FfmpegWriter.arguments(outputPath: outputPath, fps: fps))
+    }
+
+    /// Launch `executable` with `arguments`, feeding frames to its stdin.
+    init(executable: URL, arguments: [String]) throws {
+        let pipe = Pipe()
+        let process = Process()
+        process.executableURL = executable
+        process.arguments = arguments
+        process.standardInput = pipe
+        process.standardOutput = FileHandle.nullDevice
+
+        do {
+            try process.run()
+        } catch {
+            throw FfmpegWriterError.launchFailed(path: executable.path, underlying: error)
+        }
+        self.process = process
+        self.stdin = pipe.fileHandleForWriting
+    }
+
+    /// Exit status of the encoder; only meaningful after `close()`.
+    var terminationStatus: Int32 {
+        return process.terminationStatus
+    }
+
+    func writeFrame(_ data: Data, completion: @escaping () -> Void) {
+        lock.lock()
+        defer {
+            lock.unlock()
+            completion()
+        }
+        guard !closed else { return }
+
+        do {
+            try stdin.write(contentsOf: data)
+        } catch {
+            NSLog("[qorvex-streamer] ffmpeg write failed: %@", "\(error)")
+            closed = true
+        }
+    }
+
+    /// Close ffmpeg's stdin and wait for it to finalize the file.
+    func close() {
+        lock.lock()
+        let wasClosed = closed
+        closed = true
+        lock.unlock()
+
+        if !wasClosed {
+            try? stdin.close()
+        }
+        process.waitUntilExit()
+    }
+}
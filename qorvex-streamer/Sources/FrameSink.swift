@@ -0,0 +1,32 @@
+// FrameSink.swift
+// Common interface for destinations of encoded JPEG frames.
+
+import Foundation
+
+/// Capture metadata that travels with a frame.
+struct FrameInfo: Equatable {
+    /// Number of the frame among all captured ones; frames skipped under
+    /// backpressure leave gaps.
+    let sequence: UInt64
+    /// Capture time in nanoseconds on the host's monotonic clock.
+    let timestampNs: UInt64
+}
+
+/// A destination for the JPEG frames produced by `FrameStreamer`.
+protocol FrameSink: AnyObject {
+    /// Write one JPEG frame. Calls `completion` once the sink can take the next one.
+    func writeFrame(_ data: Data, completion: @escaping () -> Void)
+
+    /// Write one JPEG frame with its capture metadata. Sinks that have no use
+    /// for the metadata get the plain `writeFrame`.
+    func writeFrame(_ data: Data, info: FrameInfo, completion: @escaping () -> Void)
+
+    /// Flush and release the sink. Safe to call more than once.
+    func close()
+}
+
+extension FrameSink {
+    func writeFrame(_ data: Data, info: FrameInfo, completion: @escaping () -> Void) {
+        writeFrame(data, completion: completion)
+    }
+}
@@ -1,6 +1,8 @@
 // FrameStreamer.swift
 // Captures frames from a Simulator window via ScreenCaptureKit,
-// encodes them as JPEG, and writes them to a SocketWriter.
+// encodes them as JPEG, and writes them to one or more FrameSinks.
+// With an AdaptiveQuality, frames skipped under backpressure lower the JPEG
+// quality and frame rate until the sinks keep up.
 
 import Foundation
 import ScreenCaptureKit
@@ -11,19 +13,32 @@ import CoreGraphics
 final class FrameStreamer: NSObject, SCStreamOutput, SCStreamDelegate {
     private let window: SCWindow
     private let display: SCDisplay
-    private let fps: Int
-    private let quality: CGFloat
-    private let socketWriter: SocketWriter
+    private var fps: Int
+    private var quality: CGFloat
+    private var adaptive: AdaptiveQuality?
+    private let sinks: [FrameSink]
     private var stream: SCStream?
+    private var configuration: SCStreamConfiguration?
     private var isWriting = false // Backpressure flag
+    private var sequence: UInt64 = 0 // Counts skipped frames too
     private let writeQueue = DispatchQueue(label: "com.qorvex.streamer.write")
 
-    init(window: SCWindow, display: SCDisplay, fps: Int, quality: Int, socketWriter: SocketWriter) {
+    /// Captures at `fps` and `quality`, or at the upper bounds of `adaptive`
+    /// when given.
+    init(
+        window: SCWindow,
+        display: SCDisplay,
+        fps: Int,
+        quality: Int,
+        adaptive: AdaptiveQuality? = nil,
+        sinks: [FrameSink]
+    ) {
         self.window = window
         self.display = display
-        self.fps = fps
-        self.quality = CGFloat(quality) / 100.0
-        self.socketWriter = socketWriter
+        self.fps = adaptive?.fps ?? fps
+        self.quality = CGFloat(adaptive?.quality ?? quality) / 100.0
+        self.adaptive = adaptive
+        self.sinks = sinks
         super.init()
     }
 
@@ -42,6 +57,7 @@ final class FrameStreamer: NSObject, SCStreamOutput, SCStreamDelegate {
 
         let stream = SCStream(filter: filter, configuration: config, delegate: self)
         self.stream = stream
+        self.configuration = config
 
         try stream.addStreamOutput(self, type: .screen, sampleHandlerQueue: writeQueue)
         try await stream.startCapture()
@@ -61,14 +77,24 @@ final class FrameStreamer: NSObject, SCStreamOutput, SCStreamDelegate {
     func stream(_ stream: SCStream, didOutputSampleBuffer sampleBuffer: CMSampleBuffer, of type: SCStreamOutputType) {
         guard type == .screen else { return }
 
+        // Idle frames carry no image and are not counted.
+        guard let pixelBuffer = CMSampleBufferGetImageBuffer(sampleBuffer) else { return }
+        sequence += 1
+        let info = FrameInfo(sequence: sequence, timestampNs: captureTimeNs(sampleBuffer))
+
         // Backpressure: skip if previous write hasn't completed.
+        adapt(skipped: isWriting)
         if isWriting { return }
 
-        guard let pixelBuffer = CMSampleBufferGetImageBuffer(sampleBuffer) else { return }
         guard let jpegData = encodeJPEG(pixelBuffer: pixelBuffer) else { return }
 
         isWriting = true
-        socketWriter.writeFrame(jpegData) { [weak self] in
+        let group = DispatchGroup()
+        for sink in sinks {
+            group.enter()
+            sink.writeFrame(jpegData, info: info) { group.leave() }
+        }
+        group.notify(queue: writeQueue) { [weak self] in
             self?.isWriting = false
         }
     }
@@ -77,10 +103,43 @@ final class FrameStreamer: NSObject, SCStreamOutput, SCStreamDelegate {
 
     func stream(_ stream: SCStream, didStopWithError error: Error) {
         NSLog("[qorvex-streamer] Stream stopped with error: %@", "\(error)")
-        socketWriter.close()
+        sinks.forEach { $0.close() }
         exit(1)
     }
 
+    // MARK: - Adaptive quality
+
+    /// Feeds the backpressure signal to `adaptive` and applies any change:
+    /// quality from the next encoded frame, the frame rate by reconfiguring
+    /// the capture.
+    private func adapt(skipped: Bool) {
+        guard adaptive?.record(skipped: skipped) == true, let adaptive else { return }
+
+        quality = CGFloat(adaptive.quality) / 100.0
+        NSLog("[qorvex-streamer] Adapted to %d fps, quality %d", adaptive.fps, adaptive.quality)
+        guard adaptive.fps != fps else { return }
+        fps = adaptive.fps
+
+        guard let stream, let configuration else { return }
+        configuration.minimumFrameInterval = CMTime(value: 1, timescale: CMTimeScale(fps))
+        stream.updateConfiguration(configuration) { error in
+            if let error = error {
+                NSLog("[qorvex-streamer] Error changing frame rate: %@", "\(error)")
+            }
+        }
+    }
+
+    // MARK: - Frame metadata
+
+    /// The sample's presentation time, which ScreenCaptureKit stamps on the
+    /// host clock, in nanoseconds; the current uptime if it has none.
+    private func captureTimeNs(_ sampleBuffer: CMSampleBuffer) -> UInt64 {
+        let pts = CMSampleBufferGetPresentationTimeStamp(sampleBuffer)
+        guard pts.isValid else { return DispatchTime.now().uptimeNanoseconds }
+        let ns = CMTimeConvertScale(pts, timescale: 1_000_000_000, method: .default).value
+        return UInt64(max(ns, 0))
+    }
+
     // MARK: - JPEG encoding
 
     private func encodeJPEG(pixelBuffer: CVPixelBuffer) -> Data? {
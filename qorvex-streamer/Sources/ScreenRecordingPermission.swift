@@ -0,0 +1,26 @@
+// ScreenRecordingPermission.swift
+// Checks for Screen Recording permission before capture starts, so a missing
+// permission exits with guidance instead of failing inside ScreenCaptureKit.
+
+import CoreGraphics
+
+enum ScreenRecordingPermission {
+
+    /// Exit code when the permission is missing; qorvex-live looks for it.
+    static let deniedExitCode: Int32 = 2
+
+    static let guidance = "Screen Recording permission required. Grant it to your terminal in System Settings > Privacy & Security > Screen & System Audio Recording, then restart the terminal."
+
+    /// Whether capture is allowed. When it is not, `request` runs once so
+    /// macOS can show its prompt and list the terminal in System Settings.
+    static func preflight(
+        query: () -> Bool = CGPreflightScreenCaptureAccess,
+        request: () -> Bool = CGRequestScreenCaptureAccess
+    ) -> Bool {
+        if query() {
+            return true
+        }
+        _ = request()
+        return false
+    }
+}
@@ -1,6 +1,11 @@
 // SocketWriter.swift
 // Manages a Unix domain socket server that accepts a single client
 // and writes length-prefixed JPEG frames using 4-byte LE u32 framing.
+//
+// Each connection opens with a stream header, "QVXS" and a version byte (2),
+// and every frame carries its sequence number and capture time:
+// [u32 LE JPEG length][u64 LE sequence][u64 LE timestamp ns][JPEG].
+// Readers that find no header fall back to plain length-prefixed frames.
 
 import Foundation
 
@@ -24,7 +29,10 @@ enum SocketWriterError: Error, CustomStringConvertible {
     }
 }
 
-final class SocketWriter {
+final class SocketWriter: FrameSink {
+    /// Sent first on every connection so readers know frames carry metadata.
+    static let streamHeader = Data("QVXS".utf8) + Data([2])
+
     private let socketPath: String
     private var serverFd: Int32 = -1
     private var clientFd: Int32 = -1
@@ -87,6 +95,11 @@ final class SocketWriter {
         }
 
         if fd >= 0 {
+            guard writeAll(fd: fd, data: SocketWriter.streamHeader) else {
+                NSLog("[qorvex-streamer] Failed to send stream header")
+                Darwin.close(fd)
+                return
+            }
             lock.lock()
             clientFd = fd
             lock.unlock()
@@ -95,10 +108,26 @@ final class SocketWriter {
         }
     }
 
-    /// Write a frame with 4-byte little-endian length prefix followed by JPEG data.
-    /// Calls completion when done (or on error). On broken pipe, closes the client
-    /// and waits for a new connection.
+    /// The frame header: JPEG length, sequence and capture time, little-endian.
+    static func frameHeader(length: Int, info: FrameInfo) -> Data {
+        var header = Data(capacity: 20)
+        withUnsafeBytes(of: UInt32(length).littleEndian) { header.append(contentsOf: $0) }
+        withUnsafeBytes(of: info.sequence.littleEndian) { header.append(contentsOf: $0) }
+        withUnsafeBytes(of: info.timestampNs.littleEndian) { header.append(contentsOf: $0) }
+        return header
+    }
+
+    /// Write a frame without capture metadata, stamped with the current uptime
+    /// and sequence 0.
     func writeFrame(_ data: Data, completion: @escaping () -> Void) {
+        let info = FrameInfo(sequence: 0, timestampNs: DispatchTime.now().uptimeNanoseconds)
+        writeFrame(data, info: info, completion: completion)
+    }
+
+    /// Write a frame header followed by JPEG data. Calls completion when done
+    /// (or on error). On broken pipe, closes the client and waits for a new
+    /// connection.
+    func writeFrame(_ data: Data, info: FrameInfo, completion: @escaping () -> Void) {
         lock.lock()
         let fd = clientFd
         lock.unlock()
@@ -108,10 +137,7 @@ final class SocketWriter {
             return
         }
 
-        // Build the length header (4-byte LE u32).
-        var length = UInt32(data.count).littleEndian
-        let headerData = Data(bytes: &length, count: 4)
-
+        let headerData = SocketWriter.frameHeader(length: data.count, info: info)
         let success = writeAll(fd: fd, data: headerData) && writeAll(fd: fd, data: data)
 
         if !success {
@@ -17,7 +17,7 @@ enum WindowFinderError: Error, CustomStringConvertible {
         case .noDisplayFound:
             return "No display found containing the Simulator window."
         case .sharingNotPermitted:
-            return "Screen capture permission denied. Grant access in System Settings > Privacy & Security > Screen & System Audio Recording."
+            return ScreenRecordingPermission.guidance
         }
     }
 }
@@ -1,7 +1,8 @@
 // main.swift
 // CLI entry point for qorvex-streamer.
 // Captures a Simulator window via ScreenCaptureKit and streams JPEG frames
-// over a Unix domain socket using length-prefixed binary framing.
+// over a Unix domain socket using length-prefixed binary framing, and/or
+// encodes them to an MP4 file through ffmpeg.
 
 import Foundation
 import ScreenCaptureKit
@@ -14,24 +15,44 @@ let _ = NSApplication.shared
 
 func printUsage() -> Never {
     fputs("""
-    Usage: qorvex-streamer --socket-path <path> --udid <udid> [--fps <n>] [--quality <n>]
+    Usage: qorvex-streamer --udid <udid> [--socket-path <path>] [--output <file.mp4>]
+                           [--duration <seconds>] [--fps <n>] [--quality <n>]
+                           [--min-fps <n>] [--min-quality <n>]
 
     Options:
-      --socket-path  Path for the Unix domain socket (required)
+      --socket-path  Path for the Unix domain socket
+      --output       Encode frames to this MP4 file (requires ffmpeg)
+      --duration     Stop after this many seconds of capture
       --udid         Simulator UDID (required)
       --fps          Frames per second (default: 15)
       --quality      JPEG quality 0-100 (default: 70)
+      --min-fps      Lowest frame rate when the socket client lags (default: 5)
+      --min-quality  Lowest JPEG quality when the socket client lags (default: 30)
+
+    At least one of --socket-path and --output is required.
+    When the socket client falls behind, quality and then frame rate step down
+    toward the minimums and recover once it keeps up; --fps and --quality are
+    the upper bounds. Set a minimum equal to its bound to keep it fixed. With
+    --output, frames are captured at fixed settings so the recording keeps its
+    timing and quality.
 
     """, stderr)
     exit(1)
 }
 
-func parseArgs() -> (socketPath: String, udid: String, fps: Int, quality: Int) {
+func parseArgs() -> (
+    socketPath: String?, outputPath: String?, duration: Double?, udid: String, fps: Int, quality: Int,
+    minFps: Int, minQuality: Int
+) {
     let args = CommandLine.arguments
     var socketPath: String?
+    var outputPath: String?
+    var duration: Double?
     var udid: String?
     var fps = 15
     var quality = 70
+    var minFps: Int?
+    var minQuality: Int?
 
     var i = 1
     while i < args.count {
@@ -40,6 +61,14 @@ func parseArgs() -> (socketPath: String, udid: String, fps: Int, quality: Int) {
             i += 1
             guard i < args.count else { printUsage() }
             socketPath = args[i]
+        case "--output":
+            i += 1
+            guard i < args.count else { printUsage() }
+            outputPath = args[i]
+        case "--duration":
+            i += 1
+            guard i < args.count, let v = Double(args[i]), v > 0 else { printUsage() }
+            duration = v
         case "--udid":
             i += 1
             guard i < args.count else { printUsage() }
@@ -52,6 +81,14 @@ func parseArgs() -> (socketPath: String, udid: String, fps: Int, quality: Int) {
             i += 1
             guard i < args.count, let v = Int(args[i]), v >= 0, v <= 100 else { printUsage() }
             quality = v
+        case "--min-fps":
+            i += 1
+            guard i < args.count, let v = Int(args[i]), v > 0 else { printUsage() }
+            minFps = v
+        case "--min-quality":
+            i += 1
+            guard i < args.count, let v = Int(args[i]), v >= 0, v <= 100 else { printUsage() }
+            minQuality = v
         default:
             fputs("[qorvex-streamer] Unknown argument: \(args[i])\n", stderr)
             printUsage()
@@ -59,10 +96,16 @@ func parseArgs() -> (socketPath: String, udid: String, fps: Int, quality: Int) {
         i += 1
     }
 
-    guard let sp = socketPath, let u = udid else {
+    guard let u = udid, socketPath != nil || outputPath != nil else {
         printUsage()
     }
-    return (sp, u, fps, quality)
+    // Explicit minimums may not exceed their bounds; the defaults shrink to fit.
+    if let v = minFps, v > fps { printUsage() }
+    if let v = minQuality, v > quality { printUsage() }
+    return (
+        socketPath, outputPath, duration, u, fps, quality,
+        minFps ?? min(5, fps), minQuality ?? min(30, quality)
+    )
 }
 
 // MARK: - Device name resolution
@@ -110,6 +153,9 @@ func installSignalHandlers(cleanup: @escaping () -> Void) -> [DispatchSourceSign
     signal(SIGTERM, SIG_IGN)
     signal(SIGINT, SIG_IGN)
 
+    // Report broken pipes (a gone client or ffmpeg) as write errors instead.
+    signal(SIGPIPE, SIG_IGN)
+
     let sources = [SIGTERM, SIGINT].map { sig -> DispatchSourceSignal in
         let source = DispatchSource.makeSignalSource(signal: sig, queue: .main)
         source.setEventHandler {
@@ -126,6 +172,23 @@ func installSignalHandlers(cleanup: @escaping () -> Void) -> [DispatchSourceSign
 
 let config = parseArgs()
 
+// Check the permission before binding the socket or finding the window, so
+// the parent learns about it from the exit code rather than a failed capture.
+guard ScreenRecordingPermission.preflight() else {
+    fputs("[qorvex-streamer] \(ScreenRecordingPermission.guidance)\n", stderr)
+    exit(ScreenRecordingPermission.deniedExitCode)
+}
+
+// Check for ffmpeg before touching the Simulator so a missing encoder fails fast.
+var ffmpegPath: URL?
+if config.outputPath != nil {
+    guard let path = FfmpegWriter.locate() else {
+        fputs("[qorvex-streamer] ffmpeg not found; install it (e.g. `brew install ffmpeg`) to use --output\n", stderr)
+        exit(2)
+    }
+    ffmpegPath = path
+}
+
 guard let deviceName = resolveDeviceName(udid: config.udid) else {
     fputs("[qorvex-streamer] Could not resolve device name for UDID: \(config.udid)\n", stderr)
     exit(1)
@@ -133,51 +196,84 @@ guard let deviceName = resolveDeviceName(udid: config.udid) else {
 
 NSLog("[qorvex-streamer] Device: %@ (UDID: %@)", deviceName, config.udid)
 
-let socketWriter = SocketWriter(socketPath: config.socketPath)
+var sinks: [FrameSink] = []
+var streamer: FrameStreamer?
 
-let _signalSources = installSignalHandlers {
+/// Stop capturing, then let every sink flush (ffmpeg finalizes the MP4 here).
+func finishCapture() {
     NSLog("[qorvex-streamer] Shutting down")
-    socketWriter.close()
+    streamer?.stop()
+    sinks.forEach { $0.close() }
 }
+
+let _signalSources = installSignalHandlers(cleanup: finishCapture)
 _ = _signalSources // Keep sources alive
 
-// Bind the socket and wait for a client before starting capture.
-do {
-    try socketWriter.bind()
-} catch {
-    fputs("[qorvex-streamer] Failed to bind socket: \(error)\n", stderr)
-    exit(1)
+if let outputPath = config.outputPath, let ffmpeg = ffmpegPath {
+    do {
+        sinks.append(try FfmpegWriter(ffmpeg: ffmpeg, outputPath: outputPath, fps: config.fps))
+    } catch {
+        fputs("[qorvex-streamer] \(error)\n", stderr)
+        exit(1)
+    }
+    NSLog("[qorvex-streamer] Recording to %@", outputPath)
 }
 
-NSLog("[qorvex-streamer] Socket bound at %@, waiting for client", config.socketPath)
-socketWriter.acceptClient()
-NSLog("[qorvex-streamer] Client connected")
+if let socketPath = config.socketPath {
+    let socketWriter = SocketWriter(socketPath: socketPath)
+    sinks.append(socketWriter)
+
+    // Bind the socket and wait for a client before starting capture.
+    do {
+        try socketWriter.bind()
+    } catch {
+        fputs("[qorvex-streamer] Failed to bind socket: \(error)\n", stderr)
+        exit(1)
+    }
+
+    NSLog("[qorvex-streamer] Socket bound at %@, waiting for client", socketPath)
+    socketWriter.acceptClient()
+    NSLog("[qorvex-streamer] Client connected")
+}
 
 // Use a semaphore to bridge async -> sync in main.
 let sem = DispatchSemaphore(value: 0)
-var streamer: FrameStreamer?
 
 Task {
     do {
         let (window, display) = try await WindowFinder.findSimulatorWindow(deviceName: deviceName)
         NSLog("[qorvex-streamer] Found window: %@", window.title ?? "<untitled>")
 
+        // Only a live view trades quality for latency; recordings stay fixed.
+        var adaptive: AdaptiveQuality?
+        if config.outputPath == nil {
+            adaptive = AdaptiveQuality(bounds: AdaptiveQuality.Bounds(
+                quality: config.minQuality...config.quality,
+                fps: config.minFps...config.fps
+            ))
+        }
+
         let fs = FrameStreamer(
             window: window,
             display: display,
             fps: config.fps,
             quality: config.quality,
-            socketWriter: socketWriter
+            adaptive: adaptive,
+            sinks: sinks
         )
         streamer = fs
         try await fs.start()
         NSLog("[qorvex-streamer] Streaming at %d fps, quality %d", config.fps, config.quality)
+        if adaptive != nil {
+            NSLog("[qorvex-streamer] Adapting down to %d fps, quality %d under backpressure",
+                  config.minFps, config.minQuality)
+        }
         sem.signal()
     } catch {
         let desc = "\(error)"
         if desc.contains("permission") || desc.contains("denied") || desc.contains("TCCDeny") {
-            fputs("[qorvex-streamer] Screen capture permission denied\n", stderr)
-            exit(2)
+            fputs("[qorvex-streamer] \(ScreenRecordingPermission.guidance)\n", stderr)
+            exit(ScreenRecordingPermission.deniedExitCode)
         }
         fputs("[qorvex-streamer] Failed to start capture: \(error)\n", stderr)
         exit(1)
@@ -186,5 +282,12 @@ Task {
 
 sem.wait()
 
+if let duration = config.duration {
+    DispatchQueue.main.asyncAfter(deadline: .now() + duration) {
+        finishCapture()
+        exit(0)
+    }
+}
+
 // Keep the process alive.
 RunLoop.current.run()
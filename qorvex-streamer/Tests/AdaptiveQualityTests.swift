@@ -0,0 +1,81 @@
+// AdaptiveQualityTests.swift
+// Unit tests for the adaptive quality state machine, driven by simulated
+// windows of sent and skipped frames.
+
+import XCTest
+@testable import qorvex_streamer
+
+final class AdaptiveQualityTests: XCTestCase {
+
+    private let bounds = AdaptiveQuality.Bounds(quality: 40...70, fps: 5...15)
+
+    /// Feeds one window with `skipped` dropped frames; returns whether the
+    /// settings changed at its end.
+    @discardableResult
+    private func window(_ adaptive: inout AdaptiveQuality, skipped: Int) -> Bool {
+        var changed = false
+        for i in 0..<adaptive.windowSize {
+            changed = adaptive.record(skipped: i < skipped)
+        }
+        return changed
+    }
+
+    func testStartsAtUpperBounds() {
+        let adaptive = AdaptiveQuality(bounds: bounds)
+        XCTAssertEqual(adaptive.quality, 70)
+        XCTAssertEqual(adaptive.fps, 15)
+    }
+
+    func testLagLowersQualityThenFrameRate() {
+        var adaptive = AdaptiveQuality(bounds: bounds)
+
+        XCTAssertTrue(window(&adaptive, skipped: 8))
+        XCTAssertEqual(adaptive.quality, 60)
+        window(&adaptive, skipped: 8)
+        window(&adaptive, skipped: 8)
+        XCTAssertEqual(adaptive.quality, 40)
+        XCTAssertEqual(adaptive.fps, 15, "frame rate holds until quality bottoms out")
+
+        window(&adaptive, skipped: 8)
+        XCTAssertEqual(adaptive.fps, 10)
+        window(&adaptive, skipped: 8)
+        XCTAssertFalse(window(&adaptive, skipped: 8), "already at the floor")
+        XCTAssertEqual(adaptive.quality, 40)
+        XCTAssertEqual(adaptive.fps, 5)
+    }
+
+    func testRecoveryNeedsCleanWindowsAndRestoresFrameRateFirst() {
+        var adaptive = AdaptiveQuality(bounds: bounds)
+        for _ in 0..<4 { window(&adaptive, skipped: 8) }
+        XCTAssertEqual(adaptive.fps, 10)
+
+        XCTAssertFalse(window(&adaptive, skipped: 0))
+        XCTAssertTrue(window(&adaptive, skipped: 0))
+        XCTAssertEqual(adaptive.fps, 15)
+        XCTAssertEqual(adaptive.quality, 40)
+
+        // A window with a few drops restarts the clean count
+        window(&adaptive, skipped: 0)
+        XCTAssertFalse(window(&adaptive, skipped: 2))
+        XCTAssertFalse(window(&adaptive, skipped: 0))
+        XCTAssertTrue(window(&adaptive, skipped: 0))
+        XCTAssertEqual(adaptive.quality, 50)
+    }
+
+    func testOccasionalDropsHoldSettings() {
+        var adaptive = AdaptiveQuality(bounds: bounds)
+        for _ in 0..<5 {
+            XCTAssertFalse(window(&adaptive, skipped: 3))
+        }
+        XCTAssertEqual(adaptive.quality, 70)
+        XCTAssertEqual(adaptive.fps, 15)
+    }
+
+    func testFixedBoundsNeverChange() {
+        let fixed = AdaptiveQuality.Bounds(quality: 70...70, fps: 15...15)
+        var adaptive = AdaptiveQuality(bounds: fixed)
+        XCTAssertFalse(window(&adaptive, skipped: 15))
+        XCTAssertFalse(window(&adaptive, skipped: 0))
+        XCTAssertFalse(window(&adaptive, skipped: 0))
+    }
+}
@@ -0,0 +1,72 @@
+// FfmpegWriterTests.swift
+// Unit tests for FfmpegWriter: the ffmpeg argument vector, executable lookup,
+// and flushing on close. A shell stand-in replaces ffmpeg, so ffmpeg itself
+// is not required.
+
+import XCTest
+@testable import qorvex_streamer
+
+final class FfmpegWriterTests: XCTestCase {
+
+    private var tempDir: URL!
+
+    override func setUpWithError() throws {
+        tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent("qorvex-streamer-tests-\(UUID().uuidString)")
+        try FileManager.default.createDirectory(at: tempDir, withIntermediateDirectories: true)
+    }
+
+    override func tearDownWithError() throws {
+        try? FileManager.default.removeItem(at: tempDir)
+    }
+
+    func testArgumentsReadJpegPipeAndWriteMp4() {
+        XCTAssertEqual(FfmpegWriter.arguments(outputPath: "/tmp/run.mp4", fps: 30), [
+            "-hide_banner", "-loglevel", "error",
+            "-f", "image2pipe", "-c:v", "mjpeg", "-framerate", "30", "-i", "-",
+            "-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+            "-c:v", "libx264", "-pix_fmt", "yuv420p", "-movflags", "+faststart",
+            "-y", "/tmp/run.mp4",
+        ])
+    }
+
+    func testLocateFindsExecutableOnPath() throws {
+        let ffmpeg = tempDir.appendingPathComponent("ffmpeg")
+        try "#!/bin/sh\n".write(to: ffmpeg, atomically: true, encoding: .utf8)
+        try FileManager.default.setAttributes([.posixPermissions: 0o755], ofItemAtPath: ffmpeg.path)
+
+        let found = FfmpegWriter.locate(path: "/nonexistent:\(tempDir.path)", fallbackDirectories: [])
+        XCTAssertEqual(found?.path, ffmpeg.path)
+    }
+
+    func testLocateReturnsNilWhenMissing() {
+        XCTAssertNil(FfmpegWriter.locate(path: tempDir.path, fallbackDirectories: []))
+        XCTAssertNil(FfmpegWriter.locate(path: nil, fallbackDirectories: []))
+    }
+
+    func testCloseFlushesFramesAndWaitsForExit() throws {
+        let output = tempDir.appendingPathComponent("frames.bin")
+        let writer = try FfmpegWriter(
+            executable: URL(fileURLWithPath: "/bin/sh"),
+            arguments: ["-c", "cat > \"$0\"", output.path]
+        )
+
+        let frames = [Data([0xFF, 0xD8, 0x01, 0xFF, 0xD9]), Data([0xFF, 0xD8, 0x02, 0xFF, 0xD9])]
+        for frame in frames {
+            let written = expectation(description: "frame written")
+            writer.writeFrame(frame) { written.fulfill() }
+            wait(for: [written], timeout: 5)
+        }
+
+        writer.close()
+        XCTAssertEqual(writer.terminationStatus, 0)
+        // Frames reach the encoder back to back, with no framing between them
+        XCTAssertEqual(try Data(contentsOf: output), frames[0] + frames[1])
+
+        // Writes after close are dropped, and closing again is harmless
+        let dropped = expectation(description: "late frame completed")
+        writer.writeFrame(frames[0]) { dropped.fulfill() }
+        wait(for: [dropped], timeout: 5)
+        writer.close()
+    }
+}
@@ -0,0 +1,34 @@
+// ScreenRecordingPermissionTests.swift
+// Unit tests for the Screen Recording preflight, with the system queries
+// replaced so the result does not depend on this machine's settings.
+
+import XCTest
+@testable import qorvex_streamer
+
+final class ScreenRecordingPermissionTests: XCTestCase {
+
+    func testGrantedSkipsTheRequest() {
+        var requested = false
+        let granted = ScreenRecordingPermission.preflight(
+            query: { true },
+            request: { requested = true; return true }
+        )
+        XCTAssertTrue(granted)
+        XCTAssertFalse(requested)
+    }
+
+    func testDeniedRequestsOnceAndFails() {
+        var requests = 0
+        let granted = ScreenRecordingPermission.preflight(
+            query: { false },
+            request: { requests += 1; return false }
+        )
+        XCTAssertFalse(granted)
+        XCTAssertEqual(requests, 1)
+    }
+
+    func testGuidanceNamesTheSettingsPane() {
+        XCTAssertTrue(ScreenRecordingPermission.guidance.contains("Screen & System Audio Recording"))
+        XCTAssertEqual(ScreenRecordingPermission.deniedExitCode, 2)
+    }
+}
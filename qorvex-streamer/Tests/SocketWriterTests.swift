@@ -0,0 +1,24 @@
+// SocketWriterTests.swift
+// Unit tests for the socket stream format: the connection header and the
+// per-frame header that carries the sequence number and capture time.
+
+import XCTest
+@testable import qorvex_streamer
+
+final class SocketWriterTests: XCTestCase {
+
+    func testStreamHeaderIsMagicAndVersion() {
+        XCTAssertEqual(Array(SocketWriter.streamHeader), Array("QVXS".utf8) + [2])
+    }
+
+    func testFrameHeaderIsLittleEndian() {
+        let info = FrameInfo(sequence: 0x0102, timestampNs: 0x0A0B_0C0D)
+        let header = SocketWriter.frameHeader(length: 0x0304, info: info)
+
+        XCTAssertEqual(Array(header), [
+            0x04, 0x03, 0x00, 0x00,
+            0x02, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x0D, 0x0C, 0x0B, 0x0A, 0x00, 0x00, 0x00, 0x00,
+        ])
+    }
+}